@@ -2,6 +2,14 @@
 //!
 //! This benchmark compares performance across different HTML sizes and query types.
 //! Run with: `cargo bench --bench comparison`
+//!
+//! The `hot_paths` group covers the class matching, text search, and escaping that the
+//! `simd` feature accelerates. Compare a scalar baseline with the SIMD build:
+//!
+//! ```text
+//! cargo bench -p scrape-core --bench comparison -- hot_paths --save-baseline scalar
+//! cargo bench -p scrape-core --bench comparison --features simd -- hot_paths --baseline scalar
+//! ```
 
 use std::hint::black_box;
 
@@ -292,6 +300,37 @@ fn bench_extraction(c: &mut Criterion) {
     group.finish();
 }
 
+// ==================== SIMD Hot Path Benchmarks ====================
+
+fn bench_hot_paths(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hot_paths");
+
+    let medium = fixtures::medium();
+    let soup = Soup::parse(&medium);
+
+    group.bench_function("class_selector", |b| {
+        b.iter(|| {
+            let _ = soup.select(black_box("div.product-card span.price")).unwrap();
+        });
+    });
+
+    group.bench_function("class_selector_miss", |b| {
+        b.iter(|| {
+            let _ = soup.select(black_box("div.product-card.featured")).unwrap();
+        });
+    });
+
+    group.bench_function("find_all_by_text", |b| {
+        b.iter(|| black_box(soup.find_all_by_text(black_box("product 499"))));
+    });
+
+    group.bench_function("serialize", |b| {
+        b.iter(|| black_box(soup.to_html()));
+    });
+
+    group.finish();
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default().sample_size(200);
@@ -301,6 +340,7 @@ criterion_group! {
               bench_select,
               bench_navigation,
               bench_throughput,
-              bench_extraction
+              bench_extraction,
+              bench_hot_paths
 }
 criterion_main!(benches);
@@ -0,0 +1,93 @@
+//! Rust reference runner for the cross-language overhead harness.
+//!
+//! Runs the shared workloads on a fixture written by `benches/overhead/run.py` and prints
+//! one JSON object to stdout. Binding runners perform the same calls, so the difference to
+//! these timings is the per-call cost of crossing the language boundary.
+//!
+//! Run with: `cargo bench -p scrape-core --bench bindings_overhead -- FIXTURE [ITERATIONS]`
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use scrape_core::Soup;
+use serde_json::{Value, json};
+
+const RECORD: &str = "div.record";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).filter(|a| !a.starts_with("--")).collect();
+    let Some(path) = args.first() else {
+        eprintln!("usage: bindings_overhead FIXTURE [ITERATIONS]");
+        std::process::exit(2);
+    };
+    let iterations = args.get(1).and_then(|n| n.parse().ok()).unwrap_or(20);
+    let html = std::fs::read_to_string(path).expect("failed to read fixture");
+
+    let soup = Soup::parse(&html);
+    let records = soup.find_all(RECORD).expect("valid selector").len();
+
+    let parse = measure(iterations, 1, || {
+        black_box(Soup::parse(&html));
+        records
+    });
+    let find_all = measure(iterations, 1, || soup.find_all(RECORD).unwrap().len());
+    let extract = measure(iterations, 1 + 6 * records, || extract(&soup));
+    let extract_bulk = measure(iterations, 3, || extract_bulk(&soup));
+
+    let report = json!({
+        "runtime": "rust",
+        "records": records,
+        "iterations": iterations,
+        "workloads": {
+            "parse": parse,
+            "find_all": find_all,
+            "extract": extract,
+            "extract_bulk": extract_bulk,
+        },
+    });
+    println!("{report}");
+}
+
+/// Per-record extraction: one lookup and one accessor call per field.
+fn extract(soup: &Soup) -> usize {
+    let mut total = 0;
+    for record in soup.find_all(RECORD).unwrap() {
+        let name = record.find(".name").unwrap().unwrap().text();
+        let price = record.find(".price").unwrap().unwrap().text();
+        let link = record.find("a").unwrap().unwrap();
+        let href = link.get("href").unwrap_or_default();
+        total += name.len() + price.len() + href.len();
+    }
+    total
+}
+
+/// Bulk extraction: one call per field across all records.
+fn extract_bulk(soup: &Soup) -> usize {
+    let names = soup.select_text("div.record .name").unwrap();
+    let prices = soup.select_text("div.record .price").unwrap();
+    let hrefs = soup.select_attr("div.record a", "href").unwrap();
+    names.iter().chain(&prices).map(String::len).sum::<usize>()
+        + hrefs.iter().flatten().map(String::len).sum::<usize>()
+}
+
+/// Times `f` after one warmup run and returns the shared result schema.
+fn measure(iterations: usize, calls: usize, mut f: impl FnMut() -> usize) -> Value {
+    let checksum = f();
+    let mut times: Vec<Duration> = (0..iterations.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    times.sort_unstable();
+    let ms = |d: Duration| d.as_secs_f64() * 1e3;
+    json!({
+        "calls": calls,
+        "checksum": checksum,
+        "median_ms": ms(times[times.len() / 2]),
+        "min_ms": ms(times[0]),
+    })
+}
@@ -0,0 +1,119 @@
+#!/usr/bin/env node
+/**
+ * Node.js and WASM runner for the cross-language overhead harness.
+ *
+ * Runs the shared workloads through @fast-scrape/node, or through the WASM build
+ * with --wasm, and prints one JSON object to stdout. Invoked by benches/overhead/run.py:
+ *
+ *     node benches/overhead/overhead_node.mjs [--wasm] FIXTURE [ITERATIONS]
+ */
+
+import { readFileSync } from 'fs';
+import { fileURLToPath } from 'url';
+import { dirname, join } from 'path';
+
+const __dirname = dirname(fileURLToPath(import.meta.url));
+const CRATES_DIR = join(__dirname, '..', '..', 'crates');
+const RECORD = 'div.record';
+
+/**
+ * Time fn after one warmup run and return the shared result schema.
+ */
+function measure(iterations, calls, fn) {
+    const checksum = fn();
+    const times = [];
+    for (let i = 0; i < Math.max(iterations, 1); i++) {
+        const start = process.hrtime.bigint();
+        fn();
+        times.push(Number(process.hrtime.bigint() - start) / 1_000_000);
+    }
+    times.sort((a, b) => a - b);
+    return {
+        calls,
+        checksum,
+        median_ms: times[Math.floor(times.length / 2)],
+        min_ms: times[0],
+    };
+}
+
+/**
+ * Per-record extraction: one lookup and one accessor call per field.
+ */
+function extract(soup) {
+    let total = 0;
+    for (const record of soup.findAll(RECORD)) {
+        const name = record.find('.name').text;
+        const price = record.find('.price').text;
+        const href = record.find('a').get('href') ?? '';
+        total += name.length + price.length + href.length;
+    }
+    return total;
+}
+
+/**
+ * Bulk extraction: one call per field across all records.
+ */
+function extractBulk(soup) {
+    const names = soup.selectText('div.record .name');
+    const prices = soup.selectText('div.record .price');
+    const hrefs = soup.selectAttr('div.record a', 'href');
+    const sum = (values) => values.reduce((n, v) => n + (v ?? '').length, 0);
+    return sum(names) + sum(prices) + sum(hrefs);
+}
+
+async function loadSoup(wasm) {
+    const path = wasm
+        ? join(CRATES_DIR, 'scrape-wasm', 'pkg', 'scrape_wasm.js')
+        : join(CRATES_DIR, 'scrape-node', 'index.js');
+    try {
+        const mod = await import(path);
+        const Soup = mod.Soup ?? mod.default?.Soup;
+        if (typeof Soup !== 'function') {
+            throw new Error(`${path} does not export Soup`);
+        }
+        return Soup;
+    } catch (err) {
+        console.error(`Error: failed to load ${wasm ? 'WASM' : 'Node.js'} bindings. Build them first:`);
+        console.error(
+            wasm
+                ? '  cd crates/scrape-wasm && pnpm run build:nodejs'
+                : '  cd crates/scrape-node && pnpm run build',
+        );
+        console.error('Error details:', err.message);
+        process.exit(1);
+    }
+}
+
+async function main() {
+    const args = process.argv.slice(2);
+    const wasm = args.includes('--wasm');
+    const [fixture, iterationsArg] = args.filter((a) => !a.startsWith('--'));
+    if (!fixture) {
+        console.error('usage: overhead_node.mjs [--wasm] FIXTURE [ITERATIONS]');
+        process.exit(2);
+    }
+    const iterations = Number.parseInt(iterationsArg ?? '20', 10);
+    const html = readFileSync(fixture, 'utf-8');
+
+    const Soup = await loadSoup(wasm);
+    const soup = new Soup(html);
+    const records = soup.findAll(RECORD).length;
+
+    const report = {
+        runtime: wasm ? 'wasm' : 'node',
+        records,
+        iterations,
+        workloads: {
+            parse: measure(iterations, 1, () => {
+                new Soup(html);
+                return records;
+            }),
+            find_all: measure(iterations, 1, () => soup.findAll(RECORD).length),
+            extract: measure(iterations, 1 + 6 * records, () => extract(soup)),
+            extract_bulk: measure(iterations, 3, () => extractBulk(soup)),
+        },
+    };
+    console.log(JSON.stringify(report));
+}
+
+main();
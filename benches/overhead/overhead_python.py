@@ -0,0 +1,91 @@
+#!/usr/bin/env python3
+"""Python runner for the cross-language overhead harness.
+
+Runs the shared workloads through the scrape_rs bindings and prints one JSON object
+to stdout. Invoked by benches/overhead/run.py:
+
+    python benches/overhead/overhead_python.py FIXTURE [ITERATIONS]
+"""
+
+import json
+import sys
+import time
+from pathlib import Path
+from typing import Callable
+
+RECORD = "div.record"
+
+
+def measure(iterations: int, calls: int, fn: Callable[[], int]) -> dict:
+    """Time fn after one warmup run and return the shared result schema."""
+    checksum = fn()
+    times = []
+    for _ in range(max(iterations, 1)):
+        start = time.perf_counter()
+        fn()
+        times.append((time.perf_counter() - start) * 1000)
+    times.sort()
+    return {
+        "calls": calls,
+        "checksum": checksum,
+        "median_ms": times[len(times) // 2],
+        "min_ms": times[0],
+    }
+
+
+def extract(soup) -> int:
+    """Per-record extraction: one lookup and one accessor call per field."""
+    total = 0
+    for record in soup.find_all(RECORD):
+        name = record.find(".name").text
+        price = record.find(".price").text
+        href = record.find("a").get("href") or ""
+        total += len(name) + len(price) + len(href)
+    return total
+
+
+def extract_bulk(soup) -> int:
+    """Bulk extraction: one call per field across all records."""
+    names = soup.select_text("div.record .name")
+    prices = soup.select_text("div.record .price")
+    hrefs = soup.select_attr("div.record a", "href")
+    return sum(map(len, names)) + sum(map(len, prices)) + sum(len(h or "") for h in hrefs)
+
+
+def main() -> None:
+    try:
+        from scrape_rs import Soup
+    except ImportError:
+        print("Error: scrape_rs not found. Run:", file=sys.stderr)
+        print("  cd crates/scrape-py && uv run maturin develop --release", file=sys.stderr)
+        sys.exit(1)
+
+    if len(sys.argv) < 2:
+        print("usage: overhead_python.py FIXTURE [ITERATIONS]", file=sys.stderr)
+        sys.exit(2)
+    html = Path(sys.argv[1]).read_text()
+    iterations = int(sys.argv[2]) if len(sys.argv) > 2 else 20
+
+    soup = Soup(html)
+    records = len(soup.find_all(RECORD))
+
+    def parse() -> int:
+        Soup(html)
+        return records
+
+    report = {
+        "runtime": "python",
+        "records": records,
+        "iterations": iterations,
+        "workloads": {
+            "parse": measure(iterations, 1, parse),
+            "find_all": measure(iterations, 1, lambda: len(soup.find_all(RECORD))),
+            "extract": measure(iterations, 1 + 6 * records, lambda: extract(soup)),
+            "extract_bulk": measure(iterations, 3, lambda: extract_bulk(soup)),
+        },
+    }
+    print(json.dumps(report))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,172 @@
+#!/usr/bin/env python3
+"""Measure the per-call overhead of the Python, Node.js, and WASM bindings.
+
+Generates one fixture, runs identical workloads through the Rust core and each
+binding, and reports how much slower each binding is than Rust, both in total and
+per call crossing the language boundary. Results can be written as JSON for tracking.
+
+Workloads:
+    parse         parse the fixture (1 call)
+    find_all      select all records (1 call)
+    extract       per record: find + text for two fields, find + get for a link
+                  (1 + 6 calls per record)
+    extract_bulk  select_text twice and select_attr once over all records (3 calls)
+
+Run from project root, after building the bindings you want to include:
+    cd crates/scrape-py && uv run maturin develop --release
+    cd crates/scrape-node && pnpm run build
+    cd crates/scrape-wasm && pnpm run build:nodejs
+    python benches/overhead/run.py --output overhead.json
+"""
+
+import argparse
+import json
+import shutil
+import subprocess
+import sys
+import tempfile
+from pathlib import Path
+
+OVERHEAD_DIR = Path(__file__).parent
+ROOT = OVERHEAD_DIR.parent.parent
+RUNTIMES = ("rust", "python", "node", "wasm")
+WORKLOADS = ("parse", "find_all", "extract", "extract_bulk")
+
+
+def generate_fixture(records: int) -> str:
+    """Generate a catalog page with `records` uniform product records."""
+    parts = ["<!DOCTYPE html><html><head><title>Catalog</title></head><body><main>"]
+    for i in range(records):
+        parts.append(
+            f'<div class="record" data-id="{i}">'
+            f'<span class="name">Product {i}</span>'
+            f'<span class="price">${i % 1000}.{i % 100:02d}</span>'
+            f'<a href="/products/{i}">Details</a>'
+            "</div>"
+        )
+    parts.append("</main></body></html>")
+    return "\n".join(parts)
+
+
+def runner_command(runtime: str, fixture: Path, iterations: int) -> list[str]:
+    """Return the command that runs the workloads for `runtime`."""
+    args = [str(fixture), str(iterations)]
+    if runtime == "rust":
+        cargo = ["cargo", "bench", "-q", "-p", "scrape-core", "--bench", "bindings_overhead"]
+        return [*cargo, "--", *args]
+    if runtime == "python":
+        return [sys.executable, str(OVERHEAD_DIR / "overhead_python.py"), *args]
+    node = [shutil.which("node") or "node", str(OVERHEAD_DIR / "overhead_node.mjs")]
+    return [*node, "--wasm", *args] if runtime == "wasm" else [*node, *args]
+
+
+def run_runtime(runtime: str, fixture: Path, iterations: int) -> dict | None:
+    """Run one runtime and return its report, or None if it is unavailable."""
+    print(f"Running {runtime}...", file=sys.stderr)
+    try:
+        result = subprocess.run(
+            runner_command(runtime, fixture, iterations),
+            cwd=ROOT,
+            capture_output=True,
+            text=True,
+            check=False,
+        )
+    except FileNotFoundError as e:
+        print(f"  skipped: {e}", file=sys.stderr)
+        return None
+    if result.returncode != 0:
+        print(f"  skipped: {result.stderr.strip()}", file=sys.stderr)
+        return None
+    return json.loads(result.stdout.strip().splitlines()[-1])
+
+
+def compute_overhead(reports: dict[str, dict]) -> dict[str, dict]:
+    """Compare every binding against the Rust reference, workload by workload."""
+    rust = reports["rust"]["workloads"]
+    overhead = {}
+    for runtime, report in reports.items():
+        if runtime == "rust":
+            continue
+        overhead[runtime] = {}
+        for name in WORKLOADS:
+            ours, ref = report["workloads"][name], rust[name]
+            extra_ms = ours["median_ms"] - ref["median_ms"]
+            overhead[runtime][name] = {
+                "ratio": ours["median_ms"] / ref["median_ms"] if ref["median_ms"] else None,
+                "extra_ms": extra_ms,
+                "per_call_us": extra_ms * 1000 / ours["calls"],
+                "checksum_match": ours["checksum"] == ref["checksum"],
+            }
+    return overhead
+
+
+def print_table(reports: dict[str, dict], overhead: dict[str, dict]) -> None:
+    """Print median times and per-call overhead for each runtime and workload."""
+    print(f"{'workload':<14} {'runtime':<8} {'median':>12} {'vs rust':>9} {'per call':>12}")
+    print("-" * 59)
+    for name in WORKLOADS:
+        for runtime, report in reports.items():
+            median = report["workloads"][name]["median_ms"]
+            line = f"{name:<14} {runtime:<8} {median:>9.3f} ms"
+            if runtime in overhead:
+                o = overhead[runtime][name]
+                ratio = f"{o['ratio']:.2f}x" if o["ratio"] is not None else "-"
+                line += f" {ratio:>9} {o['per_call_us']:>9.3f} µs"
+                if not o["checksum_match"]:
+                    line += "  (checksum mismatch)"
+            print(line)
+        print()
+
+
+def main() -> None:
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("--records", type=int, default=10_000, help="records in the fixture")
+    parser.add_argument("--iterations", type=int, default=20, help="timed runs per workload")
+    parser.add_argument(
+        "--runtimes",
+        default=",".join(RUNTIMES),
+        help="comma-separated runtimes to run (default: all)",
+    )
+    parser.add_argument("--output", type=Path, help="write results as JSON to this file")
+    args = parser.parse_args()
+
+    runtimes = [r.strip() for r in args.runtimes.split(",") if r.strip()]
+    unknown = set(runtimes) - set(RUNTIMES)
+    if unknown:
+        parser.error(f"unknown runtimes: {', '.join(sorted(unknown))}")
+    if "rust" not in runtimes:
+        runtimes.insert(0, "rust")
+
+    with tempfile.TemporaryDirectory() as tmp:
+        fixture = Path(tmp) / "overhead.html"
+        fixture.write_text(generate_fixture(args.records))
+        reports = {}
+        for runtime in runtimes:
+            report = run_runtime(runtime, fixture, args.iterations)
+            if report is not None:
+                reports[runtime] = report
+
+    if "rust" not in reports:
+        print("Error: the Rust reference runner failed", file=sys.stderr)
+        sys.exit(1)
+
+    overhead = compute_overhead(reports)
+    print_table(reports, overhead)
+
+    if args.output:
+        results = {
+            "records": args.records,
+            "iterations": args.iterations,
+            "runtimes": reports,
+            "overhead": overhead,
+        }
+        args.output.write_text(json.dumps(results, indent=2) + "\n")
+        print(f"Results written to {args.output}", file=sys.stderr)
+
+    if any(not w["checksum_match"] for o in overhead.values() for w in o.values()):
+        print("Error: bindings returned different results than Rust", file=sys.stderr)
+        sys.exit(1)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,55 @@
+//! `scrape archive` subcommand.
+
+use std::{
+    fs,
+    io::{self, Write},
+    num::NonZeroUsize,
+};
+
+use anyhow::{Context, Result};
+use scrape_core::Soup;
+
+use crate::{
+    args::ArchiveArgs,
+    fetch::{FetchConfig, Fetcher, is_url},
+};
+
+/// Downloads the page's stylesheets and images, inlines them, and writes the page.
+///
+/// Returns `false` if any asset could not be downloaded.
+///
+/// # Errors
+///
+/// Returns an error if the page cannot be read or the archive cannot be written.
+pub fn run(args: &ArchiveArgs) -> Result<bool> {
+    let fetcher =
+        Fetcher::new(FetchConfig { retries: 2, ..FetchConfig::default() }, NonZeroUsize::MIN, None);
+
+    let (html, page_url) = if is_url(&args.input) {
+        (fetcher.fetch(&args.input)?, Some(args.input.as_str()))
+    } else {
+        let html = fs::read_to_string(&args.input)
+            .with_context(|| format!("failed to read {}", args.input))?;
+        (html, None)
+    };
+
+    let mut soup = Soup::parse(&html);
+    let report = soup.inline_external_assets(&fetcher, args.base_url.as_deref().or(page_url));
+    if !args.quiet {
+        for failure in &report.failed {
+            eprintln!("{}: {}", failure.url, failure.error);
+        }
+    }
+
+    let archive = format!("<!DOCTYPE html>\n{}\n", soup.to_html());
+    if let Some(path) = &args.output {
+        fs::write(path, archive).with_context(|| format!("failed to write {}", path.display()))?;
+    } else {
+        let stdout = io::stdout();
+        let mut writer = stdout.lock();
+        writer.write_all(archive.as_bytes())?;
+        writer.flush()?;
+    }
+
+    Ok(report.failed.is_empty())
+}
@@ -1,8 +1,21 @@
 //! CLI argument parsing using clap derive.
 
-use std::path::PathBuf;
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
 
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
+use scrape_core::{SerializeOptions, extract::Schema};
+
+#[cfg(feature = "render")]
+use crate::render::{Renderer, WaitFor};
+use crate::{
+    batch::Input,
+    extract::{self, ParseCheck},
+    fetch::{FetchConfig, Fetcher, is_url},
+    output::Template,
+};
 
 /// High-performance HTML extraction tool.
 ///
@@ -11,22 +24,41 @@ use clap::{Parser, ValueEnum};
 #[derive(Parser, Debug)]
 #[command(name = "scrape")]
 #[command(author, version, about, long_about = None)]
-#[command(after_help = "EXAMPLES:
+#[command(after_help = r"EXAMPLES:
     scrape 'h1' page.html              Extract h1 text
     scrape -o json 'a[href]' page.html Extract links as JSON
     scrape -a href 'a' page.html       Extract href attributes
+    scrape -o ndjson 'a' *.html        Stream results as JSON Lines
     curl url | scrape 'title'          Extract from stdin
     scrape -s title='h1' -s links='a' page.html
+    scrape 'h1' https://example.com https://example.org --concurrency 8
+    scrape --table 'table' -o csv page.html
+    scrape --dump-assets assets/ page.html
+    scrape --metadata -o json page.html
+    scrape --article https://example.com/post
+    scrape --schema schema.json -o ndjson *.html
+    scrape --watch 'h1' page.html      Re-run when page.html changes
+    scrape contacts page.html          Harvest emails, phones, and URLs
+    scrape archive https://example.com -o page.html
+    scrape diff old.html new.html --selector main
+    scrape crawl https://example.com -f '/blog/*' -s title=h1
+    scrape bench                       Measure parse/query throughput
 ")]
+#[command(args_conflicts_with_subcommands = true)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct Args {
+    /// Subcommand to run instead of selector extraction.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// CSS selector for extraction.
     ///
-    /// If not provided, --select must be used instead.
+    /// If not provided, --select must be used instead. With --select, this is the first
+    /// input.
     #[arg(value_name = "SELECTOR")]
     pub selector: Option<String>,
 
-    /// Input HTML files.
+    /// Input HTML files or http(s) URLs.
     ///
     /// If not provided, reads from stdin.
     #[arg(value_name = "FILES")]
@@ -43,6 +75,59 @@ pub struct Args {
     #[arg(short = 'o', long, value_enum, default_value_t = OutputFormat::Text)]
     pub output: OutputFormat,
 
+    /// Line format for `-o template`, with `{field}` placeholders.
+    ///
+    /// Fields: `{text}`, `{html}` (outer HTML), `{inner_html}`, `{attr:NAME}`, `{name}`
+    /// (the --select name), `{filename}`, and `{index}` (the match's position in its
+    /// input, from 0). Write `{{` and `}}` for literal braces.
+    /// Example: -o template --template '{text} ({attr:href})'
+    #[arg(long, value_name = "TEMPLATE", value_parser = Template::parse)]
+    pub template: Option<Template>,
+
+    /// Extract tables matching this CSS selector as records.
+    ///
+    /// Header cells become column names, and colspan/rowspan are resolved. All
+    /// positional arguments are inputs. Supports text, csv, json, and ndjson output.
+    #[arg(long, value_name = "SELECTOR", conflicts_with_all = ["selects", "attribute"])]
+    pub table: Option<String>,
+
+    /// Write inline assets to this directory.
+    ///
+    /// Decodes data: URIs and saves inline SVG and style blocks as numbered files,
+    /// printing one line per file. All positional arguments are inputs.
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["selects", "attribute", "table"])]
+    pub dump_assets: Option<PathBuf>,
+
+    /// Extract page metadata: meta tags, Open Graph, Twitter cards, and JSON-LD.
+    ///
+    /// Prints one JSON object per input, or `key<TAB>value` lines with text output.
+    /// All positional arguments are inputs.
+    #[arg(long, conflicts_with_all = ["selects", "attribute", "table", "dump_assets"])]
+    pub metadata: bool,
+
+    /// Extract the main article content, like a browser's reader mode.
+    ///
+    /// Prints the title, byline, and body as Markdown, or one JSON object per input
+    /// with plain-text and Markdown bodies. All positional arguments are inputs.
+    #[arg(
+        long,
+        conflicts_with_all = ["selects", "attribute", "table", "dump_assets", "metadata"]
+    )]
+    pub article: bool,
+
+    /// Extract records described by a JSON schema file.
+    ///
+    /// The file maps field names to selectors, like --select, or to objects with
+    /// `selector`, `attr`, `html`, `many`, `transform`, `required`, and nested `fields`.
+    /// Prints one JSON record per input, in a JSON array for text (the default) and json
+    /// output or one per line for ndjson. All positional arguments are inputs.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["selects", "attribute", "table", "dump_assets", "metadata", "article"]
+    )]
+    pub schema: Option<PathBuf>,
+
     /// Extract attribute value instead of text content.
     #[arg(short = 'a', long = "attribute", value_name = "ATTR")]
     pub attribute: Option<String>,
@@ -51,6 +136,38 @@ pub struct Args {
     #[arg(short = '1', long)]
     pub first: bool,
 
+    /// Record where each value came from: its selector and the CSS path and source line of
+    /// its element.
+    ///
+    /// Adds a `provenance` object to every value in JSON and NDJSON output.
+    #[arg(long, requires = "selects")]
+    pub provenance: bool,
+
+    /// Reject an input's record when the --select field NAME matches nothing.
+    ///
+    /// Repeatable. Rejected records are left out of the output and counted in a summary
+    /// on stderr; NDJSON output also logs each one as a `{"rejected": REASON}` line.
+    #[arg(long, value_name = "NAME", requires = "selects")]
+    pub require: Vec<String>,
+
+    /// How inputs are parsed.
+    ///
+    /// XML keeps the case of tag names, lets `<tag/>` close any element, and reads
+    /// CDATA sections as text, for feeds, sitemaps, and XHTML. Malformed XML fails the
+    /// input. Applies to selector extraction.
+    #[arg(long, value_enum, default_value_t = InputFormat::Html)]
+    pub input_format: InputFormat,
+
+    /// Fail an input on its first HTML parse error.
+    ///
+    /// The error names the line it was found on. Applies to selector extraction.
+    #[arg(long, conflicts_with_all = ["table", "dump_assets", "metadata", "article"])]
+    pub strict: bool,
+
+    /// Print HTML parse errors to stderr, one line per error.
+    #[arg(long, conflicts_with_all = ["table", "dump_assets", "metadata", "article"])]
+    pub warnings: bool,
+
     /// Colorize output.
     #[arg(short = 'c', long, value_enum, default_value_t = ColorMode::Auto)]
     pub color: ColorMode,
@@ -59,6 +176,10 @@ pub struct Args {
     #[arg(short = 'p', long)]
     pub pretty: bool,
 
+    /// Indent HTML output (-o html, -o template), one block-level element per line.
+    #[arg(long)]
+    pub pretty_html: bool,
+
     /// Use NUL as line delimiter (for xargs -0).
     #[arg(short = '0', long)]
     pub null: bool,
@@ -92,10 +213,304 @@ pub struct Args {
     #[arg(long = "explain")]
     pub explain: bool,
 
+    /// Re-run the extraction whenever an input file changes.
+    ///
+    /// Input files (and the --schema file) are watched for changes. The first run prints
+    /// its output in full, and each later run only the lines that changed, as `-` and `+`
+    /// lines. Runs until interrupted.
+    #[arg(short = 'w', long, conflicts_with_all = ["interactive", "explain"])]
+    pub watch: bool,
+
     /// Request timeout in seconds (for URL fetch).
     #[cfg(feature = "url")]
     #[arg(long = "timeout", default_value = "30", value_name = "SECONDS")]
     pub timeout: u64,
+
+    /// Maximum number of URLs fetched at the same time.
+    #[cfg(feature = "url")]
+    #[arg(long, default_value_t = 4, value_name = "N")]
+    pub concurrency: usize,
+
+    /// Maximum requests per second to any single host.
+    #[cfg(feature = "url")]
+    #[arg(long, value_name = "N")]
+    pub rate_limit: Option<f64>,
+
+    /// Retries after a timeout, connection error, 429, or 5xx response.
+    #[cfg(feature = "url")]
+    #[arg(long, default_value_t = 2, value_name = "N")]
+    pub retries: u32,
+
+    /// Load URL inputs in headless Chromium and extract from the rendered page.
+    ///
+    /// For pages that build their content with JavaScript. The HTML is taken once the
+    /// network has been idle for half a second, or once --wait-for matches.
+    #[cfg(feature = "render")]
+    #[arg(long)]
+    pub render: bool,
+
+    /// With --render, wait for an element matching SELECTOR instead of network idle.
+    #[cfg(feature = "render")]
+    #[arg(long, value_name = "SELECTOR", requires = "render")]
+    pub wait_for: Option<String>,
+}
+
+/// Subcommands.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Harvest email addresses, phone numbers, and URLs.
+    ///
+    /// Scans mailto:, tel:, and http(s) links plus visible text. Results from all
+    /// inputs are validated, normalized, and deduplicated.
+    Contacts(ContactsArgs),
+
+    /// Print the DOM structure of a page as an indented tree.
+    ///
+    /// A quick way to get oriented before writing selectors.
+    Tree(TreeArgs),
+
+    /// Measure parse and query throughput on this machine.
+    ///
+    /// Benchmarks the given files, or a pinned corpus of real-world pages that is
+    /// downloaded once and cached. Exits with status 1 if any metric regresses
+    /// against --baseline by more than --max-regression.
+    Bench(BenchArgs),
+
+    /// Save a page as a single HTML file with its stylesheets and images inlined.
+    ///
+    /// Downloads linked stylesheets and images and embeds them as `<style>` blocks and
+    /// data: URIs, for snapshotting pages. Exits with status 1 if any asset could not
+    /// be downloaded; the archive is still written.
+    Archive(ArchiveArgs),
+
+    /// Compare two pages structurally and print the changed elements.
+    ///
+    /// Reports added and removed elements, changed attributes, and changed text, with
+    /// the CSS path of each element. Exits with status 1 if anything changed.
+    Diff(DiffArgs),
+
+    /// Crawl a site from a start URL and extract fields from every page as NDJSON.
+    ///
+    /// Follows links on the same host breadth-first, up to --max-depth links away and
+    /// --max-pages pages in total. Obeys robots.txt, including Crawl-delay, and robots
+    /// meta tags unless --ignore-robots is given.
+    Crawl(CrawlArgs),
+
+    /// Check pages for common HTML mistakes.
+    ///
+    /// Reports duplicate ids, elements nested where HTML does not allow them,
+    /// obsolete tags, images without alt text, and elements left unclosed. Exits with
+    /// status 1 if any problem is found.
+    Lint(LintArgs),
+}
+
+/// Arguments for `scrape contacts`.
+#[derive(clap::Args, Debug)]
+pub struct ContactsArgs {
+    /// Input HTML files.
+    ///
+    /// If not provided, reads from stdin.
+    #[arg(value_name = "FILES")]
+    pub files: Vec<PathBuf>,
+
+    /// Output format (text, json, or ndjson).
+    #[arg(short = 'o', long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Pretty-print JSON output.
+    #[arg(short = 'p', long)]
+    pub pretty: bool,
+
+    /// Fetch HTML from URL instead of file.
+    #[cfg(feature = "url")]
+    #[arg(short = 'u', long = "url", value_name = "URL")]
+    pub url: Option<String>,
+}
+
+/// Arguments for `scrape tree`.
+#[derive(clap::Args, Debug)]
+pub struct TreeArgs {
+    /// Input HTML file or http(s) URL.
+    ///
+    /// If not provided, reads from stdin.
+    #[arg(value_name = "FILE|URL")]
+    pub input: Option<String>,
+
+    /// Maximum depth to print below each root.
+    #[arg(short = 'd', long, value_name = "N")]
+    pub depth: Option<usize>,
+
+    /// Print only the subtrees of elements matching this CSS selector.
+    #[arg(short = 's', long, value_name = "SELECTOR")]
+    pub selector: Option<String>,
+
+    /// Include comment nodes.
+    #[arg(long)]
+    pub comments: bool,
+
+    /// Colorize output.
+    #[arg(short = 'c', long, value_enum, default_value_t = ColorMode::Auto)]
+    pub color: ColorMode,
+}
+
+/// Arguments for `scrape archive`.
+#[derive(clap::Args, Debug)]
+pub struct ArchiveArgs {
+    /// Page to archive: an http(s) URL or an HTML file.
+    #[arg(value_name = "URL|FILE")]
+    pub input: String,
+
+    /// Write the archived page to this file instead of stdout.
+    #[arg(short = 'o', long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Base URL for resolving relative references; defaults to the page URL.
+    #[arg(long, value_name = "URL")]
+    pub base_url: Option<String>,
+
+    /// Suppress messages about assets that could not be downloaded.
+    #[arg(short = 'q', long)]
+    pub quiet: bool,
+}
+
+/// Arguments for `scrape diff`.
+#[derive(clap::Args, Debug)]
+pub struct DiffArgs {
+    /// The old version of the page: an HTML file or http(s) URL.
+    #[arg(value_name = "OLD")]
+    pub old: String,
+
+    /// The new version of the page: an HTML file or http(s) URL.
+    #[arg(value_name = "NEW")]
+    pub new: String,
+
+    /// Compare only the elements matching this CSS selector.
+    #[arg(short = 's', long, value_name = "SELECTOR")]
+    pub selector: Option<String>,
+
+    /// Output format (text, json, or ndjson).
+    #[arg(short = 'o', long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Pretty-print JSON output.
+    #[arg(short = 'p', long)]
+    pub pretty: bool,
+}
+
+/// Arguments for `scrape crawl`.
+#[derive(clap::Args, Debug)]
+pub struct CrawlArgs {
+    /// URL to start crawling from.
+    #[arg(value_name = "URL")]
+    pub start: String,
+
+    /// Named selector to extract from every page (NAME=SELECTOR, can be repeated).
+    #[arg(short = 's', long = "select", value_name = "NAME=SELECTOR")]
+    pub selects: Vec<String>,
+
+    /// Extract this attribute instead of text.
+    #[arg(short = 'a', long, value_name = "ATTR")]
+    pub attribute: Option<String>,
+
+    /// Extract the fields described by a JSON schema file instead of --select.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["selects", "attribute"])]
+    pub schema: Option<PathBuf>,
+
+    /// Links to follow (can be repeated): a CSS selector, or a URL pattern starting with
+    /// `/` or http(s):// in which `*` matches anything. Defaults to every link.
+    ///
+    /// A selector follows the links it matches and the links inside the elements it
+    /// matches. A pattern starting with `/` is matched against the path and query.
+    #[arg(short = 'f', long, value_name = "SELECTOR|PATTERN")]
+    pub follow: Vec<String>,
+
+    /// Follow links at most this many steps away from the start URL.
+    #[arg(short = 'd', long, default_value_t = 2, value_name = "N")]
+    pub max_depth: usize,
+
+    /// Stop after fetching this many pages.
+    #[arg(short = 'n', long, default_value_t = 100, value_name = "N")]
+    pub max_pages: usize,
+
+    /// Also start from the pages listed in the site's sitemaps.
+    #[arg(long)]
+    pub sitemap: bool,
+
+    /// Ignore robots.txt and robots meta tags.
+    #[arg(long)]
+    pub ignore_robots: bool,
+
+    /// Request timeout in seconds.
+    #[arg(long, default_value_t = 30, value_name = "SECONDS")]
+    pub timeout: u64,
+
+    /// Maximum requests per second; robots.txt Crawl-delay may lower it.
+    #[arg(long, value_name = "N")]
+    pub rate_limit: Option<f64>,
+
+    /// Suppress messages about pages that were skipped or failed.
+    #[arg(short = 'q', long)]
+    pub quiet: bool,
+}
+
+/// Arguments for `scrape lint`.
+#[derive(clap::Args, Debug)]
+pub struct LintArgs {
+    /// Input HTML files.
+    ///
+    /// If not provided, reads from stdin.
+    #[arg(value_name = "FILES")]
+    pub files: Vec<PathBuf>,
+
+    /// Output format (text, json, or ndjson).
+    #[arg(short = 'o', long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Pretty-print JSON output.
+    #[arg(short = 'p', long)]
+    pub pretty: bool,
+}
+
+/// Arguments for `scrape bench`.
+#[derive(clap::Args, Debug)]
+pub struct BenchArgs {
+    /// HTML files to benchmark instead of the pinned corpus.
+    #[arg(value_name = "FILES")]
+    pub files: Vec<PathBuf>,
+
+    /// Directory caching the pinned corpus.
+    ///
+    /// Defaults to scrape/bench-corpus in the user cache directory.
+    #[arg(long, value_name = "DIR")]
+    pub corpus_dir: Option<PathBuf>,
+
+    /// Fail instead of downloading missing corpus pages.
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Timed runs per measurement; the median is reported.
+    #[arg(short = 'n', long, default_value_t = 20, value_name = "N")]
+    pub iterations: usize,
+
+    /// Untimed warmup runs per measurement.
+    #[arg(long, default_value_t = 3, value_name = "N")]
+    pub warmup: usize,
+
+    /// Selector to time (can be repeated); replaces the default set.
+    #[arg(short = 's', long = "selector", value_name = "SELECTOR")]
+    pub selectors: Vec<String>,
+
+    /// Compare results against a baseline saved with --save-baseline.
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+
+    /// Save results as a baseline file.
+    #[arg(long, value_name = "FILE")]
+    pub save_baseline: Option<PathBuf>,
+
+    /// Largest tolerated slowdown against --baseline, in percent.
+    #[arg(long, default_value_t = 10.0, value_name = "PERCENT")]
+    pub max_regression: f64,
 }
 
 /// Output format for extraction results.
@@ -105,10 +520,27 @@ pub enum OutputFormat {
     Text,
     /// JSON array or object
     Json,
+    /// One JSON object per line (JSON Lines)
+    Ndjson,
     /// HTML fragments
     Html,
     /// CSV format (for named selectors)
     Csv,
+    /// Markdown rendering of each match
+    Markdown,
+    /// Per-selector share of inputs with a match, printed after the last input
+    Summary,
+    /// One line per match, laid out by --template
+    Template,
+}
+
+/// Input format for selector extraction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// HTML, parsed the way browsers do
+    Html,
+    /// XML, such as RSS and Atom feeds, sitemaps, and XHTML
+    Xml,
 }
 
 /// Color mode for terminal output.
@@ -128,20 +560,194 @@ impl Args {
     /// # Errors
     ///
     /// Returns an error if arguments are invalid or conflicting.
+    #[allow(clippy::too_many_lines, clippy::literal_string_with_formatting_args)]
     pub fn parse_and_validate() -> Result<Self, String> {
-        let args = Self::parse();
+        let mut args = Self::parse();
+
+        match &args.command {
+            Some(Command::Contacts(contacts)) => {
+                if matches!(
+                    contacts.output,
+                    OutputFormat::Html
+                        | OutputFormat::Csv
+                        | OutputFormat::Markdown
+                        | OutputFormat::Summary
+                        | OutputFormat::Template
+                ) {
+                    return Err("contacts supports only text, json, and ndjson output".into());
+                }
+                return Ok(args);
+            }
+            Some(Command::Diff(diff)) => {
+                if !matches!(
+                    diff.output,
+                    OutputFormat::Text | OutputFormat::Json | OutputFormat::Ndjson
+                ) {
+                    return Err("diff supports only text, json, and ndjson output".into());
+                }
+                return Ok(args);
+            }
+            Some(Command::Tree(_) | Command::Archive(_)) => return Ok(args),
+            Some(Command::Bench(bench)) => {
+                if bench.iterations == 0 {
+                    return Err("--iterations must be at least 1".into());
+                }
+                if !(0.0..100.0).contains(&bench.max_regression) {
+                    return Err("--max-regression must be between 0 and 100".into());
+                }
+                return Ok(args);
+            }
+            Some(Command::Crawl(crawl)) => {
+                if !is_url(&crawl.start) {
+                    return Err("crawl requires an http(s) start URL".into());
+                }
+                if let Some(select) = crawl.selects.iter().find(|select| !select.contains('=')) {
+                    return Err(format!("Invalid --select format: {select}. Use NAME=SELECTOR"));
+                }
+                if crawl.rate_limit.is_some_and(|rate| !(rate.is_finite() && rate > 0.0)) {
+                    return Err("--rate-limit must be a positive number".into());
+                }
+                return Ok(args);
+            }
+            Some(Command::Lint(lint)) => {
+                if !matches!(
+                    lint.output,
+                    OutputFormat::Text | OutputFormat::Json | OutputFormat::Ndjson
+                ) {
+                    return Err("lint supports only text, json, and ndjson output".into());
+                }
+                return Ok(args);
+            }
+            None => {}
+        }
+
+        // With --select there is no positional selector, so the first positional argument is
+        // an input, unless it names neither a file nor a URL.
+        if !args.selects.is_empty()
+            && let Some(first) = args.selector.take()
+        {
+            if !is_url(&first) && !Path::new(&first).exists() {
+                return Err("Cannot use both <SELECTOR> and --select".into());
+            }
+            args.files.insert(0, PathBuf::from(first));
+        }
+
+        if args.parallel == Some(0) {
+            return Err("--parallel must be at least 1".into());
+        }
+        #[cfg(feature = "url")]
+        if args.concurrency == 0 {
+            return Err("--concurrency must be at least 1".into());
+        }
+        #[cfg(feature = "url")]
+        if args.rate_limit.is_some_and(|rate| !(rate.is_finite() && rate > 0.0)) {
+            return Err("--rate-limit must be a positive number".into());
+        }
+
+        if args.pretty_html && !matches!(args.output, OutputFormat::Html | OutputFormat::Template) {
+            return Err("--pretty-html requires -o html or -o template".into());
+        }
+        if args.output == OutputFormat::Template && args.template.is_none() {
+            return Err("-o template requires --template".into());
+        }
+        if args.template.is_some() && args.output != OutputFormat::Template {
+            return Err("--template requires -o template".into());
+        }
+
+        if args.input_format == InputFormat::Xml {
+            if args.interactive
+                || args.table.is_some()
+                || args.dump_assets.is_some()
+                || args.metadata
+                || args.article
+                || args.schema.is_some()
+            {
+                return Err("--input-format xml supports only <SELECTOR> and --select".into());
+            }
+            if args.strict || args.warnings {
+                return Err("--strict and --warnings apply only to HTML input".into());
+            }
+        }
 
         // Interactive and explain modes don't need selectors
         if args.interactive || args.explain {
             return Ok(args);
         }
 
-        if args.selector.is_none() && args.selects.is_empty() {
-            return Err("Either <SELECTOR> or --select must be provided".into());
+        if args.watch {
+            let inputs = args.inputs();
+            if inputs.is_empty() || inputs.iter().any(Input::is_url) {
+                return Err("--watch requires local input files".into());
+            }
+        }
+
+        if args.dump_assets.is_some() {
+            if matches!(
+                args.output,
+                OutputFormat::Html
+                    | OutputFormat::Csv
+                    | OutputFormat::Markdown
+                    | OutputFormat::Summary
+                    | OutputFormat::Template
+            ) {
+                return Err("--dump-assets supports only text, json, and ndjson output".into());
+            }
+            return Ok(args);
+        }
+
+        if args.metadata {
+            if matches!(
+                args.output,
+                OutputFormat::Html
+                    | OutputFormat::Csv
+                    | OutputFormat::Markdown
+                    | OutputFormat::Summary
+                    | OutputFormat::Template
+            ) {
+                return Err("--metadata supports only text, json, and ndjson output".into());
+            }
+            return Ok(args);
+        }
+
+        if args.article {
+            if matches!(
+                args.output,
+                OutputFormat::Html
+                    | OutputFormat::Csv
+                    | OutputFormat::Summary
+                    | OutputFormat::Template
+            ) {
+                return Err(
+                    "--article supports only text, markdown, json, and ndjson output".into()
+                );
+            }
+            return Ok(args);
         }
 
-        if args.selector.is_some() && !args.selects.is_empty() {
-            return Err("Cannot use both <SELECTOR> and --select".into());
+        if args.schema.is_some() {
+            if !matches!(
+                args.output,
+                OutputFormat::Text | OutputFormat::Json | OutputFormat::Ndjson
+            ) {
+                return Err("--schema supports only text, json, and ndjson output".into());
+            }
+            return Ok(args);
+        }
+
+        if args.table.is_some() {
+            if matches!(
+                args.output,
+                OutputFormat::Html | OutputFormat::Summary | OutputFormat::Template
+            ) {
+                return Err(
+                    "--table supports only text, csv, markdown, json, and ndjson output".into()
+                );
+            }
+            return Ok(args);
+        }
+
+        if args.selector.is_none() && args.selects.is_empty() {
+            return Err("Either <SELECTOR> or --select must be provided".into());
         }
 
         if args.output == OutputFormat::Csv && args.selects.is_empty() {
@@ -154,9 +760,30 @@ impl Args {
             }
         }
 
+        if !args.selects.is_empty() && args.template.as_ref().is_some_and(Template::needs_element) {
+            return Err("--template with --select supports only {text}, {name}, {filename}, and \
+                        {index}"
+                .into());
+        }
+
+        let names: Vec<_> =
+            args.selects.iter().filter_map(|s| s.split_once('=')).map(|(name, _)| name).collect();
+        if let Some(name) = args.require.iter().find(|name| !names.contains(&name.as_str())) {
+            return Err(format!("--require {name} does not name a --select field"));
+        }
+
         Ok(args)
     }
 
+    /// Build the extraction schema for --select, with the --require rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any selector is invalid.
+    pub fn named_schema(&self) -> anyhow::Result<Schema> {
+        extract::named_schema(&self.parse_selects(), self.attribute.as_deref(), &self.require)
+    }
+
     /// Parse named selectors into (name, selector) pairs.
     #[must_use]
     pub fn parse_selects(&self) -> Vec<(String, String)> {
@@ -169,6 +796,66 @@ impl Args {
             .collect()
     }
 
+    /// Collect the inputs: the --url page, then positional files and URLs.
+    ///
+    /// With --table, --dump-assets, --metadata, --article, or --schema there is no
+    /// positional selector, so the first positional argument is an input too.
+    #[must_use]
+    pub fn inputs(&self) -> Vec<Input> {
+        let mut inputs = Vec::with_capacity(self.files.len() + 2);
+        #[cfg(feature = "url")]
+        inputs.extend(self.url.iter().map(|url| Input::Url(url.clone())));
+        if self.table.is_some()
+            || self.dump_assets.is_some()
+            || self.metadata
+            || self.article
+            || self.schema.is_some()
+        {
+            inputs.extend(self.selector.iter().map(|s| Input::from_path(PathBuf::from(s))));
+        }
+        inputs.extend(self.files.iter().cloned().map(Input::from_path));
+        inputs
+    }
+
+    /// Build the fetcher for URL inputs from the request flags.
+    #[cfg(feature = "url")]
+    #[must_use]
+    pub fn fetcher(&self) -> Fetcher {
+        let timeout = std::time::Duration::from_secs(self.timeout);
+        let config = FetchConfig { timeout, retries: self.retries, ..FetchConfig::default() };
+        let concurrency = NonZeroUsize::new(self.concurrency).unwrap_or(NonZeroUsize::MIN);
+        let fetcher = Fetcher::new(config, concurrency, self.rate_limit);
+        #[cfg(feature = "render")]
+        if self.render {
+            let wait_for = self.wait_for.clone().map_or(WaitFor::NetworkIdle, WaitFor::Selector);
+            return fetcher.with_renderer(Renderer::new(wait_for, timeout));
+        }
+        fetcher
+    }
+
+    /// Build the fetcher for URL inputs from the request flags.
+    #[cfg(not(feature = "url"))]
+    #[must_use]
+    pub fn fetcher(&self) -> Fetcher {
+        Fetcher::new(FetchConfig::default(), NonZeroUsize::MIN, None)
+    }
+
+    /// Input parsing and parse error handling from --input-format, --strict, and --warnings.
+    #[must_use]
+    pub const fn parse_check(&self) -> ParseCheck {
+        ParseCheck {
+            strict: self.strict,
+            warnings: self.warnings,
+            xml: matches!(self.input_format, InputFormat::Xml),
+        }
+    }
+
+    /// HTML serialization for the matches of a selector, from --pretty-html.
+    #[must_use]
+    pub fn serialize_options(&self) -> SerializeOptions {
+        SerializeOptions { pretty: self.pretty_html, ..SerializeOptions::default() }
+    }
+
     /// Determine if filenames should be shown.
     #[must_use]
     pub fn show_filename(&self) -> bool {
@@ -178,7 +865,11 @@ impl Args {
         if self.no_filename {
             return false;
         }
-        self.files.len() > 1
+        #[cfg(feature = "url")]
+        let inputs = self.files.len() + usize::from(self.url.is_some());
+        #[cfg(not(feature = "url"))]
+        let inputs = self.files.len();
+        inputs > 1
     }
 }
 
@@ -189,14 +880,27 @@ mod tests {
     #[test]
     fn test_parse_selects() {
         let args = Args {
+            command: None,
             selector: None,
             files: vec![],
             selects: vec!["title=h1".into(), "links=a[href]".into()],
             output: OutputFormat::Text,
+            template: None,
+            table: None,
+            dump_assets: None,
+            metadata: false,
+            article: false,
+            schema: None,
             attribute: None,
             first: false,
+            provenance: false,
+            require: Vec::new(),
+            input_format: InputFormat::Html,
+            strict: false,
+            warnings: false,
             color: ColorMode::Auto,
             pretty: false,
+            pretty_html: false,
             null: false,
             quiet: false,
             parallel: None,
@@ -206,8 +910,19 @@ mod tests {
             url: None,
             interactive: false,
             explain: false,
+            watch: false,
             #[cfg(feature = "url")]
             timeout: 30,
+            #[cfg(feature = "url")]
+            concurrency: 4,
+            #[cfg(feature = "url")]
+            rate_limit: None,
+            #[cfg(feature = "url")]
+            retries: 2,
+            #[cfg(feature = "render")]
+            render: false,
+            #[cfg(feature = "render")]
+            wait_for: None,
         };
 
         let selects = args.parse_selects();
@@ -219,14 +934,27 @@ mod tests {
     #[test]
     fn test_show_filename_explicit() {
         let mut args = Args {
+            command: None,
             selector: Some("h1".into()),
             files: vec![],
             selects: vec![],
             output: OutputFormat::Text,
+            template: None,
+            table: None,
+            dump_assets: None,
+            metadata: false,
+            article: false,
+            schema: None,
             attribute: None,
             first: false,
+            provenance: false,
+            require: Vec::new(),
+            input_format: InputFormat::Html,
+            strict: false,
+            warnings: false,
             color: ColorMode::Auto,
             pretty: false,
+            pretty_html: false,
             null: false,
             quiet: false,
             parallel: None,
@@ -236,8 +964,19 @@ mod tests {
             url: None,
             interactive: false,
             explain: false,
+            watch: false,
             #[cfg(feature = "url")]
             timeout: 30,
+            #[cfg(feature = "url")]
+            concurrency: 4,
+            #[cfg(feature = "url")]
+            rate_limit: None,
+            #[cfg(feature = "url")]
+            retries: 2,
+            #[cfg(feature = "render")]
+            render: false,
+            #[cfg(feature = "render")]
+            wait_for: None,
         };
 
         assert!(args.show_filename());
@@ -250,14 +989,27 @@ mod tests {
     #[test]
     fn test_show_filename_auto() {
         let mut args = Args {
+            command: None,
             selector: Some("h1".into()),
             files: vec!["a.html".into()],
             selects: vec![],
             output: OutputFormat::Text,
+            template: None,
+            table: None,
+            dump_assets: None,
+            metadata: false,
+            article: false,
+            schema: None,
             attribute: None,
             first: false,
+            provenance: false,
+            require: Vec::new(),
+            input_format: InputFormat::Html,
+            strict: false,
+            warnings: false,
             color: ColorMode::Auto,
             pretty: false,
+            pretty_html: false,
             null: false,
             quiet: false,
             parallel: None,
@@ -267,8 +1019,19 @@ mod tests {
             url: None,
             interactive: false,
             explain: false,
+            watch: false,
             #[cfg(feature = "url")]
             timeout: 30,
+            #[cfg(feature = "url")]
+            concurrency: 4,
+            #[cfg(feature = "url")]
+            rate_limit: None,
+            #[cfg(feature = "url")]
+            retries: 2,
+            #[cfg(feature = "render")]
+            render: false,
+            #[cfg(feature = "render")]
+            wait_for: None,
         };
 
         assert!(!args.show_filename());
@@ -276,4 +1039,115 @@ mod tests {
         args.files.push("b.html".into());
         assert!(args.show_filename());
     }
+
+    #[test]
+    fn test_table_inputs() {
+        let args = Args {
+            command: None,
+            selector: Some("a.html".into()),
+            files: vec!["b.html".into()],
+            selects: vec![],
+            output: OutputFormat::Csv,
+            template: None,
+            table: Some("table".into()),
+            dump_assets: None,
+            metadata: false,
+            article: false,
+            schema: None,
+            attribute: None,
+            first: false,
+            provenance: false,
+            require: Vec::new(),
+            input_format: InputFormat::Html,
+            strict: false,
+            warnings: false,
+            color: ColorMode::Auto,
+            pretty: false,
+            pretty_html: false,
+            null: false,
+            quiet: false,
+            parallel: None,
+            with_filename: false,
+            no_filename: false,
+            #[cfg(feature = "url")]
+            url: None,
+            interactive: false,
+            explain: false,
+            watch: false,
+            #[cfg(feature = "url")]
+            timeout: 30,
+            #[cfg(feature = "url")]
+            concurrency: 4,
+            #[cfg(feature = "url")]
+            rate_limit: None,
+            #[cfg(feature = "url")]
+            retries: 2,
+            #[cfg(feature = "render")]
+            render: false,
+            #[cfg(feature = "render")]
+            wait_for: None,
+        };
+
+        assert_eq!(args.inputs(), vec![Input::File("a.html".into()), Input::File("b.html".into())]);
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn test_inputs_and_fetcher() {
+        let mut args = Args {
+            command: None,
+            selector: Some("h1".into()),
+            files: vec!["a.html".into(), "https://example.org/".into()],
+            selects: vec![],
+            output: OutputFormat::Text,
+            template: None,
+            table: None,
+            dump_assets: None,
+            metadata: false,
+            article: false,
+            schema: None,
+            attribute: None,
+            first: false,
+            provenance: false,
+            require: Vec::new(),
+            input_format: InputFormat::Html,
+            strict: false,
+            warnings: false,
+            color: ColorMode::Auto,
+            pretty: false,
+            pretty_html: false,
+            null: false,
+            quiet: false,
+            parallel: None,
+            with_filename: false,
+            no_filename: false,
+            url: Some("https://example.com/".into()),
+            interactive: false,
+            explain: false,
+            watch: false,
+            timeout: 30,
+            concurrency: 8,
+            rate_limit: Some(2.0),
+            retries: 2,
+            #[cfg(feature = "render")]
+            render: false,
+            #[cfg(feature = "render")]
+            wait_for: None,
+        };
+
+        assert_eq!(
+            args.inputs(),
+            vec![
+                Input::Url("https://example.com/".into()),
+                Input::File("a.html".into()),
+                Input::Url("https://example.org/".into()),
+            ]
+        );
+        assert_eq!(args.fetcher().concurrency(), 8);
+
+        args.files.clear();
+        assert!(!args.show_filename());
+        args.files.push("b.html".into());
+        assert!(args.show_filename());
+    }
 }
@@ -0,0 +1,142 @@
+//! `--article` extraction of the main content, like a browser's reader mode.
+
+use std::io::{self, Read, Write};
+
+use anyhow::Result;
+use scrape_core::{Soup, article::Article};
+use serde_json::{Value, json};
+
+use crate::{
+    args::{Args, OutputFormat},
+    batch::{Input, process_articles},
+    fetch::Fetcher,
+};
+
+/// Extracts the article of every input and prints it.
+///
+/// Returns `true` if any input has article text.
+///
+/// # Errors
+///
+/// Returns an error if stdin or output cannot be read or written.
+pub fn run(
+    args: &Args,
+    inputs: &[Input],
+    fetcher: &Fetcher,
+    writer: &mut dyn Write,
+) -> Result<bool> {
+    let mut found = Vec::new();
+
+    if inputs.is_empty() {
+        let mut html = String::new();
+        io::stdin().read_to_string(&mut html)?;
+        found.push(("-".to_string(), Soup::parse(&html).extract_article()));
+    } else {
+        for file_result in process_articles(inputs, fetcher) {
+            match file_result.result {
+                Ok(article) => found.push((file_result.filename, article)),
+                Err(e) if !args.quiet => eprintln!("{}: {e}", file_result.filename),
+                Err(_) => {}
+            }
+        }
+    }
+
+    let any = found.iter().any(|(_, article)| !article.text.is_empty());
+    write_articles(writer, found, args.output, args.pretty)?;
+    Ok(any)
+}
+
+/// Writes each article as a Markdown document with the title as a heading, or as JSON
+/// objects with an `input` field: one array for all inputs, or one object per line.
+fn write_articles(
+    writer: &mut dyn Write,
+    found: Vec<(String, Article)>,
+    format: OutputFormat,
+    pretty: bool,
+) -> io::Result<()> {
+    if matches!(format, OutputFormat::Json | OutputFormat::Ndjson) {
+        let objects = found.into_iter().map(|(input, article)| {
+            json!({
+                "input": input,
+                "title": article.title,
+                "byline": article.byline,
+                "published_date": article.published_date.map(|date| date.to_string()),
+                "text": article.text,
+                "markdown": article.markdown,
+            })
+        });
+        if format == OutputFormat::Ndjson {
+            for object in objects {
+                writeln!(writer, "{object}")?;
+            }
+            return Ok(());
+        }
+        let objects: Vec<Value> = objects.collect();
+        let output = if pretty {
+            serde_json::to_string_pretty(&objects)
+        } else {
+            serde_json::to_string(&objects)
+        }
+        .map_err(io::Error::other)?;
+        return writeln!(writer, "{output}");
+    }
+
+    for (i, (_, article)) in found.iter().enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        let header =
+            [article.title.as_ref().map(|title| format!("# {title}")), article.byline.clone()];
+        let blocks: Vec<_> = header
+            .into_iter()
+            .flatten()
+            .chain((!article.markdown.is_empty()).then(|| article.markdown.clone()))
+            .collect();
+        if !blocks.is_empty() {
+            writeln!(writer, "{}", blocks.join("\n\n"))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = "<title>Green Tea Basics | Tea Blog</title><meta name=\"author\" \
+                        content=\"Ann\"><meta name=\"date\" \
+                        content=\"2024-05-02\"><article><p>Brew green tea at 80 degrees, for two \
+                        minutes, in a warm pot.</p></article>";
+
+    #[test]
+    fn test_write_articles_text() {
+        let found = vec![("a.html".into(), Soup::parse(HTML).extract_article())];
+        let mut buf = Vec::new();
+        write_articles(&mut buf, found, OutputFormat::Text, false).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "# Green Tea Basics\n\nAnn\n\nBrew green tea at 80 degrees, for two minutes, in a \
+             warm pot.\n"
+        );
+    }
+
+    #[test]
+    fn test_write_articles_ndjson() {
+        let found = vec![
+            ("a.html".into(), Soup::parse(HTML).extract_article()),
+            ("b.html".into(), Article::default()),
+        ];
+        let mut buf = Vec::new();
+        write_articles(&mut buf, found, OutputFormat::Ndjson, false).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        let objects: Vec<Value> =
+            output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0]["input"], "a.html");
+        assert_eq!(objects[0]["byline"], "Ann");
+        assert_eq!(objects[0]["published_date"], "2024-05-02");
+        assert!(objects[0]["text"].as_str().unwrap().starts_with("Brew green tea"));
+        assert_eq!(objects[1]["title"], Value::Null);
+        assert_eq!(objects[1]["published_date"], Value::Null);
+    }
+}
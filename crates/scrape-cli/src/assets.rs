@@ -0,0 +1,139 @@
+//! `--dump-assets` extraction of inline assets to files.
+
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use scrape_core::{Soup, extract::InlineAsset};
+use serde::Serialize;
+
+use crate::{
+    args::{Args, OutputFormat},
+    batch::{Input, process_assets},
+    fetch::Fetcher,
+};
+
+/// A written asset file, as reported on stdout.
+#[derive(Debug, Serialize)]
+struct Written {
+    /// Input the asset came from (`-` for stdin).
+    input: String,
+    /// Path of the written file.
+    path: PathBuf,
+    /// MIME type of the asset.
+    mime: String,
+    /// Size in bytes.
+    size: usize,
+}
+
+/// Writes the inline assets of every input to `dir` and reports each file.
+///
+/// Files are named `asset-NNNN.EXT`, numbered in input order and document order.
+/// Returns `true` if any asset was written.
+///
+/// # Errors
+///
+/// Returns an error if `dir` or an asset file cannot be written, or if output cannot
+/// be written.
+pub fn run(
+    args: &Args,
+    dir: &Path,
+    inputs: &[Input],
+    fetcher: &Fetcher,
+    writer: &mut dyn Write,
+) -> Result<bool> {
+    let mut found = Vec::new();
+
+    if inputs.is_empty() {
+        let mut html = String::new();
+        io::stdin().read_to_string(&mut html)?;
+        found.push(("-".to_string(), Soup::parse(&html).inline_assets()));
+    } else {
+        for file_result in process_assets(inputs, fetcher) {
+            match file_result.result {
+                Ok(assets) => found.push((file_result.filename, assets)),
+                Err(e) if !args.quiet => eprintln!("{}: {e}", file_result.filename),
+                Err(_) => {}
+            }
+        }
+    }
+
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let mut written = Vec::new();
+    for (input, assets) in found {
+        for asset in assets {
+            written.push(save(dir, written.len() + 1, &input, asset)?);
+        }
+    }
+
+    write_report(writer, &written, args.output, args.pretty)?;
+    Ok(!written.is_empty())
+}
+
+fn save(dir: &Path, n: usize, input: &str, asset: InlineAsset) -> Result<Written> {
+    let path = dir.join(format!("asset-{n:04}.{}", asset.extension()));
+    fs::write(&path, &asset.data).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(Written { input: input.to_string(), path, mime: asset.mime, size: asset.data.len() })
+}
+
+/// Writes `path<TAB>mime<TAB>size` lines, a JSON array, or one JSON object per line.
+fn write_report(
+    writer: &mut dyn Write,
+    written: &[Written],
+    format: OutputFormat,
+    pretty: bool,
+) -> io::Result<()> {
+    if format == OutputFormat::Json {
+        let output = if pretty {
+            serde_json::to_string_pretty(written)
+        } else {
+            serde_json::to_string(written)
+        }
+        .map_err(io::Error::other)?;
+        return writeln!(writer, "{output}");
+    }
+
+    for file in written {
+        if format == OutputFormat::Ndjson {
+            serde_json::to_writer(&mut *writer, file).map_err(io::Error::other)?;
+            writeln!(writer)?;
+        } else {
+            writeln!(writer, "{}\t{}\t{}", file.path.display(), file.mime, file.size)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_save_and_report() {
+        let dir = TempDir::new().unwrap();
+        let soup = Soup::parse("<img src=\"data:image/png;base64,iVBORw0KGgo=\">");
+        let asset = soup.inline_assets().remove(0);
+
+        let file = save(dir.path(), 7, "page.html", asset).unwrap();
+        assert_eq!(file.path, dir.path().join("asset-0007.png"));
+        assert_eq!(fs::read(&file.path).unwrap(), b"\x89PNG\r\n\x1a\n");
+
+        let mut buf = Vec::new();
+        write_report(&mut buf, std::slice::from_ref(&file), OutputFormat::Text, false).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            format!("{}\timage/png\t8\n", file.path.display())
+        );
+
+        let mut buf = Vec::new();
+        write_report(&mut buf, &[file], OutputFormat::Ndjson, false).unwrap();
+        let line = String::from_utf8(buf).unwrap();
+        assert!(line.starts_with("{\"input\":\"page.html\",\"path\":"));
+        assert!(line.ends_with("\"mime\":\"image/png\",\"size\":8}\n"));
+    }
+}
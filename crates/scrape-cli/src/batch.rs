@@ -1,11 +1,76 @@
-//! Parallel batch file processing.
+//! Parallel batch processing of files and URLs.
 
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
 
 use anyhow::Result;
 use rayon::prelude::*;
+use scrape_core::{
+    Soup,
+    article::Article,
+    extract::{InlineAsset, Schema, Table},
+    metadata::Metadata,
+    runtime,
+};
+
+use crate::{
+    extract::{
+        Extraction, MatchFormat, NamedExtraction, ParseCheck, extract, extract_named,
+        extract_tables,
+    },
+    fetch::{Fetcher, is_url},
+};
+
+/// A batch input: a local file or an `http(s)://` URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// Local HTML file.
+    File(PathBuf),
+    /// URL fetched over HTTP.
+    Url(String),
+}
+
+impl Input {
+    /// Classifies a positional argument as a URL or a file path.
+    #[must_use]
+    pub fn from_path(path: PathBuf) -> Self {
+        match path.to_str() {
+            Some(s) if is_url(s) => Self::Url(s.to_string()),
+            _ => Self::File(path),
+        }
+    }
+
+    /// Returns `true` if the input is fetched over HTTP.
+    #[must_use]
+    pub const fn is_url(&self) -> bool {
+        matches!(self, Self::Url(_))
+    }
+
+    /// Name shown as the filename prefix and in error messages.
+    #[must_use]
+    pub fn name(&self) -> String {
+        match self {
+            Self::File(path) => path.display().to_string(),
+            Self::Url(url) => url.clone(),
+        }
+    }
 
-use crate::extract::{Extraction, extract, extract_named};
+    /// Reads the file or fetches the URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or the URL cannot be fetched.
+    pub fn read(&self, fetcher: &Fetcher) -> Result<String> {
+        match self {
+            Self::File(path) => Ok(fs::read_to_string(path)?),
+            Self::Url(url) => Ok(fetcher.fetch(url)?),
+        }
+    }
+}
 
 /// Result of processing a single file.
 pub struct FileResult {
@@ -20,59 +85,209 @@ pub struct FileNamedResult {
     /// The filename that was processed.
     pub filename: String,
     /// The extraction results or error.
-    pub result: Result<HashMap<String, Vec<Extraction>>>,
+    pub result: Result<NamedExtraction>,
 }
 
-/// Process multiple files in parallel with a single selector.
+/// Result of extracting tables from a single file.
+pub struct FileTablesResult {
+    /// The filename that was processed.
+    pub filename: String,
+    /// The extracted tables or error.
+    pub result: Result<Vec<Table>>,
+}
+
+/// Result of collecting inline assets from a single file.
+pub struct FileAssetsResult {
+    /// The filename that was processed.
+    pub filename: String,
+    /// The inline assets or error.
+    pub result: Result<Vec<InlineAsset>>,
+}
+
+/// Result of collecting metadata from a single file.
+pub struct FileMetadataResult {
+    /// The filename that was processed.
+    pub filename: String,
+    /// The metadata or error.
+    pub result: Result<Metadata>,
+}
+
+/// Result of extracting the article from a single file.
+pub struct FileArticleResult {
+    /// The filename that was processed.
+    pub filename: String,
+    /// The article or error.
+    pub result: Result<Article>,
+}
+
+/// Process multiple files and URLs in parallel with a single selector.
+///
+/// Runs on the thread pool configured through [`runtime::configure`].
 pub fn process_files(
-    files: &[PathBuf],
+    inputs: &[Input],
     selector: &str,
     attribute: Option<&str>,
     first_only: bool,
-    threads: Option<usize>,
+    format: MatchFormat<'_>,
+    check: ParseCheck,
+    fetcher: &Fetcher,
 ) -> Vec<FileResult> {
-    if let Some(n) = threads {
-        rayon::ThreadPoolBuilder::new().num_threads(n).build_global().ok();
-    }
-
-    files
-        .par_iter()
-        .map(|path| {
-            let filename = path.display().to_string();
-
-            let result = fs::read_to_string(path)
-                .map_err(anyhow::Error::from)
-                .and_then(|html| extract(&html, selector, attribute, first_only, false));
-
-            FileResult { filename, result }
-        })
-        .collect()
+    runtime::install(|| {
+        inputs
+            .par_iter()
+            .map(|input| {
+                let filename = input.name();
+
+                let result = input.read(fetcher).and_then(|html| {
+                    let soup = check.parse(&filename, &html)?;
+                    extract(&soup, selector, attribute, first_only, format)
+                });
+
+                FileResult { filename, result }
+            })
+            .collect()
+    })
 }
 
-/// Process multiple files in parallel with named selectors.
+/// Process multiple files and URLs in parallel with a schema of named selectors.
+///
+/// Runs on the thread pool configured through [`runtime::configure`].
 pub fn process_files_named(
-    files: &[PathBuf],
-    selectors: &[(String, String)],
-    attribute: Option<&str>,
+    inputs: &[Input],
+    schema: &Schema,
     first_only: bool,
-    threads: Option<usize>,
+    provenance: bool,
+    check: ParseCheck,
+    fetcher: &Fetcher,
 ) -> Vec<FileNamedResult> {
-    if let Some(n) = threads {
-        rayon::ThreadPoolBuilder::new().num_threads(n).build_global().ok();
+    runtime::install(|| {
+        inputs
+            .par_iter()
+            .map(|input| {
+                let filename = input.name();
+
+                let result = input.read(fetcher).and_then(|html| {
+                    let soup = check.parse(&filename, &html)?;
+                    Ok(extract_named(&soup, schema, first_only, provenance))
+                });
+
+                FileNamedResult { filename, result }
+            })
+            .collect()
+    })
+}
+
+/// Counts the records rejected by `--require` rules across a batch.
+#[derive(Debug, Default)]
+pub struct RejectionSummary {
+    records: usize,
+    reasons: BTreeMap<String, usize>,
+}
+
+impl RejectionSummary {
+    /// Counts one extracted record and its rejection reason, if it was rejected.
+    pub fn record(&mut self, rejection: Option<&str>) {
+        self.records += 1;
+        if let Some(reason) = rejection {
+            *self.reasons.entry(reason.to_string()).or_default() += 1;
+        }
     }
 
-    files
-        .par_iter()
-        .map(|path| {
-            let filename = path.display().to_string();
+    /// Writes how many records were rejected and why, or nothing if none were.
+    ///
+    /// # Errors
+    ///
+    /// Returns an IO error if writing fails.
+    pub fn write(&self, writer: &mut dyn Write) -> io::Result<()> {
+        let rejected: usize = self.reasons.values().sum();
+        if rejected == 0 {
+            return Ok(());
+        }
+        writeln!(writer, "rejected {rejected} of {} records:", self.records)?;
+        for (reason, count) in &self.reasons {
+            writeln!(writer, "  {count} {reason}")?;
+        }
+        Ok(())
+    }
+}
 
-            let result = fs::read_to_string(path)
-                .map_err(anyhow::Error::from)
-                .and_then(|html| extract_named(&html, selectors, attribute, first_only));
+/// Process multiple files and URLs in parallel, extracting matching tables.
+///
+/// Runs on the thread pool configured through [`runtime::configure`].
+pub fn process_tables(
+    inputs: &[Input],
+    selector: &str,
+    first_only: bool,
+    fetcher: &Fetcher,
+) -> Vec<FileTablesResult> {
+    runtime::install(|| {
+        inputs
+            .par_iter()
+            .map(|input| {
+                let filename = input.name();
+
+                let result = input
+                    .read(fetcher)
+                    .and_then(|html| extract_tables(&html, selector, first_only));
+
+                FileTablesResult { filename, result }
+            })
+            .collect()
+    })
+}
+
+/// Process multiple files and URLs in parallel, collecting their inline assets.
+///
+/// Runs on the thread pool configured through [`runtime::configure`].
+pub fn process_assets(inputs: &[Input], fetcher: &Fetcher) -> Vec<FileAssetsResult> {
+    runtime::install(|| {
+        inputs
+            .par_iter()
+            .map(|input| {
+                let filename = input.name();
+
+                let result = input.read(fetcher).map(|html| Soup::parse(&html).inline_assets());
+
+                FileAssetsResult { filename, result }
+            })
+            .collect()
+    })
+}
+
+/// Process multiple files and URLs in parallel, collecting their metadata.
+///
+/// Runs on the thread pool configured through [`runtime::configure`].
+pub fn process_metadata(inputs: &[Input], fetcher: &Fetcher) -> Vec<FileMetadataResult> {
+    runtime::install(|| {
+        inputs
+            .par_iter()
+            .map(|input| {
+                let filename = input.name();
+
+                let result = input.read(fetcher).map(|html| Soup::parse(&html).metadata());
+
+                FileMetadataResult { filename, result }
+            })
+            .collect()
+    })
+}
 
-            FileNamedResult { filename, result }
-        })
-        .collect()
+/// Process multiple files and URLs in parallel, extracting their main article.
+///
+/// Runs on the thread pool configured through [`runtime::configure`].
+pub fn process_articles(inputs: &[Input], fetcher: &Fetcher) -> Vec<FileArticleResult> {
+    runtime::install(|| {
+        inputs
+            .par_iter()
+            .map(|input| {
+                let filename = input.name();
+
+                let result = input.read(fetcher).map(|html| Soup::parse(&html).extract_article());
+
+                FileArticleResult { filename, result }
+            })
+            .collect()
+    })
 }
 
 #[cfg(test)]
@@ -82,6 +297,11 @@ mod tests {
     use tempfile::TempDir;
 
     use super::*;
+    use crate::{extract::named_schema, fetch::FetchConfig};
+
+    fn fetcher() -> Fetcher {
+        Fetcher::new(FetchConfig::default(), std::num::NonZeroUsize::MIN, None)
+    }
 
     #[test]
     fn test_process_files() {
@@ -95,8 +315,16 @@ mod tests {
         let mut f2 = File::create(&path_b).unwrap();
         writeln!(f2, "<h1>File B</h1>").unwrap();
 
-        let files = vec![path_a, path_b];
-        let results = process_files(&files, "h1", None, false, None);
+        let files = vec![Input::File(path_a), Input::File(path_b)];
+        let results = process_files(
+            &files,
+            "h1",
+            None,
+            false,
+            MatchFormat::default(),
+            ParseCheck::default(),
+            &fetcher(),
+        );
 
         assert_eq!(results.len(), 2);
 
@@ -113,8 +341,16 @@ mod tests {
 
     #[test]
     fn test_process_files_with_error() {
-        let files = vec![PathBuf::from("/nonexistent/file.html")];
-        let results = process_files(&files, "h1", None, false, None);
+        let files = vec![Input::File(PathBuf::from("/nonexistent/file.html"))];
+        let results = process_files(
+            &files,
+            "h1",
+            None,
+            false,
+            MatchFormat::default(),
+            ParseCheck::default(),
+            &fetcher(),
+        );
 
         assert_eq!(results.len(), 1);
         assert!(results[0].result.is_err());
@@ -128,17 +364,44 @@ mod tests {
         let mut f = File::create(&file).unwrap();
         writeln!(f, "<h1>Title</h1><a href=\"/\">Link</a>").unwrap();
 
-        let files = vec![file];
+        let files = vec![Input::File(file)];
         let selectors = vec![("title".into(), "h1".into()), ("link".into(), "a".into())];
-        let results = process_files_named(&files, &selectors, None, false, None);
+        let schema = named_schema(&selectors, None, &[]).unwrap();
+        let results =
+            process_files_named(&files, &schema, false, false, ParseCheck::default(), &fetcher());
 
         assert_eq!(results.len(), 1);
 
-        let extractions = results[0].result.as_ref().unwrap();
+        let extractions = &results[0].result.as_ref().unwrap().fields;
         assert_eq!(extractions["title"][0].text, "Title");
         assert_eq!(extractions["link"][0].text, "Link");
     }
 
+    #[test]
+    fn test_process_files_strict() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("test.html");
+        fs::write(&file, "<!DOCTYPE html><h1>Test</h1>\n</p>").unwrap();
+
+        let files = vec![Input::File(file)];
+        let results = process_files(
+            &files,
+            "h1",
+            None,
+            false,
+            MatchFormat::default(),
+            ParseCheck::default(),
+            &fetcher(),
+        );
+        assert!(results[0].result.is_ok());
+
+        let check = ParseCheck { strict: true, ..ParseCheck::default() };
+        let results =
+            process_files(&files, "h1", None, false, MatchFormat::default(), check, &fetcher());
+        let err = results[0].result.as_ref().unwrap_err().to_string();
+        assert!(err.ends_with("at line 2, column 1"), "{err}");
+    }
+
     #[test]
     fn test_process_files_with_threads() {
         let dir = TempDir::new().unwrap();
@@ -147,10 +410,73 @@ mod tests {
         let mut f = File::create(&file).unwrap();
         writeln!(f, "<h1>Test</h1>").unwrap();
 
-        let files = vec![file];
-        let results = process_files(&files, "h1", None, false, Some(2));
+        let files = vec![Input::File(file)];
+        runtime::configure(Some(2), None).unwrap();
+        let results = process_files(
+            &files,
+            "h1",
+            None,
+            false,
+            MatchFormat::default(),
+            ParseCheck::default(),
+            &fetcher(),
+        );
+        runtime::reset();
 
         assert_eq!(results.len(), 1);
         assert!(results[0].result.is_ok());
     }
+
+    #[test]
+    fn test_rejection_summary() {
+        let mut summary = RejectionSummary::default();
+        summary.record(None);
+        let mut buf = Vec::new();
+        summary.write(&mut buf).unwrap();
+        assert!(buf.is_empty());
+
+        summary.record(Some("missing required field `price`"));
+        summary.record(Some("missing required field `name`"));
+        summary.record(Some("missing required field `price`"));
+        summary.write(&mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "rejected 3 of 4 records:\n  1 missing required field `name`\n  2 missing required \
+             field `price`\n"
+        );
+    }
+
+    #[test]
+    fn test_input_from_path() {
+        assert_eq!(
+            Input::from_path("https://example.com/a".into()),
+            Input::Url("https://example.com/a".into())
+        );
+        assert_eq!(Input::from_path("page.html".into()), Input::File("page.html".into()));
+        assert_eq!(Input::from_path("http://example.com".into()).name(), "http://example.com");
+        assert!(!Input::File("a.html".into()).is_url());
+    }
+
+    #[test]
+    fn test_process_mixed_inputs() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("test.html");
+        fs::write(&file, "<h1>Local</h1>").unwrap();
+
+        // Nothing listens on port 1, so the URL fails without touching the network.
+        let inputs = vec![Input::File(file), Input::Url("http://127.0.0.1:1/".into())];
+        let results = process_files(
+            &inputs,
+            "h1",
+            None,
+            false,
+            MatchFormat::default(),
+            ParseCheck::default(),
+            &fetcher(),
+        );
+
+        assert_eq!(results[0].result.as_ref().unwrap()[0].text, "Local");
+        assert_eq!(results[1].filename, "http://127.0.0.1:1/");
+        assert!(results[1].result.is_err());
+    }
 }
@@ -0,0 +1,170 @@
+//! `scrape bench` subcommand.
+
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use scrape_core::bench_corpus::{self, Baseline, BenchOptions, BenchReport, Corpus, CorpusPage};
+
+use crate::{
+    args::BenchArgs,
+    fetch::{FetchConfig, fetch_url},
+};
+
+/// Benchmarks the input files or the pinned corpus and prints a throughput table.
+///
+/// Returns `false` if a metric regressed against `--baseline`.
+///
+/// # Errors
+///
+/// Returns an error if the corpus cannot be loaded, a selector is invalid, or a baseline
+/// cannot be read or written.
+pub fn run(args: &BenchArgs) -> Result<bool> {
+    let corpus = load_corpus(args)?;
+    let mut options = BenchOptions {
+        iterations: args.iterations,
+        warmup: args.warmup,
+        ..BenchOptions::default()
+    };
+    if !args.selectors.is_empty() {
+        options.selectors.clone_from(&args.selectors);
+    }
+
+    let report = bench_corpus::run(&corpus, &options).context("Invalid CSS selector")?;
+
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    write_report(&mut writer, &report)?;
+    writer.flush()?;
+
+    let current = report.to_baseline();
+    if let Some(path) = &args.save_baseline {
+        std::fs::write(path, current.to_string())
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    let Some(path) = &args.baseline else {
+        return Ok(true);
+    };
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let baseline = Baseline::parse(&content)
+        .with_context(|| format!("invalid baseline {}", path.display()))?;
+
+    let regressions = baseline.regressions(&current, args.max_regression / 100.0);
+    for regression in &regressions {
+        eprintln!("regression: {regression}");
+    }
+    eprintln!(
+        "{} of {} metrics regressed by more than {}% against {}",
+        regressions.len(),
+        baseline.len(),
+        args.max_regression,
+        path.display()
+    );
+
+    Ok(regressions.is_empty())
+}
+
+/// Loads the input files, or the pinned corpus from the cache directory.
+fn load_corpus(args: &BenchArgs) -> Result<Corpus> {
+    if !args.files.is_empty() {
+        let pages = args
+            .files
+            .iter()
+            .map(|path| {
+                let html = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                Ok(CorpusPage::new(path.display().to_string(), html))
+            })
+            .collect::<Result<_>>()?;
+        return Ok(Corpus::new(pages));
+    }
+
+    let dir = args.corpus_dir.clone().unwrap_or_else(default_corpus_dir);
+    let corpus = if args.offline {
+        Corpus::load(&dir)?
+    } else {
+        let config = FetchConfig::default();
+        Corpus::load_or_fetch(&dir, |url| {
+            eprintln!("fetching {url}");
+            fetch_url(url, &config)
+        })?
+    };
+    Ok(corpus)
+}
+
+/// Returns `scrape/bench-corpus` under `$XDG_CACHE_HOME` or `~/.cache`.
+fn default_corpus_dir() -> PathBuf {
+    let cache = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(std::env::temp_dir);
+    cache.join("scrape").join("bench-corpus")
+}
+
+/// Writes one row per page and one indented row per selector.
+fn write_report<W: Write>(writer: &mut W, report: &BenchReport) -> io::Result<()> {
+    let width = report
+        .pages
+        .iter()
+        .map(|page| page.name.len())
+        .chain(report.pages.iter().flat_map(|p| p.queries.iter().map(|q| q.selector.len() + 2)))
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    writeln!(writer, "{:<width$}  {:>10}  {:>10}  {:>14}", "page", "size", "time", "throughput")?;
+    for page in &report.pages {
+        writeln!(
+            writer,
+            "{:<width$}  {:>10}  {:>10}  {:>9.1} MB/s",
+            page.name,
+            format_bytes(page.bytes),
+            format_duration(page.parse),
+            page.parse_throughput()
+        )?;
+        for query in &page.queries {
+            writeln!(
+                writer,
+                "{:<width$}  {:>10}  {:>10}  {:>11.0} /s",
+                format!("  {}", query.selector),
+                format!("{} hits", query.matches),
+                format_duration(query.time),
+                query.per_second()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..1_000 => format!("{bytes} B"),
+        1_000..1_000_000 => format!("{:.1} KB", bytes as f64 / 1e3),
+        _ => format!("{:.1} MB", bytes as f64 / 1e6),
+    }
+}
+
+fn format_duration(time: Duration) -> String {
+    let micros = time.as_secs_f64() * 1e6;
+    if micros < 1_000.0 { format!("{micros:.1} µs") } else { format!("{:.2} ms", micros / 1e3) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(2_500), "2.5 KB");
+        assert_eq!(format_bytes(3_200_000), "3.2 MB");
+        assert_eq!(format_duration(Duration::from_nanos(1_500)), "1.5 µs");
+        assert_eq!(format_duration(Duration::from_micros(2_340)), "2.34 ms");
+    }
+}
@@ -0,0 +1,136 @@
+//! `scrape contacts` subcommand.
+
+use std::io::{self, Read, Write};
+
+use anyhow::{Context, Result};
+use scrape_core::{
+    Soup,
+    extract::{Contacts, contacts},
+};
+use serde_json::json;
+
+use crate::args::{ContactsArgs, OutputFormat};
+
+/// Harvests contacts from every input and prints the merged result.
+///
+/// Returns `true` if anything was found.
+///
+/// # Errors
+///
+/// Returns an error if an input cannot be read or output cannot be written.
+pub fn run(args: &ContactsArgs) -> Result<bool> {
+    let mut found = Contacts::default();
+
+    #[cfg(feature = "url")]
+    if let Some(url) = &args.url {
+        let html = crate::fetch::fetch_url(url, &crate::fetch::FetchConfig::default())?;
+        found.merge(contacts(&Soup::parse(&html)));
+    }
+
+    for path in &args.files {
+        let html = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        found.merge(contacts(&Soup::parse(&html)));
+    }
+
+    if args.files.is_empty() && !has_url(args) {
+        let mut html = String::new();
+        io::stdin().read_to_string(&mut html)?;
+        found.merge(contacts(&Soup::parse(&html)));
+    }
+
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    write_contacts(&mut writer, &found, args.output, args.pretty)?;
+    writer.flush()?;
+
+    Ok(!found.is_empty())
+}
+
+#[cfg(feature = "url")]
+fn has_url(args: &ContactsArgs) -> bool {
+    args.url.is_some()
+}
+
+#[cfg(not(feature = "url"))]
+fn has_url(_args: &ContactsArgs) -> bool {
+    false
+}
+
+/// Writes contacts as `kind<TAB>value` lines, a JSON object, or one
+/// `{"kind": ..., "value": ...}` JSON object per line.
+fn write_contacts(
+    writer: &mut dyn Write,
+    found: &Contacts,
+    format: OutputFormat,
+    pretty: bool,
+) -> io::Result<()> {
+    if format == OutputFormat::Json {
+        let value = json!({
+            "emails": found.emails,
+            "phones": found.phones,
+            "urls": found.urls,
+        });
+        let output = if pretty {
+            serde_json::to_string_pretty(&value)
+        } else {
+            serde_json::to_string(&value)
+        }
+        .map_err(io::Error::other)?;
+        return writeln!(writer, "{output}");
+    }
+
+    let groups = [("email", &found.emails), ("phone", &found.phones), ("url", &found.urls)];
+    for (kind, values) in groups {
+        for value in values {
+            if format == OutputFormat::Ndjson {
+                writeln!(writer, "{}", json!({ "kind": kind, "value": value }))?;
+            } else {
+                writeln!(writer, "{kind}\t{value}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Contacts {
+        Contacts {
+            emails: vec!["a@example.com".into()],
+            phones: vec!["+15550100".into()],
+            urls: vec!["https://example.com".into()],
+        }
+    }
+
+    #[test]
+    fn test_write_contacts_text() {
+        let mut buf = Vec::new();
+        write_contacts(&mut buf, &sample(), OutputFormat::Text, false).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "email\ta@example.com\nphone\t+15550100\nurl\thttps://example.com\n"
+        );
+    }
+
+    #[test]
+    fn test_write_contacts_json() {
+        let mut buf = Vec::new();
+        write_contacts(&mut buf, &sample(), OutputFormat::Json, false).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "{\"emails\":[\"a@example.com\"],\"phones\":[\"+15550100\"],\"urls\":[\"https://example.com\"]}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_contacts_ndjson() {
+        let mut buf = Vec::new();
+        write_contacts(&mut buf, &sample(), OutputFormat::Ndjson, false).unwrap();
+        let lines: Vec<_> = String::from_utf8(buf).unwrap().lines().map(str::to_owned).collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "{\"kind\":\"email\",\"value\":\"a@example.com\"}");
+    }
+}
@@ -0,0 +1,453 @@
+//! `scrape crawl` subcommand: a minimal breadth-first crawler.
+//!
+//! Starting from one URL, pages are fetched one at a time, extracted with the --select
+//! or --schema fields, and printed as NDJSON while links on the same host are queued.
+//! The site's `robots.txt` rules and `Crawl-delay` are obeyed, as are `noindex` and
+//! `nofollow` robots meta tags and `rel="nofollow"` links.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    io::{self, Write},
+    num::NonZeroUsize,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use scrape_core::{
+    CompiledSelector, NodeId, Soup,
+    extract::{Schema, Value, links::links},
+    robots::{RobotsTxt, robots_directives},
+    sitemap::Sitemap,
+};
+use serde::Serialize;
+
+use crate::{
+    args::CrawlArgs,
+    extract::named_schema,
+    fetch::{FetchConfig, Fetcher, host_key, is_url},
+    schema,
+};
+
+/// Product token matched against `robots.txt` groups and robots meta tags.
+const ROBOTS_AGENT: &str = "scrape-cli";
+
+/// Which links a crawl follows.
+enum Follow {
+    /// Links matching, or inside elements matching, a CSS selector.
+    Selector(CompiledSelector),
+    /// Links whose URL matches a pattern where `*` matches anything; patterns starting
+    /// with `/` match the path and query.
+    Pattern(String),
+}
+
+impl Follow {
+    fn parse(value: &str) -> Result<Self> {
+        if value.starts_with('/') || is_url(value) {
+            return Ok(Self::Pattern(value.to_string()));
+        }
+        let selector = CompiledSelector::compile(value)
+            .with_context(|| format!("Invalid --follow selector '{value}'"))?;
+        Ok(Self::Selector(selector))
+    }
+}
+
+/// Crawls from the start URL and prints one NDJSON record per page.
+///
+/// Returns `true` if any page produced a record.
+///
+/// # Errors
+///
+/// Returns an error if the schema or a --follow selector is invalid, or output cannot be
+/// written.
+pub fn run(args: &CrawlArgs) -> Result<bool> {
+    let schema = if let Some(path) = &args.schema {
+        schema::load(path)?
+    } else {
+        let selects: Vec<_> = args
+            .selects
+            .iter()
+            .filter_map(|s| s.split_once('='))
+            .map(|(name, selector)| (name.to_string(), selector.to_string()))
+            .collect();
+        named_schema(&selects, args.attribute.as_deref(), &[])?
+    };
+    let follow = args.follow.iter().map(|value| Follow::parse(value)).collect::<Result<_>>()?;
+
+    let config = FetchConfig {
+        timeout: Duration::from_secs(args.timeout),
+        retries: 2,
+        ..FetchConfig::default()
+    };
+    let start = strip_fragment(&args.start).to_string();
+    let robots = if args.ignore_robots {
+        RobotsTxt::default()
+    } else {
+        // A missing or unreachable robots.txt allows everything.
+        Fetcher::new(config.clone(), NonZeroUsize::MIN, None)
+            .fetch(&format!("{}/robots.txt", origin(&start)))
+            .map(|text| RobotsTxt::parse(&text))
+            .unwrap_or_default()
+    };
+    let delay_rate = robots.crawl_delay(ROBOTS_AGENT).map(|delay| 1.0 / delay.as_secs_f64());
+    let rate = args.rate_limit.into_iter().chain(delay_rate).min_by(f64::total_cmp);
+    let fetcher = Fetcher::new(config, NonZeroUsize::MIN, rate);
+
+    let crawl = Crawl {
+        schema: &schema,
+        follow,
+        robots,
+        max_depth: args.max_depth,
+        max_pages: args.max_pages,
+        quiet: args.quiet,
+    };
+    let fetch = |url: &str| fetcher.fetch(url);
+    let mut seeds = if args.sitemap {
+        crawl.sitemap_urls(&start, |url| fetcher.fetch_asset(url).map(|asset| asset.data))
+    } else {
+        Vec::new()
+    };
+    seeds.insert(0, start);
+
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    let found = crawl.run(seeds, fetch, &mut writer)?;
+    writer.flush()?;
+    Ok(found)
+}
+
+/// The settings of one crawl.
+struct Crawl<'a> {
+    schema: &'a Schema,
+    follow: Vec<Follow>,
+    robots: RobotsTxt,
+    max_depth: usize,
+    max_pages: usize,
+    quiet: bool,
+}
+
+#[derive(Serialize)]
+struct PageRecord<'a> {
+    url: &'a str,
+    depth: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<&'a Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rejected: Option<&'a str>,
+}
+
+impl Crawl<'_> {
+    /// Visits the seeds and the links found on them breadth-first, writing a record for
+    /// every page. Seeds on other hosts than the first are skipped.
+    fn run<E: fmt::Display>(
+        &self,
+        seeds: Vec<String>,
+        fetch: impl Fn(&str) -> Result<String, E>,
+        writer: &mut dyn Write,
+    ) -> io::Result<bool> {
+        let host = seeds.first().map(|seed| host_key(seed)).unwrap_or_default();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        for seed in seeds {
+            if host_key(&seed) == host && seen.insert(seed.clone()) {
+                queue.push_back((seed, 0));
+            }
+        }
+        let has_fields = self.schema.field_names().next().is_some();
+
+        let mut pages = 0;
+        let mut found = false;
+        while let Some((url, depth)) = queue.pop_front() {
+            if pages == self.max_pages {
+                break;
+            }
+            if !self.robots.is_url_allowed(ROBOTS_AGENT, &url) {
+                self.warn(&url, "disallowed by robots.txt");
+                continue;
+            }
+            pages += 1;
+            let html = match fetch(&url) {
+                Ok(html) => html,
+                Err(e) => {
+                    self.warn(&url, e);
+                    continue;
+                }
+            };
+
+            let soup = Soup::parse(&html);
+            let directives = robots_directives(&soup, Some(ROBOTS_AGENT));
+            if directives.can_index() {
+                let (data, rejections) = self.schema.extract_with_rejections(&soup);
+                let rejected = rejections
+                    .iter()
+                    .find(|rejection| rejection.field.is_empty())
+                    .map(|rejection| rejection.reason.as_str());
+                found |= rejected.is_none();
+                let data = (has_fields && rejected.is_none()).then_some(&data);
+                let record = PageRecord { url: &url, depth, data, rejected };
+                serde_json::to_writer(&mut *writer, &record).map_err(io::Error::other)?;
+                writeln!(writer)?;
+            }
+            if depth < self.max_depth && directives.can_follow() {
+                for link in self.links(&soup, &url) {
+                    if host_key(&link) == host && seen.insert(link.clone()) {
+                        queue.push_back((link, depth + 1));
+                    }
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Returns the absolute URLs of the links on a page that --follow selects, without
+    /// fragments. Without --follow, every link is followed.
+    fn links(&self, soup: &Soup, page_url: &str) -> Vec<String> {
+        let selected: Option<HashSet<NodeId>> =
+            self.follow.iter().any(|follow| matches!(follow, Follow::Selector(_))).then(|| {
+                self.follow
+                    .iter()
+                    .filter_map(|follow| match follow {
+                        Follow::Selector(selector) => Some(selector),
+                        Follow::Pattern(_) => None,
+                    })
+                    .flat_map(|selector| soup.select_compiled(selector))
+                    .flat_map(|tag| {
+                        let inner = tag.select("a[href], area[href]").unwrap_or_default();
+                        std::iter::once(tag).chain(inner).map(|tag| tag.node_id())
+                    })
+                    .collect()
+            });
+        let patterns: Vec<_> = self
+            .follow
+            .iter()
+            .filter_map(|follow| match follow {
+                Follow::Pattern(pattern) => Some(pattern.as_str()),
+                Follow::Selector(_) => None,
+            })
+            .collect();
+
+        links(soup, Some(page_url))
+            .into_iter()
+            .filter(|link| selected.as_ref().is_none_or(|ids| ids.contains(&link.node)))
+            .filter(|link| !link.rel.iter().any(|rel| rel == "nofollow"))
+            .filter_map(|link| link.url)
+            .map(|url| strip_fragment(&url).to_string())
+            .filter(|url| is_url(url))
+            .filter(|url| {
+                patterns.is_empty()
+                    || patterns.iter().any(|pattern| {
+                        let target = if pattern.starts_with('/') {
+                            path_and_query(url)
+                        } else {
+                            url.clone()
+                        };
+                        glob_match(pattern, &target)
+                    })
+            })
+            .collect()
+    }
+
+    /// Collects the page URLs listed in the sitemaps named by robots.txt, or in
+    /// `/sitemap.xml` if it names none. Sitemap indexes are followed one level deep, and
+    /// gzipped sitemaps are decompressed.
+    fn sitemap_urls<E: fmt::Display>(
+        &self,
+        start: &str,
+        fetch: impl Fn(&str) -> Result<Vec<u8>, E>,
+    ) -> Vec<String> {
+        let mut sitemaps = self.robots.sitemaps().to_vec();
+        if sitemaps.is_empty() {
+            sitemaps.push(format!("{}/sitemap.xml", origin(start)));
+        }
+        let mut urls = Vec::new();
+        for _ in 0..2 {
+            let mut nested = Vec::new();
+            for sitemap in sitemaps {
+                let parsed = match fetch(&sitemap) {
+                    Ok(data) => Sitemap::from_reader(data.as_slice()),
+                    Err(e) => {
+                        self.warn(&sitemap, e);
+                        continue;
+                    }
+                };
+                match parsed {
+                    Ok(parsed) => {
+                        nested.extend(locations(parsed.sitemaps.iter().map(|s| s.loc.as_str())));
+                        urls.extend(locations(parsed.urls.iter().map(|url| url.loc.as_str())));
+                    }
+                    Err(e) => self.warn(&sitemap, e),
+                }
+            }
+            sitemaps = nested;
+        }
+        urls
+    }
+
+    fn warn(&self, url: &str, message: impl fmt::Display) {
+        if !self.quiet {
+            eprintln!("{url}: {message}");
+        }
+    }
+}
+
+/// Returns the http(s) URLs among sitemap `<loc>` values, without fragments.
+fn locations<'a>(locs: impl Iterator<Item = &'a str>) -> impl Iterator<Item = String> {
+    locs.map(strip_fragment).filter(|url| is_url(url)).map(ToString::to_string)
+}
+
+/// Returns the `scheme://host[:port]` part of a URL.
+fn origin(url: &str) -> &str {
+    let start = url.find("://").map_or(0, |i| i + 3);
+    let end = url[start..].find(['/', '?', '#']).map_or(url.len(), |i| start + i);
+    &url[..end]
+}
+
+/// Returns the path and query of a URL, which is `/` for a bare origin.
+fn path_and_query(url: &str) -> String {
+    let rest = &url[origin(url).len()..];
+    if rest.starts_with('/') { rest.to_string() } else { format!("/{rest}") }
+}
+
+fn strip_fragment(url: &str) -> &str {
+    url.split_once('#').map_or(url, |(url, _)| url)
+}
+
+/// Returns `true` if `text` matches `pattern` in full, where `*` matches any run of
+/// characters.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = text.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<_> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        let Some(i) = rest.find(part) else { return false };
+        rest = &rest[i + part.len()..];
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const SITE: &[(&str, &str)] = &[
+        (
+            "https://example.com/",
+            "<h1>Home</h1><nav><a href=\"/blog/\">Blog</a></nav>\
+             <a href=\"/private/keys\">Keys</a><a href=\"https://other.com/\">Other</a>",
+        ),
+        (
+            "https://example.com/blog/",
+            "<h1>Blog</h1><a href=\"post-1#top\">One</a><a href=\"post-2\" rel=\"nofollow\">Two</a>\
+             <a href=\"/tag/tea\">Tea</a>",
+        ),
+        (
+            "https://example.com/blog/post-1",
+            "<meta name=\"robots\" content=\"nofollow\"><h1>One</h1><a href=\"/deep\">Deep</a>",
+        ),
+        ("https://example.com/tag/tea", "<meta name=\"robots\" content=\"noindex\"><p>Tea</p>"),
+        (
+            "https://example.com/sitemap.xml",
+            "<?xml version=\"1.0\"?><urlset><url><loc> https://example.com/about </loc></url>\
+             </urlset>",
+        ),
+        ("https://example.com/about", "<h1>About</h1>"),
+    ];
+
+    fn fetch(url: &str) -> Result<String, String> {
+        let site: HashMap<_, _> = SITE.iter().copied().collect();
+        site.get(url).map(ToString::to_string).ok_or_else(|| "HTTP error: HTTP 404".into())
+    }
+
+    fn crawl<'a>(schema: &'a Schema, follow: &[&str], max_depth: usize) -> Crawl<'a> {
+        Crawl {
+            schema,
+            follow: follow.iter().map(|value| Follow::parse(value).unwrap()).collect(),
+            robots: RobotsTxt::parse("User-agent: *\nDisallow: /private/"),
+            max_depth,
+            max_pages: 100,
+            quiet: true,
+        }
+    }
+
+    fn output(crawl: &Crawl<'_>, seeds: &[&str]) -> String {
+        let mut buf = Vec::new();
+        crawl.run(seeds.iter().map(ToString::to_string).collect(), fetch, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    fn titles() -> Schema {
+        named_schema(&[("title".into(), "h1".into())], None, &[]).unwrap()
+    }
+
+    #[test]
+    fn test_crawl_follows_links_breadth_first() {
+        let schema = titles();
+        let printed = output(&crawl(&schema, &[], 2), &["https://example.com/"]);
+        assert_eq!(
+            printed.lines().collect::<Vec<_>>(),
+            [
+                r#"{"url":"https://example.com/","depth":0,"data":{"title":["Home"]}}"#,
+                r#"{"url":"https://example.com/blog/","depth":1,"data":{"title":["Blog"]}}"#,
+                r#"{"url":"https://example.com/blog/post-1","depth":2,"data":{"title":["One"]}}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_crawl_limits() {
+        let schema = Schema::new();
+        let mut limited = crawl(&schema, &[], 5);
+        limited.max_pages = 2;
+        assert_eq!(
+            output(&limited, &["https://example.com/"]),
+            "{\"url\":\"https://example.com/\",\"depth\":0}\n\
+             {\"url\":\"https://example.com/blog/\",\"depth\":1}\n"
+        );
+        assert_eq!(
+            output(&crawl(&schema, &[], 0), &["https://example.com/"]),
+            "{\"url\":\"https://example.com/\",\"depth\":0}\n"
+        );
+    }
+
+    #[test]
+    fn test_crawl_follow() {
+        let schema = Schema::new();
+        let only_nav = output(&crawl(&schema, &["nav"], 1), &["https://example.com/"]);
+        assert_eq!(only_nav.lines().count(), 2);
+
+        let pattern = output(&crawl(&schema, &["/blog/*", "/tag/*"], 2), &["https://example.com/"]);
+        assert!(pattern.contains("/blog/post-1"));
+        assert!(!pattern.contains("/tag/tea"), "noindex pages are not printed");
+
+        let absolute = crawl(&schema, &["https://example.com/blog/"], 2);
+        assert_eq!(output(&absolute, &["https://example.com/"]).lines().count(), 2);
+        assert!(Follow::parse("div[").is_err());
+    }
+
+    #[test]
+    fn test_sitemap_urls() {
+        let schema = Schema::new();
+        let crawl = crawl(&schema, &[], 0);
+        let fetch_bytes = |url: &str| fetch(url).map(String::into_bytes);
+        assert_eq!(
+            crawl.sitemap_urls("https://example.com/blog/", fetch_bytes),
+            ["https://example.com/about"]
+        );
+    }
+
+    #[test]
+    fn test_url_helpers() {
+        assert_eq!(origin("https://example.com:8080/a?b"), "https://example.com:8080");
+        assert!(glob_match("https://*.example.com/*", "https://www.example.com/a"));
+        assert!(glob_match("/blog/*", "/blog/"));
+        assert!(!glob_match("/blog/*", "/blogs/"));
+        assert!(!glob_match("/a", "/ab"));
+    }
+}
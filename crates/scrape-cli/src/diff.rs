@@ -0,0 +1,160 @@
+//! `scrape diff` subcommand.
+
+use std::io::{self, Write};
+
+use anyhow::{Context, Result};
+use scrape_core::{
+    NodeId, Soup,
+    diff::{DomChange, diff, diff_tags},
+    serialize_node,
+};
+use serde_json::{Value, json};
+
+use crate::{
+    args::{DiffArgs, OutputFormat},
+    fetch::{FetchConfig, fetch_url, is_url},
+};
+
+/// Prints the structural changes between two pages.
+///
+/// Returns `true` if the pages, or the elements matching `--selector`, are equal.
+///
+/// # Errors
+///
+/// Returns an error if an input cannot be read, the selector is invalid, or output
+/// cannot be written.
+pub fn run(args: &DiffArgs) -> Result<bool> {
+    let old = Soup::parse(&read_input(&args.old)?);
+    let new = Soup::parse(&read_input(&args.new)?);
+    let changes = match &args.selector {
+        Some(selector) => {
+            let old_tags = old.select(selector).context("Invalid CSS selector")?;
+            diff_tags(&old_tags, &new.select(selector)?)
+        }
+        None => diff(&old, &new),
+    };
+
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    write_changes(&mut writer, &changes, &old, &new, args.output, args.pretty)?;
+    writer.flush()?;
+
+    Ok(changes.is_empty())
+}
+
+/// Reads HTML from a URL or a file.
+fn read_input(input: &str) -> Result<String> {
+    if is_url(input) {
+        Ok(fetch_url(input, &FetchConfig::default())?)
+    } else {
+        std::fs::read_to_string(input).with_context(|| format!("failed to read {input}"))
+    }
+}
+
+/// Writes changes as `+`/`-`/`~` lines, a JSON array, or one JSON object per line.
+fn write_changes(
+    writer: &mut impl Write,
+    changes: &[DomChange],
+    old: &Soup,
+    new: &Soup,
+    format: OutputFormat,
+    pretty: bool,
+) -> Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let records: Vec<_> = changes.iter().map(|change| record(change, old, new)).collect();
+            if pretty {
+                serde_json::to_writer_pretty(&mut *writer, &records)?;
+            } else {
+                serde_json::to_writer(&mut *writer, &records)?;
+            }
+            writeln!(writer)?;
+        }
+        OutputFormat::Ndjson => {
+            for change in changes {
+                serde_json::to_writer(&mut *writer, &record(change, old, new))?;
+                writeln!(writer)?;
+            }
+        }
+        _ => {
+            for change in changes {
+                match change {
+                    DomChange::Added { path, node } => {
+                        writeln!(writer, "+ {path}: {}", outer_html(new, *node))?;
+                    }
+                    DomChange::Removed { path, node } => {
+                        writeln!(writer, "- {path}: {}", outer_html(old, *node))?;
+                    }
+                    DomChange::AttributeChanged { path, name, old, new, .. } => {
+                        writeln!(
+                            writer,
+                            "~ {path} [{name}]: {} -> {}",
+                            quoted(old.as_ref()),
+                            quoted(new.as_ref())
+                        )?;
+                    }
+                    DomChange::TextChanged { path, old, new, .. } => {
+                        writeln!(writer, "~ {path}: {old:?} -> {new:?}")?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn record(change: &DomChange, old: &Soup, new: &Soup) -> Value {
+    match change {
+        DomChange::Added { path, node } => {
+            json!({"change": "added", "path": path, "html": outer_html(new, *node)})
+        }
+        DomChange::Removed { path, node } => {
+            json!({"change": "removed", "path": path, "html": outer_html(old, *node)})
+        }
+        DomChange::AttributeChanged { path, name, old, new, .. } => {
+            json!({"change": "attribute", "path": path, "name": name, "old": old, "new": new})
+        }
+        DomChange::TextChanged { path, old, new, .. } => {
+            json!({"change": "text", "path": path, "old": old, "new": new})
+        }
+    }
+}
+
+fn outer_html(soup: &Soup, node: NodeId) -> String {
+    let mut html = String::new();
+    serialize_node(soup.document(), node, &mut html);
+    html
+}
+
+fn quoted(value: Option<&String>) -> String {
+    value.map_or_else(|| "(none)".to_string(), |value| format!("{value:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(format: OutputFormat) -> String {
+        let old = Soup::parse("<p title=\"a\">Tea</p><hr>");
+        let new = Soup::parse("<p>Coffee</p><br>");
+        let changes = diff(&old, &new);
+        let mut buf = Vec::new();
+        write_changes(&mut buf, &changes, &old, &new, format, false).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_write_changes() {
+        assert_eq!(
+            output(OutputFormat::Text),
+            "~ html > body > p [title]: \"a\" -> (none)\n~ html > body > p: \"Tea\" -> \
+             \"Coffee\"\n- html > body > hr: <hr>\n+ html > body > br: <br>\n"
+        );
+        let ndjson = output(OutputFormat::Ndjson);
+        assert_eq!(ndjson.lines().count(), 4);
+        assert!(ndjson.starts_with(
+            "{\"change\":\"attribute\",\"name\":\"title\",\"new\":null,\"old\":\"a\",\"path\""
+        ));
+        assert!(output(OutputFormat::Json).contains("{\"change\":\"added\",\"html\":\"<br>\""));
+    }
+}
@@ -2,8 +2,11 @@
 
 use std::collections::HashMap;
 
-use anyhow::{Context, Result};
-use scrape_core::Soup;
+use anyhow::{Context, Result, bail};
+use scrape_core::{
+    Attributes, SerializeOptions, Soup,
+    extract::{Field, Provenance, Schema, Table, Value},
+};
 use serde::Serialize;
 
 /// Result of extracting data from HTML.
@@ -13,26 +16,101 @@ pub struct Extraction {
     pub text: String,
     /// All attributes of the matched element.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub attrs: Option<HashMap<String, String>>,
+    pub attrs: Option<Attributes>,
     /// The outer HTML of the matched element.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub html: Option<String>,
+    /// The inner HTML of the matched element.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inner_html: Option<String>,
+    /// Where the value came from, with `--provenance`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+/// How inputs are parsed, and how their parse errors are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseCheck {
+    /// Fail the input on its first parse error.
+    pub strict: bool,
+    /// Print every parse error to stderr.
+    pub warnings: bool,
+    /// Parse the input as XML instead of HTML.
+    pub xml: bool,
+}
+
+impl ParseCheck {
+    /// Parses `html` from the input called `name`, checking it for parse errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first parse error in strict mode, or an error if the document
+    /// cannot be parsed at all, such as malformed XML.
+    pub fn parse(self, name: &str, html: &str) -> Result<Soup> {
+        if self.xml {
+            return Ok(Soup::parse_xml(html)?);
+        }
+        if !self.strict && !self.warnings {
+            return Ok(Soup::parse(html));
+        }
+        self.parse_checked(name, html)
+    }
+
+    /// Checks `html` from the input called `name` for parse errors.
+    ///
+    /// Does nothing unless `strict` or `warnings` is set, so unchecked inputs are
+    /// parsed only once.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first parse error in strict mode, or an error if the document
+    /// cannot be parsed at all.
+    pub fn run(self, name: &str, html: &str) -> Result<()> {
+        if self.strict || self.warnings {
+            self.parse_checked(name, html)?;
+        }
+        Ok(())
+    }
+
+    fn parse_checked(self, name: &str, html: &str) -> Result<Soup> {
+        let (soup, warnings) = Soup::parse_checked(html)?;
+        if self.warnings {
+            for warning in &warnings {
+                eprintln!("{name}: warning: {warning}");
+            }
+        }
+        if self.strict
+            && let Some(first) = warnings.first()
+        {
+            bail!("parse error: {first}");
+        }
+        Ok(soup)
+    }
+}
+
+/// What [`extract`] keeps from each match besides its text or attribute.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MatchFormat<'a> {
+    /// Keep the attributes and the outer HTML, serialized with these options.
+    pub html: Option<&'a SerializeOptions>,
+    /// Also keep the inner HTML, serialized with the same options as `html`.
+    pub inner_html: bool,
+    /// Render the text of each match from its content as Markdown.
+    pub markdown: bool,
 }
 
-/// Extract data from HTML using a CSS selector.
+/// Extract data from a parsed document using a CSS selector.
 ///
 /// # Errors
 ///
 /// Returns an error if the selector is invalid.
 pub fn extract(
-    html: &str,
+    soup: &Soup,
     selector: &str,
     attribute: Option<&str>,
     first_only: bool,
-    include_html: bool,
+    format: MatchFormat<'_>,
 ) -> Result<Vec<Extraction>> {
-    let soup = Soup::parse(html);
-
     let tags = if first_only {
         soup.find(selector).context("Invalid CSS selector")?.into_iter().collect::<Vec<_>>()
     } else {
@@ -42,58 +120,120 @@ pub fn extract(
     let mut results = Vec::with_capacity(tags.len());
 
     for tag in tags {
-        let text = attribute
-            .map_or_else(|| tag.text(), |attr| tag.get(attr).unwrap_or_default().to_string());
+        let text = match attribute {
+            Some(attr) => tag.get(attr).unwrap_or_default().to_string(),
+            None if format.markdown => tag.to_markdown(),
+            None => tag.text(),
+        };
 
-        let attrs = if include_html { tag.attrs().cloned() } else { None };
+        let attrs = format.html.and_then(|_| tag.attrs().cloned());
 
-        let html = if include_html { Some(tag.outer_html()) } else { None };
+        // Pretty-printed elements end in a newline; the output adds its own delimiter.
+        let trim = |mut html: String| {
+            html.truncate(html.trim_end_matches('\n').len());
+            html
+        };
+        let html = format.html.map(|options| trim(tag.outer_html_with(options)));
+        let inner_html = format
+            .html
+            .filter(|_| format.inner_html)
+            .map(|options| trim(tag.inner_html_with(options)));
 
-        results.push(Extraction { text, attrs, html });
+        results.push(Extraction { text, attrs, html, inner_html, provenance: None });
     }
 
     Ok(results)
 }
 
-/// Extract multiple named selectors from HTML.
+/// Extract tables matching a CSS selector from HTML.
+///
+/// Matched elements that are not `<table>` elements are skipped.
 ///
 /// # Errors
 ///
-/// Returns an error if any selector is invalid.
-pub fn extract_named(
-    html: &str,
-    selectors: &[(String, String)],
-    attribute: Option<&str>,
-    first_only: bool,
-) -> Result<HashMap<String, Vec<Extraction>>> {
+/// Returns an error if the selector is invalid.
+pub fn extract_tables(html: &str, selector: &str, first_only: bool) -> Result<Vec<Table>> {
     let soup = Soup::parse(html);
-    let mut results = HashMap::new();
+    let tags = soup.find_all(selector).context("Invalid CSS selector")?;
+    let tables = tags.iter().filter_map(scrape_core::Tag::as_table);
 
-    for (name, selector) in selectors {
-        let tags = if first_only {
-            soup.find(selector)
-                .context(format!("Invalid CSS selector for '{name}'"))?
-                .into_iter()
-                .collect::<Vec<_>>()
-        } else {
-            soup.find_all(selector).context(format!("Invalid CSS selector for '{name}'"))?
-        };
+    Ok(if first_only { tables.take(1).collect() } else { tables.collect() })
+}
 
-        let extractions: Vec<Extraction> = tags
-            .into_iter()
-            .map(|tag| {
-                let text = attribute.map_or_else(
-                    || tag.text(),
-                    |attr| tag.get(attr).unwrap_or_default().to_string(),
-                );
-                Extraction { text, attrs: None, html: None }
-            })
-            .collect();
+/// Named extractions from one input.
+#[derive(Debug, Clone, Default)]
+pub struct NamedExtraction {
+    /// Extractions per selector name; all empty if the record was rejected.
+    pub fields: HashMap<String, Vec<Extraction>>,
+    /// Why a `--require` rule rejected the input's record.
+    pub rejection: Option<String>,
+}
 
-        results.insert(name.clone(), extractions);
+/// Builds an extraction [`Schema`] with one repeated field per named selector.
+///
+/// Each name in `required` rejects records in which its field matches nothing.
+///
+/// # Errors
+///
+/// Returns an error if any selector is invalid.
+pub fn named_schema(
+    selectors: &[(String, String)],
+    attribute: Option<&str>,
+    required: &[String],
+) -> Result<Schema> {
+    let mut schema = Schema::new();
+    for (name, selector) in selectors {
+        let field = attribute
+            .map_or_else(|| Field::text(selector), |attr| Field::attr(selector, attr))
+            .with_context(|| format!("Invalid CSS selector for '{name}'"))?;
+        schema = schema.field(name.as_str(), field.many());
     }
+    Ok(required.iter().fold(schema, |schema, name| schema.require(name.as_str())))
+}
 
-    Ok(results)
+/// Extract multiple named selectors from a parsed document with a schema from
+/// [`named_schema`].
+///
+/// With `provenance`, each extraction records the selector and CSS path of its element.
+#[must_use]
+pub fn extract_named(
+    soup: &Soup,
+    schema: &Schema,
+    first_only: bool,
+    provenance: bool,
+) -> NamedExtraction {
+    let (data, records, rejections) = if provenance {
+        let report = schema.extract_report(soup);
+        let records = report.provenance.into_iter().map(|r| (r.field.clone(), r)).collect();
+        (report.value, records, report.rejections)
+    } else {
+        let (data, rejections) = schema.extract_with_rejections(soup);
+        (data, HashMap::new(), rejections)
+    };
+    let limit = if first_only { 1 } else { usize::MAX };
+    let fields = schema
+        .field_names()
+        .map(|name| {
+            let values = data.get(name).and_then(Value::as_list).unwrap_or_default();
+            let extractions = values
+                .iter()
+                .take(limit)
+                .enumerate()
+                .map(|(i, value)| Extraction {
+                    // A matched element without the requested attribute yields `Null`.
+                    text: value.as_str().unwrap_or_default().to_string(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: records.get(&format!("{name}[{i}]")).cloned(),
+                })
+                .collect();
+            (name.to_string(), extractions)
+        })
+        .collect();
+    let rejection = rejections.into_iter().next().map(|rejection| rejection.reason);
+
+    NamedExtraction { fields, rejection }
 }
 
 #[cfg(test)]
@@ -103,7 +243,8 @@ mod tests {
     #[test]
     fn test_extract_text() {
         let html = "<html><body><h1>Hello World</h1></body></html>";
-        let results = extract(html, "h1", None, false, false).unwrap();
+        let results =
+            extract(&Soup::parse(html), "h1", None, false, MatchFormat::default()).unwrap();
 
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].text, "Hello World");
@@ -112,7 +253,8 @@ mod tests {
     #[test]
     fn test_extract_attribute() {
         let html = "<a href=\"/page\">Link</a>";
-        let results = extract(html, "a", Some("href"), false, false).unwrap();
+        let results =
+            extract(&Soup::parse(html), "a", Some("href"), false, MatchFormat::default()).unwrap();
 
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].text, "/page");
@@ -121,7 +263,7 @@ mod tests {
     #[test]
     fn test_extract_first_only() {
         let html = "<p>First</p><p>Second</p><p>Third</p>";
-        let results = extract(html, "p", None, true, false).unwrap();
+        let results = extract(&Soup::parse(html), "p", None, true, MatchFormat::default()).unwrap();
 
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].text, "First");
@@ -130,37 +272,143 @@ mod tests {
     #[test]
     fn test_extract_with_html() {
         let html = "<div class=\"item\">Content</div>";
-        let results = extract(html, "div", None, false, true).unwrap();
+        let options = SerializeOptions::default();
+        let format = MatchFormat { html: Some(&options), ..MatchFormat::default() };
+        let results = extract(&Soup::parse(html), "div", None, false, format).unwrap();
 
         assert_eq!(results.len(), 1);
         assert!(results[0].html.is_some());
         assert!(results[0].attrs.is_some());
     }
 
+    #[test]
+    fn test_extract_pretty_html() {
+        let html = "<ul><li>One</li><li>Two</li></ul>";
+        let options = SerializeOptions { pretty: true, ..SerializeOptions::default() };
+        let format = MatchFormat { html: Some(&options), ..MatchFormat::default() };
+        let results = extract(&Soup::parse(html), "ul", None, false, format).unwrap();
+
+        assert_eq!(results[0].html.as_deref(), Some("<ul>\n  <li>One</li>\n  <li>Two</li>\n</ul>"));
+    }
+
+    #[test]
+    fn test_extract_markdown() {
+        let html = "<article><h2>Tea</h2><p>Brew <em>hot</em>.</p></article>";
+        let format = MatchFormat { markdown: true, ..MatchFormat::default() };
+        let results = extract(&Soup::parse(html), "article", None, false, format).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "## Tea\n\nBrew *hot*.");
+    }
+
     #[test]
     fn test_extract_no_matches() {
         let html = "<div>Content</div>";
-        let results = extract(html, "span", None, false, false).unwrap();
+        let results =
+            extract(&Soup::parse(html), "span", None, false, MatchFormat::default()).unwrap();
 
         assert!(results.is_empty());
     }
 
+    fn schema(selectors: &[(&str, &str)], attribute: Option<&str>) -> Schema {
+        let selectors: Vec<_> =
+            selectors.iter().map(|&(name, selector)| (name.into(), selector.into())).collect();
+        named_schema(&selectors, attribute, &[]).unwrap()
+    }
+
     #[test]
     fn test_extract_named() {
         let html = "<h1>Title</h1><a href=\"/\">Link</a>";
-        let selectors = vec![("title".into(), "h1".into()), ("link".into(), "a".into())];
-        let results = extract_named(html, &selectors, None, false).unwrap();
+        let schema = schema(&[("title", "h1"), ("link", "a")], None);
+        let results = extract_named(&Soup::parse(html), &schema, false, false).fields;
 
         assert_eq!(results.len(), 2);
         assert_eq!(results["title"][0].text, "Title");
         assert_eq!(results["link"][0].text, "Link");
     }
 
+    #[test]
+    fn test_extract_named_attribute_and_first() {
+        let html = "<a href=\"/1\">A</a><a>B</a><a href=\"/3\">C</a>";
+        let schema = schema(&[("links", "a")], Some("href"));
+
+        let results = extract_named(&Soup::parse(html), &schema, false, false).fields;
+        let hrefs: Vec<_> = results["links"].iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(hrefs, vec!["/1", "", "/3"]);
+
+        let results = extract_named(&Soup::parse(html), &schema, true, false).fields;
+        assert_eq!(results["links"].len(), 1);
+        assert!(named_schema(&[("bad".into(), "[[[".into())], None, &[]).is_err());
+    }
+
+    #[test]
+    fn test_extract_named_required() {
+        let selectors = vec![("title".into(), "h1".into()), ("price".into(), ".price".into())];
+        let schema = named_schema(&selectors, None, &["price".into()]).unwrap();
+
+        let results =
+            extract_named(&Soup::parse("<h1>Tea</h1><b class=price>4</b>"), &schema, false, false);
+        assert_eq!(results.rejection, None);
+        assert_eq!(results.fields["price"][0].text, "4");
+
+        let results = extract_named(&Soup::parse("<h1>Tea</h1>"), &schema, false, false);
+        assert_eq!(results.rejection.as_deref(), Some("missing required field `price`"));
+        assert!(results.fields["title"].is_empty());
+    }
+
+    #[test]
+    fn test_extract_named_provenance() {
+        let html = "<ul>\n<li>Green</li>\n<li>Black</li>\n</ul>";
+        let schema = schema(&[("teas", "li")], None);
+
+        let results = extract_named(&Soup::parse(html), &schema, false, true).fields;
+        let provenance = results["teas"][1].provenance.as_ref().unwrap();
+        assert_eq!(provenance.field, "teas[1]");
+        assert_eq!(provenance.selector.as_deref(), Some("li"));
+        assert_eq!(provenance.css_path, "html > body > ul > li:nth-of-type(2)");
+        assert_eq!(provenance.line, Some(3));
+
+        let results = extract_named(&Soup::parse(html), &schema, false, false).fields;
+        assert!(results["teas"][0].provenance.is_none());
+    }
+
     #[test]
     fn test_extract_invalid_selector() {
         let html = "<div>Content</div>";
-        let result = extract(html, "[[[", None, false, false);
+        let result = extract(&Soup::parse(html), "[[[", None, false, MatchFormat::default());
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_check_xml() {
+        let xml =
+            "<rss><channel><link>https://example.com/</link><pubDate>Mon</pubDate></channel></rss>";
+        let check = ParseCheck { xml: true, ..ParseCheck::default() };
+        let soup = check.parse("feed.xml", xml).unwrap();
+        let results = extract(&soup, "channel > link", None, false, MatchFormat::default());
+        assert_eq!(results.unwrap()[0].text, "https://example.com/");
+        let results = extract(&soup, "pubDate", None, false, MatchFormat::default());
+        assert_eq!(results.unwrap().len(), 1);
+        assert!(check.parse("bad.xml", "<a/><b/>").is_err());
+
+        // The HTML parser reads `<link>` as a void element.
+        let soup = ParseCheck::default().parse("feed.xml", xml).unwrap();
+        let results = extract(&soup, "channel > link", None, false, MatchFormat::default());
+        assert_eq!(results.unwrap()[0].text, "");
+    }
+
+    #[test]
+    fn test_extract_tables() {
+        let html = "<table id=\"a\"><tr><td>1</td></tr></table><div \
+                    class=\"t\"></div><table><tr><td>2</td></tr></table>";
+
+        let tables = extract_tables(html, "table, .t", false).unwrap();
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[1].rows, vec![vec!["2"]]);
+
+        let first = extract_tables(html, "table", true).unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].rows, vec![vec!["1"]]);
+    }
 }
@@ -1,6 +1,23 @@
 //! URL fetching module for CLI.
 
-use std::time::Duration;
+use std::{
+    num::NonZeroUsize,
+    sync::{Condvar, Mutex, PoisonError},
+    thread,
+    time::Duration,
+};
+
+pub use scrape_core::fetch::{FetchError, is_url};
+#[cfg(feature = "url")]
+use scrape_core::fetch::{Session, fetch};
+use scrape_core::{
+    archive::{AssetFetcher, FetchedAsset},
+    fetch::{FetchOptions, FetchResponse},
+    robots::RateLimiter,
+};
+
+#[cfg(feature = "render")]
+use crate::render::Renderer;
 
 /// Configuration for URL fetching.
 #[derive(Debug, Clone)]
@@ -11,6 +28,10 @@ pub struct FetchConfig {
     pub user_agent: String,
     /// Maximum response size in bytes.
     pub max_size: usize,
+    /// Number of times a request is retried after a transient failure.
+    pub retries: u32,
+    /// Delay before the first retry; doubled for each further retry.
+    pub retry_backoff: Duration,
 }
 
 impl Default for FetchConfig {
@@ -19,62 +40,42 @@ impl Default for FetchConfig {
             timeout: Duration::from_secs(30),
             user_agent: format!("scrape-cli/{}", env!("CARGO_PKG_VERSION")),
             max_size: 10 * 1024 * 1024, // 10MB
+            retries: 0,
+            retry_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl FetchConfig {
+    /// Returns the request options for a single attempt.
+    #[must_use]
+    pub fn options(&self) -> FetchOptions {
+        FetchOptions {
+            user_agent: self.user_agent.clone(),
+            timeout: self.timeout,
+            max_size: self.max_size,
+            ..FetchOptions::default()
         }
     }
 }
 
-/// Error type for fetch operations.
-#[derive(Debug, thiserror::Error)]
-pub enum FetchError {
-    /// HTTP error.
-    #[error("HTTP error: {0}")]
-    Http(String),
-    /// Timeout error.
-    #[error("timeout after {0:?}")]
-    Timeout(Duration),
-    /// Response too large.
-    #[error("response too large: {size} bytes (max: {max})")]
-    TooLarge {
-        /// Actual size.
-        size: usize,
-        /// Maximum allowed size.
-        max: usize,
-    },
-    /// Invalid URL.
-    #[error("invalid URL: {0}")]
-    InvalidUrl(String),
+/// Returns the lowercased `host[:port]` part of a URL, used to group requests per host.
+#[must_use]
+pub fn host_key(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    host.to_ascii_lowercase()
 }
 
-/// Fetches HTML content from a URL.
+/// Fetches HTML content from a URL, decoded with the charset of the response.
 ///
 /// # Errors
 ///
 /// Returns `FetchError` if the request fails.
 #[cfg(feature = "url")]
 pub fn fetch_url(url: &str, config: &FetchConfig) -> Result<String, FetchError> {
-    // Make GET request with User-Agent header
-    // Note: ureq 3.x uses default global timeout, custom timeout per-request not directly supported
-    let mut response =
-        ureq::get(url).header("User-Agent", &config.user_agent).call().map_err(|e| match e {
-            ureq::Error::StatusCode(code) => FetchError::Http(format!("HTTP {code}")),
-            ureq::Error::Timeout(_) => FetchError::Timeout(config.timeout),
-            ureq::Error::BadUri(msg) => FetchError::InvalidUrl(msg),
-            ureq::Error::Io(io_err) => FetchError::Http(format!("I/O error: {io_err}")),
-            other => FetchError::Http(format!("{other}")),
-        })?;
-
-    // Read response body to string
-    let body_str = response
-        .body_mut()
-        .read_to_string()
-        .map_err(|e| FetchError::Http(format!("Failed to read response: {e}")))?;
-
-    // Check size limit
-    if body_str.len() > config.max_size {
-        return Err(FetchError::TooLarge { size: body_str.len(), max: config.max_size });
-    }
-
-    Ok(body_str)
+    Ok(fetch(url, &config.options())?.text().into_owned())
 }
 
 #[cfg(not(feature = "url"))]
@@ -82,8 +83,173 @@ pub fn fetch_url(_url: &str, _config: &FetchConfig) -> Result<String, FetchError
     Err(FetchError::Http("URL support not compiled (use --features url)".to_string()))
 }
 
+/// Fetches many URLs with bounded concurrency, per-host rate limiting, and retries.
+///
+/// Shared by the worker threads of a batch run: at most `concurrency` requests are in
+/// flight at once, and requests to the same host are spaced at least `1 / rate_limit`
+/// seconds apart. Transient failures are retried as configured in [`FetchConfig`].
+/// Requests share one session, so connections are reused and cookies set by one page
+/// are sent with the next.
+#[derive(Debug)]
+pub struct Fetcher {
+    config: FetchConfig,
+    #[cfg(feature = "url")]
+    session: Session,
+    #[cfg(feature = "render")]
+    renderer: Option<Renderer>,
+    concurrency: NonZeroUsize,
+    rate_limiter: Option<RateLimiter>,
+    in_flight: Mutex<usize>,
+    slot_freed: Condvar,
+}
+
+impl Fetcher {
+    /// Creates a fetcher allowing `concurrency` parallel requests and at most
+    /// `rate_limit` requests per second to any single host.
+    #[must_use]
+    pub fn new(config: FetchConfig, concurrency: NonZeroUsize, rate_limit: Option<f64>) -> Self {
+        Self {
+            #[cfg(feature = "url")]
+            session: Session::new(config.options()),
+            #[cfg(feature = "render")]
+            renderer: None,
+            config,
+            concurrency,
+            rate_limiter: rate_limit.map(RateLimiter::per_second),
+            in_flight: Mutex::new(0),
+            slot_freed: Condvar::new(),
+        }
+    }
+
+    /// Loads pages with `renderer` instead of plain requests.
+    ///
+    /// Only [`Fetcher::fetch`] is affected; assets are still requested directly.
+    #[cfg(feature = "render")]
+    #[must_use]
+    pub fn with_renderer(mut self, renderer: Renderer) -> Self {
+        self.renderer = Some(renderer);
+        self
+    }
+
+    /// Returns the maximum number of parallel requests.
+    #[must_use]
+    pub const fn concurrency(&self) -> usize {
+        self.concurrency.get()
+    }
+
+    /// Fetches `url`, waiting for a free slot and for the host's rate limit, and
+    /// retrying transient failures with exponential backoff.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last `FetchError` once retries are exhausted, or the first
+    /// non-transient one.
+    pub fn fetch(&self, url: &str) -> Result<String, FetchError> {
+        #[cfg(feature = "render")]
+        if let Some(renderer) = &self.renderer {
+            return self.retrying(url, || renderer.render(url));
+        }
+        self.fetch_with(url, |response| response.text().into_owned())
+    }
+
+    /// Fetches a binary resource like [`Fetcher::fetch`], with its content type.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last `FetchError` once retries are exhausted, or the first
+    /// non-transient one.
+    pub fn fetch_asset(&self, url: &str) -> Result<FetchedAsset, FetchError> {
+        self.fetch_with(url, |response| FetchedAsset {
+            content_type: response.content_type,
+            data: response.body,
+        })
+    }
+
+    fn fetch_with<T>(&self, url: &str, convert: fn(FetchResponse) -> T) -> Result<T, FetchError> {
+        self.retrying(url, || self.get(url).map(convert))
+    }
+
+    /// Runs `attempt` for `url` within the concurrency and rate limits, retrying
+    /// transient failures.
+    fn retrying<T>(
+        &self,
+        url: &str,
+        attempt: impl Fn() -> Result<T, FetchError>,
+    ) -> Result<T, FetchError> {
+        let mut backoff = self.config.retry_backoff;
+        for _ in 0..self.config.retries {
+            match self.attempt_once(url, &attempt) {
+                Err(e) if e.is_transient() => {
+                    thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+        self.attempt_once(url, &attempt)
+    }
+
+    fn attempt_once<T>(
+        &self,
+        url: &str,
+        attempt: impl Fn() -> Result<T, FetchError>,
+    ) -> Result<T, FetchError> {
+        self.wait_for_host(url);
+        let _slot = self.acquire_slot();
+        attempt()
+    }
+
+    #[cfg(feature = "url")]
+    fn get(&self, url: &str) -> Result<FetchResponse, FetchError> {
+        self.session.get(url)
+    }
+
+    #[cfg(not(feature = "url"))]
+    fn get(&self, _url: &str) -> Result<FetchResponse, FetchError> {
+        Err(FetchError::Http("URL support not compiled (use --features url)".to_string()))
+    }
+
+    /// Reserves the next request time for the URL's host and sleeps until it.
+    fn wait_for_host(&self, url: &str) {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire(&host_key(url));
+        }
+    }
+
+    /// Blocks until fewer than `concurrency` requests are in flight.
+    fn acquire_slot(&self) -> Slot<'_> {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(PoisonError::into_inner);
+        while *in_flight >= self.concurrency.get() {
+            in_flight = self.slot_freed.wait(in_flight).unwrap_or_else(PoisonError::into_inner);
+        }
+        *in_flight += 1;
+        drop(in_flight);
+        Slot(self)
+    }
+}
+
+impl AssetFetcher for Fetcher {
+    type Error = FetchError;
+
+    fn fetch(&self, url: &str) -> Result<FetchedAsset, FetchError> {
+        self.fetch_asset(url)
+    }
+}
+
+/// An in-flight request; frees its slot when dropped.
+struct Slot<'a>(&'a Fetcher);
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        *self.0.in_flight.lock().unwrap_or_else(PoisonError::into_inner) -= 1;
+        self.0.slot_freed.notify_one();
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::Instant;
+
     use super::*;
 
     #[test]
@@ -135,6 +301,8 @@ mod tests {
             timeout: Duration::from_secs(10),
             user_agent: "custom-agent/1.0".to_string(),
             max_size: 5 * 1024 * 1024,
+            retries: 3,
+            retry_backoff: Duration::from_millis(100),
         };
         assert_eq!(config.timeout, Duration::from_secs(10));
         assert_eq!(config.user_agent, "custom-agent/1.0");
@@ -160,4 +328,79 @@ mod tests {
         let config = FetchConfig::default();
         assert!(config.user_agent.contains("scrape-cli/"));
     }
+
+    #[test]
+    fn test_fetch_error_is_transient() {
+        assert!(FetchError::Timeout(Duration::from_secs(1)).is_transient());
+        assert!(FetchError::Io("connection reset".into()).is_transient());
+        assert!(FetchError::Status(429).is_transient());
+        assert!(FetchError::Status(503).is_transient());
+        assert!(!FetchError::Status(404).is_transient());
+        assert!(!FetchError::InvalidUrl("bad".into()).is_transient());
+        assert!(!FetchError::TooLarge { size: 2, max: 1 }.is_transient());
+        assert_eq!(FetchError::Status(404).to_string(), "HTTP error: HTTP 404");
+    }
+
+    #[test]
+    fn test_is_url_and_host_key() {
+        assert!(is_url("https://example.com"));
+        assert!(is_url("http://example.com/a"));
+        assert!(!is_url("page.html"));
+        assert!(!is_url("ftp://example.com"));
+
+        assert_eq!(host_key("https://Example.COM/path?q=1"), "example.com");
+        assert_eq!(host_key("http://user:pw@example.com:8080#top"), "example.com:8080");
+        assert_eq!(host_key("https://example.com?q"), "example.com");
+    }
+
+    #[test]
+    fn test_fetcher_rate_limits_per_host() {
+        let one = NonZeroUsize::new(1).unwrap();
+        let fetcher = Fetcher::new(FetchConfig::default(), one, Some(20.0));
+
+        let start = Instant::now();
+        fetcher.wait_for_host("https://a.example/1");
+        fetcher.wait_for_host("https://b.example/1");
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        fetcher.wait_for_host("https://a.example/2");
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_fetcher_limits_concurrency() {
+        let fetcher = Fetcher::new(FetchConfig::default(), NonZeroUsize::new(2).unwrap(), None);
+        assert_eq!(fetcher.concurrency(), 2);
+
+        let first = fetcher.acquire_slot();
+        let _second = fetcher.acquire_slot();
+        assert_eq!(*fetcher.in_flight.lock().unwrap(), 2);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| drop(fetcher.acquire_slot()));
+            thread::sleep(Duration::from_millis(20));
+            assert!(!waiter.is_finished());
+            drop(first);
+            waiter.join().unwrap();
+        });
+        assert_eq!(*fetcher.in_flight.lock().unwrap(), 1);
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn test_fetcher_retries_transient_errors() {
+        let config = FetchConfig {
+            timeout: Duration::from_secs(5),
+            retries: 2,
+            retry_backoff: Duration::from_millis(20),
+            ..Default::default()
+        };
+        let fetcher = Fetcher::new(config, NonZeroUsize::MIN, None);
+
+        // Nothing listens on port 1, so every attempt fails with a connection error.
+        let start = Instant::now();
+        let err = fetcher.fetch("http://127.0.0.1:1/").unwrap_err();
+        assert!(err.is_transient(), "unexpected error: {err}");
+        assert!(start.elapsed() >= Duration::from_millis(60));
+    }
 }
@@ -0,0 +1,144 @@
+//! `scrape lint` subcommand.
+
+use std::io::{self, Read, Write};
+
+use anyhow::{Context, Result};
+use scrape_core::lint::{LintWarning, lint};
+use serde_json::{Value, json};
+
+use crate::args::{LintArgs, OutputFormat};
+
+/// Lints every input and prints the problems found.
+///
+/// Returns `true` if no input has any problem.
+///
+/// # Errors
+///
+/// Returns an error if an input cannot be read or parsed, or output cannot be written.
+pub fn run(args: &LintArgs) -> Result<bool> {
+    let mut reports = Vec::new();
+    for path in &args.files {
+        let name = path.display().to_string();
+        let html =
+            std::fs::read_to_string(path).with_context(|| format!("failed to read {name}"))?;
+        let warnings = lint(&html).with_context(|| format!("failed to parse {name}"))?;
+        reports.push((name, warnings));
+    }
+    if args.files.is_empty() {
+        let mut html = String::new();
+        io::stdin().read_to_string(&mut html)?;
+        reports.push(("-".to_string(), lint(&html)?));
+    }
+
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    write_reports(&mut writer, &reports, args.output, args.pretty)?;
+    writer.flush()?;
+
+    Ok(reports.iter().all(|(_, warnings)| warnings.is_empty()))
+}
+
+/// Writes warnings as an aligned table, a JSON array, or one JSON object per line.
+///
+/// The table has a `FILE` column only when more than one input was linted.
+fn write_reports(
+    writer: &mut impl Write,
+    reports: &[(String, Vec<LintWarning>)],
+    format: OutputFormat,
+    pretty: bool,
+) -> Result<()> {
+    let records = reports
+        .iter()
+        .flat_map(|(file, warnings)| warnings.iter().map(move |warning| record(file, warning)));
+    match format {
+        OutputFormat::Json => {
+            let records: Vec<_> = records.collect();
+            if pretty {
+                serde_json::to_writer_pretty(&mut *writer, &records)?;
+            } else {
+                serde_json::to_writer(&mut *writer, &records)?;
+            }
+            writeln!(writer)?;
+        }
+        OutputFormat::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut *writer, &record)?;
+                writeln!(writer)?;
+            }
+        }
+        _ => {
+            let with_file = reports.len() > 1;
+            let mut rows = vec![["FILE", "LINE", "KIND", "LOCATION", "MESSAGE"].map(String::from)];
+            for (file, warnings) in reports {
+                for warning in warnings {
+                    rows.push([
+                        file.clone(),
+                        warning.line.map_or_else(|| "-".to_string(), |line| line.to_string()),
+                        warning.kind.to_string(),
+                        warning.path.clone().unwrap_or_else(|| "-".to_string()),
+                        warning.message.clone(),
+                    ]);
+                }
+            }
+            if rows.len() == 1 {
+                return Ok(());
+            }
+            let skip = usize::from(!with_file);
+            let mut widths = [0; 5];
+            for row in &rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+            for row in &rows {
+                let [cells @ .., message] = row;
+                for (cell, width) in cells.iter().zip(widths).skip(skip) {
+                    write!(writer, "{cell:<width$}  ")?;
+                }
+                writeln!(writer, "{message}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn record(file: &str, warning: &LintWarning) -> Value {
+    json!({
+        "file": file,
+        "kind": warning.kind.as_str(),
+        "message": warning.message,
+        "path": warning.path,
+        "line": warning.line,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(format: OutputFormat, files: usize) -> String {
+        let warnings = lint("<!DOCTYPE html><p id=\"a\">A</p><img id=\"a\">").unwrap();
+        let reports: Vec<_> =
+            (0..files).map(|i| (format!("page{i}.html"), warnings.clone())).collect();
+        let mut buf = Vec::new();
+        write_reports(&mut buf, &reports, format, false).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_write_reports() {
+        assert_eq!(
+            output(OutputFormat::Text, 1),
+            "LINE  KIND          LOCATION           MESSAGE\n-     duplicate-id  html > body > \
+             img  id \"a\" is already used by html > body > p\n-     missing-alt   html > body > \
+             img  <img> has no alt attribute\n"
+        );
+        assert!(output(OutputFormat::Text, 2).starts_with("FILE        LINE  KIND  "));
+        let ndjson = output(OutputFormat::Ndjson, 2);
+        assert_eq!(ndjson.lines().count(), 4);
+        assert!(
+            ndjson.starts_with("{\"file\":\"page0.html\",\"kind\":\"duplicate-id\",\"line\":null")
+        );
+        assert!(output(OutputFormat::Json, 1).starts_with("[{\"file\":\"page0.html\""));
+    }
+}
@@ -1,20 +1,39 @@
 //! scrape - High-performance HTML extraction CLI.
 
+mod archive;
 mod args;
+mod article;
+mod assets;
 mod batch;
+mod bench;
+mod contacts;
+mod crawl;
+mod diff;
 mod extract;
 mod fetch;
+mod lint;
+mod metadata;
 mod output;
+#[cfg(feature = "render")]
+mod render;
 mod repl;
+mod schema;
+mod table;
+mod tree;
+mod watch;
 
 use std::{
     io::{self, Read, Write},
     process::ExitCode,
 };
 
-use args::{Args, ColorMode, OutputFormat};
+use args::{Args, ColorMode, Command, OutputFormat};
+use extract::MatchFormat;
 use is_terminal::IsTerminal;
-use output::{CsvOutput, HtmlOutput, JsonOutput, Output, TextOutput};
+use output::{
+    CsvOutput, HtmlOutput, JsonOutput, MarkdownOutput, NdjsonOutput, Output, SummaryOutput,
+    Template, TemplateOutput, TextOutput,
+};
 
 fn main() -> ExitCode {
     let args = match Args::parse_and_validate() {
@@ -25,6 +44,13 @@ fn main() -> ExitCode {
         }
     };
 
+    if args.watch {
+        if let Err(e) = watch::run(&args, run_extraction) {
+            eprintln!("Error: {e}");
+        }
+        return ExitCode::from(2);
+    }
+
     match run(&args) {
         Ok(found) => {
             if found {
@@ -42,8 +68,18 @@ fn main() -> ExitCode {
     }
 }
 
-#[allow(clippy::too_many_lines)]
 fn run(args: &Args) -> anyhow::Result<bool> {
+    match &args.command {
+        Some(Command::Contacts(contacts_args)) => return contacts::run(contacts_args),
+        Some(Command::Tree(tree_args)) => return tree::run(tree_args),
+        Some(Command::Bench(bench_args)) => return bench::run(bench_args),
+        Some(Command::Archive(archive_args)) => return archive::run(archive_args),
+        Some(Command::Diff(diff_args)) => return diff::run(diff_args),
+        Some(Command::Crawl(crawl_args)) => return crawl::run(crawl_args),
+        Some(Command::Lint(lint_args)) => return lint::run(lint_args),
+        None => {}
+    }
+
     // Handle interactive mode
     if args.interactive {
         let mut repl = repl::Repl::new();
@@ -68,6 +104,12 @@ fn run(args: &Args) -> anyhow::Result<bool> {
         return Err(anyhow::anyhow!("--explain requires a selector"));
     }
 
+    run_extraction(args, &mut io::stdout().lock())
+}
+
+/// Extracts from the inputs as `args` describe, writing the results to `writer`.
+#[allow(clippy::too_many_lines)]
+fn run_extraction(args: &Args, writer: &mut dyn Write) -> anyhow::Result<bool> {
     let use_color = match args.color {
         ColorMode::Always => true,
         ColorMode::Never => false,
@@ -79,57 +121,124 @@ fn run(args: &Args) -> anyhow::Result<bool> {
     let output: Box<dyn Output> = match args.output {
         OutputFormat::Text => Box::new(TextOutput { delimiter, color: use_color }),
         OutputFormat::Json => Box::new(JsonOutput { pretty: args.pretty }),
+        OutputFormat::Ndjson => Box::new(NdjsonOutput),
         OutputFormat::Html => Box::new(HtmlOutput { delimiter }),
         OutputFormat::Csv => Box::new(CsvOutput),
+        OutputFormat::Markdown => Box::new(MarkdownOutput),
+        OutputFormat::Summary => {
+            Box::new(SummaryOutput::new(args.selector.clone().unwrap_or_default()))
+        }
+        OutputFormat::Template => Box::new(TemplateOutput {
+            template: args.template.clone().unwrap_or_default(),
+            delimiter,
+        }),
     };
 
-    let stdout = io::stdout();
-    let mut writer = stdout.lock();
+    // Templates can name the element's HTML and attributes, and `{filename}` even for
+    // a single input.
+    let template = args.template.as_ref();
+    let keep_element = template.is_some_and(Template::needs_element);
+    let inner_html = template.is_some_and(Template::needs_inner_html);
+    let show_filename = args.show_filename() || template.is_some();
 
     let mut found_any = false;
+    let mut rejections = batch::RejectionSummary::default();
+    let inputs = args.inputs();
+    let fetcher = args.fetcher();
+    if let Some(threads) = batch_threads(args.parallel, &inputs, fetcher.concurrency()) {
+        scrape_core::runtime::configure(Some(threads), None)?;
+    }
+
+    if let Some(ref dir) = args.dump_assets {
+        let found = assets::run(args, dir, &inputs, &fetcher, writer)?;
+        writer.flush()?;
+        return Ok(found);
+    }
+
+    if args.metadata {
+        let found = metadata::run(args, &inputs, &fetcher, writer)?;
+        writer.flush()?;
+        return Ok(found);
+    }
+
+    if args.article {
+        let found = article::run(args, &inputs, &fetcher, writer)?;
+        writer.flush()?;
+        return Ok(found);
+    }
+
+    if let Some(ref path) = args.schema {
+        let found = schema::run(args, path, &inputs, &fetcher, writer)?;
+        writer.flush()?;
+        return Ok(found);
+    }
+
+    if let Some(ref selector) = args.table {
+        let found = table::run(args, selector, &inputs, &fetcher, writer)?;
+        writer.flush()?;
+        return Ok(found);
+    }
 
-    if args.files.is_empty() {
+    if inputs.is_empty() {
         // Read from stdin
         let mut html = String::new();
         io::stdin().read_to_string(&mut html)?;
+        let soup = args.parse_check().parse("-", &html)?;
 
         if let Some(ref selector) = args.selector {
-            let results = extract::extract(
-                &html,
-                selector,
-                args.attribute.as_deref(),
-                args.first,
-                args.output == OutputFormat::Json,
-            )?;
+            let options = args.serialize_options();
+            let format = MatchFormat {
+                html: (keep_element
+                    || matches!(
+                        args.output,
+                        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Html
+                    ))
+                .then_some(&options),
+                inner_html,
+                markdown: args.output == OutputFormat::Markdown,
+            };
+            let results =
+                extract::extract(&soup, selector, args.attribute.as_deref(), args.first, format)?;
             found_any = !results.is_empty();
-            output.format_single(&mut writer, &results, None)?;
+            output.format_single(writer, &results, None)?;
         } else {
-            let selectors = args.parse_selects();
-            let results =
-                extract::extract_named(&html, &selectors, args.attribute.as_deref(), args.first)?;
-            found_any = results.values().any(|v| !v.is_empty());
-            output.format_named(&mut writer, &results, None)?;
+            let schema = args.named_schema()?;
+            let results = extract::extract_named(&soup, &schema, args.first, args.provenance);
+            rejections.record(results.rejection.as_deref());
+            if let Some(reason) = &results.rejection {
+                output.format_rejected(writer, reason, None)?;
+            } else {
+                found_any = results.fields.values().any(|v| !v.is_empty());
+                output.format_named(writer, &results.fields, None)?;
+            }
         }
     } else if let Some(ref selector) = args.selector {
-        // Single selector, multiple files
+        // Single selector, multiple files or URLs
+        let options = args.serialize_options();
+        let format = MatchFormat {
+            html: (keep_element || args.output == OutputFormat::Html).then_some(&options),
+            inner_html,
+            markdown: args.output == OutputFormat::Markdown,
+        };
         let results = batch::process_files(
-            &args.files,
+            &inputs,
             selector,
             args.attribute.as_deref(),
             args.first,
-            args.parallel,
+            format,
+            args.parse_check(),
+            &fetcher,
         );
 
+        // The summary counts inputs without matches too.
+        let summary = args.output == OutputFormat::Summary;
         for file_result in results {
             match file_result.result {
-                Ok(extractions) if !extractions.is_empty() => {
-                    found_any = true;
-                    let filename = if args.show_filename() {
-                        Some(file_result.filename.as_str())
-                    } else {
-                        None
-                    };
-                    output.format_single(&mut writer, &extractions, filename)?;
+                Ok(extractions) if !extractions.is_empty() || summary => {
+                    found_any |= !extractions.is_empty();
+                    let filename =
+                        if show_filename { Some(file_result.filename.as_str()) } else { None };
+                    output.format_single(writer, &extractions, filename)?;
                 }
                 Err(e) if !args.quiet => {
                     eprintln!("{}: {e}", file_result.filename);
@@ -138,28 +247,31 @@ fn run(args: &Args) -> anyhow::Result<bool> {
             }
         }
     } else {
-        // Named selectors, multiple files
-        let selectors = args.parse_selects();
+        // Named selectors, multiple files or URLs
+        let schema = args.named_schema()?;
         let results = batch::process_files_named(
-            &args.files,
-            &selectors,
-            args.attribute.as_deref(),
+            &inputs,
+            &schema,
             args.first,
-            args.parallel,
+            args.provenance,
+            args.parse_check(),
+            &fetcher,
         );
 
         for file_result in results {
             match file_result.result {
                 Ok(extractions) => {
-                    if extractions.values().any(|v| !v.is_empty()) {
+                    rejections.record(extractions.rejection.as_deref());
+                    let filename =
+                        if show_filename { Some(file_result.filename.as_str()) } else { None };
+                    if let Some(reason) = &extractions.rejection {
+                        output.format_rejected(writer, reason, filename)?;
+                        continue;
+                    }
+                    if extractions.fields.values().any(|v| !v.is_empty()) {
                         found_any = true;
                     }
-                    let filename = if args.show_filename() {
-                        Some(file_result.filename.as_str())
-                    } else {
-                        None
-                    };
-                    output.format_named(&mut writer, &extractions, filename)?;
+                    output.format_named(writer, &extractions.fields, filename)?;
                 }
                 Err(e) if !args.quiet => {
                     eprintln!("{}: {e}", file_result.filename);
@@ -169,6 +281,25 @@ fn run(args: &Args) -> anyhow::Result<bool> {
         }
     }
 
+    output.finish(writer)?;
     writer.flush()?;
+    if !args.quiet {
+        rejections.write(&mut io::stderr())?;
+    }
     Ok(found_any)
 }
+
+/// Threads for batch processing: `-j` if given, otherwise enough to keep `concurrency`
+/// fetches in flight when any input is a URL, and rayon's default for files only.
+fn batch_threads(
+    parallel: Option<usize>,
+    inputs: &[batch::Input],
+    concurrency: usize,
+) -> Option<usize> {
+    parallel.or_else(|| {
+        inputs.iter().any(batch::Input::is_url).then(|| {
+            let cores = std::thread::available_parallelism().map_or(1, std::num::NonZero::get);
+            cores.max(concurrency)
+        })
+    })
+}
@@ -0,0 +1,157 @@
+//! `--metadata` extraction of meta tags, Open Graph, Twitter cards, and JSON-LD.
+
+use std::io::{self, Read, Write};
+
+use anyhow::Result;
+use scrape_core::{Soup, metadata::Metadata};
+use serde_json::Value;
+
+use crate::{
+    args::{Args, OutputFormat},
+    batch::{Input, process_metadata},
+    fetch::Fetcher,
+};
+
+/// Collects the metadata of every input and prints it.
+///
+/// Returns `true` if any input has metadata.
+///
+/// # Errors
+///
+/// Returns an error if stdin or output cannot be read or written.
+pub fn run(
+    args: &Args,
+    inputs: &[Input],
+    fetcher: &Fetcher,
+    writer: &mut dyn Write,
+) -> Result<bool> {
+    let mut found = Vec::new();
+
+    if inputs.is_empty() {
+        let mut html = String::new();
+        io::stdin().read_to_string(&mut html)?;
+        found.push(("-".to_string(), Soup::parse(&html).metadata()));
+    } else {
+        for file_result in process_metadata(inputs, fetcher) {
+            match file_result.result {
+                Ok(metadata) => found.push((file_result.filename, metadata)),
+                Err(e) if !args.quiet => eprintln!("{}: {e}", file_result.filename),
+                Err(_) => {}
+            }
+        }
+    }
+
+    let any = found.iter().any(|(_, metadata)| !metadata.is_empty());
+    write_metadata(writer, found, args.output, args.pretty)?;
+    Ok(any)
+}
+
+/// Writes `key<TAB>value` lines with a blank line between inputs, or JSON objects with
+/// an `input` field: one array for all inputs, or one object per line.
+fn write_metadata(
+    writer: &mut dyn Write,
+    found: Vec<(String, Metadata)>,
+    format: OutputFormat,
+    pretty: bool,
+) -> io::Result<()> {
+    if matches!(format, OutputFormat::Json | OutputFormat::Ndjson) {
+        let objects = found.into_iter().map(|(input, metadata)| {
+            let mut value = Value::from(metadata);
+            value["input"] = Value::String(input);
+            value
+        });
+        if format == OutputFormat::Ndjson {
+            for object in objects {
+                writeln!(writer, "{object}")?;
+            }
+            return Ok(());
+        }
+        let objects: Vec<_> = objects.collect();
+        let output = if pretty {
+            serde_json::to_string_pretty(&objects)
+        } else {
+            serde_json::to_string(&objects)
+        }
+        .map_err(io::Error::other)?;
+        return writeln!(writer, "{output}");
+    }
+
+    for (i, (_, metadata)) in found.iter().enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        for (key, value) in lines(metadata) {
+            writeln!(writer, "{key}\t{}", value.split_whitespace().collect::<Vec<_>>().join(" "))?;
+        }
+    }
+    Ok(())
+}
+
+/// Flattens metadata into `(key, value)` pairs: field names for single values,
+/// `meta:NAME` for meta tags, property names for Open Graph and Twitter fields,
+/// `icon` for favicons, and `json-ld` for compact JSON-LD blocks.
+fn lines(metadata: &Metadata) -> Vec<(String, String)> {
+    let fields = [
+        ("title", &metadata.title),
+        ("description", &metadata.description),
+        ("lang", &metadata.lang),
+        ("charset", &metadata.charset),
+        ("canonical", &metadata.canonical),
+    ];
+    let mut lines: Vec<_> = fields
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value.clone()?)))
+        .collect();
+    lines.extend(
+        metadata.meta.iter().map(|(name, content)| (format!("meta:{name}"), content.clone())),
+    );
+    for (property, values) in &metadata.open_graph {
+        lines.extend(values.iter().map(|value| (property.clone(), value.clone())));
+    }
+    lines.extend(metadata.twitter.iter().map(|(key, value)| (key.clone(), value.clone())));
+    lines.extend(metadata.favicons.iter().map(|icon| ("icon".to_string(), icon.href.clone())));
+    lines.extend(metadata.json_ld.iter().map(|value| ("json-ld".to_string(), value.to_string())));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = "<title>Tea</title><meta name=\"author\" content=\"Ann\"><meta \
+                        property=\"og:image\" content=\"a.png\"><meta property=\"og:image\" \
+                        content=\"b.png\"><link rel=\"icon\" href=\"/favicon.ico\"><script \
+                        type=\"application/ld+json\">{\"@type\": \"Thing\"}</script>";
+
+    #[test]
+    fn test_write_metadata_text() {
+        let found = vec![("a.html".into(), Soup::parse(HTML).metadata())];
+        let mut buf = Vec::new();
+        write_metadata(&mut buf, found, OutputFormat::Text, false).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            concat!(
+                "title\tTea\nmeta:author\tAnn\nog:image\ta.png\nog:image\tb.png\n",
+                "icon\t/favicon.ico\njson-ld\t{\"@type\":\"Thing\"}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_write_metadata_ndjson() {
+        let found = vec![
+            ("a.html".into(), Soup::parse(HTML).metadata()),
+            ("b.html".into(), Metadata::default()),
+        ];
+        let mut buf = Vec::new();
+        write_metadata(&mut buf, found, OutputFormat::Ndjson, false).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        let objects: Vec<Value> =
+            output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0]["input"], "a.html");
+        assert_eq!(objects[0]["open_graph"]["og:image"], serde_json::json!(["a.png", "b.png"]));
+        assert_eq!(objects[0]["json_ld"][0]["@type"], "Thing");
+        assert_eq!(objects[1]["title"], Value::Null);
+    }
+}
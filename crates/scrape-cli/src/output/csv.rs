@@ -75,8 +75,20 @@ mod tests {
     fn test_format_single() {
         let output = CsvOutput;
         let results = vec![
-            Extraction { text: "Hello".into(), attrs: None, html: None },
-            Extraction { text: "World".into(), attrs: None, html: None },
+            Extraction {
+                text: "Hello".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
+            Extraction {
+                text: "World".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
         ];
 
         let mut buf = Vec::new();
@@ -90,7 +102,13 @@ mod tests {
     #[test]
     fn test_format_single_with_filename() {
         let output = CsvOutput;
-        let results = vec![Extraction { text: "Hello".into(), attrs: None, html: None }];
+        let results = vec![Extraction {
+            text: "Hello".into(),
+            attrs: None,
+            html: None,
+            inner_html: None,
+            provenance: None,
+        }];
 
         let mut buf = Vec::new();
         output.format_single(&mut buf, &results, Some("test.html")).unwrap();
@@ -106,15 +124,39 @@ mod tests {
         results.insert(
             "name".into(),
             vec![
-                Extraction { text: "Alice".into(), attrs: None, html: None },
-                Extraction { text: "Bob".into(), attrs: None, html: None },
+                Extraction {
+                    text: "Alice".into(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: None,
+                },
+                Extraction {
+                    text: "Bob".into(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: None,
+                },
             ],
         );
         results.insert(
             "age".into(),
             vec![
-                Extraction { text: "30".into(), attrs: None, html: None },
-                Extraction { text: "25".into(), attrs: None, html: None },
+                Extraction {
+                    text: "30".into(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: None,
+                },
+                Extraction {
+                    text: "25".into(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: None,
+                },
             ],
         );
 
@@ -133,12 +175,32 @@ mod tests {
         results.insert(
             "name".into(),
             vec![
-                Extraction { text: "Alice".into(), attrs: None, html: None },
-                Extraction { text: "Bob".into(), attrs: None, html: None },
+                Extraction {
+                    text: "Alice".into(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: None,
+                },
+                Extraction {
+                    text: "Bob".into(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: None,
+                },
             ],
         );
-        results
-            .insert("age".into(), vec![Extraction { text: "30".into(), attrs: None, html: None }]);
+        results.insert(
+            "age".into(),
+            vec![Extraction {
+                text: "30".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            }],
+        );
 
         let mut buf = Vec::new();
         output.format_named(&mut buf, &results, None).unwrap();
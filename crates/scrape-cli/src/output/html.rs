@@ -74,7 +74,13 @@ mod tests {
     #[test]
     fn test_format_single_text() {
         let output = HtmlOutput { delimiter: b'\n' };
-        let results = vec![Extraction { text: "Hello".into(), attrs: None, html: None }];
+        let results = vec![Extraction {
+            text: "Hello".into(),
+            attrs: None,
+            html: None,
+            inner_html: None,
+            provenance: None,
+        }];
 
         let mut buf = Vec::new();
         output.format_single(&mut buf, &results, None).unwrap();
@@ -88,6 +94,8 @@ mod tests {
             text: "Hello".into(),
             attrs: None,
             html: Some("<span>Hello</span>".into()),
+            inner_html: None,
+            provenance: None,
         }];
 
         let mut buf = Vec::new();
@@ -98,7 +106,13 @@ mod tests {
     #[test]
     fn test_format_single_with_filename() {
         let output = HtmlOutput { delimiter: b'\n' };
-        let results = vec![Extraction { text: "Hello".into(), attrs: None, html: None }];
+        let results = vec![Extraction {
+            text: "Hello".into(),
+            attrs: None,
+            html: None,
+            inner_html: None,
+            provenance: None,
+        }];
 
         let mut buf = Vec::new();
         output.format_single(&mut buf, &results, Some("test.html")).unwrap();
@@ -113,7 +127,13 @@ mod tests {
         let mut results = HashMap::new();
         results.insert(
             "title".into(),
-            vec![Extraction { text: "Hello".into(), attrs: None, html: None }],
+            vec![Extraction {
+                text: "Hello".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            }],
         );
 
         let mut buf = Vec::new();
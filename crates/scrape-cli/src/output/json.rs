@@ -46,8 +46,11 @@ impl Output for JsonOutput {
         let value: Value = results
             .iter()
             .map(|(name, extractions)| {
-                let texts: Vec<&str> = extractions.iter().map(|e| e.text.as_str()).collect();
-                (name.clone(), json!(texts))
+                let values: Vec<Value> = extractions
+                    .iter()
+                    .map(|e| if e.provenance.is_some() { json!(e) } else { json!(e.text) })
+                    .collect();
+                (name.clone(), Value::Array(values))
             })
             .collect();
 
@@ -64,14 +67,28 @@ impl Output for JsonOutput {
 
 #[cfg(test)]
 mod tests {
+    use scrape_core::extract::Provenance;
+
     use super::*;
 
     #[test]
     fn test_format_single_simple() {
         let output = JsonOutput { pretty: false };
         let results = vec![
-            Extraction { text: "Hello".into(), attrs: None, html: None },
-            Extraction { text: "World".into(), attrs: None, html: None },
+            Extraction {
+                text: "Hello".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
+            Extraction {
+                text: "World".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
         ];
 
         let mut buf = Vec::new();
@@ -83,12 +100,14 @@ mod tests {
     #[test]
     fn test_format_single_with_attrs() {
         let output = JsonOutput { pretty: false };
-        let mut attrs = HashMap::new();
-        attrs.insert("href".into(), "/page".into());
+        let mut attrs = scrape_core::Attributes::new();
+        attrs.insert("href", "/page");
         let results = vec![Extraction {
             text: "Link".into(),
             attrs: Some(attrs),
             html: Some("<a href=\"/page\">Link</a>".into()),
+            inner_html: None,
+            provenance: None,
         }];
 
         let mut buf = Vec::new();
@@ -102,7 +121,13 @@ mod tests {
     #[test]
     fn test_format_single_pretty() {
         let output = JsonOutput { pretty: true };
-        let results = vec![Extraction { text: "Hello".into(), attrs: None, html: None }];
+        let results = vec![Extraction {
+            text: "Hello".into(),
+            attrs: None,
+            html: None,
+            inner_html: None,
+            provenance: None,
+        }];
 
         let mut buf = Vec::new();
         output.format_single(&mut buf, &results, None).unwrap();
@@ -116,13 +141,31 @@ mod tests {
         let mut results = HashMap::new();
         results.insert(
             "title".into(),
-            vec![Extraction { text: "Hello".into(), attrs: None, html: None }],
+            vec![Extraction {
+                text: "Hello".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            }],
         );
         results.insert(
             "links".into(),
             vec![
-                Extraction { text: "A".into(), attrs: None, html: None },
-                Extraction { text: "B".into(), attrs: None, html: None },
+                Extraction {
+                    text: "A".into(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: None,
+                },
+                Extraction {
+                    text: "B".into(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: None,
+                },
             ],
         );
 
@@ -132,4 +175,34 @@ mod tests {
         assert!(json_str.contains("\"title\":[\"Hello\"]"));
         assert!(json_str.contains("\"links\":[\"A\",\"B\"]"));
     }
+    #[test]
+    fn test_format_named_provenance() {
+        let output = JsonOutput { pretty: false };
+        let provenance = Provenance {
+            field: "title[0]".into(),
+            selector: Some("h1".into()),
+            css_path: "html > body > h1".into(),
+            line: Some(3),
+        };
+        let mut results = HashMap::new();
+        results.insert(
+            "title".into(),
+            vec![Extraction {
+                text: "Hello".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: Some(provenance),
+            }],
+        );
+
+        let mut buf = Vec::new();
+        output.format_named(&mut buf, &results, None).unwrap();
+        let json_str = String::from_utf8(buf).unwrap();
+        assert!(json_str.contains("\"text\":\"Hello\""));
+        // Keys of named output are sorted.
+        assert!(json_str.contains(
+            r#""provenance":{"css_path":"html > body > h1","field":"title[0]","line":3,"selector":"h1"}"#
+        ));
+    }
 }
@@ -0,0 +1,101 @@
+//! Markdown output formatter.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use super::{Extraction, Output};
+
+/// Markdown output formatter.
+///
+/// Results are separated by blank lines. File and selector names are written as HTML
+/// comments, which Markdown renderers hide.
+pub struct MarkdownOutput;
+
+impl Output for MarkdownOutput {
+    fn format_single(
+        &self,
+        writer: &mut dyn Write,
+        results: &[Extraction],
+        filename: Option<&str>,
+    ) -> io::Result<()> {
+        if let Some(name) = filename {
+            writeln!(writer, "<!-- {} -->\n", name.replace("--", "- -"))?;
+        }
+        for result in results {
+            writeln!(writer, "{}\n", result.text)?;
+        }
+        Ok(())
+    }
+
+    fn format_named(
+        &self,
+        writer: &mut dyn Write,
+        results: &HashMap<String, Vec<Extraction>>,
+        filename: Option<&str>,
+    ) -> io::Result<()> {
+        if let Some(fname) = filename {
+            writeln!(writer, "<!-- {} -->\n", fname.replace("--", "- -"))?;
+        }
+
+        let mut keys: Vec<_> = results.keys().collect();
+        keys.sort();
+
+        for name in keys {
+            writeln!(writer, "<!-- {} -->\n", name.replace("--", "- -"))?;
+            for extraction in &results[name] {
+                writeln!(writer, "{}\n", extraction.text)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_single() {
+        let results = vec![
+            Extraction {
+                text: "# One".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
+            Extraction {
+                text: "- two".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
+        ];
+
+        let mut buf = Vec::new();
+        MarkdownOutput.format_single(&mut buf, &results, Some("a--b.html")).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "<!-- a- -b.html -->\n\n# One\n\n- two\n\n");
+    }
+
+    #[test]
+    fn test_format_named() {
+        let mut results = HashMap::new();
+        results.insert(
+            "title".into(),
+            vec![Extraction {
+                text: "Hello".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            }],
+        );
+
+        let mut buf = Vec::new();
+        MarkdownOutput.format_named(&mut buf, &results, None).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "<!-- title -->\n\nHello\n\n");
+    }
+}
@@ -3,6 +3,10 @@
 mod csv;
 mod html;
 mod json;
+mod markdown;
+mod ndjson;
+mod summary;
+mod template;
 mod text;
 
 use std::{
@@ -10,7 +14,16 @@ use std::{
     io::{self, Write},
 };
 
-pub use self::{csv::CsvOutput, html::HtmlOutput, json::JsonOutput, text::TextOutput};
+pub use self::{
+    csv::CsvOutput,
+    html::HtmlOutput,
+    json::JsonOutput,
+    markdown::MarkdownOutput,
+    ndjson::NdjsonOutput,
+    summary::SummaryOutput,
+    template::{Template, TemplateOutput},
+    text::TextOutput,
+};
 use crate::extract::Extraction;
 
 /// Trait for output formatters.
@@ -40,4 +53,31 @@ pub trait Output {
         results: &HashMap<String, Vec<Extraction>>,
         filename: Option<&str>,
     ) -> io::Result<()>;
+
+    /// Report an input whose record a `--require` rule rejected.
+    ///
+    /// Writes nothing by default; NDJSON logs a line per rejection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an IO error if writing fails.
+    fn format_rejected(
+        &self,
+        _writer: &mut dyn Write,
+        _reason: &str,
+        _filename: Option<&str>,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Finish the output after the last input.
+    ///
+    /// Writes nothing by default; the summary format prints its totals here.
+    ///
+    /// # Errors
+    ///
+    /// Returns an IO error if writing fails.
+    fn finish(&self, _writer: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
 }
@@ -0,0 +1,160 @@
+//! NDJSON (JSON Lines) output formatter.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use serde_json::{Map, Value, json};
+
+use super::{Extraction, Output};
+
+/// NDJSON output formatter.
+///
+/// Writes one compact JSON object per line: one per extraction for a single selector,
+/// one per input for named selectors. Inputs rejected by `--require` get a
+/// `{"rejected": reason}` line instead. With a filename, each object gets a `"file"` key.
+pub struct NdjsonOutput;
+
+impl NdjsonOutput {
+    fn write_line(
+        writer: &mut dyn Write,
+        mut object: Map<String, Value>,
+        filename: Option<&str>,
+    ) -> io::Result<()> {
+        if let Some(name) = filename {
+            object.insert("file".into(), json!(name));
+        }
+        serde_json::to_writer(&mut *writer, &object).map_err(io::Error::other)?;
+        writeln!(writer)
+    }
+}
+
+impl Output for NdjsonOutput {
+    fn format_single(
+        &self,
+        writer: &mut dyn Write,
+        results: &[Extraction],
+        filename: Option<&str>,
+    ) -> io::Result<()> {
+        for result in results {
+            let Value::Object(object) = json!(result) else {
+                unreachable!("Extraction serializes to an object");
+            };
+            Self::write_line(writer, object, filename)?;
+        }
+        Ok(())
+    }
+
+    fn format_named(
+        &self,
+        writer: &mut dyn Write,
+        results: &HashMap<String, Vec<Extraction>>,
+        filename: Option<&str>,
+    ) -> io::Result<()> {
+        let object = results
+            .iter()
+            .map(|(name, extractions)| {
+                let values: Vec<Value> = extractions
+                    .iter()
+                    .map(|e| if e.provenance.is_some() { json!(e) } else { json!(e.text) })
+                    .collect();
+                (name.clone(), Value::Array(values))
+            })
+            .collect();
+
+        Self::write_line(writer, object, filename)
+    }
+
+    fn format_rejected(
+        &self,
+        writer: &mut dyn Write,
+        reason: &str,
+        filename: Option<&str>,
+    ) -> io::Result<()> {
+        let mut object = Map::new();
+        object.insert("rejected".into(), json!(reason));
+        Self::write_line(writer, object, filename)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_single_one_object_per_line() {
+        let results = vec![
+            Extraction {
+                text: "Hello".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
+            Extraction {
+                text: "World".into(),
+                attrs: None,
+                html: Some("<p>World</p>".into()),
+                inner_html: None,
+                provenance: None,
+            },
+        ];
+
+        let mut buf = Vec::new();
+        NdjsonOutput.format_single(&mut buf, &results, Some("a.html")).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "{\"file\":\"a.html\",\"text\":\"Hello\"}\n{\"file\":\"a.html\",\"html\":\"<p>World</\
+             p>\",\"text\":\"World\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_named_one_object_per_input() {
+        let mut results = HashMap::new();
+        results.insert(
+            "links".into(),
+            vec![
+                Extraction {
+                    text: "A".into(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: None,
+                },
+                Extraction {
+                    text: "B".into(),
+                    attrs: None,
+                    html: None,
+                    inner_html: None,
+                    provenance: None,
+                },
+            ],
+        );
+        results.insert("title".into(), vec![]);
+
+        let mut buf = Vec::new();
+        NdjsonOutput.format_named(&mut buf, &results, None).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "{\"links\":[\"A\",\"B\"],\"title\":[]}\n");
+    }
+
+    #[test]
+    fn test_format_rejected() {
+        let mut buf = Vec::new();
+        NdjsonOutput
+            .format_rejected(&mut buf, "missing required field `a`", Some("x.html"))
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "{\"file\":\"x.html\",\"rejected\":\"missing required field `a`\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_single_empty() {
+        let mut buf = Vec::new();
+        NdjsonOutput.format_single(&mut buf, &[], None).unwrap();
+        assert!(buf.is_empty());
+    }
+}
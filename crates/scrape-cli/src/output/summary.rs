@@ -0,0 +1,118 @@
+//! Selector coverage summary formatter.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+};
+
+use super::{Extraction, Output};
+
+/// Coverage summary formatter.
+///
+/// Writes nothing per input. After the last input, prints for each selector the share of
+/// inputs it matched on, so a selector that broke on part of a corpus stands out.
+pub struct SummaryOutput {
+    /// Name shown for the positional selector.
+    label: String,
+    inputs: Cell<usize>,
+    matched: RefCell<BTreeMap<String, usize>>,
+}
+
+impl SummaryOutput {
+    /// Creates a summary that shows the positional selector as `label`.
+    #[must_use]
+    pub fn new(label: impl Into<String>) -> Self {
+        Self { label: label.into(), inputs: Cell::new(0), matched: RefCell::default() }
+    }
+
+    fn record<'a>(&self, names: impl IntoIterator<Item = (&'a str, bool)>) {
+        self.inputs.set(self.inputs.get() + 1);
+        let mut matched = self.matched.borrow_mut();
+        for (name, found) in names {
+            let count = matched.entry(name.to_string()).or_default();
+            *count += usize::from(found);
+        }
+    }
+}
+
+impl Output for SummaryOutput {
+    fn format_single(
+        &self,
+        _writer: &mut dyn Write,
+        results: &[Extraction],
+        _filename: Option<&str>,
+    ) -> io::Result<()> {
+        self.record([(self.label.as_str(), !results.is_empty())]);
+        Ok(())
+    }
+
+    fn format_named(
+        &self,
+        _writer: &mut dyn Write,
+        results: &HashMap<String, Vec<Extraction>>,
+        _filename: Option<&str>,
+    ) -> io::Result<()> {
+        self.record(results.iter().map(|(name, values)| (name.as_str(), !values.is_empty())));
+        Ok(())
+    }
+
+    fn finish(&self, writer: &mut dyn Write) -> io::Result<()> {
+        let inputs = self.inputs.get();
+        let matched = self.matched.borrow();
+        let width = matched.keys().map(|name| name.chars().count()).max().unwrap_or(0);
+        for (name, &count) in &*matched {
+            let percent = count * 100 / inputs;
+            writeln!(writer, "{name:<width$}  {percent:>3}%  ({count}/{inputs} inputs)")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extraction(text: &str) -> Extraction {
+        Extraction {
+            text: text.into(),
+            attrs: None,
+            html: None,
+            inner_html: None,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_summary_named() {
+        let output = SummaryOutput::new("");
+        let mut buf = Vec::new();
+        for price in [Some("4"), None, Some("9")] {
+            let mut results = HashMap::new();
+            results.insert("title".to_string(), vec![extraction("Tea")]);
+            results.insert("price".to_string(), price.into_iter().map(extraction).collect());
+            output.format_named(&mut buf, &results, Some("page.html")).unwrap();
+        }
+        assert!(buf.is_empty());
+
+        output.finish(&mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "price   66%  (2/3 inputs)\ntitle  100%  (3/3 inputs)\n"
+        );
+    }
+
+    #[test]
+    fn test_summary_single() {
+        let output = SummaryOutput::new("h1");
+        let mut buf = Vec::new();
+        output.format_single(&mut buf, &[], None).unwrap();
+        output.format_single(&mut buf, &[extraction("A"), extraction("B")], None).unwrap();
+        output.finish(&mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "h1   50%  (1/2 inputs)\n");
+
+        let mut buf = Vec::new();
+        SummaryOutput::new("h1").finish(&mut buf).unwrap();
+        assert!(buf.is_empty());
+    }
+}
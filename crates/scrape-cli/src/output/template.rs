@@ -0,0 +1,240 @@
+//! Template output formatter.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    mem,
+};
+
+use super::{Extraction, Output};
+
+/// A piece of a [`Template`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Text,
+    Html,
+    InnerHtml,
+    Attr(String),
+    Name,
+    Filename,
+    Index,
+}
+
+/// A line format from `--template`, with `{field}` placeholders.
+///
+/// Fields are `{text}`, `{html}` (or `{outer_html}`), `{inner_html}`, `{attr:NAME}`,
+/// `{name}`, `{filename}`, and `{index}`. `{{` and `}}` stand for literal braces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// Parses a template.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an unknown field or an unmatched brace.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = source;
+        while let Some(i) = rest.find(['{', '}']) {
+            literal.push_str(&rest[..i]);
+            let brace = &rest[i..=i];
+            rest = &rest[i + 1..];
+            if let Some(after) = rest.strip_prefix(brace) {
+                literal.push_str(brace);
+                rest = after;
+                continue;
+            }
+            if brace == "}" {
+                return Err("unmatched `}` in template; write `}}` for a literal brace".into());
+            }
+            let end = rest.find('}').ok_or("unclosed `{` in template")?;
+            let part = match &rest[..end] {
+                "text" => Part::Text,
+                "html" | "outer_html" => Part::Html,
+                "inner_html" => Part::InnerHtml,
+                "name" => Part::Name,
+                "filename" => Part::Filename,
+                "index" => Part::Index,
+                field => match field.strip_prefix("attr:") {
+                    Some(attr) if !attr.is_empty() => Part::Attr(attr.to_ascii_lowercase()),
+                    _ => return Err(format!("unknown template field `{{{field}}}`")),
+                },
+            };
+            if !literal.is_empty() {
+                parts.push(Part::Literal(mem::take(&mut literal)));
+            }
+            parts.push(part);
+            rest = &rest[end + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Whether the template uses the matched element's HTML or attributes.
+    pub fn needs_element(&self) -> bool {
+        self.parts.iter().any(|part| matches!(part, Part::Html | Part::InnerHtml | Part::Attr(_)))
+    }
+
+    /// Whether the template uses the matched element's inner HTML.
+    pub fn needs_inner_html(&self) -> bool {
+        self.parts.contains(&Part::InnerHtml)
+    }
+
+    /// Writes the template for one extraction, without a delimiter.
+    ///
+    /// Fields the extraction doesn't have, like `{name}` for a single selector, are
+    /// left empty.
+    fn render(
+        &self,
+        writer: &mut dyn Write,
+        extraction: &Extraction,
+        name: Option<&str>,
+        filename: Option<&str>,
+        index: usize,
+    ) -> io::Result<()> {
+        for part in &self.parts {
+            let value = match part {
+                Part::Literal(text) => text,
+                Part::Text => &extraction.text,
+                Part::Html => extraction.html.as_deref().unwrap_or_default(),
+                Part::InnerHtml => extraction.inner_html.as_deref().unwrap_or_default(),
+                Part::Attr(attr) => {
+                    extraction.attrs.as_ref().and_then(|attrs| attrs.get(attr)).unwrap_or_default()
+                }
+                Part::Name => name.unwrap_or_default(),
+                Part::Filename => filename.unwrap_or_default(),
+                Part::Index => {
+                    write!(writer, "{index}")?;
+                    continue;
+                }
+            };
+            writer.write_all(value.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// Template output formatter.
+///
+/// Writes one line per extraction, laid out by a [`Template`]. `{index}` counts the
+/// extractions of each input, or of each selector name, from 0. `{filename}` is empty
+/// for stdin.
+pub struct TemplateOutput {
+    /// The line format.
+    pub template: Template,
+    /// Line delimiter (newline or NUL).
+    pub delimiter: u8,
+}
+
+impl Output for TemplateOutput {
+    fn format_single(
+        &self,
+        writer: &mut dyn Write,
+        results: &[Extraction],
+        filename: Option<&str>,
+    ) -> io::Result<()> {
+        for (index, result) in results.iter().enumerate() {
+            self.template.render(writer, result, None, filename, index)?;
+            writer.write_all(&[self.delimiter])?;
+        }
+        Ok(())
+    }
+
+    fn format_named(
+        &self,
+        writer: &mut dyn Write,
+        results: &HashMap<String, Vec<Extraction>>,
+        filename: Option<&str>,
+    ) -> io::Result<()> {
+        let mut keys: Vec<_> = results.keys().collect();
+        keys.sort();
+
+        for name in keys {
+            for (index, extraction) in results[name].iter().enumerate() {
+                self.template.render(writer, extraction, Some(name), filename, index)?;
+                writer.write_all(&[self.delimiter])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scrape_core::Attributes;
+
+    use super::*;
+
+    fn link(text: &str, href: &str) -> Extraction {
+        Extraction {
+            text: text.into(),
+            attrs: Some(Attributes::from([("href", href)])),
+            html: Some(format!("<a href=\"{href}\"><b>{text}</b></a>")),
+            inner_html: Some(format!("<b>{text}</b>")),
+            provenance: None,
+        }
+    }
+
+    fn render(template: &str, results: &[Extraction], filename: Option<&str>) -> String {
+        let output =
+            TemplateOutput { template: Template::parse(template).unwrap(), delimiter: b'\n' };
+        let mut buf = Vec::new();
+        output.format_single(&mut buf, results, filename).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{text} {{literal}}").is_ok());
+        assert_eq!(Template::parse("{txt}").unwrap_err(), "unknown template field `{txt}`");
+        assert!(Template::parse("{attr:}").is_err());
+        assert!(Template::parse("{text").is_err());
+        assert!(Template::parse("text}").is_err());
+    }
+
+    #[test]
+    fn test_needs_element() {
+        assert!(!Template::parse("{index}: {text} ({filename})").unwrap().needs_element());
+        assert!(Template::parse("{attr:href}").unwrap().needs_element());
+        assert!(!Template::parse("{html}").unwrap().needs_inner_html());
+        assert!(Template::parse("{inner_html}").unwrap().needs_inner_html());
+    }
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    fn test_format_single() {
+        let results = [link("One", "/1"), link("Two", "/2")];
+        assert_eq!(
+            render("{index}. {text} ({attr:HREF}){attr:title}", &results, None),
+            "0. One (/1)\n1. Two (/2)\n"
+        );
+        assert_eq!(
+            render("{filename}\t{inner_html}\t{outer_html}", &results[..1], Some("a.html")),
+            "a.html\t<b>One</b>\t<a href=\"/1\"><b>One</b></a>\n"
+        );
+        assert_eq!(render("{{{text}}}", &results[..1], None), "{One}\n");
+    }
+
+    #[test]
+    fn test_format_named() {
+        let output = TemplateOutput {
+            template: Template::parse("{name}={text}").unwrap(),
+            delimiter: b'\0',
+        };
+        let mut results = HashMap::new();
+        results.insert("title".into(), vec![link("Hello", "/")]);
+        results.insert("author".into(), vec![link("Ann", "/ann"), link("Bo", "/bo")]);
+
+        let mut buf = Vec::new();
+        output.format_named(&mut buf, &results, None).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "author=Ann\0author=Bo\0title=Hello\0");
+    }
+}
@@ -74,8 +74,20 @@ mod tests {
     fn test_format_single() {
         let output = TextOutput { delimiter: b'\n', color: false };
         let results = vec![
-            Extraction { text: "Hello".into(), attrs: None, html: None },
-            Extraction { text: "World".into(), attrs: None, html: None },
+            Extraction {
+                text: "Hello".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
+            Extraction {
+                text: "World".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
         ];
 
         let mut buf = Vec::new();
@@ -86,7 +98,13 @@ mod tests {
     #[test]
     fn test_format_single_with_filename() {
         let output = TextOutput { delimiter: b'\n', color: false };
-        let results = vec![Extraction { text: "Hello".into(), attrs: None, html: None }];
+        let results = vec![Extraction {
+            text: "Hello".into(),
+            attrs: None,
+            html: None,
+            inner_html: None,
+            provenance: None,
+        }];
 
         let mut buf = Vec::new();
         output.format_single(&mut buf, &results, Some("test.html")).unwrap();
@@ -97,8 +115,20 @@ mod tests {
     fn test_format_single_null_delimiter() {
         let output = TextOutput { delimiter: b'\0', color: false };
         let results = vec![
-            Extraction { text: "A".into(), attrs: None, html: None },
-            Extraction { text: "B".into(), attrs: None, html: None },
+            Extraction {
+                text: "A".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
+            Extraction {
+                text: "B".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            },
         ];
 
         let mut buf = Vec::new();
@@ -112,7 +142,13 @@ mod tests {
         let mut results = HashMap::new();
         results.insert(
             "title".into(),
-            vec![Extraction { text: "Hello".into(), attrs: None, html: None }],
+            vec![Extraction {
+                text: "Hello".into(),
+                attrs: None,
+                html: None,
+                inner_html: None,
+                provenance: None,
+            }],
         );
 
         let mut buf = Vec::new();
@@ -0,0 +1,136 @@
+//! Rendering JavaScript-driven pages in headless Chromium.
+
+use std::{fmt, sync::OnceLock, time::Duration};
+
+use chromiumoxide::{
+    Browser, BrowserConfig, Page, cdp::browser_protocol::page::EventLifecycleEvent,
+};
+use futures_util::StreamExt;
+use tokio::{runtime::Runtime, task::JoinHandle, time};
+
+use crate::fetch::FetchError;
+
+/// How often the page is checked for the `--wait-for` selector.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What to wait for after a page has loaded before its HTML is taken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitFor {
+    /// No network connections for 500 ms.
+    NetworkIdle,
+    /// An element matching a CSS selector.
+    Selector(String),
+}
+
+/// Loads pages in headless Chromium and returns their HTML after scripts have run.
+///
+/// The browser is started on the first render and shared by all later ones, with a
+/// tab per page. Chromium is found on the `PATH` and in the usual install locations,
+/// or taken from the `CHROME` environment variable.
+pub struct Renderer {
+    wait_for: WaitFor,
+    timeout: Duration,
+    launched: OnceLock<Result<Launched, FetchError>>,
+}
+
+/// A running browser with the runtime that drives it.
+struct Launched {
+    runtime: Runtime,
+    browser: Browser,
+    events: JoinHandle<()>,
+}
+
+impl Renderer {
+    /// Creates a renderer that gives each page `timeout` to load and settle.
+    #[must_use]
+    pub const fn new(wait_for: WaitFor, timeout: Duration) -> Self {
+        Self { wait_for, timeout, launched: OnceLock::new() }
+    }
+
+    /// Loads `url` and returns the HTML of the page once it has settled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FetchError::Timeout`] if the page does not settle in time, or
+    /// [`FetchError::Http`] if the browser cannot be started or fails to load the page.
+    pub fn render(&self, url: &str) -> Result<String, FetchError> {
+        let Launched { runtime, browser, .. } =
+            self.launched.get_or_init(|| self.launch()).as_ref().map_err(Clone::clone)?;
+        runtime.block_on(async {
+            let page = browser.new_page("about:blank").await.map_err(browser_error)?;
+            let html = time::timeout(self.timeout, self.load(&page, url))
+                .await
+                .unwrap_or(Err(FetchError::Timeout(self.timeout)));
+            page.close().await.ok();
+            html
+        })
+    }
+
+    fn launch(&self) -> Result<Launched, FetchError> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| FetchError::Io(e.to_string()))?;
+        let (browser, events) = runtime.block_on(async {
+            let config = BrowserConfig::builder()
+                .request_timeout(self.timeout)
+                .build()
+                .map_err(browser_error)?;
+            let (browser, mut handler) = Browser::launch(config).await.map_err(browser_error)?;
+            let events = tokio::spawn(async move { while handler.next().await.is_some() {} });
+            Ok::<_, FetchError>((browser, events))
+        })?;
+        Ok(Launched { runtime, browser, events })
+    }
+
+    async fn load(&self, page: &Page, url: &str) -> Result<String, FetchError> {
+        let mut lifecycle =
+            page.event_listener::<EventLifecycleEvent>().await.map_err(browser_error)?;
+        page.goto(url).await.map_err(browser_error)?;
+
+        match &self.wait_for {
+            WaitFor::NetworkIdle => {
+                // Skip the events still queued from about:blank.
+                let mut navigated = false;
+                while let Some(event) = lifecycle.next().await {
+                    navigated |= event.name == "init";
+                    if navigated && event.name == "networkIdle" {
+                        break;
+                    }
+                }
+            }
+            WaitFor::Selector(selector) => {
+                while page.find_element(selector.as_str()).await.is_err() {
+                    time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+        page.content().await.map_err(browser_error)
+    }
+}
+
+impl fmt::Debug for Renderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Renderer")
+            .field("wait_for", &self.wait_for)
+            .field("timeout", &self.timeout)
+            .field("launched", &matches!(self.launched.get(), Some(Ok(_))))
+            .finish()
+    }
+}
+
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        if let Some(Ok(Launched { runtime, browser, events })) = self.launched.get_mut() {
+            runtime.block_on(async {
+                browser.close().await.ok();
+                browser.wait().await.ok();
+            });
+            events.abort();
+        }
+    }
+}
+
+fn browser_error(error: impl fmt::Display) -> FetchError {
+    FetchError::Http(format!("browser: {error}"))
+}
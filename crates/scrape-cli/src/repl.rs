@@ -2,7 +2,11 @@
 
 use std::io::{self, BufRead, Write};
 
-use scrape_core::{Soup, query::explain};
+use is_terminal::IsTerminal;
+use scrape_core::{
+    FormatOptions, Soup, SoupConfig,
+    query::{explain, explain_with_document},
+};
 
 /// REPL state.
 pub struct Repl {
@@ -20,7 +24,8 @@ impl Repl {
 
     /// Loads HTML into the REPL.
     pub fn load(&mut self, html: &str) {
-        self.soup = Some(Soup::parse(html));
+        let config = SoupConfig::builder().enable_query_cache(true).build();
+        self.soup = Some(Soup::parse_with_config(html, config));
         self.source = Some(html.to_string());
         println!("Loaded {} bytes of HTML", html.len());
     }
@@ -79,7 +84,7 @@ impl Repl {
             ":url" => self.cmd_url(arg),
             ":explain" => self.cmd_explain(arg),
             ":count" => self.cmd_count(arg),
-            ":tree" => self.cmd_tree(),
+            ":tree" => self.cmd_tree(arg),
             _ => println!("Unknown command: {cmd}. Type :help for available commands."),
         }
         true
@@ -91,7 +96,7 @@ impl Repl {
         println!("  :url <url>        Fetch and load HTML from URL");
         println!("  :explain <sel>    Explain a CSS selector");
         println!("  :count <sel>      Count matches for selector");
-        println!("  :tree             Show DOM tree structure");
+        println!("  :tree [depth]     Show DOM tree structure");
         println!("  :history          Show command history");
         println!("  :help, :h         Show this help");
         println!("  :quit, :q         Exit");
@@ -142,7 +147,11 @@ impl Repl {
             println!("Usage: :explain <selector>");
             return;
         }
-        match explain(selector) {
+        let result = self.soup.as_ref().map_or_else(
+            || explain(selector),
+            |soup| explain_with_document(selector, soup.document()),
+        );
+        match result {
             Ok(explanation) => println!("{}", explanation.format()),
             Err(e) => println!("Error: {e}"),
         }
@@ -163,14 +172,26 @@ impl Repl {
         }
     }
 
-    fn cmd_tree(&self) {
-        let Some(_soup) = &self.soup else {
+    fn cmd_tree(&self, depth: &str) {
+        let Some(soup) = &self.soup else {
             println!("No HTML loaded. Use :load or :url first.");
             return;
         };
-        // Simplified DOM tree display
-        println!("DOM tree (feature not yet fully implemented):");
-        println!("Use CSS selectors to explore the structure instead.");
+        let max_depth = if depth.is_empty() {
+            None
+        } else if let Ok(depth) = depth.parse() {
+            Some(depth)
+        } else {
+            println!("Usage: :tree [depth]");
+            return;
+        };
+
+        let options = FormatOptions {
+            max_depth,
+            color: io::stdout().is_terminal(),
+            ..FormatOptions::default()
+        };
+        print!("{}", soup.document().format_tree(options));
     }
 
     fn execute_selector(&self, selector: &str) {
@@ -0,0 +1,419 @@
+//! `--schema` extraction of structured records described by a JSON schema file.
+//!
+//! The file maps field names to CSS selectors, either as a `selector` or
+//! `selector@attribute` string like `--select`, or as an object:
+//!
+//! ```json
+//! {
+//!   "title": "h1",
+//!   "image": "meta[property='og:image']@content",
+//!   "price": { "selector": ".price", "transform": "price", "required": true },
+//!   "items": {
+//!     "selector": "li.item",
+//!     "many": true,
+//!     "fields": { "name": ".name", "url": "a@href" }
+//!   }
+//! }
+//! ```
+//!
+//! Objects take a `selector` and at most one of `attr`, `html` (`"outer"` or `"inner"`),
+//! and `fields`, which reads a nested record from each match. `many` collects every
+//! match, `transform` is one of `trim`, `normalize_space`, `lowercase`, `uppercase`,
+//! `number`, `price`, and `date`, and `required` rejects records in which the field
+//! matches nothing. Records keep the field order of the file.
+
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{self, Read, Write},
+    num::NonZero,
+    path::Path,
+};
+
+use anyhow::{Context, Result, bail};
+use scrape_core::{
+    extract::{Field, Schema, Transform, Value},
+    pipeline::{self, PipelineError, PipelineOptions, PipelineRecord},
+};
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{MapAccess, Visitor},
+};
+
+use crate::{
+    args::{Args, OutputFormat},
+    batch::{Input, RejectionSummary},
+    fetch::Fetcher,
+};
+
+/// The record extracted from one input.
+#[derive(Debug, Clone)]
+pub struct Record {
+    /// The extracted fields; `Null` if the record was rejected.
+    pub data: Value,
+    /// Why a `required` field rejected the record.
+    pub rejection: Option<String>,
+}
+
+/// Named fields in the order of the schema file.
+struct Fields(Vec<(String, FieldSpec)>);
+
+impl<'de> Deserialize<'de> for Fields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldsVisitor;
+
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = Fields;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an object of named fields")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Fields, A::Error> {
+                let mut fields = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry()? {
+                    fields.push(entry);
+                }
+                Ok(Fields(fields))
+            }
+        }
+
+        deserializer.deserialize_map(FieldsVisitor)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a selector string or a field object")]
+enum FieldSpec {
+    Selector(String),
+    Object(FieldObject),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldObject {
+    selector: String,
+    #[serde(default)]
+    attr: Option<String>,
+    #[serde(default)]
+    html: Option<HtmlSource>,
+    #[serde(default)]
+    fields: Option<Fields>,
+    #[serde(default)]
+    many: bool,
+    #[serde(default)]
+    transform: Option<TransformName>,
+    #[serde(default)]
+    required: bool,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum HtmlSource {
+    Outer,
+    Inner,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TransformName {
+    Trim,
+    NormalizeSpace,
+    Lowercase,
+    Uppercase,
+    Number,
+    Price,
+    Date,
+}
+
+impl From<TransformName> for Transform {
+    fn from(name: TransformName) -> Self {
+        match name {
+            TransformName::Trim => Self::Trim,
+            TransformName::NormalizeSpace => Self::NormalizeSpace,
+            TransformName::Lowercase => Self::Lowercase,
+            TransformName::Uppercase => Self::Uppercase,
+            TransformName::Number => Self::Number,
+            TransformName::Price => Self::Price,
+            TransformName::Date => Self::Date,
+        }
+    }
+}
+
+/// Reads and compiles the schema file at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, is not a valid schema, or contains an
+/// invalid selector.
+pub fn load(path: &Path) -> Result<Schema> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("Cannot read schema {}", path.display()))?;
+    parse_schema(&json).with_context(|| format!("Invalid schema {}", path.display()))
+}
+
+/// Compiles a schema from the JSON text of a schema file.
+///
+/// # Errors
+///
+/// Returns an error if `json` is not a valid schema or contains an invalid selector.
+pub fn parse_schema(json: &str) -> Result<Schema> {
+    build(serde_json::from_str(json)?)
+}
+
+fn build(fields: Fields) -> Result<Schema> {
+    let mut schema = Schema::new();
+    for (name, spec) in fields.0 {
+        let (field, required) =
+            build_field(spec).with_context(|| format!("Invalid field '{name}'"))?;
+        schema = schema.field(name.as_str(), field);
+        if required {
+            schema = schema.require(name);
+        }
+    }
+    Ok(schema)
+}
+
+/// Compiles one field, returning whether it is required.
+fn build_field(spec: FieldSpec) -> Result<(Field, bool)> {
+    let spec = match spec {
+        FieldSpec::Selector(spec) => return Ok((Field::parse(&spec)?, false)),
+        FieldSpec::Object(spec) => spec,
+    };
+    let selector = spec.selector.as_str();
+    let mut field = match (spec.fields, spec.attr, spec.html) {
+        (Some(_), _, _) if spec.transform.is_some() => {
+            bail!("`transform` does not apply to `fields`")
+        }
+        (Some(fields), None, None) => Field::group(selector, build(fields)?)?,
+        (None, Some(attr), None) => Field::attr(selector, &attr)?,
+        (None, None, Some(HtmlSource::Outer)) => Field::html(selector)?,
+        (None, None, Some(HtmlSource::Inner)) => Field::inner_html(selector)?,
+        (None, None, None) => Field::text(selector)?,
+        _ => bail!("use only one of `attr`, `html`, and `fields`"),
+    };
+    if let Some(transform) = spec.transform {
+        field = field.transform(transform.into());
+    }
+    if spec.many {
+        field = field.many();
+    }
+    Ok((field, spec.required))
+}
+
+/// Extracts the record of every input with the core pipeline and prints it.
+///
+/// Returns `true` if any accepted record has a value.
+///
+/// # Errors
+///
+/// Returns an error if the schema cannot be loaded, stdin or output cannot be read or
+/// written, or stdin fails the parse check.
+pub fn run(
+    args: &Args,
+    path: &Path,
+    inputs: &[Input],
+    fetcher: &Fetcher,
+    writer: &mut dyn Write,
+) -> Result<bool> {
+    let schema = load(path)?;
+    let check = args.parse_check();
+
+    let mut stdin = String::new();
+    let names: Vec<String> = if inputs.is_empty() {
+        io::stdin().read_to_string(&mut stdin)?;
+        check.run("-", &stdin)?;
+        vec!["-".to_string()]
+    } else {
+        inputs.iter().map(Input::name).collect()
+    };
+    let by_name: HashMap<String, &Input> =
+        inputs.iter().map(|input| (input.name(), input)).collect();
+    let read = |name: &str| -> Result<String> {
+        let Some(input) = by_name.get(name) else { return Ok(stdin.clone()) };
+        let html = input.read(fetcher)?;
+        check.run(name, &html)?;
+        Ok(html)
+    };
+
+    let concurrency = args.parallel.unwrap_or_else(|| {
+        let cores = std::thread::available_parallelism().map_or(1, NonZero::get);
+        cores.max(fetcher.concurrency())
+    });
+    let options = PipelineOptions { concurrency, ..PipelineOptions::default() };
+    let mut results = Vec::with_capacity(names.len());
+    let mut sink = |record: PipelineRecord| results.push(record);
+    pipeline::run(names, &schema, &read, &options, &mut sink)?;
+    results.sort_by_key(|record| record.index);
+
+    let mut found = Vec::with_capacity(results.len());
+    let mut rejections = RejectionSummary::default();
+    for PipelineRecord { url: input, result, .. } in results {
+        match record(result) {
+            Ok(record) => {
+                rejections.record(record.rejection.as_deref());
+                found.push((input, record));
+            }
+            Err(message) if !args.quiet => eprintln!("{input}: {message}"),
+            Err(_) => {}
+        }
+    }
+
+    let any = found.iter().any(|(_, record)| has_value(&record.data));
+    write_records(writer, &found, args.output, args.pretty)?;
+    if !args.quiet {
+        rejections.write(&mut io::stderr())?;
+    }
+    Ok(any)
+}
+
+/// Turns a pipeline result into a record, or the message of a failed read.
+fn record(
+    result: std::result::Result<Value, PipelineError>,
+) -> std::result::Result<Record, String> {
+    match result {
+        Ok(data) => Ok(Record { data, rejection: None }),
+        Err(PipelineError::Rejected(reason)) => {
+            Ok(Record { data: Value::Null, rejection: Some(reason) })
+        }
+        Err(PipelineError::Fetch(message)) => Err(message),
+    }
+}
+
+/// Returns `true` if `value` holds anything but nulls and empty lists.
+fn has_value(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::List(items) => items.iter().any(has_value),
+        Value::Object(fields) => fields.iter().any(|(_, value)| has_value(value)),
+        _ => true,
+    }
+}
+
+#[derive(Serialize)]
+struct RecordObject<'a> {
+    input: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<&'a Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rejected: Option<&'a str>,
+}
+
+/// Writes `{"input", "data"}` objects: one array for all inputs, or one object per line
+/// with NDJSON. NDJSON also logs rejected records as `{"input", "rejected"}` lines; JSON
+/// leaves them out.
+fn write_records(
+    writer: &mut dyn Write,
+    found: &[(String, Record)],
+    format: OutputFormat,
+    pretty: bool,
+) -> io::Result<()> {
+    let objects = found.iter().map(|(input, record)| {
+        let rejected = record.rejection.as_deref();
+        RecordObject { input, data: rejected.is_none().then_some(&record.data), rejected }
+    });
+
+    if format == OutputFormat::Ndjson {
+        for object in objects {
+            serde_json::to_writer(&mut *writer, &object).map_err(io::Error::other)?;
+            writeln!(writer)?;
+        }
+        return Ok(());
+    }
+
+    let objects: Vec<_> = objects.filter(|object| object.rejected.is_none()).collect();
+    let output = if pretty {
+        serde_json::to_string_pretty(&objects)
+    } else {
+        serde_json::to_string(&objects)
+    }
+    .map_err(io::Error::other)?;
+    writeln!(writer, "{output}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"{
+        "title": "h1",
+        "image": "img@src",
+        "price": { "selector": ".price", "transform": "number", "required": true },
+        "items": {
+            "selector": "li",
+            "many": true,
+            "fields": { "name": "b", "url": { "selector": "a", "attr": "href" } }
+        }
+    }"#;
+
+    const HTML: &str = "<h1>Tea</h1><img src=t.png><span \
+                        class=price>4.50</span><ul><li><b>Green</b><a \
+                        href=/g>x</a></li><li><b>Black</b></li></ul>";
+
+    fn extract_record(html: &str, schema: &Schema) -> Record {
+        record(pipeline::extract_page(html, schema)).unwrap()
+    }
+
+    fn to_json(value: &Value) -> String {
+        serde_json::to_string(value).unwrap()
+    }
+
+    #[test]
+    fn test_schema_file_extracts_nested_records() {
+        let record = extract_record(HTML, &parse_schema(SCHEMA).unwrap());
+        assert!(record.rejection.is_none());
+        assert_eq!(
+            to_json(&record.data),
+            concat!(
+                r#"{"title":"Tea","image":"t.png","price":4.5,"items":"#,
+                r#"[{"name":"Green","url":"/g"},{"name":"Black","url":null}]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_schema_file_required_field() {
+        let record = extract_record("<h1>Tea</h1>", &parse_schema(SCHEMA).unwrap());
+        assert!(record.data.is_null());
+        assert_eq!(record.rejection.as_deref(), Some("missing required field `price`"));
+        assert!(!has_value(&record.data));
+    }
+
+    #[test]
+    fn test_schema_file_errors() {
+        for (json, message) in [
+            (r#"["h1"]"#, "an object of named fields"),
+            (r#"{"a": {"selector": "a", "attr": "href", "html": "outer"}}"#, "only one of"),
+            (r#"{"a": {"selector": "a", "fields": {}, "transform": "trim"}}"#, "`transform`"),
+            (r#"{"a": "div["}"#, "Invalid field 'a'"),
+            (r#"{"a": {"selector": "a", "atr": "href"}}"#, "a selector string or a field"),
+        ] {
+            let err = format!("{:#}", parse_schema(json).unwrap_err());
+            assert!(err.contains(message), "{json}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_write_records() {
+        let schema = parse_schema(r#"{"title": {"selector": "h1", "required": true}}"#).unwrap();
+        let found = vec![
+            ("a.html".to_string(), extract_record("<h1>Tea</h1>", &schema)),
+            ("b.html".to_string(), extract_record("<p>No title</p>", &schema)),
+        ];
+
+        let mut buf = Vec::new();
+        write_records(&mut buf, &found, OutputFormat::Ndjson, false).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "{\"input\":\"a.html\",\"data\":{\"title\":\"Tea\"}}\n{\"input\":\"b.html\",\"\
+             rejected\":\"missing required field `title`\"}\n"
+        );
+
+        let mut buf = Vec::new();
+        write_records(&mut buf, &found, OutputFormat::Json, false).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "[{\"input\":\"a.html\",\"data\":{\"title\":\"Tea\"}}]\n"
+        );
+    }
+}
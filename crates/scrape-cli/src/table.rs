@@ -0,0 +1,168 @@
+//! `--table` extraction of HTML tables as records.
+
+use std::io::{self, Read, Write};
+
+use anyhow::Result;
+use scrape_core::extract::Table;
+use serde_json::json;
+
+use crate::{
+    args::{Args, OutputFormat},
+    batch::{Input, process_tables},
+    extract::extract_tables,
+    fetch::Fetcher,
+};
+
+/// Extracts the tables matching `selector` from every input and prints them.
+///
+/// Returns `true` if any matched table has body rows.
+///
+/// # Errors
+///
+/// Returns an error if the selector is invalid for stdin input, or if output cannot
+/// be written.
+pub fn run(
+    args: &Args,
+    selector: &str,
+    inputs: &[Input],
+    fetcher: &Fetcher,
+    writer: &mut dyn Write,
+) -> Result<bool> {
+    let tables = if inputs.is_empty() {
+        let mut html = String::new();
+        io::stdin().read_to_string(&mut html)?;
+        extract_tables(&html, selector, args.first)?
+    } else {
+        let mut tables = Vec::new();
+        for file_result in process_tables(inputs, selector, args.first, fetcher) {
+            match file_result.result {
+                Ok(found) => tables.extend(found),
+                Err(e) if !args.quiet => eprintln!("{}: {e}", file_result.filename),
+                Err(_) => {}
+            }
+        }
+        tables
+    };
+
+    write_tables(writer, &tables, args.output, args.pretty)?;
+    Ok(tables.iter().any(|table| !table.is_empty()))
+}
+
+/// Writes tables as tab-separated or CSV rows under a header line or as Markdown pipe
+/// tables, with a blank line between tables, or as JSON records: one array for all
+/// tables, or one object per line.
+fn write_tables(
+    writer: &mut dyn Write,
+    tables: &[Table],
+    format: OutputFormat,
+    pretty: bool,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let records: Vec<_> = tables.iter().flat_map(Table::to_records).collect();
+            let output = if pretty {
+                serde_json::to_string_pretty(&records)
+            } else {
+                serde_json::to_string(&records)
+            }
+            .map_err(io::Error::other)?;
+            writeln!(writer, "{output}")
+        }
+        OutputFormat::Ndjson => {
+            for record in tables.iter().flat_map(Table::to_records) {
+                writeln!(writer, "{}", json!(record))?;
+            }
+            Ok(())
+        }
+        OutputFormat::Markdown => {
+            for (i, table) in tables.iter().enumerate() {
+                if i > 0 {
+                    writeln!(writer)?;
+                }
+                writeln!(writer, "{}", table.to_markdown())?;
+            }
+            Ok(())
+        }
+        OutputFormat::Csv
+        | OutputFormat::Text
+        | OutputFormat::Html
+        | OutputFormat::Summary
+        | OutputFormat::Template => {
+            // Cell text has collapsed whitespace, so tab-separated text needs no quoting.
+            let (delimiter, quoting) = if format == OutputFormat::Csv {
+                (b',', csv::QuoteStyle::Necessary)
+            } else {
+                (b'\t', csv::QuoteStyle::Never)
+            };
+            for (i, table) in tables.iter().enumerate() {
+                if i > 0 {
+                    writeln!(writer)?;
+                }
+                let mut wtr = csv::WriterBuilder::new()
+                    .delimiter(delimiter)
+                    .quote_style(quoting)
+                    .from_writer(&mut *writer);
+                wtr.write_record(table.record_keys())?;
+                for row in &table.rows {
+                    wtr.write_record(row)?;
+                }
+                wtr.flush()?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Table> {
+        vec![Table {
+            caption: None,
+            headers: vec!["Item".into(), "Price".into()],
+            rows: vec![vec!["Tea, green".into(), "3".into()]],
+        }]
+    }
+
+    #[test]
+    fn test_write_tables_csv() {
+        let mut buf = Vec::new();
+        write_tables(&mut buf, &sample(), OutputFormat::Csv, false).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "Item,Price\n\"Tea, green\",3\n");
+    }
+
+    #[test]
+    fn test_write_tables_text_separates_tables() {
+        let mut tables = sample();
+        tables.push(Table { caption: None, headers: vec![], rows: vec![vec!["x".into()]] });
+
+        let mut buf = Vec::new();
+        write_tables(&mut buf, &tables, OutputFormat::Text, false).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "Item\tPrice\nTea, green\t3\n\ncolumn_1\nx\n");
+    }
+
+    #[test]
+    fn test_write_tables_markdown() {
+        let mut buf = Vec::new();
+        write_tables(&mut buf, &sample(), OutputFormat::Markdown, false).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "| Item | Price |\n| --- | --- |\n| Tea, green | 3 |\n"
+        );
+    }
+
+    #[test]
+    fn test_write_tables_json() {
+        let mut buf = Vec::new();
+        write_tables(&mut buf, &sample(), OutputFormat::Json, false).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "[{\"Item\":\"Tea, green\",\"Price\":\"3\"}]\n"
+        );
+
+        let mut buf = Vec::new();
+        write_tables(&mut buf, &sample(), OutputFormat::Ndjson, false).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "{\"Item\":\"Tea, green\",\"Price\":\"3\"}\n");
+    }
+}
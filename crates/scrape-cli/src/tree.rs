@@ -0,0 +1,71 @@
+//! `scrape tree` subcommand.
+
+use std::io::{self, Read, Write};
+
+use anyhow::{Context, Result};
+use is_terminal::IsTerminal;
+use scrape_core::{FormatOptions, Soup};
+
+use crate::{
+    args::{ColorMode, TreeArgs},
+    fetch::{FetchConfig, fetch_url, is_url},
+};
+
+/// Prints the DOM tree of the input, or of each element matching `--selector`.
+///
+/// Returns `false` if the selector matched nothing.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read, the selector is invalid, or output
+/// cannot be written.
+pub fn run(args: &TreeArgs) -> Result<bool> {
+    let html = read_input(args.input.as_deref())?;
+    let soup = Soup::parse(&html);
+
+    let options = FormatOptions {
+        max_depth: args.depth,
+        color: match args.color {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => io::stdout().is_terminal(),
+        },
+        comments: args.comments,
+        ..FormatOptions::default()
+    };
+
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+
+    let Some(selector) = &args.selector else {
+        write!(writer, "{}", soup.document().format_tree(options))?;
+        writer.flush()?;
+        return Ok(true);
+    };
+
+    let tags = soup.select(selector).context("Invalid CSS selector")?;
+    for (i, tag) in tags.iter().enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        write!(writer, "{}", soup.document().format_subtree(tag.node_id(), options))?;
+    }
+    writer.flush()?;
+
+    Ok(!tags.is_empty())
+}
+
+/// Reads HTML from a URL, a file, or stdin when `input` is `None`.
+fn read_input(input: Option<&str>) -> Result<String> {
+    match input {
+        Some(url) if is_url(url) => Ok(fetch_url(url, &FetchConfig::default())?),
+        Some(path) => {
+            std::fs::read_to_string(path).with_context(|| format!("failed to read {path}"))
+        }
+        None => {
+            let mut html = String::new();
+            io::stdin().read_to_string(&mut html)?;
+            Ok(html)
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! `--watch` mode: re-runs the extraction whenever an input file changes.
+//!
+//! The directories holding the watched files are watched through the platform's file
+//! system notifications, with events debounced so an editor's save that touches a file
+//! several times triggers a single run. Watching directories rather than the files
+//! themselves also catches editors that save by replacing the file.
+//!
+//! The first run prints its output in full. Later runs print only the lines that
+//! changed, as a diff against the previous output: `-` for removed lines and `+` for
+//! added ones.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use notify_debouncer_mini::{
+    DebounceEventResult, DebouncedEvent, Debouncer, new_debouncer,
+    notify::{RecommendedWatcher, RecursiveMode},
+};
+
+use crate::{args::Args, batch::Input};
+
+/// How long a file must stay untouched before its changes are reported.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Watches a set of files for changes.
+pub struct Watcher {
+    files: BTreeSet<PathBuf>,
+    events: Receiver<DebounceEventResult>,
+    // Dropping the debouncer stops the notifications.
+    _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+impl Watcher {
+    /// Starts watching `paths`, which need not exist yet; their directories must.
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Result<Self> {
+        let files: BTreeSet<PathBuf> =
+            paths.into_iter().map(|path| absolute(&path)).collect::<Result<_>>()?;
+        let (sender, events) = mpsc::channel();
+        let mut debouncer = new_debouncer(DEBOUNCE, sender)?;
+        let dirs: BTreeSet<&Path> = files.iter().filter_map(|file| file.parent()).collect();
+        for dir in dirs {
+            debouncer
+                .watcher()
+                .watch(dir, RecursiveMode::NonRecursive)
+                .with_context(|| format!("cannot watch {}", dir.display()))?;
+        }
+        Ok(Self { files, events, _debouncer: debouncer })
+    }
+
+    /// Blocks until at least one watched file changes, and returns the changed files.
+    pub fn wait(&self) -> Result<Vec<PathBuf>> {
+        loop {
+            let events = self.events.recv().context("file watcher stopped")??;
+            let changed = changed_files(&events, &self.files);
+            if !changed.is_empty() {
+                return Ok(changed);
+            }
+        }
+    }
+}
+
+/// Returns the watched `files` that `events` report, in order and without duplicates.
+///
+/// Events for other files in the watched directories are ignored.
+fn changed_files(events: &[DebouncedEvent], files: &BTreeSet<PathBuf>) -> Vec<PathBuf> {
+    let mut changed = Vec::new();
+    for event in events {
+        if files.contains(&event.path) && !changed.contains(&event.path) {
+            changed.push(event.path.clone());
+        }
+    }
+    changed
+}
+
+/// Returns `path` with its directory made absolute and symlinks resolved, the way
+/// notification events name it.
+fn absolute(path: &Path) -> Result<PathBuf> {
+    let dir =
+        path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let dir = fs::canonicalize(dir).with_context(|| format!("cannot watch {}", path.display()))?;
+    Ok(path.file_name().map_or_else(|| dir.clone(), |name| dir.join(name)))
+}
+
+/// Runs `extract` once, then again every time an input file or the --schema file
+/// changes.
+///
+/// Each run after the first prints a diff of its output. Extraction errors are printed
+/// and watching continues; this returns only if the files cannot be watched.
+pub fn run(args: &Args, extract: impl Fn(&Args, &mut dyn Write) -> Result<bool>) -> Result<()> {
+    let mut paths: Vec<PathBuf> = args
+        .inputs()
+        .into_iter()
+        .filter_map(|input| match input {
+            Input::File(path) => Some(path),
+            Input::Url(_) => None,
+        })
+        .collect();
+    paths.extend(args.schema.iter().cloned());
+    let watcher = Watcher::new(paths)?;
+
+    let mut previous: Option<String> = None;
+    loop {
+        let mut buf = Vec::new();
+        if let Err(e) = extract(args, &mut buf)
+            && !args.quiet
+        {
+            eprintln!("Error: {e}");
+        }
+        let output = String::from_utf8_lossy(&buf).into_owned();
+
+        let mut stdout = io::stdout().lock();
+        match &previous {
+            None => stdout.write_all(output.as_bytes())?,
+            Some(previous) if *previous == output => {
+                if !args.quiet {
+                    eprintln!("[watch] output unchanged");
+                }
+            }
+            Some(previous) => write_diff(&mut stdout, previous, &output)?,
+        }
+        stdout.flush()?;
+        previous = Some(output);
+
+        let changed = watcher.wait()?;
+        if !args.quiet {
+            let names: Vec<_> = changed.iter().map(|path| path.display().to_string()).collect();
+            eprintln!("[watch] {} changed, re-running", names.join(", "));
+        }
+    }
+}
+
+/// Writes the lines removed from `old` with a `-` prefix and the lines added in `new`
+/// with a `+` prefix, in order; unchanged lines are left out.
+fn write_diff(writer: &mut dyn Write, old: &str, new: &str) -> io::Result<()> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            writeln!(writer, "-{}", old[i])?;
+            i += 1;
+        } else {
+            writeln!(writer, "+{}", new[j])?;
+            j += 1;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use notify_debouncer_mini::DebouncedEventKind;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> String {
+        let mut buf = Vec::new();
+        write_diff(&mut buf, old, new).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_write_diff() {
+        assert_eq!(diff("Green\nBlack\nOolong\n", "Green\nWhite\nOolong\n"), "-Black\n+White\n");
+        assert_eq!(diff("a\nb\n", "a\nb\nc\n"), "+c\n");
+        assert_eq!(diff("a\nb\n", ""), "-a\n-b\n");
+        assert_eq!(diff("same\n", "same\n"), "");
+    }
+
+    #[test]
+    fn test_changed_files_filters_and_dedups() {
+        let a = PathBuf::from("/site/a.html");
+        let b = PathBuf::from("/site/b.html");
+        let event = |path: &str| DebouncedEvent::new(path.into(), DebouncedEventKind::Any);
+        let events = [
+            event("/site/b.html"),
+            event("/site/.a.html.swp"),
+            event("/site/b.html"),
+            event("/site/a.html"),
+        ];
+        let files = BTreeSet::from([a.clone(), b.clone()]);
+        assert_eq!(changed_files(&events, &files), [b, a]);
+        assert!(changed_files(&events[1..2], &files).is_empty());
+    }
+
+    #[test]
+    fn test_watcher_reports_changed_file() {
+        let dir = TempDir::new().unwrap();
+        let page = dir.path().join("a.html");
+        fs::write(&page, "<p>A</p>").unwrap();
+        let watcher = Watcher::new([page.clone()]).unwrap();
+
+        // Changes to other files in the directory are not reported.
+        fs::write(dir.path().join("b.html"), "<p>B</p>").unwrap();
+        fs::write(&page, "<p>A2</p>").unwrap();
+
+        // Like `wait`, but failing instead of blocking forever.
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let changed = loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let events = watcher.events.recv_timeout(timeout).unwrap().unwrap();
+            let changed = changed_files(&events, &watcher.files);
+            if !changed.is_empty() {
+                break changed;
+            }
+        };
+        assert_eq!(changed, [fs::canonicalize(&page).unwrap()]);
+    }
+}
@@ -36,6 +36,27 @@ fn test_json_output() {
         .stdout(predicate::str::contains("\"text\":\"Test\""));
 }
 
+#[test]
+fn test_ndjson_output() {
+    scrape()
+        .args(["-o", "ndjson", "li"])
+        .write_stdin("<ul><li>A</li><li>B</li></ul>")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"html\":\"<li>A</li>\""))
+        .stdout(predicate::function(|out: &str| out.lines().count() == 2));
+
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("page.html");
+    fs::write(&file, "<h1>Title</h1>").unwrap();
+    scrape()
+        .args(["-o", "ndjson", "-H", "-s", "title=h1"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(format!("{{\"file\":\"{}\",\"title\":[\"Title\"]}}\n", file.display()));
+}
+
 #[test]
 fn test_attribute_extraction() {
     scrape()
@@ -116,6 +137,27 @@ fn test_html_output() {
         .stdout(predicate::str::contains("Hello"));
 }
 
+#[test]
+fn test_html_output_is_outer_html() {
+    scrape()
+        .args(["-o", "html", "p"])
+        .write_stdin("<p class=\"a\">Hello <b>World</b></p>")
+        .assert()
+        .success()
+        .stdout("<p class=\"a\">Hello <b>World</b></p>\n");
+}
+
+#[test]
+fn test_pretty_html_output() {
+    scrape()
+        .args(["-o", "html", "--pretty-html", "ul"])
+        .write_stdin("<ul><li>One</li><li>Two <em>2</em></li></ul>")
+        .assert()
+        .success()
+        .stdout("<ul>\n  <li>One</li>\n  <li>Two <em>2</em></li>\n</ul>\n");
+    scrape().args(["--pretty-html", "ul"]).write_stdin("").assert().code(4);
+}
+
 #[test]
 fn test_csv_named_selectors() {
     scrape()
@@ -238,6 +280,26 @@ fn test_nonexistent_file() {
         .stderr(predicate::str::contains("nonexistent"));
 }
 
+#[test]
+fn test_url_inputs() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("local.html");
+    fs::write(&file, "<h1>Local</h1>").unwrap();
+
+    // Nothing listens on port 1: the URL fails fast and the file is still processed.
+    scrape()
+        .args(["--no-filename", "--retries", "0", "--concurrency", "2", "h1"])
+        .arg(&file)
+        .arg("http://127.0.0.1:1/")
+        .assert()
+        .success()
+        .stdout("Local\n")
+        .stderr(predicate::str::contains("http://127.0.0.1:1/: HTTP error"));
+
+    scrape().args(["--concurrency", "0", "h1", "https://example.com"]).assert().code(4);
+    scrape().args(["--rate-limit", "0", "h1", "https://example.com"]).assert().code(4);
+}
+
 // Phase 19 Tests - Selector Explanation
 
 #[test]
@@ -398,3 +460,572 @@ fn test_very_large_document() {
     let very_large = format!("<html><body>{items}</body></html>");
     scrape().arg("p").write_stdin(very_large).assert().success();
 }
+
+#[test]
+fn test_contacts_subcommand() {
+    let dir = TempDir::new().unwrap();
+    let a = dir.path().join("a.html");
+    let b = dir.path().join("b.html");
+    fs::write(&a, "<a href=\"mailto:Team@Example.com\">Mail</a><p>Call +1 555 010 9999</p>")
+        .unwrap();
+    fs::write(&b, "<p>team@example.com or https://example.com/about.</p>").unwrap();
+
+    scrape()
+        .arg("contacts")
+        .arg(&a)
+        .arg(&b)
+        .assert()
+        .success()
+        .stdout("email\tteam@example.com\nphone\t+15550109999\nurl\thttps://example.com/about\n");
+}
+
+#[test]
+fn test_contacts_json_and_not_found() {
+    scrape()
+        .args(["contacts", "-o", "json"])
+        .write_stdin("<p>Write to info@example.org</p>")
+        .assert()
+        .success()
+        .stdout("{\"emails\":[\"info@example.org\"],\"phones\":[],\"urls\":[]}\n");
+
+    scrape().arg("contacts").write_stdin("<p>nothing here</p>").assert().code(1);
+}
+
+#[test]
+fn test_tree_subcommand() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("page.html");
+    fs::write(&file, "<div class=\"main\"><ul><li>One</li></ul></div><p>Other</p>").unwrap();
+
+    scrape()
+        .args(["tree", "--color", "never", "--selector", "div.main"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout("div.main\n  ul\n    li\n      \"One\"\n");
+
+    scrape()
+        .args(["tree", "-c", "never", "--depth", "1"])
+        .write_stdin("<div><p>Deep</p></div>")
+        .assert()
+        .success()
+        .stdout("html\n  head\n  body\n    … (1 child)\n");
+
+    scrape().args(["tree", "-s", "table"]).write_stdin("<p>x</p>").assert().code(1);
+}
+
+#[test]
+fn test_bench_subcommand() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("page.html");
+    fs::write(&file, "<ul><li><a href=\"/a\">A</a></li><li><a href=\"/b\">B</a></li></ul>")
+        .unwrap();
+    let baseline = dir.path().join("baseline.tsv");
+
+    scrape()
+        .args(["bench", "-n", "2", "--warmup", "0", "-s", "a[href]", "--save-baseline"])
+        .arg(&baseline)
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("page.html"))
+        .stdout(predicate::str::contains("  a[href]").and(predicate::str::contains("2 hits")));
+
+    let saved = fs::read_to_string(&baseline).unwrap();
+    assert!(saved.contains("parse/"));
+    assert!(saved.contains("/a[href]\t"));
+
+    let key = saved.lines().find(|line| line.starts_with("parse/")).unwrap();
+    let (key, _) = key.rsplit_once('\t').unwrap();
+    fs::write(&baseline, format!("{key}\t1e15\n")).unwrap();
+    scrape()
+        .args(["bench", "-n", "1", "--warmup", "0", "-s", "a", "--baseline"])
+        .arg(&baseline)
+        .arg(&file)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("regression: parse/"));
+}
+
+#[test]
+fn test_bench_errors() {
+    let dir = TempDir::new().unwrap();
+    scrape()
+        .args(["bench", "--offline", "--corpus-dir"])
+        .arg(dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("not cached"));
+
+    scrape().args(["bench", "-n", "0"]).assert().code(4);
+
+    let file = dir.path().join("page.html");
+    fs::write(&file, "<p>x</p>").unwrap();
+    scrape().args(["bench", "-s", "["]).arg(&file).assert().code(2);
+}
+
+#[test]
+fn test_table_extraction() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("table.html");
+    fs::write(
+        &file,
+        "<table class=\"prices\"><tr><th>Item</th><th>Price</th></tr><tr><td>Tea</td><td>3</td></\
+         tr><tr><td colspan=\"2\">n/a</td></tr></table>",
+    )
+    .unwrap();
+
+    scrape()
+        .args(["--table", "table.prices", "-o", "csv"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout("Item,Price\nTea,3\nn/a,n/a\n");
+
+    scrape()
+        .args(["--table", "table", "-o", "json"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout("[{\"Item\":\"Tea\",\"Price\":\"3\"},{\"Item\":\"n/a\",\"Price\":\"n/a\"}]\n");
+
+    scrape().args(["--table", "table"]).write_stdin("<p>No tables</p>").assert().code(1);
+    scrape().args(["--table", "table", "-o", "html"]).write_stdin("").assert().code(4);
+}
+
+#[test]
+fn test_dump_assets() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("assets");
+
+    scrape()
+        .arg("--dump-assets")
+        .arg(&out)
+        .write_stdin("<img src=\"data:image/gif;base64,R0lGODlh\"><svg><rect/></svg>")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("asset-0001.gif\timage/gif\t6\n"))
+        .stdout(predicate::str::contains("asset-0002.svg\timage/svg+xml\t"));
+    assert_eq!(fs::read(out.join("asset-0001.gif")).unwrap(), b"GIF89a");
+
+    scrape().arg("--dump-assets").arg(&out).write_stdin("<p>plain</p>").assert().code(1);
+}
+
+#[test]
+fn test_metadata_extraction() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("page.html");
+    fs::write(
+        &file,
+        "<head><title>Tea</title><meta property=\"og:type\" content=\"article\">\
+         <link rel=\"canonical\" href=\"https://example.com/tea\"></head>",
+    )
+    .unwrap();
+
+    scrape()
+        .arg("--metadata")
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout("title\tTea\ncanonical\thttps://example.com/tea\nog:type\tarticle\n");
+
+    scrape()
+        .args(["--metadata", "-o", "ndjson"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"open_graph\":{\"og:type\":[\"article\"]}"));
+
+    scrape().arg("--metadata").write_stdin("<p>plain</p>").assert().code(1);
+    scrape().args(["--metadata", "-o", "csv"]).write_stdin("").assert().code(4);
+}
+
+#[test]
+fn test_archive_subcommand() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("page.html");
+    let output = dir.path().join("out.html");
+    fs::write(&file, "<p style=\"background: url(data:image/png;base64,AA==)\">Hi</p>").unwrap();
+
+    scrape().arg("archive").arg(&file).arg("-o").arg(&output).assert().success();
+    let archived = fs::read_to_string(&output).unwrap();
+    assert!(archived.starts_with("<!DOCTYPE html>\n"));
+    assert!(archived.contains("data:image/png;base64,AA=="));
+
+    scrape()
+        .args(["archive", "--base-url", "http://127.0.0.1:1/"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<p style="));
+
+    fs::write(&file, "<img src=\"logo.png\">").unwrap();
+    scrape()
+        .args(["archive", "--base-url", "http://127.0.0.1:1/"])
+        .arg(&file)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("<img src=\"logo.png\">"))
+        .stderr(predicate::str::contains("http://127.0.0.1:1/logo.png"));
+}
+
+#[test]
+fn test_article_extraction() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("post.html");
+    fs::write(
+        &file,
+        "<title>Brewing Tea | Blog</title><nav><a href=\"/\">Home</a></nav><div \
+         class=\"post\"><p>Warm the pot, add the leaves, and pour water off the \
+         boil.</p></div><div class=\"sidebar\"><p>Subscribe to our newsletter, it is free and \
+         weekly.</p></div>",
+    )
+    .unwrap();
+
+    scrape()
+        .arg("--article")
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout("# Brewing Tea\n\nWarm the pot, add the leaves, and pour water off the boil.\n");
+
+    scrape()
+        .args(["--article", "-o", "ndjson"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"title\":\"Brewing Tea\""));
+
+    scrape().arg("--article").write_stdin("").assert().code(1);
+    scrape().args(["--article", "-o", "html"]).write_stdin("").assert().code(4);
+}
+
+#[test]
+fn test_markdown_output() {
+    scrape()
+        .args(["-o", "markdown", "article"])
+        .write_stdin(
+            "<article><h2>Tea</h2><p>Brew it <b>hot</b>, see <a \
+             href=\"/tea\">more</a>.</p></article><article><ul><li>Green</li><li>Black</li></ul></\
+             article>",
+        )
+        .assert()
+        .success()
+        .stdout("## Tea\n\nBrew it **hot**, see [more](/tea).\n\n- Green\n- Black\n\n");
+
+    scrape()
+        .args(["--table", "table", "-o", "markdown"])
+        .write_stdin("<table><tr><th>Item</th></tr><tr><td>Tea</td></tr></table>")
+        .assert()
+        .success()
+        .stdout("| Item |\n| --- |\n| Tea |\n");
+
+    scrape().args(["--metadata", "-o", "markdown"]).write_stdin("").assert().code(4);
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn test_template_output() {
+    let html = "<a href=\"/tea\" title=\"Tea\">Green <b>tea</b></a><a href=\"/cake\">Cake</a>";
+
+    scrape()
+        .args(["-o", "template", "--template", "{index}: {text} ({attr:href}) {{{attr:title}}}"])
+        .arg("a")
+        .write_stdin(html)
+        .assert()
+        .success()
+        .stdout("0: Green tea (/tea) {Tea}\n1: Cake (/cake) {}\n");
+
+    scrape()
+        .args(["-o", "template", "--template", "{inner_html}|{html}", "-1", "a"])
+        .write_stdin(html)
+        .assert()
+        .success()
+        .stdout("Green <b>tea</b>|<a href=\"/tea\" title=\"Tea\">Green <b>tea</b></a>\n");
+
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("page.html");
+    fs::write(&file, html).unwrap();
+    scrape()
+        .args(["-o", "template", "--template", "{name}\t{text}\t{filename}", "-s", "link=b"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("link\ttea\t"))
+        .stdout(predicate::str::ends_with("page.html\n"));
+
+    scrape().args(["-o", "template", "a"]).write_stdin(html).assert().code(4);
+    scrape().args(["--template", "{text}", "a"]).write_stdin(html).assert().code(4);
+    scrape()
+        .args(["-o", "template", "--template", "{href}", "a"])
+        .write_stdin(html)
+        .assert()
+        .failure();
+    scrape()
+        .args(["-o", "template", "--template", "{html}", "-s", "link=a"])
+        .write_stdin(html)
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn test_strict_and_warnings() {
+    let html = "<!DOCTYPE html><h1>Hi</h1>\n</p>";
+
+    scrape().arg("h1").write_stdin(html).assert().success().stdout("Hi\n").stderr("");
+
+    scrape()
+        .args(["--warnings", "h1"])
+        .write_stdin(html)
+        .assert()
+        .success()
+        .stdout("Hi\n")
+        .stderr(predicate::str::starts_with("-: warning: "))
+        .stderr(predicate::str::contains("at line 2, column 1"));
+
+    scrape()
+        .args(["--strict", "h1"])
+        .write_stdin(html)
+        .assert()
+        .code(2)
+        .stdout("")
+        .stderr(predicate::str::contains("parse error: "));
+
+    scrape()
+        .args(["--strict", "h1"])
+        .write_stdin("<!DOCTYPE html><h1>Hi</h1>")
+        .assert()
+        .success()
+        .stdout("Hi\n");
+}
+
+#[test]
+fn test_xml_input() {
+    let feed = "<?xml version=\"1.0\"?>\n<rss><channel><item><title>Tea</title>\
+                <link>https://example.com/tea</link><pubDate>Mon, 01 Jan 2024</pubDate>\
+                <description><![CDATA[<p>Green</p>]]></description></item></channel></rss>";
+
+    scrape()
+        .args(["--input-format", "xml", "-s", "link=item > link", "-s", "date=pubDate"])
+        .args(["-o", "ndjson"])
+        .write_stdin(feed)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"link\":[\"https://example.com/tea\"]"))
+        .stdout(predicate::str::contains("\"date\":[\"Mon, 01 Jan 2024\"]"));
+
+    scrape()
+        .args(["--input-format", "xml", "-o", "html", "item > link, description"])
+        .write_stdin(feed)
+        .assert()
+        .success()
+        .stdout(
+            "<link>https://example.com/tea</link>\n\
+             <description>&lt;p&gt;Green&lt;/p&gt;</description>\n",
+        );
+
+    // The HTML parser reads `<link>` as a void element.
+    scrape().args(["item > link"]).write_stdin(feed).assert().success().stdout("\n");
+
+    scrape()
+        .args(["--input-format", "xml", "title"])
+        .write_stdin("<a/><b/>")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("malformed XML: multiple root elements"));
+    scrape()
+        .args(["--input-format", "xml", "--strict", "title"])
+        .write_stdin(feed)
+        .assert()
+        .code(4);
+    scrape().args(["--input-format", "xml", "--table", "table"]).write_stdin(feed).assert().code(4);
+}
+
+#[test]
+fn test_provenance() {
+    scrape()
+        .args(["-o", "json", "--provenance", "-s", "tea=li"])
+        .write_stdin("<ul><li>Green</li><li>Black</li></ul>")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""css_path":"html > body > ul > li:nth-of-type(2)","field":"tea[1]","line":1,"selector":"li""#,
+        ));
+
+    scrape().args(["--provenance", "li"]).write_stdin("<li>Green</li>").assert().failure();
+}
+
+#[test]
+fn test_summary_output() {
+    let dir = TempDir::new().unwrap();
+    let priced = dir.path().join("a.html");
+    let unpriced = dir.path().join("b.html");
+    fs::write(&priced, "<h1>Tea</h1><b class=\"price\">4</b>").unwrap();
+    fs::write(&unpriced, "<h1>Mug</h1>").unwrap();
+
+    scrape()
+        .args(["-o", "summary", "-s", "title=h1", "-s", "price=.price"])
+        .arg(&priced)
+        .arg(&unpriced)
+        .assert()
+        .success()
+        .stdout("price   50%  (1/2 inputs)\ntitle  100%  (2/2 inputs)\n");
+
+    scrape()
+        .args(["-o", "summary", ".price"])
+        .arg(&priced)
+        .arg(&unpriced)
+        .assert()
+        .success()
+        .stdout(".price   50%  (1/2 inputs)\n");
+
+    scrape().args(["-o", "summary", "--metadata"]).arg(&priced).assert().code(4);
+}
+
+#[test]
+fn test_require() {
+    let dir = TempDir::new().unwrap();
+    let priced = dir.path().join("a.html");
+    let unpriced = dir.path().join("b.html");
+    fs::write(&priced, "<h1>Tea</h1><b class=\"price\">4</b>").unwrap();
+    fs::write(&unpriced, "<h1>Mug</h1>").unwrap();
+
+    scrape()
+        .args(["-o", "ndjson", "-s", "title=h1", "-s", "price=.price", "--require", "price"])
+        .arg(&priced)
+        .arg(&unpriced)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""price":["4"],"title":["Tea"]"#))
+        .stdout(predicate::str::contains(r#""rejected":"missing required field `price`""#))
+        .stdout(predicate::str::contains("Mug").not())
+        .stderr(predicate::str::contains(
+            "rejected 1 of 2 records:\n  1 missing required field `price`\n",
+        ));
+
+    scrape()
+        .args(["-s", "title=h1", "--require", "price"])
+        .write_stdin("<h1>Tea</h1>")
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("--require price does not name a --select field"));
+}
+
+#[test]
+fn test_schema_file() {
+    let dir = TempDir::new().unwrap();
+    let schema = dir.path().join("schema.json");
+    let page = dir.path().join("page.html");
+    fs::write(
+        &schema,
+        r#"{
+            "title": {"selector": "h1", "required": true},
+            "items": {"selector": "li", "many": true, "fields": {"name": "b", "url": "a@href"}}
+        }"#,
+    )
+    .unwrap();
+    fs::write(&page, "<h1>Teas</h1><ul><li><b>Green</b><a href=\"/g\">x</a></li></ul>").unwrap();
+
+    scrape().arg("--schema").arg(&schema).arg(&page).assert().success().stdout(
+        predicate::str::contains(
+            r#""data":{"title":"Teas","items":[{"name":"Green","url":"/g"}]}"#,
+        ),
+    );
+
+    scrape()
+        .args(["-o", "ndjson", "--schema"])
+        .arg(&schema)
+        .write_stdin("<p>No title</p>")
+        .assert()
+        .code(1)
+        .stdout("{\"input\":\"-\",\"rejected\":\"missing required field `title`\"}\n")
+        .stderr(predicate::str::contains("rejected 1 of 1 records"));
+
+    let message = "--schema supports only text, json, and ndjson output";
+    for format in ["csv", "html", "markdown"] {
+        scrape()
+            .args(["-o", format, "--schema"])
+            .arg(&schema)
+            .write_stdin("")
+            .assert()
+            .code(4)
+            .stderr(predicate::str::contains(message));
+    }
+}
+
+#[test]
+fn test_watch_requires_input_files() {
+    scrape()
+        .args(["--watch", "h1"])
+        .write_stdin("<h1>Tea</h1>")
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("--watch requires local input files"));
+}
+
+#[test]
+fn test_watch_missing_directory() {
+    scrape()
+        .args(["--watch", "h1", "no-such-dir/page.html"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot watch no-such-dir/page.html"));
+}
+
+#[test]
+fn test_crawl_subcommand() {
+    scrape()
+        .args(["crawl", "page.html"])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("crawl requires an http(s) start URL"));
+    scrape().args(["crawl", "https://example.com", "-s", "h1"]).assert().code(4);
+
+    scrape()
+        .args(["crawl", "http://127.0.0.1:1/", "-s", "title=h1"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("http://127.0.0.1:1/: "));
+}
+
+#[test]
+fn test_diff_subcommand() {
+    let dir = TempDir::new().unwrap();
+    let old = dir.path().join("old.html");
+    let new = dir.path().join("new.html");
+    fs::write(&old, "<nav>Home</nav><main><p class=\"price\">3 EUR</p></main>").unwrap();
+    fs::write(&new, "<nav>Start</nav><main>\n  <p class=\"price\">4 EUR</p>\n</main>").unwrap();
+
+    scrape()
+        .args(["diff", "--selector", "main"])
+        .arg(&old)
+        .arg(&new)
+        .assert()
+        .code(1)
+        .stdout("~ html > body > main > p: \"3 EUR\" -> \"4 EUR\"\n");
+    scrape()
+        .args(["diff", "-s", "p", "-o", "ndjson"])
+        .arg(&old)
+        .arg(&old)
+        .assert()
+        .success()
+        .stdout("");
+    scrape().args(["diff", "-o", "csv"]).arg(&old).arg(&new).assert().code(4);
+}
+
+#[test]
+fn test_lint_subcommand() {
+    scrape().arg("lint").write_stdin("<!DOCTYPE html><img src=\"a.png\">").assert().code(1).stdout(
+        "LINE  KIND         LOCATION           MESSAGE\n-     missing-alt  html > body > img  \
+         <img> has no alt attribute\n",
+    );
+    scrape()
+        .args(["lint", "-o", "ndjson"])
+        .write_stdin("<!DOCTYPE html>\n<div><span>Tea")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("\"kind\":\"unclosed-element\",\"line\":2"));
+    scrape().arg("lint").write_stdin("<!DOCTYPE html><p>Tea</p>").assert().success().stdout("");
+    scrape().args(["lint", "-o", "csv"]).write_stdin("").assert().code(4);
+}
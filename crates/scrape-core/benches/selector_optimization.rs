@@ -1,14 +1,17 @@
 //! Benchmarks for Phase 13 query engine features.
 //!
 //! Tests performance of:
-//! - CompiledSelector vs string selectors
+//! - CompiledSelector vs cached and uncached string selectors
 //! - TextNodesIter vs .text()
 //! - Filtered iterators (.children_by_name, .children_by_class)
 
 use std::hint::black_box;
 
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
-use scrape_core::{Soup, query::CompiledSelector};
+use scrape_core::{
+    Soup,
+    query::{self, CompiledSelector},
+};
 
 /// Generate HTML with many nested elements for benchmarking.
 fn generate_nested_html(depth: usize, breadth: usize) -> String {
@@ -113,6 +116,13 @@ fn bench_compiled_vs_string(c: &mut Criterion) {
         b.iter(|| soup.select_compiled(black_box(&compiled_complex)));
     });
 
+    // String selectors recompiled on every call
+    query::set_selector_cache_capacity(0);
+    group.bench_function("uncached/complex/single", |b| {
+        b.iter(|| soup.find(black_box(complex_selector)));
+    });
+    query::set_selector_cache_capacity(query::SELECTOR_CACHE_CAPACITY);
+
     group.finish();
 }
 
@@ -0,0 +1,431 @@
+//! Single-file page archiving: inlining external stylesheets and images.
+//!
+//! [`inline_external_assets`] (also available as [`Soup::inline_external_assets`]) is the
+//! counterpart of [`inline_assets`](crate::extract::assets::inline_assets): it downloads the
+//! resources a page references through an [`AssetFetcher`] and embeds them, so the
+//! serialized document renders without network access:
+//!
+//! - `<link rel="stylesheet">` becomes a `<style>` block with the downloaded CSS
+//! - `<img src>`, `<video poster>`, and icon `<link href>` become `data:` URIs; `srcset` is dropped
+//!   from inlined images
+//! - `url(...)` references in stylesheets, `<style>` blocks, and `style` attributes become `data:`
+//!   URIs, resolved against the stylesheet's own URL
+//!
+//! Only `http(s)` URLs are fetched, each at most once. `@import` targets are embedded as
+//! `data:` URIs without inlining their own references. Scripts are left untouched.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{Soup, archive::FetchedAsset};
+//!
+//! let mut soup = Soup::parse(r#"<link rel="stylesheet" href="/site.css"><img src="logo.gif">"#);
+//! let fetch = |url: &str| match url {
+//!     "https://example.com/site.css" => Ok(FetchedAsset::new("text/css", "p { color: red }")),
+//!     "https://example.com/logo.gif" => Ok(FetchedAsset::new("image/gif", "GIF89a")),
+//!     _ => Err("not found"),
+//! };
+//!
+//! let report = soup.inline_external_assets(&fetch, Some("https://example.com/"));
+//! assert_eq!(report.inlined.len(), 2);
+//! assert!(report.failed.is_empty());
+//! assert_eq!(soup.find("style").unwrap().unwrap().text(), "p { color: red }");
+//! let img = soup.find("img").unwrap().unwrap();
+//! assert_eq!(img.get("src"), Some("data:image/gif;base64,R0lGODlh"));
+//! ```
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    NodeId, Soup,
+    dom::{Attributes, NodeKind, TagId},
+    extract::{
+        assets::{encode_data_uri, rewrite_css_urls},
+        links::base_url,
+    },
+    serialize::collect_text,
+    utils::{resolve_url, split_attr_list},
+};
+
+/// Downloads the resources referenced by a page.
+///
+/// Implemented for closures `Fn(&str) -> Result<FetchedAsset, E>`.
+pub trait AssetFetcher {
+    /// Error returned when a download fails.
+    type Error: fmt::Display;
+
+    /// Downloads the resource at the absolute `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the resource cannot be downloaded.
+    fn fetch(&self, url: &str) -> Result<FetchedAsset, Self::Error>;
+}
+
+impl<F, E> AssetFetcher for F
+where
+    F: Fn(&str) -> Result<FetchedAsset, E>,
+    E: fmt::Display,
+{
+    type Error = E;
+
+    fn fetch(&self, url: &str) -> Result<FetchedAsset, E> {
+        self(url)
+    }
+}
+
+/// A downloaded resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchedAsset {
+    /// The `Content-Type` of the response, if known. Parameters are ignored.
+    pub content_type: Option<String>,
+    /// The response body.
+    pub data: Vec<u8>,
+}
+
+impl FetchedAsset {
+    /// Creates an asset with a known content type.
+    #[must_use]
+    pub fn new(content_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self { content_type: Some(content_type.into()), data: data.into() }
+    }
+
+    /// Returns the lowercase MIME type without parameters, guessed from the URL's file
+    /// extension when the content type is missing or generic.
+    fn mime(&self, url: &str) -> String {
+        let mime = self
+            .content_type
+            .as_deref()
+            .and_then(|ct| ct.split(';').next())
+            .map(|m| m.trim().to_ascii_lowercase())
+            .filter(|m| !m.is_empty() && m != "application/octet-stream");
+        mime.unwrap_or_else(|| guess_mime(url).to_string())
+    }
+}
+
+/// What [`inline_external_assets`] embedded and what it could not download.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveReport {
+    /// Absolute URLs that were downloaded and embedded, in the order first fetched.
+    pub inlined: Vec<String>,
+    /// URLs whose download failed; their references are left unchanged.
+    pub failed: Vec<ArchiveFailure>,
+}
+
+/// A resource that could not be downloaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveFailure {
+    /// The absolute URL.
+    pub url: String,
+    /// The fetcher's error message.
+    pub error: String,
+}
+
+/// Downloads and embeds the external stylesheets and images of a document.
+///
+/// References are resolved like [`links`](crate::extract::links); relative references
+/// without an absolute base are left unchanged. See the [module documentation](self) for
+/// what is inlined.
+pub fn inline_external_assets<F: AssetFetcher>(
+    soup: &mut Soup,
+    fetcher: &F,
+    base_url: Option<&str>,
+) -> ArchiveReport {
+    let base = self::base_url(soup, base_url);
+    let mut archiver =
+        Archiver { fetcher, base, cache: HashMap::new(), report: ArchiveReport::default() };
+
+    let edits: Vec<Edit> =
+        collect_refs(soup).into_iter().filter_map(|reference| archiver.edit(reference)).collect();
+
+    let doc = soup.document_mut();
+    for edit in edits {
+        match edit {
+            Edit::Attribute { node, name, value, drop } => {
+                doc.set_attribute(node, name, value);
+                if let Some(drop) = drop {
+                    doc.remove_attribute(node, drop);
+                }
+            }
+            Edit::Text { node, css } => {
+                while let Some(child) = doc.first_child(node) {
+                    doc.remove_node(child);
+                }
+                let text = doc.create_text(css);
+                doc.append_child(node, text);
+            }
+            Edit::Stylesheet { node, media, css } => {
+                let attributes: Attributes =
+                    media.map(|media| ("media", media)).into_iter().collect();
+                let style = doc.create_element("style", attributes);
+                let text = doc.create_text(css);
+                doc.append_child(style, text);
+                doc.replace_node(node, style);
+            }
+        }
+    }
+
+    archiver.report
+}
+
+// ==================== Helpers ====================
+
+/// An external reference found in the document.
+enum Reference {
+    /// A URL attribute; `drop` is removed once the URL is inlined.
+    Url { node: NodeId, attr: &'static str, url: String, drop: Option<&'static str> },
+    /// A `<link rel="stylesheet">`.
+    Stylesheet { node: NodeId, url: String, media: Option<String> },
+    /// CSS in a `style` attribute.
+    StyleAttribute { node: NodeId, css: String },
+    /// CSS in a `<style>` block.
+    StyleBlock { node: NodeId, css: String },
+}
+
+/// A change to apply once all downloads are done.
+enum Edit {
+    Attribute { node: NodeId, name: &'static str, value: String, drop: Option<&'static str> },
+    Text { node: NodeId, css: String },
+    Stylesheet { node: NodeId, media: Option<String>, css: String },
+}
+
+struct Archiver<'a, F> {
+    fetcher: &'a F,
+    base: Option<String>,
+    /// Downloads by absolute URL; `None` records a failure.
+    cache: HashMap<String, Option<FetchedAsset>>,
+    report: ArchiveReport,
+}
+
+impl<F: AssetFetcher> Archiver<'_, F> {
+    fn edit(&mut self, reference: Reference) -> Option<Edit> {
+        match reference {
+            Reference::Url { node, attr, url, drop } => {
+                let value = self.data_uri(self.base.clone().as_deref(), &url)?;
+                Some(Edit::Attribute { node, name: attr, value, drop })
+            }
+            Reference::Stylesheet { node, url, media } => {
+                let url = Self::resolve(self.base.as_deref(), &url)?;
+                let css = String::from_utf8_lossy(&self.fetch(&url)?.data).into_owned();
+                let css = self.inline_css(Some(&url), &css);
+                Some(Edit::Stylesheet { node, media, css })
+            }
+            Reference::StyleAttribute { node, css } => {
+                let value = self.inline_css(self.base.clone().as_deref(), &css);
+                (value != css).then_some(Edit::Attribute { node, name: "style", value, drop: None })
+            }
+            Reference::StyleBlock { node, css } => {
+                let inlined = self.inline_css(self.base.clone().as_deref(), &css);
+                (inlined != css).then_some(Edit::Text { node, css: inlined })
+            }
+        }
+    }
+
+    /// Replaces the `url(...)` references in `css` with `data:` URIs, resolving them
+    /// against `base`.
+    fn inline_css(&mut self, base: Option<&str>, css: &str) -> String {
+        rewrite_css_urls(css, |url| self.data_uri(base, url))
+    }
+
+    fn data_uri(&mut self, base: Option<&str>, reference: &str) -> Option<String> {
+        let url = Self::resolve(base, reference)?;
+        let asset = self.fetch(&url)?;
+        Some(encode_data_uri(&asset.mime(&url), &asset.data))
+    }
+
+    /// Resolves a reference to an absolute `http(s)` URL worth fetching.
+    fn resolve(base: Option<&str>, reference: &str) -> Option<String> {
+        let reference = reference.trim();
+        if reference.is_empty() || reference.starts_with('#') {
+            return None;
+        }
+        let url = resolve_url(base.unwrap_or(reference), reference)?;
+        let scheme = url.split_once(':').map(|(scheme, _)| scheme.to_ascii_lowercase());
+        matches!(scheme.as_deref(), Some("http" | "https")).then_some(url)
+    }
+
+    fn fetch(&mut self, url: &str) -> Option<FetchedAsset> {
+        if let Some(cached) = self.cache.get(url) {
+            return cached.clone();
+        }
+        let result = match self.fetcher.fetch(url) {
+            Ok(asset) => {
+                self.report.inlined.push(url.to_string());
+                Some(asset)
+            }
+            Err(e) => {
+                self.report
+                    .failed
+                    .push(ArchiveFailure { url: url.to_string(), error: e.to_string() });
+                None
+            }
+        };
+        self.cache.insert(url.to_string(), result.clone());
+        result
+    }
+}
+
+/// Collects the external references of the document in document order.
+fn collect_refs(soup: &Soup) -> Vec<Reference> {
+    let doc = soup.document();
+    let mut refs = Vec::new();
+    let Some(root) = doc.root() else {
+        return refs;
+    };
+
+    for node in std::iter::once(root).chain(doc.descendants(root)) {
+        let Some(NodeKind::Element { tag_id, attributes, .. }) = doc.get(node).map(|n| &n.kind)
+        else {
+            continue;
+        };
+        let attr = |name: &str| attributes.get(name).map(str::to_string);
+
+        match tag_id {
+            TagId::Img => {
+                if let Some(url) = attr("src") {
+                    refs.push(Reference::Url { node, attr: "src", url, drop: Some("srcset") });
+                }
+            }
+            TagId::Video => {
+                if let Some(url) = attr("poster") {
+                    refs.push(Reference::Url { node, attr: "poster", url, drop: None });
+                }
+            }
+            TagId::Link => {
+                let rel: Vec<String> = attributes.get("rel").map_or_else(Vec::new, |rel| {
+                    split_attr_list(rel).map(str::to_ascii_lowercase).collect()
+                });
+                if let Some(url) = attr("href") {
+                    if rel.iter().any(|t| t == "stylesheet") {
+                        refs.push(Reference::Stylesheet { node, url, media: attr("media") });
+                    } else if rel.iter().any(|t| t == "icon" || t.starts_with("apple-touch-icon")) {
+                        refs.push(Reference::Url { node, attr: "href", url, drop: None });
+                    }
+                }
+            }
+            TagId::Style => {
+                let mut css = String::new();
+                collect_text(doc, node, &mut css);
+                refs.push(Reference::StyleBlock { node, css });
+            }
+            _ => {}
+        }
+        if let Some(css) = attr("style") {
+            refs.push(Reference::StyleAttribute { node, css });
+        }
+    }
+
+    refs
+}
+
+/// Guesses a MIME type from the file extension of a URL's path.
+fn guess_mime(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let extension = path.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("css") => "text/css",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        Some("woff2") => "font/woff2",
+        Some("woff") => "font/woff",
+        Some("ttf") => "font/ttf",
+        Some("otf") => "font/otf",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    fn fetch(url: &str) -> Result<FetchedAsset, String> {
+        match url {
+            "https://example.com/css/site.css" => Ok(FetchedAsset::new(
+                "text/css; charset=utf-8",
+                "body { background: url(../img/bg.png) } i { mask: url(\"data:,x\") }",
+            )),
+            "https://example.com/img/bg.png" => {
+                Ok(FetchedAsset { content_type: None, data: b"PNG".to_vec() })
+            }
+            "https://example.com/a.jpg" => Ok(FetchedAsset::new("image/jpeg", "JPG")),
+            _ => Err(format!("404 for {url}")),
+        }
+    }
+
+    #[test]
+    fn test_inline_stylesheet_and_css_urls() {
+        let mut soup = Soup::parse(
+            "<head><link rel=\"stylesheet\" media=\"print\" href=\"css/site.css\"></head>",
+        );
+        let report = inline_external_assets(&mut soup, &fetch, Some("https://example.com/"));
+        assert_eq!(
+            report.inlined,
+            vec!["https://example.com/css/site.css", "https://example.com/img/bg.png"]
+        );
+        let style = soup.find("style").unwrap().unwrap();
+        assert_eq!(style.get("media"), Some("print"));
+        assert_eq!(
+            style.text(),
+            "body { background: url(data:image/png;base64,UE5H) } i { mask: url(\"data:,x\") }"
+        );
+        assert!(soup.find("link").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_inline_images_and_style_attributes() {
+        let mut soup = Soup::parse(
+            "<img src=\"/a.jpg\" srcset=\"/a-2x.jpg 2x\"><img src=\"/a.jpg\"><div \
+             style=\"background: url('a.jpg')\"></div><img src=\"/missing.png\">",
+        );
+        let report = inline_external_assets(&mut soup, &fetch, Some("https://example.com/"));
+        assert_eq!(report.inlined, vec!["https://example.com/a.jpg"]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].error, "404 for https://example.com/missing.png");
+
+        let srcs = soup.select_attr("img", "src").unwrap();
+        assert_eq!(
+            srcs,
+            vec![
+                Some("data:image/jpeg;base64,SlBH".to_string()),
+                Some("data:image/jpeg;base64,SlBH".to_string()),
+                Some("/missing.png".to_string()),
+            ]
+        );
+        assert_eq!(soup.select_attr("img", "srcset").unwrap()[0], None);
+        let div = soup.find("div").unwrap().unwrap();
+        assert_eq!(div.get("style"), Some("background: url('data:image/jpeg;base64,SlBH')"));
+    }
+
+    #[test]
+    fn test_skips_unresolvable_references() {
+        let calls = RefCell::new(Vec::new());
+        let fetcher = |url: &str| {
+            calls.borrow_mut().push(url.to_string());
+            fetch(url)
+        };
+        let mut soup = Soup::parse(
+            "<img src=\"relative.png\"><img src=\"data:image/gif;base64,R0lGODlh\">\
+             <svg><rect style=\"fill: url(#grad)\"/></svg><img src=\"ftp://example.com/a.png\">",
+        );
+        let report = inline_external_assets(&mut soup, &fetcher, None);
+        assert_eq!(report, ArchiveReport::default());
+        assert!(calls.borrow().is_empty());
+        assert_eq!(soup.find("img").unwrap().unwrap().get("src"), Some("relative.png"));
+    }
+
+    #[test]
+    fn test_guess_mime() {
+        assert_eq!(guess_mime("https://example.com/a.PNG?v=2"), "image/png");
+        assert_eq!(guess_mime("https://example.com/font.woff2#x"), "font/woff2");
+        assert_eq!(guess_mime("https://example.com/blob"), "application/octet-stream");
+        let asset = FetchedAsset::new("application/octet-stream", "x");
+        assert_eq!(asset.mime("https://example.com/a.svg"), "image/svg+xml");
+    }
+}
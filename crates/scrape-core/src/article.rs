@@ -0,0 +1,468 @@
+//! Readability-style extraction of a page's main content.
+//!
+//! [`extract_article`] (also available as [`Soup::extract_article`]) finds the element
+//! holding the main content of a page, the way reader modes do, and returns it with the
+//! title, byline, publication date, and body as plain text and Markdown.
+//!
+//! Content is found by scoring: every paragraph-like element with at least 25
+//! characters of text scores points for its length and commas, and passes them to its
+//! parent (in full), grandparent (half), and great-grandparent (a third). Ancestors
+//! start with a bonus or penalty for their tag and for `class`/`id` names such as
+//! `article` or `sidebar`, and their final score is reduced by the share of their text
+//! inside links. The highest-scoring element wins; `<body>` is the fallback.
+//!
+//! Scripts, styles, forms, navigation, and elements whose `class`/`id` look like
+//! comments, sidebars, ads, or share widgets are ignored while scoring and rendering.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::Soup;
+//!
+//! let soup = Soup::parse(
+//!     r#"<html><head><title>Brewing Green Tea | Tea Blog</title>
+//!        <meta name="author" content="Ann Lee">
+//!        <meta property="article:published_time" content="2024-05-02"></head>
+//!        <body><nav><a href="/">Home</a> <a href="/about">About</a></nav>
+//!        <div class="post">
+//!          <p>Green tea tastes best with water well below boiling, around 80 degrees.</p>
+//!          <p>Steep it for two minutes, then taste, and steep longer if needed.</p>
+//!        </div>
+//!        <div class="sidebar"><p>Subscribe to our newsletter for weekly tea news.</p></div>
+//!        </body></html>"#,
+//! );
+//! let article = soup.extract_article();
+//! assert_eq!(article.title.as_deref(), Some("Brewing Green Tea"));
+//! assert_eq!(article.byline.as_deref(), Some("Ann Lee"));
+//! assert_eq!(article.published_date.unwrap().to_string(), "2024-05-02");
+//! assert!(article.text.starts_with("Green tea tastes best"));
+//! assert!(!article.text.contains("Subscribe"));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    Attributes, Document, NodeId, NodeKind, Soup, Tag,
+    convert::{MarkdownOptions, render},
+    extract::dates::{self, DateTime},
+    query::find_all,
+};
+
+/// The main content of a page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Article {
+    /// The element holding the content, or `None` for an empty document.
+    pub node: Option<NodeId>,
+    /// The headline: `og:title` or `twitter:title`, else `<title>` without a trailing
+    /// site name such as `| Example`, else the first `<h1>`.
+    pub title: Option<String>,
+    /// The author: `<meta name="author">`, else the text of a short `rel="author"`,
+    /// `itemprop="author"`, or `byline`/`author` class element.
+    pub byline: Option<String>,
+    /// The publication date, from meta tags, `datePublished` microdata, or the first
+    /// `<time>` element; see [`dates::published_date`].
+    pub published_date: Option<DateTime>,
+    /// The content as plain text, with blank lines between paragraphs.
+    pub text: String,
+    /// The content as Markdown, with headings, emphasis, links, images, lists, quotes,
+    /// and code blocks.
+    pub markdown: String,
+}
+
+/// Extracts the main content of a document.
+///
+/// See the [module documentation](self) for how the content is found.
+#[must_use]
+pub fn extract_article(soup: &Soup) -> Article {
+    let doc = soup.document();
+    let node = top_candidate(doc).or_else(|| {
+        find_all(doc, "body").ok().and_then(|ids| ids.first().copied()).or_else(|| doc.root())
+    });
+    let options = MarkdownOptions::default();
+
+    Article {
+        node,
+        title: title(soup),
+        byline: byline(soup),
+        published_date: dates::published_date(doc),
+        text: node.map(|node| render(doc, node, false, &options, is_skipped)).unwrap_or_default(),
+        markdown: node
+            .map(|node| render(doc, node, true, &options, is_skipped))
+            .unwrap_or_default(),
+    }
+}
+
+// ==================== Scoring ====================
+
+/// Elements that never hold article content.
+const SKIP: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "svg", "canvas", "object", "embed",
+    "form", "button", "input", "select", "textarea", "nav", "aside", "footer",
+];
+
+/// Elements whose text is scored as a paragraph.
+const PARAGRAPHS: &[&str] = &["p", "pre", "td", "blockquote"];
+
+/// Elements that make a `<div>` a container rather than a paragraph.
+const BLOCKS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "table",
+    "ul",
+    "ol",
+    "dl",
+    "pre",
+    "blockquote",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "figure",
+];
+
+const POSITIVE: &[&str] =
+    &["article", "content", "main", "body", "post", "entry", "story", "text", "blog"];
+
+const NEGATIVE: &[&str] = &[
+    "comment",
+    "sidebar",
+    "footer",
+    "footnote",
+    "masthead",
+    "menu",
+    "nav",
+    "share",
+    "social",
+    "sponsor",
+    "promo",
+    "advert",
+    "banner",
+    "cookie",
+    "popup",
+    "modal",
+    "related",
+    "breadcrumb",
+    "widget",
+    "subscribe",
+    "newsletter",
+];
+
+const MIN_PARAGRAPH_LEN: usize = 25;
+
+fn top_candidate(doc: &Document) -> Option<NodeId> {
+    let mut scores: HashMap<NodeId, f64> = HashMap::new();
+    let mut paragraphs = Vec::new();
+    if let Some(root) = doc.root() {
+        collect_paragraphs(doc, root, &mut paragraphs);
+    }
+
+    for node in paragraphs {
+        let text = text_of(doc, node);
+        let len = text.chars().count();
+        if len < MIN_PARAGRAPH_LEN {
+            continue;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let score = 1.0 + text.matches(',').count() as f64 + (len / 100).min(3) as f64;
+
+        for (level, ancestor) in doc.ancestors(node).take(3).enumerate() {
+            if element(doc, ancestor).is_none() {
+                break;
+            }
+            let divider = match level {
+                0 => 1.0,
+                1 => 2.0,
+                _ => 3.0,
+            };
+            *scores.entry(ancestor).or_insert_with(|| initial_score(doc, ancestor)) +=
+                score / divider;
+        }
+    }
+
+    scores
+        .into_iter()
+        .map(|(node, score)| (node, score * (1.0 - link_density(doc, node))))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(node, _)| node)
+}
+
+/// Collects the paragraph-like elements outside skipped subtrees, in document order.
+fn collect_paragraphs(doc: &Document, node: NodeId, out: &mut Vec<NodeId>) {
+    let Some((name, _)) = element(doc, node) else { return };
+    if is_skipped(doc, node) {
+        return;
+    }
+    let children = || doc.children(node).filter_map(|child| element(doc, child));
+    if PARAGRAPHS.contains(&name)
+        || (name == "div" && !children().any(|(child, _)| BLOCKS.contains(&child)))
+    {
+        out.push(node);
+    }
+    for child in doc.children(node) {
+        collect_paragraphs(doc, child, out);
+    }
+}
+
+fn initial_score(doc: &Document, node: NodeId) -> f64 {
+    let tag_score = match element(doc, node).map(|(name, _)| name) {
+        Some("div" | "article" | "main") => 5.0,
+        Some("pre" | "td" | "blockquote") => 3.0,
+        Some("address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li") => -3.0,
+        Some("h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th") => -5.0,
+        _ => 0.0,
+    };
+    tag_score + class_weight(doc, node)
+}
+
+/// Scores the `class` and `id` names: +25 for content-like, -25 for clutter-like.
+fn class_weight(doc: &Document, node: NodeId) -> f64 {
+    let names = class_and_id(doc, node);
+    let mut weight = 0.0;
+    if POSITIVE.iter().any(|word| names.contains(word)) {
+        weight += 25.0;
+    }
+    if NEGATIVE.iter().any(|word| names.contains(word)) {
+        weight -= 25.0;
+    }
+    weight
+}
+
+/// Returns `true` for elements that are never content: [`SKIP`] tags and elements
+/// whose `class`/`id` look like clutter and not like content.
+fn is_skipped(doc: &Document, node: NodeId) -> bool {
+    let Some((name, attributes)) = element(doc, node) else { return false };
+    if SKIP.contains(&name) || attributes.contains_key("hidden") {
+        return true;
+    }
+    if matches!(name, "html" | "body" | "article" | "main") {
+        return false;
+    }
+    let names = class_and_id(doc, node);
+    NEGATIVE.iter().any(|word| names.contains(word))
+        && !POSITIVE.iter().any(|word| names.contains(word))
+}
+
+/// Returns the share of an element's text that is inside links.
+fn link_density(doc: &Document, node: NodeId) -> f64 {
+    let total = text_of(doc, node).chars().count();
+    if total == 0 {
+        return 0.0;
+    }
+    let linked: usize = std::iter::once(node)
+        .chain(doc.descendants(node))
+        .filter(|&id| element(doc, id).is_some_and(|(name, _)| name == "a"))
+        .map(|id| text_of(doc, id).chars().count())
+        .sum();
+    #[allow(clippy::cast_precision_loss)]
+    let density = linked as f64 / total as f64;
+    density.min(1.0)
+}
+
+fn class_and_id(doc: &Document, node: NodeId) -> String {
+    let Some((_, attributes)) = element(doc, node) else { return String::new() };
+    let class = attributes.get("class").unwrap_or_default();
+    let id = attributes.get("id").unwrap_or_default();
+    format!("{class} {id}").to_ascii_lowercase()
+}
+
+// ==================== Title and byline ====================
+
+const TITLE_SEPARATORS: &[&str] = &[" | ", " - ", " – ", " — ", " :: ", " / "];
+
+fn title(soup: &Soup) -> Option<String> {
+    let meta = ["og:title", "twitter:title"].into_iter().find_map(|property| {
+        tags(soup, "meta[content]")
+            .find(|tag| {
+                [tag.get("property"), tag.get("name")]
+                    .into_iter()
+                    .flatten()
+                    .any(|key| key.eq_ignore_ascii_case(property))
+            })
+            .and_then(|tag| non_empty(tag.get("content")?))
+    });
+    meta.or_else(|| {
+        let title = non_empty(&tags(soup, "title").next()?.text())?;
+        Some(strip_site_name(&title))
+    })
+    .or_else(|| tags(soup, "h1").find_map(|tag| non_empty(&tag.text())))
+}
+
+/// Removes a trailing site name such as `| Example` if at least two words remain.
+fn strip_site_name(title: &str) -> String {
+    TITLE_SEPARATORS
+        .iter()
+        .filter_map(|separator| title.rfind(separator))
+        .max()
+        .map(|i| title[..i].trim())
+        .filter(|head| head.split_whitespace().count() >= 2)
+        .unwrap_or(title)
+        .to_string()
+}
+
+fn byline(soup: &Soup) -> Option<String> {
+    let meta = tags(soup, "meta[name][content]")
+        .find(|tag| tag.get("name").is_some_and(|name| name.eq_ignore_ascii_case("author")))
+        .and_then(|tag| non_empty(tag.get("content")?));
+    meta.or_else(|| {
+        tags(soup, "[rel=author], [itemprop=author], [class*=byline], [class*=author]")
+            .filter(|tag| !is_skipped(tag.document(), tag.node_id()))
+            .filter_map(|tag| non_empty(&tag.text()))
+            .find(|text| text.chars().count() < 100)
+    })
+}
+
+// ==================== Helpers ====================
+
+fn element(doc: &Document, node: NodeId) -> Option<(&str, &Attributes)> {
+    match &doc.get(node)?.kind {
+        NodeKind::Element { name, attributes, .. } => Some((name.as_str(), attributes)),
+        _ => None,
+    }
+}
+
+fn tags<'a>(soup: &'a Soup, selector: &str) -> impl Iterator<Item = Tag<'a>> {
+    let doc = soup.document();
+    find_all(doc, selector).unwrap_or_default().into_iter().map(move |id| Tag::new(doc, id))
+}
+
+/// Returns the element's text outside skipped subtrees, with whitespace collapsed.
+fn text_of(doc: &Document, node: NodeId) -> String {
+    fn walk(doc: &Document, node: NodeId, buf: &mut String) {
+        match doc.get(node).map(|n| &n.kind) {
+            Some(NodeKind::Text { content }) => buf.push_str(content),
+            Some(NodeKind::Element { .. }) if !is_skipped(doc, node) => {
+                for child in doc.children(node) {
+                    walk(doc, child, buf);
+                    buf.push(' ');
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut buf = String::new();
+    walk(doc, node, &mut buf);
+    collapse(&buf)
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    let text = collapse(text);
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SoupConfig;
+
+    const PAGE: &str = concat!(
+        "<html><head><title>Tea</title></head><body>",
+        "<div id=\"menu\"><a href=\"/a\">Home</a> <a href=\"/b\">Shop</a> <a \
+         href=\"/c\">Blog</a></div>",
+        "<div class=\"article-body\">",
+        "<h2>How to brew</h2>",
+        "<p>Warm the pot first, then add one spoon of leaves per cup, and one for the pot.</p>",
+        "<p>Pour water just off the boil, and wait <b>three</b> to <i>five</i> minutes.</p>",
+        "<ul><li>Black tea</li><li>Oolong, <a href=\"/oolong\">see guide</a></li></ul>",
+        "</div>",
+        "<div class=\"comments\"><p>Great post, thanks for sharing, I loved it, really.</p></div>",
+        "<footer><p>Copyright 2024, Tea Company, all rights reserved worldwide.</p></footer>",
+        "</body></html>",
+    );
+
+    #[test]
+    fn test_extract_content() {
+        let soup = Soup::parse(PAGE);
+        let article = extract_article(&soup);
+        let node = article.node.unwrap();
+        assert_eq!(Tag::new(soup.document(), node).get("class"), Some("article-body"));
+        assert_eq!(
+            article.text,
+            "How to brew\n\nWarm the pot first, then add one spoon of leaves per cup, and one for \
+             the pot.\n\nPour water just off the boil, and wait three to five minutes.\n\nBlack \
+             tea\nOolong, see guide"
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        let article = extract_article(&Soup::parse(PAGE));
+        assert!(article.markdown.starts_with("## How to brew\n\n"));
+        assert!(article.markdown.contains("wait **three** to *five* minutes."));
+        assert!(article.markdown.ends_with("- Black tea\n- Oolong, [see guide](/oolong)"));
+    }
+
+    #[test]
+    fn test_markdown_blocks() {
+        let config = SoupConfig::builder().preserve_whitespace(true).build();
+        let soup = Soup::parse_with_config(
+            "<article><blockquote><p>Quoted, and long enough to count as a \
+             paragraph.</p></blockquote><ol \
+             start=\"3\"><li>One</li><li>Two<ul><li>Nested</li></ul></li></ol><pre>let x = 1;\n  \
+             x + 1\n</pre><p>Line<br>break <code>a b</code> <img src=\"t.png\" \
+             alt=\"Tea\"></p></article>",
+            config,
+        );
+        let article = extract_article(&soup);
+        assert_eq!(
+            article.markdown,
+            "> Quoted, and long enough to count as a paragraph.\n\n3. One\n4. Two\n  - \
+             Nested\n\n```\nlet x = 1;\n  x + 1\n```\n\nLine\\\nbreak `a b` ![Tea](t.png)"
+        );
+    }
+
+    #[test]
+    fn test_title() {
+        let title = |html| extract_article(&Soup::parse(html)).title;
+        assert_eq!(title("<title>Brewing Tea | Tea Blog</title>").as_deref(), Some("Brewing Tea"));
+        assert_eq!(title("<title>Home - Tea Blog</title>").as_deref(), Some("Home - Tea Blog"));
+        assert_eq!(
+            title("<meta property=\"og:title\" content=\"OG\"><title>Doc Title - X</title>")
+                .as_deref(),
+            Some("OG")
+        );
+        assert_eq!(
+            title("<h1> First  heading </h1><h1>Second</h1>").as_deref(),
+            Some("First heading")
+        );
+        assert_eq!(title("<p>none</p>"), None);
+    }
+
+    #[test]
+    fn test_byline() {
+        let byline = |html| extract_article(&Soup::parse(html)).byline;
+        assert_eq!(byline("<meta name=\"Author\" content=\" Ann \">").as_deref(), Some("Ann"));
+        assert_eq!(
+            byline("<p>Tea</p><span class=\"byline\"> By  Bo Li </span>").as_deref(),
+            Some("By Bo Li")
+        );
+        assert_eq!(byline("<p>no author</p>"), None);
+    }
+
+    #[test]
+    fn test_published_date() {
+        let published = |html| extract_article(&Soup::parse(html)).published_date;
+        assert_eq!(
+            published("<p>Posted <time datetime=\"2024-05-02T08:00:00Z\">Thursday</time></p>")
+                .map(|date| date.to_string())
+                .as_deref(),
+            Some("2024-05-02T08:00:00Z")
+        );
+        assert_eq!(published("<p>no date</p>"), None);
+    }
+
+    #[test]
+    fn test_empty_document() {
+        let article = extract_article(&Soup::parse(""));
+        assert!(article.text.is_empty());
+        assert!(article.markdown.is_empty());
+        assert_eq!(article.title, None);
+    }
+}
@@ -0,0 +1,681 @@
+//! Reproducible benchmark corpus and throughput runners.
+//!
+//! This module lets users check parse and query throughput on their own hardware, against
+//! a pinned set of real-world pages or against their own documents, and gate on regressions
+//! relative to a saved baseline.
+//!
+//! # Feature Flag
+//!
+//! This module is only available when the `bench-corpus` feature is enabled:
+//!
+//! ```toml
+//! [dependencies]
+//! scrape-core = { version = "0.2", features = ["bench-corpus"] }
+//! ```
+//!
+//! # Pinning
+//!
+//! [`PINNED_PAGES`] point at fixed Wayback Machine snapshots, so every download returns the
+//! same bytes. scrape-core has no HTTP client, so [`Corpus::load_or_fetch`] takes a fetch
+//! callback. Downloaded pages are cached in a directory together with a `corpus.lock` file
+//! recording a checksum per page; later loads verify the cache against it, so results from
+//! different runs are always measured on identical input.
+//!
+//! # Example
+//!
+//! ```rust
+//! use scrape_core::bench_corpus::{BenchOptions, Corpus, CorpusPage, run};
+//!
+//! let corpus = Corpus::new(vec![CorpusPage::new("list", "<ul><li>a</li><li>b</li></ul>")]);
+//! let options = BenchOptions { iterations: 3, warmup: 0, selectors: vec!["li".into()] };
+//! let report = run(&corpus, &options).unwrap();
+//!
+//! assert_eq!(report.pages[0].queries[0].matches, 2);
+//! assert!(report.pages[0].parse_throughput() > 0.0);
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{CompiledSelector, QueryResult, Soup};
+
+/// Name of the checksum file kept next to cached corpus pages.
+pub const LOCK_FILE: &str = "corpus.lock";
+
+/// Downloads a page, returning its body or an error message.
+type FetchFn<'a> = &'a mut dyn FnMut(&str) -> Result<String, String>;
+
+/// A real-world page pinned to an immutable snapshot URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PinnedPage {
+    /// Short identifier, also used as the cache file stem.
+    pub name: &'static str,
+    /// Snapshot URL returning the original page bytes.
+    pub url: &'static str,
+}
+
+/// Pages in the default benchmark corpus.
+///
+/// The selection covers a long article, a dense link listing, a large application shell,
+/// reference documentation, and a very large specification page.
+pub const PINNED_PAGES: &[PinnedPage] = &[
+    PinnedPage {
+        name: "wikipedia",
+        url: "https://web.archive.org/web/20240601000000id_/https://en.wikipedia.org/wiki/Rust_(programming_language)",
+    },
+    PinnedPage {
+        name: "hacker-news",
+        url: "https://web.archive.org/web/20240601000000id_/https://news.ycombinator.com/",
+    },
+    PinnedPage {
+        name: "github",
+        url: "https://web.archive.org/web/20240601000000id_/https://github.com/rust-lang/rust",
+    },
+    PinnedPage {
+        name: "python-docs",
+        url: "https://web.archive.org/web/20240601000000id_/https://docs.python.org/3/library/stdtypes.html",
+    },
+    PinnedPage {
+        name: "html-spec",
+        url: "https://web.archive.org/web/20240601000000id_/https://html.spec.whatwg.org/multipage/parsing.html",
+    },
+];
+
+/// Selectors timed by default against every page.
+pub const DEFAULT_SELECTORS: &[&str] =
+    &["a[href]", "div p", "ul > li", "img[alt]", "h1, h2, h3", "table td", "[id]"];
+
+/// Errors from loading a corpus or a baseline.
+#[derive(Debug, thiserror::Error)]
+pub enum CorpusError {
+    /// Reading or writing a file failed.
+    #[error("I/O error on {}: {source}", .path.display())]
+    Io {
+        /// Path of the file.
+        path: PathBuf,
+        /// Underlying error.
+        #[source]
+        source: io::Error,
+    },
+    /// The fetch callback failed for a pinned page.
+    #[error("failed to fetch {name} from {url}: {message}")]
+    Fetch {
+        /// Page name.
+        name: String,
+        /// Snapshot URL.
+        url: String,
+        /// Error reported by the callback.
+        message: String,
+    },
+    /// A cached page no longer matches the checksum recorded in the lock file.
+    #[error("{name} does not match corpus.lock (expected {expected:016x}, found {found:016x})")]
+    ChecksumMismatch {
+        /// Page name.
+        name: String,
+        /// Checksum recorded in the lock file.
+        expected: u64,
+        /// Checksum of the cached page.
+        found: u64,
+    },
+    /// A pinned page is not cached and fetching is disabled.
+    #[error("{name} is not cached; fetch the corpus first")]
+    Missing {
+        /// Page name.
+        name: String,
+    },
+    /// A lock or baseline file contains a line that cannot be parsed.
+    #[error("malformed line {line}: {content:?}")]
+    Malformed {
+        /// One-based line number.
+        line: usize,
+        /// Offending line.
+        content: String,
+    },
+}
+
+/// A named HTML document in a [`Corpus`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusPage {
+    /// Page name used in reports and baseline keys.
+    pub name: String,
+    /// Page source.
+    pub html: String,
+}
+
+impl CorpusPage {
+    /// Creates a page from a name and its HTML source.
+    #[must_use]
+    pub fn new(name: impl Into<String>, html: impl Into<String>) -> Self {
+        Self { name: name.into(), html: html.into() }
+    }
+
+    /// Returns the FNV-1a checksum of the page bytes, as recorded in `corpus.lock`.
+    #[must_use]
+    pub fn checksum(&self) -> u64 {
+        checksum(self.html.as_bytes())
+    }
+}
+
+/// A set of documents to benchmark.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Corpus {
+    pages: Vec<CorpusPage>,
+}
+
+impl Corpus {
+    /// Creates a corpus from arbitrary pages, such as a user's own documents.
+    #[must_use]
+    pub fn new(pages: Vec<CorpusPage>) -> Self {
+        Self { pages }
+    }
+
+    /// Loads the pinned corpus from `dir`, fetching pages that are not cached yet.
+    ///
+    /// `fetch` receives the snapshot URL of each missing page and returns its body. Fetched
+    /// pages are written to `dir`, and their checksums are added to `corpus.lock`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CorpusError::Fetch`] if `fetch` fails, [`CorpusError::ChecksumMismatch`] if
+    /// a page differs from its recorded checksum, and [`CorpusError::Io`] on file errors.
+    pub fn load_or_fetch<F, E>(dir: &Path, mut fetch: F) -> Result<Self, CorpusError>
+    where
+        F: FnMut(&str) -> Result<String, E>,
+        E: fmt::Display,
+    {
+        let fetch: FetchFn<'_> = &mut |url| fetch(url).map_err(|e| e.to_string());
+        Self::load_pinned(dir, Some(fetch))
+    }
+
+    /// Loads the pinned corpus from `dir` without fetching anything.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CorpusError::Missing`] if a page is not cached, and otherwise the same
+    /// errors as [`load_or_fetch`](Self::load_or_fetch).
+    pub fn load(dir: &Path) -> Result<Self, CorpusError> {
+        Self::load_pinned(dir, None)
+    }
+
+    fn load_pinned(dir: &Path, mut fetch: Option<FetchFn<'_>>) -> Result<Self, CorpusError> {
+        let lock_path = dir.join(LOCK_FILE);
+        let mut lock = match fs::read_to_string(&lock_path) {
+            Ok(content) => parse_lock(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(source) => return Err(CorpusError::Io { path: lock_path, source }),
+        };
+        let lock_len = lock.len();
+
+        let mut pages = Vec::with_capacity(PINNED_PAGES.len());
+        for pinned in PINNED_PAGES {
+            let path = dir.join(format!("{}.html", pinned.name));
+            let html = match fs::read_to_string(&path) {
+                Ok(html) => html,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    let Some(fetch) = fetch.as_mut() else {
+                        return Err(CorpusError::Missing { name: pinned.name.to_string() });
+                    };
+                    let html = fetch(pinned.url).map_err(|message| CorpusError::Fetch {
+                        name: pinned.name.to_string(),
+                        url: pinned.url.to_string(),
+                        message,
+                    })?;
+                    fs::create_dir_all(dir)
+                        .map_err(|source| CorpusError::Io { path: dir.to_path_buf(), source })?;
+                    fs::write(&path, &html)
+                        .map_err(|source| CorpusError::Io { path: path.clone(), source })?;
+                    html
+                }
+                Err(source) => return Err(CorpusError::Io { path, source }),
+            };
+
+            let page = CorpusPage::new(pinned.name, html);
+            let found = page.checksum();
+            match lock.get(pinned.name) {
+                Some(&expected) if expected != found => {
+                    return Err(CorpusError::ChecksumMismatch {
+                        name: pinned.name.to_string(),
+                        expected,
+                        found,
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    lock.insert(pinned.name.to_string(), found);
+                }
+            }
+            pages.push(page);
+        }
+
+        if lock.len() != lock_len {
+            let content = lock.iter().fold(String::new(), |mut content, (name, sum)| {
+                let _ = writeln!(content, "{name}\t{sum:016x}");
+                content
+            });
+            fs::write(&lock_path, content)
+                .map_err(|source| CorpusError::Io { path: lock_path, source })?;
+        }
+
+        Ok(Self { pages })
+    }
+
+    /// Returns the pages in the corpus.
+    #[must_use]
+    pub fn pages(&self) -> &[CorpusPage] {
+        &self.pages
+    }
+
+    /// Returns the total size of all pages in bytes.
+    #[must_use]
+    pub fn total_bytes(&self) -> usize {
+        self.pages.iter().map(|page| page.html.len()).sum()
+    }
+}
+
+/// Options for [`run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchOptions {
+    /// Number of timed runs per measurement; the median is reported.
+    pub iterations: usize,
+    /// Number of untimed runs before measuring.
+    pub warmup: usize,
+    /// Selectors timed against every page.
+    pub selectors: Vec<String>,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            iterations: 20,
+            warmup: 3,
+            selectors: DEFAULT_SELECTORS.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+/// Median timing of one selector on one page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryTiming {
+    /// Selector source.
+    pub selector: String,
+    /// Number of matching elements.
+    pub matches: usize,
+    /// Median time per query.
+    pub time: Duration,
+}
+
+impl QueryTiming {
+    /// Returns queries per second.
+    #[must_use]
+    pub fn per_second(&self) -> f64 {
+        per_second(self.time)
+    }
+}
+
+/// Measurements for one page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageReport {
+    /// Page name.
+    pub name: String,
+    /// Page size in bytes.
+    pub bytes: usize,
+    /// Median parse time.
+    pub parse: Duration,
+    /// Query timings, in the order of [`BenchOptions::selectors`].
+    pub queries: Vec<QueryTiming>,
+}
+
+impl PageReport {
+    /// Returns parse throughput in MB/s.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn parse_throughput(&self) -> f64 {
+        self.bytes as f64 / 1_000_000.0 * per_second(self.parse)
+    }
+}
+
+/// Result of [`run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BenchReport {
+    /// Per-page measurements, in corpus order.
+    pub pages: Vec<PageReport>,
+}
+
+impl BenchReport {
+    /// Returns the report as baseline metrics, for saving or comparison.
+    #[must_use]
+    pub fn to_baseline(&self) -> Baseline {
+        let mut metrics = BTreeMap::new();
+        for page in &self.pages {
+            metrics.insert(format!("parse/{}", page.name), page.parse_throughput());
+            for query in &page.queries {
+                metrics
+                    .insert(format!("query/{}/{}", page.name, query.selector), query.per_second());
+            }
+        }
+        Baseline { metrics }
+    }
+}
+
+/// Measures parse and query throughput for every page in `corpus`.
+///
+/// Selectors are compiled once, so query timings exclude selector parsing.
+///
+/// # Errors
+///
+/// Returns [`QueryError`](crate::QueryError) if a selector is invalid.
+pub fn run(corpus: &Corpus, options: &BenchOptions) -> QueryResult<BenchReport> {
+    let selectors = options
+        .selectors
+        .iter()
+        .map(|s| CompiledSelector::compile(s))
+        .collect::<QueryResult<Vec<_>>>()?;
+
+    let pages = corpus
+        .pages()
+        .iter()
+        .map(|page| {
+            let parse = median_time(options, || {
+                std::hint::black_box(Soup::parse(&page.html));
+            });
+
+            let soup = Soup::parse(&page.html);
+            let queries = options
+                .selectors
+                .iter()
+                .zip(&selectors)
+                .map(|(source, selector)| QueryTiming {
+                    selector: source.clone(),
+                    matches: soup.select_compiled(selector).len(),
+                    time: median_time(options, || {
+                        std::hint::black_box(soup.select_compiled(selector));
+                    }),
+                })
+                .collect();
+
+            PageReport { name: page.name.clone(), bytes: page.html.len(), parse, queries }
+        })
+        .collect();
+
+    Ok(BenchReport { pages })
+}
+
+/// Saved throughput metrics; higher is better for every metric.
+///
+/// Metric keys are `parse/<page>` in MB/s and `query/<page>/<selector>` in queries per
+/// second. The text form has one `key<TAB>value` line per metric.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Baseline {
+    metrics: BTreeMap<String, f64>,
+}
+
+impl Baseline {
+    /// Parses the text form produced by [`Display`](fmt::Display).
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CorpusError::Malformed`] for lines that are not `key<TAB>number`.
+    pub fn parse(s: &str) -> Result<Self, CorpusError> {
+        let mut metrics = BTreeMap::new();
+        for (i, line) in s.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let value = line
+                .rsplit_once('\t')
+                .and_then(|(key, value)| Some((key, value.trim().parse::<f64>().ok()?)))
+                .filter(|(key, value)| !key.is_empty() && value.is_finite());
+            let Some((key, value)) = value else {
+                return Err(CorpusError::Malformed { line: i + 1, content: line.to_string() });
+            };
+            metrics.insert(key.to_string(), value);
+        }
+        Ok(Self { metrics })
+    }
+
+    /// Returns the value of a metric.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<f64> {
+        self.metrics.get(key).copied()
+    }
+
+    /// Returns the number of metrics.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.metrics.len()
+    }
+
+    /// Returns `true` if there are no metrics.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+
+    /// Returns metrics in `current` that fell more than `max_regression` below this baseline.
+    ///
+    /// `max_regression` is a fraction, e.g. `0.1` tolerates a 10% slowdown. Metrics missing
+    /// from either side are not compared.
+    #[must_use]
+    pub fn regressions(&self, current: &Baseline, max_regression: f64) -> Vec<Regression> {
+        self.metrics
+            .iter()
+            .filter_map(|(key, &baseline)| {
+                let current = current.get(key)?;
+                (current < baseline * (1.0 - max_regression)).then(|| Regression {
+                    metric: key.clone(),
+                    baseline,
+                    current,
+                })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.metrics {
+            writeln!(f, "{key}\t{value:.3}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A metric that regressed beyond the allowed threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    /// Metric key.
+    pub metric: String,
+    /// Baseline value.
+    pub baseline: f64,
+    /// Current value.
+    pub current: f64,
+}
+
+impl Regression {
+    /// Returns the relative change, e.g. `-0.25` for a 25% slowdown.
+    #[must_use]
+    pub fn change(&self) -> f64 {
+        self.current / self.baseline - 1.0
+    }
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.3} -> {:.3} ({:+.1}%)",
+            self.metric,
+            self.baseline,
+            self.current,
+            self.change() * 100.0
+        )
+    }
+}
+
+/// Runs `f` `warmup` times untimed, then returns the median of `iterations` timed runs.
+fn median_time(options: &BenchOptions, mut f: impl FnMut()) -> Duration {
+    for _ in 0..options.warmup {
+        f();
+    }
+    let mut times: Vec<Duration> = (0..options.iterations.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect();
+    times.sort_unstable();
+    times[times.len() / 2]
+}
+
+fn per_second(time: Duration) -> f64 {
+    1.0 / time.as_secs_f64().max(f64::MIN_POSITIVE)
+}
+
+fn parse_lock(content: &str) -> Result<BTreeMap<String, u64>, CorpusError> {
+    let mut lock = BTreeMap::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let entry = line
+            .split_once('\t')
+            .and_then(|(name, sum)| Some((name, u64::from_str_radix(sum.trim(), 16).ok()?)));
+        let Some((name, sum)) = entry else {
+            return Err(CorpusError::Malformed { line: i + 1, content: line.to_string() });
+        };
+        lock.insert(name.to_string(), sum);
+    }
+    Ok(lock)
+}
+
+/// 64-bit FNV-1a, stable across platforms and Rust versions.
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("scrape-bench-corpus-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn fake_page(url: &str) -> String {
+        format!("<html><body><a href=\"{url}\">link</a><p>text</p></body></html>")
+    }
+
+    #[test]
+    fn test_checksum_is_fnv1a() {
+        assert_eq!(checksum(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(checksum(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_load_or_fetch_caches_and_pins() {
+        let dir = temp_dir("pins");
+        let mut fetched = Vec::new();
+        let corpus = Corpus::load_or_fetch(&dir, |url| {
+            fetched.push(url.to_string());
+            Ok::<_, String>(fake_page(url))
+        })
+        .unwrap();
+        assert_eq!(fetched.len(), PINNED_PAGES.len());
+        assert_eq!(corpus.pages().len(), PINNED_PAGES.len());
+        assert!(dir.join(LOCK_FILE).exists());
+
+        let cached = Corpus::load(&dir).unwrap();
+        assert_eq!(cached, corpus);
+
+        fs::write(dir.join("wikipedia.html"), "<p>changed</p>").unwrap();
+        let err = Corpus::load(&dir).unwrap_err();
+        assert!(
+            matches!(err, CorpusError::ChecksumMismatch { ref name, .. } if name == "wikipedia")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_without_cache() {
+        let dir = temp_dir("missing");
+        assert!(matches!(Corpus::load(&dir), Err(CorpusError::Missing { .. })));
+
+        let err = Corpus::load_or_fetch(&dir, |_| Err("offline")).unwrap_err();
+        assert!(err.to_string().contains("offline"));
+    }
+
+    #[test]
+    fn test_run_reports_matches() {
+        let corpus = Corpus::new(vec![
+            CorpusPage::new("one", "<div><p>a</p><p>b</p></div>"),
+            CorpusPage::new("two", "<a href=\"/\">x</a>"),
+        ]);
+        let options = BenchOptions {
+            iterations: 3,
+            warmup: 1,
+            selectors: vec!["div p".into(), "a[href]".into()],
+        };
+        let report = run(&corpus, &options).unwrap();
+
+        assert_eq!(report.pages.len(), 2);
+        assert_eq!(report.pages[0].bytes, corpus.pages()[0].html.len());
+        let matches: Vec<_> = report
+            .pages
+            .iter()
+            .map(|p| p.queries.iter().map(|q| q.matches).collect::<Vec<_>>())
+            .collect();
+        assert_eq!(matches, [[2, 0], [0, 1]]);
+
+        let baseline = report.to_baseline();
+        assert_eq!(baseline.len(), 6);
+        assert!(baseline.get("parse/one").is_some());
+        assert!(baseline.get("query/two/a[href]").is_some());
+    }
+
+    #[test]
+    fn test_run_invalid_selector() {
+        let corpus = Corpus::new(vec![CorpusPage::new("one", "<p></p>")]);
+        let options = BenchOptions { selectors: vec!["[".into()], ..BenchOptions::default() };
+        assert!(run(&corpus, &options).is_err());
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let baseline =
+            Baseline::parse("# comment\nparse/a\t120.5\n\nquery/a/div p\t9000\n").unwrap();
+        assert_eq!(baseline.get("query/a/div p"), Some(9000.0));
+        assert_eq!(Baseline::parse(&baseline.to_string()).unwrap(), baseline);
+
+        let err = Baseline::parse("parse/a\tfast").unwrap_err();
+        assert!(matches!(err, CorpusError::Malformed { line: 1, .. }));
+    }
+
+    #[test]
+    fn test_baseline_regressions() {
+        let baseline = Baseline::parse("parse/a\t100\nparse/b\t100\nparse/c\t100\n").unwrap();
+        let current = Baseline::parse("parse/a\t95\nparse/b\t80\nparse/d\t1\n").unwrap();
+
+        let regressions = baseline.regressions(&current, 0.1);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].metric, "parse/b");
+        assert!((regressions[0].change() + 0.2).abs() < 1e-9);
+        assert_eq!(regressions[0].to_string(), "parse/b: 100.000 -> 80.000 (-20.0%)");
+
+        assert!(baseline.regressions(&current, 0.25).is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+//! Process-wide cache of parsed documents, keyed by a hash of their HTML.
+//!
+//! Services that receive the same payloads over and over, such as templated error pages
+//! or responses replayed from a CDN, can parse through [`parse`] instead of
+//! [`Soup::parse`]. Once [`enable`] gives the cache a capacity, parsing HTML that is
+//! already cached returns the shared [`Soup`] without parsing it again.
+//!
+//! The capacity is measured in bytes of cached HTML source; the least recently used
+//! documents are evicted to stay within it. Documents larger than the whole capacity are
+//! parsed but not cached. The cache is disabled until [`enable`] is called, in which case
+//! [`parse`] simply parses.
+//!
+//! A hit requires the same HTML and the same [`SoupConfig`], so a hash collision can never
+//! return the wrong document.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::sync::Arc;
+//!
+//! use scrape_core::cache;
+//!
+//! cache::enable(1 << 20);
+//! let first = cache::parse("<h1>Service unavailable</h1>");
+//! let second = cache::parse("<h1>Service unavailable</h1>");
+//! assert!(Arc::ptr_eq(&first, &second));
+//! assert_eq!(second.find("h1").unwrap().unwrap().text(), "Service unavailable");
+//! cache::disable();
+//! ```
+
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{Soup, SoupConfig};
+
+static CACHE: Mutex<ParseCache> = Mutex::new(ParseCache::new(0));
+
+/// Counters describing the cache, as returned by [`stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of cached documents.
+    pub entries: usize,
+    /// Bytes of HTML source held by the cached documents.
+    pub bytes: usize,
+    /// The capacity in bytes; `0` while the cache is disabled.
+    pub capacity_bytes: usize,
+    /// Number of [`parse`] calls answered from the cache.
+    pub hits: u64,
+    /// Number of [`parse`] calls that had to parse while the cache was enabled.
+    pub misses: u64,
+}
+
+/// Least recently used map from HTML hash to parsed document.
+struct ParseCache {
+    entries: Option<HashMap<u64, Entry>>,
+    capacity: usize,
+    bytes: usize,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+struct Entry {
+    html: Box<str>,
+    config: SoupConfig,
+    soup: Arc<Soup>,
+    last_used: u64,
+}
+
+impl ParseCache {
+    const fn new(capacity: usize) -> Self {
+        Self { entries: None, capacity, bytes: 0, clock: 0, hits: 0, misses: 0 }
+    }
+
+    fn get(&mut self, key: u64, html: &str, config: &SoupConfig) -> Option<Arc<Soup>> {
+        self.clock += 1;
+        let clock = self.clock;
+        let found = self
+            .entries
+            .as_mut()
+            .and_then(|entries| entries.get_mut(&key))
+            .filter(|entry| &*entry.html == html && entry.config == *config)
+            .map(|entry| {
+                entry.last_used = clock;
+                Arc::clone(&entry.soup)
+            });
+        if found.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        found
+    }
+
+    fn insert(&mut self, key: u64, html: &str, config: SoupConfig, soup: Arc<Soup>) {
+        if html.len() > self.capacity {
+            return;
+        }
+        self.remove(key);
+        self.evict_to(self.capacity - html.len());
+        self.clock += 1;
+        self.bytes += html.len();
+        let entry = Entry { html: html.into(), config, soup, last_used: self.clock };
+        self.entries.get_or_insert_with(HashMap::new).insert(key, entry);
+    }
+
+    fn clear(&mut self) {
+        self.entries = None;
+        self.bytes = 0;
+    }
+
+    fn remove(&mut self, key: u64) {
+        if let Some(entry) = self.entries.as_mut().and_then(|entries| entries.remove(&key)) {
+            self.bytes -= entry.html.len();
+        }
+    }
+
+    /// Removes least recently used entries until at most `bytes` of HTML remain.
+    fn evict_to(&mut self, bytes: usize) {
+        while self.bytes > bytes {
+            let Some(entries) = self.entries.as_ref() else { return };
+            let oldest = entries.iter().min_by_key(|(_, entry)| entry.last_used);
+            let Some(&oldest) = oldest.map(|(key, _)| key) else { return };
+            self.remove(oldest);
+        }
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.as_ref().map_or(0, HashMap::len),
+            bytes: self.bytes,
+            capacity_bytes: self.capacity,
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+fn cache() -> MutexGuard<'static, ParseCache> {
+    // The cache holds no invariants a panicking thread could break.
+    CACHE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn key(html: &str, config: &SoupConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    html.hash(&mut hasher);
+    config.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Enables the cache with room for `capacity_bytes` bytes of HTML, evicting the least
+/// recently used documents if it already holds more. A capacity of `0` disables it.
+pub fn enable(capacity_bytes: usize) {
+    let mut cache = cache();
+    cache.capacity = capacity_bytes;
+    cache.evict_to(capacity_bytes);
+}
+
+/// Disables the cache and drops every cached document.
+pub fn disable() {
+    let mut cache = cache();
+    cache.capacity = 0;
+    cache.clear();
+}
+
+/// Returns `true` if [`enable`] gave the cache a non-zero capacity.
+#[must_use]
+pub fn is_enabled() -> bool {
+    cache().capacity > 0
+}
+
+/// Drops every cached document and resets the hit and miss counters, leaving the cache
+/// enabled.
+pub fn clear() {
+    let mut cache = cache();
+    cache.clear();
+    cache.hits = 0;
+    cache.misses = 0;
+}
+
+/// Returns the current size and hit counts of the cache.
+#[must_use]
+pub fn stats() -> CacheStats {
+    cache().stats()
+}
+
+/// Parses `html` with the default configuration, reusing a cached document if the same
+/// HTML was parsed before.
+#[must_use]
+pub fn parse(html: &str) -> Arc<Soup> {
+    parse_with_config(html, SoupConfig::default())
+}
+
+/// Parses `html` with `config`, reusing a cached document if the same HTML was parsed
+/// before with an equal configuration.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use scrape_core::{SoupConfig, cache};
+///
+/// cache::enable(64 * 1024);
+/// let config = SoupConfig::builder().include_comments(true).build();
+/// let with_comments = cache::parse_with_config("<p>Hi<!-- x --></p>", config);
+/// let without = cache::parse("<p>Hi<!-- x --></p>");
+/// assert!(!Arc::ptr_eq(&with_comments, &without));
+/// cache::disable();
+/// ```
+#[must_use]
+pub fn parse_with_config(html: &str, config: SoupConfig) -> Arc<Soup> {
+    if !is_enabled() {
+        return Arc::new(Soup::parse_with_config(html, config));
+    }
+    let key = key(html, &config);
+    let cached = cache().get(key, html, &config);
+    if let Some(soup) = cached {
+        return soup;
+    }
+    // Parse without holding the lock; a concurrent miss on the same HTML just parses it
+    // twice.
+    let soup = Arc::new(Soup::parse_with_config(html, config.clone()));
+    cache().insert(key, html, config, Arc::clone(&soup));
+    soup
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The global cache is shared with every other test, so these use a private one.
+
+    fn insert(cache: &mut ParseCache, html: &str) {
+        let config = SoupConfig::default();
+        cache.insert(key(html, &config), html, config, Arc::new(Soup::parse(html)));
+    }
+
+    fn get(cache: &mut ParseCache, html: &str) -> Option<Arc<Soup>> {
+        let config = SoupConfig::default();
+        cache.get(key(html, &config), html, &config)
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut cache = ParseCache::new(20);
+        insert(&mut cache, "<p>aa</p>");
+        insert(&mut cache, "<p>bb</p>");
+        assert!(get(&mut cache, "<p>aa</p>").is_some());
+        insert(&mut cache, "<i>c</i>");
+
+        assert!(get(&mut cache, "<p>bb</p>").is_none());
+        assert!(get(&mut cache, "<p>aa</p>").is_some());
+        assert!(get(&mut cache, "<i>c</i>").is_some());
+        assert_eq!(cache.stats().entries, 2);
+        assert_eq!(cache.stats().bytes, 17);
+
+        // Documents larger than the capacity are not cached.
+        insert(&mut cache, &"x".repeat(21));
+        assert_eq!(cache.stats().entries, 2);
+        assert_eq!((cache.stats().hits, cache.stats().misses), (3, 1));
+
+        cache.evict_to(0);
+        assert_eq!(
+            cache.stats(),
+            CacheStats { capacity_bytes: 20, hits: 3, misses: 1, ..CacheStats::default() }
+        );
+    }
+
+    #[test]
+    fn test_cache_checks_html_and_config() {
+        let mut cache = ParseCache::new(100);
+        insert(&mut cache, "<p>x</p>");
+        let config = SoupConfig::default();
+        assert!(cache.get(key("<p>x</p>", &config), "<p>y</p>", &config).is_none());
+        let other = SoupConfig::builder().max_depth(3).build();
+        assert!(cache.get(key("<p>x</p>", &config), "<p>x</p>", &other).is_none());
+        assert!(get(&mut cache, "<p>x</p>").is_some());
+    }
+}
@@ -0,0 +1,276 @@
+//! Canonical URLs, AMP and mobile variants, and clustering of duplicate pages.
+//!
+//! [`canonical_url`] (also available as [`Soup::canonical_url`]) returns the absolute URL
+//! of a page's first `<link rel="canonical">`. [`amp_variant_url`] and
+//! [`mobile_variant_url`] return the URLs of a page's AMP and separate mobile versions,
+//! whose simpler markup is often easier to scrape, and [`is_amp`] tells whether a page
+//! is itself an AMP page. [`CanonicalClusters`] groups fetched pages
+//! by that URL, so result sets can be deduplicated by canonical rather than by the URL
+//! they were fetched from: mirrors, tracking-parameter variants, and print views of the
+//! same article land in one cluster.
+//!
+//! Cluster keys are normalized: the scheme and host are lowercased, default ports and
+//! fragments are removed, and an empty path becomes `/`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{Soup, canonical::CanonicalClusters};
+//!
+//! let article = Soup::parse(r#"<link rel="canonical" href="/tea">"#);
+//! let plain = Soup::parse("<p>No canonical</p>");
+//!
+//! let mut clusters = CanonicalClusters::new();
+//! assert!(clusters.insert("https://example.com/tea?utm_source=feed", &article));
+//! assert!(!clusters.insert("https://EXAMPLE.com/print/tea", &article));
+//! assert!(clusters.insert("https://example.com/about", &plain));
+//!
+//! let clusters = clusters.into_clusters();
+//! assert_eq!(clusters.len(), 2);
+//! assert_eq!(clusters[0].canonical, "https://example.com/tea");
+//! assert_eq!(clusters[0].urls.len(), 2);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{Soup, Tag, extract::links::base_url, query::find_all, utils::resolve_url};
+
+/// Pages that share a canonical URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    /// The normalized canonical URL shared by the pages.
+    pub canonical: String,
+    /// URLs of the pages in insertion order; the first is the representative.
+    pub urls: Vec<String>,
+}
+
+/// Groups pages by canonical URL.
+///
+/// A page without a canonical link, or whose canonical link cannot be resolved, is
+/// keyed by its own URL.
+#[derive(Debug, Clone, Default)]
+pub struct CanonicalClusters {
+    clusters: Vec<Cluster>,
+    index: HashMap<String, usize>,
+}
+
+impl CanonicalClusters {
+    /// Creates an empty set of clusters.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the page fetched from `url` to the cluster of its canonical URL.
+    ///
+    /// Returns `true` if the page starts a new cluster, `false` if it duplicates a page
+    /// added before.
+    pub fn insert(&mut self, url: &str, soup: &Soup) -> bool {
+        let key = canonical_url(soup, Some(url)).unwrap_or_else(|| url.to_string());
+        self.insert_with_canonical(url, &key)
+    }
+
+    /// Adds a page whose canonical URL is already known, such as from a `Link` header.
+    ///
+    /// Returns `true` if the page starts a new cluster.
+    pub fn insert_with_canonical(&mut self, url: &str, canonical: &str) -> bool {
+        let key = normalize(canonical);
+        if let Some(&i) = self.index.get(&key) {
+            self.clusters[i].urls.push(url.to_string());
+            return false;
+        }
+        self.index.insert(key.clone(), self.clusters.len());
+        self.clusters.push(Cluster { canonical: key, urls: vec![url.to_string()] });
+        true
+    }
+
+    /// Returns the cluster containing pages with this canonical URL.
+    #[must_use]
+    pub fn get(&self, canonical: &str) -> Option<&Cluster> {
+        self.index.get(&normalize(canonical)).map(|&i| &self.clusters[i])
+    }
+
+    /// Returns the number of clusters.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.clusters.len()
+    }
+
+    /// Returns `true` if no page was added.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    /// Returns the clusters in order of their first page.
+    #[must_use]
+    pub fn clusters(&self) -> &[Cluster] {
+        &self.clusters
+    }
+
+    /// Consumes the set and returns the clusters in order of their first page.
+    #[must_use]
+    pub fn into_clusters(self) -> Vec<Cluster> {
+        self.clusters
+    }
+}
+
+/// Returns the absolute URL of the document's first `<link rel="canonical">`.
+///
+/// The `href` is resolved against `<base href>` and `base_url` like
+/// [`links`](crate::extract::links::links). Returns `None` if there is no canonical
+/// link or it cannot be made absolute.
+#[must_use]
+pub fn canonical_url(soup: &Soup, base_url: Option<&str>) -> Option<String> {
+    link_url(soup, base_url, "canonical", |_| true)
+}
+
+/// Returns the absolute URL of the document's first `<link rel="amphtml">`, the AMP
+/// version of the page.
+///
+/// The `href` is resolved like [`canonical_url`].
+#[must_use]
+pub fn amp_variant_url(soup: &Soup, base_url: Option<&str>) -> Option<String> {
+    link_url(soup, base_url, "amphtml", |_| true)
+}
+
+/// Returns the absolute URL of the document's separate mobile version.
+///
+/// This is the first `<link rel="alternate">` whose `media` query targets small
+/// screens, such as `only screen and (max-width: 640px)`, or `handheld` devices. The
+/// `href` is resolved like [`canonical_url`].
+#[must_use]
+pub fn mobile_variant_url(soup: &Soup, base_url: Option<&str>) -> Option<String> {
+    link_url(soup, base_url, "alternate", |tag| {
+        tag.get("media").is_some_and(|media| {
+            let media = media.to_ascii_lowercase();
+            media.contains("max-width") || media.contains("handheld")
+        })
+    })
+}
+
+/// Returns `true` if the document is an AMP page, marked by an `amp` or `⚡`
+/// attribute on `<html>`.
+#[must_use]
+pub fn is_amp(soup: &Soup) -> bool {
+    soup.root().is_some_and(|html| {
+        html.name() == Some("html") && (html.has_attr("amp") || html.has_attr("⚡"))
+    })
+}
+
+/// Returns the resolved `href` of the first `<link>` with the `rel` token that matches
+/// `filter`.
+fn link_url(
+    soup: &Soup,
+    base_url: Option<&str>,
+    rel: &str,
+    filter: impl Fn(&Tag<'_>) -> bool,
+) -> Option<String> {
+    let doc = soup.document();
+    let href = find_all(doc, "link[rel][href]")
+        .unwrap_or_default()
+        .into_iter()
+        .map(|id| Tag::new(doc, id))
+        .find(|tag| tag.attr_list("rel").any(|t| t.eq_ignore_ascii_case(rel)) && filter(tag))?
+        .get("href")?
+        .trim()
+        .to_string();
+    if href.is_empty() {
+        return None;
+    }
+    let base = self::base_url(soup, base_url);
+    resolve_url(base.as_deref().unwrap_or(&href), &href)
+}
+
+/// Normalizes a URL for use as a cluster key.
+fn normalize(url: &str) -> String {
+    let url = url.trim();
+    let url = url.split('#').next().unwrap_or_default();
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let scheme = scheme.to_ascii_lowercase();
+    let end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(end);
+
+    let mut authority = authority.to_ascii_lowercase();
+    let default_port = match scheme.as_str() {
+        "http" => Some(":80"),
+        "https" => Some(":443"),
+        _ => None,
+    };
+    if let Some(port) = default_port
+        && authority.ends_with(port)
+    {
+        authority.truncate(authority.len() - port.len());
+    }
+    let path = if path.is_empty() || path.starts_with('?') {
+        format!("/{path}")
+    } else {
+        path.to_string()
+    };
+    format!("{scheme}://{authority}{path}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_url() {
+        let soup = Soup::parse(
+            "<base href=\"https://example.com/blog/\"><link rel=\"alternate\" \
+             href=\"/feed\"><link rel=\"Canonical\" href=\" post-1 \"><link rel=\"canonical\" \
+             href=\"/ignored\">",
+        );
+        assert_eq!(canonical_url(&soup, None).as_deref(), Some("https://example.com/blog/post-1"));
+
+        let soup = Soup::parse("<link rel=\"canonical\" href=\"/tea\">");
+        assert_eq!(canonical_url(&soup, None), None);
+        assert_eq!(
+            canonical_url(&soup, Some("https://example.com/a/b")).as_deref(),
+            Some("https://example.com/tea")
+        );
+        assert_eq!(canonical_url(&Soup::parse("<link rel=\"canonical\" href=\"\">"), None), None);
+    }
+
+    #[test]
+    fn test_variants() {
+        let soup = Soup::parse(
+            "<link rel=\"alternate\" hreflang=\"de\" href=\"/de/tea\"><link rel=\"alternate\" \
+             media=\"only screen and (MAX-WIDTH: 640px)\" href=\"/m/tea\"><link rel=\"AmpHtml\" \
+             href=\"/amp/tea\">",
+        );
+        let base = Some("https://example.com/tea");
+        assert_eq!(amp_variant_url(&soup, base).as_deref(), Some("https://example.com/amp/tea"));
+        assert_eq!(mobile_variant_url(&soup, base).as_deref(), Some("https://example.com/m/tea"));
+        assert!(!is_amp(&soup));
+
+        assert!(is_amp(&Soup::parse("<html amp><p>Tea</p></html>")));
+        assert!(is_amp(&Soup::parse("<html \u{26a1} lang=\"en\"><p>Tea</p></html>")));
+        assert_eq!(mobile_variant_url(&Soup::parse("<p>Tea</p>"), base), None);
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("HTTPS://Example.COM:443"), "https://example.com/");
+        assert_eq!(normalize("http://example.com:8080/A?b=1#top"), "http://example.com:8080/A?b=1");
+        assert_eq!(normalize("http://example.com:80?q"), "http://example.com/?q");
+        assert_eq!(normalize("urn:isbn:123"), "urn:isbn:123");
+    }
+
+    #[test]
+    fn test_clusters() {
+        let mut clusters = CanonicalClusters::new();
+        assert!(clusters.is_empty());
+        assert!(clusters.insert_with_canonical("https://a.example/1", "https://example.com/x"));
+        assert!(!clusters.insert_with_canonical("https://b.example/1", "https://example.com/x#a"));
+        assert!(clusters.insert("https://example.com/y", &Soup::parse("<p>y</p>")));
+        assert!(!clusters.insert("https://example.com/y#comments", &Soup::parse("<p>y</p>")));
+
+        assert_eq!(clusters.len(), 2);
+        let cluster = clusters.get("https://EXAMPLE.com/x").unwrap();
+        assert_eq!(cluster.urls, ["https://a.example/1", "https://b.example/1"]);
+        assert_eq!(clusters.clusters()[1].canonical, "https://example.com/y");
+    }
+}
@@ -0,0 +1,597 @@
+//! Conversion of HTML subtrees to Markdown.
+//!
+//! [`to_markdown`] (also available as [`Tag::to_markdown`] and
+//! [`Soup::to_markdown`](crate::Soup::to_markdown)) renders an element and its descendants
+//! as [CommonMark] with the table extension from GitHub Flavored Markdown:
+//!
+//! - `<h1>`–`<h6>` as `#` headings, `<p>` and other blocks as paragraphs
+//! - `<ul>` and `<ol>` as `-` and numbered lists, nested by indentation
+//! - `<blockquote>` as `>` quotes, `<hr>` as `---`, `<br>` as hard line breaks
+//! - `<pre>` as fenced code blocks, with the language from a `language-*` or `lang-*` class;
+//!   `<code>` as inline code
+//! - `<strong>`/`<b>`, `<em>`/`<i>`, and `<del>`/`<s>` as `**`, `*`, and `~~`
+//! - `<table>` as a pipe table, with spans resolved like
+//!   [`extract::table`](mod@crate::extract::table)
+//! - links and images as configured by [`MarkdownOptions`]
+//!
+//! Whitespace is collapsed as a browser would, Markdown punctuation in text is escaped,
+//! and `<head>`, scripts, and styles are left out. The default parse drops whitespace-only
+//! text, such as the space in `<b>a</b> <i>b</i>`; parse with
+//! [`SoupConfig::preserve_whitespace`](crate::SoupConfig::preserve_whitespace) to
+//! keep the spaces between inline elements.
+//!
+//! [CommonMark]: https://commonmark.org
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{
+//!     Soup,
+//!     convert::{LinkStyle, MarkdownOptions},
+//! };
+//!
+//! let soup = Soup::parse(
+//!     r#"<h2>Install</h2>
+//!        <p>Run <code>cargo add scrape-core</code>, then see the <a href="/docs">docs</a>.</p>
+//!        <pre><code class="language-rust">let soup = Soup::parse(html);</code></pre>"#,
+//! );
+//! assert_eq!(
+//!     soup.to_markdown(),
+//!     "## Install\n\nRun `cargo add scrape-core`, then see the [docs](/docs).\n\n```rust\nlet \
+//!      soup = Soup::parse(html);\n```"
+//! );
+//!
+//! let options = MarkdownOptions {
+//!     links: LinkStyle::Reference,
+//!     base_url: Some("https://example.com/".into()),
+//!     ..MarkdownOptions::default()
+//! };
+//! let p = soup.find("p").unwrap().unwrap();
+//! assert_eq!(
+//!     p.to_markdown_with_options(&options),
+//!     "Run `cargo add scrape-core`, then see the [docs][1].\n\n[1]: https://example.com/docs"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::{Document, NodeId, NodeKind, Tag, serialize::collect_text, utils::resolve_url};
+
+/// How links are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LinkStyle {
+    /// `[text](url)`.
+    #[default]
+    Inline,
+    /// `[text][1]`, with `[1]: url` definitions after the content.
+    Reference,
+    /// The link text only.
+    Text,
+}
+
+/// How images are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageStyle {
+    /// `![alt](src)`.
+    #[default]
+    Inline,
+    /// The `alt` text only.
+    Alt,
+    /// Nothing.
+    Omit,
+}
+
+/// Options for [`to_markdown`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarkdownOptions {
+    /// How links are written.
+    pub links: LinkStyle,
+    /// How images are written.
+    pub images: ImageStyle,
+    /// Base URL for resolving relative link and image URLs; relative URLs are kept as
+    /// written when `None`.
+    pub base_url: Option<String>,
+}
+
+/// Renders an element and its descendants as Markdown.
+///
+/// See the [module documentation](self) for the supported elements.
+#[must_use]
+pub fn to_markdown(doc: &Document, node: NodeId, options: &MarkdownOptions) -> String {
+    render(doc, node, true, options, is_hidden)
+}
+
+/// Renders an element as plain text or Markdown, leaving out elements for which `skip`
+/// returns `true`.
+///
+/// Plain text keeps the block structure, with blank lines between paragraphs and line
+/// breaks between list items, but no markup.
+pub(crate) fn render(
+    doc: &Document,
+    node: NodeId,
+    markdown: bool,
+    options: &MarkdownOptions,
+    skip: fn(&Document, NodeId) -> bool,
+) -> String {
+    let mut renderer = Renderer {
+        doc,
+        markdown,
+        options,
+        skip,
+        out: String::new(),
+        line: String::new(),
+        lists: Vec::new(),
+        quotes: 0,
+        marker: None,
+        last_in_list: false,
+        references: Vec::new(),
+    };
+    renderer.node(node);
+    renderer.flush();
+    renderer.finish()
+}
+
+/// Elements that are never rendered by [`to_markdown`].
+///
+/// Template content is held outside the tree, so only declarative shadow roots attached
+/// with [`SoupConfig::pierce_shadow`](crate::SoupConfig::pierce_shadow) are rendered.
+fn is_hidden(doc: &Document, node: NodeId) -> bool {
+    doc.get(node)
+        .and_then(|n| n.kind.tag_name())
+        .is_some_and(|name| matches!(name, "head" | "script" | "style" | "noscript"))
+}
+
+struct Renderer<'a> {
+    doc: &'a Document,
+    markdown: bool,
+    options: &'a MarkdownOptions,
+    skip: fn(&Document, NodeId) -> bool,
+    out: String,
+    /// Inline content of the current block; `\n` marks a `<br>`.
+    line: String,
+    /// Open lists: `Some(n)` for ordered lists numbering the next item `n`.
+    lists: Vec<Option<usize>>,
+    /// Blockquote nesting depth.
+    quotes: usize,
+    /// List marker for the next block, such as `- ` or `2. `.
+    marker: Option<String>,
+    /// Whether the last block was a list item, so the next item follows without a blank
+    /// line.
+    last_in_list: bool,
+    /// Link URLs in order of first use, for [`LinkStyle::Reference`].
+    references: Vec<String>,
+}
+
+impl Renderer<'_> {
+    fn finish(mut self) -> String {
+        if !self.references.is_empty() {
+            if !self.out.is_empty() {
+                self.out.push_str("\n\n");
+            }
+            let definitions: Vec<_> = self
+                .references
+                .iter()
+                .enumerate()
+                .map(|(i, url)| format!("[{}]: {}", i + 1, destination(url)))
+                .collect();
+            self.out.push_str(&definitions.join("\n"));
+        }
+        self.out
+    }
+
+    fn children(&mut self, node: NodeId) {
+        for child in self.doc.children(node) {
+            self.node(child);
+        }
+    }
+
+    fn node(&mut self, node: NodeId) {
+        let doc = self.doc;
+        let Some(kind) = doc.get(node).map(|n| &n.kind) else { return };
+        let name = match kind {
+            NodeKind::Text { content } => {
+                let text = content.replace('\n', " ");
+                if self.markdown {
+                    self.line.push_str(&escape(&text));
+                } else {
+                    self.line.push_str(&text);
+                }
+                return;
+            }
+            NodeKind::Comment { .. } | NodeKind::Doctype { .. } => return,
+            NodeKind::Element { name, .. } => name.as_str(),
+        };
+        if (self.skip)(doc, node) {
+            return;
+        }
+
+        match name {
+            "br" => self.line.push('\n'),
+            "hr" => {
+                self.flush();
+                if self.markdown {
+                    self.emit("---");
+                }
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.flush();
+                self.children(node);
+                if self.markdown && !self.line.trim().is_empty() {
+                    let level = usize::from(name.as_bytes()[1] - b'0');
+                    self.line.insert_str(0, &format!("{} ", "#".repeat(level)));
+                }
+                self.flush();
+            }
+            "ul" | "ol" => {
+                self.flush();
+                let start = self.attr(node, "start").and_then(|s| s.trim().parse().ok());
+                self.lists.push((name == "ol").then(|| start.unwrap_or(1)));
+                self.children(node);
+                self.flush();
+                self.lists.pop();
+            }
+            "li" => {
+                self.flush();
+                if self.markdown {
+                    self.marker = Some(match self.lists.last_mut() {
+                        Some(Some(n)) => {
+                            *n += 1;
+                            format!("{}. ", *n - 1)
+                        }
+                        _ => "- ".to_string(),
+                    });
+                }
+                self.children(node);
+                self.flush();
+            }
+            "blockquote" => {
+                self.flush();
+                self.quotes += 1;
+                self.children(node);
+                self.flush();
+                self.quotes -= 1;
+            }
+            "pre" => self.pre(node),
+            "table" if self.markdown => self.table(node),
+            "td" | "th" => {
+                self.children(node);
+                self.line.push(' ');
+            }
+            "p" | "div" | "section" | "article" | "main" | "header" | "footer" | "nav"
+            | "aside" | "figure" | "figcaption" | "table" | "caption" | "tr" | "dl" | "dt"
+            | "dd" | "address" | "details" | "summary" => {
+                self.flush();
+                self.children(node);
+                self.flush();
+            }
+            _ if !self.markdown => self.children(node),
+            "a" => self.link(node),
+            "img" => self.image(node),
+            "strong" | "b" => self.wrap(node, "**"),
+            "em" | "i" => self.wrap(node, "*"),
+            "del" | "s" | "strike" => self.wrap(node, "~~"),
+            "code" | "kbd" | "samp" => self.code(node),
+            _ => self.children(node),
+        }
+    }
+
+    fn pre(&mut self, node: NodeId) {
+        self.flush();
+        let mut code = String::new();
+        collect_text(self.doc, node, &mut code);
+        let code = code.trim_end_matches('\n');
+        if self.markdown {
+            let fence = "`".repeat(longest_run(code, '`').max(2) + 1);
+            let language = self.language(node).unwrap_or_default();
+            self.emit(&format!("{fence}{language}\n{code}\n{fence}"));
+        } else {
+            self.emit(code);
+        }
+    }
+
+    fn table(&mut self, node: NodeId) {
+        self.flush();
+        if let Some(table) = Tag::new(self.doc, node).as_table().filter(|table| table.width() > 0) {
+            if let Some(caption) = &table.caption {
+                self.emit(&escape(caption));
+            }
+            self.emit(&table.to_markdown());
+        } else {
+            self.children(node);
+            self.flush();
+        }
+    }
+
+    fn code(&mut self, node: NodeId) {
+        let mut code = String::new();
+        collect_text(self.doc, node, &mut code);
+        let code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !code.is_empty() {
+            let ticks = "`".repeat(longest_run(&code, '`') + 1);
+            let pad = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+            let _ = write!(self.line, "{ticks}{pad}{code}{pad}{ticks}");
+        }
+    }
+
+    fn link(&mut self, node: NodeId) {
+        let href = self.attr(node, "href").filter(|href| is_link(href));
+        let (Some(href), false) = (href, self.options.links == LinkStyle::Text) else {
+            self.children(node);
+            return;
+        };
+
+        let start = self.line.len();
+        self.line.push('[');
+        self.children(node);
+        if self.line[start + 1..].trim().is_empty() {
+            self.line.truncate(start);
+            return;
+        }
+        let url = self.url(&href);
+        if self.options.links == LinkStyle::Reference {
+            let n = if let Some(i) = self.references.iter().position(|r| *r == url) {
+                i + 1
+            } else {
+                self.references.push(url);
+                self.references.len()
+            };
+            let _ = write!(self.line, "][{n}]");
+        } else {
+            let _ = write!(self.line, "]({})", destination(&url));
+        }
+    }
+
+    fn image(&mut self, node: NodeId) {
+        let alt = self.attr(node, "alt").unwrap_or_default();
+        let alt = escape(&alt.split_whitespace().collect::<Vec<_>>().join(" "));
+        match self.options.images {
+            ImageStyle::Inline => {
+                if let Some(src) = self.attr(node, "src").filter(|src| !src.trim().is_empty()) {
+                    let src = self.url(&src);
+                    let _ = write!(self.line, "![{alt}]({})", destination(&src));
+                }
+            }
+            ImageStyle::Alt => self.line.push_str(&alt),
+            ImageStyle::Omit => {}
+        }
+    }
+
+    fn wrap(&mut self, node: NodeId, delimiter: &str) {
+        let start = self.line.len();
+        self.children(node);
+        let inner = self.line.split_off(start);
+        // Delimiters must hug the text, so surrounding spaces stay outside.
+        let trimmed = inner.trim();
+        if trimmed.is_empty() {
+            self.line.push_str(&inner);
+            return;
+        }
+        if inner.starts_with(char::is_whitespace) {
+            self.line.push(' ');
+        }
+        let _ = write!(self.line, "{delimiter}{trimmed}{delimiter}");
+        if inner.ends_with(char::is_whitespace) {
+            self.line.push(' ');
+        }
+    }
+
+    fn attr(&self, node: NodeId, name: &str) -> Option<String> {
+        match &self.doc.get(node)?.kind {
+            NodeKind::Element { attributes, .. } => attributes.get(name).map(str::to_string),
+            _ => None,
+        }
+    }
+
+    /// Returns the language of a code block from a `language-*` or `lang-*` class on the
+    /// `<pre>` or its `<code>` child.
+    fn language(&self, pre: NodeId) -> Option<String> {
+        let code = self
+            .doc
+            .children(pre)
+            .find(|&child| self.doc.get(child).and_then(|n| n.kind.tag_name()) == Some("code"));
+        [Some(pre), code].into_iter().flatten().find_map(|node| {
+            let class = self.attr(node, "class")?;
+            class.split_ascii_whitespace().find_map(|token| {
+                let language =
+                    token.strip_prefix("language-").or_else(|| token.strip_prefix("lang-"))?;
+                (!language.is_empty()).then(|| language.to_string())
+            })
+        })
+    }
+
+    fn url(&self, reference: &str) -> String {
+        let reference = reference.trim();
+        self.options
+            .base_url
+            .as_deref()
+            .and_then(|base| resolve_url(base, reference))
+            .unwrap_or_else(|| reference.to_string())
+    }
+
+    /// Ends the current block, collapsing whitespace within each line. Line breaks
+    /// become Markdown hard breaks.
+    fn flush(&mut self) {
+        let lines: Vec<String> = self.line.split('\n').map(collapse).collect();
+        self.line.clear();
+        let Some(first) = lines.iter().position(|line| !line.is_empty()) else { return };
+        let last = lines.iter().rposition(|line| !line.is_empty()).unwrap_or(first);
+        let block = lines[first..=last].join(if self.markdown { "\\\n" } else { "\n" });
+        self.emit(&block);
+    }
+
+    /// Appends a block, separated from the previous one by a blank line, or by a line
+    /// break between list items.
+    fn emit(&mut self, block: &str) {
+        let in_list = !self.lists.is_empty();
+        if !self.out.is_empty() {
+            self.out.push_str(if in_list && self.last_in_list { "\n" } else { "\n\n" });
+        }
+        self.last_in_list = in_list;
+        if !self.markdown {
+            self.out.push_str(block);
+            return;
+        }
+
+        let quote = "> ".repeat(self.quotes);
+        let mut indent = "  ".repeat(self.lists.len().saturating_sub(1));
+        let marker = self.marker.take();
+        if in_list && marker.is_none() {
+            indent.push_str("  ");
+        }
+        for (i, line) in block.lines().enumerate() {
+            if i > 0 {
+                self.out.push('\n');
+            }
+            self.out.push_str(&quote);
+            self.out.push_str(&indent);
+            match &marker {
+                Some(marker) if i == 0 => self.out.push_str(marker),
+                Some(marker) => self.out.push_str(&" ".repeat(marker.len())),
+                None => {}
+            }
+            self.out.push_str(line);
+        }
+    }
+}
+
+// ==================== Helpers ====================
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Escapes characters that would otherwise start Markdown emphasis, code, or links.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Wraps a link destination in `<...>` if it contains spaces or parentheses.
+fn destination(url: &str) -> String {
+    if url.contains([' ', '(', ')']) { format!("<{url}>") } else { url.to_string() }
+}
+
+fn longest_run(text: &str, c: char) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for ch in text.chars() {
+        current = if ch == c { current + 1 } else { 0 };
+        longest = longest.max(current);
+    }
+    longest
+}
+
+fn is_link(href: &str) -> bool {
+    let href = href.trim();
+    !href.is_empty()
+        && !href.starts_with('#')
+        && !href.to_ascii_lowercase().starts_with("javascript:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Soup, SoupConfig};
+
+    /// Parses `html` keeping the whitespace-only text between inline elements.
+    fn parse(html: &str) -> Soup {
+        Soup::parse_with_config(html, SoupConfig::builder().preserve_whitespace(true).build())
+    }
+
+    fn markdown(html: &str) -> String {
+        parse(html).to_markdown()
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(
+            markdown(
+                "<head><title>Skipped</title></head><h1>Title</h1><p>One\n  \
+                 two</p><hr><blockquote><p>Quote</p><p>More</p></blockquote><p>a<br>b</p>"
+            ),
+            "# Title\n\nOne two\n\n---\n\n> Quote\n\n> More\n\na\\\nb"
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(
+            markdown("<ol start=\"9\"><li>Nine<ul><li>Sub</li></ul></li><li>Ten</li></ol>"),
+            "9. Nine\n  - Sub\n10. Ten"
+        );
+    }
+
+    #[test]
+    fn test_inline() {
+        assert_eq!(
+            markdown(
+                "<p>Use <b> bold </b>, <em>em</em>, <del>old</del>, <code>a`b</code>, and 2*3_x \
+                 [sic].</p>"
+            ),
+            "Use **bold** , *em*, ~~old~~, ``a`b``, and 2\\*3\\_x \\[sic\\]."
+        );
+    }
+
+    #[test]
+    fn test_code_block() {
+        assert_eq!(
+            markdown("<pre class=\"lang-py\">print(1)\n```\n</pre>"),
+            "````py\nprint(1)\n```\n````"
+        );
+    }
+
+    #[test]
+    fn test_links_and_images() {
+        let html = "<p><a href=\"/a b\">A</a> <a href=\"#top\">Top</a> <a href=\"/x\"><img \
+                    src=\"i.png\" alt=\"Logo\"></a> <a href=\"/a b\">again</a> <a \
+                    href=\"/e\"></a></p>";
+        assert_eq!(markdown(html), "[A](</a b>) Top [![Logo](i.png)](/x) [again](</a b>)");
+
+        let soup = parse(html);
+        let root = soup.document().root().unwrap();
+        let options = MarkdownOptions {
+            links: LinkStyle::Reference,
+            images: ImageStyle::Alt,
+            base_url: Some("https://example.com/docs/".into()),
+        };
+        assert_eq!(
+            to_markdown(soup.document(), root, &options),
+            "[A][1] Top [Logo][2] [again][1]\n\n\
+             [1]: <https://example.com/a b>\n[2]: https://example.com/x"
+        );
+
+        let options = MarkdownOptions {
+            links: LinkStyle::Text,
+            images: ImageStyle::Omit,
+            ..MarkdownOptions::default()
+        };
+        assert_eq!(to_markdown(soup.document(), root, &options), "A Top again");
+    }
+
+    #[test]
+    fn test_table() {
+        assert_eq!(
+            markdown(
+                "<table><caption>Prices</caption><tr><th>Item</th><th>Note</th></tr><tr><td>Tea</\
+                 td><td>a|b</td></tr><tr><td colspan=\"2\">Sold out</td></tr></table>"
+            ),
+            "Prices\n\n| Item | Note |\n| --- | --- |\n| Tea | a\\|b |\n| Sold out | Sold out |"
+        );
+        assert_eq!(markdown("<table><tr><td>x</td></tr></table>"), "|  |\n| --- |\n| x |");
+    }
+
+    #[test]
+    fn test_tag_subtree() {
+        let soup = Soup::parse("<div><h3>Part</h3><ul><li>One</li></ul></div>");
+        let heading = soup.find("h3").unwrap().unwrap();
+        assert_eq!(heading.to_markdown(), "### Part");
+        let list = soup.find("ul").unwrap().unwrap();
+        assert_eq!(list.to_markdown(), "- One");
+    }
+}
@@ -0,0 +1,374 @@
+//! Structural diffs between two documents.
+//!
+//! [`diff`] compares two parsed pages element by element and reports what changed as a
+//! list of [`DomChange`]s: elements added or removed, attribute values changed, and
+//! changed text. This suits change monitoring, where a textual diff of the HTML source
+//! is swamped by reformatting and reordered attributes.
+//!
+//! Children are aligned in two passes: identical subtrees are matched first, and the
+//! remaining elements are then paired by tag name and `id`. Paired elements are compared
+//! recursively; unpaired ones are reported as added or removed as a whole. Text is
+//! compared with whitespace collapsed, attribute order is ignored, and comments are
+//! skipped.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{Soup, diff::{DomChange, diff}};
+//!
+//! let old = Soup::parse("<h1 class=\"a\">Tea</h1><ul><li>Green</li></ul>");
+//! let new = Soup::parse("<h1 class=\"b\">Tea</h1><ul><li>Green</li><li>Oolong</li></ul>");
+//!
+//! let changes = diff(&old, &new);
+//! assert_eq!(changes.len(), 2);
+//! assert!(matches!(&changes[0], DomChange::AttributeChanged { name, .. } if name == "class"));
+//! assert!(matches!(&changes[1], DomChange::Added { .. }));
+//! assert_eq!(changes[1].path(), "html > body > ul > li:nth-of-type(2)");
+//! ```
+
+use std::{
+    collections::BTreeSet,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{
+    Soup, Tag,
+    dom::{Attributes, NodeId, NodeKind},
+};
+
+/// One difference between two documents.
+///
+/// Paths are CSS selectors as returned by [`Tag::css_path`]: in the old document for
+/// [`Removed`](Self::Removed), and in the new document otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DomChange {
+    /// An element, with its whole subtree, that only the new document has.
+    Added {
+        /// Path of the element in the new document.
+        path: String,
+        /// The element in the new document.
+        node: NodeId,
+    },
+    /// An element, with its whole subtree, that only the old document has.
+    Removed {
+        /// Path of the element in the old document.
+        path: String,
+        /// The element in the old document.
+        node: NodeId,
+    },
+    /// An attribute that was added, removed, or given a different value.
+    AttributeChanged {
+        /// Path of the element in the new document.
+        path: String,
+        /// The element in the old document.
+        old_node: NodeId,
+        /// The element in the new document.
+        new_node: NodeId,
+        /// Attribute name.
+        name: String,
+        /// Value in the old document, or `None` if the attribute was added.
+        old: Option<String>,
+        /// Value in the new document, or `None` if the attribute was removed.
+        new: Option<String>,
+    },
+    /// A change to the text directly inside an element, not counting its child elements.
+    TextChanged {
+        /// Path of the element in the new document.
+        path: String,
+        /// The element in the old document.
+        old_node: NodeId,
+        /// The element in the new document.
+        new_node: NodeId,
+        /// Old text, whitespace collapsed.
+        old: String,
+        /// New text, whitespace collapsed.
+        new: String,
+    },
+}
+
+impl DomChange {
+    /// Returns the CSS path of the changed element.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Added { path, .. }
+            | Self::Removed { path, .. }
+            | Self::AttributeChanged { path, .. }
+            | Self::TextChanged { path, .. } => path,
+        }
+    }
+}
+
+/// Returns the changes that turn `old` into `new`, in document order.
+///
+/// Returns an empty list if the documents are structurally equal.
+#[must_use]
+pub fn diff(old: &Soup, new: &Soup) -> Vec<DomChange> {
+    let old: Vec<_> = old.root().into_iter().collect();
+    let new: Vec<_> = new.root().into_iter().collect();
+    diff_tags(&old, &new)
+}
+
+/// Returns the changes between two lists of elements, such as the matches of one
+/// selector in two versions of a page.
+///
+/// The lists are aligned like the children of an element, so an element missing from
+/// one list is reported as added or removed.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, diff::{DomChange, diff_tags}};
+///
+/// let old = Soup::parse("<nav>Home</nav><main><p>Price: 3 EUR</p></main>");
+/// let new = Soup::parse("<nav>Start</nav><main><p>Price: 4 EUR</p></main>");
+///
+/// let changes = diff_tags(&old.select("main").unwrap(), &new.select("main").unwrap());
+/// assert_eq!(changes.len(), 1);
+/// assert!(matches!(&changes[0], DomChange::TextChanged { new, .. } if new == "Price: 4 EUR"));
+/// ```
+#[must_use]
+pub fn diff_tags(old: &[Tag<'_>], new: &[Tag<'_>]) -> Vec<DomChange> {
+    let mut changes = Vec::new();
+    align(old, new, &mut changes);
+    changes
+}
+
+/// Pairs up two sibling lists and records the differences.
+fn align(old: &[Tag<'_>], new: &[Tag<'_>], changes: &mut Vec<DomChange>) {
+    // Identical subtrees anchor the alignment; the gaps between them are paired by key.
+    let old_hashes: Vec<_> = old.iter().map(subtree_hash).collect();
+    let new_hashes: Vec<_> = new.iter().map(subtree_hash).collect();
+    let (mut i, mut j) = (0, 0);
+    for (next_i, next_j) in
+        common_subsequence(&old_hashes, &new_hashes).into_iter().chain([(old.len(), new.len())])
+    {
+        align_by_key(&old[i..next_i], &new[j..next_j], changes);
+        (i, j) = (next_i + 1, next_j + 1);
+    }
+}
+
+fn align_by_key(old: &[Tag<'_>], new: &[Tag<'_>], changes: &mut Vec<DomChange>) {
+    let old_keys: Vec<_> = old.iter().map(key).collect();
+    let new_keys: Vec<_> = new.iter().map(key).collect();
+    let (mut i, mut j) = (0, 0);
+    for (next_i, next_j) in
+        common_subsequence(&old_keys, &new_keys).into_iter().chain([(old.len(), new.len())])
+    {
+        for tag in &old[i..next_i] {
+            changes.push(DomChange::Removed { path: tag.css_path(), node: tag.node_id() });
+        }
+        for tag in &new[j..next_j] {
+            changes.push(DomChange::Added { path: tag.css_path(), node: tag.node_id() });
+        }
+        if let (Some(old), Some(new)) = (old.get(next_i), new.get(next_j)) {
+            compare(old, new, changes);
+        }
+        (i, j) = (next_i + 1, next_j + 1);
+    }
+}
+
+/// Compares two paired elements and their children.
+fn compare(old: &Tag<'_>, new: &Tag<'_>, changes: &mut Vec<DomChange>) {
+    let path = new.css_path();
+    let empty = Attributes::new();
+    let old_attrs = old.attrs().unwrap_or(&empty);
+    let new_attrs = new.attrs().unwrap_or(&empty);
+    let names: BTreeSet<_> = old_attrs.keys().chain(new_attrs.keys()).collect();
+    for name in names {
+        let (old_value, new_value) = (old_attrs.get(name), new_attrs.get(name));
+        if old_value != new_value {
+            changes.push(DomChange::AttributeChanged {
+                path: path.clone(),
+                old_node: old.node_id(),
+                new_node: new.node_id(),
+                name: name.to_string(),
+                old: old_value.map(str::to_string),
+                new: new_value.map(str::to_string),
+            });
+        }
+    }
+
+    let (old_text, new_text) = (own_text(old), own_text(new));
+    if old_text != new_text {
+        changes.push(DomChange::TextChanged {
+            path,
+            old_node: old.node_id(),
+            new_node: new.node_id(),
+            old: old_text,
+            new: new_text,
+        });
+    }
+
+    let old_children: Vec<_> = old.children().collect();
+    let new_children: Vec<_> = new.children().collect();
+    align(&old_children, &new_children, changes);
+}
+
+/// Returns the index pairs of a longest common subsequence of `a` and `b`.
+fn common_subsequence<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    // Equal ends are matched directly, which keeps the table small for long lists with
+    // a few edits.
+    let prefix = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+    let suffix =
+        a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    // lengths[i][j] is the length of the longest common subsequence of a_mid[i..] and
+    // b_mid[j..].
+    let width = b_mid.len() + 1;
+    let mut lengths = vec![0_u32; (a_mid.len() + 1) * width];
+    for i in (0..a_mid.len()).rev() {
+        for j in (0..b_mid.len()).rev() {
+            lengths[i * width + j] = if a_mid[i] == b_mid[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut pairs: Vec<_> = (0..prefix).map(|i| (i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a_mid.len() && j < b_mid.len() {
+        if a_mid[i] == b_mid[j] {
+            pairs.push((prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    let (a_end, b_end) = (a.len() - suffix, b.len() - suffix);
+    pairs.extend((0..suffix).map(|k| (a_end + k, b_end + k)));
+    pairs
+}
+
+/// The tag name and `id` attribute, which must agree for two elements to be paired.
+fn key<'a>(tag: &Tag<'a>) -> (Option<&'a str>, Option<&'a str>) {
+    match tag.document().get(tag.node_id()).map(|node| &node.kind) {
+        Some(NodeKind::Element { name, attributes, .. }) => {
+            (Some(name.as_str()), attributes.get("id"))
+        }
+        _ => (None, None),
+    }
+}
+
+/// Hashes an element's name, attributes, text, and descendants, ignoring attribute order,
+/// whitespace differences, and comments.
+fn subtree_hash(tag: &Tag<'_>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_node(tag, &mut hasher);
+    hasher.finish()
+}
+
+fn hash_node(tag: &Tag<'_>, hasher: &mut DefaultHasher) {
+    tag.name().hash(hasher);
+    let mut attrs: Vec<_> = tag.attrs().into_iter().flatten().collect();
+    attrs.sort_unstable();
+    attrs.len().hash(hasher);
+    for (name, value) in attrs {
+        name.hash(hasher);
+        value.hash(hasher);
+    }
+    own_text(tag).hash(hasher);
+    tag.children().count().hash(hasher);
+    for child in tag.children() {
+        hash_node(&child, hasher);
+    }
+}
+
+/// Returns the text nodes directly inside `tag`, joined with whitespace collapsed.
+fn own_text(tag: &Tag<'_>) -> String {
+    let doc = tag.document();
+    let mut text = String::new();
+    for child in doc.children(tag.node_id()) {
+        if let Some(NodeKind::Text { content }) = doc.get(child).map(|node| &node.kind) {
+            for word in content.split_whitespace() {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(word);
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(changes: &[DomChange]) -> Vec<String> {
+        changes
+            .iter()
+            .map(|change| match change {
+                DomChange::Added { path, .. } => format!("+ {path}"),
+                DomChange::Removed { path, .. } => format!("- {path}"),
+                DomChange::AttributeChanged { path, name, old, new, .. } => {
+                    format!("~ {path} [{name}] {old:?} -> {new:?}")
+                }
+                DomChange::TextChanged { path, old, new, .. } => {
+                    format!("~ {path} {old:?} -> {new:?}")
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_equal_documents() {
+        let old = Soup::parse("<div id=\"a\" class=\"x\">  Tea\n <!-- note --></div>");
+        let new = Soup::parse("<div class=\"x\" id=\"a\">Tea</div>");
+        assert!(diff(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn test_diff_inserted_and_removed_elements() {
+        let old = Soup::parse("<ul><li>A</li><li>B</li><li>C</li></ul>");
+        let new = Soup::parse("<ul><li>New</li><li>A</li><li>C</li></ul>");
+        assert_eq!(
+            kinds(&diff(&old, &new)),
+            ["+ html > body > ul > li:nth-of-type(1)", "- html > body > ul > li:nth-of-type(2)",]
+        );
+    }
+
+    #[test]
+    fn test_diff_changed_elements() {
+        let old = Soup::parse(
+            "<p id=\"price\" data-x=\"1\">3 <b>EUR</b></p><p id=\"stock\">In stock</p><hr>",
+        );
+        let new = Soup::parse(
+            "<p id=\"price\" title=\"t\">4 <b>EUR</b></p><section><p>Gone</p></section><p \
+             id=\"stock\">In stock</p>",
+        );
+        assert_eq!(
+            kinds(&diff(&old, &new)),
+            [
+                "~ html > body > p:nth-of-type(1) [data-x] Some(\"1\") -> None",
+                "~ html > body > p:nth-of-type(1) [title] None -> Some(\"t\")",
+                "~ html > body > p:nth-of-type(1) \"3\" -> \"4\"",
+                "+ html > body > section",
+                "- html > body > hr",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_tags_unmatched_lists() {
+        let old = Soup::parse("<main><p>A</p></main>");
+        let new = Soup::parse("<main><p>A</p></main><main>B</main>");
+        let changes = diff_tags(&old.select("main").unwrap(), &new.select("main").unwrap());
+        assert_eq!(kinds(&changes), ["+ html > body > main:nth-of-type(2)"]);
+        assert_eq!(changes[0].path(), "html > body > main:nth-of-type(2)");
+        assert!(diff_tags(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_common_subsequence() {
+        assert_eq!(common_subsequence(&[1, 2, 3, 4], &[1, 3, 5, 4]), [(0, 0), (2, 1), (3, 3)]);
+        assert!(common_subsequence(&[1, 2], &[3]).is_empty());
+        assert!(common_subsequence(&[], &[1]).is_empty());
+    }
+}
@@ -9,7 +9,7 @@ use std::ops::{Index, IndexMut};
 ///
 /// Nodes are stored in a single `Vec`, indexed by their allocation order.
 /// This provides cache-friendly access and eliminates per-node heap allocations.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Arena<T> {
     nodes: Vec<T>,
 }
@@ -75,6 +75,16 @@ impl<T> Arena<T> {
         self.nodes.iter().enumerate()
     }
 
+    /// Returns an iterator over mutable references to all items.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.nodes.iter_mut()
+    }
+
+    /// Releases capacity beyond the allocated items.
+    pub fn shrink_to_fit(&mut self) {
+        self.nodes.shrink_to_fit();
+    }
+
     /// Clears all items from the arena.
     pub fn clear(&mut self) {
         self.nodes.clear();
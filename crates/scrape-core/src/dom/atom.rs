@@ -0,0 +1,192 @@
+//! Interned tag and attribute names.
+
+use std::{borrow::Borrow, cmp::Ordering, fmt, hash, ops::Deref};
+
+use markup5ever::LocalName;
+
+/// An interned tag or attribute name.
+///
+/// Names the HTML parser knows, such as `div` or `href`, live in a static table and other
+/// names in a shared global one, so each name is stored once however many elements use
+/// it. An `Atom` takes eight bytes, compares in constant time with other atoms, and
+/// dereferences to `str`.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::Atom;
+///
+/// let name = Atom::from("data-id");
+/// assert_eq!(name, "data-id");
+/// assert_eq!(name.len(), 7);
+/// assert_eq!(Atom::from(String::from("data-id")), name);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Atom(LocalName);
+
+impl Atom {
+    /// Wraps a name interned by the HTML parser, without looking it up again.
+    pub(crate) const fn from_local_name(name: LocalName) -> Self {
+        Self(name)
+    }
+
+    /// Returns the name.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Atom {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Atom {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Hashes like the `str` it holds, so atoms can key maps that are queried with `&str`.
+impl Borrow<str> for Atom {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl hash::Hash for Atom {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl PartialOrd for Atom {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Atom {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl fmt::Debug for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for Atom {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Atom {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Atom {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<Atom> for str {
+    fn eq(&self, other: &Atom) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<Atom> for &str {
+    fn eq(&self, other: &Atom) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<Atom> for String {
+    fn eq(&self, other: &Atom) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(name: &str) -> Self {
+        Self(LocalName::from(name))
+    }
+}
+
+impl From<&String> for Atom {
+    fn from(name: &String) -> Self {
+        Self::from(name.as_str())
+    }
+}
+
+impl From<String> for Atom {
+    fn from(name: String) -> Self {
+        Self(LocalName::from(name))
+    }
+}
+
+impl From<Atom> for String {
+    fn from(name: Atom) -> Self {
+        name.as_str().to_string()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Atom;
+
+    impl Serialize for Atom {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Atom {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            String::deserialize(deserializer).map(Atom::from)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_atoms_share_names() {
+        let known = Atom::from("div");
+        let other = Atom::from(String::from("x-widget"));
+        assert_eq!(known, Atom::from_local_name(LocalName::from("div")));
+        assert_eq!(other, Atom::from("x-widget"));
+        assert_ne!(known, other);
+        assert_eq!(size_of::<Atom>(), 8);
+
+        let mut counts = HashMap::new();
+        counts.insert(other.clone(), 1);
+        assert_eq!(counts.get("x-widget"), Some(&1));
+        assert!(known < other);
+        assert_eq!(format!("{other} {other:?}"), "x-widget \"x-widget\"");
+        assert_eq!(String::from(other), "x-widget");
+    }
+}
@@ -0,0 +1,400 @@
+//! Element attributes in source order.
+
+use std::{collections::HashMap, fmt, ops::Index, slice};
+
+use smallvec::SmallVec;
+
+use super::atom::Atom;
+
+/// The number of attributes an element holds without a separate allocation.
+const INLINE: usize = 1;
+
+/// The longest value, in bytes, stored without a separate allocation.
+const INLINE_VALUE: usize = 22;
+
+/// The attributes of an element, in the order they were written or added.
+///
+/// Names are [`Atom`]s, so an attribute name is stored once for the whole document.
+/// Values of up to 22 bytes, such as most ids and classes, are stored inline, and an
+/// element with a single attribute keeps it inline too, so short attributes need no
+/// allocation of their own. Lookups by name return the first attribute with that name, as HTML
+/// does. An element normally has each attribute once; documents parsed with
+/// [`ParseConfig::preserve_duplicate_attributes`](crate::ParseConfig::preserve_duplicate_attributes)
+/// also keep the repeated ones, which [`get_all`](Self::get_all) returns.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::Attributes;
+///
+/// let mut attrs = Attributes::new();
+/// attrs.insert("href", "/a");
+/// attrs.insert("class", "link");
+/// attrs.insert("href", "/b");
+///
+/// assert_eq!(attrs.get("href"), Some("/b"));
+/// assert_eq!(attrs.keys().collect::<Vec<_>>(), ["href", "class"]);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Attributes {
+    entries: SmallVec<[(Atom, Value); INLINE]>,
+}
+
+/// An attribute value, inline if it is short and in an exact-size allocation otherwise.
+#[derive(Clone)]
+enum Value {
+    Inline { len: u8, bytes: [u8; INLINE_VALUE] },
+    Heap(Box<str>),
+}
+
+impl Attributes {
+    /// Creates an empty attribute list.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { entries: SmallVec::new_const() }
+    }
+
+    /// Creates an empty attribute list with room for `capacity` attributes.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { entries: SmallVec::with_capacity(capacity) }
+    }
+
+    /// Returns the number of attributes, counting repeated names.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no attributes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value of the first attribute named `name`.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the values of every attribute named `name`, in order.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.entries.iter().filter(move |(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Returns `true` if there is an attribute named `name`.
+    #[must_use]
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.entries.iter().any(|(key, _)| key == name)
+    }
+
+    /// Returns `true` if some attribute name appears more than once.
+    #[must_use]
+    pub fn has_duplicates(&self) -> bool {
+        self.entries.iter().enumerate().any(|(i, (key, _))| self.contains_key_before(key, i))
+    }
+
+    /// Sets an attribute, returning its previous value.
+    ///
+    /// An existing attribute keeps its position; a new one is added at the end.
+    pub fn insert(&mut self, name: impl Into<Atom>, value: impl Into<String>) -> Option<String> {
+        let name = name.into();
+        let value = Value::from(value.into());
+        if let Some((_, existing)) = self.entries.iter_mut().find(|(key, _)| *key == name) {
+            return Some(std::mem::replace(existing, value).into_string());
+        }
+        self.entries.push((name, value));
+        None
+    }
+
+    /// Adds an attribute at the end, even if one with the same name exists.
+    pub fn push(&mut self, name: impl Into<Atom>, value: impl Into<String>) {
+        self.entries.push((name.into(), Value::from(value.into())));
+    }
+
+    /// Removes every attribute named `name`, returning the first one's value.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let position = self.entries.iter().position(|(key, _)| key == name)?;
+        let (_, value) = self.entries.remove(position);
+        self.entries.retain(|(key, _)| *key != *name);
+        Some(value.into_string())
+    }
+
+    /// Keeps only the attributes for which `keep` returns `true`.
+    pub fn retain(&mut self, mut keep: impl FnMut(&str, &str) -> bool) {
+        self.entries.retain(|(key, value)| keep(key, value.as_str()));
+    }
+
+    /// Returns an iterator over the names and values, in order.
+    #[must_use]
+    pub fn iter(&self) -> AttributesIter<'_> {
+        AttributesIter(self.entries.iter())
+    }
+
+    /// Returns an iterator over the names, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &Atom> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values, in order.
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(_, value)| value.as_str())
+    }
+
+    /// Releases spare capacity.
+    pub fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+    }
+
+    fn contains_key_before(&self, name: &str, end: usize) -> bool {
+        self.entries[..end].iter().any(|(key, _)| key == name)
+    }
+}
+
+impl fmt::Debug for Attributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl Index<&str> for Attributes {
+    type Output = str;
+
+    /// Returns the value of the first attribute named `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such attribute.
+    fn index(&self, name: &str) -> &str {
+        self.get(name).unwrap_or_else(|| panic!("no attribute named {name:?}"))
+    }
+}
+
+/// Builds attributes by [`insert`](Attributes::insert)ing each pair, so a later value
+/// replaces an earlier one with the same name.
+impl<K: Into<Atom>, V: Into<String>> FromIterator<(K, V)> for Attributes {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut attributes = Self::new();
+        attributes.extend(iter);
+        attributes
+    }
+}
+
+impl<K: Into<Atom>, V: Into<String>> Extend<(K, V)> for Attributes {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (name, value) in iter {
+            self.insert(name, value);
+        }
+    }
+}
+
+impl<K: Into<Atom>, V: Into<String>, const N: usize> From<[(K, V); N]> for Attributes {
+    fn from(pairs: [(K, V); N]) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+/// Converts a map, ordering the attributes by name since a `HashMap` has no order.
+impl From<HashMap<String, String>> for Attributes {
+    fn from(map: HashMap<String, String>) -> Self {
+        let mut entries: Vec<_> = map.into_iter().collect();
+        entries.sort_unstable();
+        entries.into_iter().map(|(name, value)| (Atom::from(name), value)).collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a Attributes {
+    type Item = (&'a Atom, &'a str);
+    type IntoIter = AttributesIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Attributes {
+    type Item = (Atom, String);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.entries.into_iter())
+    }
+}
+
+/// An iterator over the names and values of [`Attributes`], in order.
+#[derive(Debug, Clone)]
+pub struct AttributesIter<'a>(slice::Iter<'a, (Atom, Value)>);
+
+impl<'a> Iterator for AttributesIter<'a> {
+    type Item = (&'a Atom, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, value)| (key, value.as_str()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for AttributesIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, value)| (key, value.as_str()))
+    }
+}
+
+impl ExactSizeIterator for AttributesIter<'_> {}
+
+/// An owning iterator over the names and values of [`Attributes`], in order.
+#[derive(Debug)]
+pub struct IntoIter(smallvec::IntoIter<[(Atom, Value); INLINE]>);
+
+impl Iterator for IntoIter {
+    type Item = (Atom, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, value)| (key, value.into_string()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, value)| (key, value.into_string()))
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl Value {
+    fn as_str(&self) -> &str {
+        match self {
+            // The bytes were copied from a whole `str`, so they are valid UTF-8.
+            Self::Inline { len, bytes } => {
+                std::str::from_utf8(&bytes[..usize::from(*len)]).unwrap_or_default()
+            }
+            Self::Heap(value) => value,
+        }
+    }
+
+    fn into_string(self) -> String {
+        match self {
+            Self::Inline { .. } => self.as_str().to_string(),
+            Self::Heap(value) => value.into_string(),
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        let len = value.len();
+        if len > INLINE_VALUE {
+            return Self::Heap(value.into_boxed_str());
+        }
+        let mut bytes = [0; INLINE_VALUE];
+        bytes[..len].copy_from_slice(value.as_bytes());
+        Self::Inline { len: u8::try_from(len).unwrap_or_default(), bytes }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Value {}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{
+        Deserialize, Deserializer, Serialize, Serializer,
+        de::{MapAccess, Visitor},
+    };
+
+    use super::{Atom, Attributes, fmt};
+
+    /// Serializes as a map in order; repeated names become repeated keys.
+    impl Serialize for Attributes {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_map(self.iter())
+        }
+    }
+
+    /// Deserializes a map in order, keeping repeated keys.
+    impl<'de> Deserialize<'de> for Attributes {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct AttributesVisitor;
+
+            impl<'de> Visitor<'de> for AttributesVisitor {
+                type Value = Attributes;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("a map of attribute names to values")
+                }
+
+                fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Attributes, A::Error> {
+                    let mut attributes = Attributes::with_capacity(map.size_hint().unwrap_or(0));
+                    while let Some((name, value)) = map.next_entry::<Atom, String>()? {
+                        attributes.push(name, value);
+                    }
+                    Ok(attributes)
+                }
+            }
+
+            deserializer.deserialize_map(AttributesVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attributes_keep_order() {
+        let mut attrs: Attributes = [("b", "1"), ("a", "2"), ("c", "3")].into();
+        assert_eq!(attrs.insert("a", "4"), Some("2".to_string()));
+        attrs.insert("d", "5");
+        let pairs: Vec<_> = attrs.iter().map(|(k, v)| (k.as_str(), v)).collect();
+        assert_eq!(pairs, [("b", "1"), ("a", "4"), ("c", "3"), ("d", "5")]);
+        assert_eq!(attrs.remove("c"), Some("3".to_string()));
+        assert_eq!(attrs.keys().collect::<Vec<_>>(), ["b", "a", "d"]);
+        assert_eq!(&attrs["d"], "5");
+        assert_eq!(format!("{attrs:?}"), r#"{"b": "1", "a": "4", "d": "5"}"#);
+    }
+
+    #[test]
+    fn test_attributes_duplicates() {
+        let mut attrs = Attributes::new();
+        attrs.push("class", "a");
+        attrs.push("id", "x");
+        attrs.push("class", "b");
+        assert!(attrs.has_duplicates());
+        assert_eq!(attrs.len(), 3);
+        assert_eq!(attrs.get("class"), Some("a"));
+        assert_eq!(attrs.get_all("class").collect::<Vec<_>>(), ["a", "b"]);
+
+        assert_eq!(attrs.remove("class"), Some("a".to_string()));
+        assert!(!attrs.has_duplicates());
+        assert_eq!(attrs.len(), 1);
+    }
+
+    #[test]
+    fn test_attributes_from_hash_map_are_sorted() {
+        let map =
+            HashMap::from([("z".to_string(), String::new()), ("a".to_string(), String::new())]);
+        let attrs = Attributes::from(map);
+        assert_eq!(attrs.keys().collect::<Vec<_>>(), ["a", "z"]);
+    }
+}
@@ -4,9 +4,12 @@ use std::{collections::HashMap, marker::PhantomData};
 
 use super::{
     arena::Arena,
+    atom::Atom,
+    attributes::Attributes,
     index::DocumentIndex,
-    node::{Node, NodeId, NodeKind},
+    node::{Namespace, Node, NodeId, NodeKind},
     state::{Building, DocumentState, MutableState, Queryable, QueryableState, Sealed},
+    text::TextContent,
 };
 
 /// An HTML document containing a tree of nodes.
@@ -36,11 +39,13 @@ use super::{
 /// Internally, the document uses typestate pattern to enforce lifecycle
 /// guarantees at compile time. The type parameter `S` tracks whether
 /// the document is being built, is queryable, or is sealed.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DocumentImpl<S: DocumentState = Queryable> {
     arena: Arena<Node>,
     root: Option<NodeId>,
     index: Option<DocumentIndex>,
+    /// Source line of each element's start tag, by node index; 0 where unknown.
+    lines: Vec<u32>,
     _state: PhantomData<S>,
 }
 
@@ -81,7 +86,13 @@ impl DocumentImpl<Building> {
     /// Use this when you know the approximate number of nodes to avoid reallocations.
     #[must_use]
     pub fn with_capacity(capacity: usize) -> Self {
-        Self { arena: Arena::with_capacity(capacity), root: None, index: None, _state: PhantomData }
+        Self {
+            arena: Arena::with_capacity(capacity),
+            root: None,
+            index: None,
+            lines: Vec::new(),
+            _state: PhantomData,
+        }
     }
 
     /// Sets the root node ID.
@@ -92,14 +103,24 @@ impl DocumentImpl<Building> {
     /// Creates a new element node and returns its ID.
     pub fn create_element(
         &mut self,
-        name: impl Into<String>,
-        attributes: HashMap<String, String>,
+        name: impl Into<Atom>,
+        attributes: impl Into<Attributes>,
     ) -> NodeId {
         NodeId::new(self.arena.alloc(Node::element(name, attributes)))
     }
 
+    /// Creates a new element node in the given namespace and returns its ID.
+    pub fn create_element_ns(
+        &mut self,
+        name: impl Into<Atom>,
+        attributes: impl Into<Attributes>,
+        namespace: Namespace,
+    ) -> NodeId {
+        NodeId::new(self.arena.alloc(Node::element_ns(name, attributes, namespace)))
+    }
+
     /// Creates a new text node and returns its ID.
-    pub fn create_text(&mut self, content: impl Into<String>) -> NodeId {
+    pub fn create_text(&mut self, content: impl Into<TextContent>) -> NodeId {
         NodeId::new(self.arena.alloc(Node::text(content)))
     }
 
@@ -108,6 +129,43 @@ impl DocumentImpl<Building> {
         NodeId::new(self.arena.alloc(Node::comment(content)))
     }
 
+    /// Creates a new doctype node and returns its ID.
+    pub fn create_doctype(
+        &mut self,
+        name: impl Into<Atom>,
+        public_id: impl Into<Atom>,
+        system_id: impl Into<Atom>,
+    ) -> NodeId {
+        NodeId::new(self.arena.alloc(Node::doctype(name, public_id, system_id)))
+    }
+
+    /// Records the 1-based source line of the element `id`'s start tag; see
+    /// [`line`](DocumentImpl::line).
+    pub fn set_line(&mut self, id: NodeId, line: usize) {
+        let Ok(line) = u32::try_from(line) else { return };
+        if self.lines.len() <= id.index() {
+            self.lines.resize(id.index() + 1, 0);
+        }
+        self.lines[id.index()] = line;
+    }
+
+    /// Links the parentless `node` right after the parentless `sibling`, which must be
+    /// the last in its sibling chain.
+    ///
+    /// This is how the doctype and comments outside the root element sit beside it.
+    pub fn append_sibling(&mut self, sibling: NodeId, node: NodeId) {
+        debug_assert!(self.arena.get(sibling.index()).is_some_and(|n| n.parent.is_none()));
+        debug_assert!(self.arena.get(node.index()).is_some_and(|n| n.parent.is_none()));
+
+        if let Some(new_node) = self.arena.get_mut(node.index()) {
+            new_node.prev_sibling = Some(sibling);
+            new_node.next_sibling = None;
+        }
+        if let Some(sib) = self.arena.get_mut(sibling.index()) {
+            sib.next_sibling = Some(node);
+        }
+    }
+
     /// Appends a child node to a parent.
     ///
     /// Updates parent, `first_child`, `last_child`, and sibling links.
@@ -149,65 +207,12 @@ impl DocumentImpl<Building> {
     ///
     /// `sibling` must have a parent; `new_child` must not already be in the tree.
     pub fn insert_before(&mut self, sibling: NodeId, new_child: NodeId) {
-        let Some(parent) = self.arena.get(sibling.index()).and_then(|n| n.parent) else {
-            return;
-        };
-
-        let prev = self.arena.get(sibling.index()).and_then(|n| n.prev_sibling);
-
-        // Wire new_child into the chain
-        if let Some(node) = self.arena.get_mut(new_child.index()) {
-            node.parent = Some(parent);
-            node.prev_sibling = prev;
-            node.next_sibling = Some(sibling);
-        }
-
-        // Update previous sibling's next pointer (or parent's first_child)
-        if let Some(prev_id) = prev {
-            if let Some(prev_node) = self.arena.get_mut(prev_id.index()) {
-                prev_node.next_sibling = Some(new_child);
-            }
-        } else if let Some(parent_node) = self.arena.get_mut(parent.index()) {
-            parent_node.first_child = Some(new_child);
-        }
-
-        // Update sibling's prev pointer
-        if let Some(sib) = self.arena.get_mut(sibling.index()) {
-            sib.prev_sibling = Some(new_child);
-        }
+        self.link_before(sibling, new_child);
     }
 
     /// Detaches `target` from its parent, fixing up sibling and parent links.
     pub fn remove_from_parent(&mut self, target: NodeId) {
-        let Some(parent) = self.arena.get(target.index()).and_then(|n| n.parent) else {
-            return;
-        };
-        let prev = self.arena.get(target.index()).and_then(|n| n.prev_sibling);
-        let next = self.arena.get(target.index()).and_then(|n| n.next_sibling);
-
-        // Bridge prev <-> next
-        if let Some(prev_id) = prev {
-            if let Some(prev_node) = self.arena.get_mut(prev_id.index()) {
-                prev_node.next_sibling = next;
-            }
-        } else if let Some(parent_node) = self.arena.get_mut(parent.index()) {
-            parent_node.first_child = next;
-        }
-
-        if let Some(next_id) = next {
-            if let Some(next_node) = self.arena.get_mut(next_id.index()) {
-                next_node.prev_sibling = prev;
-            }
-        } else if let Some(parent_node) = self.arena.get_mut(parent.index()) {
-            parent_node.last_child = prev;
-        }
-
-        // Detach target
-        if let Some(node) = self.arena.get_mut(target.index()) {
-            node.parent = None;
-            node.prev_sibling = None;
-            node.next_sibling = None;
-        }
+        self.unlink(target);
     }
 
     /// Moves all children of `src` to become the last children of `dst`.
@@ -259,6 +264,21 @@ impl DocumentImpl<Building> {
         false
     }
 
+    /// Releases spare capacity in the node arena and in text contents and attribute maps,
+    /// which grow in steps while parsing.
+    pub fn shrink_to_fit(&mut self) {
+        self.arena.shrink_to_fit();
+        self.lines.shrink_to_fit();
+        for node in self.arena.iter_mut() {
+            match &mut node.kind {
+                NodeKind::Element { attributes, .. } => attributes.shrink_to_fit(),
+                NodeKind::Text { content } => content.shrink_to_fit(),
+                NodeKind::Comment { content } => content.shrink_to_fit(),
+                NodeKind::Doctype { .. } => {}
+            }
+        }
+    }
+
     /// Transitions the document from Building to Queryable state.
     ///
     /// This is a one-way transition. Once built, the document structure
@@ -280,7 +300,13 @@ impl DocumentImpl<Building> {
     /// ```
     #[must_use]
     pub fn build(self) -> DocumentImpl<Queryable> {
-        DocumentImpl { arena: self.arena, root: self.root, index: self.index, _state: PhantomData }
+        DocumentImpl {
+            arena: self.arena,
+            root: self.root,
+            index: self.index,
+            lines: self.lines,
+            _state: PhantomData,
+        }
     }
 }
 
@@ -316,8 +342,8 @@ impl DocumentImpl<Queryable> {
     /// Available on Queryable for backward compatibility with tests.
     pub fn create_element(
         &mut self,
-        name: impl Into<String>,
-        attributes: HashMap<String, String>,
+        name: impl Into<Atom>,
+        attributes: impl Into<Attributes>,
     ) -> NodeId {
         NodeId::new(self.arena.alloc(Node::element(name, attributes)))
     }
@@ -325,7 +351,7 @@ impl DocumentImpl<Queryable> {
     /// Creates a new text node and returns its ID.
     ///
     /// Available on Queryable for backward compatibility with tests.
-    pub fn create_text(&mut self, content: impl Into<String>) -> NodeId {
+    pub fn create_text(&mut self, content: impl Into<TextContent>) -> NodeId {
         NodeId::new(self.arena.alloc(Node::text(content)))
     }
 
@@ -340,7 +366,8 @@ impl DocumentImpl<Queryable> {
     ///
     /// Available on Queryable for backward compatibility with tests.
     ///
-    /// Updates parent, `first_child`, `last_child`, and sibling links.
+    /// Updates parent, `first_child`, `last_child`, and sibling links. Like the other
+    /// mutations, this drops the [`DocumentIndex`].
     ///
     /// # Panics
     ///
@@ -348,6 +375,7 @@ impl DocumentImpl<Queryable> {
     pub fn append_child(&mut self, parent_id: NodeId, child_id: NodeId) {
         debug_assert!(parent_id.index() < self.arena.len(), "Invalid parent_id");
         debug_assert!(child_id.index() < self.arena.len(), "Invalid child_id");
+        self.index = None;
 
         // Get current last child of parent
         let prev_last = self.arena.get(parent_id.index()).and_then(|p| p.last_child);
@@ -381,7 +409,13 @@ impl DocumentImpl<Queryable> {
     /// the document will never change.
     #[must_use]
     pub fn seal(self) -> DocumentImpl<Sealed> {
-        DocumentImpl { arena: self.arena, root: self.root, index: self.index, _state: PhantomData }
+        DocumentImpl {
+            arena: self.arena,
+            root: self.root,
+            index: self.index,
+            lines: self.lines,
+            _state: PhantomData,
+        }
     }
 
     /// Sets the document index.
@@ -393,6 +427,377 @@ impl DocumentImpl<Queryable> {
     }
 }
 
+// ==================== Tree Mutation (Queryable) ====================
+
+impl DocumentImpl<Queryable> {
+    /// Inserts `node` immediately before `sibling`.
+    ///
+    /// If `node` is already attached somewhere in the tree it is moved. Returns `false`
+    /// without modifying the tree when `sibling` has no parent or when `node` is
+    /// `sibling` itself or one of its ancestors.
+    ///
+    /// Any mutation drops the [`DocumentIndex`]; queries fall back to tree traversal.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<ul><li>B</li></ul>");
+    /// let li = soup.find("li").unwrap().unwrap().node_id();
+    /// let doc = soup.document_mut();
+    /// let new_li = doc.create_element("li", HashMap::new());
+    /// let text = doc.create_text("A");
+    /// doc.append_child(new_li, text);
+    /// assert!(doc.insert_before(li, new_li));
+    /// assert_eq!(soup.find("ul").unwrap().unwrap().inner_html(), "<li>A</li><li>B</li>");
+    /// ```
+    pub fn insert_before(&mut self, sibling: NodeId, node: NodeId) -> bool {
+        if !self.can_insert_at(sibling, node) {
+            return false;
+        }
+        self.unlink(node);
+        self.link_before(sibling, node);
+        self.index = None;
+        true
+    }
+
+    /// Inserts `node` immediately after `sibling`.
+    ///
+    /// Follows the same rules as [`insert_before`](Self::insert_before).
+    pub fn insert_after(&mut self, sibling: NodeId, node: NodeId) -> bool {
+        if !self.can_insert_at(sibling, node) {
+            return false;
+        }
+        self.unlink(node);
+        self.link_after(sibling, node);
+        self.index = None;
+        true
+    }
+
+    /// Detaches `node` and its subtree from the tree.
+    ///
+    /// Sibling and parent links are fixed up; the detached node stays allocated and can
+    /// be re-inserted later. Returns `false` if `node` has no parent.
+    pub fn remove_node(&mut self, node: NodeId) -> bool {
+        if self.parent(node).is_none() {
+            return false;
+        }
+        self.unlink(node);
+        self.index = None;
+        true
+    }
+
+    /// Removes `node` and its subtree from the tree for good.
+    ///
+    /// Unlike [`remove_node`](Self::remove_node), the removed nodes are cleared,
+    /// releasing their attributes and text, and must not be used again; their IDs refer
+    /// to empty placeholders until [`compact`](Self::compact) reclaims their slots.
+    /// Returns `false` if `node` has no parent.
+    pub fn remove_subtree(&mut self, node: NodeId) -> bool {
+        if !self.remove_node(node) {
+            return false;
+        }
+        let removed: Vec<_> = std::iter::once(node).chain(self.descendants(node)).collect();
+        for id in removed {
+            self.arena[id.index()] = Node::text(TextContent::default());
+        }
+        true
+    }
+
+    /// Replaces `old` with `new` at the same position, detaching `old`.
+    ///
+    /// Returns `false` without modifying the tree when `old` has no parent or when
+    /// `new` is `old` itself or one of its ancestors.
+    pub fn replace_node(&mut self, old: NodeId, new: NodeId) -> bool {
+        if !self.insert_before(old, new) {
+            return false;
+        }
+        self.unlink(old);
+        true
+    }
+
+    /// Wraps `node` in `wrapper`, which takes `node`'s place in the tree.
+    ///
+    /// `wrapper` is typically a freshly created element; `node` becomes its last child.
+    /// Returns `false` if `node` has no parent, `wrapper` is not an element, or `wrapper`
+    /// is `node` itself or one of its ancestors.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<p><b>bold</b></p>");
+    /// let b = soup.find("b").unwrap().unwrap().node_id();
+    /// let doc = soup.document_mut();
+    /// let em = doc.create_element("em", HashMap::new());
+    /// assert!(doc.wrap(b, em));
+    /// assert_eq!(soup.find("p").unwrap().unwrap().inner_html(), "<em><b>bold</b></em>");
+    /// ```
+    pub fn wrap(&mut self, node: NodeId, wrapper: NodeId) -> bool {
+        let is_element = self.get(wrapper).is_some_and(|n| n.kind.is_element());
+        if !is_element || !self.replace_node(node, wrapper) {
+            return false;
+        }
+        self.append_child(wrapper, node);
+        true
+    }
+
+    /// Replaces `node` with its children, detaching `node` itself.
+    ///
+    /// Returns `false` if `node` has no parent.
+    pub fn unwrap(&mut self, node: NodeId) -> bool {
+        if self.parent(node).is_none() {
+            return false;
+        }
+        let mut child = self.first_child(node);
+        while let Some(child_id) = child {
+            child = self.next_sibling(child_id);
+            self.unlink(child_id);
+            self.link_before(node, child_id);
+        }
+        self.unlink(node);
+        self.index = None;
+        true
+    }
+
+    /// Sets an attribute on an element, replacing any existing value.
+    ///
+    /// Returns `false` if `node` is not an element.
+    pub fn set_attribute(
+        &mut self,
+        node: NodeId,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> bool {
+        let Some(NodeKind::Element { attributes, .. }) =
+            self.arena.get_mut(node.index()).map(|n| &mut n.kind)
+        else {
+            return false;
+        };
+        attributes.insert(name.into(), value.into());
+        self.index = None;
+        true
+    }
+
+    /// Removes an attribute from an element, returning its previous value.
+    pub fn remove_attribute(&mut self, node: NodeId, name: &str) -> Option<String> {
+        let Some(NodeKind::Element { attributes, .. }) =
+            self.arena.get_mut(node.index()).map(|n| &mut n.kind)
+        else {
+            return None;
+        };
+        let removed = attributes.remove(name);
+        if removed.is_some() {
+            self.index = None;
+        }
+        removed
+    }
+
+    /// Rebuilds the node arena with only the nodes in the tree, in document order.
+    ///
+    /// The tree includes the root's siblings, such as the doctype (see
+    /// [`top_level`](Self::top_level)). Nodes outside it are dropped: subtrees removed with
+    /// [`remove_subtree`](Self::remove_subtree) or [`remove_node`](Self::remove_node),
+    /// and nodes that were created but never inserted. Every remaining node gets a new
+    /// ID; the returned map takes each old ID to its new one. The compacted document
+    /// gets a fresh index.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<ul><li>A</li><li>B</li></ul>");
+    /// let items: Vec<_> = soup.select("li").unwrap().iter().map(|li| li.node_id()).collect();
+    /// let doc = soup.document_mut();
+    /// let before = doc.len();
+    /// assert!(doc.remove_subtree(items[0]));
+    /// assert_eq!(doc.len(), before);
+    ///
+    /// let ids = doc.compact();
+    /// assert_eq!(doc.len(), before - 2);
+    /// assert!(!ids.contains_key(&items[0]));
+    /// assert_eq!(doc.get(ids[&items[1]]).unwrap().kind.tag_name(), Some("li"));
+    /// assert_eq!(soup.select_text("li").unwrap(), ["B"]);
+    /// ```
+    pub fn compact(&mut self) -> HashMap<NodeId, NodeId> {
+        let order: Vec<_> = self
+            .top_level()
+            .flat_map(|id| std::iter::once(id).chain(self.descendants(id)))
+            .collect();
+        let mut new_ids = vec![None; self.arena.len()];
+        for (new, old) in order.iter().enumerate() {
+            new_ids[old.index()] = Some(NodeId::new(new));
+        }
+        let remap = |id: Option<NodeId>| id.and_then(|id| new_ids[id.index()]);
+
+        let mut arena = Arena::with_capacity(order.len());
+        for old in &order {
+            let node =
+                std::mem::replace(&mut self.arena[old.index()], Node::text(TextContent::default()));
+            arena.alloc(Node {
+                parent: remap(node.parent),
+                first_child: remap(node.first_child),
+                last_child: remap(node.last_child),
+                prev_sibling: remap(node.prev_sibling),
+                next_sibling: remap(node.next_sibling),
+                kind: node.kind,
+            });
+        }
+
+        self.arena = arena;
+        self.lines =
+            order.iter().map(|old| self.lines.get(old.index()).copied().unwrap_or(0)).collect();
+        self.root = self.root.and_then(|root| remap(Some(root)));
+        self.index = Some(DocumentIndex::from_document(self));
+        order.into_iter().filter_map(|old| Some((old, new_ids[old.index()]?))).collect()
+    }
+
+    /// Returns `true` if `node` can be inserted next to `sibling` without creating a cycle.
+    fn can_insert_at(&self, sibling: NodeId, node: NodeId) -> bool {
+        self.parent(sibling).is_some()
+            && node.index() < self.arena.len()
+            && node != sibling
+            && !self.ancestors(sibling).any(|a| a == node)
+    }
+}
+
+// ==================== Subtree Copy (Queryable) ====================
+
+impl DocumentImpl<Queryable> {
+    /// Copies `node` and its descendants into a new document rooted at the copy of `node`.
+    ///
+    /// Only the subtree is copied, in document order, so the new document's size is
+    /// proportional to the subtree rather than to `self`. The copy gets a fresh index.
+    /// Returns `None` if `node` does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<main><article><p>Keep</p></article><p>Drop</p></main>");
+    /// let article = soup.find("article").unwrap().unwrap().node_id();
+    /// let copy = soup.document().subtree(article).unwrap();
+    /// assert_eq!(copy.len(), 3);
+    /// ```
+    #[must_use]
+    pub fn subtree(&self, node: NodeId) -> Option<Self> {
+        let mut copy = Self::new();
+        let root = copy.copy_from(self, node)?;
+        copy.set_root(root);
+        copy.set_index(DocumentIndex::from_document(&copy));
+        Some(copy)
+    }
+
+    /// Copies the subtrees of `source` rooted at `nodes` and appends them, in order, as
+    /// the last children of `parent`.
+    ///
+    /// Returns the IDs of the copied roots; IDs in `nodes` that do not exist in `source`
+    /// are skipped, and nothing is copied if `parent` is not an element. Unlike other
+    /// mutations, this keeps the [`DocumentIndex`]: the new elements are added to it
+    /// when they land at the end of the document, and it is rebuilt otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<ul><li>A</li></ul>");
+    /// let page = Soup::parse_fragment("<li>B</li><li>C</li>");
+    /// let ul = soup.find("ul").unwrap().unwrap().node_id();
+    /// let page_root = page.root().unwrap().node_id();
+    /// let items: Vec<_> = page.document().children(page_root).collect();
+    ///
+    /// let copies = soup.document_mut().append_copies(ul, page.document(), &items);
+    /// assert_eq!(copies.len(), 2);
+    /// assert_eq!(soup.select_text("li").unwrap(), ["A", "B", "C"]);
+    /// ```
+    pub fn append_copies(
+        &mut self,
+        parent: NodeId,
+        source: &Self,
+        nodes: &[NodeId],
+    ) -> Vec<NodeId> {
+        if !self.get(parent).is_some_and(|n| n.kind.is_element()) {
+            return Vec::new();
+        }
+        let path: Vec<_> = std::iter::once(parent).chain(self.ancestors(parent)).collect();
+        let at_end = path.last().copied() == self.root
+            && path.iter().all(|&id| self.next_sibling(id).is_none());
+        let first_new = self.arena.len();
+        // Taken before `append_child` drops it.
+        let index = self.index.take();
+
+        let copies: Vec<_> = nodes
+            .iter()
+            .filter_map(|&node| {
+                let copy = self.copy_from(source, node)?;
+                self.append_child(parent, copy);
+                Some(copy)
+            })
+            .collect();
+
+        self.index = match index {
+            // Copies are allocated in document order, after every existing node.
+            Some(mut index) if at_end => {
+                for (id, node) in self.arena.iter().skip(first_new) {
+                    index.register_node(&node.kind, NodeId::new(id));
+                }
+                Some(index)
+            }
+            Some(_) => Some(DocumentIndex::from_document(self)),
+            None => None,
+        };
+        copies
+    }
+
+    /// Builds an indexed document from nodes whose links are known to form a tree.
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_nodes(nodes: Vec<Node>, root: Option<NodeId>) -> Self {
+        let mut doc = Self::with_capacity(nodes.len());
+        for node in nodes {
+            doc.arena.alloc(node);
+        }
+        doc.root = root;
+        doc.set_index(DocumentIndex::from_document(&doc));
+        doc
+    }
+
+    /// Copies `node` of `source` and its descendants into this document, in document
+    /// order, and returns the unlinked copy of `node`.
+    fn copy_from(&mut self, source: &Self, node: NodeId) -> Option<NodeId> {
+        let root = self.alloc_copy(source.get(node)?);
+        let mut ids = HashMap::from([(node, root)]);
+        for src in source.descendants(node) {
+            let Some(src_node) = source.get(src) else { continue };
+            let Some(&parent) = src_node.parent.and_then(|p| ids.get(&p)) else { continue };
+            let id = self.alloc_copy(src_node);
+            self.append_child(parent, id);
+            ids.insert(src, id);
+        }
+        Some(root)
+    }
+
+    /// Allocates an unlinked copy of `node`.
+    fn alloc_copy(&mut self, node: &Node) -> NodeId {
+        NodeId::new(self.arena.alloc(Node {
+            kind: node.kind.clone(),
+            parent: None,
+            first_child: None,
+            last_child: None,
+            prev_sibling: None,
+            next_sibling: None,
+        }))
+    }
+}
+
 // ==================== Shared Methods (All States) ====================
 
 impl<S: DocumentState> DocumentImpl<S> {
@@ -402,6 +807,17 @@ impl<S: DocumentState> DocumentImpl<S> {
         self.root
     }
 
+    /// Returns the root and its siblings in document order.
+    ///
+    /// In a parsed document the root element's siblings are the doctype and the comments
+    /// before and after `<html>`; other nodes without a parent are not included.
+    pub fn top_level(&self) -> impl Iterator<Item = NodeId> {
+        let first = self.root.map(|root| self.prev_siblings(root).last().unwrap_or(root));
+        first
+            .into_iter()
+            .flat_map(move |first| std::iter::once(first).chain(self.next_siblings(first)))
+    }
+
     /// Returns a reference to the node with the given ID.
     #[inline]
     #[must_use]
@@ -409,6 +825,26 @@ impl<S: DocumentState> DocumentImpl<S> {
         self.arena.get(id.index())
     }
 
+    /// Returns the 1-based source line of the element `id`'s start tag.
+    ///
+    /// The HTML parser records the line its tokenizer was on when the start tag ended,
+    /// so for a tag spanning several lines this is the line of its `>`. Returns `None`
+    /// for other nodes, elements created after parsing, and documents parsed as XML.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul>\n<li>A</li>\n<li id=\"b\">B</li>\n</ul>");
+    /// let b = soup.find("#b").unwrap().unwrap().node_id();
+    /// assert_eq!(soup.document().line(b), Some(3));
+    /// ```
+    #[must_use]
+    pub fn line(&self, id: NodeId) -> Option<usize> {
+        usize::try_from(*self.lines.get(id.index())?).ok().filter(|&line| line != 0)
+    }
+
     /// Returns the number of nodes in the document.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -666,6 +1102,103 @@ impl<S: DocumentState> DocumentImpl<S> {
     }
 }
 
+// ==================== Link Helpers (All States) ====================
+
+impl<S: DocumentState> DocumentImpl<S> {
+    /// Links a detached `node` immediately before `sibling`.
+    fn link_before(&mut self, sibling: NodeId, node: NodeId) {
+        let Some(parent) = self.arena.get(sibling.index()).and_then(|n| n.parent) else {
+            return;
+        };
+
+        let prev = self.arena.get(sibling.index()).and_then(|n| n.prev_sibling);
+
+        // Wire node into the chain
+        if let Some(new_node) = self.arena.get_mut(node.index()) {
+            new_node.parent = Some(parent);
+            new_node.prev_sibling = prev;
+            new_node.next_sibling = Some(sibling);
+        }
+
+        // Update previous sibling's next pointer (or parent's first_child)
+        if let Some(prev_id) = prev {
+            if let Some(prev_node) = self.arena.get_mut(prev_id.index()) {
+                prev_node.next_sibling = Some(node);
+            }
+        } else if let Some(parent_node) = self.arena.get_mut(parent.index()) {
+            parent_node.first_child = Some(node);
+        }
+
+        // Update sibling's prev pointer
+        if let Some(sib) = self.arena.get_mut(sibling.index()) {
+            sib.prev_sibling = Some(node);
+        }
+    }
+
+    /// Links a detached `node` immediately after `sibling`.
+    fn link_after(&mut self, sibling: NodeId, node: NodeId) {
+        let Some(parent) = self.arena.get(sibling.index()).and_then(|n| n.parent) else {
+            return;
+        };
+
+        let next = self.arena.get(sibling.index()).and_then(|n| n.next_sibling);
+
+        // Wire node into the chain
+        if let Some(new_node) = self.arena.get_mut(node.index()) {
+            new_node.parent = Some(parent);
+            new_node.prev_sibling = Some(sibling);
+            new_node.next_sibling = next;
+        }
+
+        // Update next sibling's prev pointer (or parent's last_child)
+        if let Some(next_id) = next {
+            if let Some(next_node) = self.arena.get_mut(next_id.index()) {
+                next_node.prev_sibling = Some(node);
+            }
+        } else if let Some(parent_node) = self.arena.get_mut(parent.index()) {
+            parent_node.last_child = Some(node);
+        }
+
+        // Update sibling's next pointer
+        if let Some(sib) = self.arena.get_mut(sibling.index()) {
+            sib.next_sibling = Some(node);
+        }
+    }
+
+    /// Detaches `target` from its parent, fixing up sibling and parent links.
+    fn unlink(&mut self, target: NodeId) {
+        let Some(parent) = self.arena.get(target.index()).and_then(|n| n.parent) else {
+            return;
+        };
+        let prev = self.arena.get(target.index()).and_then(|n| n.prev_sibling);
+        let next = self.arena.get(target.index()).and_then(|n| n.next_sibling);
+
+        // Bridge prev <-> next
+        if let Some(prev_id) = prev {
+            if let Some(prev_node) = self.arena.get_mut(prev_id.index()) {
+                prev_node.next_sibling = next;
+            }
+        } else if let Some(parent_node) = self.arena.get_mut(parent.index()) {
+            parent_node.first_child = next;
+        }
+
+        if let Some(next_id) = next {
+            if let Some(next_node) = self.arena.get_mut(next_id.index()) {
+                next_node.prev_sibling = prev;
+            }
+        } else if let Some(parent_node) = self.arena.get_mut(parent.index()) {
+            parent_node.last_child = prev;
+        }
+
+        // Detach target
+        if let Some(node) = self.arena.get_mut(target.index()) {
+            node.parent = None;
+            node.prev_sibling = None;
+            node.next_sibling = None;
+        }
+    }
+}
+
 /// Iterator over direct children of a node.
 ///
 /// Created by [`Document::children`].
@@ -1412,4 +1945,268 @@ mod tests {
 
         assert_eq!(doc.siblings(li2).elements().count(), 2); // li1, li3
     }
+
+    fn create_list(doc: &mut Document, count: usize) -> (NodeId, Vec<NodeId>) {
+        let ul = doc.create_element("ul", HashMap::new());
+        doc.set_root(ul);
+        let items = (0..count)
+            .map(|_| {
+                let li = doc.create_element("li", HashMap::new());
+                doc.append_child(ul, li);
+                li
+            })
+            .collect();
+        (ul, items)
+    }
+
+    fn assert_links_consistent(doc: &Document, parent: NodeId) {
+        let forward: Vec<_> = doc.children(parent).collect();
+        let mut backward = Vec::new();
+        let mut current = doc.last_child(parent);
+        while let Some(id) = current {
+            assert_eq!(doc.parent(id), Some(parent));
+            backward.push(id);
+            current = doc.prev_sibling(id);
+        }
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(doc.first_child(parent), forward.first().copied());
+    }
+
+    #[test]
+    fn test_insert_before_and_after() {
+        let mut doc = Document::new();
+        let (ul, items) = create_list(&mut doc, 2);
+        let first = doc.create_element("li", HashMap::new());
+        let middle = doc.create_element("li", HashMap::new());
+        let last = doc.create_element("li", HashMap::new());
+
+        assert!(doc.insert_before(items[0], first));
+        assert!(doc.insert_after(items[0], middle));
+        assert!(doc.insert_after(items[1], last));
+
+        let children: Vec<_> = doc.children(ul).collect();
+        assert_eq!(children, vec![first, items[0], middle, items[1], last]);
+        assert_links_consistent(&doc, ul);
+    }
+
+    #[test]
+    fn test_insert_moves_attached_node() {
+        let mut doc = Document::new();
+        let (ul, items) = create_list(&mut doc, 3);
+
+        assert!(doc.insert_before(items[0], items[2]));
+
+        let children: Vec<_> = doc.children(ul).collect();
+        assert_eq!(children, vec![items[2], items[0], items[1]]);
+        assert_links_consistent(&doc, ul);
+    }
+
+    #[test]
+    fn test_insert_rejects_cycles_and_orphans() {
+        let mut doc = Document::new();
+        let (ul, items) = create_list(&mut doc, 1);
+        let orphan = doc.create_element("li", HashMap::new());
+
+        assert!(!doc.insert_before(items[0], ul));
+        assert!(!doc.insert_after(items[0], items[0]));
+        assert!(!doc.insert_before(ul, orphan));
+        assert_eq!(doc.children(ul).collect::<Vec<_>>(), items);
+    }
+
+    #[test]
+    fn test_remove_node_fixes_links() {
+        let mut doc = Document::new();
+        let (ul, items) = create_list(&mut doc, 3);
+
+        assert!(doc.remove_node(items[1]));
+        assert_eq!(doc.children(ul).collect::<Vec<_>>(), vec![items[0], items[2]]);
+        assert_links_consistent(&doc, ul);
+        assert_eq!(doc.parent(items[1]), None);
+        assert!(!doc.remove_node(items[1]));
+
+        assert!(doc.remove_node(items[0]));
+        assert!(doc.remove_node(items[2]));
+        assert_eq!(doc.first_child(ul), None);
+        assert_eq!(doc.last_child(ul), None);
+    }
+
+    #[test]
+    fn test_remove_subtree_and_compact() {
+        let mut doc = Document::new();
+        let (ul, items) = create_list(&mut doc, 4);
+        let text = doc.create_text("kept");
+        doc.append_child(items[2], text);
+        let removed_text = doc.create_text("removed");
+        doc.append_child(items[1], removed_text);
+        let orphan = doc.create_element("li", HashMap::new());
+
+        assert!(doc.remove_subtree(items[1]));
+        assert!(!doc.remove_subtree(items[1]));
+        assert_eq!(doc.get(removed_text).unwrap().kind.as_text(), Some(""));
+        assert_links_consistent(&doc, ul);
+        assert_eq!(doc.len(), 8);
+
+        let ids = doc.compact();
+        assert_eq!(doc.len(), 5);
+        assert_eq!(ids.len(), 5);
+        for gone in [items[1], removed_text, orphan] {
+            assert!(!ids.contains_key(&gone));
+        }
+        let ul = ids[&ul];
+        assert_eq!(doc.root(), Some(ul));
+        assert_links_consistent(&doc, ul);
+        let kept: Vec<_> = [items[0], items[2], items[3]].iter().map(|id| ids[id]).collect();
+        assert_eq!(doc.children(ul).collect::<Vec<_>>(), kept);
+        assert_eq!(doc.first_child(kept[1]), Some(ids[&text]));
+        assert_eq!(doc.get(ids[&text]).unwrap().kind.as_text(), Some("kept"));
+        assert!(doc.index().is_some());
+    }
+
+    #[test]
+    fn test_line_follows_compact() {
+        let mut soup = crate::Soup::parse("<ul>\n<li>A</li>\n<li>B\n</li>\n<li\nid=c>C</li></ul>");
+        let items: Vec<_> = soup.select("li").unwrap().iter().map(crate::Tag::node_id).collect();
+        let doc = soup.document_mut();
+        let lines: Vec<_> = items.iter().map(|&id| doc.line(id)).collect();
+        assert_eq!(lines, [Some(2), Some(3), Some(6)]);
+        assert_eq!(doc.line(doc.first_child(items[0]).unwrap()), None);
+        let added = doc.create_element("li", HashMap::new());
+        assert_eq!(doc.line(added), None);
+
+        assert!(doc.remove_subtree(items[0]));
+        let ids = doc.compact();
+        assert_eq!(doc.line(ids[&items[1]]), Some(3));
+        assert_eq!(doc.line(ids[&items[2]]), Some(6));
+    }
+
+    #[test]
+    fn test_replace_node() {
+        let mut doc = Document::new();
+        let (ul, items) = create_list(&mut doc, 3);
+        let replacement = doc.create_text("x");
+
+        assert!(doc.replace_node(items[1], replacement));
+        assert_eq!(doc.children(ul).collect::<Vec<_>>(), vec![items[0], replacement, items[2]]);
+        assert_eq!(doc.parent(items[1]), None);
+        assert_links_consistent(&doc, ul);
+    }
+
+    #[test]
+    fn test_wrap_and_unwrap() {
+        let mut doc = Document::new();
+        let (ul, items) = create_list(&mut doc, 3);
+        let wrapper = doc.create_element("div", HashMap::new());
+
+        assert!(doc.wrap(items[1], wrapper));
+        assert_eq!(doc.children(ul).collect::<Vec<_>>(), vec![items[0], wrapper, items[2]]);
+        assert_eq!(doc.children(wrapper).collect::<Vec<_>>(), vec![items[1]]);
+
+        assert!(!doc.unwrap(ul));
+        assert!(doc.unwrap(wrapper));
+        assert_eq!(doc.children(ul).collect::<Vec<_>>(), items);
+        assert_eq!(doc.parent(wrapper), None);
+        assert_links_consistent(&doc, ul);
+
+        let text = doc.create_text("not an element");
+        assert!(!doc.wrap(items[0], text));
+    }
+
+    #[test]
+    fn test_set_and_remove_attribute() {
+        let mut doc = Document::new();
+        let (ul, _) = create_list(&mut doc, 0);
+        let text = doc.create_text("t");
+        doc.set_index(DocumentIndex::new());
+
+        assert!(doc.set_attribute(ul, "class", "menu"));
+        assert!(doc.index().is_none());
+        assert_eq!(&doc.get(ul).unwrap().kind.attributes().unwrap()["class"], "menu");
+        assert_eq!(doc.remove_attribute(ul, "class").as_deref(), Some("menu"));
+        assert_eq!(doc.remove_attribute(ul, "class"), None);
+        assert!(!doc.set_attribute(text, "class", "menu"));
+    }
+
+    #[test]
+    fn test_subtree_copies_only_descendants() {
+        let doc = create_test_doc();
+        let body = doc.children(doc.root().unwrap()).nth(1).unwrap();
+
+        let copy = doc.subtree(body).unwrap();
+        assert_eq!(copy.len(), 3);
+
+        let root = copy.root().unwrap();
+        assert_eq!(copy.get(root).unwrap().kind.tag_name(), Some("body"));
+        assert_eq!(copy.parent(root), None);
+        assert_links_consistent(&copy, root);
+
+        let div = copy.first_child(root).unwrap();
+        assert_eq!(copy.index().unwrap().get_by_tag("div"), &[div]);
+        let text = copy.first_child(div).unwrap();
+        assert_eq!(copy.get(text).unwrap().kind.as_text(), Some("Hello"));
+    }
+
+    #[test]
+    fn test_subtree_preserves_order_and_attributes() {
+        let mut doc = Document::new();
+        let (ul, items) = create_list(&mut doc, 3);
+        doc.set_attribute(items[1], "id", "second");
+
+        let copy = doc.subtree(ul).unwrap();
+        let root = copy.root().unwrap();
+        let children: Vec<_> = copy.children(root).collect();
+        assert_eq!(children.len(), 3);
+        assert_eq!(copy.index().unwrap().get_by_id("second"), Some(children[1]));
+        assert!(doc.subtree(NodeId::new(99)).is_none());
+    }
+
+    #[test]
+    fn test_append_child_drops_index() {
+        let mut soup = crate::Soup::parse("<ul><li>A</li></ul>");
+        let ul = soup.find("ul").unwrap().unwrap().node_id();
+        let doc = soup.document_mut();
+        let li = doc.create_element("li", [("id", "new"), ("class", "x")]);
+        doc.append_child(ul, li);
+        assert!(doc.index().is_none());
+
+        assert_eq!(soup.find("#new").unwrap().map(|tag| tag.node_id()), Some(li));
+        assert_eq!(soup.find_all(".x").unwrap().len(), 1);
+        assert_eq!(soup.find_all("li").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_append_copies_extends_index() {
+        let mut page = Document::new();
+        let (page_ul, page_items) = create_list(&mut page, 2);
+        page.set_attribute(page_items[1], "id", "last");
+
+        let mut doc = Document::new();
+        let (ul, items) = create_list(&mut doc, 1);
+        doc.set_index(DocumentIndex::from_document(&doc));
+        let copies = doc.append_copies(ul, &page, &page_items);
+
+        assert_eq!(copies.len(), 2);
+        assert_links_consistent(&doc, ul);
+        assert_eq!(doc.children(ul).collect::<Vec<_>>(), [items[0], copies[0], copies[1]]);
+        let index = doc.index().unwrap();
+        assert_eq!(index.get_by_tag("li"), [items[0], copies[0], copies[1]]);
+        assert_eq!(index.get_by_id("last"), Some(copies[1]));
+        assert_eq!(page.children(page_ul).count(), 2);
+    }
+
+    #[test]
+    fn test_append_copies_rebuilds_index_mid_document() {
+        let mut page = Document::new();
+        let (_, page_items) = create_list(&mut page, 1);
+
+        let mut doc = Document::new();
+        let (_, items) = create_list(&mut doc, 2);
+        doc.set_index(DocumentIndex::from_document(&doc));
+        let copies = doc.append_copies(items[0], &page, &page_items);
+
+        let index = doc.index().unwrap();
+        assert_eq!(index.get_by_tag("li"), [items[0], copies[0], items[1]]);
+        let text = doc.create_text("t");
+        assert!(doc.append_copies(text, &page, &page_items).is_empty());
+    }
 }
@@ -0,0 +1,254 @@
+//! Indented tree rendering for debugging and interactive exploration.
+
+use super::{
+    document::DocumentImpl,
+    node::{NodeId, NodeKind},
+    state::DocumentState,
+};
+
+const RESET: &str = "\x1b[0m";
+const TAG: &str = "\x1b[1;34m";
+const ID: &str = "\x1b[33m";
+const CLASS: &str = "\x1b[32m";
+const ATTR: &str = "\x1b[36m";
+const TEXT: &str = "\x1b[37m";
+const DIM: &str = "\x1b[90m";
+
+/// Options for [`Document::format_tree`](super::Document::format_tree).
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{FormatOptions, Soup};
+///
+/// let soup = Soup::parse("<ul id=\"nav\"><li class=\"a b\">One</li></ul>");
+/// let options = FormatOptions { max_depth: Some(3), ..FormatOptions::default() };
+/// let tree = soup.document().format_tree(options);
+/// assert!(tree.contains("ul#nav"));
+/// assert!(tree.contains("li.a.b"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct FormatOptions {
+    /// Maximum depth to descend below the starting node; `None` renders the whole subtree.
+    pub max_depth: Option<usize>,
+    /// Number of spaces per nesting level.
+    pub indent: usize,
+    /// Whether to emit ANSI color codes.
+    pub color: bool,
+    /// Whether to render attributes other than `id` and `class`.
+    pub attributes: bool,
+    /// Whether to render text nodes; whitespace-only text is always skipped.
+    pub text: bool,
+    /// Whether to render comment nodes.
+    pub comments: bool,
+    /// Maximum number of characters shown for text and comments before truncating.
+    pub max_text_len: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            indent: 2,
+            color: false,
+            attributes: true,
+            text: true,
+            comments: false,
+            max_text_len: 40,
+        }
+    }
+}
+
+impl<S: DocumentState> DocumentImpl<S> {
+    /// Renders the document as an indented tree, one node per line.
+    ///
+    /// Elements are shown as `tag#id.class [attr="value"]`, text as quoted and
+    /// whitespace-collapsed previews. Subtrees cut off by
+    /// [`max_depth`](FormatOptions::max_depth) are summarized as `… (N children)`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{FormatOptions, Soup};
+    ///
+    /// let soup = Soup::parse("<div><p>Hello</p></div>");
+    /// let tree = soup.document().format_tree(FormatOptions::default());
+    /// assert!(tree.contains("    div\n      p\n        \"Hello\"\n"));
+    /// ```
+    #[must_use]
+    pub fn format_tree(&self, options: FormatOptions) -> String {
+        self.root().map(|root| self.format_subtree(root, options)).unwrap_or_default()
+    }
+
+    /// Renders the subtree rooted at `id` as an indented tree.
+    ///
+    /// See [`format_tree`](Self::format_tree) for the output format.
+    #[must_use]
+    pub fn format_subtree(&self, id: NodeId, options: FormatOptions) -> String {
+        let mut out = String::new();
+        let mut stack = vec![(id, 0)];
+
+        while let Some((id, depth)) = stack.pop() {
+            let Some(node) = self.get(id) else { continue };
+            if !format_node(&mut out, &node.kind, depth, &options) {
+                continue;
+            }
+
+            let children: Vec<NodeId> = self.children(id).collect();
+            if children.is_empty() {
+                continue;
+            }
+            if options.max_depth.is_some_and(|max| depth >= max) {
+                let count = children.len();
+                let label =
+                    format!("… ({count} {})", if count == 1 { "child" } else { "children" });
+                push_indent(&mut out, depth + 1, &options);
+                push_colored(&mut out, &label, DIM, &options);
+                out.push('\n');
+                continue;
+            }
+            stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+
+        out
+    }
+}
+
+/// Writes one line for a node; returns `false` if the node is not rendered.
+fn format_node(out: &mut String, kind: &NodeKind, depth: usize, options: &FormatOptions) -> bool {
+    match kind {
+        NodeKind::Element { name, attributes, .. } => {
+            push_indent(out, depth, options);
+            push_colored(out, name, TAG, options);
+            if let Some(id) = attributes.get("id") {
+                push_colored(out, &format!("#{id}"), ID, options);
+            }
+            if let Some(class) = attributes.get("class") {
+                for class in class.split_ascii_whitespace() {
+                    push_colored(out, &format!(".{class}"), CLASS, options);
+                }
+            }
+            if options.attributes {
+                let mut rest: Vec<_> = attributes
+                    .iter()
+                    .filter(|(name, _)| !matches!(name.as_str(), "id" | "class"))
+                    .collect();
+                rest.sort_unstable();
+                for (name, value) in rest {
+                    out.push(' ');
+                    let value = preview(value, options.max_text_len);
+                    push_colored(out, &format!("[{name}=\"{value}\"]"), ATTR, options);
+                }
+            }
+        }
+        NodeKind::Text { content } => {
+            if !options.text || content.trim().is_empty() {
+                return false;
+            }
+            push_indent(out, depth, options);
+            let text = format!("\"{}\"", preview(content, options.max_text_len));
+            push_colored(out, &text, TEXT, options);
+        }
+        NodeKind::Comment { content } => {
+            if !options.comments {
+                return false;
+            }
+            push_indent(out, depth, options);
+            let comment = format!("<!-- {} -->", preview(content, options.max_text_len));
+            push_colored(out, &comment, DIM, options);
+        }
+        NodeKind::Doctype { name, .. } => {
+            push_indent(out, depth, options);
+            push_colored(out, &format!("<!DOCTYPE {name}>"), DIM, options);
+        }
+    }
+    out.push('\n');
+    true
+}
+
+fn push_indent(out: &mut String, depth: usize, options: &FormatOptions) {
+    out.push_str(&" ".repeat(depth * options.indent));
+}
+
+fn push_colored(out: &mut String, s: &str, color: &str, options: &FormatOptions) {
+    if options.color {
+        out.push_str(color);
+        out.push_str(s);
+        out.push_str(RESET);
+    } else {
+        out.push_str(s);
+    }
+}
+
+/// Collapses whitespace and truncates to `max_len` characters, marking cuts with `…`.
+fn preview(s: &str, max_len: usize) -> String {
+    let collapsed = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= max_len {
+        return collapsed;
+    }
+    let mut truncated: String = collapsed.chars().take(max_len.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Soup, SoupConfig};
+
+    #[test]
+    fn test_format_tree_structure() {
+        let soup = Soup::parse(
+            r#"<html><head></head><body><div id="main" class="a  b" data-x="1"><p>Hello   <b>world</b></p><!-- note --></div></body></html>"#,
+        );
+        let tree = soup.document().format_tree(FormatOptions::default());
+        let expected = [
+            "html",
+            "  head",
+            "  body",
+            "    div#main.a.b [data-x=\"1\"]",
+            "      p",
+            "        \"Hello\"",
+            "        b",
+            "          \"world\"",
+        ];
+        assert_eq!(tree, expected.map(|line| format!("{line}\n")).concat());
+    }
+
+    #[test]
+    fn test_format_tree_options() {
+        let config = SoupConfig::builder().include_comments(true).build();
+        let soup = Soup::parse_with_config(
+            "<div title=\"t\"><p>Some long text here</p><!--c--></div>",
+            config,
+        );
+        let div = soup.find("div").unwrap().unwrap().node_id();
+
+        let options = FormatOptions {
+            attributes: false,
+            comments: true,
+            max_text_len: 8,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            soup.document().format_subtree(div, options),
+            "div\n  p\n    \"Some lo…\"\n  <!-- c -->\n"
+        );
+
+        let shallow = FormatOptions { max_depth: Some(0), ..FormatOptions::default() };
+        assert_eq!(
+            soup.document().format_subtree(div, shallow),
+            "div [title=\"t\"]\n  … (2 children)\n"
+        );
+    }
+
+    #[test]
+    fn test_format_tree_color() {
+        let soup = Soup::parse("<span id=\"x\">hi</span>");
+        let span = soup.find("span").unwrap().unwrap().node_id();
+        let options = FormatOptions { color: true, ..FormatOptions::default() };
+        let tree = soup.document().format_subtree(span, options);
+        assert_eq!(tree, format!("{TAG}span{RESET}{ID}#x{RESET}\n  {TEXT}\"hi\"{RESET}\n"));
+    }
+}
@@ -1,14 +1,17 @@
 //! Document indexing for fast element lookup.
 
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
-use super::NodeId;
+use super::{DocumentImpl, DocumentState, NodeId, NodeKind};
 
-/// Index for fast element lookup by ID and class.
+/// Index for fast element lookup by ID, class, and tag name.
+///
+/// Class and tag entries are kept in document order.
 #[derive(Debug, Default, Clone)]
 pub struct DocumentIndex {
-    by_id: HashMap<String, NodeId>,
-    by_class: HashMap<String, Vec<NodeId>>,
+    ids: HashMap<String, NodeId>,
+    classes: HashMap<String, Vec<NodeId>>,
+    tags: HashMap<String, Vec<NodeId>>,
 }
 
 impl DocumentIndex {
@@ -18,54 +21,118 @@ impl DocumentIndex {
         Self::default()
     }
 
+    /// Builds an index by walking the tree of `doc` in document order.
+    ///
+    /// The parser registers elements as it creates them, which matches document order
+    /// unless nodes are moved afterwards (foster parenting, misnested formatting tags);
+    /// in that case it rebuilds the index with this function.
+    #[must_use]
+    pub fn from_document<S: DocumentState>(doc: &DocumentImpl<S>) -> Self {
+        let mut index = Self::new();
+        let Some(root) = doc.root() else { return index };
+        for node_id in std::iter::once(root).chain(doc.descendants(root)) {
+            if let Some(node) = doc.get(node_id) {
+                index.register_node(&node.kind, node_id);
+            }
+        }
+        index
+    }
+
+    /// Registers an element's tag name, ID, and classes; other nodes are ignored.
+    ///
+    /// Nodes must be registered in document order.
+    pub(crate) fn register_node(&mut self, kind: &NodeKind, node_id: NodeId) {
+        let Some(tag) = kind.tag_name() else { return };
+        self.register_tag(tag, node_id);
+        if let Some(attributes) = kind.attributes() {
+            if let Some(id) = attributes.get("id") {
+                self.register_id(id.to_string(), node_id);
+            }
+            if let Some(classes) = attributes.get("class") {
+                self.register_classes(classes, node_id);
+            }
+        }
+    }
+
     /// Registers an element's ID.
     ///
     /// Per HTML spec, first occurrence wins if duplicate IDs exist.
     pub fn register_id(&mut self, id: String, node_id: NodeId) {
-        self.by_id.entry(id).or_insert(node_id);
+        self.ids.entry(id).or_insert(node_id);
     }
 
     /// Registers an element's classes.
     pub fn register_classes(&mut self, classes: &str, node_id: NodeId) {
         for class in classes.split_whitespace() {
-            self.by_class.entry(class.to_string()).or_default().push(node_id);
+            self.classes.entry(class.to_string()).or_default().push(node_id);
+        }
+    }
+
+    /// Registers an element's tag name.
+    ///
+    /// Names are kept in lowercase, so SVG's `foreignObject` and XML's `pubDate` are
+    /// found as `foreignobject` and `pubdate`, like full selector matching finds them.
+    pub fn register_tag(&mut self, tag: &str, node_id: NodeId) {
+        let tag = if tag.bytes().any(|b| b.is_ascii_uppercase()) {
+            Cow::Owned(tag.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(tag)
+        };
+        match self.tags.get_mut(tag.as_ref()) {
+            Some(nodes) => nodes.push(node_id),
+            None => {
+                self.tags.insert(tag.into_owned(), vec![node_id]);
+            }
         }
     }
 
     /// Looks up an element by ID.
     #[must_use]
     pub fn get_by_id(&self, id: &str) -> Option<NodeId> {
-        self.by_id.get(id).copied()
+        self.ids.get(id).copied()
     }
 
     /// Looks up elements by class.
     #[must_use]
     pub fn get_by_class(&self, class: &str) -> &[NodeId] {
-        self.by_class.get(class).map_or(&[], Vec::as_slice)
+        self.classes.get(class).map_or(&[], Vec::as_slice)
+    }
+
+    /// Looks up elements by tag name, given in lowercase.
+    #[must_use]
+    pub fn get_by_tag(&self, tag: &str) -> &[NodeId] {
+        self.tags.get(tag).map_or(&[], Vec::as_slice)
     }
 
     /// Returns whether the index is empty.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.by_id.is_empty() && self.by_class.is_empty()
+        self.ids.is_empty() && self.classes.is_empty() && self.tags.is_empty()
     }
 
     /// Returns the number of indexed IDs.
     #[must_use]
     pub fn id_count(&self) -> usize {
-        self.by_id.len()
+        self.ids.len()
     }
 
     /// Returns the number of indexed classes.
     #[must_use]
     pub fn class_count(&self) -> usize {
-        self.by_class.len()
+        self.classes.len()
+    }
+
+    /// Returns the number of indexed tag names.
+    #[must_use]
+    pub fn tag_count(&self) -> usize {
+        self.tags.len()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dom::Attributes;
 
     #[test]
     fn test_index_register_id() {
@@ -95,6 +162,40 @@ mod tests {
         assert_eq!(index.get_by_class("qux"), &[]);
     }
 
+    #[test]
+    fn test_index_register_tag() {
+        let mut index = DocumentIndex::new();
+        index.register_tag("li", NodeId::new(1));
+        index.register_tag("li", NodeId::new(3));
+        index.register_tag("ul", NodeId::new(2));
+
+        assert_eq!(index.get_by_tag("li"), &[NodeId::new(1), NodeId::new(3)]);
+        assert_eq!(index.get_by_tag("ul"), &[NodeId::new(2)]);
+        assert_eq!(index.get_by_tag("p"), &[]);
+        assert_eq!(index.tag_count(), 2);
+        assert!(!index.is_empty());
+
+        index.register_tag("pubDate", NodeId::new(4));
+        assert_eq!(index.get_by_tag("pubdate"), &[NodeId::new(4)]);
+    }
+
+    #[test]
+    fn test_index_from_document_order() {
+        let mut doc = crate::Document::new();
+        let root = doc.create_element("div", HashMap::new());
+        doc.set_root(root);
+        let late = doc.create_element("p", Attributes::from([("class", "x")]));
+        let early = doc.create_element("p", Attributes::from([("id", "a"), ("class", "x")]));
+        doc.append_child(root, early);
+        doc.append_child(root, late);
+
+        let index = DocumentIndex::from_document(&doc);
+        assert_eq!(index.get_by_tag("p"), &[early, late]);
+        assert_eq!(index.get_by_class("x"), &[early, late]);
+        assert_eq!(index.get_by_id("a"), Some(early));
+        assert_eq!(index.get_by_tag("div"), &[root]);
+    }
+
     #[test]
     fn test_index_empty() {
         let index = DocumentIndex::new();
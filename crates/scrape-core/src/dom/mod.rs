@@ -17,23 +17,44 @@
 //!
 //! - Direct: [`Document::parent`], [`Document::first_child`], [`Document::last_child`]
 //! - Iterators: [`Document::children`], [`Document::ancestors`], [`Document::descendants`]
+//!
+//! [`Document::format_tree`] renders a document as an indented tree for debugging, and
+//! [`Document::strings`] collects its tag, attribute, and class vocabulary.
+//!
+//! With the `serde` feature, [`Document`] and [`Node`] implement `Serialize` and
+//! `Deserialize`, so a parsed document can be stored or sent elsewhere and restored
+//! without parsing the HTML again.
 
 mod arena;
+mod atom;
+mod attributes;
 mod document;
+mod format;
 mod index;
 mod node;
 pub mod node_type;
+#[cfg(feature = "serde")]
+mod serde_impl;
 pub mod state;
+mod strings;
 mod tag_id;
+mod text;
 
+pub use atom::Atom;
+pub use attributes::{Attributes, AttributesIter, IntoIter as AttributesIntoIter};
 #[allow(unused_imports)]
 pub use document::{
     AncestorsIter, ChildrenIter, DescendantsIter, Document, DocumentImpl, ElementAncestorsIter,
     ElementChildrenIter, ElementDescendantsIter, ElementNextSiblingsIter, ElementPrevSiblingsIter,
     ElementSiblingsIter, NextSiblingsIter, PrevSiblingsIter, SiblingsIter,
 };
+pub use format::FormatOptions;
 pub use index::DocumentIndex;
-pub use node::{Node, NodeId, NodeKind};
+pub use node::{Doctype, Namespace, Node, NodeId, NodeKind};
 pub use node_type::{CommentMarker, ElementMarker, NodeType, TextMarker};
+#[cfg(feature = "serde")]
+pub use serde_impl::json_tree;
 pub use state::{Building, DocumentState, MutableState, Queryable, QueryableState, Sealed};
+pub use strings::StringTable;
 pub use tag_id::TagId;
+pub use text::TextContent;
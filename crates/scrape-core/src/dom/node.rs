@@ -1,28 +1,94 @@
 //! DOM node types and identifiers.
 
-use std::collections::HashMap;
+use std::{fmt, num::NonZeroU32};
 
-use super::tag_id::TagId;
+use super::{atom::Atom, attributes::Attributes, tag_id::TagId, text::TextContent};
 
 /// A node ID in the DOM tree.
 ///
-/// This is an opaque handle to a node in the document.
-/// The inner value is `pub(crate)` to allow internal indexing while
-/// preventing external construction.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct NodeId(pub(crate) usize);
+/// This is an opaque handle to a node in the document. It is stored as a non-zero
+/// 32-bit value, so the `Option<NodeId>` links every node holds take 4 bytes each.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(NonZeroU32);
 
 impl NodeId {
+    /// The number of distinct node IDs, and so the most nodes a document can hold.
+    pub(crate) const MAX_COUNT: usize = u32::MAX as usize;
+
     /// Creates a new node ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is [`MAX_COUNT`](Self::MAX_COUNT) or larger. The parsers check
+    /// the limit and return [`ParseError::TooManyNodes`](crate::parser::ParseError)
+    /// instead of reaching it.
+    #[must_use]
+    pub(crate) fn new(id: usize) -> Self {
+        Self::try_new(id).expect("node index exceeds the u32 range")
+    }
+
+    /// Creates a new node ID, or returns `None` if `id` is out of range.
     #[must_use]
-    pub(crate) const fn new(id: usize) -> Self {
-        Self(id)
+    pub(crate) fn try_new(id: usize) -> Option<Self> {
+        let id = u32::try_from(id).ok()?.checked_add(1)?;
+        NonZeroU32::new(id).map(Self)
     }
 
     /// Returns the raw ID value (for internal use).
     #[must_use]
     pub(crate) const fn index(self) -> usize {
-        self.0
+        (self.0.get() - 1) as usize
+    }
+}
+
+impl fmt::Debug for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NodeId").field(&self.index()).finish()
+    }
+}
+
+/// The namespace of an element.
+///
+/// HTML parsing puts `<svg>` and `<math>` subtrees in their own namespaces; every other
+/// element is in the HTML namespace. XML parsing takes each element's namespace from the
+/// `xmlns` declarations in scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Namespace {
+    /// The HTML namespace.
+    #[default]
+    Html,
+    /// The SVG namespace, for `<svg>` and its descendants.
+    Svg,
+    /// The `MathML` namespace, for `<math>` and its descendants.
+    MathMl,
+    /// Any other namespace, or none, for elements of XML documents.
+    Other,
+}
+
+impl Namespace {
+    /// Returns the namespace URL, such as `http://www.w3.org/2000/svg`.
+    ///
+    /// [`Other`](Self::Other) has no URL and returns an empty string.
+    #[must_use]
+    pub const fn url(self) -> &'static str {
+        match self {
+            Self::Html => "http://www.w3.org/1999/xhtml",
+            Self::Svg => "http://www.w3.org/2000/svg",
+            Self::MathMl => "http://www.w3.org/1998/Math/MathML",
+            Self::Other => "",
+        }
+    }
+
+    /// Returns the namespace with the given URL, or `None` if it is not an element
+    /// namespace.
+    #[must_use]
+    pub fn from_url(url: &str) -> Option<Self> {
+        [Self::Html, Self::Svg, Self::MathMl].into_iter().find(|ns| ns.url() == url)
     }
 }
 
@@ -33,21 +99,39 @@ pub enum NodeKind {
     Element {
         /// Interned tag identifier for fast comparison.
         tag_id: TagId,
-        /// Tag name (lowercase).
-        name: String,
-        /// Element attributes.
-        attributes: HashMap<String, String>,
+        /// Tag name: lowercase for HTML, as written for XML.
+        name: Atom,
+        /// Element attributes, in source order.
+        ///
+        /// Attributes in a namespace, such as `xlink:href` on SVG elements, are keyed by
+        /// the namespace URL and local name, e.g. `http://www.w3.org/1999/xlink:href`.
+        attributes: Attributes,
+        /// Element namespace.
+        namespace: Namespace,
     },
     /// Text node.
     Text {
         /// Text content.
-        content: String,
+        content: TextContent,
     },
     /// Comment node.
     Comment {
         /// Comment content.
         content: String,
     },
+    /// Document type declaration, such as `<!DOCTYPE html>`.
+    ///
+    /// A parsed document keeps it, like comments outside `<html>`, as a sibling of the
+    /// root element. The fields are interned like tag names, since documents share a few
+    /// well-known identifiers.
+    Doctype {
+        /// Doctype name, such as `html`.
+        name: Atom,
+        /// Public identifier; empty if there is none.
+        public_id: Atom,
+        /// System identifier; empty if there is none.
+        system_id: Atom,
+    },
 }
 
 impl NodeKind {
@@ -79,13 +163,22 @@ impl NodeKind {
 
     /// Returns the attributes if this is an element node.
     #[must_use]
-    pub fn attributes(&self) -> Option<&HashMap<String, String>> {
+    pub fn attributes(&self) -> Option<&Attributes> {
         match self {
             Self::Element { attributes, .. } => Some(attributes),
             _ => None,
         }
     }
 
+    /// Returns the namespace if this is an element node.
+    #[must_use]
+    pub const fn namespace(&self) -> Option<Namespace> {
+        match self {
+            Self::Element { namespace, .. } => Some(*namespace),
+            _ => None,
+        }
+    }
+
     /// Returns true if this element has the given tag ID (fast path).
     #[inline]
     #[must_use]
@@ -100,7 +193,7 @@ impl NodeKind {
     #[must_use]
     pub fn as_text(&self) -> Option<&str> {
         match self {
-            Self::Text { content } => Some(content),
+            Self::Text { content } => Some(content.as_str()),
             _ => None,
         }
     }
@@ -114,6 +207,17 @@ impl NodeKind {
         }
     }
 
+    /// Returns the doctype if this is a doctype node.
+    #[must_use]
+    pub fn as_doctype(&self) -> Option<Doctype<'_>> {
+        match self {
+            Self::Doctype { name, public_id, system_id } => {
+                Some(Doctype { name, public_id, system_id })
+            }
+            _ => None,
+        }
+    }
+
     /// Returns `true` if this is an element node.
     #[must_use]
     pub const fn is_element(&self) -> bool {
@@ -131,6 +235,34 @@ impl NodeKind {
     pub const fn is_comment(&self) -> bool {
         matches!(self, Self::Comment { .. })
     }
+
+    /// Returns `true` if this is a doctype node.
+    #[must_use]
+    pub const fn is_doctype(&self) -> bool {
+        matches!(self, Self::Doctype { .. })
+    }
+}
+
+/// A document type declaration, borrowed from a [`NodeKind::Doctype`] node.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::Soup;
+///
+/// let soup = Soup::parse("<!DOCTYPE html><p>Hi</p>");
+/// let doctype = soup.doctype().unwrap();
+/// assert_eq!(doctype.name, "html");
+/// assert!(doctype.public_id.is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Doctype<'a> {
+    /// Doctype name, such as `html`.
+    pub name: &'a str,
+    /// Public identifier; empty if there is none.
+    pub public_id: &'a str,
+    /// System identifier; empty if there is none.
+    pub system_id: &'a str,
 }
 
 /// A node in the DOM tree.
@@ -139,8 +271,9 @@ impl NodeKind {
 /// and `prev_sibling`/`next_sibling` for sibling relationships. This linked structure
 /// eliminates per-node `Vec` allocations and enables O(1) append operations.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
-    /// The kind of node (element, text, or comment).
+    /// The kind of node (element, text, comment, or doctype).
     pub kind: NodeKind,
     /// Parent node, if any.
     pub parent: Option<NodeId>,
@@ -155,13 +288,25 @@ pub struct Node {
 }
 
 impl Node {
-    /// Creates a new element node.
+    /// Creates a new element node in the HTML namespace.
+    #[must_use]
+    pub fn element(name: impl Into<Atom>, attributes: impl Into<Attributes>) -> Self {
+        Self::element_ns(name, attributes, Namespace::Html)
+    }
+
+    /// Creates a new element node in the given namespace.
     #[must_use]
-    pub fn element(name: impl Into<String>, attributes: HashMap<String, String>) -> Self {
+    pub fn element_ns(
+        name: impl Into<Atom>,
+        attributes: impl Into<Attributes>,
+        namespace: Namespace,
+    ) -> Self {
         let name = name.into();
-        let tag_id = TagId::from_name(&name);
+        // Tag IDs name HTML elements; an XML `<link>` is not one.
+        let tag_id =
+            if namespace == Namespace::Other { TagId::Unknown } else { TagId::from_name(&name) };
         Self {
-            kind: NodeKind::Element { tag_id, name, attributes },
+            kind: NodeKind::Element { tag_id, name, attributes: attributes.into(), namespace },
             parent: None,
             first_child: None,
             last_child: None,
@@ -172,7 +317,7 @@ impl Node {
 
     /// Creates a new text node.
     #[must_use]
-    pub fn text(content: impl Into<String>) -> Self {
+    pub fn text(content: impl Into<TextContent>) -> Self {
         Self {
             kind: NodeKind::Text { content: content.into() },
             parent: None,
@@ -195,6 +340,27 @@ impl Node {
             next_sibling: None,
         }
     }
+
+    /// Creates a new doctype node.
+    #[must_use]
+    pub fn doctype(
+        name: impl Into<Atom>,
+        public_id: impl Into<Atom>,
+        system_id: impl Into<Atom>,
+    ) -> Self {
+        Self {
+            kind: NodeKind::Doctype {
+                name: name.into(),
+                public_id: public_id.into(),
+                system_id: system_id.into(),
+            },
+            parent: None,
+            first_child: None,
+            last_child: None,
+            prev_sibling: None,
+            next_sibling: None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -210,18 +376,30 @@ mod tests {
         assert_ne!(id1, id3);
     }
 
+    #[test]
+    fn node_id_is_compact() {
+        assert_eq!(std::mem::size_of::<Option<NodeId>>(), 4);
+        assert_eq!(NodeId::new(0).index(), 0);
+        assert_eq!(format!("{:?}", NodeId::new(7)), "NodeId(7)");
+        assert!(NodeId::try_new(NodeId::MAX_COUNT).is_none());
+        assert!(NodeId::try_new(NodeId::MAX_COUNT - 1).is_some());
+        assert_eq!(NodeId::try_new(5), Some(NodeId::new(5)));
+    }
+
     #[test]
     fn node_kind_element() {
         let kind = NodeKind::Element {
             tag_id: TagId::Div,
             name: "div".into(),
-            attributes: HashMap::new(),
+            attributes: Attributes::new(),
+            namespace: Namespace::Html,
         };
         assert!(kind.is_element());
         assert!(!kind.is_text());
         assert!(!kind.is_comment());
         assert_eq!(kind.tag_name(), Some("div"));
         assert_eq!(kind.tag_id(), Some(TagId::Div));
+        assert_eq!(kind.namespace(), Some(Namespace::Html));
     }
 
     #[test]
@@ -244,7 +422,7 @@ mod tests {
 
     #[test]
     fn node_element_constructor() {
-        let node = Node::element("div", HashMap::new());
+        let node = Node::element("div", Attributes::new());
         assert!(node.kind.is_element());
         assert!(node.parent.is_none());
         assert!(node.first_child.is_none());
@@ -0,0 +1,402 @@
+//! Serde support for documents and nodes.
+//!
+//! A [`Document`] serializes as its node arena, `{"root": 0, "nodes": [...]}`, in which
+//! each [`Node`] holds its kind and the ids of its parent, children, and siblings, so node
+//! ids stay valid across a round trip. Deserializing checks that the links form a tree
+//! and rebuilds the query index, without parsing any HTML.
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::Error as _,
+    ser::{SerializeMap, SerializeStruct},
+};
+
+use super::{
+    atom::Atom,
+    attributes::Attributes,
+    document::{Document, DocumentImpl},
+    node::{Namespace, Node, NodeId, NodeKind},
+    state::DocumentState,
+};
+
+impl Serialize for NodeId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.index().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NodeId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = usize::deserialize(deserializer)?;
+        Self::try_new(index)
+            .ok_or_else(|| D::Error::custom(format!("node id {index} is too large")))
+    }
+}
+
+impl Serialize for NodeKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Element { name, attributes, namespace, .. } => {
+                let len = if *namespace == Namespace::Html { 3 } else { 4 };
+                let mut state = serializer.serialize_struct("NodeKind", len)?;
+                state.serialize_field("type", "element")?;
+                state.serialize_field("name", name)?;
+                state.serialize_field("attributes", attributes)?;
+                if *namespace != Namespace::Html {
+                    state.serialize_field("namespace", namespace)?;
+                }
+                state.end()
+            }
+            Self::Text { content } => {
+                let mut state = serializer.serialize_struct("NodeKind", 2)?;
+                state.serialize_field("type", "text")?;
+                state.serialize_field("content", content.as_str())?;
+                state.end()
+            }
+            Self::Comment { content } => {
+                let mut state = serializer.serialize_struct("NodeKind", 2)?;
+                state.serialize_field("type", "comment")?;
+                state.serialize_field("content", content)?;
+                state.end()
+            }
+            Self::Doctype { name, public_id, system_id } => {
+                let mut state = serializer.serialize_struct("NodeKind", 4)?;
+                state.serialize_field("type", "doctype")?;
+                state.serialize_field("name", name)?;
+                state.serialize_field("public_id", public_id)?;
+                state.serialize_field("system_id", system_id)?;
+                state.end()
+            }
+        }
+    }
+}
+
+/// The serialized form of [`NodeKind`]; the tag id is derived from the name.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum NodeKindRepr {
+    Element {
+        name: Atom,
+        #[serde(default)]
+        attributes: Attributes,
+        #[serde(default)]
+        namespace: Namespace,
+    },
+    Text {
+        content: String,
+    },
+    Comment {
+        content: String,
+    },
+    Doctype {
+        name: Atom,
+        #[serde(default)]
+        public_id: Atom,
+        #[serde(default)]
+        system_id: Atom,
+    },
+}
+
+impl<'de> Deserialize<'de> for NodeKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match NodeKindRepr::deserialize(deserializer)? {
+            NodeKindRepr::Element { name, attributes, namespace } => {
+                Node::element_ns(name, attributes, namespace).kind
+            }
+            NodeKindRepr::Text { content } => Self::Text { content: content.into() },
+            NodeKindRepr::Comment { content } => Self::Comment { content },
+            NodeKindRepr::Doctype { name, public_id, system_id } => {
+                Self::Doctype { name, public_id, system_id }
+            }
+        })
+    }
+}
+
+impl<S: DocumentState> Serialize for DocumentImpl<S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        struct Nodes<'a, S: DocumentState>(&'a DocumentImpl<S>);
+
+        impl<S: DocumentState> Serialize for Nodes<'_, S> {
+            fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+                serializer.collect_seq(self.0.nodes().map(|(_, node)| node))
+            }
+        }
+
+        let mut state = serializer.serialize_struct("Document", 2)?;
+        state.serialize_field("root", &self.root())?;
+        state.serialize_field("nodes", &Nodes(self))?;
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+struct DocumentRepr {
+    root: Option<NodeId>,
+    nodes: Vec<Node>,
+}
+
+/// Restores a document serialized with its [`Serialize`] implementation.
+///
+/// # Errors
+///
+/// Fails if a node id is out of range or the parent, child, and sibling links do not form
+/// a tree.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Document, Soup, SoupConfig};
+///
+/// let soup = Soup::parse("<ul><li class=\"tea\">Green</li><li>Black</li></ul>");
+/// let json = serde_json::to_string(soup.document()).unwrap();
+///
+/// let document: Document = serde_json::from_str(&json).unwrap();
+/// let restored = Soup::from_document(document, SoupConfig::default());
+/// assert_eq!(restored.find("li.tea").unwrap().unwrap().text(), "Green");
+/// assert_eq!(restored.to_html(), soup.to_html());
+/// ```
+impl<'de> Deserialize<'de> for Document {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let DocumentRepr { root, nodes } = DocumentRepr::deserialize(deserializer)?;
+        check_tree(&nodes, root).map_err(|e| D::Error::custom(format!("invalid document: {e}")))?;
+        Ok(Self::from_nodes(nodes, root))
+    }
+}
+
+/// Checks that every link points at an existing node and that the links form a forest,
+/// with `root` at the top of one of its trees.
+///
+/// Parentless nodes other than the root may only have siblings if they are the root's,
+/// like the doctype of a parsed document.
+fn check_tree(nodes: &[Node], root: Option<NodeId>) -> Result<(), String> {
+    let get = |id: NodeId| nodes.get(id.index()).ok_or_else(|| format!("no node {}", id.index()));
+
+    let mut top_level = vec![false; nodes.len()];
+    if let Some(root) = root {
+        if get(root)?.parent.is_some() {
+            return Err(format!("root node {} has a parent", root.index()));
+        }
+        top_level[root.index()] = true;
+        let step = |node: &Node, forward: bool| {
+            if forward { node.next_sibling } else { node.prev_sibling }
+        };
+        for forward in [false, true] {
+            let mut current = root;
+            while let Some(next) = step(get(current)?, forward) {
+                let sibling = get(next)?;
+                if sibling.parent.is_some()
+                    || step(sibling, !forward) != Some(current)
+                    || top_level[next.index()]
+                {
+                    return Err("siblings of the root node are not linked consistently".into());
+                }
+                top_level[next.index()] = true;
+                current = next;
+            }
+        }
+    }
+
+    let mut linked = 0;
+    for (index, node) in nodes.iter().enumerate() {
+        let id = NodeId::new(index);
+        for link in
+            [node.parent, node.first_child, node.last_child, node.prev_sibling, node.next_sibling]
+        {
+            link.map(get).transpose()?;
+        }
+        if node.parent.is_none()
+            && !top_level[index]
+            && (node.prev_sibling.is_some() || node.next_sibling.is_some())
+        {
+            return Err(format!("node {index} has siblings but no parent"));
+        }
+
+        // Children must be doubly linked and point back at their parent.
+        let mut prev = None;
+        let mut next = node.first_child;
+        while let Some(child_id) = next {
+            let child = get(child_id)?;
+            if child.parent != Some(id) || child.prev_sibling != prev || linked == nodes.len() {
+                return Err(format!("children of node {index} are not linked consistently"));
+            }
+            linked += 1;
+            prev = Some(child_id);
+            next = child.next_sibling;
+        }
+        if prev != node.last_child {
+            return Err(format!("last child of node {index} is not linked consistently"));
+        }
+
+        // Walking up from any node must reach a parentless node.
+        let mut ancestor = node.parent;
+        for _ in 0..nodes.len() {
+            let Some(up) = ancestor else { break };
+            ancestor = get(up)?.parent;
+        }
+        if ancestor.is_some() {
+            return Err(format!("the ancestors of node {index} form a cycle"));
+        }
+    }
+
+    // Each listed child was checked to point back at its parent, so equal counts mean no
+    // node claims a parent that does not list it.
+    if linked != nodes.iter().filter(|node| node.parent.is_some()).count() {
+        return Err("a node is missing from its parent's children".into());
+    }
+    Ok(())
+}
+
+/// Returns `id` and its descendants as nested JSON objects; see
+/// [`Tag::to_json_tree`](crate::Tag::to_json_tree).
+pub fn json_tree<S: DocumentState>(doc: &DocumentImpl<S>, id: NodeId) -> serde_json::Value {
+    /// Serializes a node's kind followed by its `children`, without intermediate values.
+    struct Tree<'a, S: DocumentState>(&'a DocumentImpl<S>, NodeId, &'a NodeKind);
+
+    impl<S: DocumentState> Serialize for Tree<'_, S> {
+        fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+            let &Self(doc, id, kind) = self;
+            let mut map = serializer.serialize_map(None)?;
+            match kind {
+                NodeKind::Element { name, attributes, namespace, .. } => {
+                    map.serialize_entry("type", "element")?;
+                    map.serialize_entry("name", name)?;
+                    map.serialize_entry("attributes", attributes)?;
+                    if *namespace != Namespace::Html {
+                        map.serialize_entry("namespace", namespace)?;
+                    }
+                    let children: Vec<_> = doc
+                        .children(id)
+                        .filter_map(|child| doc.get(child).map(|node| Tree(doc, child, &node.kind)))
+                        .collect();
+                    map.serialize_entry("children", &children)?;
+                }
+                NodeKind::Text { content } => {
+                    map.serialize_entry("type", "text")?;
+                    map.serialize_entry("content", content.as_str())?;
+                }
+                NodeKind::Comment { content } => {
+                    map.serialize_entry("type", "comment")?;
+                    map.serialize_entry("content", content)?;
+                }
+                NodeKind::Doctype { name, public_id, system_id } => {
+                    map.serialize_entry("type", "doctype")?;
+                    map.serialize_entry("name", name)?;
+                    map.serialize_entry("public_id", public_id)?;
+                    map.serialize_entry("system_id", system_id)?;
+                }
+            }
+            map.end()
+        }
+    }
+
+    doc.get(id)
+        .and_then(|node| serde_json::to_value(Tree(doc, id, &node.kind)).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Soup, SoupConfig, dom::TagId};
+
+    fn round_trip(doc: &Document) -> Document {
+        serde_json::from_str(&serde_json::to_string(doc).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_node_kind_format() {
+        let soup = Soup::parse("<p id=\"a\">Hi</p><svg><circle/></svg>");
+        let p = soup.find("p").unwrap().unwrap();
+        let node = soup.document().get(p.node_id()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&node.kind).unwrap(),
+            serde_json::json!({"type": "element", "name": "p", "attributes": {"id": "a"}})
+        );
+        let circle = soup.find("circle").unwrap().unwrap();
+        let kind = &soup.document().get(circle.node_id()).unwrap().kind;
+        assert_eq!(serde_json::to_value(kind).unwrap()["namespace"], "svg");
+
+        let kind: NodeKind = serde_json::from_str(r#"{"type": "element", "name": "div"}"#).unwrap();
+        assert_eq!(kind.tag_id(), Some(TagId::Div));
+        let json = r#"{"type": "element", "name": "link", "namespace": "other"}"#;
+        let kind: NodeKind = serde_json::from_str(json).unwrap();
+        assert_eq!(kind.tag_id(), Some(TagId::Unknown));
+        assert!(serde_json::from_str::<NodeKind>(r#"{"type": "text"}"#).is_err());
+    }
+
+    #[test]
+    fn test_document_round_trip_keeps_node_ids() {
+        let mut soup = Soup::parse("<!DOCTYPE html><div><p>One</p><p>Two</p><!-- note --></div>");
+        let first = soup.find("p").unwrap().unwrap().node_id();
+        soup.document_mut().remove_node(first);
+
+        let restored = round_trip(soup.document());
+        assert_eq!(restored.len(), soup.document().len());
+        assert_eq!(restored.root(), soup.document().root());
+        assert!(restored.index().is_some());
+        assert_eq!(restored.get(first).unwrap().parent, None);
+        assert!(restored.top_level().eq(soup.document().top_level()));
+        let restored = Soup::from_document(restored, SoupConfig::default());
+        assert_eq!(restored.to_html(), soup.to_html());
+        assert_eq!(restored.find_all("p").unwrap().len(), 1);
+
+        let empty = round_trip(&Document::default());
+        assert!(empty.is_empty() && empty.root().is_none());
+    }
+
+    #[test]
+    fn test_document_rejects_broken_links() {
+        // Each node lists its parent, first child, last child, previous and next sibling.
+        let parse = |nodes: &[[Option<usize>; 5]]| {
+            let nodes: Vec<_> = nodes
+                .iter()
+                .map(|&[parent, first_child, last_child, prev_sibling, next_sibling]| {
+                    serde_json::json!({
+                        "kind": {"type": "element", "name": "div"},
+                        "parent": parent,
+                        "first_child": first_child,
+                        "last_child": last_child,
+                        "prev_sibling": prev_sibling,
+                        "next_sibling": next_sibling,
+                    })
+                })
+                .collect();
+            serde_json::from_value::<Document>(serde_json::json!({"root": 0, "nodes": nodes}))
+        };
+        let leaf = [Some(0), None, None, None, None];
+
+        assert!(parse(&[[None, Some(1), Some(1), None, None], leaf]).is_ok());
+        assert!(parse(&[[None, Some(5), Some(5), None, None]]).is_err());
+        assert!(parse(&[[Some(1), Some(1), Some(1), None, None], leaf]).is_err());
+        assert!(parse(&[[None; 5], leaf]).is_err(), "unlisted child");
+        let root_sibling = [None, None, None, Some(0), None];
+        assert!(parse(&[[None, None, None, None, Some(1)], root_sibling]).is_ok());
+        assert!(parse(&[[None, None, None, None, Some(1)], [None; 5]]).is_err());
+        let error = parse(&[[None; 5], [None, None, None, None, Some(2)], [None; 5]]).unwrap_err();
+        assert!(error.to_string().contains("siblings but no parent"));
+        let error = parse(&[[None, Some(1), None, None, None], leaf]).unwrap_err();
+        assert!(error.to_string().starts_with("invalid document: last child of node 0"));
+    }
+
+    #[test]
+    fn test_json_tree() {
+        let config = SoupConfig::builder().include_comments(true).build();
+        let soup =
+            Soup::parse_with_config("<ul class=\"teas\"><li>Green</li><!-- more --></ul>", config);
+        let ul = soup.find("ul").unwrap().unwrap();
+        assert_eq!(
+            json_tree(soup.document(), ul.node_id()),
+            serde_json::json!({
+                "type": "element",
+                "name": "ul",
+                "attributes": {"class": "teas"},
+                "children": [
+                    {"type": "element", "name": "li", "attributes": {}, "children": [
+                        {"type": "text", "content": "Green"},
+                    ]},
+                    {"type": "comment", "content": " more "},
+                ],
+            })
+        );
+    }
+}
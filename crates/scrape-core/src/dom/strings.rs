@@ -0,0 +1,121 @@
+//! Tag, attribute, and class vocabulary for corpus-level analytics.
+
+use std::collections::BTreeMap;
+
+use super::{document::DocumentImpl, state::DocumentState};
+
+/// Distinct tag, attribute, and class names of a document with their counts.
+///
+/// Returned by [`Document::strings`](super::Document::strings). Names are sorted, and
+/// tables from many documents can be combined with [`merge`](Self::merge) to survey a
+/// whole crawl without running extraction passes.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::Soup;
+///
+/// let soup = Soup::parse("<p class=\"lead\">One</p><p class=\"lead note\">Two</p>");
+/// let strings = soup.document().strings();
+/// assert_eq!(strings.tags["p"], 2);
+/// assert_eq!(strings.classes["lead"], 2);
+/// assert_eq!(strings.attributes["class"], 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StringTable {
+    /// Number of elements per tag name.
+    pub tags: BTreeMap<String, usize>,
+    /// Number of elements carrying each attribute name.
+    pub attributes: BTreeMap<String, usize>,
+    /// Number of elements carrying each class name.
+    pub classes: BTreeMap<String, usize>,
+}
+
+impl StringTable {
+    /// Returns `true` if the table has no names.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.attributes.is_empty() && self.classes.is_empty()
+    }
+
+    /// Adds the counts of `other` to this table.
+    pub fn merge(&mut self, other: &Self) {
+        for (mine, theirs) in [
+            (&mut self.tags, &other.tags),
+            (&mut self.attributes, &other.attributes),
+            (&mut self.classes, &other.classes),
+        ] {
+            for (name, count) in theirs {
+                *mine.entry(name.clone()).or_default() += count;
+            }
+        }
+    }
+}
+
+impl<S: DocumentState> DocumentImpl<S> {
+    /// Collects the tag, attribute, and class names used in the document tree.
+    ///
+    /// Each element counts once per name, so a class repeated within one `class`
+    /// attribute is counted once. Nodes detached from the tree are ignored.
+    #[must_use]
+    pub fn strings(&self) -> StringTable {
+        let mut table = StringTable::default();
+        let Some(root) = self.root() else { return table };
+
+        for node_id in std::iter::once(root).chain(self.descendants(root)) {
+            let Some(node) = self.get(node_id) else { continue };
+            let Some(tag) = node.kind.tag_name() else { continue };
+            *table.tags.entry(tag.to_string()).or_default() += 1;
+
+            let Some(attributes) = node.kind.attributes() else { continue };
+            for name in attributes.keys() {
+                *table.attributes.entry(name.to_string()).or_default() += 1;
+            }
+            if let Some(classes) = attributes.get("class") {
+                let mut seen = Vec::new();
+                for class in classes.split_whitespace() {
+                    if !seen.contains(&class) {
+                        seen.push(class);
+                        *table.classes.entry(class.to_string()).or_default() += 1;
+                    }
+                }
+            }
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Soup;
+
+    #[test]
+    fn test_strings() {
+        let soup = Soup::parse(
+            r#"<div id="a" class="x y x"><a href="/" class="y">One</a><a href="/2">Two</a></div>"#,
+        );
+        let strings = soup.document().strings();
+
+        assert_eq!(strings.tags["a"], 2);
+        assert_eq!(strings.tags["div"], 1);
+        assert!(strings.tags.contains_key("html"));
+        assert_eq!(strings.attributes["href"], 2);
+        assert_eq!(strings.attributes["id"], 1);
+        assert_eq!(strings.classes.len(), 2);
+        assert_eq!(strings.classes["x"], 1);
+        assert_eq!(strings.classes["y"], 2);
+    }
+
+    #[test]
+    fn test_strings_merge() {
+        let mut total = Soup::parse("<p class=\"a\">1</p>").document().strings();
+        total.merge(&Soup::parse("<p class=\"a b\">2</p><br>").document().strings());
+
+        assert_eq!(total.tags["p"], 2);
+        assert_eq!(total.tags["br"], 1);
+        assert_eq!(total.classes["a"], 2);
+        assert_eq!(total.classes["b"], 1);
+        assert!(!total.is_empty());
+        assert!(Soup::parse("").document().strings().is_empty());
+    }
+}
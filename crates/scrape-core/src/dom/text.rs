@@ -0,0 +1,250 @@
+//! Text node content that can borrow from the parsed input.
+
+use std::{
+    borrow::Borrow,
+    fmt,
+    ops::{Deref, Range},
+    sync::Arc,
+};
+
+/// The content of a text node.
+///
+/// Text parsed with [`Soup::parse_borrowed`](crate::Soup::parse_borrowed) is stored as a
+/// byte range into a shared copy of the input rather than in its own allocation. Like a
+/// [`Cow`](std::borrow::Cow), it is copied into an owned `String` the first time it is
+/// modified. Only text nodes use it; attribute values are always owned.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use scrape_core::TextContent;
+///
+/// let source: Arc<str> = Arc::from("<p>Hello world</p>");
+/// let mut text = TextContent::shared(&source, 3..14).unwrap();
+/// assert_eq!(text, "Hello world");
+/// assert_eq!(text.span(), Some(3..14));
+///
+/// text.push_str("!");
+/// assert_eq!(text, "Hello world!");
+/// assert!(!text.is_shared());
+/// ```
+#[derive(Clone)]
+pub struct TextContent(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Owned(String),
+    /// A byte range of the parsed input; offsets are 32-bit, like [`NodeId`](super::NodeId).
+    Shared {
+        source: Arc<str>,
+        start: u32,
+        end: u32,
+    },
+}
+
+impl TextContent {
+    /// Creates owned text content.
+    #[must_use]
+    pub fn new(content: impl Into<String>) -> Self {
+        Self(Repr::Owned(content.into()))
+    }
+
+    /// Creates text content referring to `range` of `source`.
+    ///
+    /// Returns `None` if the range is out of bounds, does not fall on character
+    /// boundaries, or ends past 4 GiB.
+    #[must_use]
+    pub fn shared(source: &Arc<str>, range: Range<usize>) -> Option<Self> {
+        source.get(range.clone())?;
+        let start = u32::try_from(range.start).ok()?;
+        let end = u32::try_from(range.end).ok()?;
+        Some(Self(Repr::Shared { source: Arc::clone(source), start, end }))
+    }
+
+    /// Returns the text.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Owned(content) => content,
+            Repr::Shared { source, start, end } => &source[*start as usize..*end as usize],
+        }
+    }
+
+    /// Returns `true` if the text refers to the parsed input instead of owning a copy.
+    #[must_use]
+    pub const fn is_shared(&self) -> bool {
+        matches!(self.0, Repr::Shared { .. })
+    }
+
+    /// Returns the byte range of the text in the parsed input, if it refers to it.
+    #[must_use]
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self.0 {
+            Repr::Owned(_) => None,
+            Repr::Shared { start, end, .. } => Some(start as usize..end as usize),
+        }
+    }
+
+    /// Returns the text as a mutable `String`, copying it out of the input first if it
+    /// refers to it.
+    pub fn to_mut(&mut self) -> &mut String {
+        if let Repr::Shared { .. } = self.0 {
+            self.0 = Repr::Owned(self.as_str().to_string());
+        }
+        match &mut self.0 {
+            Repr::Owned(content) => content,
+            Repr::Shared { .. } => unreachable!("shared text was just copied"),
+        }
+    }
+
+    /// Appends text, copying the content out of the input first if it refers to it.
+    pub fn push_str(&mut self, text: &str) {
+        self.to_mut().push_str(text);
+    }
+
+    /// Extends the text by `range` of `source` without copying, if the text refers to
+    /// `source` and ends where `range` starts. Returns whether it was extended.
+    pub(crate) fn extend_shared(&mut self, source: &Arc<str>, range: Range<usize>) -> bool {
+        if let Repr::Shared { source: own, end, .. } = &mut self.0
+            && Arc::ptr_eq(own, source)
+            && *end as usize == range.start
+            && let Ok(new_end) = u32::try_from(range.end)
+        {
+            *end = new_end;
+            return true;
+        }
+        false
+    }
+
+    /// Releases spare capacity of owned text.
+    pub fn shrink_to_fit(&mut self) {
+        if let Repr::Owned(content) = &mut self.0 {
+            content.shrink_to_fit();
+        }
+    }
+
+    /// Returns the text as an owned `String`.
+    #[must_use]
+    pub fn into_string(self) -> String {
+        match self.0 {
+            Repr::Owned(content) => content,
+            Repr::Shared { source, start, end } => source[start as usize..end as usize].to_string(),
+        }
+    }
+}
+
+impl Default for TextContent {
+    fn default() -> Self {
+        Self::new(String::new())
+    }
+}
+
+impl Deref for TextContent {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for TextContent {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for TextContent {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for TextContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for TextContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq for TextContent {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for TextContent {}
+
+impl PartialEq<str> for TextContent {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for TextContent {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for TextContent {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl From<String> for TextContent {
+    fn from(content: String) -> Self {
+        Self::new(content)
+    }
+}
+
+impl From<&str> for TextContent {
+    fn from(content: &str) -> Self {
+        Self::new(content)
+    }
+}
+
+impl From<TextContent> for String {
+    fn from(content: TextContent) -> Self {
+        content.into_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_text_is_copied_on_write() {
+        let source: Arc<str> = Arc::from("<b>ab</b><i>cd</i>");
+        let mut text = TextContent::shared(&source, 3..5).unwrap();
+        assert!(text.is_shared());
+        assert_eq!(text, "ab");
+
+        assert!(!text.extend_shared(&source, 6..7));
+        assert!(!text.extend_shared(&Arc::from("<b>ab</b><i>cd</i>"), 5..6));
+        assert!(text.extend_shared(&source, 5..9));
+        assert_eq!(text, "ab</b>");
+
+        text.to_mut().truncate(1);
+        assert!(!text.is_shared());
+        assert_eq!(text.span(), None);
+        assert_eq!(text, TextContent::new("a"));
+        assert_eq!(Arc::strong_count(&source), 1);
+    }
+
+    #[test]
+    fn test_shared_rejects_invalid_ranges() {
+        let source: Arc<str> = Arc::from("caf\u{e9}");
+        assert!(TextContent::shared(&source, 0..4).is_none());
+        assert!(TextContent::shared(&source, 2..9).is_none());
+        assert_eq!(TextContent::shared(&source, 3..5).unwrap(), "\u{e9}");
+    }
+}
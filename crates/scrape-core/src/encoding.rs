@@ -0,0 +1,165 @@
+//! Character encoding detection for HTML bytes.
+//!
+//! [`decode`] and [`decode_with_label`] follow a simplified form of the HTML encoding
+//! sniffing algorithm. The first of these that applies picks the encoding:
+//!
+//! 1. A byte order mark.
+//! 2. An encoding supplied by the caller, e.g. from a `Content-Type` header.
+//! 3. A `<meta charset>` or `<meta http-equiv="Content-Type">` declaration in the first 1024 bytes.
+//! 4. UTF-8, if the bytes are valid UTF-8.
+//! 5. windows-1252, the web's legacy default.
+//!
+//! UTF-8 and pure ASCII input is borrowed instead of copied, so decoding it only costs a
+//! validation pass.
+//!
+//! Requires the `encoding` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::encoding::decode;
+//!
+//! let bytes = b"<meta charset=\"iso-8859-1\"><p>Caf\xe9</p>";
+//! let decoded = decode(bytes);
+//! assert_eq!(decoded.encoding, "windows-1252");
+//! assert!(decoded.html.ends_with("<p>Café</p>"));
+//! ```
+
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+
+use crate::{Error, Result};
+
+/// How many leading bytes are scanned for a `<meta>` encoding declaration.
+const PRESCAN_LEN: usize = 1024;
+
+/// HTML decoded from bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded<'a> {
+    /// The decoded document, borrowed from the input when no conversion was needed.
+    pub html: Cow<'a, str>,
+    /// The canonical name of the encoding used, such as `UTF-8` or `Shift_JIS`.
+    pub encoding: &'static str,
+    /// Whether malformed byte sequences were replaced with U+FFFD.
+    pub had_errors: bool,
+}
+
+/// Detects the encoding of `bytes` and decodes them.
+#[must_use]
+pub fn decode(bytes: &[u8]) -> Decoded<'_> {
+    decode_as(bytes, None)
+}
+
+/// Decodes `bytes` in the encoding named by `label`, such as `"latin1"` or `"utf-8"`.
+///
+/// The label overrides any `<meta>` declaration, but a byte order mark still takes
+/// precedence, as in browsers.
+///
+/// # Errors
+///
+/// Returns [`Error::UnknownEncoding`] if `label` is not a known encoding label.
+pub fn decode_with_label<'a>(bytes: &'a [u8], label: &str) -> Result<Decoded<'a>> {
+    let encoding = Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| Error::UnknownEncoding { label: label.to_string() })?;
+    Ok(decode_as(bytes, Some(encoding)))
+}
+
+fn decode_as<'a>(bytes: &'a [u8], given: Option<&'static Encoding>) -> Decoded<'a> {
+    let (encoding, bytes) = match Encoding::for_bom(bytes) {
+        Some((encoding, bom_len)) => (encoding, &bytes[bom_len..]),
+        None => (given.or_else(|| prescan(bytes)).unwrap_or_else(|| fallback(bytes)), bytes),
+    };
+    let (html, had_errors) = encoding.decode_without_bom_handling(bytes);
+    Decoded { html, encoding: encoding.name(), had_errors }
+}
+
+/// Returns UTF-8 for valid UTF-8, windows-1252 otherwise.
+fn fallback(bytes: &[u8]) -> &'static Encoding {
+    if Encoding::utf8_valid_up_to(bytes) == bytes.len() { UTF_8 } else { WINDOWS_1252 }
+}
+
+/// Looks for a `charset=` declaration inside a `<meta>` tag near the start of the document.
+fn prescan(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = bytes[..bytes.len().min(PRESCAN_LEN)].to_ascii_lowercase();
+    let mut rest = head.as_slice();
+    while let Some(start) = find(rest, b"<meta") {
+        let tag = &rest[start..];
+        let tag = &tag[..find(tag, b">").unwrap_or(tag.len())];
+        if let Some(encoding) = find(tag, b"charset").and_then(|at| charset_value(&tag[at..])) {
+            // A document that declares UTF-16 in ASCII is not actually UTF-16.
+            return Some(match encoding.name() {
+                "UTF-16LE" | "UTF-16BE" => UTF_8,
+                "x-user-defined" => WINDOWS_1252,
+                _ => encoding,
+            });
+        }
+        rest = &rest[start + tag.len()..];
+    }
+    None
+}
+
+/// Parses the label after `charset`, as in `charset="utf-8"` or `text/html; charset=utf-8`.
+fn charset_value(declaration: &[u8]) -> Option<&'static Encoding> {
+    let value = declaration.strip_prefix(b"charset")?.trim_ascii_start();
+    let value = value.strip_prefix(b"=")?.trim_ascii_start();
+    let value = value.strip_prefix(b"\"").or_else(|| value.strip_prefix(b"'")).unwrap_or(value);
+    let end = value
+        .iter()
+        .position(|&b| matches!(b, b'"' | b'\'' | b';' | b'/' | b'>') || b.is_ascii_whitespace())
+        .unwrap_or(value.len());
+    Encoding::for_label(&value[..end])
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    memchr::memmem::find(haystack, needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_utf8_is_borrowed() {
+        let decoded = decode("<p>Café</p>".as_bytes());
+        assert_eq!(decoded.encoding, "UTF-8");
+        assert!(matches!(decoded.html, Cow::Borrowed("<p>Café</p>")));
+        assert!(!decoded.had_errors);
+    }
+
+    #[test]
+    fn test_decode_precedence() {
+        // BOM beats the given label and the meta declaration.
+        let decoded =
+            decode_with_label(b"\xef\xbb\xbf<meta charset=latin1>\xc3\xa9", "sjis").unwrap();
+        assert_eq!(decoded.encoding, "UTF-8");
+        assert_eq!(decoded.html, "<meta charset=latin1>é");
+
+        // The given label beats the meta declaration.
+        let decoded = decode_with_label(b"<meta charset=utf-8>\xe9", " Latin1 ").unwrap();
+        assert_eq!(decoded.encoding, "windows-1252");
+        assert_eq!(decoded.html, "<meta charset=utf-8>é");
+
+        let decoded = decode(b"<p>\xe9</p>");
+        assert_eq!(decoded.encoding, "windows-1252");
+        assert_eq!(decoded.html, "<p>é</p>");
+
+        assert!(matches!(
+            decode_with_label(b"", "klingon"),
+            Err(Error::UnknownEncoding { label }) if label == "klingon"
+        ));
+    }
+
+    #[test]
+    fn test_prescan_meta() {
+        let html = b"<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; Charset=Shift_JIS\">";
+        assert_eq!(prescan(html), Some(encoding_rs::SHIFT_JIS));
+        assert_eq!(prescan(b"<meta name=x><meta charset='koi8-r'>"), Some(encoding_rs::KOI8_R));
+        assert_eq!(prescan(b"<meta charset=utf-16le>"), Some(UTF_8));
+        assert_eq!(prescan(b"<meta charset=bogus><p>"), None);
+        assert_eq!(prescan(b"<p>charset=latin1</p>"), None);
+
+        let late = [b" ".repeat(PRESCAN_LEN).as_slice(), b"<meta charset=latin1>"].concat();
+        assert_eq!(prescan(&late), None);
+    }
+}
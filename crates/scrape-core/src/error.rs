@@ -40,6 +40,19 @@ pub enum Error {
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Fetching a document over HTTP failed.
+    #[cfg(feature = "fetch")]
+    #[error(transparent)]
+    Fetch(#[from] crate::fetch::FetchError),
+
+    /// Unknown character encoding label.
+    #[cfg(feature = "encoding")]
+    #[error("unknown encoding: {label}")]
+    UnknownEncoding {
+        /// The label that names no known encoding.
+        label: String,
+    },
+
     /// Streaming parser in invalid state for this operation.
     #[cfg(feature = "streaming")]
     #[error("invalid streaming state: {message}")]
@@ -58,13 +71,22 @@ pub enum Error {
 
     /// Streaming selector compilation failed.
     #[cfg(feature = "streaming")]
-    #[error("streaming selector error: {message}")]
+    #[error("streaming selector error{}: {message}", format_position(span.as_ref()))]
     StreamingSelectorError {
         /// Description of the selector error.
         message: String,
+        /// Location of a syntax error in the selector, if known.
+        span: Option<SourceSpan>,
     },
 }
 
+#[cfg(feature = "streaming")]
+fn format_position(span: Option<&SourceSpan>) -> String {
+    span.map_or_else(String::new, |s| {
+        format!(" at line {}, column {}", s.start.line, s.start.column)
+    })
+}
+
 impl Error {
     /// Creates a new parse error with the given message.
     #[must_use]
@@ -108,7 +130,20 @@ impl Error {
     #[cfg(feature = "streaming")]
     #[must_use]
     pub fn streaming_selector_error(message: impl Into<String>) -> Self {
-        Self::StreamingSelectorError { message: message.into() }
+        Self::StreamingSelectorError { message: message.into(), span: None }
+    }
+
+    /// Returns the source span if available.
+    ///
+    /// Only selector syntax errors from the streaming handler registrations carry a span.
+    #[cfg(feature = "streaming")]
+    #[must_use]
+    pub const fn span(&self) -> Option<&SourceSpan> {
+        match self {
+            #[cfg(feature = "streaming")]
+            Self::StreamingSelectorError { span, .. } => span.as_ref(),
+            _ => None,
+        }
     }
 }
 
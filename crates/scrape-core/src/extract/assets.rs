@@ -0,0 +1,424 @@
+//! Inline asset extraction: `data:` URIs, inline SVG, and `<style>` blocks.
+//!
+//! [`inline_assets`] (also available as [`Soup::inline_assets`]) walks a document in
+//! document order and returns every embedded resource with its decoded bytes:
+//!
+//! - `data:` URIs in attribute values (`src`, `href`, `poster`, `data-*`, ...), and in `url(...)`
+//!   references inside `style` attributes and `<style>` blocks
+//! - Outermost `<svg>` elements, serialized as `image/svg+xml`
+//! - `<style>` blocks, as `text/css`
+//!
+//! Base64 payloads are decoded, other payloads are percent-decoded. Malformed `data:`
+//! URIs are skipped.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{Soup, extract::assets::AssetSource};
+//!
+//! let soup = Soup::parse(
+//!     r#"<img src="data:image/png;base64,iVBORw0KGgo=">
+//!        <div style="background: url('data:text/plain,hi%21')"></div>"#,
+//! );
+//! let assets = soup.inline_assets();
+//! assert_eq!(assets.len(), 2);
+//! assert_eq!(assets[0].mime, "image/png");
+//! assert_eq!(assets[0].data, b"\x89PNG\r\n\x1a\n");
+//! assert_eq!(assets[0].extension(), "png");
+//! assert_eq!(assets[1].source, AssetSource::Attribute("style".into()));
+//! assert_eq!(assets[1].data, b"hi!");
+//! ```
+
+use crate::{
+    Soup,
+    dom::{Document, NodeId, NodeKind, TagId},
+    serialize::{collect_text, serialize_node},
+};
+
+/// Where an inline asset was found on its element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetSource {
+    /// A `data:` URI in the named attribute, or in a `url(...)` inside a `style` attribute.
+    Attribute(String),
+    /// A `data:` URI in a `url(...)` inside a `<style>` block.
+    Stylesheet,
+    /// The element itself: an inline `<svg>` or a `<style>` block.
+    Element,
+}
+
+/// An embedded resource with its decoded content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineAsset {
+    /// The element the asset was found on.
+    pub node: NodeId,
+    /// Where on the element the asset was found.
+    pub source: AssetSource,
+    /// Lowercase MIME type without parameters, such as `image/png`.
+    pub mime: String,
+    /// Decoded bytes.
+    pub data: Vec<u8>,
+}
+
+impl InlineAsset {
+    /// Returns a file extension for the MIME type, or `bin` if it is not recognized.
+    #[must_use]
+    pub fn extension(&self) -> &'static str {
+        match self.mime.as_str() {
+            "image/png" => "png",
+            "image/jpeg" | "image/jpg" => "jpg",
+            "image/gif" => "gif",
+            "image/webp" => "webp",
+            "image/avif" => "avif",
+            "image/bmp" => "bmp",
+            "image/svg+xml" => "svg",
+            "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+            "font/woff2" | "application/font-woff2" => "woff2",
+            "font/woff" | "application/font-woff" => "woff",
+            "font/ttf" | "application/x-font-ttf" => "ttf",
+            "font/otf" | "application/x-font-opentype" => "otf",
+            "text/css" => "css",
+            "text/html" => "html",
+            "text/plain" => "txt",
+            "text/javascript" | "application/javascript" => "js",
+            "application/json" => "json",
+            "application/pdf" => "pdf",
+            _ => "bin",
+        }
+    }
+}
+
+/// Finds every inline asset in the document, in document order.
+///
+/// See the [module documentation](self) for what is collected.
+#[must_use]
+pub fn inline_assets(soup: &Soup) -> Vec<InlineAsset> {
+    let doc = soup.document();
+    let mut assets = Vec::new();
+    let Some(root) = doc.root() else {
+        return assets;
+    };
+
+    for id in std::iter::once(root).chain(doc.descendants(root)) {
+        let Some(NodeKind::Element { tag_id, name, attributes, .. }) = doc.get(id).map(|n| &n.kind)
+        else {
+            continue;
+        };
+
+        let mut attrs: Vec<_> = attributes.iter().collect();
+        attrs.sort_by_key(|&(name, _)| name);
+        for (attr, value) in attrs {
+            let source = AssetSource::Attribute(attr.to_string());
+            if attr == "style" {
+                push_css_urls(&mut assets, id, &source, value);
+            } else if let Some((mime, data)) = decode_data_uri(value) {
+                assets.push(InlineAsset { node: id, source, mime, data });
+            }
+        }
+
+        if *tag_id == TagId::Style {
+            let mut css = String::new();
+            collect_text(doc, id, &mut css);
+            push_css_urls(&mut assets, id, &AssetSource::Stylesheet, &css);
+            assets.push(element_asset(id, "text/css", css));
+        } else if name == "svg" && !inside_svg(doc, id) {
+            let mut svg = String::new();
+            serialize_node(doc, id, &mut svg);
+            assets.push(element_asset(id, "image/svg+xml", svg));
+        }
+    }
+
+    assets
+}
+
+/// Decodes a `data:` URI into its MIME type and bytes.
+///
+/// The MIME type is lowercased without parameters and defaults to `text/plain`.
+/// Returns `None` if `uri` is not a `data:` URI or its payload is malformed.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::assets::decode_data_uri;
+///
+/// let (mime, data) = decode_data_uri("data:image/gif;base64,R0lGODlh").unwrap();
+/// assert_eq!((mime.as_str(), data.as_slice()), ("image/gif", b"GIF89a".as_slice()));
+///
+/// let (mime, data) = decode_data_uri("data:,a%20b").unwrap();
+/// assert_eq!((mime.as_str(), data.as_slice()), ("text/plain", b"a b".as_slice()));
+///
+/// assert!(decode_data_uri("data:image/png;base64,***").is_none());
+/// ```
+#[must_use]
+pub fn decode_data_uri(uri: &str) -> Option<(String, Vec<u8>)> {
+    let uri = uri.trim();
+    let rest = uri.get(..5).filter(|s| s.eq_ignore_ascii_case("data:")).map(|_| &uri[5..])?;
+    let (header, payload) = rest.split_once(',')?;
+
+    let mut params = header.split(';').map(str::trim);
+    let mime = params.next().unwrap_or_default().to_ascii_lowercase();
+    let base64 = params.any(|p| p.eq_ignore_ascii_case("base64"));
+    let mime = if mime.is_empty() { "text/plain".to_string() } else { mime };
+
+    let data =
+        if base64 { decode_base64(&percent_decode(payload))? } else { percent_decode(payload) };
+    Some((mime, data))
+}
+
+/// Encodes bytes as a base64 `data:` URI with the given MIME type.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::assets::{decode_data_uri, encode_data_uri};
+///
+/// let uri = encode_data_uri("image/gif", b"GIF89a");
+/// assert_eq!(uri, "data:image/gif;base64,R0lGODlh");
+/// assert_eq!(decode_data_uri(&uri).unwrap().1, b"GIF89a");
+/// ```
+#[must_use]
+pub fn encode_data_uri(mime: &str, data: &[u8]) -> String {
+    format!("data:{mime};base64,{}", encode_base64(data))
+}
+
+// ==================== Helpers ====================
+
+fn element_asset(node: NodeId, mime: &str, content: String) -> InlineAsset {
+    InlineAsset {
+        node,
+        source: AssetSource::Element,
+        mime: mime.to_string(),
+        data: content.into_bytes(),
+    }
+}
+
+fn inside_svg(doc: &Document, id: NodeId) -> bool {
+    doc.ancestors(id).any(|a| doc.get(a).and_then(|n| n.kind.tag_name()) == Some("svg"))
+}
+
+/// Pushes the `data:` URIs referenced by `url(...)` in a CSS string.
+fn push_css_urls(assets: &mut Vec<InlineAsset>, node: NodeId, source: &AssetSource, css: &str) {
+    for (start, end) in css_urls(css) {
+        if let Some((mime, data)) = decode_data_uri(&css[start..end]) {
+            assets.push(InlineAsset { node, source: source.clone(), mime, data });
+        }
+    }
+}
+
+/// Replaces each `url(...)` reference in a CSS string for which `replace` returns a new
+/// URL, keeping the surrounding quotes and syntax.
+pub(crate) fn rewrite_css_urls(
+    css: &str,
+    mut replace: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut last = 0;
+    for (start, end) in css_urls(css) {
+        if let Some(url) = replace(&css[start..end]) {
+            out.push_str(&css[last..start]);
+            out.push_str(&url);
+            last = end;
+        }
+    }
+    out.push_str(&css[last..]);
+    out
+}
+
+/// Returns the byte ranges of the URLs referenced by `url(...)` in a CSS string.
+///
+/// Quoted URLs end at the closing quote, so unencoded parentheses inside them (common in
+/// SVG data URIs) are kept.
+fn css_urls(css: &str) -> Vec<(usize, usize)> {
+    let mut urls = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = find_ignore_case(&css[pos..], "url(") {
+        pos += found + 4;
+        pos += css[pos..].len() - css[pos..].trim_start().len();
+        let rest = &css[pos..];
+        let (start, end) = if let Some(quote @ ('"' | '\'')) = rest.chars().next() {
+            (1, rest[1..].find(quote).map_or(rest.len(), |i| i + 1))
+        } else {
+            let end = rest.find(')').unwrap_or(rest.len());
+            (0, rest[..end].trim_end().len())
+        };
+        urls.push((pos + start, pos + end));
+        pos += end;
+    }
+    urls
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let hex = |b: u8| char::from(b).to_digit(16);
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let (Some(&hi), Some(&lo)) = (bytes.get(i + 1), bytes.get(i + 2))
+            && let (Some(hi), Some(lo)) = (hex(hi), hex(lo))
+        {
+            #[allow(clippy::cast_possible_truncation)]
+            out.push((hi * 16 + lo) as u8);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Decodes standard or URL-safe base64, ignoring whitespace and missing padding.
+fn decode_base64(input: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() / 4 * 3);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut padding = false;
+
+    for &b in input {
+        let value = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => {
+                padding = true;
+                continue;
+            }
+            b if b.is_ascii_whitespace() => continue,
+            _ => return None,
+        };
+        if padding {
+            return None;
+        }
+        acc = (acc << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            #[allow(clippy::cast_possible_truncation)]
+            out.push((acc >> bits) as u8);
+        }
+    }
+
+    // A single leftover character cannot encode a whole byte.
+    (bits < 6).then_some(out)
+}
+
+/// Encodes standard base64 with padding.
+fn encode_base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i)) as usize & 63]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64(b"aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64(b"aGVsbG8").unwrap(), b"hello");
+        assert_eq!(decode_base64(b"aGVs\nbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64(b"-_8=").unwrap(), [0xfb, 0xff]);
+        assert!(decode_base64(b"aGVsb").is_none());
+        assert!(decode_base64(b"aG=Vs").is_none());
+    }
+
+    #[test]
+    fn test_encode_base64() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"h"), "aA==");
+        assert_eq!(encode_base64(b"hi"), "aGk=");
+        assert_eq!(encode_base64(b"hello"), "aGVsbG8=");
+        assert_eq!(encode_base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+        assert_eq!(
+            decode_base64(encode_base64(&[0, 1, 254, 255]).as_bytes()).unwrap(),
+            [0, 1, 254, 255]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_css_urls() {
+        let css = "a { background: url( 'x.png' ) } b { src: URL(y.woff) url(\"#f\") }";
+        let rewritten =
+            rewrite_css_urls(css, |url| (!url.starts_with('#')).then(|| format!("/{url}")));
+        assert_eq!(
+            rewritten,
+            "a { background: url( '/x.png' ) } b { src: URL(/y.woff) url(\"#f\") }"
+        );
+    }
+
+    #[test]
+    fn test_decode_data_uri() {
+        let (mime, data) =
+            decode_data_uri(" DATA:Image/SVG+XML;charset=utf-8,%3Csvg%2F%3E").unwrap();
+        assert_eq!(mime, "image/svg+xml");
+        assert_eq!(data, b"<svg/>");
+
+        let (mime, data) = decode_data_uri("data:;base64,aGk=").unwrap();
+        assert_eq!(mime, "text/plain");
+        assert_eq!(data, b"hi");
+
+        assert!(decode_data_uri("https://example.com/a.png").is_none());
+        assert!(decode_data_uri("data:image/png").is_none());
+    }
+
+    #[test]
+    fn test_inline_assets_order_and_sources() {
+        let soup = Soup::parse(
+            "<head><style>@font-face { src: url(data:font/woff2;base64,d09GMg==) \
+             }</style></head><body><svg><svg><circle r=\"1\"/></svg></svg><a \
+             href=\"data:text/plain,x\" title=\"data:,y\">link</a></body>",
+        );
+        let assets = inline_assets(&soup);
+        let summary: Vec<_> = assets.iter().map(|a| (a.source.clone(), a.mime.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (AssetSource::Stylesheet, "font/woff2"),
+                (AssetSource::Element, "text/css"),
+                (AssetSource::Element, "image/svg+xml"),
+                (AssetSource::Attribute("href".into()), "text/plain"),
+                (AssetSource::Attribute("title".into()), "text/plain"),
+            ]
+        );
+        assert_eq!(assets[0].data, b"wOF2");
+        assert_eq!(assets[0].extension(), "woff2");
+        assert!(String::from_utf8_lossy(&assets[2].data).starts_with("<svg><svg>"));
+    }
+
+    #[test]
+    fn test_css_urls_with_parentheses() {
+        let soup = Soup::parse(
+            "<div style='background:URL( \"data:image/svg+xml,<svg fill=rgb(0,0,0)/>\" )'></div>",
+        );
+        let assets = inline_assets(&soup);
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].data, b"<svg fill=rgb(0,0,0)/>");
+    }
+
+    #[test]
+    fn test_inline_assets_skips_malformed() {
+        let soup = Soup::parse("<img src=\"data:image/png;base64,!!!\"><p>data: not a uri</p>");
+        assert!(inline_assets(&soup).is_empty());
+    }
+}
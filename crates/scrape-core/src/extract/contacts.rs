@@ -0,0 +1,494 @@
+//! Email, phone number, and URL harvesting.
+//!
+//! [`contacts`] walks a document once, in document order, and collects:
+//!
+//! - Emails from `mailto:` links and from visible text
+//! - Phone numbers from `tel:` links and from visible text
+//! - Absolute `http(s)` URLs from `<a href>` and from visible text
+//!
+//! Text inside `<script>`, `<style>`, `<noscript>`, and `<template>` is ignored. Every
+//! value is validated and normalized before it is deduplicated, so `Info@Example.COM`
+//! and `mailto:info@example.com` are reported once.
+//!
+//! Phone numbers in free text are matched conservatively: a candidate must start with
+//! `+` or `(`, or be split into at least two digit groups by spaces or dashes, and it
+//! must not look like a date. Links are trusted as-is.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{Soup, extract::contacts};
+//!
+//! let soup = Soup::parse(
+//!     r#"<footer>
+//!         <a href="mailto:Sales@Example.com?subject=Hi">Email us</a>
+//!         <a href="tel:+1-555-010-9999">Call</a>
+//!         Support: support@example.com, +44 20 7946 0958
+//!         <a href="https://example.com/contact">Contact</a>
+//!     </footer>"#,
+//! );
+//! let found = contacts(&soup);
+//! assert_eq!(found.emails, vec!["sales@example.com", "support@example.com"]);
+//! assert_eq!(found.phones, vec!["+15550109999", "+442079460958"]);
+//! assert_eq!(found.urls, vec!["https://example.com/contact"]);
+//! ```
+
+use std::collections::HashSet;
+
+use crate::{
+    Soup,
+    dom::{NodeKind, TagId},
+};
+
+/// Shortest and longest digit counts accepted as a phone number (E.164 allows 15).
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 7..=15;
+
+/// Characters that may end a URL in free text but are usually punctuation.
+const URL_TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '\''];
+
+/// Contact details harvested from a document.
+///
+/// Each list is deduplicated and keeps the order in which values first appear.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Contacts {
+    /// Email addresses, lowercased.
+    pub emails: Vec<String>,
+    /// Phone numbers as digits, with a leading `+` when the source had one.
+    pub phones: Vec<String>,
+    /// Absolute `http` and `https` URLs.
+    pub urls: Vec<String>,
+}
+
+impl Contacts {
+    /// Returns `true` if no contact details were found.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.emails.is_empty() && self.phones.is_empty() && self.urls.is_empty()
+    }
+
+    /// Appends the values from `other` that are not already present.
+    pub fn merge(&mut self, other: Contacts) {
+        merge_unique(&mut self.emails, other.emails);
+        merge_unique(&mut self.phones, other.phones);
+        merge_unique(&mut self.urls, other.urls);
+    }
+}
+
+/// Harvests emails, phone numbers, and URLs from links and visible text.
+///
+/// See the [module documentation](self) for the matching rules.
+#[must_use]
+pub fn contacts(soup: &Soup) -> Contacts {
+    let mut collector = Collector::default();
+    let doc = soup.document();
+    let Some(root) = doc.root() else {
+        return collector.contacts;
+    };
+
+    for id in std::iter::once(root).chain(doc.descendants(root)) {
+        let Some(node) = doc.get(id) else { continue };
+        match &node.kind {
+            NodeKind::Element { tag_id: TagId::A, attributes, .. } => {
+                if let Some(href) = attributes.get("href") {
+                    collector.add_href(href.trim());
+                }
+            }
+            NodeKind::Text { content } => {
+                let hidden = doc.ancestors(id).any(|a| {
+                    doc.get(a).and_then(|n| n.kind.tag_id()).is_some_and(|tag| {
+                        matches!(
+                            tag,
+                            TagId::Script | TagId::Style | TagId::Noscript | TagId::Template
+                        )
+                    })
+                });
+                if !hidden {
+                    collector.add_text(content);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    collector.contacts
+}
+
+/// Finds all valid email addresses in free text, lowercased.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::contacts::find_emails;
+///
+/// assert_eq!(find_emails("Write to Jane.Doe@Example.org."), vec!["jane.doe@example.org"]);
+/// assert!(find_emails("user@localhost or @handle").is_empty());
+/// ```
+#[must_use]
+pub fn find_emails(text: &str) -> Vec<String> {
+    let bytes = text.as_bytes();
+    let mut found = Vec::new();
+    let mut resume = 0;
+
+    for (at, _) in text.match_indices('@') {
+        if at < resume {
+            continue;
+        }
+        let start = bytes[..at]
+            .iter()
+            .rposition(|&b| !is_text_local_byte(b))
+            .map_or(0, |i| i + 1)
+            .max(resume);
+        let end = bytes[at + 1..]
+            .iter()
+            .position(|&b| !is_domain_byte(b))
+            .map_or(text.len(), |i| at + 1 + i);
+        let candidate = text[start..end].trim_start_matches('.').trim_end_matches(['.', '-']);
+        if let Some(email) = normalize_email(candidate) {
+            found.push(email);
+            resume = end;
+        }
+    }
+
+    found
+}
+
+/// Finds phone numbers in free text and returns them normalized.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::contacts::find_phones;
+///
+/// assert_eq!(find_phones("Call (555) 010-9999 today"), vec!["5550109999"]);
+/// assert_eq!(find_phones("Tel. +49 30 901820"), vec!["+4930901820"]);
+/// assert!(find_phones("Order 12345678 placed 2024-03-05").is_empty());
+/// ```
+#[must_use]
+pub fn find_phones(text: &str) -> Vec<String> {
+    let bytes = text.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        let starts = (b.is_ascii_digit() || b == b'+' || b == b'(')
+            && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric());
+        if !starts {
+            i += 1;
+            continue;
+        }
+
+        let mut end = i + 1;
+        while end < bytes.len() && is_phone_byte(bytes[end]) {
+            end += 1;
+        }
+        let candidate = text[i..end].trim_end_matches(|c: char| !c.is_ascii_digit());
+        // A letter glued to the last digit means this is part of a word, not a phone number.
+        let glued = bytes.get(i + candidate.len()).is_some_and(u8::is_ascii_alphanumeric);
+
+        if !glued
+            && looks_like_phone(candidate)
+            && let Some(phone) = normalize_phone(candidate)
+        {
+            found.push(phone);
+        }
+        i = end.max(i + 1);
+    }
+
+    found
+}
+
+/// Finds absolute `http` and `https` URLs in free text.
+///
+/// Trailing punctuation such as a sentence-ending period is not part of the URL.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::contacts::find_urls;
+///
+/// assert_eq!(
+///     find_urls("See https://example.com/docs. Or (http://example.org)"),
+///     vec!["https://example.com/docs", "http://example.org"]
+/// );
+/// ```
+#[must_use]
+pub fn find_urls(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut rest = text;
+
+    while let Some(pos) = rest.find("http") {
+        let candidate = &rest[pos..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '`'))
+            .unwrap_or(candidate.len());
+        let url = candidate[..end].trim_end_matches(URL_TRAILING_PUNCTUATION);
+        if is_http_url(url) {
+            found.push(url.to_string());
+            rest = &candidate[end..];
+        } else {
+            rest = &candidate[4..];
+        }
+    }
+
+    found
+}
+
+/// Validates an email address and returns it lowercased.
+///
+/// Accepts the dot-atom form used on real pages: a local part of letters, digits, and
+/// ``.!#$%&'*+/=?^_`{|}~-``, and a domain with at least two labels and an alphabetic
+/// top-level domain.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::contacts::normalize_email;
+///
+/// assert_eq!(normalize_email("Info@Example.COM").as_deref(), Some("info@example.com"));
+/// assert_eq!(normalize_email("a..b@example.com"), None);
+/// assert_eq!(normalize_email("name@example"), None);
+/// ```
+#[must_use]
+pub fn normalize_email(email: &str) -> Option<String> {
+    let (local, domain) = email.split_once('@')?;
+    let local_ok = (1..=64).contains(&local.len())
+        && local.bytes().all(is_local_byte)
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..");
+    (local_ok && is_valid_domain(domain)).then(|| email.to_ascii_lowercase())
+}
+
+/// Validates a phone number and normalizes it to digits with an optional leading `+`.
+///
+/// Spaces, dashes, dots, slashes, and parentheses are accepted as separators. The
+/// number must have between 7 and 15 digits.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::contacts::normalize_phone;
+///
+/// assert_eq!(normalize_phone("+1 (555) 010-9999").as_deref(), Some("+15550109999"));
+/// assert_eq!(normalize_phone("555-0100"), Some("5550100".to_string()));
+/// assert_eq!(normalize_phone("12-34"), None);
+/// ```
+#[must_use]
+pub fn normalize_phone(phone: &str) -> Option<String> {
+    let phone = phone.trim();
+    let (plus, body) = phone.strip_prefix('+').map_or((false, phone), |rest| (true, rest));
+    if !body
+        .bytes()
+        .all(|b| b.is_ascii_digit() || matches!(b, b' ' | b'-' | b'.' | b'/' | b'(' | b')'))
+    {
+        return None;
+    }
+
+    let mut normalized = String::with_capacity(body.len() + 1);
+    if plus {
+        normalized.push('+');
+    }
+    normalized.extend(body.chars().filter(char::is_ascii_digit));
+    let digits = normalized.len() - usize::from(plus);
+    PHONE_DIGITS.contains(&digits).then_some(normalized)
+}
+
+// ==================== Collection ====================
+
+#[derive(Default)]
+struct Collector {
+    contacts: Contacts,
+    seen_emails: HashSet<String>,
+    seen_phones: HashSet<String>,
+    seen_urls: HashSet<String>,
+}
+
+impl Collector {
+    fn add_href(&mut self, href: &str) {
+        if let Some(rest) = strip_scheme(href, "mailto:") {
+            let addresses = rest.split('?').next().unwrap_or_default();
+            for address in addresses.split(',') {
+                let address = address.trim().replace("%40", "@");
+                if let Some(email) = normalize_email(&address) {
+                    push_unique(&mut self.contacts.emails, &mut self.seen_emails, email);
+                }
+            }
+        } else if let Some(rest) = strip_scheme(href, "tel:") {
+            let number = rest.split(';').next().unwrap_or_default().replace("%20", " ");
+            if let Some(phone) = normalize_phone(&number) {
+                push_unique(&mut self.contacts.phones, &mut self.seen_phones, phone);
+            }
+        } else if is_http_url(href) {
+            push_unique(&mut self.contacts.urls, &mut self.seen_urls, href.to_string());
+        }
+    }
+
+    fn add_text(&mut self, text: &str) {
+        if text.contains('@') {
+            for email in find_emails(text) {
+                push_unique(&mut self.contacts.emails, &mut self.seen_emails, email);
+            }
+        }
+        if text.bytes().any(|b| b.is_ascii_digit()) {
+            for phone in find_phones(text) {
+                push_unique(&mut self.contacts.phones, &mut self.seen_phones, phone);
+            }
+        }
+        if text.contains("http") {
+            for url in find_urls(text) {
+                push_unique(&mut self.contacts.urls, &mut self.seen_urls, url);
+            }
+        }
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, seen: &mut HashSet<String>, value: String) {
+    if seen.insert(value.clone()) {
+        list.push(value);
+    }
+}
+
+fn merge_unique(list: &mut Vec<String>, values: Vec<String>) {
+    let mut seen: HashSet<String> = list.iter().cloned().collect();
+    for value in values {
+        push_unique(list, &mut seen, value);
+    }
+}
+
+// ==================== Validation ====================
+
+fn strip_scheme<'a>(href: &'a str, scheme: &str) -> Option<&'a str> {
+    let prefix = href.get(..scheme.len())?;
+    prefix.eq_ignore_ascii_case(scheme).then(|| &href[scheme.len()..])
+}
+
+fn is_http_url(url: &str) -> bool {
+    let rest = strip_scheme(url, "https://").or_else(|| strip_scheme(url, "http://"));
+    rest.is_some_and(|rest| {
+        let host_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+        let host = &rest[..host_end];
+        let host = host.rsplit_once('@').map_or(host, |(_, h)| h);
+        let host = host.split_once(':').map_or(host, |(h, _)| h);
+        is_valid_domain(host) || host.eq_ignore_ascii_case("localhost")
+    })
+}
+
+fn is_valid_domain(domain: &str) -> bool {
+    let labels: Vec<&str> = domain.split('.').collect();
+    let tld_ok = labels
+        .last()
+        .is_some_and(|tld| tld.len() >= 2 && tld.bytes().all(|b| b.is_ascii_alphabetic()));
+    domain.len() <= 253
+        && labels.len() >= 2
+        && tld_ok
+        && labels.iter().all(|label| {
+            (1..=63).contains(&label.len())
+                && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+        })
+}
+
+fn is_local_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+/=?^_`{|}~.-".contains(&b)
+}
+
+/// The subset of local-part characters matched in free text, to avoid swallowing `key=` or
+/// path prefixes.
+fn is_text_local_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'%' | b'+' | b'-')
+}
+
+fn is_domain_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'.' || b == b'-'
+}
+
+fn is_phone_byte(b: u8) -> bool {
+    b.is_ascii_digit() || matches!(b, b' ' | b'-' | b'.' | b'/' | b'(' | b')')
+}
+
+/// Applies the free-text heuristics that links do not need.
+fn looks_like_phone(candidate: &str) -> bool {
+    if candidate.starts_with('+') || candidate.starts_with('(') {
+        return true;
+    }
+    let groups: Vec<usize> = candidate
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|group| !group.is_empty())
+        .map(str::len)
+        .collect();
+    let spaced = candidate.contains([' ', '-']);
+    let date_shaped = matches!(groups.as_slice(), [4, 2, 2] | [2, 2, 4] | [1 | 2, 1 | 2, 4]);
+    groups.len() >= 2 && spaced && !date_shaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_emails_boundaries() {
+        assert_eq!(
+            find_emails("(a.b+tag@mail.example.co.uk), <x@y.io>; end."),
+            vec!["a.b+tag@mail.example.co.uk", "x@y.io"]
+        );
+        assert!(find_emails("@@ a@b c@.com d@e.1").is_empty());
+    }
+
+    #[test]
+    fn test_find_phones_rejects_non_phones() {
+        assert!(find_phones("v1.2.3 and 05.03.2024 and 1 299,00 € and order 12345678").is_empty());
+        assert!(find_phones("SKU 555-0100abc").is_empty());
+        assert_eq!(find_phones("Fax: 030 / 1234 5678."), vec!["03012345678"]);
+    }
+
+    #[test]
+    fn test_find_urls_trailing_punctuation() {
+        assert_eq!(
+            find_urls("Go to https://a.example/x?y=1, now!"),
+            vec!["https://a.example/x?y=1"]
+        );
+        assert!(find_urls("httpfoo and http://nodot").is_empty());
+    }
+
+    #[test]
+    fn test_contacts_links_and_dedupe() {
+        let soup = Soup::parse(
+            r#"<div>
+                <a href="MAILTO:a@example.com,b%40example.com?cc=c@example.com">mail</a>
+                <p>Reach a@EXAMPLE.com or call +1 555 010 9999.</p>
+                <a href="tel:+1-555-010-9999;ext=2">phone</a>
+                <a href="/relative">skip</a>
+                <a href="https://example.com/">home</a>
+                <p>Home: https://example.com/</p>
+            </div>"#,
+        );
+        let found = contacts(&soup);
+        assert_eq!(found.emails, vec!["a@example.com", "b@example.com"]);
+        assert_eq!(found.phones, vec!["+15550109999"]);
+        assert_eq!(found.urls, vec!["https://example.com/"]);
+    }
+
+    #[test]
+    fn test_contacts_ignores_script_and_style() {
+        let soup = Soup::parse(
+            "<script>var e = 'hidden@example.com';</script><style>/* +1 555 010 9999 \
+             */</style><p>no contacts</p>",
+        );
+        assert!(contacts(&soup).is_empty());
+    }
+
+    #[test]
+    fn test_contacts_merge() {
+        let mut a = Contacts { emails: vec!["a@x.io".into()], ..Contacts::default() };
+        let b = Contacts {
+            emails: vec!["a@x.io".into(), "b@x.io".into()],
+            urls: vec!["https://x.io".into()],
+            ..Contacts::default()
+        };
+        a.merge(b);
+        assert_eq!(a.emails, vec!["a@x.io", "b@x.io"]);
+        assert_eq!(a.urls, vec!["https://x.io"]);
+    }
+}
@@ -0,0 +1,925 @@
+//! Date extraction from `<time>` elements, meta tags, and free text.
+//!
+//! Supported formats:
+//!
+//! - ISO 8601 / RFC 3339: `2024-03-05`, `2024-03-05T10:20`, `2024-03-05 10:20:30.5+02:00`
+//! - Slash-separated ISO: `2024/03/05`
+//! - RFC 2822: `Tue, 05 Mar 2024 10:20:30 GMT`
+//! - Day first: `5 March 2024`, `5th of March, 2024`, `05.03.2024`
+//! - Month first: `March 5, 2024`, `Mar 5th 2024`
+//!
+//! Ambiguous numeric forms such as `03/05/2024` are not parsed unless a [`DateOrder`]
+//! hint says which number is the day; see [`parse_date_with`].
+//!
+//! Dates are returned as the dependency-free [`DateTime`]. With the `chrono` feature,
+//! [`DateTime::to_chrono`], [`DateTime::to_naive_datetime`], and
+//! [`DateTime::to_naive_date`] convert them to chrono types.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{Soup, extract::dates};
+//!
+//! let soup = Soup::parse(
+//!     r#"<html><head><meta property="article:published_time" content="2024-03-05T10:20:00Z">
+//!     </head><body></body></html>"#,
+//! );
+//! let published = dates::published_date(soup.document()).unwrap();
+//! assert_eq!(published.to_string(), "2024-03-05T10:20:00Z");
+//! ```
+
+use std::fmt;
+
+use crate::{
+    Tag,
+    dom::{Document, NodeKind},
+    query::find_all,
+};
+
+/// Meta tag keys that carry a publication date, in priority order.
+const PUBLISHED_META_KEYS: &[&str] = &[
+    "article:published_time",
+    "og:published_time",
+    "datepublished",
+    "publish-date",
+    "pubdate",
+    "dc.date.issued",
+    "dc.date",
+    "dcterms.created",
+    "date",
+    "sailthru.date",
+    "parsely-pub-date",
+];
+
+/// Meta tag keys that carry a last-modified date, in priority order.
+const MODIFIED_META_KEYS: &[&str] = &[
+    "article:modified_time",
+    "og:updated_time",
+    "datemodified",
+    "dcterms.modified",
+    "last-modified",
+];
+
+/// How to read numeric dates whose day and month order is ambiguous, such as `03/05/2024`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DateOrder {
+    /// Only parse unambiguous formats; `03/05/2024` is rejected.
+    #[default]
+    Strict,
+    /// Also parse `DD/MM/YYYY` and `DD-MM-YYYY`, as written in most of Europe.
+    DayFirst,
+    /// Also parse `MM/DD/YYYY` and `MM-DD-YYYY`, as written in the United States.
+    MonthFirst,
+}
+
+/// A calendar date with optional time of day and UTC offset.
+///
+/// Values are always valid calendar dates. When the source has no time, the
+/// time fields are zero and [`DateTime::has_time`] returns `false`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateTime {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    has_time: bool,
+    offset_minutes: Option<i16>,
+}
+
+impl DateTime {
+    /// Creates a date without a time of day.
+    ///
+    /// Returns `None` if the date is not a valid calendar date.
+    #[must_use]
+    pub fn from_ymd(year: i32, month: u8, day: u8) -> Option<Self> {
+        (1..=12).contains(&month).then_some(())?;
+        (1..=days_in_month(year, month)).contains(&day).then_some(())?;
+        Some(Self {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            nanosecond: 0,
+            has_time: false,
+            offset_minutes: None,
+        })
+    }
+
+    /// Sets the time of day.
+    ///
+    /// Returns `None` if the time is out of range. A second of 60 is accepted
+    /// for leap seconds.
+    #[must_use]
+    pub fn with_hms(self, hour: u8, minute: u8, second: u8) -> Option<Self> {
+        (hour < 24 && minute < 60 && second <= 60).then_some(Self {
+            hour,
+            minute,
+            second,
+            has_time: true,
+            ..self
+        })
+    }
+
+    /// Sets the fractional second in nanoseconds.
+    #[must_use]
+    pub fn with_nanosecond(self, nanosecond: u32) -> Self {
+        Self { nanosecond: nanosecond.min(999_999_999), ..self }
+    }
+
+    /// Sets the UTC offset in minutes (e.g. `120` for `+02:00`).
+    #[must_use]
+    pub fn with_offset_minutes(self, offset_minutes: i16) -> Self {
+        Self { offset_minutes: Some(offset_minutes), ..self }
+    }
+
+    /// Returns the year.
+    #[must_use]
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month (1-12).
+    #[must_use]
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Returns the day of the month (1-31).
+    #[must_use]
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Returns the hour (0-23).
+    #[must_use]
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// Returns the minute (0-59).
+    #[must_use]
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// Returns the second (0-60).
+    #[must_use]
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Returns the fractional second in nanoseconds.
+    #[must_use]
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    /// Returns `true` if the source specified a time of day.
+    #[must_use]
+    pub fn has_time(&self) -> bool {
+        self.has_time
+    }
+
+    /// Returns the UTC offset in minutes, if the source specified one.
+    #[must_use]
+    pub fn offset_minutes(&self) -> Option<i16> {
+        self.offset_minutes
+    }
+
+    /// Returns seconds since the Unix epoch.
+    ///
+    /// Values without a UTC offset are interpreted as UTC.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::extract::dates::parse_date;
+    ///
+    /// let dt = parse_date("1970-01-02T01:00:00+01:00").unwrap();
+    /// assert_eq!(dt.unix_timestamp(), 86_400);
+    /// ```
+    #[must_use]
+    pub fn unix_timestamp(&self) -> i64 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let seconds = days * 86_400
+            + i64::from(self.hour) * 3_600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second);
+        seconds - i64::from(self.offset_minutes.unwrap_or(0)) * 60
+    }
+
+    /// Converts the date to a [`chrono::NaiveDate`], dropping any time of day.
+    ///
+    /// Returns `None` if the year is outside chrono's range.
+    ///
+    /// Requires the `chrono` feature.
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn to_naive_date(&self) -> Option<chrono::NaiveDate> {
+        chrono::NaiveDate::from_ymd_opt(self.year, self.month.into(), self.day.into())
+    }
+
+    /// Converts the date and time to a [`chrono::NaiveDateTime`], dropping any UTC
+    /// offset. A date without a time of day becomes midnight.
+    ///
+    /// Returns `None` if the year is outside chrono's range.
+    ///
+    /// Requires the `chrono` feature.
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn to_naive_datetime(&self) -> Option<chrono::NaiveDateTime> {
+        // chrono writes a leap second as second 59 with an extra second of nanoseconds.
+        let (second, nanosecond) = if self.second == 60 {
+            (59, self.nanosecond + 1_000_000_000)
+        } else {
+            (self.second.into(), self.nanosecond)
+        };
+        let time = chrono::NaiveTime::from_hms_nano_opt(
+            self.hour.into(),
+            self.minute.into(),
+            second,
+            nanosecond,
+        )?;
+        Some(self.to_naive_date()?.and_time(time))
+    }
+
+    /// Converts the date and time to a [`chrono::DateTime`] in its UTC offset.
+    ///
+    /// Like [`unix_timestamp`](Self::unix_timestamp), values without a UTC offset are
+    /// interpreted as UTC. Returns `None` if the year is outside chrono's range.
+    ///
+    /// Requires the `chrono` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::extract::dates::parse_date;
+    ///
+    /// let dt = parse_date("2024-03-05T10:20:30+02:00").unwrap().to_chrono().unwrap();
+    /// assert_eq!(dt.to_rfc3339(), "2024-03-05T10:20:30+02:00");
+    /// assert_eq!(dt.timestamp(), 1_709_626_830);
+    /// ```
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn to_chrono(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let offset =
+            chrono::FixedOffset::east_opt(i32::from(self.offset_minutes.unwrap_or(0)) * 60)?;
+        self.to_naive_datetime()?.and_local_timezone(offset).single()
+    }
+}
+
+impl fmt::Display for DateTime {
+    /// Formats as RFC 3339, or as `YYYY-MM-DD` when there is no time of day.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)?;
+        if !self.has_time {
+            return Ok(());
+        }
+        write!(f, "T{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanosecond > 0 {
+            let frac = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", frac.trim_end_matches('0'))?;
+        }
+        match self.offset_minutes {
+            Some(0) => f.write_str("Z"),
+            Some(offset) => {
+                let sign = if offset < 0 { '-' } else { '+' };
+                let abs = offset.unsigned_abs();
+                write!(f, "{sign}{:02}:{:02}", abs / 60, abs % 60)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses a date string in one of the supported formats.
+///
+/// Leading and trailing whitespace is ignored; any other trailing content makes
+/// the parse fail. Use [`find_dates`] to locate dates inside longer text.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::dates::parse_date;
+///
+/// assert_eq!(parse_date("2024-03-05").unwrap().to_string(), "2024-03-05");
+/// assert_eq!(parse_date("March 5, 2024").unwrap().to_string(), "2024-03-05");
+/// assert_eq!(
+///     parse_date("Tue, 05 Mar 2024 10:20:30 +0100").unwrap().to_string(),
+///     "2024-03-05T10:20:30+01:00"
+/// );
+/// assert!(parse_date("2024-02-30").is_none());
+/// ```
+#[must_use]
+pub fn parse_date(input: &str) -> Option<DateTime> {
+    parse_date_with(input, DateOrder::Strict)
+}
+
+/// Parses a date string like [`parse_date`], reading ambiguous numeric dates in `order`.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::dates::{DateOrder, parse_date_with};
+///
+/// let us = parse_date_with("03/05/2024", DateOrder::MonthFirst).unwrap();
+/// assert_eq!(us.to_string(), "2024-03-05");
+/// let eu = parse_date_with("03/05/2024", DateOrder::DayFirst).unwrap();
+/// assert_eq!(eu.to_string(), "2024-05-03");
+/// assert!(parse_date_with("03/05/2024", DateOrder::Strict).is_none());
+/// ```
+#[must_use]
+pub fn parse_date_with(input: &str, order: DateOrder) -> Option<DateTime> {
+    let input = input.trim();
+    let (dt, len) =
+        parse_prefix(input.as_bytes()).or_else(|| parse_ordered(input.as_bytes(), order))?;
+    (len == input.len()).then_some(dt)
+}
+
+/// Finds all dates that appear in free text.
+///
+/// Dates must start and end on word boundaries.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::dates::find_dates;
+///
+/// let found = find_dates("Posted on 5 March 2024, updated 2024-04-01T08:00Z.");
+/// let found: Vec<_> = found.iter().map(ToString::to_string).collect();
+/// assert_eq!(found, vec!["2024-03-05", "2024-04-01T08:00:00Z"]);
+/// ```
+#[must_use]
+pub fn find_dates(text: &str) -> Vec<DateTime> {
+    let bytes = text.as_bytes();
+    let mut dates = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let at_word_start =
+            bytes[i].is_ascii_alphanumeric() && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric());
+        if at_word_start
+            && let Some((dt, len)) = parse_prefix(&bytes[i..])
+            && bytes.get(i + len).is_none_or(|b| !b.is_ascii_alphanumeric())
+        {
+            dates.push(dt);
+            i += len;
+        } else {
+            i += 1;
+        }
+    }
+    dates
+}
+
+/// Extracts the date from a `<time>` element.
+///
+/// Uses the `datetime` attribute when present and falls back to the element text.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, extract::dates::from_time_element};
+///
+/// let soup = Soup::parse("<time datetime=\"2024-03-05\">last Tuesday</time>");
+/// let time = soup.find("time").unwrap().unwrap();
+/// assert_eq!(from_time_element(&time).unwrap().to_string(), "2024-03-05");
+/// ```
+#[must_use]
+pub fn from_time_element(tag: &Tag<'_>) -> Option<DateTime> {
+    tag.get("datetime").and_then(parse_date).or_else(|| parse_date(&tag.text()))
+}
+
+/// Extracts the publication date from meta tags.
+///
+/// Checks `property`, `name`, and `itemprop` attributes against common keys such as
+/// `article:published_time`, `datePublished`, and `dc.date`.
+#[must_use]
+pub fn from_meta(doc: &Document) -> Option<DateTime> {
+    meta_date(doc, PUBLISHED_META_KEYS)
+}
+
+/// Extracts the publication date of a document.
+///
+/// Tries meta tags first, then `datePublished` microdata, then the first `<time>`
+/// element that contains a parseable date.
+#[must_use]
+pub fn published_date(doc: &Document) -> Option<DateTime> {
+    from_meta(doc).or_else(|| itemprop_date(doc, "datePublished")).or_else(|| {
+        find_all(doc, "time").ok()?.into_iter().find_map(|id| from_time_element(&Tag::new(doc, id)))
+    })
+}
+
+/// Extracts the last-modified date of a document from meta tags and microdata.
+#[must_use]
+pub fn modified_date(doc: &Document) -> Option<DateTime> {
+    meta_date(doc, MODIFIED_META_KEYS).or_else(|| itemprop_date(doc, "dateModified"))
+}
+
+fn meta_date(doc: &Document, keys: &[&str]) -> Option<DateTime> {
+    let metas = find_all(doc, "meta[content]").ok()?;
+    keys.iter().find_map(|key| {
+        metas.iter().find_map(|&id| {
+            let NodeKind::Element { attributes, .. } = &doc.get(id)?.kind else { return None };
+            let matches_key = ["property", "name", "itemprop"]
+                .iter()
+                .any(|attr| attributes.get(attr).is_some_and(|v| v.eq_ignore_ascii_case(key)));
+            if matches_key { attributes.get("content").and_then(parse_date) } else { None }
+        })
+    })
+}
+
+fn itemprop_date(doc: &Document, itemprop: &str) -> Option<DateTime> {
+    let selector = format!("[itemprop=\"{itemprop}\"]");
+    find_all(doc, &selector).ok()?.into_iter().find_map(|id| {
+        let tag = Tag::new(doc, id);
+        tag.get("datetime")
+            .or_else(|| tag.get("content"))
+            .and_then(parse_date)
+            .or_else(|| parse_date(&tag.text()))
+    })
+}
+
+// ==================== Parsing ====================
+
+/// Parses a date at the start of `input`, returning it with the number of bytes consumed.
+fn parse_prefix(input: &[u8]) -> Option<(DateTime, usize)> {
+    parse_numeric(input).or_else(|| parse_day_first(input)).or_else(|| parse_month_first(input))
+}
+
+/// A byte cursor over ASCII date text.
+struct Cursor<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let matched = self.peek() == Some(byte);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn skip_spaces(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b == b' ' || b == b'\t') {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    /// Reads between `min` and `max` ASCII digits.
+    fn digits(&mut self, min: usize, max: usize) -> Option<u32> {
+        let start = self.pos;
+        let mut value = 0u32;
+        while self.pos - start < max
+            && let Some(b) = self.peek().filter(u8::is_ascii_digit)
+        {
+            value = value * 10 + u32::from(b - b'0');
+            self.pos += 1;
+        }
+        if self.pos - start < min {
+            self.pos = start;
+            return None;
+        }
+        Some(value)
+    }
+
+    /// Reads a run of ASCII letters.
+    fn word(&mut self) -> &'a [u8] {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        &self.input[start..self.pos]
+    }
+}
+
+/// `YYYY-MM-DD[Thh:mm[:ss[.fff]][zone]]`, `YYYY/MM/DD`, or `DD.MM.YYYY`.
+fn parse_numeric(input: &[u8]) -> Option<(DateTime, usize)> {
+    let mut c = Cursor::new(input);
+    let first = c.digits(1, 4)?;
+    let first_len = c.pos;
+
+    let date = if first_len == 4 {
+        let sep = c.peek().filter(|b| matches!(b, b'-' | b'/'))?;
+        c.pos += 1;
+        let month = c.digits(1, 2)?;
+        c.eat(sep).then_some(())?;
+        let day = c.digits(1, 2)?;
+        DateTime::from_ymd(
+            i32::try_from(first).ok()?,
+            u8::try_from(month).ok()?,
+            u8::try_from(day).ok()?,
+        )?
+    } else if first_len <= 2 {
+        c.eat(b'.').then_some(())?;
+        let month = c.digits(1, 2)?;
+        c.eat(b'.').then_some(())?;
+        let year = c.digits(4, 4)?;
+        return DateTime::from_ymd(
+            i32::try_from(year).ok()?,
+            u8::try_from(month).ok()?,
+            u8::try_from(first).ok()?,
+        )
+        .map(|dt| (dt, c.pos));
+    } else {
+        return None;
+    };
+
+    let date_end = c.pos;
+    if matches!(c.peek(), Some(b'T' | b't' | b' '))
+        && c.input.get(c.pos + 1).is_some_and(u8::is_ascii_digit)
+    {
+        c.pos += 1;
+        if let Some(dt) = parse_time(&mut c, date, true) {
+            return Some((dt, c.pos));
+        }
+    }
+    Some((date, date_end))
+}
+
+/// `DD/MM/YYYY` or `MM/DD/YYYY`, with `-` also accepted, as chosen by `order`.
+fn parse_ordered(input: &[u8], order: DateOrder) -> Option<(DateTime, usize)> {
+    let mut c = Cursor::new(input);
+    let first = c.digits(1, 2)?;
+    let sep = c.peek().filter(|b| matches!(b, b'-' | b'/'))?;
+    c.pos += 1;
+    let second = c.digits(1, 2)?;
+    c.eat(sep).then_some(())?;
+    let year = c.digits(4, 4)?;
+    let (day, month) = match order {
+        DateOrder::Strict => return None,
+        DateOrder::DayFirst => (first, second),
+        DateOrder::MonthFirst => (second, first),
+    };
+    let date = DateTime::from_ymd(
+        i32::try_from(year).ok()?,
+        u8::try_from(month).ok()?,
+        u8::try_from(day).ok()?,
+    )?;
+
+    let date_end = c.pos;
+    if c.skip_spaces() > 0
+        && c.peek().is_some_and(|b| b.is_ascii_digit())
+        && let Some(dt) = parse_time(&mut c, date, false)
+    {
+        return Some((dt, c.pos));
+    }
+    Some((date, date_end))
+}
+
+/// `[Weekday,] D[th] [of] Month[,] YYYY [hh:mm[:ss] [zone]]`.
+fn parse_day_first(input: &[u8]) -> Option<(DateTime, usize)> {
+    let mut c = Cursor::new(input);
+    skip_weekday(&mut c);
+    let day = c.digits(1, 2)?;
+    skip_ordinal(&mut c);
+    c.skip_spaces();
+    let save = c.pos;
+    if !c.word().eq_ignore_ascii_case(b"of") {
+        c.pos = save;
+    }
+    c.skip_spaces();
+    let month = month_from_name(c.word())?;
+    c.eat(b'.');
+    c.eat(b',');
+    (c.skip_spaces() > 0).then_some(())?;
+    let year = c.digits(4, 4)?;
+    let date = DateTime::from_ymd(i32::try_from(year).ok()?, month, u8::try_from(day).ok()?)?;
+
+    let date_end = c.pos;
+    if c.skip_spaces() > 0
+        && c.peek().is_some_and(|b| b.is_ascii_digit())
+        && let Some(dt) = parse_time(&mut c, date, false)
+    {
+        return Some((dt, c.pos));
+    }
+    Some((date, date_end))
+}
+
+/// `[Weekday,] Month D[th][,] YYYY`.
+fn parse_month_first(input: &[u8]) -> Option<(DateTime, usize)> {
+    let mut c = Cursor::new(input);
+    skip_weekday(&mut c);
+    let month = month_from_name(c.word())?;
+    c.eat(b'.');
+    (c.skip_spaces() > 0).then_some(())?;
+    let day = c.digits(1, 2)?;
+    skip_ordinal(&mut c);
+    c.eat(b',');
+    (c.skip_spaces() > 0).then_some(())?;
+    let year = c.digits(4, 4)?;
+    let date = DateTime::from_ymd(i32::try_from(year).ok()?, month, u8::try_from(day).ok()?)?;
+    Some((date, c.pos))
+}
+
+/// Parses `hh:mm[:ss[.fff]]` followed by an optional zone.
+///
+/// With `compact_zone`, the zone must directly follow the time (ISO 8601);
+/// otherwise whitespace may precede it (RFC 2822).
+fn parse_time(c: &mut Cursor<'_>, date: DateTime, compact_zone: bool) -> Option<DateTime> {
+    let start = c.pos;
+    let parsed = (|| {
+        let hour = c.digits(2, 2)?;
+        c.eat(b':').then_some(())?;
+        let minute = c.digits(2, 2)?;
+        let second = if c.eat(b':') { c.digits(2, 2)? } else { 0 };
+        let mut dt = date.with_hms(
+            u8::try_from(hour).ok()?,
+            u8::try_from(minute).ok()?,
+            u8::try_from(second).ok()?,
+        )?;
+
+        if matches!(c.peek(), Some(b'.' | b','))
+            && c.input.get(c.pos + 1).is_some_and(u8::is_ascii_digit)
+        {
+            c.pos += 1;
+            let frac_start = c.pos;
+            let frac = c.digits(1, 9)?;
+            let scale = 10u32.pow(u32::try_from(9 - (c.pos - frac_start)).ok()?);
+            while c.peek().is_some_and(|b| b.is_ascii_digit()) {
+                c.pos += 1;
+            }
+            dt = dt.with_nanosecond(frac * scale);
+        }
+
+        let before_zone = c.pos;
+        if !compact_zone {
+            c.skip_spaces();
+        }
+        match parse_zone(c) {
+            Some(offset) => dt = dt.with_offset_minutes(offset),
+            None => c.pos = before_zone,
+        }
+        Some(dt)
+    })();
+    if parsed.is_none() {
+        c.pos = start;
+    }
+    parsed
+}
+
+/// Parses `Z`, `UTC`, `GMT`, `UT`, `+hh:mm`, `+hhmm`, or `+hh`.
+fn parse_zone(c: &mut Cursor<'_>) -> Option<i16> {
+    let start = c.pos;
+    match c.peek()? {
+        sign @ (b'+' | b'-') => {
+            c.pos += 1;
+            let Some(hours) = c.digits(2, 2) else {
+                c.pos = start;
+                return None;
+            };
+            c.eat(b':');
+            let minutes = c.digits(2, 2).unwrap_or(0);
+            let offset = i16::try_from(hours * 60 + minutes).ok()?;
+            Some(if sign == b'-' { -offset } else { offset })
+        }
+        b'Z' | b'z' => {
+            c.pos += 1;
+            Some(0)
+        }
+        _ => {
+            let word = c.word();
+            if [&b"UTC"[..], b"GMT", b"UT"].iter().any(|z| word.eq_ignore_ascii_case(z)) {
+                Some(0)
+            } else {
+                c.pos = start;
+                None
+            }
+        }
+    }
+}
+
+fn skip_weekday(c: &mut Cursor<'_>) {
+    const WEEKDAYS: [&str; 7] =
+        ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+    let start = c.pos;
+    let word = c.word();
+    let is_weekday = word.len() >= 3
+        && WEEKDAYS.iter().any(|day| {
+            day.len() >= word.len() && day.as_bytes()[..word.len()].eq_ignore_ascii_case(word)
+        });
+    if is_weekday {
+        c.eat(b'.');
+        c.eat(b',');
+        if c.skip_spaces() > 0 {
+            return;
+        }
+    }
+    c.pos = start;
+}
+
+fn skip_ordinal(c: &mut Cursor<'_>) {
+    let start = c.pos;
+    let word = c.word();
+    if ![&b"st"[..], b"nd", b"rd", b"th"].iter().any(|s| word.eq_ignore_ascii_case(s)) {
+        c.pos = start;
+    }
+}
+
+/// Maps an English month name or abbreviation to its number.
+fn month_from_name(word: &[u8]) -> Option<u8> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    if word.len() < 3 {
+        return None;
+    }
+    if word.eq_ignore_ascii_case(b"sept") {
+        return Some(9);
+    }
+    MONTHS
+        .iter()
+        .position(|name| {
+            (word.len() == 3 || word.len() == name.len())
+                && name.as_bytes()[..word.len()].eq_ignore_ascii_case(word)
+        })
+        .and_then(|i| u8::try_from(i + 1).ok())
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Soup;
+
+    fn parsed(input: &str) -> Option<String> {
+        parse_date(input).map(|dt| dt.to_string())
+    }
+
+    #[test]
+    fn test_parse_iso_variants() {
+        assert_eq!(parsed("2024-03-05").as_deref(), Some("2024-03-05"));
+        assert_eq!(parsed("2024/3/5").as_deref(), Some("2024-03-05"));
+        assert_eq!(parsed("2024-03-05T10:20").as_deref(), Some("2024-03-05T10:20:00"));
+        assert_eq!(parsed("2024-03-05 10:20:30Z").as_deref(), Some("2024-03-05T10:20:30Z"));
+        assert_eq!(
+            parsed("2024-03-05T10:20:30.250-05:30").as_deref(),
+            Some("2024-03-05T10:20:30.25-05:30")
+        );
+        assert_eq!(
+            parsed("2024-03-05T10:20:30+0200").as_deref(),
+            Some("2024-03-05T10:20:30+02:00")
+        );
+    }
+
+    #[test]
+    fn test_parse_textual_variants() {
+        for input in [
+            "5 March 2024",
+            "5th of March, 2024",
+            "Tuesday, 5 Mar 2024",
+            "March 5, 2024",
+            "Mar. 5th 2024",
+            "Sept 5, 2024",
+            "05.03.2024",
+        ] {
+            let expected = if input.starts_with("Sept") { "2024-09-05" } else { "2024-03-05" };
+            assert_eq!(parsed(input).as_deref(), Some(expected), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_rfc2822() {
+        assert_eq!(
+            parsed("Tue, 05 Mar 2024 10:20:30 GMT").as_deref(),
+            Some("2024-03-05T10:20:30Z")
+        );
+        assert_eq!(
+            parsed("Tue, 05 Mar 2024 10:20:30 -0800").as_deref(),
+            Some("2024-03-05T10:20:30-08:00")
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        for input in ["", "2023-02-29", "2024-13-01", "2024-03-05T25:00", "03/05/2024", "Mayday 5"]
+        {
+            assert_eq!(parsed(input), None, "{input:?}");
+        }
+        assert_eq!(parsed("2024-02-29").as_deref(), Some("2024-02-29"));
+    }
+
+    #[test]
+    fn test_parse_date_order() {
+        let with = |input, order| parse_date_with(input, order).map(|dt| dt.to_string());
+        assert_eq!(with("03/05/2024", DateOrder::DayFirst).as_deref(), Some("2024-05-03"));
+        assert_eq!(with("3-5-2024", DateOrder::MonthFirst).as_deref(), Some("2024-03-05"));
+        assert_eq!(
+            with("12/31/2024 18:30", DateOrder::MonthFirst).as_deref(),
+            Some("2024-12-31T18:30:00")
+        );
+        assert_eq!(with("12/31/2024", DateOrder::DayFirst), None);
+        assert_eq!(with("03/05-2024", DateOrder::DayFirst), None);
+        // Unambiguous formats do not depend on the order.
+        assert_eq!(with("05.03.2024", DateOrder::MonthFirst).as_deref(), Some("2024-03-05"));
+        assert_eq!(with("2024-03-05", DateOrder::DayFirst).as_deref(), Some("2024-03-05"));
+    }
+
+    #[test]
+    fn test_unix_timestamp() {
+        assert_eq!(parse_date("1970-01-01").unwrap().unix_timestamp(), 0);
+        assert_eq!(parse_date("2000-03-01T00:00:00Z").unwrap().unix_timestamp(), 951_868_800);
+        assert_eq!(parse_date("1969-12-31T23:00:00-01:00").unwrap().unix_timestamp(), 0);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_to_chrono() {
+        let date = parse_date("2024-03-05").unwrap();
+        assert_eq!(date.to_naive_date().unwrap().to_string(), "2024-03-05");
+        assert_eq!(date.to_chrono().unwrap().to_rfc3339(), "2024-03-05T00:00:00+00:00");
+
+        let dt = parse_date("2024-03-05T10:20:30.25-05:30").unwrap();
+        assert_eq!(dt.to_chrono().unwrap().to_rfc3339(), "2024-03-05T10:20:30.250-05:30");
+        assert_eq!(dt.to_chrono().unwrap().timestamp(), dt.unix_timestamp());
+
+        let leap = DateTime::from_ymd(2016, 12, 31)
+            .and_then(|date| date.with_hms(23, 59, 60))
+            .unwrap()
+            .with_nanosecond(500_000_000);
+        assert_eq!(leap.to_naive_datetime().unwrap().to_string(), "2016-12-31 23:59:60.500");
+
+        assert!(DateTime::from_ymd(i32::MAX, 1, 1).unwrap().to_chrono().is_none());
+    }
+
+    #[test]
+    fn test_find_dates_word_boundaries() {
+        let found = find_dates("ids 12024-03-05 and 2024-03-05x; real: June 1st, 2023");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].to_string(), "2023-06-01");
+    }
+
+    #[test]
+    fn test_published_date_priority() {
+        let soup = Soup::parse(
+            r#"<html><head>
+                <meta name="date" content="2020-01-01">
+                <meta property="article:published_time" content="2021-02-03T04:05:06Z">
+            </head><body><time datetime="2019-01-01">x</time></body></html>"#,
+        );
+        assert_eq!(published_date(soup.document()).unwrap().to_string(), "2021-02-03T04:05:06Z");
+    }
+
+    #[test]
+    fn test_published_date_falls_back_to_time_element() {
+        let soup = Soup::parse("<p>Posted <time>July 4, 2022</time></p>");
+        assert_eq!(published_date(soup.document()).unwrap().to_string(), "2022-07-04");
+    }
+
+    #[test]
+    fn test_modified_date_from_itemprop() {
+        let soup =
+            Soup::parse("<span itemprop=\"dateModified\" content=\"2022-10-11\">Oct 11</span>");
+        assert_eq!(modified_date(soup.document()).unwrap().to_string(), "2022-10-11");
+        assert!(from_meta(soup.document()).is_none());
+    }
+}
@@ -0,0 +1,327 @@
+//! Links and resource references with absolute URLs.
+//!
+//! [`links`], [`images`], [`scripts`], and [`stylesheets`] (also available as the
+//! [`Soup`] methods of the same names) collect references in document order and resolve
+//! them with [`resolve_url`]:
+//!
+//! - [`links`]: `<a href>` and `<area href>`, with `rel` tokens and anchor text
+//! - [`images`]: `<img src>`, or with [`images_with`], the `srcset` or `<picture>` source a browser
+//!   would pick
+//! - [`scripts`]: external `<script src>`
+//! - [`stylesheets`]: `<link href>` whose `rel` includes `stylesheet`
+//!
+//! The base URL is the document's first `<base href>`, resolved against the `base_url`
+//! argument if it is relative, or `base_url` itself when there is no `<base>`. Without
+//! an absolute base, only references that are already absolute get a `url`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::Soup;
+//!
+//! let soup = Soup::parse(
+//!     r#"<base href="/docs/">
+//!        <a href="guide.html" rel="next">Next  page</a>
+//!        <img src="//cdn.example.com/logo.png">"#,
+//! );
+//! let links = soup.links(Some("https://example.com/index.html"));
+//! assert_eq!(links[0].url.as_deref(), Some("https://example.com/docs/guide.html"));
+//! assert_eq!(links[0].rel, vec!["next"]);
+//! assert_eq!(links[0].text, "Next page");
+//!
+//! let images = soup.images(Some("https://example.com/"));
+//! assert_eq!(images[0].url.as_deref(), Some("https://cdn.example.com/logo.png"));
+//!
+//! // Relative references stay unresolved without an absolute base.
+//! assert_eq!(soup.links(None)[0].url, None);
+//! ```
+
+use crate::{
+    Soup, Tag,
+    dom::{Document, NodeId, TagId},
+    extract::srcset::{ImageConstraints, image_source},
+    utils::{resolve_url, split_attr_list},
+};
+
+/// A hyperlink from `<a href>` or `<area href>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// The element the link was found on.
+    pub node: NodeId,
+    /// The `href` attribute as written.
+    pub href: String,
+    /// The absolute URL, or `None` if `href` is relative and there is no absolute base.
+    pub url: Option<String>,
+    /// Lowercase tokens of the `rel` attribute.
+    pub rel: Vec<String>,
+    /// Anchor text with whitespace collapsed.
+    pub text: String,
+}
+
+/// A referenced resource: an image, script, or stylesheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resource {
+    /// The element the reference was found on.
+    pub node: NodeId,
+    /// The `src` or `href` attribute as written.
+    pub reference: String,
+    /// The absolute URL, or `None` if the reference is relative and there is no absolute
+    /// base.
+    pub url: Option<String>,
+}
+
+/// Returns the base URL for resolving references in the document.
+///
+/// See the [module documentation](self) for how `<base href>` and `base_url` combine.
+/// Returns `None` if neither yields an absolute URL.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, extract::links::base_url};
+///
+/// let soup = Soup::parse("<base href=\"/v2/\">");
+/// assert_eq!(
+///     base_url(&soup, Some("https://example.com/a/b")).as_deref(),
+///     Some("https://example.com/v2/")
+/// );
+/// assert_eq!(base_url(&soup, None), None);
+/// ```
+#[must_use]
+pub fn base_url(soup: &Soup, base_url: Option<&str>) -> Option<String> {
+    document_base_url(soup.document(), base_url)
+}
+
+/// [`base_url`] for a [`Document`], as reached from a [`Tag`].
+pub(crate) fn document_base_url(doc: &Document, base_url: Option<&str>) -> Option<String> {
+    let base_href = elements(doc, TagId::Base).find_map(|tag| tag.get("href").map(str::to_owned));
+    match (base_href.as_deref(), base_url) {
+        (Some(href), Some(base)) => resolve_url(base, href),
+        (Some(href), None) => resolve(None, href),
+        (None, base) => base.and_then(|base| resolve_url(base, "")),
+    }
+}
+
+/// Collects `<a href>` and `<area href>` links in document order.
+#[must_use]
+pub fn links(soup: &Soup, base_url: Option<&str>) -> Vec<Link> {
+    let base = self::base_url(soup, base_url);
+    element_tags(soup.document())
+        .filter(|tag| matches!(tag_id(tag), Some(TagId::A | TagId::Area)))
+        .filter_map(|tag| {
+            let href = tag.get("href")?;
+            Some(Link {
+                node: tag.node_id(),
+                href: href.to_string(),
+                url: resolve(base.as_deref(), href),
+                rel: tag.get("rel").map_or_else(Vec::new, |rel| {
+                    split_attr_list(rel).map(str::to_ascii_lowercase).collect()
+                }),
+                text: tag.text().split_whitespace().collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+/// Collects `<img src>` references in document order.
+#[must_use]
+pub fn images(soup: &Soup, base_url: Option<&str>) -> Vec<Resource> {
+    resources(soup, base_url, TagId::Img, "src", |_| true)
+}
+
+/// Collects the image each `<img>` shows under `constraints`, in document order.
+///
+/// Each reference is the candidate [`image_source`] picks from the `<img>`'s `srcset`
+/// and `src` or the `<source>`s of its `<picture>`. Images without any are left out.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{
+///     Soup,
+///     extract::{ImageConstraints, links::images_with},
+/// };
+///
+/// let soup = Soup::parse(r#"<img src="a.jpg" srcset="a-400.jpg 400w, a-800.jpg 800w">"#);
+/// let images = images_with(&soup, Some("https://example.com/"), &ImageConstraints::default());
+/// assert_eq!(images[0].reference, "a-800.jpg");
+/// assert_eq!(images[0].url.as_deref(), Some("https://example.com/a-800.jpg"));
+/// ```
+#[must_use]
+pub fn images_with(
+    soup: &Soup,
+    base_url: Option<&str>,
+    constraints: &ImageConstraints,
+) -> Vec<Resource> {
+    let base = self::base_url(soup, base_url);
+    elements(soup.document(), TagId::Img)
+        .filter_map(|tag| {
+            let reference = image_source(&tag, constraints)?;
+            Some(Resource {
+                node: tag.node_id(),
+                url: resolve(base.as_deref(), &reference),
+                reference,
+            })
+        })
+        .collect()
+}
+
+/// Collects external `<script src>` references in document order.
+#[must_use]
+pub fn scripts(soup: &Soup, base_url: Option<&str>) -> Vec<Resource> {
+    resources(soup, base_url, TagId::Script, "src", |_| true)
+}
+
+/// Collects `<link rel="stylesheet" href>` references in document order.
+#[must_use]
+pub fn stylesheets(soup: &Soup, base_url: Option<&str>) -> Vec<Resource> {
+    resources(soup, base_url, TagId::Link, "href", |tag| {
+        tag.get("rel")
+            .is_some_and(|rel| split_attr_list(rel).any(|t| t.eq_ignore_ascii_case("stylesheet")))
+    })
+}
+
+// ==================== Helpers ====================
+
+fn resources(
+    soup: &Soup,
+    base_url: Option<&str>,
+    tag_id: TagId,
+    attr: &str,
+    keep: impl Fn(&Tag<'_>) -> bool,
+) -> Vec<Resource> {
+    let base = self::base_url(soup, base_url);
+    elements(soup.document(), tag_id)
+        .filter(|tag| keep(tag))
+        .filter_map(|tag| {
+            let reference = tag.get(attr)?;
+            Some(Resource {
+                node: tag.node_id(),
+                reference: reference.to_string(),
+                url: resolve(base.as_deref(), reference),
+            })
+        })
+        .collect()
+}
+
+/// Resolves the first of the `href`, `src`, and `content` attributes of `tag` against
+/// `base`, for [`Soup::select_urls`] and [`Tag::select_urls`].
+pub(crate) fn reference_url(tag: &Tag<'_>, base: Option<&str>) -> Option<String> {
+    let reference = ["href", "src", "content"].into_iter().find_map(|attr| tag.get(attr))?;
+    resolve(base, reference)
+}
+
+/// Resolves against `base`, or keeps `reference` if it is already absolute.
+fn resolve(base: Option<&str>, reference: &str) -> Option<String> {
+    // An absolute reference resolves to itself; a relative one has no absolute base.
+    resolve_url(base.unwrap_or(reference), reference)
+}
+
+fn element_tags(doc: &Document) -> impl Iterator<Item = Tag<'_>> {
+    doc.root()
+        .into_iter()
+        .flat_map(move |root| std::iter::once(root).chain(doc.descendants(root)))
+        .filter(move |&id| doc.get(id).is_some_and(|n| n.kind.is_element()))
+        .map(move |id| Tag::new(doc, id))
+}
+
+fn elements(doc: &Document, tag_id: TagId) -> impl Iterator<Item = Tag<'_>> {
+    element_tags(doc).filter(move |tag| self::tag_id(tag) == Some(tag_id))
+}
+
+fn tag_id(tag: &Tag<'_>) -> Option<TagId> {
+    tag.document().get(tag.node_id()).and_then(|n| n.kind.tag_id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_precedence() {
+        let soup = Soup::parse(
+            "<base target=\"_blank\"><base href=\"https://cdn.example.org/\"><base href=\"/x/\">",
+        );
+        assert_eq!(
+            base_url(&soup, Some("https://example.com/")).as_deref(),
+            Some("https://cdn.example.org/")
+        );
+
+        let soup = Soup::parse("<p>no base</p>");
+        assert_eq!(
+            base_url(&soup, Some("https://example.com/a#top")).as_deref(),
+            Some("https://example.com/a")
+        );
+        assert_eq!(base_url(&soup, Some("relative/path")), None);
+    }
+
+    #[test]
+    fn test_links() {
+        let soup = Soup::parse(
+            "<a href=\"/about\" rel=\"NoFollow  external\"><b>About</b> us</a><a \
+             name=\"anchor\">no href</a><map><area href=\"#top\"></map><a \
+             href=\"mailto:a@example.com\">Mail</a>",
+        );
+        let links = links(&soup, Some("https://example.com/dir/page"));
+        assert_eq!(links.len(), 3);
+        assert_eq!(links[0].href, "/about");
+        assert_eq!(links[0].url.as_deref(), Some("https://example.com/about"));
+        assert_eq!(links[0].rel, vec!["nofollow", "external"]);
+        assert_eq!(links[0].text, "About us");
+        assert_eq!(links[1].url.as_deref(), Some("https://example.com/dir/page#top"));
+        assert_eq!(links[2].url.as_deref(), Some("mailto:a@example.com"));
+    }
+
+    #[test]
+    fn test_links_without_base() {
+        let soup = Soup::parse("<a href=\"/rel\">A</a><a href=\"https://example.com/\">B</a>");
+        let urls: Vec<_> = links(&soup, None).into_iter().map(|l| l.url).collect();
+        assert_eq!(urls, vec![None, Some("https://example.com/".to_string())]);
+    }
+
+    #[test]
+    fn test_resources() {
+        let soup = Soup::parse(
+            "<head><link rel=\"Stylesheet\" href=\"a.css\"><link rel=\"icon\" \
+             href=\"i.ico\"><script \
+             src=\"app.js\"></script><script>inline()</script></head><body><img \
+             src=\"img/logo.png\"><img alt=\"no src\"></body>",
+        );
+        let base = Some("https://example.com/site/");
+        let urls = |resources: Vec<Resource>| {
+            resources.into_iter().map(|r| r.url.unwrap_or_default()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(urls(stylesheets(&soup, base)), vec!["https://example.com/site/a.css"]);
+        assert_eq!(urls(scripts(&soup, base)), vec!["https://example.com/site/app.js"]);
+        assert_eq!(urls(images(&soup, base)), vec!["https://example.com/site/img/logo.png"]);
+        assert_eq!(images(&soup, base)[0].reference, "img/logo.png");
+    }
+
+    #[test]
+    fn test_images_with() {
+        let soup = Soup::parse(
+            "<picture><source type=\"image/avif\" srcset=\"a.avif\"><img \
+             src=\"a.jpg\"></picture><img srcset=\"b-1x.jpg, b-2x.jpg 2x\"><img alt=\"no src\">",
+        );
+        let base = Some("https://example.com/");
+        let references = |constraints: &ImageConstraints| {
+            images_with(&soup, base, constraints)
+                .into_iter()
+                .map(|r| r.reference)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(references(&ImageConstraints::default()), ["a.avif", "b-2x.jpg"]);
+        let constraints = ImageConstraints {
+            viewport_width: Some(1024),
+            density: 1.0,
+            types: vec!["image/jpeg".into()],
+        };
+        assert_eq!(references(&constraints), ["a.jpg", "b-1x.jpg"]);
+        assert_eq!(
+            images_with(&soup, base, &constraints)[0].url.as_deref(),
+            Some("https://example.com/a.jpg")
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! Typed value extraction from documents and text.
+//!
+//! The query module locates elements; this module turns what it finds into
+//! typed values that every scraper otherwise re-implements.
+//!
+//! - [`assets`]: `data:` URIs, inline SVG, and `<style>` blocks with decoded bytes
+//! - [`contacts`](mod@contacts): emails, phone numbers, and URLs from links and text
+//! - [`dates`]: dates from `<time datetime>`, meta tags, and free text
+//! - [`links`](mod@links): links, images, scripts, and stylesheets with absolute URLs
+//! - [`srcset`]: responsive image candidates from `srcset` and `<picture>` sources
+//! - [`numbers`]: locale-aware numbers and prices with currency detection
+//! - [`schema`]: declarative schemas that combine selectors and the parsers above
+//! - [`table`](mod@table): HTML tables as rows and records, with spans resolved
+
+pub mod assets;
+pub mod contacts;
+pub mod dates;
+pub mod links;
+pub mod numbers;
+pub mod schema;
+pub mod srcset;
+pub mod table;
+
+pub use assets::{AssetSource, InlineAsset};
+pub use contacts::{Contacts, contacts};
+pub use dates::{DateOrder, DateTime};
+pub use links::{Link, Resource};
+pub use numbers::Price;
+pub use schema::{
+    Aggregate, Field, Provenance, Rejection, Report, Schema, Source, Transform, Value,
+};
+pub use srcset::{Descriptor, ImageConstraints, SrcsetCandidate};
+pub use table::{Table, table};
@@ -0,0 +1,264 @@
+//! Locale-aware number and price extraction.
+//!
+//! Handles the thousands and decimal separator conventions found on real pages:
+//!
+//! - `1,299.00` and `1.299,00`
+//! - `1 299,00` (including no-break and narrow no-break spaces) and `1'299.00`
+//! - Currency symbols or ISO 4217 codes before or after the amount
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::extract::numbers::parse_price;
+//!
+//! let price = parse_price("1 299,00 €").unwrap();
+//! assert_eq!(price.amount, 1299.0);
+//! assert_eq!(price.currency.as_deref(), Some("EUR"));
+//! assert_eq!(price.to_string(), "1299.00 EUR");
+//! ```
+
+use std::fmt;
+
+/// Currency symbols mapped to ISO 4217 codes, longest symbols first.
+const CURRENCY_SYMBOLS: &[(&str, &str)] = &[
+    ("US$", "USD"),
+    ("R$", "BRL"),
+    ("C$", "CAD"),
+    ("A$", "AUD"),
+    ("zł", "PLN"),
+    ("€", "EUR"),
+    ("$", "USD"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("₹", "INR"),
+    ("₽", "RUB"),
+    ("₩", "KRW"),
+    ("₺", "TRY"),
+    ("₴", "UAH"),
+    ("₪", "ILS"),
+    ("₫", "VND"),
+    ("฿", "THB"),
+];
+
+/// A monetary amount with an optional ISO 4217 currency code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Price {
+    /// The numeric amount.
+    pub amount: f64,
+    /// ISO 4217 currency code (e.g. `"EUR"`), if one was found.
+    pub currency: Option<String>,
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2}", self.amount)?;
+        if let Some(currency) = &self.currency {
+            write!(f, " {currency}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the first number in a string, detecting the separator convention.
+///
+/// When both `.` and `,` appear, the last one is the decimal separator. When only
+/// one appears, it is a thousands separator if it repeats or is followed by exactly
+/// three digits, and a decimal separator otherwise. Spaces and apostrophes between
+/// digits are thousands separators.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::numbers::parse_number;
+///
+/// assert_eq!(parse_number("1,299.50"), Some(1299.5));
+/// assert_eq!(parse_number("1.299,50"), Some(1299.5));
+/// assert_eq!(parse_number("Total: 12,5 kg"), Some(12.5));
+/// assert_eq!(parse_number("-3"), Some(-3.0));
+/// assert_eq!(parse_number("n/a"), None);
+/// ```
+#[must_use]
+pub fn parse_number(text: &str) -> Option<f64> {
+    let (start, end) = number_span(text)?;
+    normalize_number(&text[start..end])
+}
+
+/// Parses a price with an optional currency symbol or ISO 4217 code.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::numbers::parse_price;
+///
+/// assert_eq!(parse_price("$1,299.99").unwrap().to_string(), "1299.99 USD");
+/// assert_eq!(parse_price("CHF 1'250.-").unwrap().to_string(), "1250.00 CHF");
+/// assert_eq!(parse_price("19.90").unwrap().currency, None);
+/// ```
+#[must_use]
+pub fn parse_price(text: &str) -> Option<Price> {
+    let (start, end) = number_span(text)?;
+    let mut amount = normalize_number(&text[start..end])?;
+    let prefix = &text[..start];
+    let currency = currency_before(prefix);
+    // A sign may precede the currency symbol, as in "-$5.00".
+    if currency.is_some() && amount > 0.0 && prefix.trim_start().starts_with(['-', '\u{2212}']) {
+        amount = -amount;
+    }
+    let currency = currency.or_else(|| currency_after(&text[end..]));
+    Some(Price { amount, currency })
+}
+
+/// Finds the byte span of the first number, including an optional leading sign.
+fn number_span(text: &str) -> Option<(usize, usize)> {
+    let first_digit = text.find(|c: char| c.is_ascii_digit())?;
+    let before = text[..first_digit].trim_end_matches([' ', '\u{a0}']);
+    let start = before
+        .strip_suffix('-')
+        .or_else(|| before.strip_suffix('\u{2212}'))
+        .map_or(first_digit, str::len);
+
+    let mut end = first_digit;
+    let mut chars = text[first_digit..].char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        if c.is_ascii_digit() {
+            end = first_digit + offset + 1;
+        } else if is_separator(c) {
+            let next_is_digit = chars.peek().is_some_and(|&(_, next)| next.is_ascii_digit());
+            if !next_is_digit {
+                break;
+            }
+        } else {
+            break;
+        }
+    }
+    Some((start, end))
+}
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '.' | ',' | ' ' | '\'' | '\u{a0}' | '\u{202f}' | '\u{2009}' | '\u{2019}')
+}
+
+/// Converts a raw numeric span into an `f64` by resolving separators.
+fn normalize_number(raw: &str) -> Option<f64> {
+    let (negative, digits) = raw
+        .strip_prefix('-')
+        .or_else(|| raw.strip_prefix('\u{2212}'))
+        .map_or((false, raw), |rest| (true, rest.trim_start_matches([' ', '\u{a0}'])));
+
+    let last_dot = digits.rfind('.');
+    let last_comma = digits.rfind(',');
+    let decimal = match (last_dot, last_comma) {
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        (Some(pos), None) | (None, Some(pos)) => {
+            let sep = digits.as_bytes()[pos];
+            let repeats = digits.bytes().filter(|&b| b == sep).count() > 1;
+            let digits_after = digits[pos + 1..].bytes().take_while(u8::is_ascii_digit).count();
+            (!repeats && digits_after != 3).then_some(pos)
+        }
+        (None, None) => None,
+    };
+
+    let mut normalized = String::with_capacity(digits.len() + 1);
+    if negative {
+        normalized.push('-');
+    }
+    for (i, c) in digits.char_indices() {
+        if c.is_ascii_digit() {
+            normalized.push(c);
+        } else if Some(i) == decimal {
+            normalized.push('.');
+        }
+    }
+    normalized.parse().ok()
+}
+
+fn currency_before(text: &str) -> Option<String> {
+    let text = text.trim_end_matches(|c: char| c.is_whitespace() || c == '-' || c == '\u{2212}');
+    CURRENCY_SYMBOLS
+        .iter()
+        .find(|(symbol, _)| text.ends_with(symbol))
+        .map(|(_, code)| (*code).to_string())
+        .or_else(|| {
+            let word_start = text.rfind(|c: char| !c.is_ascii_alphabetic()).map_or(0, |i| i + 1);
+            iso_code(&text[word_start..])
+        })
+}
+
+fn currency_after(text: &str) -> Option<String> {
+    let text = text.trim_start_matches(|c: char| c.is_whitespace() || c == '.' || c == '-');
+    CURRENCY_SYMBOLS
+        .iter()
+        .find(|(symbol, _)| text.starts_with(symbol))
+        .map(|(_, code)| (*code).to_string())
+        .or_else(|| {
+            let word_end = text.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(text.len());
+            iso_code(&text[..word_end])
+        })
+}
+
+/// Accepts a three-letter uppercase word as an ISO 4217 code.
+fn iso_code(word: &str) -> Option<String> {
+    (word.len() == 3 && word.bytes().all(|b| b.is_ascii_uppercase())).then(|| word.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number_separator_conventions() {
+        let cases = [
+            ("1299", 1299.0),
+            ("1,299", 1299.0),
+            ("1.299", 1299.0),
+            ("1,299,000", 1_299_000.0),
+            ("1.299.000", 1_299_000.0),
+            ("1,299.00", 1299.0),
+            ("1.299,00", 1299.0),
+            ("1 299,00", 1299.0),
+            ("1\u{a0}299,00", 1299.0),
+            ("1\u{202f}299,00", 1299.0),
+            ("1'299.00", 1299.0),
+            ("12,5", 12.5),
+            ("0.75", 0.75),
+            ("-4,25", -4.25),
+            ("\u{2212}4", -4.0),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_number(input), Some(expected), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_number_stops_at_non_numeric() {
+        assert_eq!(parse_number("3 items, 2 left"), Some(3.0));
+        assert_eq!(parse_number("v2.5."), Some(2.5));
+        assert_eq!(parse_number(""), None);
+    }
+
+    #[test]
+    fn test_parse_price_currency_positions() {
+        let cases = [
+            ("1 299,00 €", "1299.00 EUR"),
+            ("€1.299,00", "1299.00 EUR"),
+            ("£ 9.99", "9.99 GBP"),
+            ("US$ 15", "15.00 USD"),
+            ("R$ 1.234,56", "1234.56 BRL"),
+            ("1.234,56 zł", "1234.56 PLN"),
+            ("EUR 10,50", "10.50 EUR"),
+            ("10.50 USD", "10.50 USD"),
+            ("-$5.00", "-5.00 USD"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_price(input).unwrap().to_string(), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_price_without_currency() {
+        let price = parse_price("Price: 42").unwrap();
+        assert!((price.amount - 42.0).abs() < f64::EPSILON);
+        assert_eq!(price.currency, None);
+        assert!(parse_price("Free").is_none());
+    }
+}
@@ -0,0 +1,1543 @@
+//! Declarative extraction schemas.
+//!
+//! A [`Schema`] maps field names to CSS selectors plus what to read from each match, an
+//! optional [`Transform`], and whether the field repeats. Groups nest a schema under a
+//! selector, so lists of records come out as lists of objects. Running a schema yields a
+//! [`Value`] tree in field declaration order.
+//!
+//! Selectors are compiled when the field is declared, so extraction itself cannot fail.
+//!
+//! Fields can fall back to other fields with [`Field::or`], and fields and whole records
+//! can be gated on the presence or absence of other elements with [`Field::when`],
+//! [`Field::unless`], [`Schema::when`], and [`Schema::unless`]. Repeated fields can be
+//! reduced to a single value, such as a count or a maximum, with [`Field::aggregate`].
+//!
+//! Records can be validated with [`Schema::require`] and [`Schema::validate`]: rejected
+//! records are dropped from the result, and [`Schema::extract_report`] says why.
+//!
+//! [`Schema::extract_with_provenance`] also records, for every extracted value, the
+//! selector that matched and the CSS path of the element it was read from, so a bad value
+//! can be traced back to its source element.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{
+//!     Soup,
+//!     extract::{Field, Schema, Transform, Value},
+//! };
+//!
+//! # fn main() -> scrape_core::QueryResult<()> {
+//! let soup = Soup::parse(
+//!     r#"<h1> Catalog </h1>
+//!     <div class="product"><a href="/a">Lamp</a><span class="price">€ 19,99</span></div>
+//!     <div class="product"><a href="/b">Desk</a><span class="price">€ 120</span></div>"#,
+//! );
+//!
+//! let product = Schema::new()
+//!     .field("name", Field::text("a")?)
+//!     .field("url", Field::attr("a", "href")?)
+//!     .field("price", Field::text(".price")?.transform(Transform::Number));
+//! let schema = Schema::new()
+//!     .field("title", Field::text("h1")?.transform(Transform::Trim))
+//!     .field("products", Field::group(".product", product)?.many());
+//!
+//! let data = schema.extract(&soup);
+//! assert_eq!(data.get("title"), Some(&Value::from("Catalog")));
+//! let products = data.get("products").and_then(Value::as_list).unwrap();
+//! assert_eq!(products[1].get("url").and_then(Value::as_str), Some("/b"));
+//! assert_eq!(products[0].get("price").and_then(Value::as_f64), Some(19.99));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use super::{dates::parse_date, numbers};
+use crate::{
+    Soup, Tag,
+    dom::{Document, NodeId},
+    query::{
+        CompiledSelector, QueryError, QueryResult, find_all_compiled, find_all_within_compiled,
+        find_compiled, find_within_compiled, matches_selector,
+    },
+};
+
+/// An extracted value.
+///
+/// Objects keep their fields in schema declaration order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// No match, a missing attribute, or a failed transform.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// A number.
+    Number(f64),
+    /// A string.
+    String(String),
+    /// A repeated field.
+    List(Vec<Value>),
+    /// A schema result or structured transform output.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Returns `true` if the value is [`Value::Null`].
+    #[must_use]
+    pub const fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns the string if this is a [`Value::String`].
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the number if this is a [`Value::Number`].
+    #[must_use]
+    pub const fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Returns the items if this is a [`Value::List`].
+    #[must_use]
+    pub fn as_list(&self) -> Option<&[Self]> {
+        match self {
+            Self::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Looks up a field if this is a [`Value::Object`].
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Self::String(s)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_string())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Self::Number(n)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl<T: Into<Self>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{SerializeMap, SerializeSeq};
+
+        match self {
+            Self::Null => serializer.serialize_none(),
+            Self::Bool(b) => serializer.serialize_bool(*b),
+            Self::Number(n) => match integral(*n) {
+                Some(i) => serializer.serialize_i64(i),
+                None => serializer.serialize_f64(*n),
+            },
+            Self::String(s) => serializer.serialize_str(s),
+            Self::List(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Self::Object(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (key, value) in fields {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Value> for serde_json::Value {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(b) => Self::Bool(b),
+            Value::Number(n) => integral(n).map_or_else(
+                || serde_json::Number::from_f64(n).map_or(Self::Null, Self::Number),
+                Self::from,
+            ),
+            Value::String(s) => Self::String(s),
+            Value::List(items) => Self::Array(items.into_iter().map(Into::into).collect()),
+            Value::Object(fields) => {
+                Self::Object(fields.into_iter().map(|(k, v)| (k, v.into())).collect())
+            }
+        }
+    }
+}
+
+/// Returns `n` as an integer if it has no fractional part and fits in an `i64`, so that
+/// integral numbers deserialize into integer fields.
+#[cfg(feature = "serde")]
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss, clippy::float_cmp)]
+fn integral(n: f64) -> Option<i64> {
+    (n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64).then_some(n as i64)
+}
+
+/// Where an extracted value came from.
+///
+/// Recorded by [`Schema::extract_with_provenance`] for every value read from an element.
+/// Elements are identified by CSS path, and by source line where the parser recorded one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// Path of the value in the result, such as `items[1].price`.
+    pub field: String,
+    /// Selector of the field, or `None` for [`Field::own`] fields.
+    pub selector: Option<String>,
+    /// CSS path of the element the value was read from; see [`Tag::css_path`].
+    pub css_path: String,
+    /// Source line of the element's start tag; see [`Document::line`]. `None` for
+    /// elements added after parsing and for XML documents.
+    pub line: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Provenance {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Provenance", 4)?;
+        state.serialize_field("field", &self.field)?;
+        state.serialize_field("selector", &self.selector)?;
+        state.serialize_field("css_path", &self.css_path)?;
+        state.serialize_field("line", &self.line)?;
+        state.end()
+    }
+}
+
+/// A record dropped by a validation rule; see [`Schema::require`] and [`Schema::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejection {
+    /// Path of the group field the record belongs to, such as `items`, or empty for the
+    /// top-level record.
+    pub field: String,
+    /// CSS path of the record's element, or `None` for a record read from a whole document.
+    pub css_path: Option<String>,
+    /// Why the record was rejected.
+    pub reason: String,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rejection {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Rejection", 3)?;
+        state.serialize_field("field", &self.field)?;
+        state.serialize_field("css_path", &self.css_path)?;
+        state.serialize_field("reason", &self.reason)?;
+        state.end()
+    }
+}
+
+/// The result of [`Schema::extract_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// The extracted data, as returned by [`Schema::extract`].
+    pub value: Value,
+    /// Where every value read from an element came from, in extraction order.
+    pub provenance: Vec<Provenance>,
+    /// Records dropped by validation rules, in extraction order.
+    pub rejections: Vec<Rejection>,
+}
+
+/// Collects [`Provenance`] and [`Rejection`] records while a schema runs.
+#[derive(Default)]
+struct Trace {
+    path: String,
+    with_provenance: bool,
+    records: Vec<Provenance>,
+    rejections: Vec<Rejection>,
+}
+
+impl Trace {
+    /// Runs `f` with `segment` appended to the current field path.
+    fn nested<T>(
+        trace: Option<&mut Self>,
+        segment: &str,
+        f: impl FnOnce(Option<&mut Self>) -> T,
+    ) -> T {
+        let Some(trace) = trace else { return f(None) };
+        let len = trace.path.len();
+        if len > 0 && !segment.is_empty() && !segment.starts_with('[') {
+            trace.path.push('.');
+        }
+        trace.path.push_str(segment);
+        let result = f(Some(&mut *trace));
+        trace.path.truncate(len);
+        result
+    }
+}
+
+/// What to read from a matched element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Concatenated text content.
+    Text,
+    /// The value of an attribute.
+    Attr(String),
+    /// Outer HTML.
+    Html,
+    /// Inner HTML.
+    InnerHtml,
+}
+
+/// Post-processing applied to an extracted string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// Trims leading and trailing whitespace.
+    Trim,
+    /// Trims and collapses internal whitespace runs to single spaces.
+    NormalizeSpace,
+    /// Converts to lowercase.
+    Lowercase,
+    /// Converts to uppercase.
+    Uppercase,
+    /// Parses a number with [`numbers::parse_number`]; `Null` on failure.
+    Number,
+    /// Parses a price with [`numbers::parse_price`] into `{amount, currency}`; `Null` on
+    /// failure.
+    Price,
+    /// Parses a date with [`parse_date`] into an ISO 8601 string; `Null` on failure.
+    Date,
+}
+
+impl Transform {
+    /// Applies the transform to a string.
+    #[must_use]
+    pub fn apply(self, s: &str) -> Value {
+        match self {
+            Self::Trim => s.trim().into(),
+            Self::NormalizeSpace => s.split_whitespace().collect::<Vec<_>>().join(" ").into(),
+            Self::Lowercase => s.to_lowercase().into(),
+            Self::Uppercase => s.to_uppercase().into(),
+            Self::Number => numbers::parse_number(s).into(),
+            Self::Price => numbers::parse_price(s).map_or(Value::Null, |price| {
+                Value::Object(vec![
+                    ("amount".to_string(), price.amount.into()),
+                    ("currency".to_string(), price.currency.into()),
+                ])
+            }),
+            Self::Date => parse_date(s).map(|date| date.to_string()).into(),
+        }
+    }
+}
+
+/// Reduces the matches of a repeated field to a single value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Aggregate {
+    /// The number of matched elements.
+    Count,
+    /// The string and number values joined with a separator; missing values are skipped.
+    Join(String),
+    /// The first value that is not `Null`.
+    First,
+    /// The last value that is not `Null`.
+    Last,
+    /// The smallest number, e.g. after [`Transform::Number`]; `Null` if there is none.
+    Min,
+    /// The largest number, e.g. after [`Transform::Number`]; `Null` if there is none.
+    Max,
+}
+
+impl Aggregate {
+    /// Applies the aggregation to the items of a list; other values count as a single item.
+    #[must_use]
+    pub fn apply(&self, value: Value) -> Value {
+        let items = match value {
+            Value::List(items) => items,
+            Value::Null => Vec::new(),
+            value => vec![value],
+        };
+        let number_items = || items.iter().filter_map(Value::as_f64);
+        match self {
+            #[allow(clippy::cast_precision_loss)] // match counts stay far below 2^52
+            Self::Count => Value::Number(items.len() as f64),
+            Self::Join(separator) => items
+                .iter()
+                .filter_map(|item| match item {
+                    Value::String(s) => Some(s.clone()),
+                    Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join(separator)
+                .into(),
+            Self::First => items.iter().find(|item| !item.is_null()).cloned().into(),
+            Self::Last => items.iter().rev().find(|item| !item.is_null()).cloned().into(),
+            Self::Min => number_items().reduce(f64::min).into(),
+            Self::Max => number_items().reduce(f64::max).into(),
+        }
+    }
+}
+
+/// A single schema field.
+///
+/// Built from one of the constructors, then refined with [`many`](Field::many),
+/// [`transform`](Field::transform), and fallbacks added with [`or`](Field::or).
+#[derive(Debug, Clone)]
+pub struct Field {
+    selector: Option<CompiledSelector>,
+    kind: FieldKind,
+    many: bool,
+    fallback: Option<Box<Field>>,
+    conditions: Vec<Condition>,
+    aggregate: Option<Aggregate>,
+}
+
+#[derive(Debug, Clone)]
+enum FieldKind {
+    Value { source: Source, transform: Option<Transform> },
+    Group(Schema),
+}
+
+/// Requires an element matching `selector` to be present, or absent, in a context.
+#[derive(Debug, Clone)]
+struct Condition {
+    selector: CompiledSelector,
+    present: bool,
+}
+
+impl Condition {
+    fn new(selector: &str, present: bool) -> QueryResult<Self> {
+        Ok(Self { selector: CompiledSelector::compile(selector)?, present })
+    }
+
+    /// Checks the context element and its descendants, or the whole document at the top
+    /// level.
+    fn holds(&self, doc: &Document, scope: Option<NodeId>) -> bool {
+        let found = scope.map_or_else(
+            || find_compiled(doc, &self.selector).is_some(),
+            |scope| {
+                matches_selector(doc, scope, self.selector.selector_list())
+                    || find_within_compiled(doc, scope, &self.selector).is_some()
+            },
+        );
+        found == self.present
+    }
+
+    fn all_hold(conditions: &[Self], doc: &Document, scope: Option<NodeId>) -> bool {
+        conditions.iter().all(|condition| condition.holds(doc, scope))
+    }
+}
+
+/// A callback returning the reason for rejecting a record; see [`Schema::validate`].
+type Check = Arc<dyn Fn(&Value) -> Result<(), String> + Send + Sync>;
+
+/// A validation rule a schema checks every record against.
+#[derive(Clone)]
+enum Rule {
+    /// The field must have a value; see [`Schema::require`].
+    Require(String),
+    /// A callback returning the reason for rejecting a record.
+    Custom(Check),
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Require(name) => f.debug_tuple("Require").field(name).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl Rule {
+    fn check(&self, record: &Value) -> Result<(), String> {
+        match self {
+            Self::Require(name) => {
+                let missing = match record.get(name) {
+                    None | Some(Value::Null) => true,
+                    Some(Value::String(s)) => s.trim().is_empty(),
+                    Some(Value::List(items)) => items.is_empty(),
+                    Some(_) => false,
+                };
+                if missing { Err(format!("missing required field `{name}`")) } else { Ok(()) }
+            }
+            Self::Custom(check) => check(record),
+        }
+    }
+}
+
+impl Field {
+    /// Reads the text content of the first element matching `selector`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    pub fn text(selector: &str) -> QueryResult<Self> {
+        Self::with_source(selector, Source::Text)
+    }
+
+    /// Reads attribute `name` of the first element matching `selector`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    pub fn attr(selector: &str, name: &str) -> QueryResult<Self> {
+        Self::with_source(selector, Source::Attr(name.to_string()))
+    }
+
+    /// Reads the outer HTML of the first element matching `selector`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    pub fn html(selector: &str) -> QueryResult<Self> {
+        Self::with_source(selector, Source::Html)
+    }
+
+    /// Reads the inner HTML of the first element matching `selector`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    pub fn inner_html(selector: &str) -> QueryResult<Self> {
+        Self::with_source(selector, Source::InnerHtml)
+    }
+
+    /// Reads `source` from the element matching `selector`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    pub fn with_source(selector: &str, source: Source) -> QueryResult<Self> {
+        Ok(Self {
+            selector: Some(CompiledSelector::compile(selector)?),
+            kind: FieldKind::Value { source, transform: None },
+            many: false,
+            fallback: None,
+            conditions: Vec::new(),
+            aggregate: None,
+        })
+    }
+
+    /// Reads `source` from the context element itself instead of a descendant.
+    ///
+    /// Useful inside groups, e.g. the `href` of each matched `<a>`. At the top level the
+    /// context element is the document root.
+    #[must_use]
+    pub const fn own(source: Source) -> Self {
+        Self {
+            selector: None,
+            kind: FieldKind::Value { source, transform: None },
+            many: false,
+            fallback: None,
+            conditions: Vec::new(),
+            aggregate: None,
+        }
+    }
+
+    /// Runs `schema` with the element matching `selector` as context, producing an object.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    pub fn group(selector: &str, schema: Schema) -> QueryResult<Self> {
+        Ok(Self {
+            selector: Some(CompiledSelector::compile(selector)?),
+            kind: FieldKind::Group(schema),
+            many: false,
+            fallback: None,
+            conditions: Vec::new(),
+            aggregate: None,
+        })
+    }
+
+    /// Parses a selector spec: `selector` reads text content, and `selector@name` reads
+    /// attribute `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{
+    ///     Soup,
+    ///     extract::{Field, Schema, Value},
+    /// };
+    ///
+    /// let image = Field::parse("meta[property='og:image']@content")?;
+    /// let schema = Schema::new().field("image", image);
+    /// let soup = Soup::parse("<meta property='og:image' content='/tea.png'>");
+    /// assert_eq!(schema.extract(&soup).get("image"), Some(&Value::from("/tea.png")));
+    /// # Ok::<(), scrape_core::QueryError>(())
+    /// ```
+    pub fn parse(spec: &str) -> QueryResult<Self> {
+        let is_attr_name = |name: &str| {
+            !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+        };
+        match spec.rsplit_once('@') {
+            Some((selector, name)) if is_attr_name(name) => Self::attr(selector.trim(), name),
+            _ => Self::text(spec),
+        }
+    }
+
+    /// Reads the first of `specs` that yields a value, trying them in order.
+    ///
+    /// Each spec is parsed with [`parse`](Self::parse), and the fields are chained with
+    /// [`or`](Self::or). This is the usual defensive pattern for pages whose markup varies.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if `specs` is empty or any selector syntax
+    /// is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{
+    ///     Soup,
+    ///     extract::{Field, Schema, Value},
+    /// };
+    ///
+    /// let title = Field::first_of(&["h1.headline", "meta[property='og:title']@content", "title"])?;
+    /// let schema = Schema::new().field("title", title);
+    ///
+    /// let soup = Soup::parse("<title>Tea | Shop</title><meta property='og:title' content='Tea'>");
+    /// assert_eq!(schema.extract(&soup).get("title"), Some(&Value::from("Tea")));
+    /// # Ok::<(), scrape_core::QueryError>(())
+    /// ```
+    pub fn first_of(specs: &[&str]) -> QueryResult<Self> {
+        let Some((first, rest)) = specs.split_first() else {
+            return Err(QueryError::invalid_selector("no selectors given"));
+        };
+        rest.iter().try_fold(Self::parse(first)?, |field, spec| Ok(field.or(Self::parse(spec)?)))
+    }
+
+    /// Extracts every match as a list instead of only the first.
+    ///
+    /// Applies to the fallbacks added with [`or`](Self::or) so far as well.
+    #[must_use]
+    pub fn many(mut self) -> Self {
+        self.many = true;
+        self.fallback = self.fallback.map(|fallback| Box::new(fallback.many()));
+        self
+    }
+
+    /// Applies `transform` to each extracted string.
+    ///
+    /// Has no effect on groups. Applies to the fallbacks added with [`or`](Self::or) so
+    /// far as well.
+    #[must_use]
+    pub fn transform(mut self, transform: Transform) -> Self {
+        if let FieldKind::Value { transform: slot, .. } = &mut self.kind {
+            *slot = Some(transform);
+        }
+        self.fallback = self.fallback.map(|fallback| Box::new(fallback.transform(transform)));
+        self
+    }
+
+    /// Extracts the field only if an element matching `selector` is present.
+    ///
+    /// The condition is checked in the field's context: the group element and its
+    /// descendants inside a group, or the whole document at the top level. Otherwise the
+    /// field yields `Null`, or an empty list if it repeats, and its fallbacks are tried.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{
+    ///     Soup,
+    ///     extract::{Field, Schema, Value},
+    /// };
+    ///
+    /// let product = Schema::new()
+    ///     .field("name", Field::text("h2")?)
+    ///     .field("restock", Field::text(".restock")?.when(".sold-out")?);
+    /// let schema = Schema::new().field("products", Field::group(".product", product)?.many());
+    ///
+    /// let soup = Soup::parse(
+    ///     r#"<div class="product"><h2>Tea</h2><p class="restock">May</p></div>
+    ///     <div class="product sold-out"><h2>Mate</h2><p class="restock">June</p></div>"#,
+    /// );
+    /// let data = schema.extract(&soup);
+    /// let products = data.get("products").and_then(Value::as_list).unwrap();
+    /// assert_eq!(products[0].get("restock"), Some(&Value::Null));
+    /// assert_eq!(products[1].get("restock"), Some(&Value::from("June")));
+    /// # Ok::<(), scrape_core::QueryError>(())
+    /// ```
+    pub fn when(mut self, selector: &str) -> QueryResult<Self> {
+        self.conditions.push(Condition::new(selector, true)?);
+        Ok(self)
+    }
+
+    /// Extracts the field only if no element matching `selector` is present.
+    ///
+    /// The opposite of [`when`](Self::when), checked in the same context.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    pub fn unless(mut self, selector: &str) -> QueryResult<Self> {
+        self.conditions.push(Condition::new(selector, false)?);
+        Ok(self)
+    }
+
+    /// Extracts every match like [`many`](Self::many), then reduces them with `aggregate`.
+    ///
+    /// Fallbacks added with [`or`](Self::or) are tried before aggregating, so a chain
+    /// aggregates whichever field matched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{
+    ///     Soup,
+    ///     extract::{Aggregate, Field, Schema, Transform, Value},
+    /// };
+    ///
+    /// let schema = Schema::new()
+    ///     .field("review_count", Field::text(".review")?.aggregate(Aggregate::Count))
+    ///     .field(
+    ///         "best",
+    ///         Field::text(".stars")?.transform(Transform::Number).aggregate(Aggregate::Max),
+    ///     )
+    ///     .field("tags", Field::text(".tag")?.aggregate(Aggregate::Join(", ".into())));
+    ///
+    /// let soup = Soup::parse(
+    ///     r#"<div class="review"><b class="stars">4</b></div>
+    ///     <div class="review"><b class="stars">5</b></div>
+    ///     <i class="tag">green</i><i class="tag">loose</i>"#,
+    /// );
+    /// let data = schema.extract(&soup);
+    /// assert_eq!(data.get("review_count"), Some(&Value::from(2.0)));
+    /// assert_eq!(data.get("best"), Some(&Value::from(5.0)));
+    /// assert_eq!(data.get("tags"), Some(&Value::from("green, loose")));
+    /// # Ok::<(), scrape_core::QueryError>(())
+    /// ```
+    #[must_use]
+    pub fn aggregate(self, aggregate: Aggregate) -> Self {
+        let mut field = self.many();
+        field.aggregate = Some(aggregate);
+        field
+    }
+
+    /// Falls back to `other` when this field yields nothing.
+    ///
+    /// `other` runs when this field yields `Null` (no match, a missing attribute, or a
+    /// failed transform) or, for repeated fields, an empty list. Chained calls are tried in
+    /// order, so `a.or(b).or(c)` tries `a`, then `b`, then `c`. Only the field that
+    /// produced the value records [`Provenance`].
+    #[must_use]
+    pub fn or(mut self, other: Self) -> Self {
+        self.fallback = Some(Box::new(match self.fallback.take() {
+            Some(fallback) => fallback.or(other),
+            None => other,
+        }));
+        self
+    }
+
+    fn extract(&self, doc: &Document, scope: Option<NodeId>, trace: Option<&mut Trace>) -> Value {
+        let value = self.extract_chain(doc, scope, trace);
+        match &self.aggregate {
+            Some(aggregate) => aggregate.apply(value),
+            None => value,
+        }
+    }
+
+    /// Extracts this field, trying its fallbacks while it yields nothing.
+    fn extract_chain(
+        &self,
+        doc: &Document,
+        scope: Option<NodeId>,
+        mut trace: Option<&mut Trace>,
+    ) -> Value {
+        let recorded = trace.as_ref().map_or(0, |trace| trace.records.len());
+        let value = self.extract_own(doc, scope, trace.as_deref_mut());
+        match &self.fallback {
+            Some(fallback) if value.is_null() || value.as_list().is_some_and(<[_]>::is_empty) => {
+                if let Some(trace) = trace.as_deref_mut() {
+                    trace.records.truncate(recorded);
+                }
+                fallback.extract(doc, scope, trace)
+            }
+            _ => value,
+        }
+    }
+
+    /// Extracts this field without trying its fallbacks.
+    fn extract_own(
+        &self,
+        doc: &Document,
+        scope: Option<NodeId>,
+        mut trace: Option<&mut Trace>,
+    ) -> Value {
+        if !Condition::all_hold(&self.conditions, doc, scope) {
+            return if self.many { Value::List(Vec::new()) } else { Value::Null };
+        }
+
+        let Some(selector) = &self.selector else {
+            let id = scope.or_else(|| doc.root());
+            if !self.many {
+                return id.map_or(Value::Null, |id| self.read(doc, id, trace));
+            }
+            let value = Trace::nested(trace, "[0]", |trace| {
+                id.map_or(Value::Null, |id| self.read(doc, id, trace))
+            });
+            return Value::List(vec![value]);
+        };
+
+        let find_all = || {
+            scope.map_or_else(
+                || find_all_compiled(doc, selector),
+                |scope| find_all_within_compiled(doc, scope, selector),
+            )
+        };
+        let (gated, validated) = match &self.kind {
+            FieldKind::Group(schema) => (!schema.conditions.is_empty(), !schema.rules.is_empty()),
+            FieldKind::Value { .. } => (false, false),
+        };
+
+        if self.many {
+            let mut items = Vec::new();
+            for id in find_all() {
+                if gated && !self.keeps(doc, id) {
+                    continue;
+                }
+                let segment = format!("[{}]", items.len());
+                if let Some(value) = self.read_valid(doc, id, &segment, trace.as_deref_mut()) {
+                    items.push(value);
+                }
+            }
+            Value::List(items)
+        } else if gated || validated {
+            find_all()
+                .into_iter()
+                .filter(|&id| self.keeps(doc, id))
+                .find_map(|id| self.read_valid(doc, id, "", trace.as_deref_mut()))
+                .unwrap_or(Value::Null)
+        } else {
+            let id = scope.map_or_else(
+                || find_compiled(doc, selector),
+                |scope| find_within_compiled(doc, scope, selector),
+            );
+            id.map_or(Value::Null, |id| self.read(doc, id, trace))
+        }
+    }
+
+    /// Reads a match under `segment`, or returns `None` if it is a group record that the
+    /// schema's rules reject.
+    fn read_valid(
+        &self,
+        doc: &Document,
+        id: NodeId,
+        segment: &str,
+        mut trace: Option<&mut Trace>,
+    ) -> Option<Value> {
+        let recorded = trace.as_ref().map_or(0, |trace| trace.records.len());
+        let value = Trace::nested(trace.as_deref_mut(), segment, |trace| self.read(doc, id, trace));
+        let FieldKind::Group(schema) = &self.kind else { return Some(value) };
+        let Err(reason) = schema.check(&value) else { return Some(value) };
+        if let Some(trace) = trace {
+            trace.records.truncate(recorded);
+            trace.rejections.push(Rejection {
+                field: trace.path.clone(),
+                css_path: Some(Tag::new(doc, id).css_path()),
+                reason,
+            });
+        }
+        None
+    }
+
+    /// Returns `false` for group matches whose records the schema's conditions skip.
+    fn keeps(&self, doc: &Document, id: NodeId) -> bool {
+        match &self.kind {
+            FieldKind::Group(schema) => Condition::all_hold(&schema.conditions, doc, Some(id)),
+            FieldKind::Value { .. } => true,
+        }
+    }
+
+    fn read(&self, doc: &Document, id: NodeId, trace: Option<&mut Trace>) -> Value {
+        let tag = Tag::new(doc, id);
+        match &self.kind {
+            FieldKind::Group(schema) => schema.extract_within(doc, Some(id), trace),
+            FieldKind::Value { source, transform } => {
+                if let Some(trace) = trace.filter(|trace| trace.with_provenance) {
+                    trace.records.push(Provenance {
+                        field: trace.path.clone(),
+                        selector: self.selector.as_ref().map(|s| s.source().to_string()),
+                        css_path: tag.css_path(),
+                        line: doc.line(id),
+                    });
+                }
+                let raw = match source {
+                    Source::Text => Some(tag.text()),
+                    Source::Attr(name) => tag.get(name).map(str::to_string),
+                    Source::Html => Some(tag.outer_html()),
+                    Source::InnerHtml => Some(tag.inner_html()),
+                };
+                match (raw, transform) {
+                    (Some(raw), Some(transform)) => transform.apply(&raw),
+                    (raw, _) => raw.into(),
+                }
+            }
+        }
+    }
+}
+
+/// A declarative extraction schema: named fields in declaration order.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    fields: Vec<(String, Field)>,
+    conditions: Vec<Condition>,
+    rules: Vec<Rule>,
+}
+
+impl Schema {
+    /// Creates an empty schema.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a field, replacing any existing field with the same name.
+    #[must_use]
+    pub fn field(mut self, name: impl Into<String>, field: Field) -> Self {
+        let name = name.into();
+        match self.fields.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, slot)) => *slot = field,
+            None => self.fields.push((name, field)),
+        }
+        self
+    }
+
+    /// Runs the schema only if an element matching `selector` is present.
+    ///
+    /// Inside a group the condition is checked against each matched element and its
+    /// descendants: records that fail it are skipped, so repeated groups leave them out
+    /// and single groups take the first record that passes. At the top level the whole
+    /// document is checked, and a failed condition makes the result `Null`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{
+    ///     Soup,
+    ///     extract::{Field, Schema, Value},
+    /// };
+    ///
+    /// let product = Schema::new().field("name", Field::text("h2")?).unless(".sold-out")?;
+    /// let schema = Schema::new().field("available", Field::group(".product", product)?.many());
+    ///
+    /// let soup = Soup::parse(
+    ///     r#"<div class="product sold-out"><h2>Mate</h2></div>
+    ///     <div class="product"><h2>Tea</h2></div>"#,
+    /// );
+    /// let data = schema.extract(&soup);
+    /// let available = data.get("available").and_then(Value::as_list).unwrap();
+    /// assert_eq!(available.len(), 1);
+    /// assert_eq!(available[0].get("name"), Some(&Value::from("Tea")));
+    /// # Ok::<(), scrape_core::QueryError>(())
+    /// ```
+    pub fn when(mut self, selector: &str) -> QueryResult<Self> {
+        self.conditions.push(Condition::new(selector, true)?);
+        Ok(self)
+    }
+
+    /// Runs the schema only if no element matching `selector` is present.
+    ///
+    /// The opposite of [`when`](Self::when), checked in the same context.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`] if the selector syntax is invalid.
+    pub fn unless(mut self, selector: &str) -> QueryResult<Self> {
+        self.conditions.push(Condition::new(selector, false)?);
+        Ok(self)
+    }
+
+    /// Rejects records in which field `name` is missing: `Null`, blank text, or an empty list.
+    ///
+    /// Rejected records are left out of repeated groups, single groups take the first
+    /// record that passes, and a rejected top-level record makes the result `Null`.
+    /// [`extract_report`](Self::extract_report) reports every rejection with its reason.
+    #[must_use]
+    pub fn require(mut self, name: impl Into<String>) -> Self {
+        self.rules.push(Rule::Require(name.into()));
+        self
+    }
+
+    /// Rejects records for which `check` returns an error; the error is the reason reported
+    /// in [`Rejection::reason`].
+    ///
+    /// Records are checked after all their fields are extracted, in the order the rules
+    /// were added, and rejected records are handled as with [`require`](Self::require).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{
+    ///     Soup,
+    ///     extract::{Field, Schema, Transform, Value},
+    /// };
+    ///
+    /// let product = Schema::new()
+    ///     .field("name", Field::text("h2")?)
+    ///     .field("price", Field::text(".price")?.transform(Transform::Number))
+    ///     .require("name")
+    ///     .validate(|record| match record.get("price").and_then(Value::as_f64) {
+    ///         Some(price) if price > 0.0 => Ok(()),
+    ///         _ => Err("price is not positive".into()),
+    ///     });
+    /// let schema = Schema::new().field("products", Field::group(".product", product)?.many());
+    ///
+    /// let soup = Soup::parse(
+    ///     r#"<div class="product"><h2>Tea</h2><b class="price">4.50</b></div>
+    ///     <div class="product"><h2>Mug</h2><b class="price">0</b></div>
+    ///     <div class="product"><b class="price">9</b></div>"#,
+    /// );
+    /// let report = schema.extract_report(&soup);
+    /// let products = report.value.get("products").and_then(Value::as_list).unwrap();
+    /// assert_eq!(products.len(), 1);
+    /// let reasons: Vec<_> = report.rejections.iter().map(|r| r.reason.as_str()).collect();
+    /// assert_eq!(reasons, ["price is not positive", "missing required field `name`"]);
+    /// # Ok::<(), scrape_core::QueryError>(())
+    /// ```
+    #[must_use]
+    pub fn validate(
+        mut self,
+        check: impl Fn(&Value) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.rules.push(Rule::Custom(Arc::new(check)));
+        self
+    }
+
+    /// Returns the field names in declaration order.
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Runs the schema against a whole document, producing a [`Value::Object`].
+    #[must_use]
+    pub fn extract(&self, soup: &Soup) -> Value {
+        self.extract_record(soup.document(), None, None)
+    }
+
+    /// Runs the schema with `tag` as context; selectors match its descendants.
+    #[must_use]
+    pub fn extract_from(&self, tag: &Tag<'_>) -> Value {
+        self.extract_record(tag.document(), Some(tag.node_id()), None)
+    }
+
+    /// Runs the schema against a whole document like [`extract`](Self::extract), also
+    /// returning the [`Provenance`] of every value read from an element, in extraction
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{
+    ///     Soup,
+    ///     extract::{Field, Schema},
+    /// };
+    ///
+    /// let schema = Schema::new().field("links", Field::attr("a", "href").unwrap().many());
+    /// let soup = Soup::parse("<p><a href='/a'>A</a></p><p><a href='/b'>B</a></p>");
+    /// let (_, provenance) = schema.extract_with_provenance(&soup);
+    /// assert_eq!(provenance[1].field, "links[1]");
+    /// assert_eq!(provenance[1].selector.as_deref(), Some("a"));
+    /// assert_eq!(provenance[1].css_path, "html > body > p:nth-of-type(2) > a");
+    /// ```
+    #[must_use]
+    pub fn extract_with_provenance(&self, soup: &Soup) -> (Value, Vec<Provenance>) {
+        let report = self.extract_report(soup);
+        (report.value, report.provenance)
+    }
+
+    /// Runs the schema against a whole document like [`extract`](Self::extract), also
+    /// returning the [`Provenance`] of every value and the records that validation rules
+    /// rejected.
+    #[must_use]
+    pub fn extract_report(&self, soup: &Soup) -> Report {
+        let mut trace = Trace { with_provenance: true, ..Trace::default() };
+        let value = self.extract_record(soup.document(), None, Some(&mut trace));
+        Report { value, provenance: trace.records, rejections: trace.rejections }
+    }
+
+    /// Runs the schema against a whole document like [`extract`](Self::extract), also
+    /// returning the records that validation rules rejected.
+    ///
+    /// Cheaper than [`extract_report`](Self::extract_report) when provenance is not needed.
+    #[must_use]
+    pub fn extract_with_rejections(&self, soup: &Soup) -> (Value, Vec<Rejection>) {
+        let mut trace = Trace::default();
+        let value = self.extract_record(soup.document(), None, Some(&mut trace));
+        (value, trace.rejections)
+    }
+
+    /// Runs the schema against a whole document and deserializes the result into `T`.
+    ///
+    /// Missing single fields are `null`, so map them to `Option` fields in `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`serde_json::Error`] if the extracted data does not fit `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{
+    ///     Soup,
+    ///     extract::{Field, Schema},
+    /// };
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Page {
+    ///     title: String,
+    ///     links: Vec<String>,
+    /// }
+    ///
+    /// let schema = Schema::new()
+    ///     .field("title", Field::text("h1").unwrap())
+    ///     .field("links", Field::attr("a", "href").unwrap().many());
+    /// let soup = Soup::parse("<h1>Home</h1><a href='/a'>A</a><a href='/b'>B</a>");
+    /// let page: Page = schema.extract_as(&soup).unwrap();
+    /// assert_eq!(page.title, "Home");
+    /// assert_eq!(page.links, ["/a", "/b"]);
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn extract_as<T: serde::de::DeserializeOwned>(
+        &self,
+        soup: &Soup,
+    ) -> Result<T, serde_json::Error> {
+        serde_json::from_value(self.extract(soup).into())
+    }
+
+    /// Extracts a top-level record, returning `Null` if the rules reject it.
+    fn extract_record(
+        &self,
+        doc: &Document,
+        scope: Option<NodeId>,
+        mut trace: Option<&mut Trace>,
+    ) -> Value {
+        let recorded = trace.as_ref().map_or(0, |trace| trace.records.len());
+        let value = self.extract_within(doc, scope, trace.as_deref_mut());
+        let Err(reason) = self.check(&value) else { return value };
+        if let Some(trace) = trace {
+            trace.records.truncate(recorded);
+            trace.rejections.push(Rejection {
+                field: String::new(),
+                css_path: scope.map(|id| Tag::new(doc, id).css_path()),
+                reason,
+            });
+        }
+        Value::Null
+    }
+
+    /// Checks an extracted record against the rules; records cut by conditions pass.
+    fn check(&self, record: &Value) -> Result<(), String> {
+        if record.is_null() {
+            return Ok(());
+        }
+        self.rules.iter().try_for_each(|rule| rule.check(record))
+    }
+
+    fn extract_within(
+        &self,
+        doc: &Document,
+        scope: Option<NodeId>,
+        mut trace: Option<&mut Trace>,
+    ) -> Value {
+        if !Condition::all_hold(&self.conditions, doc, scope) {
+            return Value::Null;
+        }
+        Value::Object(
+            self.fields
+                .iter()
+                .map(|(name, field)| {
+                    let value = Trace::nested(trace.as_deref_mut(), name, |trace| {
+                        field.extract(doc, scope, trace)
+                    });
+                    (name.clone(), value)
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><title>Shop</title></head><body>
+        <ul>
+            <li class="item" data-sku="A1"><a href="/lamp">Lamp</a> <b>1.299,00 €</b>
+                <time datetime="2024-03-05">March 5</time></li>
+            <li class="item" data-sku="B2"><a href="/desk">  Desk  </a> <b>n/a</b></li>
+        </ul>
+    </body></html>"#;
+
+    #[test]
+    fn test_schema_flat_fields() {
+        let soup = Soup::parse(PAGE);
+        let schema = Schema::new()
+            .field("title", Field::text("title").unwrap())
+            .field("links", Field::attr("a", "href").unwrap().many())
+            .field("missing", Field::text("table").unwrap())
+            .field("empty", Field::text("table").unwrap().many());
+        assert_eq!(
+            schema.extract(&soup),
+            Value::Object(vec![
+                ("title".into(), "Shop".into()),
+                ("links".into(), Value::List(vec!["/lamp".into(), "/desk".into()])),
+                ("missing".into(), Value::Null),
+                ("empty".into(), Value::List(vec![])),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_schema_nested_groups_and_transforms() {
+        let soup = Soup::parse(PAGE);
+        let item = Schema::new()
+            .field("sku", Field::own(Source::Attr("data-sku".into())))
+            .field("name", Field::text("a").unwrap().transform(Transform::Trim))
+            .field("price", Field::text("b").unwrap().transform(Transform::Price))
+            .field("date", Field::attr("time", "datetime").unwrap().transform(Transform::Date));
+        let schema = Schema::new().field("items", Field::group("li.item", item).unwrap().many());
+
+        let data = schema.extract(&soup);
+        let items = data.get("items").and_then(Value::as_list).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].get("sku"), Some(&"A1".into()));
+        assert_eq!(items[1].get("name"), Some(&"Desk".into()));
+        let price = items[0].get("price").unwrap();
+        assert_eq!(price.get("amount").and_then(Value::as_f64), Some(1299.0));
+        assert_eq!(price.get("currency").and_then(Value::as_str), Some("EUR"));
+        assert_eq!(items[0].get("date"), Some(&"2024-03-05".into()));
+        assert!(items[1].get("price").is_some_and(Value::is_null));
+        assert!(items[1].get("date").is_some_and(Value::is_null));
+    }
+
+    #[test]
+    fn test_schema_extract_from_tag() {
+        let soup = Soup::parse(PAGE);
+        let ul = soup.find("ul").unwrap().unwrap();
+        let schema = Schema::new()
+            .field("first", Field::text("a").unwrap())
+            .field("title", Field::text("title").unwrap())
+            .field("tag", Field::own(Source::Html).transform(Transform::Uppercase));
+        let data = schema.extract_from(&ul);
+        assert_eq!(data.get("first"), Some(&"Lamp".into()));
+        assert_eq!(data.get("title"), Some(&Value::Null));
+        assert!(data.get("tag").and_then(Value::as_str).is_some_and(|s| s.starts_with("<UL>")));
+    }
+
+    #[test]
+    fn test_schema_invalid_selector_and_field_replacement() {
+        assert!(Field::text("[").is_err());
+        let schema = Schema::new()
+            .field("a", Field::text("p").unwrap())
+            .field("b", Field::text("p").unwrap())
+            .field("a", Field::html("p").unwrap());
+        assert_eq!(schema.field_names().collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_schema_fallback_chain() {
+        let soup = Soup::parse(PAGE);
+        let schema = Schema::new()
+            .field("title", Field::first_of(&["h1", "meta@content", "title"]).unwrap())
+            .field("sku", Field::first_of(&["li@data-id", "li@data-sku"]).unwrap())
+            .field("none", Field::first_of(&["h1", "h2"]).unwrap())
+            .field("names", Field::text("li.gone").unwrap().or(Field::text("a").unwrap()).many())
+            .field(
+                "price",
+                Field::text("i")
+                    .unwrap()
+                    .or(Field::text("b").unwrap())
+                    .transform(Transform::Number),
+            );
+        assert_eq!(
+            schema.extract(&soup),
+            Value::Object(vec![
+                ("title".into(), "Shop".into()),
+                ("sku".into(), "A1".into()),
+                ("none".into(), Value::Null),
+                ("names".into(), Value::List(vec!["Lamp".into(), "  Desk  ".into()])),
+                ("price".into(), 1299.0.into()),
+            ])
+        );
+
+        let (_, provenance) = schema.extract_with_provenance(&soup);
+        let selectors: Vec<_> = provenance.iter().map(|p| p.selector.as_deref()).collect();
+        assert_eq!(selectors, [Some("title"), Some("li"), Some("a"), Some("a"), Some("b")]);
+
+        assert!(Field::first_of(&[]).is_err());
+        assert!(Field::first_of(&["p", "["]).is_err());
+        assert!(Field::parse(r#"a[title="x@y"]"#).is_ok());
+    }
+
+    #[test]
+    fn test_schema_conditions() {
+        let soup = Soup::parse(PAGE);
+        let item = Schema::new()
+            .field("name", Field::text("a").unwrap().transform(Transform::Trim))
+            .field("when_dated", Field::attr("time", "datetime").unwrap().when("time").unwrap())
+            .field("unless_dated", Field::text("b").unwrap().unless("time").unwrap())
+            .field(
+                "self",
+                Field::own(Source::Attr("data-sku".into())).when("li[data-sku]").unwrap(),
+            );
+        let schema = Schema::new()
+            .field("items", Field::group("li.item", item.clone()).unwrap().many())
+            .field("undated", Field::group("li", item.clone().unless("time").unwrap()).unwrap())
+            .field(
+                "links",
+                Field::text("a")
+                    .unwrap()
+                    .when("table")
+                    .unwrap()
+                    .or(Field::attr("a", "href").unwrap())
+                    .many(),
+            );
+
+        let data = schema.extract(&soup);
+        let items = data.get("items").and_then(Value::as_list).unwrap();
+        assert_eq!(items[0].get("when_dated"), Some(&"2024-03-05".into()));
+        assert_eq!(items[0].get("unless_dated"), Some(&Value::Null));
+        assert_eq!(items[1].get("when_dated"), Some(&Value::Null));
+        assert_eq!(items[1].get("unless_dated"), Some(&"n/a".into()));
+        assert_eq!(items[1].get("self"), Some(&"B2".into()));
+        let undated = data.get("undated").unwrap();
+        assert_eq!(undated.get("name"), Some(&"Desk".into()));
+        // A failed condition falls through to the fallback.
+        assert_eq!(data.get("links"), Some(&Value::List(vec!["/lamp".into(), "/desk".into()])));
+
+        let gated = Schema::new().field("items", Field::group("li.item", item).unwrap().many());
+        let gated = gated.unless("ul").unwrap();
+        assert_eq!(gated.extract(&soup), Value::Null);
+        let (_, provenance) = gated.extract_with_provenance(&soup);
+        assert!(provenance.is_empty());
+
+        let dated = Schema::new()
+            .field("sku", Field::own(Source::Attr("data-sku".into())))
+            .when("time")
+            .unwrap();
+        let schema = Schema::new().field("dated", Field::group("li", dated).unwrap().many());
+        let (value, provenance) = schema.extract_with_provenance(&soup);
+        assert_eq!(
+            value.get("dated"),
+            Some(&Value::List(vec![Value::Object(vec![("sku".into(), "A1".into())])]))
+        );
+        assert_eq!(provenance.len(), 1);
+        assert_eq!(provenance[0].field, "dated[0].sku");
+
+        assert!(Field::text("p").unwrap().when("[").is_err());
+        assert!(Schema::new().unless("[").is_err());
+    }
+
+    #[test]
+    fn test_schema_aggregates() {
+        let soup = Soup::parse(PAGE);
+        let price = || Field::text("b").unwrap().transform(Transform::Number);
+        let schema = Schema::new()
+            .field("count", Field::text("li").unwrap().aggregate(Aggregate::Count))
+            .field("none", Field::text("td").unwrap().aggregate(Aggregate::Count))
+            .field(
+                "skus",
+                Field::attr("li", "data-sku").unwrap().aggregate(Aggregate::Join("|".into())),
+            )
+            .field(
+                "first_date",
+                Field::attr("li time", "datetime").unwrap().aggregate(Aggregate::First),
+            )
+            .field("last_price", price().aggregate(Aggregate::Last))
+            .field("min", price().aggregate(Aggregate::Min))
+            .field("max", Field::text("td").unwrap().or(price()).aggregate(Aggregate::Max))
+            .field("no_number", Field::text("a").unwrap().aggregate(Aggregate::Min));
+        assert_eq!(
+            schema.extract(&soup),
+            Value::Object(vec![
+                ("count".into(), 2.0.into()),
+                ("none".into(), 0.0.into()),
+                ("skus".into(), "A1|B2".into()),
+                ("first_date".into(), "2024-03-05".into()),
+                ("last_price".into(), 1299.0.into()),
+                ("min".into(), 1299.0.into()),
+                ("max".into(), 1299.0.into()),
+                ("no_number".into(), Value::Null),
+            ])
+        );
+
+        assert_eq!(Aggregate::Join(", ".into()).apply(Value::from(3.5)), Value::from("3.5"));
+        assert_eq!(Aggregate::First.apply(Value::Null), Value::Null);
+    }
+
+    #[test]
+    fn test_schema_provenance() {
+        let soup = Soup::parse(PAGE);
+        let item = Schema::new()
+            .field("sku", Field::own(Source::Attr("data-sku".into())))
+            .field("name", Field::text("a").unwrap())
+            .field("missing", Field::text("table").unwrap());
+        let schema = Schema::new()
+            .field("title", Field::text("title").unwrap())
+            .field("items", Field::group("li.item", item).unwrap().many());
+
+        let (value, provenance) = schema.extract_with_provenance(&soup);
+        assert_eq!(value, schema.extract(&soup));
+        let fields: Vec<_> = provenance.iter().map(|p| p.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["title", "items[0].sku", "items[0].name", "items[1].sku", "items[1].name"]
+        );
+        assert_eq!(provenance[0].css_path, "html > head > title");
+        assert_eq!(provenance[0].line, Some(1));
+        assert_eq!(provenance[3].selector, None);
+        assert_eq!(provenance[3].css_path, "html > body > ul > li:nth-of-type(2)");
+        assert_eq!(provenance[3].line, Some(5));
+        assert_eq!(provenance[4].selector.as_deref(), Some("a"));
+        assert_eq!(provenance[4].css_path, "html > body > ul > li:nth-of-type(2) > a");
+    }
+
+    #[test]
+    fn test_schema_validation() {
+        let soup = Soup::parse(PAGE);
+        let sku = || Field::own(Source::Attr("data-sku".into()));
+        let item = Schema::new()
+            .field("sku", sku())
+            .field("price", Field::text("b").unwrap().transform(Transform::Number))
+            .require("price");
+        let second = Schema::new().field("sku", sku()).validate(|record| {
+            match record.get("sku").and_then(Value::as_str) {
+                Some("B2") => Ok(()),
+                sku => Err(format!("unexpected sku {sku:?}")),
+            }
+        });
+        let schema = Schema::new()
+            .field("items", Field::group("li.item", item).unwrap().many())
+            .field("second", Field::group("li.item", second).unwrap());
+
+        let report = schema.extract_report(&soup);
+        assert_eq!(report.value, schema.extract(&soup));
+        assert_eq!(
+            schema.extract_with_rejections(&soup),
+            (report.value.clone(), report.rejections.clone())
+        );
+        assert_eq!(
+            report.value,
+            Value::Object(vec![
+                (
+                    "items".into(),
+                    Value::List(vec![Value::Object(vec![
+                        ("sku".into(), "A1".into()),
+                        ("price".into(), 1299.0.into()),
+                    ])])
+                ),
+                ("second".into(), Value::Object(vec![("sku".into(), "B2".into())])),
+            ])
+        );
+        assert_eq!(
+            report.rejections,
+            [
+                Rejection {
+                    field: "items".into(),
+                    css_path: Some("html > body > ul > li:nth-of-type(2)".into()),
+                    reason: "missing required field `price`".into(),
+                },
+                Rejection {
+                    field: "second".into(),
+                    css_path: Some("html > body > ul > li:nth-of-type(1)".into()),
+                    reason: "unexpected sku Some(\"A1\")".into(),
+                },
+            ]
+        );
+        let fields: Vec<_> = report.provenance.iter().map(|p| p.field.as_str()).collect();
+        assert_eq!(fields, ["items[0].sku", "items[0].price", "second.sku"]);
+
+        let page = Schema::new().field("title", Field::text("table").unwrap()).require("title");
+        let report = page.extract_report(&soup);
+        assert_eq!(report.value, Value::Null);
+        assert_eq!(report.rejections[0].field, "");
+        assert_eq!(report.rejections[0].css_path, None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_schema_serde() {
+        #[derive(serde::Deserialize)]
+        struct Item {
+            sku: String,
+            price: Option<u32>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Page {
+            title: String,
+            items: Vec<Item>,
+        }
+
+        let soup = Soup::parse(PAGE);
+        let item = Schema::new()
+            .field("sku", Field::own(Source::Attr("data-sku".into())))
+            .field("price", Field::text("b").unwrap().transform(Transform::Number));
+        let schema = Schema::new()
+            .field("title", Field::text("title").unwrap())
+            .field("items", Field::group("li.item", item).unwrap().many());
+
+        let json = serde_json::to_string(&schema.extract(&soup)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"title":"Shop","items":[{"sku":"A1","price":1299},{"sku":"B2","price":null}]}"#
+        );
+
+        let page: Page = schema.extract_as(&soup).unwrap();
+        assert_eq!(page.title, "Shop");
+        assert_eq!(page.items[0].sku, "A1");
+        assert_eq!(page.items[0].price, Some(1299));
+        assert_eq!(page.items[1].price, None);
+    }
+}
@@ -0,0 +1,392 @@
+//! Responsive images: `srcset` candidates and `<picture>` sources.
+//!
+//! [`parse_srcset`] (also available as [`Tag::srcset`]) splits a `srcset` attribute into
+//! candidates with their width (`480w`) or pixel density (`2x`) descriptors.
+//! [`best_candidate`] picks one for an [`ImageConstraints`], and [`image_source`] does so
+//! for an `<img>`, the way a browser would:
+//!
+//! 1. Inside a `<picture>`, the first `<source>` before the `<img>` whose `type` is supported,
+//!    whose `media` matches, and whose `srcset` has a candidate wins.
+//! 2. Otherwise the `<img>`'s own `srcset` is used, with `src` as its `1x` candidate.
+//!
+//! The `sizes` attribute is not evaluated: an image is assumed to fill the viewport.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{
+//!     Soup,
+//!     extract::srcset::{ImageConstraints, image_source},
+//! };
+//!
+//! let soup = Soup::parse(
+//!     r#"<picture>
+//!          <source type="image/avif" srcset="hero.avif">
+//!          <source media="(max-width: 600px)" srcset="hero-s.jpg 600w, hero-m.jpg 1200w">
+//!          <img src="hero.jpg" srcset="hero-l.jpg 2x">
+//!        </picture>"#,
+//! );
+//! let img = soup.find("img").unwrap().unwrap();
+//!
+//! // By default the largest candidate of the first source wins.
+//! assert_eq!(image_source(&img, &ImageConstraints::default()).as_deref(), Some("hero.avif"));
+//!
+//! // A phone without AVIF support.
+//! let phone = ImageConstraints {
+//!     viewport_width: Some(400),
+//!     density: 2.0,
+//!     types: vec!["image/jpeg".into()],
+//! };
+//! assert_eq!(image_source(&img, &phone).as_deref(), Some("hero-m.jpg"));
+//!
+//! // A desktop screen skips the `max-width` source.
+//! let desktop = ImageConstraints { viewport_width: Some(1400), ..phone };
+//! assert_eq!(image_source(&img, &desktop).as_deref(), Some("hero-l.jpg"));
+//! ```
+
+use crate::Tag;
+
+/// How a `srcset` candidate describes its size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Descriptor {
+    /// Intrinsic width in pixels, written `480w`.
+    Width(u32),
+    /// Pixel density, written `2x`; candidates without a descriptor are `1x`.
+    Density(f64),
+}
+
+/// An image candidate from a `srcset` attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrcsetCandidate {
+    /// The URL as written.
+    pub url: String,
+    /// The candidate's size.
+    pub descriptor: Descriptor,
+}
+
+/// The display an image is chosen for by [`best_candidate`] and [`image_source`].
+///
+/// The default has no viewport and picks the largest candidate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageConstraints {
+    /// Viewport width in CSS pixels, or `None` to pick the largest candidate.
+    ///
+    /// `w` candidates are assumed to be shown at this width, and `<source media>` is
+    /// matched against it. Without a viewport, `media` is ignored.
+    pub viewport_width: Option<u32>,
+    /// Device pixel ratio: the smallest candidate with at least this density is picked.
+    pub density: f64,
+    /// Supported MIME types, such as `image/webp`, for `<source type>`. Empty accepts
+    /// every type.
+    pub types: Vec<String>,
+}
+
+impl Default for ImageConstraints {
+    fn default() -> Self {
+        Self { viewport_width: None, density: 1.0, types: Vec::new() }
+    }
+}
+
+/// Parses a `srcset` attribute into its candidates.
+///
+/// Candidates with invalid descriptors, such as `0w`, both `w` and `x`, or an `h` height
+/// without a width, are left out. URLs may contain commas; a comma only separates
+/// candidates when it ends a URL or follows its descriptors.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::srcset::{Descriptor, parse_srcset};
+///
+/// let candidates = parse_srcset("a.jpg, b.jpg 2x, c.jpg 800w, d.jpg 2q");
+/// assert_eq!(candidates.len(), 3);
+/// assert_eq!(candidates[0].descriptor, Descriptor::Density(1.0));
+/// assert_eq!(candidates[1].descriptor, Descriptor::Density(2.0));
+/// assert_eq!(candidates[2].url, "c.jpg");
+/// assert_eq!(candidates[2].descriptor, Descriptor::Width(800));
+/// ```
+#[must_use]
+pub fn parse_srcset(srcset: &str) -> Vec<SrcsetCandidate> {
+    let mut candidates = Vec::new();
+    let mut rest = srcset;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+        if rest.is_empty() {
+            return candidates;
+        }
+        let end = rest.find(|c: char| c.is_ascii_whitespace()).unwrap_or(rest.len());
+        let (mut url, after) = rest.split_at(end);
+        rest = after;
+
+        let mut descriptors = "";
+        if url.ends_with(',') {
+            url = url.trim_end_matches(',');
+        } else {
+            // Descriptors run to the next comma outside parentheses.
+            let mut depth = 0usize;
+            let end = rest
+                .char_indices()
+                .find(|&(_, c)| {
+                    match c {
+                        '(' => depth += 1,
+                        ')' => depth = depth.saturating_sub(1),
+                        ',' => return depth == 0,
+                        _ => {}
+                    }
+                    false
+                })
+                .map_or(rest.len(), |(i, _)| i);
+            (descriptors, rest) = rest.split_at(end);
+        }
+
+        if let Some(descriptor) = parse_descriptors(descriptors) {
+            candidates.push(SrcsetCandidate { url: url.to_string(), descriptor });
+        }
+    }
+}
+
+/// Picks the candidate that best fits `constraints`.
+///
+/// With a viewport, this is the smallest candidate whose density is at least
+/// [`ImageConstraints::density`], or the densest one if none is; without one, it is the
+/// largest. Returns `None` if there are no candidates.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::extract::srcset::{ImageConstraints, best_candidate, parse_srcset};
+///
+/// let candidates = parse_srcset("s.jpg 400w, m.jpg 800w, l.jpg 1600w");
+/// let best = |constraints| best_candidate(&candidates, &constraints).unwrap().url.as_str();
+///
+/// assert_eq!(best(ImageConstraints::default()), "l.jpg");
+/// let viewport = ImageConstraints { viewport_width: Some(700), ..ImageConstraints::default() };
+/// assert_eq!(best(viewport.clone()), "m.jpg");
+/// assert_eq!(best(ImageConstraints { density: 3.0, ..viewport }), "l.jpg");
+/// ```
+#[must_use]
+pub fn best_candidate<'a>(
+    candidates: &'a [SrcsetCandidate],
+    constraints: &ImageConstraints,
+) -> Option<&'a SrcsetCandidate> {
+    let viewport = constraints.viewport_width.unwrap_or(1).max(1);
+    let density = |candidate: &SrcsetCandidate| match candidate.descriptor {
+        Descriptor::Density(density) => density,
+        Descriptor::Width(width) => f64::from(width) / f64::from(viewport),
+    };
+    let largest = candidates.iter().rev().max_by(|a, b| density(a).total_cmp(&density(b)));
+    if constraints.viewport_width.is_none() {
+        return largest;
+    }
+    candidates
+        .iter()
+        .filter(|&candidate| density(candidate) >= constraints.density)
+        .min_by(|a, b| density(a).total_cmp(&density(b)))
+        .or(largest)
+}
+
+/// Returns the URL, as written, of the image `img` shows under `constraints`.
+///
+/// See the [module documentation](self) for how `<picture>` sources and `src` are
+/// considered. Returns `None` if `img` has no candidates.
+#[must_use]
+pub fn image_source(img: &Tag<'_>, constraints: &ImageConstraints) -> Option<String> {
+    if let Some(picture) = img.parent().filter(|parent| parent.name() == Some("picture")) {
+        let sources = picture
+            .children()
+            .take_while(|child| child.node_id() != img.node_id())
+            .filter(|child| child.name() == Some("source"));
+        for source in sources {
+            if !source.get("type").is_none_or(|mime| supports_type(mime, &constraints.types)) {
+                continue;
+            }
+            if let (Some(media), Some(width)) = (source.get("media"), constraints.viewport_width)
+                && !media_matches(media, width)
+            {
+                continue;
+            }
+            let candidates = source.srcset();
+            if let Some(best) = best_candidate(&candidates, constraints) {
+                return Some(best.url.clone());
+            }
+        }
+    }
+
+    let mut candidates = img.srcset();
+    let has_1x = candidates.iter().any(|candidate| match candidate.descriptor {
+        Descriptor::Width(_) => true,
+        Descriptor::Density(density) => (density - 1.0).abs() < f64::EPSILON,
+    });
+    if let Some(src) = img.get("src").filter(|src| !src.trim().is_empty())
+        && !has_1x
+    {
+        let url = src.trim().to_string();
+        candidates.push(SrcsetCandidate { url, descriptor: Descriptor::Density(1.0) });
+    }
+    best_candidate(&candidates, constraints).map(|candidate| candidate.url.clone())
+}
+
+// ==================== Helpers ====================
+
+/// Parses the descriptors of one candidate, or returns `None` if they are invalid.
+fn parse_descriptors(descriptors: &str) -> Option<Descriptor> {
+    let mut descriptor = None;
+    let mut height = false;
+    for token in descriptors.split_ascii_whitespace() {
+        let (value, unit) = token.split_at(token.len() - token.chars().last()?.len_utf8());
+        let parsed = match unit {
+            "w" => Descriptor::Width(value.parse().ok().filter(|&width| width > 0)?),
+            "x" => Descriptor::Density(
+                value.parse().ok().filter(|density: &f64| density.is_finite() && *density > 0.0)?,
+            ),
+            // A height is allowed alongside a width, and has no effect on the choice.
+            "h" => {
+                value.parse::<u32>().ok().filter(|&height| height > 0)?;
+                if std::mem::replace(&mut height, true) {
+                    return None;
+                }
+                continue;
+            }
+            _ => return None,
+        };
+        if descriptor.replace(parsed).is_some() {
+            return None;
+        }
+    }
+    match descriptor {
+        Some(Descriptor::Density(_)) | None if height => None,
+        descriptor => Some(descriptor.unwrap_or(Descriptor::Density(1.0))),
+    }
+}
+
+/// Returns whether the MIME type `mime`, ignoring parameters, is one of `types`.
+fn supports_type(mime: &str, types: &[String]) -> bool {
+    let essence = mime.split(';').next().unwrap_or_default().trim();
+    types.is_empty() || types.iter().any(|t| t.eq_ignore_ascii_case(essence))
+}
+
+/// Returns whether the media query list `media` matches a viewport `width` pixels wide.
+///
+/// Only `min-width` and `max-width` in `px`, `em`, or `rem` are evaluated; other
+/// conditions are assumed to match.
+fn media_matches(media: &str, width: u32) -> bool {
+    let media = media.to_ascii_lowercase();
+    media.split(',').any(|query| {
+        query
+            .split('(')
+            .skip(1)
+            .filter_map(|condition| condition.split(')').next()?.split_once(':'))
+            .all(|(feature, value)| {
+                let value = value.trim();
+                let pixels = value.strip_suffix("px").map(|n| (n, 1.0)).or_else(|| {
+                    let n = value.strip_suffix("em")?;
+                    Some((n.strip_suffix('r').unwrap_or(n), 16.0))
+                });
+                let Some(pixels) =
+                    pixels.and_then(|(n, scale)| Some(n.trim().parse::<f64>().ok()? * scale))
+                else {
+                    return true;
+                };
+                match feature.trim() {
+                    "min-width" => f64::from(width) >= pixels,
+                    "max-width" => f64::from(width) <= pixels,
+                    _ => true,
+                }
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Soup;
+
+    fn urls(srcset: &str) -> Vec<String> {
+        parse_srcset(srcset).into_iter().map(|candidate| candidate.url).collect()
+    }
+
+    #[test]
+    fn test_parse_srcset() {
+        assert_eq!(urls(" a.jpg 1x ,b.jpg,, c.jpg 2x"), ["a.jpg", "b.jpg", "c.jpg"]);
+        assert_eq!(urls("a.jpg,b.jpg 2x"), ["a.jpg,b.jpg"]);
+        assert_eq!(
+            urls("https://cdn.example.com/w_300,h_200/a.jpg 300w, b.jpg 600w 400h"),
+            ["https://cdn.example.com/w_300,h_200/a.jpg", "b.jpg"]
+        );
+        assert!(parse_srcset("").is_empty());
+
+        let candidates = parse_srcset("a.jpg 1.5x, b.jpg 640w");
+        assert_eq!(candidates[0].descriptor, Descriptor::Density(1.5));
+        assert_eq!(candidates[1].descriptor, Descriptor::Width(640));
+    }
+
+    #[test]
+    fn test_parse_srcset_invalid_descriptors() {
+        assert!(urls("a.jpg 0w, b.jpg 2x 100w, c.jpg -1x, d.jpg 1x 2x, e.jpg 1.5w").is_empty());
+        assert_eq!(urls("a.jpg 100h, b.jpg (future) 2x, c.jpg 2x"), ["c.jpg"]);
+    }
+
+    #[test]
+    fn test_best_candidate() {
+        let candidates = parse_srcset("a.jpg, b.jpg 2x, c.jpg 3x");
+        let best = |viewport_width, density| {
+            let constraints =
+                ImageConstraints { viewport_width, density, ..ImageConstraints::default() };
+            best_candidate(&candidates, &constraints).unwrap().url.as_str()
+        };
+        assert_eq!(best(None, 1.0), "c.jpg");
+        assert_eq!(best(Some(800), 1.0), "a.jpg");
+        assert_eq!(best(Some(800), 1.5), "b.jpg");
+        assert_eq!(best(Some(800), 4.0), "c.jpg");
+        assert!(best_candidate(&[], &ImageConstraints::default()).is_none());
+    }
+
+    #[test]
+    fn test_image_source_without_picture() {
+        let soup = Soup::parse(
+            "<img id=\"a\" src=\" a.jpg \"><img id=\"b\" src=\"b.jpg\" srcset=\"b2.jpg 2x\"><img \
+             id=\"c\" src=\"c.jpg\" srcset=\"c1.jpg 1x, c2.jpg 2x\"><img id=\"d\" alt=\"\">",
+        );
+        let source = |id: &str, density| {
+            let img = soup.find(&format!("#{id}")).unwrap().unwrap();
+            let constraints =
+                ImageConstraints { viewport_width: Some(800), density, types: Vec::new() };
+            image_source(&img, &constraints)
+        };
+        assert_eq!(source("a", 2.0).as_deref(), Some("a.jpg"));
+        assert_eq!(source("b", 1.0).as_deref(), Some("b.jpg"));
+        assert_eq!(source("b", 2.0).as_deref(), Some("b2.jpg"));
+        assert_eq!(source("c", 1.0).as_deref(), Some("c1.jpg"));
+        assert_eq!(source("d", 1.0), None);
+    }
+
+    #[test]
+    fn test_image_source_picture() {
+        let soup = Soup::parse(
+            "<picture><source srcset=\"\"><source type=\"image/webp; q=1\" \
+             srcset=\"a.webp\"><source media=\"screen and (min-width: 50em)\" \
+             srcset=\"wide.jpg\"><img src=\"fallback.jpg\"><source srcset=\"after.jpg\"></picture>",
+        );
+        let img = soup.find("img").unwrap().unwrap();
+        let source = |viewport_width, types: &[&str]| {
+            let types = types.iter().map(ToString::to_string).collect();
+            image_source(&img, &ImageConstraints { viewport_width, density: 1.0, types })
+        };
+        assert_eq!(source(None, &[]).as_deref(), Some("a.webp"));
+        assert_eq!(source(None, &["IMAGE/WEBP"]).as_deref(), Some("a.webp"));
+        assert_eq!(source(None, &["image/jpeg"]).as_deref(), Some("wide.jpg"));
+        assert_eq!(source(Some(1024), &["image/jpeg"]).as_deref(), Some("wide.jpg"));
+        assert_eq!(source(Some(320), &["image/jpeg"]).as_deref(), Some("fallback.jpg"));
+    }
+
+    #[test]
+    fn test_media_matches() {
+        assert!(media_matches("(min-width: 600px)", 600));
+        assert!(!media_matches("(MIN-WIDTH: 600px)", 599));
+        assert!(media_matches("(max-width: 30rem)", 480));
+        assert!(!media_matches("(min-width: 400px) and (max-width: 800px)", 801));
+        assert!(media_matches("(max-width: 300px), (min-width: 1000px)", 1200));
+        assert!(media_matches("(orientation: landscape)", 100));
+        assert!(media_matches("print", 100));
+    }
+}
@@ -0,0 +1,423 @@
+//! Structured extraction from HTML tables.
+//!
+//! [`table`] (also available as [`Tag::as_table`]) reads a `<table>` into a rectangular
+//! grid of cell text:
+//!
+//! - Rows come from `<thead>`, then `<tbody>` and bare `<tr>` in document order, then `<tfoot>`
+//! - `colspan` and `rowspan` are resolved by repeating a cell's text in every slot it covers;
+//!   `rowspan="0"` extends to the end of the row group
+//! - The header is the rows of `<thead>`, or the first row when all of its cells are `<th>`;
+//!   grouped header rows are joined per column with a space
+//! - Short rows are padded with empty cells, and whitespace in cell text is collapsed
+//!
+//! Nested tables are not read as rows; their text is part of the enclosing cell.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::Soup;
+//!
+//! let soup = Soup::parse(
+//!     r#"<table>
+//!         <tr><th>City</th><th colspan="2">Temperature</th></tr>
+//!         <tr><td rowspan="2">Oslo</td><td>-3</td><td>4</td></tr>
+//!         <tr><td>-1</td><td>6</td></tr>
+//!     </table>"#,
+//! );
+//! let table = soup.find("table").unwrap().unwrap().as_table().unwrap();
+//! assert_eq!(table.headers, vec!["City", "Temperature", "Temperature"]);
+//! assert_eq!(table.rows[1], vec!["Oslo", "-1", "6"]);
+//!
+//! let records = table.to_records();
+//! assert_eq!(records[0]["Temperature_2"], "4");
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{Tag, convert::escape};
+
+/// Largest `colspan` honored, as in the HTML table processing model.
+const MAX_COLSPAN: usize = 1000;
+
+/// Largest `rowspan` honored, as in the HTML table processing model.
+const MAX_ROWSPAN: usize = 65534;
+
+/// Cell text laid out on a grid, with spans resolved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Table {
+    /// Text of the `<caption>`, if any.
+    pub caption: Option<String>,
+    /// Column headers; empty if the table has no header row.
+    pub headers: Vec<String>,
+    /// Body rows, each with one cell per column.
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Returns the number of columns.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(self.headers.len(), Vec::len)
+    }
+
+    /// Returns `true` if the table has no body rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Returns the keys used by [`to_records`](Self::to_records), one per column.
+    ///
+    /// Headers are used as-is where possible. Columns without a header are named
+    /// `column_N` (1-based), and repeated names get a `_2`, `_3`, ... suffix.
+    #[must_use]
+    pub fn record_keys(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        (0..self.width())
+            .map(|col| {
+                let base = match self.headers.get(col) {
+                    Some(header) if !header.is_empty() => header.clone(),
+                    _ => format!("column_{}", col + 1),
+                };
+                let mut key = base.clone();
+                let mut n = 1;
+                while !seen.insert(key.clone()) {
+                    n += 1;
+                    key = format!("{base}_{n}");
+                }
+                key
+            })
+            .collect()
+    }
+
+    /// Converts the body rows into maps from [`record_keys`](Self::record_keys) to cell text.
+    ///
+    /// The maps are sorted by key, so records serialize the same way on every run.
+    #[must_use]
+    pub fn to_records(&self) -> Vec<BTreeMap<String, String>> {
+        let keys = self.record_keys();
+        self.rows
+            .iter()
+            .map(|row| keys.iter().cloned().zip(row.iter().cloned()).collect())
+            .collect()
+    }
+
+    /// Renders the table as a GitHub Flavored Markdown pipe table.
+    ///
+    /// A table without headers gets an empty header row, since pipe tables require
+    /// one. Pipes and Markdown punctuation in cells are escaped; the caption is not
+    /// included.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse(
+    ///     "<table><tr><th>Item</th><th>Price</th></tr><tr><td>Tea</td><td>3</td></tr></table>",
+    /// );
+    /// let table = soup.find("table").unwrap().unwrap().as_table().unwrap();
+    /// assert_eq!(table.to_markdown(), "| Item | Price |\n| --- | --- |\n| Tea | 3 |");
+    /// ```
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let width = self.width().max(self.headers.len());
+        let row = |cells: &[String]| {
+            let cells: Vec<_> = (0..width)
+                .map(|i| {
+                    cells.get(i).map_or_else(String::new, |cell| escape(cell).replace('|', "\\|"))
+                })
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+        let mut lines = vec![row(&self.headers), format!("|{}", " --- |".repeat(width))];
+        lines.extend(self.rows.iter().map(|cells| row(cells)));
+        lines.join("\n")
+    }
+}
+
+/// Reads a `<table>` element into a [`Table`].
+///
+/// Returns `None` if `tag` is not a `<table>`. See the [module documentation](self) for
+/// how rows, spans, and headers are resolved.
+#[must_use]
+pub fn table(tag: &Tag<'_>) -> Option<Table> {
+    if tag.name() != Some("table") {
+        return None;
+    }
+
+    let mut caption = None;
+    let mut head = Vec::new();
+    let mut body = Vec::new();
+    let mut foot = Vec::new();
+    let mut bare = Vec::new();
+
+    for child in tag.children() {
+        match child.name() {
+            Some("caption") if caption.is_none() => caption = Some(collapse(&child.text())),
+            Some("thead") => head.push(row_cells(&child)),
+            Some("tbody") => {
+                flush(&mut bare, &mut body);
+                body.push(row_cells(&child));
+            }
+            Some("tfoot") => foot.push(row_cells(&child)),
+            Some("tr") => bare.push(cells(&child)),
+            _ => {}
+        }
+    }
+    flush(&mut bare, &mut body);
+
+    let mut header_rows: Vec<Vec<String>> = head.into_iter().flat_map(layout).collect();
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for (i, group) in body.into_iter().chain(foot).enumerate() {
+        let header_first =
+            i == 0 && header_rows.is_empty() && group.first().is_some_and(|r| is_header_row(r));
+        let mut group = layout(group).into_iter();
+        if header_first {
+            header_rows.extend(group.next());
+        }
+        rows.extend(group);
+    }
+
+    let width = header_rows.iter().chain(&rows).map(Vec::len).max().unwrap_or(0);
+    for row in &mut rows {
+        row.resize(width, String::new());
+    }
+    let headers = if header_rows.is_empty() {
+        Vec::new()
+    } else {
+        (0..width).map(|col| join_header(&header_rows, col)).collect()
+    };
+
+    Some(Table { caption, headers, rows })
+}
+
+// ==================== Layout ====================
+
+struct Cell {
+    text: String,
+    header: bool,
+    colspan: usize,
+    rowspan: usize,
+}
+
+fn flush(bare: &mut Vec<Vec<Cell>>, body: &mut Vec<Vec<Vec<Cell>>>) {
+    if !bare.is_empty() {
+        body.push(std::mem::take(bare));
+    }
+}
+
+fn row_cells(group: &Tag<'_>) -> Vec<Vec<Cell>> {
+    group.children().filter(|row| row.name() == Some("tr")).map(|row| cells(&row)).collect()
+}
+
+fn cells(row: &Tag<'_>) -> Vec<Cell> {
+    row.children()
+        .filter_map(|cell| {
+            let header = match cell.name()? {
+                "th" => true,
+                "td" => false,
+                _ => return None,
+            };
+            Some(Cell {
+                text: collapse(&cell.text()),
+                header,
+                colspan: span(&cell, "colspan", 1).clamp(1, MAX_COLSPAN),
+                rowspan: span(&cell, "rowspan", 1).min(MAX_ROWSPAN),
+            })
+        })
+        .collect()
+}
+
+fn span(cell: &Tag<'_>, attr: &str, default: usize) -> usize {
+    cell.attr_int(attr).map_or(default, |n| usize::try_from(n).unwrap_or(default))
+}
+
+fn is_header_row(row: &[Cell]) -> bool {
+    !row.is_empty() && row.iter().all(|cell| cell.header)
+}
+
+/// Lays out one row group, repeating spanned cells in every slot they cover.
+fn layout(rows: Vec<Vec<Cell>>) -> Vec<Vec<String>> {
+    let total = rows.len();
+    // Cells carried down by rowspan, per column: (rows still covered, text).
+    let mut carried: Vec<Option<(usize, String)>> = Vec::new();
+    let mut grid = Vec::with_capacity(total);
+
+    for (i, row) in rows.into_iter().enumerate() {
+        let rows_left = total - i;
+        let mut out = Vec::new();
+        let mut cells = row.into_iter();
+
+        loop {
+            let col = out.len();
+            if let Some(slot) = carried.get_mut(col)
+                && let Some((left, text)) = slot
+            {
+                out.push(text.clone());
+                *left -= 1;
+                if *left == 0 {
+                    *slot = None;
+                }
+                continue;
+            }
+
+            if let Some(cell) = cells.next() {
+                let rowspan =
+                    if cell.rowspan == 0 { rows_left } else { cell.rowspan.min(rows_left) };
+                let end = col + cell.colspan;
+                if carried.len() < end {
+                    carried.resize(end, None);
+                }
+                for slot in &mut carried[col..end] {
+                    *slot = (rowspan > 1).then(|| (rowspan - 1, cell.text.clone()));
+                }
+                out.resize(end, cell.text);
+                continue;
+            }
+
+            // No cells left; pad up to the next column still covered from above.
+            match carried.get(col..).and_then(|rest| rest.iter().position(Option::is_some)) {
+                Some(gap) => out.resize(col + gap, String::new()),
+                None => break,
+            }
+        }
+
+        grid.push(out);
+    }
+
+    grid
+}
+
+/// Joins the distinct non-empty header texts of a column, top to bottom.
+fn join_header(header_rows: &[Vec<String>], col: usize) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for text in header_rows.iter().filter_map(|row| row.get(col)) {
+        if !text.is_empty() && parts.last() != Some(&text.as_str()) {
+            parts.push(text);
+        }
+    }
+    parts.join(" ")
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Soup;
+
+    fn parse(html: &str) -> Table {
+        let soup = Soup::parse(html);
+        table(&soup.find("table").unwrap().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_thead_and_tbody() {
+        let table = parse(
+            "<table><caption> Prices \
+             </caption><thead><tr><th>Item</th><th>Price</th></tr></thead><tbody><tr><td>Tea</\
+             td><td>3</td></tr><tr><td>Coffee</td><td>4</td></tr></tbody></table>",
+        );
+        assert_eq!(table.caption.as_deref(), Some("Prices"));
+        assert_eq!(table.headers, vec!["Item", "Price"]);
+        assert_eq!(table.rows, vec![vec!["Tea", "3"], vec!["Coffee", "4"]]);
+        assert_eq!(table.width(), 2);
+    }
+
+    #[test]
+    fn test_first_row_of_th_is_header() {
+        let table =
+            parse("<table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>");
+        assert_eq!(table.headers, vec!["A", "B"]);
+        assert_eq!(table.rows, vec![vec!["1", "2"]]);
+    }
+
+    #[test]
+    fn test_no_header() {
+        let table =
+            parse("<table><tr><th>Row</th><td>1</td></tr><tr><td>x</td><td>2</td></tr></table>");
+        assert!(table.headers.is_empty());
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.record_keys(), vec!["column_1", "column_2"]);
+    }
+
+    #[test]
+    fn test_colspan_and_rowspan() {
+        let table = parse(
+            "<table><tr><td rowspan=\"3\">A</td><td colspan=\"2\">B</td></tr><tr><td>C</td><td \
+             rowspan=\"2\">D</td></tr><tr><td>E</td></tr></table>",
+        );
+        assert_eq!(table.rows, vec![vec!["A", "B", "B"], vec!["A", "C", "D"], vec!["A", "E", "D"]]);
+    }
+
+    #[test]
+    fn test_rowspan_zero_and_overflow_stop_at_group_end() {
+        let table = parse(
+            "<table><tbody><tr><td \
+             rowspan=\"0\">A</td><td>1</td></tr><tr><td>2</td></tr></tbody><tbody><tr><td \
+             rowspan=\"9\">B</td><td>3</td></tr></tbody><tbody><tr><td>C</td><td>4</td></tr></\
+             tbody></table>",
+        );
+        assert_eq!(
+            table.rows,
+            vec![vec!["A", "1"], vec!["A", "2"], vec!["B", "3"], vec!["C", "4"]]
+        );
+    }
+
+    #[test]
+    fn test_short_rows_are_padded() {
+        let table =
+            parse("<table><tr><td>1</td><td>2</td><td>3</td></tr><tr><td>4</td></tr></table>");
+        assert_eq!(table.rows[1], vec!["4", "", ""]);
+    }
+
+    #[test]
+    fn test_grouped_headers() {
+        let table = parse(
+            "<table><thead><tr><th rowspan=\"2\">Name</th><th \
+             colspan=\"2\">Score</th></tr><tr><th>Q1</th><th>Q2</th></tr></\
+             thead><tbody><tr><td>Ann</td><td>1</td><td>2</td></tr></tbody></table>",
+        );
+        assert_eq!(table.headers, vec!["Name", "Score Q1", "Score Q2"]);
+    }
+
+    #[test]
+    fn test_tfoot_comes_last() {
+        let table = parse(
+            "<table><tfoot><tr><td>Total</td></tr></tfoot><tbody><tr><td>Row</td></tr></tbody></\
+             table>",
+        );
+        assert_eq!(table.rows, vec![vec!["Row"], vec!["Total"]]);
+    }
+
+    #[test]
+    fn test_nested_table_is_cell_text() {
+        let table = parse(
+            "<table><tr><td><table><tr><td>inner</td></tr></table></td><td>x</td></tr></table>",
+        );
+        assert_eq!(table.rows, vec![vec!["inner", "x"]]);
+    }
+
+    #[test]
+    fn test_to_records() {
+        let table = parse(
+            "<table><tr><th>Name</th><th>Name</th><th></th></tr><tr><td>a</td><td>b</td><td>c</\
+             td></tr></table>",
+        );
+        assert_eq!(table.record_keys(), vec!["Name", "Name_2", "column_3"]);
+        let records = table.to_records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["Name"], "a");
+        assert_eq!(records[0]["Name_2"], "b");
+        assert_eq!(records[0]["column_3"], "c");
+    }
+
+    #[test]
+    fn test_not_a_table() {
+        let soup = Soup::parse("<div>x</div>");
+        assert!(table(&soup.find("div").unwrap().unwrap()).is_none());
+    }
+}
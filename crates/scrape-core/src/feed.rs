@@ -0,0 +1,613 @@
+//! Parsing RSS and Atom feeds.
+//!
+//! [`parse`] reads an RSS 2.0 (or RSS 1.0) `<rss>` document or an Atom `<feed>` into a
+//! [`Feed`] with the same shape for both formats. Dates are parsed with
+//! [`parse_date`], and entry content is kept as HTML that [`FeedEntry::soup`] parses into
+//! a [`Soup`].
+//!
+//! The common RSS extensions `content:encoded`, `dc:creator`, `dc:date`, and `atom:link`
+//! are recognized by their conventional prefixes. Other extension elements are skipped.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::feed::{self, FeedKind};
+//!
+//! let feed = feed::parse(
+//!     r#"<rss version="2.0"><channel>
+//!       <title>Example</title>
+//!       <link>https://example.com/</link>
+//!       <item>
+//!         <title>Hello</title>
+//!         <link>https://example.com/hello</link>
+//!         <pubDate>Tue, 05 Mar 2024 10:20:30 GMT</pubDate>
+//!         <description><![CDATA[<p>First <b>post</b></p>]]></description>
+//!       </item>
+//!     </channel></rss>"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(feed.kind, FeedKind::Rss);
+//! assert_eq!(feed.title.as_deref(), Some("Example"));
+//! let entry = &feed.entries[0];
+//! assert_eq!(entry.link(), Some("https://example.com/hello"));
+//! assert_eq!(entry.published.unwrap().to_string(), "2024-03-05T10:20:30Z");
+//! let soup = entry.soup().unwrap();
+//! assert_eq!(soup.find("b").unwrap().unwrap().text(), "post");
+//! ```
+
+use std::{
+    fmt::Write,
+    io::{BufReader, Read},
+};
+
+use crate::{
+    Soup,
+    extract::dates::{DateTime, parse_date},
+    utils::{escape_attr, escape_text, is_void_element},
+    xml::{CHUNK_SIZE, Token, XmlError, XmlReader, local_name},
+};
+
+/// The format of a feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeedKind {
+    /// RSS, from an `<rss>` or `<rdf:RDF>` root element.
+    Rss,
+    /// Atom, from a `<feed>` root element.
+    Atom,
+}
+
+/// A link of a feed or entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedLink {
+    /// Link target.
+    pub href: String,
+    /// Link relation: `alternate` for the page itself, `enclosure` for attached media,
+    /// `self` for the feed URL, and so on.
+    pub rel: String,
+    /// MIME type of the target, if given.
+    pub media_type: Option<String>,
+    /// Size of the target in bytes, if given.
+    pub length: Option<u64>,
+}
+
+/// An RSS `<item>` or Atom `<entry>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedEntry {
+    /// Unique identifier, from `<guid>` or `<id>`.
+    pub id: Option<String>,
+    /// Title, as plain text.
+    pub title: Option<String>,
+    /// Links, in document order.
+    pub links: Vec<FeedLink>,
+    /// Author names or addresses, from `<author>` or `<dc:creator>`.
+    pub authors: Vec<String>,
+    /// Category names, from `<category>`.
+    pub categories: Vec<String>,
+    /// Publication date, from `<pubDate>`, `<dc:date>`, or `<published>`.
+    pub published: Option<DateTime>,
+    /// Date of the last change, from Atom `<updated>`.
+    pub updated: Option<DateTime>,
+    /// Summary as HTML, from RSS `<description>` or Atom `<summary>`.
+    pub summary: Option<String>,
+    /// Full content as HTML, from `<content:encoded>` or Atom `<content>`.
+    pub content: Option<String>,
+}
+
+impl FeedEntry {
+    /// Returns the URL of the entry's page: its first `alternate` link.
+    #[must_use]
+    pub fn link(&self) -> Option<&str> {
+        alternate(&self.links)
+    }
+
+    /// Returns the most complete HTML of the entry: its content, or else its summary.
+    #[must_use]
+    pub fn html(&self) -> Option<&str> {
+        self.content.as_deref().or(self.summary.as_deref())
+    }
+
+    /// Parses [`html`](Self::html) as an HTML fragment.
+    #[must_use]
+    pub fn soup(&self) -> Option<Soup> {
+        self.html().map(Soup::parse_fragment)
+    }
+}
+
+/// A parsed RSS or Atom feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feed {
+    /// Format of the feed.
+    pub kind: FeedKind,
+    /// Title, as plain text.
+    pub title: Option<String>,
+    /// Description as HTML, from RSS `<description>` or Atom `<subtitle>`.
+    pub description: Option<String>,
+    /// Links of the feed itself, in document order.
+    pub links: Vec<FeedLink>,
+    /// Date of the last change, from `<lastBuildDate>`, `<pubDate>`, or `<updated>`.
+    pub updated: Option<DateTime>,
+    /// Entries, in document order.
+    pub entries: Vec<FeedEntry>,
+}
+
+impl Feed {
+    /// Returns the URL of the site the feed belongs to: its first `alternate` link.
+    #[must_use]
+    pub fn link(&self) -> Option<&str> {
+        alternate(&self.links)
+    }
+}
+
+/// Error parsing a feed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FeedError {
+    /// Reading the input failed.
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    /// The input is not well-formed XML.
+    #[error("malformed feed at byte {offset}: {message}")]
+    Syntax {
+        /// Byte offset of the error in the input.
+        offset: u64,
+        /// Description of the problem.
+        message: String,
+    },
+
+    /// The root element is neither `<rss>`, `<rdf:RDF>`, nor `<feed>`.
+    #[error("not a feed: root element is <{0}>")]
+    NotFeed(String),
+}
+
+impl From<XmlError> for FeedError {
+    fn from(error: XmlError) -> Self {
+        match error {
+            XmlError::Io(message) => Self::Io(message),
+            XmlError::Syntax { offset, message } => Self::Syntax { offset, message },
+        }
+    }
+}
+
+/// Parses an RSS or Atom feed.
+///
+/// # Errors
+///
+/// Returns [`FeedError`] if the input is malformed or not a feed.
+pub fn parse(xml: &str) -> Result<Feed, FeedError> {
+    from_reader(xml.as_bytes())
+}
+
+/// Reads an RSS or Atom feed.
+///
+/// # Errors
+///
+/// Returns [`FeedError`] if reading fails or the input is malformed or not a feed.
+pub fn from_reader(reader: impl Read) -> Result<Feed, FeedError> {
+    Parser::new(reader).run()
+}
+
+fn alternate(links: &[FeedLink]) -> Option<&str> {
+    links.iter().find(|link| link.rel == "alternate").map(|link| link.href.as_str())
+}
+
+/// Builds a [`Feed`] from the tokens of the document.
+struct Parser<'a> {
+    xml: XmlReader<'a>,
+    kind: FeedKind,
+    /// Namespace prefix of the root element, stripped from Atom element names.
+    prefix: String,
+    /// Names of the open elements, with the root prefix stripped.
+    stack: Vec<String>,
+    /// Title, description, links, and dates of the feed itself.
+    head: FeedEntry,
+    entries: Vec<FeedEntry>,
+    /// The entry being read, with the depth of its element.
+    entry: Option<(FeedEntry, usize)>,
+    /// The field being read, with the depth of its element.
+    field: Option<(Field, usize)>,
+    text: String,
+}
+
+impl<'a> Parser<'a> {
+    fn new(reader: impl Read + 'a) -> Self {
+        Self {
+            xml: XmlReader::new(Box::new(BufReader::with_capacity(CHUNK_SIZE, reader))),
+            kind: FeedKind::Rss,
+            prefix: String::new(),
+            stack: Vec::new(),
+            head: FeedEntry::default(),
+            entries: Vec::new(),
+            entry: None,
+            field: None,
+            text: String::new(),
+        }
+    }
+
+    fn run(mut self) -> Result<Feed, FeedError> {
+        let mut root = false;
+        while let Some(token) = self.xml.next_token(self.field.is_some())? {
+            match token {
+                Token::Text(text) => {
+                    let text = String::from_utf8_lossy(&text);
+                    if self.field.is_some_and(|(field, _)| field.is_markup()) {
+                        self.text.push_str(&escape_text(&text));
+                    } else {
+                        self.text.push_str(&text);
+                    }
+                }
+                Token::Start { name, attrs, empty } => {
+                    if !root {
+                        self.root(&name)?;
+                        root = true;
+                    }
+                    let name = match self.kind {
+                        FeedKind::Atom => local_name(&name, &self.prefix).unwrap_or(&name),
+                        FeedKind::Rss => &name,
+                    }
+                    .to_string();
+                    self.open(name, &attrs);
+                    if empty {
+                        self.close();
+                    }
+                }
+                Token::End => self.close(),
+            }
+        }
+        if !root {
+            return Err(self.xml.syntax("no root element").into());
+        }
+        if !self.stack.is_empty() {
+            return Err(self.xml.syntax("unexpected end of input").into());
+        }
+
+        let head = self.head;
+        Ok(Feed {
+            kind: self.kind,
+            title: head.title,
+            description: head.summary,
+            links: head.links,
+            updated: head.updated.or(head.published),
+            entries: self.entries,
+        })
+    }
+
+    fn root(&mut self, name: &str) -> Result<(), FeedError> {
+        let (prefix, local) = name.split_once(':').unwrap_or(("", name));
+        self.kind = match local {
+            "rss" | "RDF" => FeedKind::Rss,
+            "feed" => FeedKind::Atom,
+            _ => return Err(FeedError::NotFeed(name.to_string())),
+        };
+        prefix.clone_into(&mut self.prefix);
+        Ok(())
+    }
+
+    /// Handles a start tag.
+    fn open(&mut self, name: String, attrs: &[(String, String)]) {
+        let parent = self.stack.last().map(String::as_str);
+        let depth = self.stack.len() + 1;
+
+        if let Some((field, field_depth)) = self.field {
+            // Inside Atom XHTML content, all elements but the wrapping <div> are kept.
+            if field.is_markup() && depth > field_depth + 1 {
+                let local = name.rsplit(':').next().unwrap_or(&name);
+                let _ = write!(self.text, "<{local}");
+                for (attr, value) in attrs.iter().filter(|(attr, _)| !attr.starts_with("xmlns")) {
+                    let _ = write!(self.text, " {attr}=\"{}\"", escape_attr(value));
+                }
+                self.text.push('>');
+            }
+            self.stack.push(name);
+            return;
+        }
+
+        let entry_name = match self.kind {
+            FeedKind::Rss => "item",
+            FeedKind::Atom => "entry",
+        };
+        let in_entry = self.entry.as_ref().is_some_and(|&(_, entry_depth)| {
+            depth == entry_depth + 1
+                || (depth == entry_depth + 2 && parent == Some("author") && name == "name")
+        });
+        if name == entry_name && self.entry.is_none() && depth <= 3 {
+            self.entry = Some((FeedEntry::default(), depth));
+        } else if in_entry || (self.entry.is_none() && matches!(parent, Some("channel" | "feed"))) {
+            let target = match &mut self.entry {
+                Some((entry, _)) => entry,
+                None => &mut self.head,
+            };
+            self.field = Field::open(self.kind, &name, attrs, target).map(|field| (field, depth));
+            self.text.clear();
+        }
+        self.stack.push(name);
+    }
+
+    /// Handles an end tag.
+    fn close(&mut self) {
+        let depth = self.stack.len();
+        let Some(name) = self.stack.pop() else { return };
+
+        if let Some((field, field_depth)) = self.field {
+            if field.is_markup() && depth > field_depth + 1 {
+                let local = name.rsplit(':').next().unwrap_or(&name);
+                if !is_void_element(local) {
+                    let _ = write!(self.text, "</{local}>");
+                }
+            }
+            if depth > field_depth {
+                return;
+            }
+            self.field = None;
+            let target = match &mut self.entry {
+                Some((entry, _)) => entry,
+                None => &mut self.head,
+            };
+            field.set(target, &self.text);
+        }
+
+        if self.entry.as_ref().is_some_and(|&(_, entry_depth)| depth == entry_depth)
+            && let Some((entry, _)) = self.entry.take()
+        {
+            self.entries.push(entry);
+        }
+    }
+}
+
+/// An element whose text is a value of a feed or entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Id,
+    Title,
+    Link,
+    Author,
+    Category,
+    Published,
+    Updated,
+    Summary(Format),
+    Content(Format),
+}
+
+/// How the text of a summary or content element is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// Escaped or CDATA HTML.
+    Html,
+    /// Plain text, escaped before it is stored as HTML.
+    Text,
+    /// Atom XHTML: markup wrapped in a `<div>`, serialized back as HTML.
+    Xhtml,
+}
+
+impl Field {
+    /// Returns the field read from the text of element `name`, or stores the values of
+    /// an attribute-only element such as an Atom `<link>` in `target` directly.
+    fn open(
+        kind: FeedKind,
+        name: &str,
+        attrs: &[(String, String)],
+        target: &mut FeedEntry,
+    ) -> Option<Self> {
+        let attr =
+            |name: &str| attrs.iter().find(|(attr, _)| attr == name).map(|(_, value)| value.trim());
+        let link = |href: &str, rel: &str| FeedLink {
+            href: href.to_string(),
+            rel: rel.to_string(),
+            media_type: attr("type").map(str::to_string),
+            length: attr("length").and_then(|len| len.parse().ok()),
+        };
+
+        if matches!(name, "link" | "atom:link")
+            && let Some(href) = attr("href")
+        {
+            target.links.push(link(href, attr("rel").unwrap_or("alternate")));
+            return None;
+        }
+        let field = match (kind, name) {
+            (FeedKind::Rss, "enclosure") => {
+                target.links.push(link(attr("url")?, "enclosure"));
+                return None;
+            }
+            (FeedKind::Atom, "category") => {
+                target.categories.extend(attr("term").map(str::to_string));
+                return None;
+            }
+            (_, "title") => Self::Title,
+            (_, "link") => Self::Link,
+            (FeedKind::Rss, "guid") | (FeedKind::Atom, "id") => Self::Id,
+            (FeedKind::Rss, "author" | "dc:creator") | (FeedKind::Atom, "name") => Self::Author,
+            (FeedKind::Rss, "category") => Self::Category,
+            (FeedKind::Rss, "pubDate" | "dc:date") | (FeedKind::Atom, "published") => {
+                Self::Published
+            }
+            (FeedKind::Rss, "lastBuildDate") | (FeedKind::Atom, "updated") => Self::Updated,
+            (FeedKind::Rss, "description") => Self::Summary(Format::Html),
+            (FeedKind::Rss, "content:encoded") => Self::Content(Format::Html),
+            (FeedKind::Atom, "summary" | "subtitle" | "content") => {
+                let format = match attr("type") {
+                    Some("html" | "text/html") => Format::Html,
+                    Some("xhtml" | "application/xhtml+xml") => Format::Xhtml,
+                    _ => Format::Text,
+                };
+                if name == "content" { Self::Content(format) } else { Self::Summary(format) }
+            }
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    /// Returns whether the field's elements are kept as markup rather than read as text.
+    const fn is_markup(self) -> bool {
+        matches!(self, Self::Summary(Format::Xhtml) | Self::Content(Format::Xhtml))
+    }
+
+    fn set(self, target: &mut FeedEntry, text: &str) {
+        let value = text.trim();
+        if value.is_empty() {
+            return;
+        }
+        let html = |format| match format {
+            Format::Text => escape_text(value).into_owned(),
+            Format::Html | Format::Xhtml => value.to_string(),
+        };
+        match self {
+            Self::Id => target.id = Some(value.to_string()),
+            Self::Title => target.title = Some(value.to_string()),
+            Self::Link => target.links.push(FeedLink {
+                href: value.to_string(),
+                rel: "alternate".into(),
+                ..FeedLink::default()
+            }),
+            Self::Author => target.authors.push(value.to_string()),
+            Self::Category => target.categories.push(value.to_string()),
+            Self::Published => target.published = parse_date(value),
+            Self::Updated => target.updated = parse_date(value),
+            Self::Summary(format) => target.summary = Some(html(format)),
+            Self::Content(format) => target.content = Some(html(format)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/"
+     xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:atom="http://www.w3.org/2005/Atom">
+  <channel>
+    <title>Tea &amp; Biscuits</title>
+    <link>https://example.com/</link>
+    <atom:link href="https://example.com/feed.xml" rel="self" type="application/rss+xml"/>
+    <description>Notes on &lt;b&gt;tea&lt;/b&gt;</description>
+    <lastBuildDate>Wed, 06 Mar 2024 08:00:00 +0100</lastBuildDate>
+    <image><title>Logo</title><url>https://example.com/logo.png</url></image>
+    <item>
+      <title>Green tea</title>
+      <link>https://example.com/green</link>
+      <guid isPermaLink="false">post-1</guid>
+      <pubDate>Tue, 05 Mar 2024 10:20:30 GMT</pubDate>
+      <dc:creator>Ada</dc:creator>
+      <category>tea</category><category>green</category>
+      <description>Short &lt;i&gt;summary&lt;/i&gt;</description>
+      <content:encoded><![CDATA[<p>Full <b>post</b></p>]]></content:encoded>
+      <enclosure url="https://example.com/green.mp3" length="1024" type="audio/mpeg"/>
+    </item>
+    <item><title>Untitled</title></item>
+  </channel>
+</rss>
+"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">Example</title>
+  <subtitle type="html">A &lt;em&gt;blog&lt;/em&gt;</subtitle>
+  <link href="https://example.com/"/>
+  <link rel="self" href="https://example.com/atom.xml"/>
+  <updated>2024-03-06T08:00:00Z</updated>
+  <author><name>Site</name></author>
+  <entry>
+    <title>First</title>
+    <link rel="alternate" type="text/html" href="https://example.com/first"/>
+    <id>urn:uuid:1</id>
+    <published>2024-03-05T10:20:30+01:00</published>
+    <updated>2024-03-05T11:00:00+01:00</updated>
+    <author><name>Ada</name><email>ada@example.com</email></author>
+    <author><name>Grace</name></author>
+    <category term="tea"/>
+    <summary>1 &lt; 2 &amp; more</summary>
+    <content type="xhtml">
+      <div xmlns="http://www.w3.org/1999/xhtml"><p class="x">A<br/><b>bold</b> &amp; B</p></div>
+    </content>
+    <source><title>Elsewhere</title><id>urn:other</id></source>
+  </entry>
+  <entry>
+    <title>Second</title>
+    <content type="html">&lt;p&gt;Two&lt;/p&gt;</content>
+  </entry>
+</feed>
+"#;
+
+    fn date(value: &str) -> Option<DateTime> {
+        parse_date(value)
+    }
+
+    #[test]
+    fn test_parse_rss() {
+        let feed = parse(RSS).unwrap();
+        assert_eq!(feed.kind, FeedKind::Rss);
+        assert_eq!(feed.title.as_deref(), Some("Tea & Biscuits"));
+        assert_eq!(feed.description.as_deref(), Some("Notes on <b>tea</b>"));
+        assert_eq!(feed.link(), Some("https://example.com/"));
+        assert_eq!(feed.links[1].rel, "self");
+        assert_eq!(feed.updated, date("2024-03-06T08:00:00+01:00"));
+        assert_eq!(feed.entries.len(), 2);
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.title.as_deref(), Some("Green tea"));
+        assert_eq!(entry.id.as_deref(), Some("post-1"));
+        assert_eq!(entry.link(), Some("https://example.com/green"));
+        assert_eq!(
+            entry.links[1],
+            FeedLink {
+                href: "https://example.com/green.mp3".into(),
+                rel: "enclosure".into(),
+                media_type: Some("audio/mpeg".into()),
+                length: Some(1024),
+            }
+        );
+        assert_eq!(entry.published, date("2024-03-05T10:20:30Z"));
+        assert_eq!(entry.authors, ["Ada"]);
+        assert_eq!(entry.categories, ["tea", "green"]);
+        assert_eq!(entry.summary.as_deref(), Some("Short <i>summary</i>"));
+        assert_eq!(entry.html(), Some("<p>Full <b>post</b></p>"));
+        let soup = entry.soup().unwrap();
+        assert_eq!(soup.find("b").unwrap().unwrap().text(), "post");
+
+        assert_eq!(
+            feed.entries[1],
+            FeedEntry { title: Some("Untitled".into()), ..FeedEntry::default() }
+        );
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let feed = parse(ATOM).unwrap();
+        assert_eq!(feed.kind, FeedKind::Atom);
+        assert_eq!(feed.title.as_deref(), Some("Example"));
+        assert_eq!(feed.description.as_deref(), Some("A <em>blog</em>"));
+        assert_eq!(feed.link(), Some("https://example.com/"));
+        assert_eq!(feed.updated, date("2024-03-06T08:00:00Z"));
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.title.as_deref(), Some("First"));
+        assert_eq!(entry.id.as_deref(), Some("urn:uuid:1"));
+        assert_eq!(entry.link(), Some("https://example.com/first"));
+        assert_eq!(entry.links[0].media_type.as_deref(), Some("text/html"));
+        assert_eq!(entry.published, date("2024-03-05T10:20:30+01:00"));
+        assert_eq!(entry.updated, date("2024-03-05T11:00:00+01:00"));
+        assert_eq!(entry.authors, ["Ada", "Grace"]);
+        assert_eq!(entry.categories, ["tea"]);
+        assert_eq!(entry.summary.as_deref(), Some("1 &lt; 2 &amp; more"));
+        assert_eq!(entry.content.as_deref(), Some("<p class=\"x\">A<br><b>bold</b> &amp; B</p>"));
+        assert_eq!(feed.entries[1].html(), Some("<p>Two</p>"));
+    }
+
+    #[test]
+    fn test_prefixed_atom() {
+        let feed = parse(
+            "<a:feed xmlns:a=\"http://www.w3.org/2005/Atom\"><a:entry><a:title>T</a:title>\
+             <dc:title>ignored</dc:title></a:entry></a:feed>",
+        )
+        .unwrap();
+        assert_eq!(feed.entries[0].title.as_deref(), Some("T"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse("<html><p>hi</p></html>"), Err(FeedError::NotFeed("html".into())));
+        assert!(matches!(parse(""), Err(FeedError::Syntax { offset: 0, .. })));
+        assert!(matches!(parse("<rss><channel>"), Err(FeedError::Syntax { offset: 14, .. })));
+        assert!(matches!(parse("<feed><title"), Err(FeedError::Syntax { offset: 6, .. })));
+    }
+}
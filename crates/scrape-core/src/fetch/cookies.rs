@@ -0,0 +1,271 @@
+//! The cookie store of a [`Session`](super::Session).
+//!
+//! `Set-Cookie` headers are parsed into a name, a value, and the `Domain`, `Path`,
+//! `Secure`, `Expires`, and `Max-Age` attributes, following RFC 6265. Requests get back
+//! only the `name=value` pairs of the cookies whose attributes match them. Public
+//! suffixes are not checked, so a site may set a cookie for its whole registrable
+//! domain or above it.
+
+use std::{
+    cmp::Reverse,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, SystemTime},
+};
+
+use ureq::http::Uri;
+
+use crate::extract::dates::parse_date;
+
+/// Cookies set by responses, shared by the clones of a session.
+#[derive(Debug, Clone, Default)]
+pub(super) struct CookieJar {
+    cookies: Arc<Mutex<Vec<Cookie>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cookie {
+    name: String,
+    value: String,
+    /// The host that set the cookie, or its `Domain` attribute without a leading dot.
+    domain: String,
+    /// `true` without a `Domain` attribute: the cookie is sent only to `domain` itself.
+    host_only: bool,
+    path: String,
+    secure: bool,
+    /// `None` for a session cookie.
+    expires: Option<SystemTime>,
+}
+
+/// The parts of a request URL that cookies are matched against.
+struct Target<'a> {
+    secure: bool,
+    host: String,
+    path: &'a str,
+}
+
+impl CookieJar {
+    /// Stores the cookies of the `Set-Cookie` headers of a response from `url`.
+    ///
+    /// A cookie replaces the one with the same name, domain, and path; an expired cookie
+    /// removes it. Cookies for a domain that `url` is not part of are ignored.
+    pub(super) fn store<'h>(&self, url: &Uri, set_cookie: impl IntoIterator<Item = &'h str>) {
+        let Some(target) = Target::new(url) else { return };
+        let now = SystemTime::now();
+        let mut cookies = self.lock();
+        for header in set_cookie {
+            let Some(cookie) = Cookie::parse(header, &target, now) else { continue };
+            cookies.retain(|c| {
+                c.name != cookie.name || c.domain != cookie.domain || c.path != cookie.path
+            });
+            if !cookie.is_expired(now) {
+                cookies.push(cookie);
+            }
+        }
+    }
+
+    /// Returns the `Cookie` header for a request to `url`, or `None` if no cookie
+    /// applies.
+    ///
+    /// Cookies with longer paths come first, then older cookies, as RFC 6265 recommends.
+    pub(super) fn header(&self, url: &Uri) -> Option<String> {
+        let target = Target::new(url)?;
+        let now = SystemTime::now();
+        let mut cookies = self.lock();
+        cookies.retain(|cookie| !cookie.is_expired(now));
+        let mut matching: Vec<_> =
+            cookies.iter().filter(|cookie| cookie.matches(&target)).collect();
+        matching.sort_by_key(|cookie| Reverse(cookie.path.len()));
+        let pairs: Vec<_> =
+            matching.iter().map(|cookie| format!("{}={}", cookie.name, cookie.value)).collect();
+        drop(cookies);
+        (!pairs.is_empty()).then(|| pairs.join("; "))
+    }
+
+    /// Forgets all cookies.
+    pub(super) fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Cookie>> {
+        self.cookies.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Cookie {
+    /// Parses a `Set-Cookie` header received from `target`.
+    fn parse(header: &str, target: &Target<'_>, now: SystemTime) -> Option<Self> {
+        let mut parts = header.split(';');
+        let (name, value) = parts.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let mut cookie = Self {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+            domain: target.host.clone(),
+            host_only: true,
+            path: default_path(target.path).to_string(),
+            secure: false,
+            expires: None,
+        };
+
+        let mut max_age = None;
+        for attribute in parts {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                    if !domain_matches(&target.host, &domain) {
+                        return None;
+                    }
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "secure" => cookie.secure = true,
+                "expires" => {
+                    if let Some(date) = parse_date(value) {
+                        // Dates before 1970 have passed as well.
+                        cookie.expires = u64::try_from(date.unix_timestamp()).map_or(
+                            Some(SystemTime::UNIX_EPOCH),
+                            |seconds| {
+                                SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(seconds))
+                            },
+                        );
+                    }
+                }
+                "max-age" => max_age = value.parse::<i64>().ok().or(max_age),
+                _ => {}
+            }
+        }
+        // Max-Age takes precedence over Expires; zero or less expires the cookie at once.
+        if let Some(seconds) = max_age {
+            cookie.expires = match u64::try_from(seconds) {
+                Ok(seconds) if seconds > 0 => now.checked_add(Duration::from_secs(seconds)),
+                _ => Some(SystemTime::UNIX_EPOCH),
+            };
+        }
+        Some(cookie)
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    fn matches(&self, target: &Target<'_>) -> bool {
+        let domain = if self.host_only {
+            target.host == self.domain
+        } else {
+            domain_matches(&target.host, &self.domain)
+        };
+        domain && (!self.secure || target.secure) && path_matches(target.path, &self.path)
+    }
+}
+
+impl<'a> Target<'a> {
+    fn new(url: &'a Uri) -> Option<Self> {
+        let path = url.path();
+        Some(Self {
+            secure: url.scheme_str() == Some("https"),
+            host: url.host()?.to_ascii_lowercase(),
+            path: if path.is_empty() { "/" } else { path },
+        })
+    }
+}
+
+/// Returns `true` if `host` is `domain` or one of its subdomains.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host.strip_suffix(domain).is_some_and(|prefix| prefix.is_empty() || prefix.ends_with('.'))
+}
+
+/// Returns `true` if a request for `path` gets cookies for `cookie_path`: equal paths, or
+/// `cookie_path` is a directory of `path`.
+fn path_matches(path: &str, cookie_path: &str) -> bool {
+    path.strip_prefix(cookie_path)
+        .is_some_and(|rest| rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'))
+}
+
+/// Returns the path a cookie without a `Path` attribute gets: the directory of the
+/// request path.
+fn default_path(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(end) => &path[..end],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uri(url: &str) -> Uri {
+        url.parse().unwrap()
+    }
+
+    #[test]
+    fn test_sends_only_name_value_pairs() {
+        let jar = CookieJar::default();
+        jar.store(
+            &uri("https://example.com/login"),
+            ["sid=42; Path=/; HttpOnly; SameSite=Lax", "theme = dark"],
+        );
+        assert_eq!(
+            jar.header(&uri("https://example.com/login")).as_deref(),
+            Some("sid=42; theme=dark")
+        );
+        // Without a Path attribute, `theme` is scoped to the directory of /login, which is /.
+        assert_eq!(jar.header(&uri("https://example.com/")).as_deref(), Some("sid=42; theme=dark"));
+
+        jar.clear();
+        assert_eq!(jar.header(&uri("https://example.com/")), None);
+    }
+
+    #[test]
+    fn test_path_and_domain() {
+        let jar = CookieJar::default();
+        let from = uri("https://www.example.com/shop/cart");
+        jar.store(
+            &from,
+            [
+                "cart=1",
+                "root=2; Path=/",
+                "site=3; Domain=.Example.com; Path=/",
+                "other=4; Domain=example.org",
+            ],
+        );
+
+        assert_eq!(
+            jar.header(&uri("https://www.example.com/shop/items")).as_deref(),
+            Some("cart=1; root=2; site=3")
+        );
+        assert_eq!(
+            jar.header(&uri("https://www.example.com/shopping")).as_deref(),
+            Some("root=2; site=3")
+        );
+        assert_eq!(jar.header(&uri("https://api.example.com/")).as_deref(), Some("site=3"));
+        assert_eq!(jar.header(&uri("https://badexample.com/")), None);
+        assert_eq!(jar.header(&uri("https://example.org/")), None);
+    }
+
+    #[test]
+    fn test_expiry_and_secure() {
+        let jar = CookieJar::default();
+        let site = uri("https://example.com/");
+        jar.store(
+            &site,
+            [
+                "old=1; Expires=Tue, 05 Mar 2024 10:20:30 GMT",
+                "new=2; Expires=Fri, 01 Jan 2100 00:00:00 GMT",
+                "aged=3; Max-Age=3600; Expires=Tue, 05 Mar 2024 10:20:30 GMT",
+                "tls=4; Secure",
+            ],
+        );
+        assert_eq!(jar.header(&site).as_deref(), Some("new=2; aged=3; tls=4"));
+        assert_eq!(jar.header(&uri("http://example.com/")).as_deref(), Some("new=2; aged=3"));
+
+        jar.store(&site, ["new=; Max-Age=0", "aged=5; Max-Age=60"]);
+        assert_eq!(jar.header(&site).as_deref(), Some("tls=4; aged=5"));
+    }
+}
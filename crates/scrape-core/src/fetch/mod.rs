@@ -0,0 +1,301 @@
+//! Fetching HTML documents over HTTP.
+//!
+//! [`fetch`] downloads a page with a blocking request and returns its bytes with the
+//! final URL and content type; [`Soup::from_url`](crate::Soup::from_url) also parses it.
+//! Redirects are followed, gzip and brotli responses are decompressed, and
+//! [`FetchResponse::text`] decodes the body with the `charset` of the `Content-Type`
+//! header, falling back to the detection of [`encoding`](crate::encoding).
+//!
+//! A [`Session`] keeps cookies, default headers, and pooled connections across requests,
+//! for sites that need a login or remember state between pages.
+//!
+//! The types here are always available, so callers can share them across builds;
+//! [`fetch`] itself requires the `fetch` feature, and [`FetchResponse::text`] the
+//! `encoding` feature.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "fetch")]
+//! # fn example() {
+//! use std::time::Duration;
+//!
+//! use scrape_core::{Soup, fetch::FetchOptions};
+//!
+//! let options = FetchOptions { timeout: Duration::from_secs(10), ..FetchOptions::default() }
+//!     .header("Accept-Language", "en");
+//!
+//! let soup = Soup::from_url("https://example.com/", &options).unwrap();
+//! println!("{}", soup.title().unwrap_or_default());
+//! # }
+//! ```
+
+#[cfg(feature = "fetch")]
+mod cookies;
+#[cfg(feature = "fetch")]
+mod session;
+
+use std::time::Duration;
+
+#[cfg(feature = "fetch")]
+pub use session::Session;
+
+/// Options for [`fetch`] and [`Session`].
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// `User-Agent` header.
+    pub user_agent: String,
+    /// Additional request headers, sent in order with every request.
+    pub headers: Vec<(String, String)>,
+    /// Timeout for the whole request, including redirects and reading the body.
+    pub timeout: Duration,
+    /// Maximum number of redirects to follow; `0` disables following redirects.
+    pub max_redirects: u32,
+    /// Maximum size of the decompressed body in bytes.
+    pub max_size: usize,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            user_agent: format!("scrape-rs/{}", env!("CARGO_PKG_VERSION")),
+            headers: Vec::new(),
+            timeout: Duration::from_secs(30),
+            max_redirects: 10,
+            max_size: 10 * 1024 * 1024, // 10MB
+        }
+    }
+}
+
+impl FetchOptions {
+    /// Adds a request header.
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// Error type for fetch operations.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FetchError {
+    /// HTTP error.
+    #[error("HTTP error: {0}")]
+    Http(String),
+    /// The server answered with an error status code.
+    #[error("HTTP error: HTTP {0}")]
+    Status(u16),
+    /// Connection or transport failure.
+    #[error("HTTP error: I/O error: {0}")]
+    Io(String),
+    /// Timeout error.
+    #[error("timeout after {0:?}")]
+    Timeout(Duration),
+    /// Response too large.
+    #[error("response too large: {size} bytes (max: {max})")]
+    TooLarge {
+        /// Actual size, or `max + 1` if the body was cut off while reading.
+        size: usize,
+        /// Maximum allowed size.
+        max: usize,
+    },
+    /// Invalid URL.
+    #[error("invalid URL: {0}")]
+    InvalidUrl(String),
+}
+
+impl FetchError {
+    /// Returns `true` for failures that may succeed when retried: timeouts, connection
+    /// errors, `429 Too Many Requests`, and `5xx` responses.
+    #[must_use]
+    pub const fn is_transient(&self) -> bool {
+        match self {
+            Self::Timeout(_) | Self::Io(_) => true,
+            Self::Status(code) => *code == 429 || *code >= 500,
+            Self::Http(_) | Self::TooLarge { .. } | Self::InvalidUrl(_) => false,
+        }
+    }
+}
+
+/// A downloaded response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchResponse {
+    /// The URL the body was read from, after redirects.
+    pub url: String,
+    /// HTTP status code.
+    pub status: u16,
+    /// `Content-Type` header, if any.
+    pub content_type: Option<String>,
+    /// The decompressed body.
+    pub body: Vec<u8>,
+}
+
+impl FetchResponse {
+    /// Returns the `charset` parameter of the `Content-Type` header.
+    #[must_use]
+    pub fn charset(&self) -> Option<&str> {
+        self.content_type.as_deref()?.split(';').skip(1).find_map(|param| {
+            let (name, value) = param.split_once('=')?;
+            name.trim().eq_ignore_ascii_case("charset").then(|| value.trim().trim_matches('"'))
+        })
+    }
+
+    /// Decodes the body as text.
+    ///
+    /// A byte order mark wins, then the header [`charset`](Self::charset), then the
+    /// detection of [`encoding::decode`](crate::encoding::decode). An unknown charset label
+    /// is ignored.
+    #[cfg(feature = "encoding")]
+    #[must_use]
+    pub fn text(&self) -> std::borrow::Cow<'_, str> {
+        use crate::encoding::{decode, decode_with_label};
+
+        let decoded = self.charset().and_then(|label| decode_with_label(&self.body, label).ok());
+        decoded.unwrap_or_else(|| decode(&self.body)).html
+    }
+}
+
+/// Returns `true` if `input` is an `http://` or `https://` URL rather than a file path.
+#[must_use]
+pub fn is_url(input: &str) -> bool {
+    input.starts_with("http://") || input.starts_with("https://")
+}
+
+/// Downloads `url` with a blocking GET request.
+///
+/// Each call starts from scratch; use a [`Session`] to keep cookies and connections
+/// between requests.
+///
+/// # Errors
+///
+/// Returns [`FetchError`] if the URL is invalid, the request fails or times out, the
+/// server answers with an error status, or the body exceeds
+/// [`FetchOptions::max_size`].
+#[cfg(feature = "fetch")]
+pub fn fetch(url: &str, options: &FetchOptions) -> Result<FetchResponse, FetchError> {
+    Session::new(options.clone()).get(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves canned responses, one connection each, and returns the base URL and the
+    /// received requests, head and body.
+    #[cfg(feature = "fetch")]
+    pub(super) fn serve(
+        responses: &[&'static [u8]],
+    ) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::{
+            io::{BufRead, BufReader, Read, Write},
+            net::TcpListener,
+            thread,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let responses = responses.to_vec();
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                while reader.read_line(&mut request).unwrap() > 2 {}
+                let length = request
+                    .lines()
+                    .find_map(|line| {
+                        line.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .and_then(|len| len.trim().parse().ok())
+                    })
+                    .unwrap_or(0);
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                request.push_str(&String::from_utf8(body).unwrap());
+                stream.write_all(response).unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+        (base, handle)
+    }
+
+    fn response(content_type: Option<&str>, body: &[u8]) -> FetchResponse {
+        FetchResponse {
+            url: "https://example.com/".to_string(),
+            status: 200,
+            content_type: content_type.map(str::to_string),
+            body: body.to_vec(),
+        }
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_charset_and_text() {
+        let latin1 = response(Some("text/html; Charset=\"ISO-8859-1\""), b"<p>Caf\xe9</p>");
+        assert_eq!(latin1.charset(), Some("ISO-8859-1"));
+        assert_eq!(latin1.text(), "<p>Café</p>");
+
+        let sniffed = response(Some("text/html"), b"<meta charset=latin1><p>Caf\xe9</p>");
+        assert_eq!(sniffed.charset(), None);
+        assert!(sniffed.text().ends_with("Café</p>"));
+
+        let bogus = response(Some("text/html; charset=bogus"), "<p>é</p>".as_bytes());
+        assert_eq!(bogus.text(), "<p>é</p>");
+        assert_eq!(response(None, b"").charset(), None);
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_fetch_sends_headers_and_reads_body() {
+        let (base, server) = serve(&[b"HTTP/1.1 200 OK\r\n\
+              Content-Type: text/html; charset=windows-1252\r\n\
+              Content-Length: 11\r\nConnection: close\r\n\r\n<p>Caf\xe9</p>"]);
+        let url = format!("{base}/page");
+        let options = FetchOptions { user_agent: "test-agent/1.0".into(), ..Default::default() }
+            .header("X-Token", "abc");
+
+        let fetched = fetch(&url, &options).unwrap();
+        let head = server.join().unwrap()[0].to_ascii_lowercase();
+        assert!(head.contains("user-agent: test-agent/1.0"), "{head}");
+        assert!(head.contains("x-token: abc"), "{head}");
+        assert_eq!(fetched.status, 200);
+        assert_eq!(fetched.url, url);
+        assert_eq!(fetched.text(), "<p>Café</p>");
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_fetch_errors() {
+        let (base, server) = serve(&[
+            b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\n<p>x</p>\n",
+        ]);
+        assert_eq!(fetch(&base, &FetchOptions::default()), Err(FetchError::Status(404)));
+        let options = FetchOptions { max_size: 4, ..Default::default() };
+        assert_eq!(fetch(&base, &options), Err(FetchError::TooLarge { size: 9, max: 4 }));
+        server.join().unwrap();
+
+        // Nothing listens on port 1, so the connection fails.
+        let err = fetch("http://127.0.0.1:1/", &FetchOptions::default()).unwrap_err();
+        assert!(err.is_transient(), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_fetch_error_is_transient() {
+        assert!(FetchError::Timeout(Duration::from_secs(1)).is_transient());
+        assert!(FetchError::Status(503).is_transient());
+        assert!(!FetchError::Status(404).is_transient());
+        assert!(!FetchError::TooLarge { size: 2, max: 1 }.is_transient());
+        assert_eq!(FetchError::Status(404).to_string(), "HTTP error: HTTP 404");
+    }
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://example.com"));
+        assert!(is_url("http://example.com/a"));
+        assert!(!is_url("page.html"));
+        assert!(!is_url("ftp://example.com"));
+    }
+}
@@ -0,0 +1,331 @@
+//! Sessions that keep cookies and connections between requests.
+
+use std::time::Instant;
+
+use ureq::{
+    Agent, Body, RequestBuilder, ResponseExt,
+    http::{Response, Uri},
+};
+
+use super::{FetchError, FetchOptions, FetchResponse, cookies::CookieJar};
+use crate::{
+    Result, Soup,
+    forms::{Form, FormEnctype, FormMethod, encode_urlencoded},
+    utils::resolve_url,
+};
+
+/// A series of requests sharing cookies, default headers, and connections.
+///
+/// Cookies set by responses, redirects included, are stored and sent back with later
+/// requests to the same site, as a browser would, and connections to a host are pooled
+/// and reused. The [`FetchOptions`] apply to every request. Clones share the cookie
+/// store and the connection pool.
+///
+/// # Examples
+///
+/// Signing in and reading a page behind the login:
+///
+/// ```rust,no_run
+/// use scrape_core::fetch::{FetchOptions, Session};
+///
+/// let session = Session::new(FetchOptions::default());
+/// let login = session.get_soup("https://example.com/login").unwrap();
+/// let form = &login.forms(Some("https://example.com/login"))[0];
+/// session.submit(form, &[("user", "ada"), ("pass", "s3cret")]).unwrap();
+///
+/// let account = session.get_soup("https://example.com/account").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Session {
+    agent: Agent,
+    options: FetchOptions,
+    cookies: CookieJar,
+}
+
+impl Session {
+    /// Creates a session with an empty cookie store.
+    #[must_use]
+    pub fn new(options: FetchOptions) -> Self {
+        // Redirects are followed in `send`, so that cookies set along the way are stored.
+        let agent: Agent = Agent::config_builder().max_redirects(0).build().into();
+        Self { agent, options, cookies: CookieJar::default() }
+    }
+
+    /// Returns the options applied to every request.
+    #[must_use]
+    pub const fn options(&self) -> &FetchOptions {
+        &self.options
+    }
+
+    /// Sends a GET request.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FetchError`] as [`fetch`](super::fetch) does.
+    pub fn get(&self, url: &str) -> std::result::Result<FetchResponse, FetchError> {
+        self.send(url, None)
+    }
+
+    /// Sends a GET request and parses the response as HTML.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Fetch`](crate::Error::Fetch) if the request fails.
+    pub fn get_soup(&self, url: &str) -> Result<Soup> {
+        Ok(Soup::parse(&self.get(url)?.text()))
+    }
+
+    /// Sends `pairs` as an `application/x-www-form-urlencoded` POST body.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FetchError`] as [`fetch`](super::fetch) does.
+    pub fn post_form(
+        &self,
+        url: &str,
+        pairs: &[(String, String)],
+    ) -> std::result::Result<FetchResponse, FetchError> {
+        self.send(url, Some(encode_urlencoded(pairs)))
+    }
+
+    /// Submits `form` as a browser would, with `overrides` for the fields a user would
+    /// fill in; see [`Form::submit_payload`].
+    ///
+    /// `GET` forms send the payload in the query string and `POST` forms as a
+    /// URL-encoded body.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FetchError::InvalidUrl`] if the form has no absolute
+    /// [`url`](Form::url), [`FetchError::Http`] for `dialog` forms and `multipart/form-data`
+    /// encoding, which are not supported, or any error of the request itself.
+    pub fn submit(
+        &self,
+        form: &Form,
+        overrides: &[(&str, &str)],
+    ) -> std::result::Result<FetchResponse, FetchError> {
+        let url = form.url.as_deref().ok_or_else(|| {
+            FetchError::InvalidUrl(format!("form action {:?} is not absolute", form.action))
+        })?;
+        if form.enctype == FormEnctype::Multipart {
+            return Err(FetchError::Http("multipart/form-data forms are not supported".into()));
+        }
+
+        let payload = form.submit_payload(overrides);
+        match form.method {
+            FormMethod::Get => {
+                let base = url.split_once('#').map_or(url, |(base, _)| base);
+                let base = base.split_once('?').map_or(base, |(base, _)| base);
+                self.get(&format!("{base}?{}", encode_urlencoded(&payload)))
+            }
+            FormMethod::Post => self.post_form(url, &payload),
+            FormMethod::Dialog => {
+                Err(FetchError::Http("dialog forms do not send a request".into()))
+            }
+        }
+    }
+
+    /// Forgets all stored cookies.
+    pub fn clear_cookies(&self) {
+        self.cookies.clear();
+    }
+
+    /// Sends a GET request, or a URL-encoded POST with `form`, and follows redirects.
+    ///
+    /// `301`, `302`, and `303` redirects continue with a GET; `307` and `308` repeat the
+    /// request. The timeout covers the whole chain.
+    fn send(
+        &self,
+        url: &str,
+        mut form: Option<String>,
+    ) -> std::result::Result<FetchResponse, FetchError> {
+        let deadline = Instant::now() + self.options.timeout;
+        let mut url = url.to_string();
+        let mut redirects = 0;
+        loop {
+            let uri: Uri = url.parse().map_err(|_| FetchError::InvalidUrl(url.clone()))?;
+            let response = form
+                .as_ref()
+                .map_or_else(
+                    || self.prepare(self.agent.get(&uri), &uri, deadline).call(),
+                    |body| {
+                        self.prepare(self.agent.post(&uri), &uri, deadline)
+                            .header("Content-Type", "application/x-www-form-urlencoded")
+                            .send(body.as_str())
+                    },
+                )
+                .map_err(|e| self.error(e))?;
+
+            let set_cookie = response.headers().get_all("set-cookie");
+            self.cookies.store(&uri, set_cookie.iter().filter_map(|value| value.to_str().ok()));
+
+            let location = header(&response, "location");
+            let Some(location) = location.filter(|_| response.status().is_redirection()) else {
+                return self.read(response);
+            };
+            if redirects == self.options.max_redirects {
+                if redirects == 0 {
+                    return self.read(response);
+                }
+                return Err(FetchError::Http("too many redirects".into()));
+            }
+            url = resolve_url(&url, location)
+                .ok_or_else(|| FetchError::InvalidUrl(location.to_string()))?;
+            if !matches!(response.status().as_u16(), 307 | 308) {
+                form = None;
+            }
+            redirects += 1;
+        }
+    }
+
+    fn prepare<B>(
+        &self,
+        mut request: RequestBuilder<B>,
+        uri: &Uri,
+        deadline: Instant,
+    ) -> RequestBuilder<B> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        request = request.config().timeout_global(Some(timeout)).build();
+        request = request.header("User-Agent", &self.options.user_agent);
+        for (name, value) in &self.options.headers {
+            request = request.header(name, value);
+        }
+        if let Some(cookies) = self.cookies.header(uri) {
+            request = request.header("Cookie", cookies);
+        }
+        request
+    }
+
+    fn read(&self, mut response: Response<Body>) -> std::result::Result<FetchResponse, FetchError> {
+        let max = self.options.max_size;
+
+        if let Some(size) = header(&response, "content-length").and_then(|len| len.parse().ok())
+            && size > max
+        {
+            return Err(FetchError::TooLarge { size, max });
+        }
+        let content_type = header(&response, "content-type").map(str::to_string);
+        let url = response.get_uri().to_string();
+        let status = response.status().as_u16();
+
+        let body = response
+            .body_mut()
+            .with_config()
+            .limit(max as u64)
+            .read_to_vec()
+            .map_err(|e| self.error(e))?;
+
+        Ok(FetchResponse { url, status, content_type, body })
+    }
+
+    fn error(&self, error: ureq::Error) -> FetchError {
+        match error {
+            ureq::Error::StatusCode(code) => FetchError::Status(code),
+            ureq::Error::Timeout(_) => FetchError::Timeout(self.options.timeout),
+            ureq::Error::BadUri(msg) => FetchError::InvalidUrl(msg),
+            ureq::Error::Io(io_err) => FetchError::Io(io_err.to_string()),
+            ureq::Error::HostNotFound => FetchError::Io("host not found".to_string()),
+            ureq::Error::ConnectionFailed => FetchError::Io("connection failed".to_string()),
+            ureq::Error::BodyExceedsLimit(_) => {
+                let max = self.options.max_size;
+                FetchError::TooLarge { size: max.saturating_add(1), max }
+            }
+            other => FetchError::Http(format!("{other}")),
+        }
+    }
+}
+
+fn header<'a>(response: &'a Response<Body>, name: &str) -> Option<&'a str> {
+    response.headers().get(name).and_then(|value| value.to_str().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::tests::serve;
+
+    const SET_COOKIE: &[u8] = b"HTTP/1.1 200 OK\r\nSet-Cookie: sid=42; Path=/\r\n\
+        Content-Type: text/html\r\nContent-Length: 10\r\nConnection: close\r\n\r\n<p>in</p>\n";
+    const OK: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 9\r\n\
+        Connection: close\r\n\r\n<p>ok</p>";
+
+    fn cookie_sent(request: &str) -> bool {
+        request.lines().any(|line| line.eq_ignore_ascii_case("cookie: sid=42"))
+    }
+
+    #[test]
+    fn test_session_keeps_cookies_and_headers() {
+        let (base, server) = serve(&[SET_COOKIE, OK, OK]);
+        let session = Session::new(FetchOptions::default().header("X-Client", "test"));
+
+        session.get(&format!("{base}/login")).unwrap();
+        let soup = session.get_soup(&format!("{base}/account")).unwrap();
+        assert_eq!(soup.find("p").unwrap().unwrap().text(), "ok");
+        session.clear_cookies();
+        session.get(&format!("{base}/account")).unwrap();
+
+        let requests = server.join().unwrap();
+        assert!(!cookie_sent(&requests[0]));
+        assert!(cookie_sent(&requests[1]), "{}", requests[1]);
+        assert!(!cookie_sent(&requests[2]));
+        assert!(requests.iter().all(|r| r.to_ascii_lowercase().contains("x-client: test")));
+    }
+
+    #[test]
+    fn test_session_keeps_cookies_across_redirects() {
+        let (base, server) = serve(&[
+            b"HTTP/1.1 303 See Other\r\nLocation: /account\r\n\
+              Set-Cookie: sid=42; Path=/; HttpOnly\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            OK,
+        ]);
+        let session = Session::new(FetchOptions::default());
+
+        let response =
+            session.post_form(&format!("{base}/login"), &[("user".into(), "ada".into())]);
+        assert_eq!(response.unwrap().url, format!("{base}/account"));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /login "), "{}", requests[0]);
+        assert!(requests[1].starts_with("GET /account "), "{}", requests[1]);
+        assert!(cookie_sent(&requests[1]), "{}", requests[1]);
+    }
+
+    #[test]
+    fn test_session_redirect_limit() {
+        const LOOP: &[u8] = b"HTTP/1.1 302 Found\r\nLocation: /again\r\nContent-Length: 0\r\n\
+              Connection: close\r\n\r\n";
+        let (base, server) = serve(&[LOOP, LOOP, LOOP]);
+        let options = FetchOptions { max_redirects: 1, ..FetchOptions::default() };
+        let err = Session::new(options).get(&base).unwrap_err();
+        assert_eq!(err, FetchError::Http("too many redirects".into()));
+
+        let options = FetchOptions { max_redirects: 0, ..FetchOptions::default() };
+        assert_eq!(Session::new(options).get(&base).unwrap().status, 302);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_submit_form() {
+        let (base, server) = serve(&[OK, OK]);
+        let soup = Soup::parse(
+            r#"<form action="/login" method="post">
+                 <input type="hidden" name="csrf" value="a1b2"><input name="user">
+               </form>
+               <form action="/search?old=1"><input name="q"></form>
+               <form action="/upload" method="post" enctype="multipart/form-data"></form>"#,
+        );
+        let forms = soup.forms(Some(&format!("{base}/")));
+        let session = Session::new(FetchOptions::default());
+
+        session.submit(&forms[0], &[("user", "ada")]).unwrap();
+        session.submit(&forms[1], &[("q", "green tea")]).unwrap();
+        assert!(matches!(session.submit(&forms[2], &[]), Err(FetchError::Http(_))));
+        let relative = &Soup::parse("<form action=/x></form>").forms(None)[0];
+        assert!(matches!(session.submit(relative, &[]), Err(FetchError::InvalidUrl(_))));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /login "), "{}", requests[0]);
+        assert!(requests[0].ends_with("\r\n\r\ncsrf=a1b2&user=ada"), "{}", requests[0]);
+        assert!(requests[1].starts_with("GET /search?q=green+tea "), "{}", requests[1]);
+    }
+}
@@ -0,0 +1,594 @@
+//! HTML forms, their fields, and the data a browser would submit.
+//!
+//! [`forms`] (also available as [`Soup::forms`]) returns every `<form>` in document order
+//! with its `action`, `method`, and `enctype`, and the `<input>`, `<select>`,
+//! `<textarea>`, and `<button>` fields that belong to it, either as descendants or through
+//! a `form="id"` attribute. Fields carry their current value, options, and checked and
+//! disabled state, as a browser would show them before any user input.
+//!
+//! [`Form::submit_payload`] builds the name/value pairs the form would send, with
+//! overrides for the fields a user would fill in, and [`encode_urlencoded`] turns them
+//! into an `application/x-www-form-urlencoded` body or query string.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{
+//!     Soup,
+//!     forms::{FormMethod, encode_urlencoded},
+//! };
+//!
+//! let soup = Soup::parse(
+//!     r#"<form action="/login" method="post">
+//!          <input type="hidden" name="csrf" value="a1b2">
+//!          <input name="user"> <input type="password" name="pass">
+//!          <input type="checkbox" name="remember" checked>
+//!          <button name="go">Sign in</button>
+//!        </form>"#,
+//! );
+//! let form = &soup.forms(Some("https://example.com/account/"))[0];
+//! assert_eq!(form.method, FormMethod::Post);
+//! assert_eq!(form.url.as_deref(), Some("https://example.com/login"));
+//!
+//! let payload = form.submit_payload(&[("user", "ada"), ("pass", "s3cret &c")]);
+//! assert_eq!(encode_urlencoded(&payload), "csrf=a1b2&user=ada&pass=s3cret+%26c&remember=on");
+//! ```
+
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    Soup, Tag, dom::NodeId, extract::links::base_url, query::find_all, utils::resolve_url,
+};
+
+/// A `<form>` element and the fields that belong to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Form {
+    /// The `<form>` element.
+    pub node: NodeId,
+    /// The `id` attribute.
+    pub id: Option<String>,
+    /// The `name` attribute.
+    pub name: Option<String>,
+    /// The `action` attribute as written; empty if missing, meaning the page itself.
+    pub action: String,
+    /// The absolute URL the form submits to, or `None` if `action` is relative and there
+    /// is no absolute base.
+    pub url: Option<String>,
+    /// The HTTP method from the `method` attribute.
+    pub method: FormMethod,
+    /// The body encoding from the `enctype` attribute.
+    pub enctype: FormEnctype,
+    /// The fields of the form in document order.
+    pub fields: Vec<Field>,
+}
+
+/// How a form is submitted, from its `method` attribute.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FormMethod {
+    /// `GET`, the default: the payload goes in the query string.
+    #[default]
+    Get,
+    /// `POST`: the payload goes in the request body.
+    Post,
+    /// `dialog`: the form closes its dialog instead of sending a request.
+    Dialog,
+}
+
+/// How a form's payload is encoded, from its `enctype` attribute.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FormEnctype {
+    /// `application/x-www-form-urlencoded`, the default.
+    #[default]
+    UrlEncoded,
+    /// `multipart/form-data`, needed for file uploads.
+    Multipart,
+    /// `text/plain`.
+    TextPlain,
+}
+
+/// A control of a form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// The control element.
+    pub node: NodeId,
+    /// The `name` attribute; empty if missing.
+    pub name: String,
+    /// What kind of control this is.
+    pub kind: FieldKind,
+    /// The current value: the `value` attribute of inputs and buttons (`on` for
+    /// checkboxes and radio buttons without one), the text of a `<textarea>`, or the value
+    /// of the first selected option of a `<select>`.
+    pub value: String,
+    /// Whether a checkbox or radio button is checked.
+    pub checked: bool,
+    /// Whether the control is disabled, by its own `disabled` attribute or by a disabled
+    /// `<fieldset>` around it.
+    pub disabled: bool,
+    /// The options of a `<select>`, including those in `<optgroup>`s.
+    pub options: Vec<SelectOption>,
+}
+
+/// The kind of a form control.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldKind {
+    /// An `<input>` holding a single value, with its lowercase `type`, such as `text`,
+    /// `hidden`, `password`, or `email`.
+    Input(String),
+    /// `<input type="checkbox">`.
+    Checkbox,
+    /// `<input type="radio">`.
+    Radio,
+    /// `<input type="file">`.
+    File,
+    /// `<input type="submit">` or a `<button>` without a `type`.
+    Submit,
+    /// `<input type="image">`.
+    Image,
+    /// `<input type="reset">` or `<button type="reset">`.
+    Reset,
+    /// `<input type="button">` or `<button type="button">`.
+    Button,
+    /// A `<select>`; `multiple` if more than one option can be selected.
+    Select {
+        /// Whether the `multiple` attribute is present.
+        multiple: bool,
+    },
+    /// A `<textarea>`.
+    Textarea,
+}
+
+/// An `<option>` of a `<select>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectOption {
+    /// The `value` attribute, or the text if there is none.
+    pub value: String,
+    /// The text with whitespace collapsed.
+    pub text: String,
+    /// Whether the option is selected, as a browser would show it: without a `selected`
+    /// option, a single-choice `<select>` selects its first enabled option.
+    pub selected: bool,
+    /// Whether the option or its `<optgroup>` is disabled.
+    pub disabled: bool,
+}
+
+impl Form {
+    /// Returns the first field named `name`.
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Builds the name/value pairs the form would submit, in document order.
+    ///
+    /// Like a browser, this skips disabled and unnamed fields, unchecked checkboxes and
+    /// radio buttons, unselected and disabled options, and buttons, since no button was
+    /// pressed. File inputs submit an empty value.
+    ///
+    /// Each name in `overrides` replaces every pair with that name, at the position of the
+    /// first one; names the form does not submit are appended. Repeat a name to submit
+    /// several values, and add a button's name and value to submit as if it was clicked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse(
+    ///     "<form><select name=size><option>S<option selected>M</select><input name=q \
+    ///      value=tea></form>",
+    /// );
+    /// let form = &soup.forms(None)[0];
+    /// let pairs = |overrides: &[(&str, &str)]| {
+    ///     let payload = form.submit_payload(overrides);
+    ///     payload.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(pairs(&[]), ["size=M", "q=tea"]);
+    /// assert_eq!(
+    ///     pairs(&[("q", "green"), ("q", "black"), ("page", "2")]),
+    ///     ["size=M", "q=green", "q=black", "page=2"]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn submit_payload(&self, overrides: &[(&str, &str)]) -> Vec<(String, String)> {
+        // Override names in order of first appearance, removed once their values are in.
+        let mut pending: Vec<&str> = Vec::new();
+        for &(name, _) in overrides {
+            if !pending.contains(&name) {
+                pending.push(name);
+            }
+        }
+        let push_overrides = |payload: &mut Vec<(String, String)>, name: &str| {
+            payload.extend(
+                overrides
+                    .iter()
+                    .filter(|&&(overridden, _)| overridden == name)
+                    .map(|&(name, value)| (name.to_string(), value.to_string())),
+            );
+        };
+
+        let mut payload = Vec::new();
+        for (name, value) in self.fields.iter().flat_map(Field::entries) {
+            if !overrides.iter().any(|&(overridden, _)| overridden == name) {
+                payload.push((name.to_string(), value.to_string()));
+            } else if let Some(index) = pending.iter().position(|&pending| pending == name) {
+                pending.remove(index);
+                push_overrides(&mut payload, name);
+            }
+        }
+        for name in pending {
+            push_overrides(&mut payload, name);
+        }
+        payload
+    }
+}
+
+impl Field {
+    /// Returns the values of the selected options of a `<select>`.
+    pub fn selected_values(&self) -> impl Iterator<Item = &str> {
+        self.options.iter().filter(|option| option.selected).map(|option| option.value.as_str())
+    }
+
+    /// Returns the pairs this field submits on its own.
+    fn entries(&self) -> Vec<(&str, &str)> {
+        if self.disabled || self.name.is_empty() {
+            return Vec::new();
+        }
+        match self.kind {
+            FieldKind::Input(_) | FieldKind::Textarea => {
+                vec![(self.name.as_str(), self.value.as_str())]
+            }
+            FieldKind::Checkbox | FieldKind::Radio if self.checked => {
+                vec![(self.name.as_str(), self.value.as_str())]
+            }
+            FieldKind::File => vec![(self.name.as_str(), "")],
+            FieldKind::Select { .. } => self
+                .options
+                .iter()
+                .filter(|option| option.selected && !option.disabled)
+                .map(|option| (self.name.as_str(), option.value.as_str()))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl FormMethod {
+    /// Parses a `method` attribute, ignoring case; invalid values mean `GET`.
+    #[must_use]
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "post" => Self::Post,
+            "dialog" => Self::Dialog,
+            _ => Self::Get,
+        }
+    }
+
+    /// Returns the method as used in an HTTP request line: `GET` or `POST`, or `dialog`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+            Self::Dialog => "dialog",
+        }
+    }
+}
+
+impl FormEnctype {
+    /// Parses an `enctype` attribute, ignoring case; invalid values mean
+    /// `application/x-www-form-urlencoded`.
+    #[must_use]
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "multipart/form-data" => Self::Multipart,
+            "text/plain" => Self::TextPlain,
+            _ => Self::UrlEncoded,
+        }
+    }
+
+    /// Returns the MIME type.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::UrlEncoded => "application/x-www-form-urlencoded",
+            Self::Multipart => "multipart/form-data",
+            Self::TextPlain => "text/plain",
+        }
+    }
+}
+
+/// Collects the forms of the document in document order.
+///
+/// Actions are resolved like [links](crate::extract::links): against the document's
+/// `<base href>` and `base_url`.
+#[must_use]
+pub fn forms(soup: &Soup, base_url: Option<&str>) -> Vec<Form> {
+    let doc = soup.document();
+    let base = self::base_url(soup, base_url);
+    let mut forms: Vec<Form> = find_all(doc, "form")
+        .unwrap_or_default()
+        .into_iter()
+        .map(|id| {
+            let tag = Tag::new(doc, id);
+            let action = tag.get("action").unwrap_or_default().trim_ascii().to_string();
+            Form {
+                node: id,
+                id: tag.get("id").map(str::to_string),
+                name: tag.get("name").map(str::to_string),
+                url: resolve_url(base.as_deref().unwrap_or(&action), &action),
+                action,
+                method: FormMethod::parse(tag.get("method").unwrap_or_default()),
+                enctype: FormEnctype::parse(tag.get("enctype").unwrap_or_default()),
+                fields: Vec::new(),
+            }
+        })
+        .collect();
+
+    let by_node: HashMap<NodeId, usize> =
+        forms.iter().enumerate().map(|(index, form)| (form.node, index)).collect();
+    let mut by_id: HashMap<String, usize> = HashMap::new();
+    for (index, form) in forms.iter().enumerate() {
+        if let Some(id) = &form.id {
+            by_id.entry(id.clone()).or_insert(index);
+        }
+    }
+
+    for control in find_all(doc, "input, select, textarea, button").unwrap_or_default() {
+        let tag = Tag::new(doc, control);
+        let owner = tag.get("form").map_or_else(
+            || tag.ancestors().find_map(|ancestor| by_node.get(&ancestor.node_id()).copied()),
+            |form_id| by_id.get(form_id).copied(),
+        );
+        if let Some(owner) = owner {
+            forms[owner].fields.push(field(&tag));
+        }
+    }
+    forms
+}
+
+/// Encodes pairs as `application/x-www-form-urlencoded`, e.g. for a request body or a
+/// `GET` query string.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::forms::encode_urlencoded;
+///
+/// let pairs = [("q".to_string(), "café au lait".to_string()), ("n".into(), "1/2".into())];
+/// assert_eq!(encode_urlencoded(&pairs), "q=caf%C3%A9+au+lait&n=1%2F2");
+/// ```
+#[must_use]
+pub fn encode_urlencoded(pairs: &[(String, String)]) -> String {
+    let mut encoded = String::new();
+    for (index, (name, value)) in pairs.iter().enumerate() {
+        if index > 0 {
+            encoded.push('&');
+        }
+        encode_component(name, &mut encoded);
+        encoded.push('=');
+        encode_component(value, &mut encoded);
+    }
+    encoded
+}
+
+// ==================== Helpers ====================
+
+fn field(tag: &Tag<'_>) -> Field {
+    let name = tag.name().unwrap_or_default().to_ascii_lowercase();
+    let kind = match name.as_str() {
+        "select" => FieldKind::Select { multiple: tag.has_attr("multiple") },
+        "textarea" => FieldKind::Textarea,
+        "button" => match tag.get("type").map(str::to_ascii_lowercase).as_deref() {
+            Some("reset") => FieldKind::Reset,
+            Some("button") => FieldKind::Button,
+            _ => FieldKind::Submit,
+        },
+        _ => input_kind(tag.get("type").unwrap_or_default()),
+    };
+    let options = match kind {
+        FieldKind::Select { multiple } => select_options(tag, multiple),
+        _ => Vec::new(),
+    };
+    let value = match kind {
+        FieldKind::Textarea => tag.text(),
+        FieldKind::Select { .. } => options
+            .iter()
+            .find(|option| option.selected)
+            .map(|o| o.value.clone())
+            .unwrap_or_default(),
+        FieldKind::Checkbox | FieldKind::Radio => tag.get("value").unwrap_or("on").to_string(),
+        _ => tag.get("value").unwrap_or_default().to_string(),
+    };
+    Field {
+        node: tag.node_id(),
+        name: tag.get("name").unwrap_or_default().to_string(),
+        checked: matches!(kind, FieldKind::Checkbox | FieldKind::Radio) && tag.has_attr("checked"),
+        disabled: tag.has_attr("disabled") || in_disabled_fieldset(tag),
+        kind,
+        value,
+        options,
+    }
+}
+
+fn input_kind(input_type: &str) -> FieldKind {
+    let input_type = input_type.trim().to_ascii_lowercase();
+    match input_type.as_str() {
+        "checkbox" => FieldKind::Checkbox,
+        "radio" => FieldKind::Radio,
+        "file" => FieldKind::File,
+        "submit" => FieldKind::Submit,
+        "image" => FieldKind::Image,
+        "reset" => FieldKind::Reset,
+        "button" => FieldKind::Button,
+        "" => FieldKind::Input("text".to_string()),
+        _ => FieldKind::Input(input_type),
+    }
+}
+
+fn select_options(select: &Tag<'_>, multiple: bool) -> Vec<SelectOption> {
+    let mut options: Vec<SelectOption> = select
+        .descendants()
+        .filter(|tag| tag.name().is_some_and(|name| name.eq_ignore_ascii_case("option")))
+        .map(|option| {
+            let text = option.text().split_whitespace().collect::<Vec<_>>().join(" ");
+            SelectOption {
+                value: option.get("value").map_or_else(|| text.clone(), str::to_string),
+                text,
+                selected: option.has_attr("selected"),
+                disabled: option.has_attr("disabled")
+                    || option.parent().is_some_and(|parent| {
+                        parent.name().is_some_and(|name| name.eq_ignore_ascii_case("optgroup"))
+                            && parent.has_attr("disabled")
+                    }),
+            }
+        })
+        .collect();
+    if !multiple {
+        // A single-choice select shows its last selected option, or its first enabled one.
+        let shown = options
+            .iter()
+            .rposition(|option| option.selected)
+            .or_else(|| options.iter().position(|option| !option.disabled));
+        for (index, option) in options.iter_mut().enumerate() {
+            option.selected = Some(index) == shown;
+        }
+    }
+    options
+}
+
+/// Returns `true` if a `<fieldset disabled>` contains `tag` outside its first `<legend>`.
+fn in_disabled_fieldset(tag: &Tag<'_>) -> bool {
+    let mut child = *tag;
+    for ancestor in tag.ancestors() {
+        if ancestor.name().is_some_and(|name| name.eq_ignore_ascii_case("fieldset"))
+            && ancestor.has_attr("disabled")
+        {
+            let first_legend = ancestor
+                .children()
+                .find(|c| c.name().is_some_and(|name| name.eq_ignore_ascii_case("legend")));
+            if first_legend.is_none_or(|legend| legend.node_id() != child.node_id()) {
+                return true;
+            }
+        }
+        child = ancestor;
+    }
+    false
+}
+
+fn encode_component(s: &str, out: &mut String) {
+    for byte in s.bytes() {
+        match byte {
+            b' ' => out.push('+'),
+            b'*' | b'-' | b'.' | b'_' | b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' => {
+                out.push(char::from(byte));
+            }
+            _ => {
+                let _ = write!(out, "%{byte:02X}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_form_attributes() {
+        let soup = Soup::parse(
+            "<base href='https://example.com/app/'><form id=search name=s method=GET><input \
+             name=q></form><form action=' /upload ' method=PoSt \
+             enctype=MULTIPART/FORM-DATA></form><form method=put enctype=bogus></form>",
+        );
+        let forms = soup.forms(None);
+        assert_eq!(forms.len(), 3);
+        assert_eq!(forms[0].id.as_deref(), Some("search"));
+        assert_eq!(forms[0].name.as_deref(), Some("s"));
+        assert_eq!(forms[0].url.as_deref(), Some("https://example.com/app/"));
+        assert_eq!(forms[1].action, "/upload");
+        assert_eq!(forms[1].url.as_deref(), Some("https://example.com/upload"));
+        assert_eq!(forms[1].method, FormMethod::Post);
+        assert_eq!(forms[1].enctype.as_str(), "multipart/form-data");
+        assert_eq!(forms[2].method.as_str(), "GET");
+        assert_eq!(forms[2].enctype, FormEnctype::UrlEncoded);
+        assert_eq!(Soup::parse("<form></form>").forms(None)[0].url, None);
+    }
+
+    #[test]
+    fn test_fields() {
+        let soup = Soup::parse(
+            "<form id=f><input name=a><input type=EMAIL name=b value=x@y.z><input type=checkbox \
+             name=c><input type=radio name=d value=1 checked><textarea \
+             name=e>Hello\nthere</textarea><select name=f multiple><option value=1 \
+             selected>One<optgroup disabled><option selected>  Two  2 \
+             </option></optgroup></select><button>Go</button><button \
+             type=reset>Clear</button></form><input name=g form=f><input name=h form=missing>",
+        );
+        let form = &soup.forms(None)[0];
+        let kinds: Vec<_> = form.fields.iter().map(|field| field.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            [
+                FieldKind::Input("text".into()),
+                FieldKind::Input("email".into()),
+                FieldKind::Checkbox,
+                FieldKind::Radio,
+                FieldKind::Textarea,
+                FieldKind::Select { multiple: true },
+                FieldKind::Submit,
+                FieldKind::Reset,
+                FieldKind::Input("text".into()),
+            ]
+        );
+        assert_eq!(form.field("b").unwrap().value, "x@y.z");
+        assert_eq!(form.field("c").unwrap().value, "on");
+        assert!(!form.field("c").unwrap().checked);
+        assert!(form.field("d").unwrap().checked);
+        assert_eq!(form.field("e").unwrap().value, "Hello\nthere");
+
+        let select = form.field("f").unwrap();
+        assert_eq!(select.value, "1");
+        assert_eq!(select.selected_values().collect::<Vec<_>>(), ["1", "Two 2"]);
+        assert_eq!(select.options[1].text, "Two 2");
+        assert!(select.options[1].disabled);
+        assert!(form.field("g").is_some());
+        assert!(form.field("h").is_none());
+    }
+
+    #[test]
+    fn test_single_select_default() {
+        let soup = Soup::parse(
+            "<form><select name=a><option disabled>-<option>x<option>y</select><select \
+             name=b><option selected>x<option selected>y</select><select name=c></select></form>",
+        );
+        let form = &soup.forms(None)[0];
+        assert_eq!(form.field("a").unwrap().value, "x");
+        assert_eq!(form.field("b").unwrap().value, "y");
+        assert_eq!(form.field("c").unwrap().value, "");
+        let expected = [("a", "x"), ("b", "y")].map(|(k, v)| (k.to_string(), v.to_string()));
+        assert_eq!(form.submit_payload(&[]), expected);
+    }
+
+    #[test]
+    fn test_disabled_fields() {
+        let soup = Soup::parse(
+            "<form><input name=a disabled><fieldset disabled><legend><input \
+             name=b></legend><input name=c></fieldset><input type=file name=d><input type=image \
+             name=e><input name=f></form>",
+        );
+        let form = &soup.forms(None)[0];
+        assert!(form.field("a").unwrap().disabled);
+        assert!(!form.field("b").unwrap().disabled);
+        assert!(form.field("c").unwrap().disabled);
+        let payload = form.submit_payload(&[("a", "forced")]);
+        let pairs: Vec<_> = payload.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        assert_eq!(pairs, ["b=", "d=", "f=", "a=forced"]);
+    }
+
+    #[test]
+    fn test_encode_urlencoded() {
+        let pairs = [("a b".to_string(), "x=1&y*~".to_string()), (String::new(), "é".into())];
+        assert_eq!(encode_urlencoded(&pairs), "a+b=x%3D1%26y*%7E&=%C3%A9");
+        assert_eq!(encode_urlencoded(&[]), "");
+    }
+}
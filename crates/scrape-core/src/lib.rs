@@ -77,50 +77,83 @@
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
 
+pub mod archive;
+pub mod article;
+#[cfg(feature = "bench-corpus")]
+pub mod bench_corpus;
+pub mod cache;
+pub mod canonical;
+pub mod convert;
+pub mod diff;
 mod dom;
+#[cfg(feature = "encoding")]
+pub mod encoding;
 mod error;
+pub mod extract;
+pub mod feed;
+pub mod fetch;
+pub mod forms;
+pub mod lint;
+#[cfg(feature = "serde")]
+pub mod metadata;
 #[cfg(feature = "parallel")]
 pub mod parallel;
 mod parser;
+pub mod pipeline;
 pub mod query;
+pub mod robots;
+#[cfg(feature = "parallel")]
+pub mod runtime;
+mod selection;
 pub mod serialize;
 #[cfg(feature = "simd")]
 pub mod simd;
+pub mod sitemap;
 mod soup;
 #[cfg(feature = "streaming")]
 pub mod streaming;
 mod tag;
 pub mod utils;
+pub mod variants;
+mod xml;
 
 // Error types
 // DOM types
 pub use dom::{
-    AncestorsIter, Building, ChildrenIter, CommentMarker, DescendantsIter, Document, DocumentImpl,
-    DocumentIndex, DocumentState, ElementAncestorsIter, ElementChildrenIter,
-    ElementDescendantsIter, ElementMarker, ElementNextSiblingsIter, ElementPrevSiblingsIter,
-    ElementSiblingsIter, MutableState, NextSiblingsIter, Node, NodeId, NodeKind, NodeType,
-    PrevSiblingsIter, Queryable, QueryableState, Sealed, SiblingsIter, TagId, TextMarker,
+    AncestorsIter, Atom, Attributes, AttributesIntoIter, AttributesIter, Building, ChildrenIter,
+    CommentMarker, DescendantsIter, Doctype, Document, DocumentImpl, DocumentIndex, DocumentState,
+    ElementAncestorsIter, ElementChildrenIter, ElementDescendantsIter, ElementMarker,
+    ElementNextSiblingsIter, ElementPrevSiblingsIter, ElementSiblingsIter, FormatOptions,
+    MutableState, Namespace, NextSiblingsIter, Node, NodeId, NodeKind, NodeType, PrevSiblingsIter,
+    Queryable, QueryableState, Sealed, SiblingsIter, StringTable, TagId, TextContent, TextMarker,
 };
 pub use error::{Error, Result, SourcePosition, SourceSpan, SpanContext};
 // Parser types
 pub use parser::{
-    Html5everParser, ParseConfig, ParseError, ParseResult, ParseResultWithWarnings, ParseWarning,
-    Parser, WarningSeverity,
+    Html5everParser, IncrementalParser, ParseConfig, ParseError, ParseResult,
+    ParseResultWithWarnings, ParseWarning, Parser, WarningSeverity, XmlParser,
 };
 // Query types
 pub use query::{
-    CompiledSelector, Filter, OptimizationHint, QueryError, QueryResult, SelectorExplanation,
-    Specificity, TextNodesIter, compile_selector, explain, explain_with_document,
+    CompiledSelector, CompoundStats, DocumentAnalysis, Filter, FilterText, IndexUsage,
+    OptimizationHint, QueryError, QueryResult, RewriteSuggestion, SelectorExplanation, Specificity,
+    TextIndex, TextNodesIter, TextPattern, XPath, XPathNode, XPathValue, compile_selector, explain,
+    explain_with_document,
 };
+pub use selection::Selection;
 // Serialization utilities
-pub use serialize::{HtmlSerializer, collect_text, serialize_inner_html, serialize_node};
+pub use serialize::{
+    HtmlSerializer, QuoteStyle, SerializeOptions, TextOptions, collect_text, collect_text_with,
+    collect_visible_text, serialize_inner_html, serialize_inner_html_with, serialize_node,
+    serialize_node_with,
+};
 // High-level API
 pub use soup::{Soup, SoupConfig};
 #[cfg(feature = "streaming")]
 pub use streaming::{
-    ContentType, HtmlRewriter, RewriterConfig, StreamingConfig, StreamingElement, StreamingSoup,
-    StreamingStats, state,
+    ContentType, HtmlRewriter, OutputSink, RewriterConfig, RewriterSession, StreamingConfig,
+    StreamingElement, StreamingSoup, StreamingStats, WriterSink, state,
 };
-pub use tag::Tag;
+pub use tag::{Tag, TagMut};
 // HTML utilities
 pub use utils::{escape_attr, escape_text, is_void_element};
@@ -0,0 +1,355 @@
+//! Document validation.
+//!
+//! [`Soup::validate`] checks a parsed document for common authoring mistakes: `id`
+//! values used more than once, elements nested where HTML does not allow them, obsolete
+//! tags, and images without `alt` text. [`lint`] parses HTML source and adds the
+//! problems only the parser sees, such as elements left unclosed and the `<p>`
+//! elements it closes early because a block element was put inside them.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{Soup, lint::LintKind};
+//!
+//! let soup = Soup::parse("<div id=\"a\"><center>Hi</center></div><img id=\"a\" src=\"x.png\">");
+//! let kinds: Vec<_> = soup.validate().iter().map(|warning| warning.kind).collect();
+//! assert_eq!(kinds, [LintKind::DeprecatedTag, LintKind::DuplicateId, LintKind::MissingAlt]);
+//! ```
+
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    fmt,
+};
+
+use crate::{Result, Soup, Tag, dom::NodeId, parser::ParseWarning};
+
+/// The kind of problem a [`LintWarning`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    /// An `id` already used by an earlier element.
+    DuplicateId,
+    /// An element inside a parent that does not allow it, such as a `<div>` in a `<p>`.
+    InvalidNesting,
+    /// An obsolete element, such as `<center>` or `<font>`.
+    DeprecatedTag,
+    /// An image without an `alt` attribute.
+    MissingAlt,
+    /// An element the source never closed.
+    UnclosedElement,
+}
+
+impl LintKind {
+    /// Returns the kebab-case name of the kind, such as `duplicate-id`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::DuplicateId => "duplicate-id",
+            Self::InvalidNesting => "invalid-nesting",
+            Self::DeprecatedTag => "deprecated-tag",
+            Self::MissingAlt => "missing-alt",
+            Self::UnclosedElement => "unclosed-element",
+        }
+    }
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A problem found by [`Soup::validate`] or [`lint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    /// What kind of problem this is.
+    pub kind: LintKind,
+    /// Description of the problem.
+    pub message: String,
+    /// The offending element, for problems found in the document tree.
+    pub node: Option<NodeId>,
+    /// Path of the element as returned by [`Tag::css_path`].
+    pub path: Option<String>,
+    /// Source line, for problems reported by the parser.
+    pub line: Option<usize>,
+}
+
+impl LintWarning {
+    fn at(tag: Tag<'_>, kind: LintKind, message: String) -> Self {
+        Self { kind, message, node: Some(tag.node_id()), path: Some(tag.css_path()), line: None }
+    }
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.message)?;
+        if let Some(path) = &self.path {
+            write!(f, " at {path}")?;
+        }
+        if let Some(line) = self.line {
+            write!(f, " at line {line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Obsolete elements, from the HTML standard's list of non-conforming features.
+const DEPRECATED: &[&str] = &[
+    "acronym",
+    "applet",
+    "basefont",
+    "bgsound",
+    "big",
+    "blink",
+    "center",
+    "dir",
+    "font",
+    "frame",
+    "frameset",
+    "isindex",
+    "keygen",
+    "listing",
+    "marquee",
+    "menuitem",
+    "multicol",
+    "nextid",
+    "nobr",
+    "noembed",
+    "noframes",
+    "plaintext",
+    "spacer",
+    "strike",
+    "tt",
+    "xmp",
+];
+
+/// Elements that may only contain phrasing content.
+const PHRASING_ONLY: &[&str] = &[
+    "p", "h1", "h2", "h3", "h4", "h5", "h6", "span", "b", "i", "em", "strong", "small", "s", "u",
+    "q", "cite", "code", "abbr", "dfn", "kbd", "samp", "var", "sub", "sup", "mark", "time", "data",
+    "label", "button", "pre", "legend",
+];
+
+/// Elements that are flow content but not phrasing content.
+const BLOCKS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Parser errors that mean an element was nested where it is not allowed, with the
+/// message reported for each.
+const PARSER_NESTING: &[(&str, &str)] = &[
+    ("No <p> tag to close", "</p> without an open <p>; a block element closed the <p> early"),
+    ("nested heading tags", "heading inside another heading"),
+    ("nested forms", "<form> inside another <form>"),
+    ("nested buttons", "<button> inside another <button>"),
+    ("Nested <nobr>", "<nobr> inside another <nobr>"),
+    ("nested options", "<option> inside another <option>"),
+];
+
+/// Checks a parsed document; see [`Soup::validate`].
+#[must_use]
+pub fn validate(soup: &Soup) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let Some(root) = soup.root() else { return warnings };
+    let mut ids: HashMap<String, NodeId> = HashMap::new();
+
+    for tag in std::iter::once(root).chain(root.descendants()) {
+        let Some(name) = tag.name() else { continue };
+
+        if let Some(id) = tag.get("id").filter(|id| !id.is_empty()) {
+            match ids.entry(id.to_string()) {
+                Entry::Occupied(first) => {
+                    let first = Tag::new(soup.document(), *first.get()).css_path();
+                    let message = format!("id \"{id}\" is already used by {first}");
+                    warnings.push(LintWarning::at(tag, LintKind::DuplicateId, message));
+                }
+                Entry::Vacant(slot) => {
+                    slot.insert(tag.node_id());
+                }
+            }
+        }
+
+        if DEPRECATED.contains(&name) {
+            let message = format!("<{name}> is obsolete");
+            warnings.push(LintWarning::at(tag, LintKind::DeprecatedTag, message));
+        }
+
+        let needs_alt = match name {
+            "img" => true,
+            "area" => tag.has_attr("href"),
+            "input" => tag.get("type").is_some_and(|t| t.eq_ignore_ascii_case("image")),
+            _ => false,
+        };
+        if needs_alt && !tag.has_attr("alt") {
+            let message = format!("<{name}> has no alt attribute");
+            warnings.push(LintWarning::at(tag, LintKind::MissingAlt, message));
+        }
+
+        if let Some(parent) = tag.parent()
+            && let Some(message) = nesting_error(parent, name)
+        {
+            warnings.push(LintWarning::at(tag, LintKind::InvalidNesting, message));
+        }
+    }
+    warnings
+}
+
+/// Returns why the element `name` may not be a child of `parent`, if it may not.
+fn nesting_error(parent: Tag<'_>, name: &str) -> Option<String> {
+    let parent_name = parent.name()?;
+    if PHRASING_ONLY.contains(&parent_name) && BLOCKS.contains(&name) {
+        return Some(format!(
+            "<{name}> inside <{parent_name}>, which only allows phrasing content"
+        ));
+    }
+    if matches!(name, "a" | "button")
+        && let Some(outer) = std::iter::once(parent)
+            .chain(parent.ancestors())
+            .find(|ancestor| matches!(ancestor.name(), Some("a" | "button")))
+    {
+        let outer = outer.name().unwrap_or_default();
+        return Some(format!("interactive <{name}> inside <{outer}>"));
+    }
+    if matches!(parent_name, "ul" | "ol") && !matches!(name, "li" | "script" | "template") {
+        return Some(format!("<{name}> directly inside <{parent_name}> instead of an <li>"));
+    }
+    None
+}
+
+/// Parses `html` and checks it as [`Soup::validate`] does, adding the problems the
+/// parser reports: elements left unclosed and elements it had to move out of a parent
+/// that does not allow them.
+///
+/// Problems from the parser come first, in source order, with their
+/// [`line`](LintWarning::line); problems in the tree follow in document order.
+///
+/// # Errors
+///
+/// Returns an error if the document exceeds the maximum nesting depth.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::lint::{LintKind, lint};
+///
+/// let warnings = lint("<!DOCTYPE html>\n<div>\n<p>Tea<div>Green</div></p>\n<span>Cup").unwrap();
+/// assert_eq!(warnings[0].kind, LintKind::InvalidNesting);
+/// assert_eq!(warnings[0].line, Some(3));
+/// assert_eq!(warnings[1].kind, LintKind::UnclosedElement);
+/// ```
+pub fn lint(html: &str) -> Result<Vec<LintWarning>> {
+    let (soup, parse_warnings) = Soup::parse_checked(html)?;
+    let mut warnings: Vec<_> = parse_warnings.iter().filter_map(parser_warning).collect();
+    warnings.extend(validate(&soup));
+    Ok(warnings)
+}
+
+/// Converts a parse error about nesting or unclosed elements into a lint warning.
+fn parser_warning(warning: &ParseWarning) -> Option<LintWarning> {
+    let (kind, message) = if let Some((_, message)) =
+        PARSER_NESTING.iter().find(|(error, _)| warning.message == *error)
+    {
+        (LintKind::InvalidNesting, (*message).to_string())
+    } else {
+        (LintKind::UnclosedElement, unclosed_message(&warning.message)?)
+    };
+    let line = warning.span.map(|span| span.start.line);
+    Some(LintWarning { kind, message, node: None, path: None, line })
+}
+
+/// Describes a parser error for an element that was still open when an ancestor, or the
+/// body, ended.
+///
+/// The parser names elements by their debug representation, such as
+/// `Atom('div' type=static)` or `{http://www.w3.org/1999/xhtml}:div`.
+fn unclosed_message(error: &str) -> Option<String> {
+    if error == "Formatting element not current node" {
+        return Some("formatting element closed while an element inside it was still open".into());
+    }
+    if let Some(name) = error.strip_prefix("Unexpected open element while closing ") {
+        let name = name.split('\'').nth(1).unwrap_or(name);
+        return Some(format!("<{name}> closed while an element inside it was still open"));
+    }
+    let name = error.strip_prefix("Unexpected open tag ")?.trim_end_matches(" at end of body");
+    let name = name.rsplit_once(':').map_or(name, |(_, local)| local);
+    Some(format!("<{name}> still open at the end of the body"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(warnings: &[LintWarning]) -> Vec<LintKind> {
+        warnings.iter().map(|warning| warning.kind).collect()
+    }
+
+    #[test]
+    fn test_validate() {
+        let soup = Soup::parse(
+            "<p id=\"x\">A</p><section id=\"x\"><ul><div>B</div></ul></section><a \
+             href=\"/\"><button>C</button></a><map><area href=\"/\"><area></map><input \
+             type=\"image\" alt=\"Go\"><font>D</font><span><div>E</div></span>",
+        );
+        let warnings = soup.validate();
+
+        assert_eq!(
+            kinds(&warnings),
+            [
+                LintKind::DuplicateId,
+                LintKind::InvalidNesting,
+                LintKind::InvalidNesting,
+                LintKind::MissingAlt,
+                LintKind::DeprecatedTag,
+                LintKind::InvalidNesting,
+            ]
+        );
+        assert_eq!(warnings[0].message, "id \"x\" is already used by html > body > p");
+        assert_eq!(warnings[0].path.as_deref(), Some("html > body > section"));
+        assert_eq!(warnings[2].message, "interactive <button> inside <a>");
+        assert_eq!(warnings[3].path.as_deref(), Some("html > body > map > area:nth-of-type(1)"));
+        assert!(Soup::parse("<p>Fine <img src=\"a.png\" alt=\"\"></p>").validate().is_empty());
+    }
+
+    #[test]
+    fn test_lint() {
+        let warnings =
+            lint("<!DOCTYPE html>\n<p>A<table></table></p>\n<img>\n<div><b>B</div>").unwrap();
+
+        assert_eq!(
+            kinds(&warnings),
+            [LintKind::InvalidNesting, LintKind::UnclosedElement, LintKind::MissingAlt]
+        );
+        assert_eq!(warnings[0].line, Some(2));
+        assert_eq!(warnings[1].line, Some(4));
+        assert_eq!(warnings[1].message, "<div> closed while an element inside it was still open");
+        let warnings = lint("<!DOCTYPE html><div><span>A").unwrap();
+        assert_eq!(warnings[0].message, "<div> still open at the end of the body");
+        assert!(lint("<!DOCTYPE html><p>Fine</p>").unwrap().is_empty());
+    }
+}
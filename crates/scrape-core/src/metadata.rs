@@ -0,0 +1,291 @@
+//! Page metadata: meta tags, Open Graph, Twitter cards, favicons, and JSON-LD.
+//!
+//! [`metadata`] (also available as [`Soup::metadata`]) collects what scrapers usually
+//! assemble from a dozen selectors into one [`Metadata`] value:
+//!
+//! - `<title>`, `<html lang>`, and `<meta charset>`
+//! - `<meta name>` and `<meta http-equiv>` tags, keyed by lowercase name
+//! - Open Graph properties (`<meta property="og:...">` and other `property` tags)
+//! - Twitter card fields (`twitter:*` in either `name` or `property`)
+//! - the canonical URL and favicons from `<link>` tags
+//! - `<script type="application/ld+json">` blocks parsed as [`serde_json::Value`]
+//!
+//! URLs are kept as written; resolve them with [`resolve_url`](crate::utils::resolve_url)
+//! and [`base_url`](crate::extract::links::base_url) if needed.
+//!
+//! Requires the `serde` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::Soup;
+//!
+//! let soup = Soup::parse(
+//!     r#"<html lang="en"><head>
+//!        <title>Tea  Guide</title>
+//!        <meta name="description" content="All about tea">
+//!        <meta property="og:image" content="/a.png">
+//!        <meta property="og:image" content="/b.png">
+//!        <meta name="twitter:card" content="summary">
+//!        <link rel="canonical" href="https://example.com/tea">
+//!        <script type="application/ld+json">{"@type": "Article"}</script>
+//!        </head></html>"#,
+//! );
+//! let meta = soup.metadata();
+//! assert_eq!(meta.title.as_deref(), Some("Tea Guide"));
+//! assert_eq!(meta.description.as_deref(), Some("All about tea"));
+//! assert_eq!(meta.open_graph["og:image"], ["/a.png", "/b.png"]);
+//! assert_eq!(meta.twitter["twitter:card"], "summary");
+//! assert_eq!(meta.canonical.as_deref(), Some("https://example.com/tea"));
+//! assert_eq!(meta.json_ld[0]["@type"], "Article");
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::{Soup, Tag, query::find_all, utils::split_attr_list};
+
+/// Metadata collected from a document's `<head>` and JSON-LD blocks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    /// Text of the first `<title>`, with whitespace collapsed.
+    pub title: Option<String>,
+    /// Content of `<meta name="description">`.
+    pub description: Option<String>,
+    /// The `lang` attribute of `<html>`.
+    pub lang: Option<String>,
+    /// The `charset` of the first `<meta charset>`.
+    pub charset: Option<String>,
+    /// The `href` of the first `<link rel="canonical">`.
+    pub canonical: Option<String>,
+    /// `<meta name>` and `<meta http-equiv>` content by lowercase name; the first tag
+    /// wins. Twitter card fields are in [`twitter`](Self::twitter) instead.
+    pub meta: BTreeMap<String, String>,
+    /// `<meta property>` content by lowercase property, such as `og:title` or
+    /// `article:author`. Repeated properties keep every value in document order.
+    pub open_graph: BTreeMap<String, Vec<String>>,
+    /// Twitter card fields by lowercase name, such as `twitter:card`; the first tag wins.
+    pub twitter: BTreeMap<String, String>,
+    /// Icons from `<link>` tags in document order.
+    pub favicons: Vec<Icon>,
+    /// Parsed JSON-LD blocks in document order. Blocks that are not valid JSON are
+    /// skipped.
+    pub json_ld: Vec<serde_json::Value>,
+}
+
+/// An icon declared with `<link rel="icon">`, `apple-touch-icon`, or `mask-icon`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Icon {
+    /// The `href` attribute as written.
+    pub href: String,
+    /// Lowercase tokens of the `rel` attribute, joined by single spaces.
+    pub rel: String,
+    /// The `sizes` attribute, such as `32x32` or `any`.
+    pub sizes: Option<String>,
+    /// The `type` attribute, such as `image/png`.
+    pub mime: Option<String>,
+}
+
+impl Metadata {
+    /// Returns `true` if no metadata was found.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl From<Metadata> for serde_json::Value {
+    /// Converts to a JSON object with the field names as keys and `null` for missing
+    /// values.
+    fn from(metadata: Metadata) -> Self {
+        serde_json::json!({
+            "title": metadata.title,
+            "description": metadata.description,
+            "lang": metadata.lang,
+            "charset": metadata.charset,
+            "canonical": metadata.canonical,
+            "meta": metadata.meta,
+            "open_graph": metadata.open_graph,
+            "twitter": metadata.twitter,
+            "favicons": metadata.favicons.into_iter().map(Self::from).collect::<Vec<_>>(),
+            "json_ld": metadata.json_ld,
+        })
+    }
+}
+
+impl From<Icon> for serde_json::Value {
+    fn from(icon: Icon) -> Self {
+        serde_json::json!({
+            "href": icon.href,
+            "rel": icon.rel,
+            "sizes": icon.sizes,
+            "type": icon.mime,
+        })
+    }
+}
+
+/// Collects the metadata of a document.
+///
+/// See the [module documentation](self) for what is collected.
+#[must_use]
+pub fn metadata(soup: &Soup) -> Metadata {
+    let mut metadata = Metadata {
+        title: tags(soup, "title").next().map(|tag| collapse(&tag.text())),
+        lang: tags(soup, "html").find_map(|tag| tag.get("lang").map(str::to_string)),
+        ..Metadata::default()
+    };
+
+    for tag in tags(soup, "meta") {
+        if metadata.charset.is_none()
+            && let Some(charset) = tag.get("charset")
+        {
+            metadata.charset = Some(charset.trim().to_string());
+        }
+        let Some(content) = tag.get("content") else { continue };
+        let content = content.trim().to_string();
+
+        let name = tag.get("name").or_else(|| tag.get("http-equiv")).map(str::to_ascii_lowercase);
+        let property = tag.get("property").map(str::to_ascii_lowercase);
+        if let Some(key) = [&name, &property].into_iter().flatten().find(|k| is_twitter(k)) {
+            metadata.twitter.entry(key.clone()).or_insert(content);
+            continue;
+        }
+        if let Some(property) = property {
+            metadata.open_graph.entry(property).or_default().push(content.clone());
+        }
+        if let Some(name) = name {
+            metadata.meta.entry(name).or_insert(content);
+        }
+    }
+    metadata.description = metadata.meta.get("description").cloned();
+
+    for tag in tags(soup, "link[href]") {
+        let Some(href) = tag.get("href") else { continue };
+        let rel: Vec<_> = tag.get("rel").map_or_else(Vec::new, |rel| {
+            split_attr_list(rel).map(str::to_ascii_lowercase).collect()
+        });
+        if metadata.canonical.is_none() && rel.iter().any(|t| t == "canonical") {
+            metadata.canonical = Some(href.trim().to_string());
+        }
+        if rel.iter().any(|t| is_icon(t)) {
+            metadata.favicons.push(Icon {
+                href: href.trim().to_string(),
+                rel: rel.join(" "),
+                sizes: tag.get("sizes").map(str::to_string),
+                mime: tag.get("type").map(str::to_string),
+            });
+        }
+    }
+
+    metadata.json_ld = tags(soup, "script[type]")
+        .filter(|tag| tag.get("type").is_some_and(is_json_ld))
+        .filter_map(|tag| serde_json::from_str(tag.text().trim()).ok())
+        .collect();
+
+    metadata
+}
+
+// ==================== Helpers ====================
+
+fn tags<'a>(soup: &'a Soup, selector: &str) -> impl Iterator<Item = Tag<'a>> {
+    let doc = soup.document();
+    find_all(doc, selector).unwrap_or_default().into_iter().map(move |id| Tag::new(doc, id))
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn is_twitter(key: &str) -> bool {
+    key.starts_with("twitter:")
+}
+
+fn is_icon(rel: &str) -> bool {
+    rel == "icon" || rel.starts_with("apple-touch-icon") || rel == "mask-icon"
+}
+
+/// Matches `application/ld+json`, ignoring case, whitespace, and parameters.
+fn is_json_ld(mime: &str) -> bool {
+    mime.split(';').next().is_some_and(|m| m.trim().eq_ignore_ascii_case("application/ld+json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meta_tags() {
+        let soup = Soup::parse(
+            "<head><meta charset=\" utf-8 \"><meta charset=\"latin1\"><meta name=\"Description\" \
+             content=\" First \"><meta name=\"description\" content=\"Second\"><meta \
+             http-equiv=\"refresh\" content=\"30\"><meta name=\"keywords\"></head>",
+        );
+        let meta = metadata(&soup);
+        assert_eq!(meta.charset.as_deref(), Some("utf-8"));
+        assert_eq!(meta.description.as_deref(), Some("First"));
+        assert_eq!(meta.meta["refresh"], "30");
+        assert!(!meta.meta.contains_key("keywords"));
+    }
+
+    #[test]
+    fn test_open_graph_and_twitter() {
+        let soup = Soup::parse(
+            "<meta property=\"og:title\" content=\"Tea\"><meta property=\"OG:Image\" \
+             content=\"a.png\"><meta property=\"og:image\" content=\"b.png\"><meta \
+             property=\"article:author\" content=\"Ann\"><meta property=\"twitter:site\" \
+             content=\"@tea\"><meta name=\"twitter:card\" content=\"summary\"><meta \
+             name=\"twitter:card\" content=\"player\">",
+        );
+        let meta = metadata(&soup);
+        assert_eq!(meta.open_graph["og:title"], ["Tea"]);
+        assert_eq!(meta.open_graph["og:image"], ["a.png", "b.png"]);
+        assert_eq!(meta.open_graph["article:author"], ["Ann"]);
+        assert!(!meta.open_graph.contains_key("twitter:site"));
+        assert_eq!(meta.twitter["twitter:site"], "@tea");
+        assert_eq!(meta.twitter["twitter:card"], "summary");
+        assert!(meta.meta.is_empty());
+    }
+
+    #[test]
+    fn test_links() {
+        let soup = Soup::parse(
+            "<link rel=\"Shortcut Icon\" href=\"/favicon.ico\">\
+             <link rel=\"apple-touch-icon\" sizes=\"180x180\" type=\"image/png\" href=\"t.png\">\
+             <link rel=\"stylesheet\" href=\"a.css\">\
+             <link rel=\"canonical\" href=\" https://example.com/a \">\
+             <link rel=\"canonical\" href=\"https://example.com/b\">",
+        );
+        let meta = metadata(&soup);
+        assert_eq!(meta.canonical.as_deref(), Some("https://example.com/a"));
+        assert_eq!(meta.favicons.len(), 2);
+        assert_eq!(meta.favicons[0].rel, "shortcut icon");
+        assert_eq!(meta.favicons[0].sizes, None);
+        assert_eq!(meta.favicons[1].sizes.as_deref(), Some("180x180"));
+        assert_eq!(meta.favicons[1].mime.as_deref(), Some("image/png"));
+    }
+
+    #[test]
+    fn test_json_ld() {
+        let soup = Soup::parse(
+            "<script type=\"application/ld+json\">{\"@type\": \"Product\", \"name\": \
+             \"Tea\"}</script><script type=\"Application/LD+JSON; charset=utf-8\">[1, \
+             2]</script><script type=\"application/ld+json\">{not json</script><script \
+             type=\"application/json\">{\"ignored\": true}</script>",
+        );
+        let meta = metadata(&soup);
+        assert_eq!(meta.json_ld.len(), 2);
+        assert_eq!(meta.json_ld[0]["name"], "Tea");
+        assert_eq!(meta.json_ld[1], serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn test_empty_and_json() {
+        let soup = Soup::parse("<p>No head content</p>");
+        assert!(metadata(&soup).is_empty());
+
+        let soup = Soup::parse("<link rel=\"icon\" href=\"i.svg\" type=\"image/svg+xml\">");
+        let value = serde_json::Value::from(metadata(&soup));
+        assert_eq!(value["title"], serde_json::Value::Null);
+        assert_eq!(value["favicons"][0]["type"], "image/svg+xml");
+        assert_eq!(value["open_graph"], serde_json::json!({}));
+    }
+}
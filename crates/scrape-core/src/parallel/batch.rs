@@ -5,13 +5,13 @@
 
 use rayon::prelude::*;
 
-use crate::{Soup, SoupConfig};
+use crate::{Soup, SoupConfig, runtime};
 
 /// Parses multiple HTML documents in parallel.
 ///
-/// Uses Rayon's work-stealing scheduler for efficient parallel execution.
-/// Each document is parsed independently, making this highly parallelizable.
-/// Results are returned in the same order as the input.
+/// Uses Rayon's work-stealing scheduler for efficient parallel execution, on the pool
+/// set with [`runtime::configure`] if any. Each document is parsed independently,
+/// making this highly parallelizable. Results are returned in the same order as the input.
 ///
 /// # Performance
 ///
@@ -43,7 +43,7 @@ use crate::{Soup, SoupConfig};
 /// ```
 #[must_use]
 pub fn parse_batch(documents: &[&str]) -> Vec<Soup> {
-    documents.par_iter().map(|html| Soup::parse(html)).collect()
+    runtime::install(|| documents.par_iter().map(|html| Soup::parse(html)).collect())
 }
 
 /// Parses multiple HTML documents in parallel with custom configuration.
@@ -65,7 +65,9 @@ pub fn parse_batch(documents: &[&str]) -> Vec<Soup> {
 /// ```
 #[must_use]
 pub fn parse_batch_with_config(documents: &[&str], config: &SoupConfig) -> Vec<Soup> {
-    documents.par_iter().map(|html| Soup::parse_with_config(html, config.clone())).collect()
+    runtime::install(|| {
+        documents.par_iter().map(|html| Soup::parse_with_config(html, config.clone())).collect()
+    })
 }
 
 /// Parses owned HTML strings in parallel.
@@ -92,7 +94,7 @@ pub fn parse_batch_with_config(documents: &[&str], config: &SoupConfig) -> Vec<S
 /// ```
 #[must_use]
 pub fn parse_batch_owned(documents: &[String]) -> Vec<Soup> {
-    documents.par_iter().map(|html| Soup::parse(html)).collect()
+    runtime::install(|| documents.par_iter().map(|html| Soup::parse(html)).collect())
 }
 
 #[cfg(test)]
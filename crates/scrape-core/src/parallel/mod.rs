@@ -1,7 +1,10 @@
 //! Parallel processing utilities.
 //!
-//! This module provides parallel processing capabilities for batch operations,
-//! leveraging Rayon's work-stealing scheduler for efficient multi-threaded execution.
+//! This module provides parallel processing capabilities for batch parsing and for
+//! selector matching over large documents, leveraging Rayon's work-stealing scheduler
+//! for efficient multi-threaded execution.
+//! The thread pool is shared with the rest of the crate and sized through
+//! [`crate::runtime::configure`].
 //!
 //! # Feature Flag
 //!
@@ -20,6 +23,10 @@
 
 #[cfg(feature = "parallel")]
 mod batch;
+#[cfg(feature = "parallel")]
+mod query;
 
 #[cfg(feature = "parallel")]
 pub use batch::{parse_batch, parse_batch_owned, parse_batch_with_config};
+#[cfg(feature = "parallel")]
+pub use query::{find_all_parallel, find_all_parallel_with_selector};
@@ -0,0 +1,115 @@
+//! Parallel CSS selector matching using Rayon.
+//!
+//! The elements of a document are listed in document order and split into chunks that
+//! are matched on separate threads, so results keep document order without sorting.
+
+use rayon::prelude::*;
+use selectors::{context::SelectorCaches, parser::SelectorList};
+
+use crate::{
+    dom::{Document, NodeId},
+    query::{QueryResult, ScrapeSelector, cached_compile, matches_selector_with_caches},
+    runtime,
+};
+
+/// Number of elements matched per task.
+///
+/// Large enough that scheduling overhead stays small next to selector matching, small
+/// enough that a 10k-element document is spread over several threads.
+const CHUNK_SIZE: usize = 512;
+
+/// Finds all elements matching a CSS selector, matching in parallel.
+///
+/// Returns the same IDs as [`find_all`](crate::query::find_all), in document order. Runs
+/// on the pool set with [`runtime::configure`] if any. This pays off for large documents
+/// and complex selectors; simple `#id`, `.class`, and tag selectors are answered from the
+/// document index by [`find_all`](crate::query::find_all), which is faster.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+/// syntax is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "parallel")]
+/// # fn example() {
+/// use scrape_core::{Soup, parallel::find_all_parallel};
+///
+/// let soup = Soup::parse("<ul><li>A</li><li class=\"x\">B</li><li class=\"x\">C</li></ul>");
+/// let ids = find_all_parallel(soup.document(), "ul > li.x").unwrap();
+/// assert_eq!(ids.len(), 2);
+/// # }
+/// ```
+pub fn find_all_parallel(doc: &Document, selector: &str) -> QueryResult<Vec<NodeId>> {
+    let compiled = cached_compile(selector)?;
+    Ok(find_all_parallel_with_selector(doc, compiled.selector_list()))
+}
+
+/// Finds all elements matching a pre-compiled selector list, matching in parallel.
+#[must_use]
+pub fn find_all_parallel_with_selector(
+    doc: &Document,
+    selectors: &SelectorList<ScrapeSelector>,
+) -> Vec<NodeId> {
+    let Some(root) = doc.root() else { return Vec::new() };
+    let elements: Vec<_> = std::iter::once(root)
+        .chain(doc.descendants(root))
+        .filter(|&id| doc.get(id).is_some_and(|node| node.kind.is_element()))
+        .collect();
+
+    runtime::install(|| {
+        elements
+            .par_chunks(CHUNK_SIZE)
+            .flat_map_iter(|chunk| {
+                let mut caches = SelectorCaches::default();
+                chunk
+                    .iter()
+                    .copied()
+                    .filter(|&id| matches_selector_with_caches(doc, id, selectors, &mut caches))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use super::*;
+    use crate::{Soup, query::find_all};
+
+    #[test]
+    fn test_matches_sequential_find_all() {
+        let items = (0..3000).fold(String::new(), |mut items, i| {
+            let _ = write!(items, "<li class=\"c{}\"><a href=\"/{i}\">{i}</a></li>", i % 3);
+            items
+        });
+        let soup = Soup::parse(&format!("<ul>{items}</ul><p><a>x</a></p>"));
+
+        for selector in ["li.c1 > a[href]", "ul a", "p a, li.c2", "*", "li:nth-child(2n)"] {
+            let parallel = find_all_parallel(soup.document(), selector).unwrap();
+            assert_eq!(parallel, find_all(soup.document(), selector).unwrap(), "{selector}");
+        }
+        assert_eq!(find_all_parallel(soup.document(), "li.c1").unwrap().len(), 1000);
+    }
+
+    #[test]
+    fn test_skips_detached_nodes() {
+        let mut soup = Soup::parse("<div><p>A</p><p>B</p></div>");
+        let first = soup.find("p").unwrap().unwrap().node_id();
+        soup.document_mut().remove_node(first);
+
+        let ids = find_all_parallel(soup.document(), "div > p").unwrap();
+        assert_eq!(ids.len(), 1);
+        assert_ne!(ids[0], first);
+    }
+
+    #[test]
+    fn test_invalid_selector() {
+        let soup = Soup::parse("<div></div>");
+        assert!(find_all_parallel(soup.document(), "[").is_err());
+    }
+}
@@ -0,0 +1,266 @@
+//! Recovery of repeated attributes, which html5ever's tokenizer drops.
+//!
+//! The HTML tokenizer keeps only the first of several attributes with the same name and
+//! flags the tag instead. With
+//! [`ParseConfig::preserve_duplicate_attributes`](super::ParseConfig::preserve_duplicate_attributes),
+//! [`RestoreDuplicates`] sits between the tokenizer and the tree builder: it finds each
+//! start tag in the source, and for flagged tags re-reads the attributes there, keeping
+//! the repeated ones, before the tree builder sees the tag.
+//!
+//! Values are decoded by tokenizing each attribute again on its own, so names and
+//! character references come out exactly as html5ever reads them. A tag whose re-read
+//! attributes disagree with the tokenizer's is left as the tokenizer reported it.
+
+use std::{cell::Cell, ops::Range};
+
+use html5ever::{
+    Attribute, ParseOpts, QualName, TokenizerResult,
+    tendril::StrTendril,
+    tokenizer::{
+        BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+    },
+    tree_builder::{TreeBuilder, TreeSink, create_element},
+};
+
+/// Parses a full document like `html5ever::parse_document`, keeping repeated attributes.
+pub fn parse_document<Sink: TreeSink>(sink: Sink, opts: ParseOpts, html: &str) -> Sink::Output {
+    let tree_builder = TreeBuilder::new(sink, opts.tree_builder);
+    run(tree_builder, opts.tokenizer, html)
+}
+
+/// Parses a fragment like `html5ever::parse_fragment` with no context attributes,
+/// keeping repeated attributes.
+pub fn parse_fragment<Sink: TreeSink>(
+    sink: Sink,
+    opts: ParseOpts,
+    context_name: QualName,
+    html: &str,
+) -> Sink::Output {
+    let context = create_element(&sink, context_name, Vec::new());
+    let tree_builder = TreeBuilder::new_for_fragment(sink, context, None, opts.tree_builder);
+    let initial_state = tree_builder.tokenizer_state_for_context_elem(false);
+    let opts = TokenizerOpts { initial_state: Some(initial_state), ..opts.tokenizer };
+    run(tree_builder, opts, html)
+}
+
+/// Feeds all of `html` through a tokenizer in front of `tree_builder`.
+fn run<Sink: TreeSink>(
+    tree_builder: TreeBuilder<Sink::Handle, Sink>,
+    opts: TokenizerOpts,
+    html: &str,
+) -> Sink::Output {
+    let tokenizer = Tokenizer::new(RestoreDuplicates::new(tree_builder, html), opts);
+    let input = BufferQueue::default();
+    input.push_back(StrTendril::from(html));
+    while !matches!(tokenizer.feed(&input), TokenizerResult::Done) {}
+    tokenizer.end();
+    tokenizer.sink.inner.sink.finish()
+}
+
+/// A token sink that puts back the repeated attributes of start tags before passing
+/// them on to `inner`.
+struct RestoreDuplicates<'a, Sink> {
+    inner: Sink,
+    source: &'a str,
+    /// Where in `source` to look for the next start tag.
+    cursor: Cell<usize>,
+}
+
+impl<'a, Sink> RestoreDuplicates<'a, Sink> {
+    const fn new(inner: Sink, source: &'a str) -> Self {
+        Self { inner, source, cursor: Cell::new(0) }
+    }
+
+    /// Finds `tag` in the source and, if the tokenizer dropped repeated attributes from
+    /// it, replaces its attributes with all of them in source order.
+    fn restore(&self, tag: &mut Tag) {
+        let mut from = self.cursor.get();
+        while let Some((start, segments, end)) = find_start_tag(self.source, from, &tag.name) {
+            if !tag.had_duplicate_attributes {
+                self.cursor.set(end);
+                return;
+            }
+            if let Some(attrs) = self.read_attributes(&segments)
+                && first_of_each(&attrs).eq(tag.attrs.iter())
+            {
+                tag.attrs = attrs;
+                self.cursor.set(end);
+                return;
+            }
+            // The text looked like the tag, e.g. inside a comment; keep looking.
+            from = start + 1;
+        }
+    }
+
+    /// Tokenizes each attribute of a start tag on its own, returning all of them.
+    fn read_attributes(&self, segments: &[Range<usize>]) -> Option<Vec<Attribute>> {
+        let mut html = String::new();
+        for segment in segments {
+            html.push_str("<x ");
+            html.push_str(&self.source[segment.clone()]);
+            html.push('>');
+        }
+        let collector = Tokenizer::new(CollectAttributes::default(), TokenizerOpts::default());
+        let input = BufferQueue::default();
+        input.push_back(StrTendril::from(html));
+        let _ = collector.feed(&input);
+        collector.end();
+        let attrs = collector.sink.attrs.take();
+        (attrs.len() == segments.len()).then_some(attrs)
+    }
+}
+
+impl<Sink: TokenSink> TokenSink for RestoreDuplicates<'_, Sink> {
+    type Handle = Sink::Handle;
+
+    fn process_token(&self, mut token: Token, line_number: u64) -> TokenSinkResult<Self::Handle> {
+        if let Token::TagToken(tag) = &mut token
+            && tag.kind == TagKind::StartTag
+        {
+            self.restore(tag);
+        }
+        self.inner.process_token(token, line_number)
+    }
+
+    fn end(&self) {
+        self.inner.end();
+    }
+
+    fn adjusted_current_node_present_but_not_in_html_namespace(&self) -> bool {
+        self.inner.adjusted_current_node_present_but_not_in_html_namespace()
+    }
+}
+
+/// Collects the first attribute of every start tag.
+#[derive(Default)]
+struct CollectAttributes {
+    attrs: Cell<Vec<Attribute>>,
+}
+
+impl TokenSink for CollectAttributes {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        if let Token::TagToken(Tag { kind: TagKind::StartTag, attrs, .. }) = token
+            && let Some(attr) = attrs.into_iter().next()
+        {
+            let mut all = self.attrs.take();
+            all.push(attr);
+            self.attrs.set(all);
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+/// Returns the first attribute with each name, in order.
+fn first_of_each(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs
+        .iter()
+        .enumerate()
+        .filter(|&(i, attr)| !attrs[..i].iter().any(|earlier| earlier.name == attr.name))
+        .map(|(_, attr)| attr)
+}
+
+/// Whitespace between attributes; a carriage return is read as a line feed.
+const fn is_space(byte: u8) -> bool {
+    matches!(byte, b'\t' | b'\n' | b'\x0C' | b'\r' | b' ')
+}
+
+/// Finds the next start tag named `name` at or after `from` and returns its start, the
+/// source ranges of its attributes, and its end.
+///
+/// Attribute ranges run from the start of the name to the end of the value, quotes
+/// included, following the tokenizer's attribute states.
+fn find_start_tag(
+    source: &str,
+    from: usize,
+    name: &str,
+) -> Option<(usize, Vec<Range<usize>>, usize)> {
+    let bytes = source.as_bytes();
+    let mut search = from;
+    loop {
+        let start = search + memchr::memchr(b'<', bytes.get(search..)?)?;
+        search = start + 1;
+        let name_end = start + 1 + name.len();
+        if bytes.get(start + 1..name_end).is_some_and(|n| n.eq_ignore_ascii_case(name.as_bytes()))
+            && bytes.get(name_end).is_some_and(|&b| is_space(b) || b == b'/' || b == b'>')
+            && let Some((segments, end)) = scan_attributes(bytes, name_end)
+        {
+            return Some((start, segments, end));
+        }
+    }
+}
+
+/// Reads the attributes of a start tag from just after its name to its closing `>`.
+///
+/// Returns `None` if the input ends inside the tag, which the tokenizer then drops.
+fn scan_attributes(bytes: &[u8], mut i: usize) -> Option<(Vec<Range<usize>>, usize)> {
+    let mut segments = Vec::new();
+    loop {
+        // Before attribute name; a stray `/` is skipped like whitespace.
+        while bytes.get(i).is_some_and(|&b| is_space(b) || b == b'/') {
+            i += 1;
+        }
+        if *bytes.get(i)? == b'>' {
+            return Some((segments, i + 1));
+        }
+
+        // Attribute name; a leading `=` is part of it.
+        let name_start = i;
+        i += 1;
+        while bytes.get(i).is_some_and(|&b| !is_space(b) && !matches!(b, b'/' | b'>' | b'=')) {
+            i += 1;
+        }
+        let mut end = i;
+
+        // After attribute name.
+        while bytes.get(i).is_some_and(|&b| is_space(b)) {
+            i += 1;
+        }
+        if *bytes.get(i)? == b'=' {
+            i += 1;
+            end = i;
+            while bytes.get(i).is_some_and(|&b| is_space(b)) {
+                i += 1;
+            }
+            match *bytes.get(i)? {
+                quote @ (b'"' | b'\'') => {
+                    i += 1 + memchr::memchr(quote, bytes.get(i + 1..)?)? + 1;
+                    end = i;
+                }
+                // A missing value; the tag ends here.
+                b'>' => {}
+                _ => {
+                    while bytes.get(i).is_some_and(|&b| !is_space(b) && b != b'>') {
+                        i += 1;
+                    }
+                    end = i;
+                }
+            }
+        }
+        segments.push(name_start..end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(tag: &str) -> Vec<&str> {
+        let (_, segments, end) = find_start_tag(tag, 0, "a").unwrap();
+        assert_eq!(end, tag.len(), "{tag}");
+        segments.into_iter().map(|range| &tag[range]).collect()
+    }
+
+    #[test]
+    fn test_scan_attributes() {
+        assert_eq!(attributes("<a>"), Vec::<&str>::new());
+        assert_eq!(
+            attributes("<A href=x b = 'y>' c=\"z\"d/>"),
+            ["href=x", "b = 'y>'", "c=\"z\"", "d"]
+        );
+        assert_eq!(attributes("<a =x x=/ y= >"), ["=x", "x=/", "y="]);
+        assert!(find_start_tag("<a href='x>", 0, "a").is_none());
+        assert!(find_start_tag("<ab>", 0, "a").is_none());
+    }
+}
@@ -7,7 +7,7 @@ use crate::error::{SourceSpan, SpanContext};
 /// Result type for parser operations.
 pub type ParseResult<T> = Result<T, ParseError>;
 
-/// Errors that can occur during HTML parsing.
+/// Errors that can occur during HTML or XML parsing.
 #[derive(Debug, Error)]
 pub enum ParseError {
     /// Document exceeds maximum nesting depth.
@@ -39,6 +39,22 @@ pub enum ParseError {
         span: Option<SourceSpan>,
     },
 
+    /// Malformed XML that cannot be read as a document.
+    #[error("malformed XML: {message}{}", format_position(span.as_ref()))]
+    MalformedXml {
+        /// Description of the malformation.
+        message: String,
+        /// Source location, if available.
+        span: Option<SourceSpan>,
+    },
+
+    /// Document has more nodes than a [`NodeId`](crate::NodeId) can address.
+    #[error("document exceeds the limit of {max_nodes} nodes")]
+    TooManyNodes {
+        /// Maximum number of nodes in a document.
+        max_nodes: usize,
+    },
+
     /// Internal parser error.
     #[error("internal parser error: {0}")]
     InternalError(String),
@@ -55,7 +71,9 @@ impl ParseError {
     #[must_use]
     pub fn span(&self) -> Option<&SourceSpan> {
         match self {
-            Self::MaxDepthExceeded { span, .. } | Self::MalformedHtml { span, .. } => span.as_ref(),
+            Self::MaxDepthExceeded { span, .. }
+            | Self::MalformedHtml { span, .. }
+            | Self::MalformedXml { span, .. } => span.as_ref(),
             _ => None,
         }
     }
@@ -112,6 +130,13 @@ mod tests {
         assert_eq!(err.to_string(), "encoding error: invalid UTF-8 sequence");
     }
 
+    #[test]
+    fn test_too_many_nodes_display() {
+        let err = ParseError::TooManyNodes { max_nodes: 10 };
+        assert_eq!(err.to_string(), "document exceeds the limit of 10 nodes");
+        assert!(err.span().is_none());
+    }
+
     #[test]
     fn test_internal_error_display() {
         let err = ParseError::InternalError("unexpected state".into());
@@ -128,6 +153,16 @@ mod tests {
         assert_eq!(err.column(), Some(7));
     }
 
+    #[test]
+    fn test_malformed_xml_with_span() {
+        use crate::error::SourcePosition;
+        let span = SourceSpan::new(SourcePosition::new(3, 1, 20), SourcePosition::new(3, 1, 20));
+        let err =
+            ParseError::MalformedXml { message: "unterminated markup".into(), span: Some(span) };
+        assert_eq!(err.to_string(), "malformed XML: unterminated markup at line 3, column 1");
+        assert_eq!(err.line(), Some(3));
+    }
+
     #[test]
     fn test_span_context() {
         use crate::error::SourcePosition;
@@ -1,7 +1,7 @@
 //! HTML fragment parsing without wrapping in html/body.
 
 use super::{ParseConfig, ParseError, ParseResult};
-use crate::dom::Document;
+use crate::dom::{Document, NodeId};
 
 /// Parses an HTML fragment with body context.
 ///
@@ -52,6 +52,105 @@ pub fn parse_fragment_impl(
     super::sink::parse_html_fragment(html, context, config)
 }
 
+/// Parses `html` in the context of the element `parent` and appends the resulting nodes
+/// as the last children of `parent`, without re-parsing the rest of `doc`.
+///
+/// Returns the IDs of the appended top-level nodes. Nothing is appended if `parent` is
+/// not an element or `html` is empty or cannot be parsed.
+pub fn append_html(
+    doc: &mut Document,
+    parent: NodeId,
+    html: &str,
+    config: &ParseConfig,
+) -> Vec<NodeId> {
+    append_parsed(doc, parent, html, config).unwrap_or_default()
+}
+
+/// Parses `html` in the context of the element `parent` and inserts the resulting nodes
+/// as the first children of `parent`.
+///
+/// Follows the same rules as [`append_html`].
+pub fn prepend_html(
+    doc: &mut Document,
+    parent: NodeId,
+    html: &str,
+    config: &ParseConfig,
+) -> Vec<NodeId> {
+    let first = doc.first_child(parent);
+    let copies = append_parsed(doc, parent, html, config).unwrap_or_default();
+    if let Some(first) = first {
+        for &copy in &copies {
+            doc.insert_before(first, copy);
+        }
+    }
+    copies
+}
+
+/// Parses `html` in the context of the element `parent` and replaces the children of
+/// `parent` with the resulting nodes.
+///
+/// Returns the IDs of the new top-level nodes. The old children are detached as by
+/// [`Document::remove_node`]. Nothing changes if `parent` is not an element or `html`
+/// cannot be parsed; an empty `html` removes every child.
+pub fn set_inner_html(
+    doc: &mut Document,
+    parent: NodeId,
+    html: &str,
+    config: &ParseConfig,
+) -> Vec<NodeId> {
+    let old: Vec<_> = doc.children(parent).collect();
+    let Some(copies) = append_parsed(doc, parent, html, config) else {
+        return Vec::new();
+    };
+    for child in old {
+        doc.remove_node(child);
+    }
+    copies
+}
+
+/// Parses `html` in the context of the parent of `node` and puts the resulting nodes in
+/// the place of `node`, which is detached as by [`Document::remove_node`].
+///
+/// Returns the IDs of the new top-level nodes. Nothing changes if `node` has no parent
+/// element or `html` cannot be parsed; an empty `html` just removes `node`.
+pub fn replace_with_html(
+    doc: &mut Document,
+    node: NodeId,
+    html: &str,
+    config: &ParseConfig,
+) -> Vec<NodeId> {
+    let Some(parent) = doc.parent(node) else {
+        return Vec::new();
+    };
+    let Some(copies) = append_parsed(doc, parent, html, config) else {
+        return Vec::new();
+    };
+    for &copy in &copies {
+        doc.insert_before(node, copy);
+    }
+    doc.remove_node(node);
+    copies
+}
+
+/// Parses `html` in the context of the element `parent` and appends copies of the
+/// resulting nodes to it.
+///
+/// Returns `None` if `parent` is not an element or `html` cannot be parsed. Blank `html`
+/// appends nothing.
+fn append_parsed(
+    doc: &mut Document,
+    parent: NodeId,
+    html: &str,
+    config: &ParseConfig,
+) -> Option<Vec<NodeId>> {
+    let context = doc.get(parent)?.kind.tag_name()?;
+    if html.trim().is_empty() {
+        return Some(Vec::new());
+    }
+    let (fragment, nodes) = super::sink::parse_html_fragment_nodes(html, context, config).ok()?;
+    Some(doc.append_copies(parent, &fragment, &nodes))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,6 +212,15 @@ mod tests {
         assert!(doc.root().is_some());
     }
 
+    #[test]
+    fn test_parse_fragment_context_is_case_insensitive() {
+        for context in ["tbody", "TBODY", "TBody"] {
+            let doc = parse_fragment_with_context("<tr><td>A</td></tr>", context).unwrap();
+            let root = doc.root().and_then(|id| doc.get(id)).unwrap();
+            assert_eq!(root.kind.tag_name(), Some("tr"), "context {context}");
+        }
+    }
+
     #[test]
     fn test_parse_fragment_with_context_body() {
         let doc = parse_fragment_with_context("<div>Test</div>", "body").unwrap();
@@ -132,8 +240,8 @@ mod tests {
         let node = doc.get(root).unwrap();
 
         if let crate::dom::NodeKind::Element { attributes, .. } = &node.kind {
-            assert_eq!(attributes.get("class"), Some(&"test".to_string()));
-            assert_eq!(attributes.get("id"), Some(&"main".to_string()));
+            assert_eq!(attributes.get("class"), Some("test"));
+            assert_eq!(attributes.get("id"), Some("main"));
         } else {
             panic!("Expected element node");
         }
@@ -141,8 +249,7 @@ mod tests {
 
     #[test]
     fn test_parse_fragment_max_depth() {
-        let config =
-            ParseConfig { max_depth: 5, preserve_whitespace: false, include_comments: false };
+        let config = ParseConfig { max_depth: 5, ..ParseConfig::default() };
 
         let result = parse_fragment_impl(
             "<div><div><div><div><div><div>too deep</div></div></div></div></div></div>",
@@ -1,6 +1,10 @@
 //! html5ever-based HTML parser implementation.
 
-use super::{ParseConfig, ParseError, ParseResult, Parser, private::Sealed};
+use std::sync::Arc;
+
+use super::{
+    ParseConfig, ParseError, ParseResult, ParseResultWithWarnings, Parser, private::Sealed,
+};
 use crate::dom::Document;
 
 /// HTML5 spec-compliant parser using html5ever.
@@ -47,4 +51,59 @@ impl Html5everParser {
 
         super::sink::parse_html_document(html, config, capacity)
     }
+
+    /// Parses HTML like [`parse_with_config_and_capacity`](Self::parse_with_config_and_capacity),
+    /// but stores text nodes as byte ranges of `html` instead of copies.
+    ///
+    /// The document keeps `html` alive. Text that does not appear verbatim in the input,
+    /// such as text containing character references, is still copied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if parsing fails.
+    pub fn parse_borrowed_with_config_and_capacity(
+        &self,
+        html: &Arc<str>,
+        config: &ParseConfig,
+        capacity: usize,
+    ) -> ParseResult<Document> {
+        if html.trim().is_empty() {
+            return Err(ParseError::EmptyInput);
+        }
+
+        super::sink::parse_html_document_borrowed(html, config, capacity)
+    }
+
+    /// Parses HTML and collects the parse errors html5ever recovered from.
+    ///
+    /// Each warning has [`RecoveredError`](super::WarningSeverity::RecoveredError) severity and a
+    /// span covering the line the error was reported on; html5ever does not track columns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if parsing fails, like
+    /// [`parse_with_config`](Parser::parse_with_config).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use scrape_core::{Html5everParser, ParseConfig};
+    ///
+    /// let parsed = Html5everParser
+    ///     .parse_with_warnings("<!DOCTYPE html>\n<p>One</div>", &ParseConfig::default())
+    ///     .unwrap();
+    /// assert!(parsed.result.root().is_some());
+    /// assert_eq!(parsed.warnings[0].span.unwrap().start.line, 2);
+    /// ```
+    pub fn parse_with_warnings(
+        &self,
+        html: &str,
+        config: &ParseConfig,
+    ) -> ParseResult<ParseResultWithWarnings<Document>> {
+        if html.trim().is_empty() {
+            return Err(ParseError::EmptyInput);
+        }
+
+        super::sink::parse_html_document_with_warnings(html, config, 256)
+    }
 }
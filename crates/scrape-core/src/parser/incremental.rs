@@ -0,0 +1,142 @@
+//! Incremental HTML document parsing from byte chunks.
+
+use html5ever::{
+    ParseOpts,
+    driver::Parser as Html5everDriver,
+    parse_document,
+    tendril::{ByteTendril, TendrilSink, stream::Utf8LossyDecoder},
+};
+
+use super::{ParseConfig, ParseError, ParseResult, sink::DocBuilderSink};
+use crate::dom::Document;
+
+/// Builds a document from HTML delivered in byte chunks.
+///
+/// Chunks are fed straight into the html5ever tokenizer, so the full input never
+/// has to be buffered as a single string. Chunk boundaries may fall anywhere,
+/// including inside multi-byte UTF-8 sequences; invalid UTF-8 is replaced with
+/// U+FFFD.
+///
+/// # Example
+///
+/// ```rust
+/// use scrape_core::{IncrementalParser, ParseConfig};
+///
+/// let mut parser = IncrementalParser::new(&ParseConfig::default());
+/// parser.feed(b"<html><body><p>Hel");
+/// parser.feed(b"lo</p></body></html>");
+/// let document = parser.finish().unwrap();
+/// assert!(document.root().is_some());
+/// ```
+pub struct IncrementalParser {
+    driver: Utf8LossyDecoder<Html5everDriver<DocBuilderSink>>,
+    bytes_fed: usize,
+    has_content: bool,
+}
+
+impl IncrementalParser {
+    /// Creates a new incremental parser with the given configuration.
+    #[must_use]
+    pub fn new(config: &ParseConfig) -> Self {
+        Self::with_capacity(config, 256)
+    }
+
+    /// Creates a new incremental parser with pre-allocated node capacity.
+    #[must_use]
+    pub fn with_capacity(config: &ParseConfig, capacity: usize) -> Self {
+        let sink = DocBuilderSink::new(config.clone(), capacity);
+        Self {
+            driver: parse_document(sink, ParseOpts::default()).from_utf8(),
+            bytes_fed: 0,
+            has_content: false,
+        }
+    }
+
+    /// Feeds the next chunk of HTML bytes into the parser.
+    pub fn feed(&mut self, chunk: &[u8]) {
+        if chunk.is_empty() {
+            return;
+        }
+        self.bytes_fed += chunk.len();
+        self.has_content |= !chunk.iter().all(u8::is_ascii_whitespace);
+        self.driver.process(ByteTendril::from_slice(chunk));
+    }
+
+    /// Returns the total number of bytes fed so far.
+    #[must_use]
+    pub fn bytes_fed(&self) -> usize {
+        self.bytes_fed
+    }
+
+    /// Finishes parsing and returns the built document.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if parsing fails:
+    /// - [`ParseError::EmptyInput`] if no non-whitespace bytes were fed
+    /// - [`ParseError::MaxDepthExceeded`] if nesting exceeds `config.max_depth`
+    pub fn finish(self) -> ParseResult<Document> {
+        if !self.has_content {
+            return Err(ParseError::EmptyInput);
+        }
+        self.driver.finish().finish_document()
+    }
+}
+
+impl std::fmt::Debug for IncrementalParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IncrementalParser")
+            .field("bytes_fed", &self.bytes_fed)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[test]
+    fn test_incremental_matches_full_parse() {
+        let html = "<html><body><ul><li>A</li><li>B</li></ul></body></html>";
+        let full = crate::Html5everParser.parse(html).unwrap();
+
+        let mut parser = IncrementalParser::new(&ParseConfig::default());
+        for chunk in html.as_bytes().chunks(7) {
+            parser.feed(chunk);
+        }
+        let incremental = parser.finish().unwrap();
+
+        assert_eq!(incremental.len(), full.len());
+    }
+
+    #[test]
+    fn test_incremental_split_utf8_sequence() {
+        let html = "<p>caf\u{e9}</p>".as_bytes();
+        let split = html.iter().position(|&b| b >= 0x80).unwrap() + 1;
+
+        let mut parser = IncrementalParser::new(&ParseConfig::default());
+        parser.feed(&html[..split]);
+        parser.feed(&html[split..]);
+        let doc = parser.finish().unwrap();
+
+        let p = crate::query::find(&doc, "p").unwrap().unwrap();
+        assert_eq!(crate::Tag::new(&doc, p).text(), "caf\u{e9}");
+    }
+
+    #[test]
+    fn test_incremental_empty_input_returns_error() {
+        let mut parser = IncrementalParser::new(&ParseConfig::default());
+        parser.feed(b"   \n");
+        assert!(matches!(parser.finish(), Err(ParseError::EmptyInput)));
+    }
+
+    #[test]
+    fn test_incremental_bytes_fed() {
+        let mut parser = IncrementalParser::new(&ParseConfig::default());
+        parser.feed(b"<div>");
+        parser.feed(b"");
+        parser.feed(b"</div>");
+        assert_eq!(parser.bytes_fed(), 11);
+    }
+}
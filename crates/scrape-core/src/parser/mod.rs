@@ -1,8 +1,10 @@
 //! HTML parsing implementations.
 //!
-//! This module provides different HTML parsing strategies:
+//! This module provides different parsing strategies:
 //!
 //! - **html5ever**: Spec-compliant HTML5 parser for correct parsing of all HTML
+//! - **incremental**: Chunked input fed to html5ever as bytes arrive
+//! - **xml**: Case-sensitive, namespace-aware parsing of XML documents such as feeds
 //!
 //! # Architecture
 //!
@@ -19,17 +21,22 @@
 //! let document = parser.parse_with_config("<html><body>Hello</body></html>", &config)?;
 //! ```
 
+mod duplicate_attrs;
 mod error;
 pub mod fragment;
 mod html5;
+mod incremental;
 pub mod sink;
 #[cfg(test)]
 mod tests;
 pub mod warnings;
+mod xml;
 
 pub use error::{ParseError, ParseResult};
 pub use html5::Html5everParser;
+pub use incremental::IncrementalParser;
 pub use warnings::{ParseResultWithWarnings, ParseWarning, WarningSeverity};
+pub use xml::XmlParser;
 
 use crate::dom::Document;
 
@@ -42,7 +49,7 @@ mod private {
 /// A sealed trait for HTML parsers.
 ///
 /// This trait is sealed and cannot be implemented outside of this crate.
-/// Use [`Html5everParser`] for spec-compliant HTML5 parsing.
+/// Use [`Html5everParser`] for spec-compliant HTML5 parsing and [`XmlParser`] for XML.
 ///
 /// # Example
 ///
@@ -79,9 +86,16 @@ pub trait Parser: private::Sealed {
 /// ```rust
 /// use scrape_core::ParseConfig;
 ///
-/// let config = ParseConfig { max_depth: 256, preserve_whitespace: true, include_comments: false };
+/// let config = ParseConfig {
+///     max_depth: 256,
+///     preserve_whitespace: true,
+///     include_comments: false,
+///     pierce_shadow: false,
+///     preserve_duplicate_attributes: false,
+/// };
 /// ```
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ParseConfig {
     /// Maximum nesting depth for the DOM tree.
     ///
@@ -91,17 +105,42 @@ pub struct ParseConfig {
 
     /// Whether to preserve whitespace-only text nodes.
     ///
-    /// When `false` (default), text nodes containing only whitespace are filtered out.
+    /// When `false` (default), text nodes containing only whitespace are filtered out,
+    /// except inside `<pre>`, `<code>`, `<textarea>`, and elements with an inline
+    /// `white-space: pre*` style, where whitespace is significant.
     pub preserve_whitespace: bool,
 
     /// Whether to include comment nodes in the parsed document.
     ///
     /// Default: `false`.
     pub include_comments: bool,
+
+    /// Whether to attach declarative shadow roots to the tree.
+    ///
+    /// When `true`, the content of a `<template shadowrootmode="open|closed">` becomes
+    /// the children of the `<template>` element, which stays in place inside its shadow
+    /// host, so selectors and text extraction reach into the shadow root. When `false`
+    /// (default), shadow root content is held apart like any other template content.
+    pub pierce_shadow: bool,
+
+    /// Whether to keep repeated attributes on an element.
+    ///
+    /// HTML uses the first of several attributes with the same name and drops the rest.
+    /// When `true`, the repeated ones are kept after it, in source order, and are
+    /// available from [`Attributes::get_all`](crate::Attributes::get_all). Lookups by
+    /// name still return the first. [`IncrementalParser`] ignores this option, since it
+    /// never holds the whole input. Default: `false`.
+    pub preserve_duplicate_attributes: bool,
 }
 
 impl Default for ParseConfig {
     fn default() -> Self {
-        Self { max_depth: 512, preserve_whitespace: false, include_comments: false }
+        Self {
+            max_depth: 512,
+            preserve_whitespace: false,
+            include_comments: false,
+            pierce_shadow: false,
+            preserve_duplicate_attributes: false,
+        }
     }
 }
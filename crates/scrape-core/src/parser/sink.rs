@@ -7,7 +7,8 @@ use std::{
     borrow::Cow,
     cell::{Ref, RefCell},
     collections::HashMap,
-    sync::OnceLock,
+    ops::Range,
+    sync::{Arc, OnceLock},
 };
 
 use html5ever::{
@@ -16,8 +17,20 @@ use html5ever::{
     tendril::StrTendril,
 };
 
-use super::{ParseConfig, ParseError, ParseResult};
-use crate::dom::{Building, DocumentImpl, DocumentIndex, NodeId, NodeKind};
+use super::{
+    ParseConfig, ParseError, ParseResult, ParseResultWithWarnings, ParseWarning, WarningSeverity,
+    duplicate_attrs,
+};
+use crate::{
+    dom::{
+        Atom, Attributes, Building, DocumentImpl, DocumentIndex, Namespace, NodeId, NodeKind,
+        TagId, TextContent,
+    },
+    error::{SourcePosition, SourceSpan},
+};
+
+/// Parse errors reported by html5ever, each with the line it was reported on.
+type LineErrors = Vec<(u64, Cow<'static, str>)>;
 
 // ── Handle ───────────────────────────────────────────────────────────────────
 
@@ -31,7 +44,7 @@ use crate::dom::{Building, DocumentImpl, DocumentIndex, NodeId, NodeKind};
 pub enum SinkHandle {
     /// Virtual document root.
     Document,
-    /// Regular element, text, or comment node.
+    /// Regular element, text, comment, or doctype node.
     Node(NodeId),
     /// Template element: (`element_id`, `template_contents_id`).
     Template(NodeId, NodeId),
@@ -57,6 +70,10 @@ impl SinkHandle {
 
 // ── Inner mutable state ───────────────────────────────────────────────────────
 
+/// How far past the end of the last text found in the source a text chunk is looked
+/// for, so chunks that do not appear verbatim do not each scan the rest of the input.
+const SOURCE_SEARCH_WINDOW: usize = 64 * 1024;
+
 struct SinkInner {
     document: DocumentImpl<Building>,
     index: DocumentIndex,
@@ -67,6 +84,27 @@ struct SinkInner {
     depth_exceeded: bool,
     /// Set of nodes that are `MathML` annotation-xml integration points.
     mathml_annotation_integration_points: std::collections::HashSet<NodeId>,
+    /// Set once the incrementally built index stops matching the tree (nodes moved
+    /// after creation, detached template contents); it is then rebuilt from the tree.
+    rebuild_index: bool,
+    /// Declarative shadow root `<template>` elements attached to their host, whose
+    /// content goes directly under them (with `pierce_shadow`).
+    shadow_templates: std::collections::HashSet<NodeId>,
+    /// Line of the token being processed, as reported by the tree builder.
+    current_line: u64,
+    /// Parse errors with their lines, when collection is enabled.
+    errors: Option<LineErrors>,
+    /// The input being parsed, when text nodes should refer to it instead of copying.
+    source: Option<Arc<str>>,
+    /// Where in `source` to look for the next text chunk.
+    source_cursor: usize,
+    /// The last node appended to the document itself: the root element, the doctype,
+    /// or a comment outside the root element.
+    last_top_level: Option<NodeId>,
+    /// Most nodes the document may hold; nodes past it are dropped.
+    max_nodes: usize,
+    /// Set when a node was dropped because the document was full.
+    too_many_nodes: bool,
 }
 
 impl SinkInner {
@@ -78,7 +116,84 @@ impl SinkInner {
             depth_map: HashMap::new(),
             depth_exceeded: false,
             mathml_annotation_integration_points: std::collections::HashSet::new(),
+            rebuild_index: false,
+            shadow_templates: std::collections::HashSet::new(),
+            current_line: 1,
+            errors: None,
+            source: None,
+            source_cursor: 0,
+            last_top_level: None,
+            max_nodes: NodeId::MAX_COUNT,
+            too_many_nodes: false,
+        }
+    }
+
+    /// Returns whether `count` more nodes fit in the document, recording the error for
+    /// `finish` if they do not.
+    fn has_room_for(&mut self, count: usize) -> bool {
+        if self.document.len() + count <= self.max_nodes {
+            return true;
+        }
+        self.too_many_nodes = true;
+        false
+    }
+
+    /// Returns the error that ended the parse early, if any.
+    fn error(&self) -> Option<ParseError> {
+        if self.depth_exceeded {
+            Some(ParseError::MaxDepthExceeded { max_depth: self.config.max_depth, span: None })
+        } else if self.too_many_nodes {
+            Some(ParseError::TooManyNodes { max_nodes: self.max_nodes })
+        } else {
+            None
+        }
+    }
+
+    /// Finds a text chunk in the source, searching forward from the last chunk found.
+    ///
+    /// Returns `None` when not borrowing from the source or when the chunk does not
+    /// appear verbatim, e.g. because it was decoded from a character reference. Any
+    /// occurrence has the same bytes, so the match need not be the chunk's own position.
+    fn locate_text(&mut self, text: &str) -> Option<Range<usize>> {
+        let source = self.source.as_ref()?;
+        let from = self.source_cursor;
+        let to = source.len().min(from + text.len() + SOURCE_SEARCH_WINDOW);
+        let start = from + memchr::memmem::find(&source.as_bytes()[from..to], text.as_bytes())?;
+        self.source_cursor = start + text.len();
+        Some(start..start + text.len())
+    }
+
+    /// Returns the content for a new text node: a range of the source if the chunk is
+    /// found there, otherwise a copy.
+    fn text_content(&mut self, text: &str) -> TextContent {
+        self.locate_text(text)
+            .and_then(|range| TextContent::shared(self.source.as_ref()?, range))
+            .unwrap_or_else(|| TextContent::new(text))
+    }
+
+    /// Appends text to the last child of `parent` if it is a text node; returns `true`
+    /// when the text was merged, `false` when a new node is needed.
+    fn append_text_to_last_child(&mut self, parent: NodeId, text: &str) -> bool {
+        self.document
+            .get(parent)
+            .and_then(|n| n.last_child)
+            .is_some_and(|last| self.append_text_to_node(last, text))
+    }
+
+    /// Appends text to `node` itself if it is a text node, extending its range of the
+    /// source when the text follows it there; returns `true` when the text was merged.
+    fn append_text_to_node(&mut self, node: NodeId, text: &str) -> bool {
+        if self.source.is_some() && self.document.get(node).is_some_and(|n| n.kind.is_text()) {
+            let range = self.locate_text(text);
+            if let (Some(source), Some(range)) = (&self.source, range)
+                && let Some(NodeKind::Text { content }) =
+                    self.document.get_mut(node).map(|n| &mut n.kind)
+                && content.extend_shared(source, range)
+            {
+                return true;
+            }
         }
+        self.document.try_append_text_to_node(node, text)
     }
 
     /// Recursively recalculates `depth_map` for `node` and all its descendants,
@@ -97,6 +212,17 @@ impl SinkInner {
         }
     }
 
+    /// Returns `true` if whitespace-only text under `parent` is significant: inside
+    /// `<pre>`, `<code>`, or `<textarea>`, or an element whose inline style sets a
+    /// preserving `white-space` value.
+    fn keeps_whitespace(&self, parent: Option<NodeId>) -> bool {
+        parent.is_some_and(|parent| {
+            std::iter::once(parent).chain(self.document.ancestors(parent)).any(|id| {
+                self.document.get(id).is_some_and(|node| preserves_whitespace(&node.kind))
+            })
+        })
+    }
+
     /// Appends a child to `parent`, checking max depth.
     fn attach(&mut self, parent: &SinkHandle, child: NodeId) -> Result<(), ParseError> {
         let parent_depth = match parent {
@@ -119,9 +245,18 @@ impl SinkInner {
 
         match parent {
             SinkHandle::Document => {
-                if self.document.root().is_none() {
+                // The first element is the root; the doctype and comments around it
+                // become its siblings.
+                let is_element = self.document.get(child).is_some_and(|n| n.kind.is_element());
+                if is_element && self.document.root().is_some() {
+                    return Ok(());
+                }
+                if is_element {
                     self.document.set_root(child);
                 }
+                if let Some(last) = self.last_top_level.replace(child) {
+                    self.document.append_sibling(last, child);
+                }
             }
             SinkHandle::Node(parent_id) => {
                 self.document.append_child(*parent_id, child);
@@ -135,7 +270,7 @@ impl SinkInner {
     }
 
     /// Creates an element node from html5ever attributes, registers it in the
-    /// id/class index, and stores its `QualName` for `elem_name` lookups.
+    /// id/class/tag index, and stores its `QualName` for `elem_name` lookups.
     fn make_element(
         &mut self,
         name: &QualName,
@@ -143,25 +278,31 @@ impl SinkInner {
         flags: &ElementFlags,
         qual_names: &RefCell<HashMap<NodeId, QualName>>,
     ) -> SinkHandle {
-        let tag_name = name.local.to_string();
-        let mut attributes = HashMap::with_capacity(attrs.len());
+        if !self.has_room_for(if flags.template { 2 } else { 1 }) {
+            return SinkHandle::Phantom;
+        }
+        let tag_name = Atom::from_local_name(name.local.clone());
+        let mut attributes = Attributes::with_capacity(attrs.len());
         for attr in attrs {
-            let key = if attr.name.ns.is_empty() {
-                attr.name.local.to_string()
-            } else {
-                format!("{}:{}", attr.name.ns, attr.name.local)
-            };
-            attributes.insert(key, attr.value.to_string());
+            // html5ever passes each name once unless duplicates are being preserved.
+            attributes.push(attribute_key(attr), attr.value.to_string());
         }
 
-        let node_id = self.document.create_element(tag_name, attributes.clone());
+        // Elements outside the SVG and MathML namespaces are treated as HTML.
+        let namespace = Namespace::from_url(&name.ns).unwrap_or_default();
+        let node_id = self.document.create_element_ns(tag_name, attributes, namespace);
+        self.document.set_line(node_id, usize::try_from(self.current_line).unwrap_or(usize::MAX));
         qual_names.borrow_mut().insert(node_id, name.clone());
 
-        if let Some(id_attr) = attributes.get("id") {
-            self.index.register_id(id_attr.clone(), node_id);
-        }
-        if let Some(class_attr) = attributes.get("class") {
-            self.index.register_classes(class_attr, node_id);
+        self.index.register_tag(&name.local, node_id);
+
+        if let Some(attributes) = self.document.get(node_id).and_then(|n| n.kind.attributes()) {
+            if let Some(id_attr) = attributes.get("id") {
+                self.index.register_id(id_attr.to_string(), node_id);
+            }
+            if let Some(class_attr) = attributes.get("class") {
+                self.index.register_classes(class_attr, node_id);
+            }
         }
 
         if flags.mathml_annotation_xml_integration_point {
@@ -170,7 +311,9 @@ impl SinkInner {
 
         if flags.template {
             let contents_id =
-                self.document.create_element("template-contents".to_string(), HashMap::new());
+                self.document.create_element("template-contents".to_string(), Attributes::new());
+            // Template contents live outside the tree, but their elements were indexed.
+            self.rebuild_index = true;
             SinkHandle::Template(node_id, contents_id)
         } else {
             SinkHandle::Node(node_id)
@@ -178,6 +321,33 @@ impl SinkInner {
     }
 }
 
+/// Returns the key an html5ever attribute is stored under: its local name, prefixed
+/// with its namespace URL if it has one.
+fn attribute_key(attr: &Attribute) -> Atom {
+    if attr.name.ns.is_empty() {
+        Atom::from_local_name(attr.name.local.clone())
+    } else {
+        Atom::from(format!("{}:{}", attr.name.ns, attr.name.local))
+    }
+}
+
+/// Returns `true` for elements whose whitespace is significant: `<pre>`, `<code>`,
+/// `<textarea>`, and elements styled `white-space: pre`, `pre-wrap`, `pre-line`, or
+/// `break-spaces`.
+fn preserves_whitespace(kind: &NodeKind) -> bool {
+    let NodeKind::Element { tag_id, attributes, .. } = kind else { return false };
+    if matches!(tag_id, TagId::Pre | TagId::Code | TagId::Textarea) {
+        return true;
+    }
+    attributes.get("style").is_some_and(|style| {
+        style.split(';').filter_map(|decl| decl.split_once(':')).any(|(property, value)| {
+            let value = value.trim().to_ascii_lowercase();
+            property.trim().eq_ignore_ascii_case("white-space")
+                && (value.starts_with("pre") || value.starts_with("break-spaces"))
+        })
+    })
+}
+
 // ── Public sink ───────────────────────────────────────────────────────────────
 
 /// A `TreeSink` that builds a `DocumentImpl<Building>` directly.
@@ -203,22 +373,54 @@ impl DocBuilderSink {
         }
     }
 
+    /// Enables collection of html5ever parse errors, returned by
+    /// [`finish_with_errors`](Self::finish_with_errors).
+    #[must_use]
+    pub fn collect_errors(self) -> Self {
+        self.inner.borrow_mut().errors = Some(Vec::new());
+        self
+    }
+
+    /// Stores text nodes as ranges of `source`, the input being parsed, wherever the
+    /// text appears in it verbatim.
+    #[must_use]
+    pub fn borrow_text(self, source: Arc<str>) -> Self {
+        self.inner.borrow_mut().source = Some(source);
+        self
+    }
+
     /// Consumes the sink and returns the finished document.
     ///
     /// # Errors
     ///
-    /// Returns `MaxDepthExceeded` if the HTML exceeded `config.max_depth`.
+    /// Returns `MaxDepthExceeded` if the HTML exceeded `config.max_depth`, or
+    /// `TooManyNodes` if it has more nodes than a document can hold.
     pub fn finish_document(self) -> ParseResult<crate::dom::Document> {
-        let inner = self.inner.into_inner();
-        if inner.depth_exceeded {
-            return Err(ParseError::MaxDepthExceeded {
-                max_depth: inner.config.max_depth,
-                span: None,
-            });
+        self.finish_with_errors().map(|(doc, _)| doc)
+    }
+
+    /// Consumes the sink and returns the finished document with the collected parse
+    /// errors and the 1-indexed lines they were reported on.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MaxDepthExceeded` if the HTML exceeded `config.max_depth`, or
+    /// `TooManyNodes` if it has more nodes than a document can hold.
+    pub fn finish_with_errors(self) -> ParseResult<(crate::dom::Document, LineErrors)> {
+        let mut inner = self.inner.into_inner();
+        let errors = inner.errors.take().unwrap_or_default();
+        if let Some(error) = inner.error() {
+            return Err(error);
         }
+        let index = if inner.rebuild_index {
+            DocumentIndex::from_document(&inner.document)
+        } else {
+            inner.index
+        };
+        inner.document.shrink_to_fit();
         let mut doc = inner.document.build();
-        doc.set_index(inner.index);
-        Ok(doc)
+        doc.set_index(index);
+        Ok((doc, errors))
     }
 }
 
@@ -245,8 +447,17 @@ impl TreeSink for DocBuilderSink {
         self
     }
 
-    fn parse_error(&self, _msg: Cow<'static, str>) {
-        // html5ever parse errors are informational; error recovery is automatic.
+    fn parse_error(&self, msg: Cow<'static, str>) {
+        // Error recovery is automatic; errors are only kept when asked for.
+        let mut inner = self.inner.borrow_mut();
+        let line = inner.current_line;
+        if let Some(errors) = &mut inner.errors {
+            errors.push((line, msg));
+        }
+    }
+
+    fn set_current_line(&self, line_number: u64) {
+        self.inner.borrow_mut().current_line = line_number;
     }
 
     fn get_document(&self) -> Self::Handle {
@@ -275,7 +486,7 @@ impl TreeSink for DocBuilderSink {
 
     fn create_comment(&self, text: StrTendril) -> Self::Handle {
         let mut inner = self.inner.borrow_mut();
-        if !inner.config.include_comments {
+        if !inner.config.include_comments || !inner.has_room_for(1) {
             return SinkHandle::Phantom;
         }
         let node_id = inner.document.create_comment(text.to_string());
@@ -291,14 +502,23 @@ impl TreeSink for DocBuilderSink {
         let mut inner = self.inner.borrow_mut();
         match child {
             NodeOrText::AppendText(text) => {
-                if !inner.config.preserve_whitespace && text.trim().is_empty() {
+                if !inner.config.preserve_whitespace
+                    && text.trim().is_empty()
+                    && !inner.keeps_whitespace(parent.node_id())
+                {
+                    // Whitespace still continues a text node, as the space of `&amp; chips`
+                    // that html5ever hands over on its own. It is copied: searching the
+                    // source for it could skip past the chunks that follow.
+                    if let Some(id) = parent.node_id() {
+                        inner.document.try_append_text_to_last_child(id, &text);
+                    }
                     return;
                 }
-                let merged = parent
-                    .node_id()
-                    .is_some_and(|id| inner.document.try_append_text_to_last_child(id, &text));
-                if !merged {
-                    let node_id = inner.document.create_text(text.to_string());
+                let merged =
+                    parent.node_id().is_some_and(|id| inner.append_text_to_last_child(id, &text));
+                if !merged && inner.has_room_for(1) {
+                    let content = inner.text_content(&text);
+                    let node_id = inner.document.create_text(content);
                     let _ = inner.attach(parent, node_id);
                 }
             }
@@ -318,20 +538,28 @@ impl TreeSink for DocBuilderSink {
 
         match new_node {
             NodeOrText::AppendText(text) => {
-                if !inner.config.preserve_whitespace && text.trim().is_empty() {
-                    return;
-                }
                 // Coalesce with prev_sibling if it is itself a text node (not its last child).
                 let prev = inner.document.get(sibling_id).and_then(|n| n.prev_sibling);
-                let merged = prev
-                    .is_some_and(|prev_id| inner.document.try_append_text_to_node(prev_id, &text));
-                if !merged {
-                    let node_id = inner.document.create_text(text.to_string());
+                if !inner.config.preserve_whitespace && text.trim().is_empty() {
+                    let parent = inner.document.get(sibling_id).and_then(|n| n.parent);
+                    if !inner.keeps_whitespace(parent) {
+                        // As in `append`, whitespace only continues a text node.
+                        if let Some(prev_id) = prev {
+                            inner.document.try_append_text_to_node(prev_id, &text);
+                        }
+                        return;
+                    }
+                }
+                let merged = prev.is_some_and(|prev_id| inner.append_text_to_node(prev_id, &text));
+                if !merged && inner.has_room_for(1) {
+                    let content = inner.text_content(&text);
+                    let node_id = inner.document.create_text(content);
                     inner.document.insert_before(sibling_id, node_id);
                 }
             }
             NodeOrText::AppendNode(handle) => {
                 let Some(node_id) = handle.node_id() else { return };
+                inner.rebuild_index = true;
                 if inner.document.get(node_id).and_then(|n| n.parent).is_some() {
                     inner.document.remove_from_parent(node_id);
                 }
@@ -368,15 +596,23 @@ impl TreeSink for DocBuilderSink {
 
     fn append_doctype_to_document(
         &self,
-        _name: StrTendril,
-        _public_id: StrTendril,
-        _system_id: StrTendril,
+        name: StrTendril,
+        public_id: StrTendril,
+        system_id: StrTendril,
     ) {
-        // Doctypes are not represented in our DOM.
+        let mut inner = self.inner.borrow_mut();
+        if !inner.has_room_for(1) {
+            return;
+        }
+        let node_id = inner.document.create_doctype(&*name, &*public_id, &*system_id);
+        let _ = inner.attach(&SinkHandle::Document, node_id);
     }
 
     fn get_template_contents(&self, target: &Self::Handle) -> Self::Handle {
         match target {
+            SinkHandle::Template(id, _) if self.inner.borrow().shadow_templates.contains(id) => {
+                SinkHandle::Node(*id)
+            }
             SinkHandle::Template(_, contents_id) => SinkHandle::Node(*contents_id),
             _ => panic!("get_template_contents called on non-template handle"),
         }
@@ -400,25 +636,26 @@ impl TreeSink for DocBuilderSink {
             && let NodeKind::Element { attributes, .. } = &mut node.kind
         {
             for attr in attrs {
-                let key = if attr.name.ns.is_empty() {
-                    attr.name.local.to_string()
-                } else {
-                    format!("{}:{}", attr.name.ns, attr.name.local)
-                };
-                attributes.entry(key).or_insert_with(|| attr.value.to_string());
+                let key = attribute_key(&attr);
+                if !attributes.contains_key(&key) {
+                    attributes.push(key, attr.value.to_string());
+                }
             }
         }
     }
 
     fn remove_from_parent(&self, target: &Self::Handle) {
         if let Some(node_id) = target.node_id() {
-            self.inner.borrow_mut().document.remove_from_parent(node_id);
+            let mut inner = self.inner.borrow_mut();
+            inner.rebuild_index = true;
+            inner.document.remove_from_parent(node_id);
         }
     }
 
     fn reparent_children(&self, node: &Self::Handle, new_parent: &Self::Handle) {
         let (Some(src), Some(dst)) = (node.node_id(), new_parent.node_id()) else { return };
         let mut inner = self.inner.borrow_mut();
+        inner.rebuild_index = true;
         inner.document.reparent_children(src, dst);
         // Recalculate depths for all moved children.
         let dst_depth = inner.depth_map.get(&dst).copied().unwrap_or(0);
@@ -434,6 +671,22 @@ impl TreeSink for DocBuilderSink {
             self.inner.borrow().mathml_annotation_integration_points.contains(&id)
         })
     }
+
+    fn attach_declarative_shadow(
+        &self,
+        location: &Self::Handle,
+        template: &Self::Handle,
+        _attrs: &[Attribute],
+    ) -> bool {
+        let mut inner = self.inner.borrow_mut();
+        let SinkHandle::Template(id, _) = template else { return false };
+        // Declined roots are inserted as ordinary templates by the tree builder.
+        if !inner.config.pierce_shadow || inner.attach(location, *id).is_err() {
+            return false;
+        }
+        inner.shadow_templates.insert(*id);
+        true
+    }
 }
 
 // ── Convenience functions ─────────────────────────────────────────────────────
@@ -447,49 +700,143 @@ pub fn parse_html_document(
     use html5ever::{ParseOpts, parse_document, tendril::TendrilSink};
 
     let sink = DocBuilderSink::new(config.clone(), capacity);
-    let sink = parse_document(sink, ParseOpts::default())
-        .from_utf8()
-        .read_from(&mut html.as_bytes())
-        .map_err(|e| ParseError::InternalError(e.to_string()))?;
+    let sink = if config.preserve_duplicate_attributes {
+        duplicate_attrs::parse_document(sink, ParseOpts::default(), html)
+    } else {
+        parse_document(sink, ParseOpts::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .map_err(|e| ParseError::InternalError(e.to_string()))?
+    };
+    sink.finish_document()
+}
+
+/// Parses a full HTML document like [`parse_html_document`], storing text nodes as
+/// ranges of `html` instead of copies wherever the text appears in it verbatim.
+pub fn parse_html_document_borrowed(
+    html: &Arc<str>,
+    config: &ParseConfig,
+    capacity: usize,
+) -> ParseResult<crate::dom::Document> {
+    use html5ever::{ParseOpts, parse_document, tendril::TendrilSink};
+
+    let sink = DocBuilderSink::new(config.clone(), capacity).borrow_text(Arc::clone(html));
+    let sink = if config.preserve_duplicate_attributes {
+        duplicate_attrs::parse_document(sink, ParseOpts::default(), html)
+    } else {
+        parse_document(sink, ParseOpts::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .map_err(|e| ParseError::InternalError(e.to_string()))?
+    };
     sink.finish_document()
 }
 
+/// Parses a full HTML document like [`parse_html_document`], collecting html5ever parse
+/// errors as warnings.
+///
+/// html5ever reports only the line of each error, so each warning's span covers its
+/// whole line.
+pub fn parse_html_document_with_warnings(
+    html: &str,
+    config: &ParseConfig,
+    capacity: usize,
+) -> ParseResult<ParseResultWithWarnings<crate::dom::Document>> {
+    use html5ever::{
+        ParseOpts, parse_document, tendril::TendrilSink, tree_builder::TreeBuilderOpts,
+    };
+
+    let opts = ParseOpts {
+        tree_builder: TreeBuilderOpts { exact_errors: true, ..TreeBuilderOpts::default() },
+        ..ParseOpts::default()
+    };
+    let sink = DocBuilderSink::new(config.clone(), capacity).collect_errors();
+    let sink = if config.preserve_duplicate_attributes {
+        duplicate_attrs::parse_document(sink, opts, html)
+    } else {
+        parse_document(sink, opts)
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .map_err(|e| ParseError::InternalError(e.to_string()))?
+    };
+    let (doc, errors) = sink.finish_with_errors()?;
+
+    let line_starts: Vec<usize> =
+        std::iter::once(0).chain(html.match_indices('\n').map(|(i, _)| i + 1)).collect();
+    let warnings = errors
+        .into_iter()
+        .map(|(line, message)| {
+            let line = usize::try_from(line).unwrap_or(usize::MAX).clamp(1, line_starts.len());
+            let start = line_starts[line - 1];
+            let end = line_starts.get(line).map_or(html.len(), |&next| next - 1);
+            let columns = html[start..end].trim_end_matches('\r').chars().count();
+            let span = SourceSpan::new(
+                SourcePosition::new(line, 1, start),
+                SourcePosition::new(line, columns + 1, end),
+            );
+            ParseWarning::new(WarningSeverity::RecoveredError, message).with_span(span)
+        })
+        .collect();
+    Ok(ParseResultWithWarnings::new(doc, warnings))
+}
+
 /// Builds a `DocBuilderSink`, parses an HTML fragment, and returns the result.
 pub fn parse_html_fragment(
     html: &str,
     context: &str,
     config: &ParseConfig,
 ) -> ParseResult<crate::dom::Document> {
+    parse_html_fragment_nodes(html, context, config).map(|(doc, _)| doc)
+}
+
+/// Parses an HTML fragment like [`parse_html_fragment`] and also returns the
+/// fragment's top-level nodes in order.
+pub fn parse_html_fragment_nodes(
+    html: &str,
+    context: &str,
+    config: &ParseConfig,
+) -> ParseResult<(crate::dom::Document, Vec<NodeId>)> {
     use html5ever::{ParseOpts, parse_fragment as html5ever_parse_fragment, tendril::TendrilSink};
     use markup5ever::QualName;
 
-    let context_name =
-        QualName::new(None, html5ever::ns!(html), html5ever::LocalName::from(context));
+    // Tag names are matched in lowercase, so `"TBODY"` gives the same table rules.
+    let context_name = QualName::new(
+        None,
+        html5ever::ns!(html),
+        html5ever::LocalName::from(context.to_ascii_lowercase()),
+    );
 
     let sink = DocBuilderSink::new(config.clone(), 64);
-    let sink = html5ever_parse_fragment(sink, ParseOpts::default(), context_name, vec![], false)
-        .from_utf8()
-        .read_from(&mut html.as_bytes())
-        .map_err(|e| ParseError::InternalError(e.to_string()))?;
+    let sink = if config.preserve_duplicate_attributes {
+        duplicate_attrs::parse_fragment(sink, ParseOpts::default(), context_name, html)
+    } else {
+        html5ever_parse_fragment(sink, ParseOpts::default(), context_name, vec![], false)
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .map_err(|e| ParseError::InternalError(e.to_string()))?
+    };
 
     finish_fragment(sink, config)
 }
 
 /// Finalises a fragment sink by unwrapping the html/body wrappers that
 /// html5ever adds around fragment content, then building the final `Document`.
+///
+/// Also returns the fragment's top-level nodes.
 fn finish_fragment(
     sink: DocBuilderSink,
     config: &ParseConfig,
-) -> ParseResult<crate::dom::Document> {
-    let inner = sink.inner.into_inner();
-    if inner.depth_exceeded {
-        return Err(ParseError::MaxDepthExceeded { max_depth: inner.config.max_depth, span: None });
+) -> ParseResult<(crate::dom::Document, Vec<NodeId>)> {
+    let mut inner = sink.inner.into_inner();
+    if let Some(error) = inner.error() {
+        return Err(error);
     }
+    inner.document.shrink_to_fit();
     let mut doc = inner.document.build();
     doc.set_index(inner.index);
 
     // html5ever fragment parsing wraps the content in <html><body>; unwrap it.
-    let Some(root) = doc.root() else { return Ok(doc) };
+    let Some(root) = doc.root() else { return Ok((doc, Vec::new())) };
 
     let body = unwrap_single_element_child(&doc, root, "html")
         .and_then(|html_id| unwrap_single_element_child(&doc, html_id, "body"))
@@ -502,6 +849,7 @@ fn finish_fragment(
         .filter(|&id| {
             doc.get(id).is_some_and(|node| match &node.kind {
                 NodeKind::Comment { .. } => config.include_comments,
+                NodeKind::Doctype { .. } => false,
                 NodeKind::Text { content } => {
                     config.preserve_whitespace || !content.trim().is_empty()
                 }
@@ -511,16 +859,21 @@ fn finish_fragment(
         .collect();
 
     if real_children.is_empty() {
-        return Ok(crate::dom::DocumentImpl::default());
+        return Ok((crate::dom::DocumentImpl::default(), Vec::new()));
     }
 
-    if real_children.len() == 1 {
+    let nodes = if real_children.len() == 1 {
         doc.set_root(real_children[0]);
+        real_children
     } else {
         doc.set_root(fragment_root);
-    }
+        doc.children(fragment_root).collect()
+    };
+    // Re-rooting leaves the html/body wrappers outside the tree; index what remains.
+    let index = DocumentIndex::from_document(&doc);
+    doc.set_index(index);
 
-    Ok(doc)
+    Ok((doc, nodes))
 }
 
 /// Returns the first child of `parent` that is an element named `tag`.
@@ -564,6 +917,20 @@ mod tests {
         assert_eq!(h.template_contents_id(), Some(contents));
     }
 
+    #[test]
+    fn test_too_many_nodes() {
+        use html5ever::{ParseOpts, parse_document, tendril::TendrilSink};
+
+        let parse = |max_nodes| {
+            let sink = DocBuilderSink::new(ParseConfig::default(), 16);
+            sink.inner.borrow_mut().max_nodes = max_nodes;
+            parse_document(sink, ParseOpts::default()).one("<p>a</p><p>b</p>").finish_document()
+        };
+        // html, head, body, and two paragraphs with their text.
+        assert_eq!(parse(7).unwrap().len(), 7);
+        assert!(matches!(parse(6), Err(ParseError::TooManyNodes { max_nodes: 6 })));
+    }
+
     #[test]
     fn test_same_node() {
         let sink = DocBuilderSink::new(ParseConfig::default(), 16);
@@ -9,14 +9,24 @@ fn test_parse_config_default() {
     assert_eq!(config.max_depth, 512);
     assert!(!config.preserve_whitespace);
     assert!(!config.include_comments);
+    assert!(!config.pierce_shadow);
+    assert!(!config.preserve_duplicate_attributes);
 }
 
 #[test]
 fn test_parse_config_custom() {
-    let config = ParseConfig { max_depth: 256, preserve_whitespace: true, include_comments: true };
+    let config = ParseConfig {
+        max_depth: 256,
+        preserve_whitespace: true,
+        include_comments: true,
+        pierce_shadow: true,
+        preserve_duplicate_attributes: true,
+    };
     assert_eq!(config.max_depth, 256);
     assert!(config.preserve_whitespace);
     assert!(config.include_comments);
+    assert!(config.pierce_shadow);
+    assert!(config.preserve_duplicate_attributes);
 }
 
 #[test]
@@ -117,9 +127,9 @@ fn test_parse_preserves_attributes() {
     let root_id = doc.root().unwrap();
     let anchor = find_anchor(&doc, root_id).expect("Should find anchor element");
     if let NodeKind::Element { attributes, .. } = &anchor.kind {
-        assert_eq!(attributes.get("href"), Some(&"https://example.com".to_string()));
-        assert_eq!(attributes.get("class"), Some(&"link".to_string()));
-        assert_eq!(attributes.get("data-id"), Some(&"123".to_string()));
+        assert_eq!(attributes.get("href"), Some("https://example.com"));
+        assert_eq!(attributes.get("class"), Some("link"));
+        assert_eq!(attributes.get("data-id"), Some("123"));
     } else {
         panic!("Expected element node");
     }
@@ -160,6 +170,15 @@ fn test_parse_skips_whitespace_text() {
     assert!(!has_whitespace_only_text, "Should not include whitespace-only text");
 }
 
+#[test]
+fn test_parse_keeps_whitespace_after_character_reference() {
+    let html = "<p>Fish &amp; chips</p><p>&lt;\n&gt;</p>";
+    for soup in [crate::Soup::parse(html), crate::Soup::parse_borrowed(html)] {
+        let texts: Vec<_> = soup.find_all("p").unwrap().iter().map(crate::Tag::text).collect();
+        assert_eq!(texts, ["Fish & chips", "<\n>"]);
+    }
+}
+
 #[test]
 fn test_parse_preserves_whitespace_when_configured() {
     let parser = Html5everParser;
@@ -173,6 +192,22 @@ fn test_parse_preserves_whitespace_when_configured() {
     assert!(has_text_with_spaces, "Should preserve whitespace in text");
 }
 
+#[test]
+fn test_parse_keeps_significant_whitespace() {
+    let html = "<pre><b>fn</b> <i>main</i>()\n\n<code>  </code></pre><textarea>   </textarea><div \
+                style=\"color: red; White-Space: \
+                pre-wrap\"><span>a</span>\n<span>b</span></div><p><span>x</span> \
+                <span>y</span></p>";
+    let soup = crate::Soup::parse(html);
+    let text = |selector| soup.find(selector).unwrap().unwrap().text();
+
+    assert_eq!(text("pre"), "fn main()\n\n  ");
+    assert_eq!(text("code"), "  ");
+    assert_eq!(text("textarea"), "   ");
+    assert_eq!(text("div"), "a\nb");
+    assert_eq!(text("p"), "xy", "whitespace outside preserving elements is still dropped");
+}
+
 #[test]
 fn test_parse_captures_text_content() {
     let parser = Html5everParser;
@@ -1,5 +1,7 @@
 //! Parse warning collection for developer experience.
 
+use std::fmt;
+
 use crate::error::SourceSpan;
 
 /// Severity level for parse warnings.
@@ -48,6 +50,16 @@ impl ParseWarning {
     }
 }
 
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        if let Some(span) = &self.span {
+            write!(f, " at line {}, column {}", span.start.line, span.start.column)?;
+        }
+        Ok(())
+    }
+}
+
 /// Result of parsing with warnings collected.
 #[derive(Debug)]
 pub struct ParseResultWithWarnings<T> {
@@ -100,6 +112,16 @@ mod tests {
         assert_eq!(warning.span.unwrap(), span);
     }
 
+    #[test]
+    fn test_warning_display() {
+        let warning = ParseWarning::new(WarningSeverity::RecoveredError, "Unexpected token");
+        assert_eq!(warning.to_string(), "Unexpected token");
+
+        let span = SourceSpan::new(SourcePosition::new(3, 1, 20), SourcePosition::new(3, 9, 28));
+        let warning = warning.with_span(span);
+        assert_eq!(warning.to_string(), "Unexpected token at line 3, column 1");
+    }
+
     #[test]
     fn test_warning_with_spec_reference() {
         let warning = ParseWarning::new(WarningSeverity::Info, "test")
@@ -0,0 +1,264 @@
+//! XML parser implementation.
+
+use super::{ParseConfig, ParseError, ParseResult, Parser, private::Sealed};
+use crate::{
+    dom::{Attributes, Building, Document, DocumentImpl, DocumentIndex, Namespace, NodeId},
+    error::{SourcePosition, SourceSpan},
+    xml::{Token, XmlError, XmlReader},
+};
+
+/// Namespace bound to the `xml` prefix in every document.
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Parser for XML documents such as feeds, sitemaps, and XHTML.
+///
+/// Unlike HTML parsing, tag and attribute names keep their case, `<tag/>` closes any
+/// element, and CDATA sections become text. Names are kept as written, prefix included,
+/// so `<dc:creator>` is found with the selector `dc\:creator`; each element's
+/// [`Namespace`](crate::Namespace) comes from the `xmlns` declarations in scope, so
+/// XHTML elements are HTML elements and anything else is
+/// [`Namespace::Other`](crate::Namespace::Other).
+///
+/// This is a lenient reader rather than a validating one: end tags are not matched
+/// against start tags, and elements still open at the end of the input are closed.
+/// Comments, processing instructions, and the doctype are skipped, so
+/// [`ParseConfig::include_comments`] has no effect, and
+/// [`ParseConfig::pierce_shadow`] does not apply.
+///
+/// # Example
+///
+/// ```rust
+/// use scrape_core::{Parser, XmlParser};
+///
+/// let document = XmlParser.parse("<rss><channel><pubDate/></channel></rss>").unwrap();
+/// let root = document.root().unwrap();
+/// assert_eq!(document.get(root).unwrap().kind.tag_name(), Some("rss"));
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XmlParser;
+
+impl Sealed for XmlParser {}
+
+impl Parser for XmlParser {
+    fn parse_with_config(&self, xml: &str, config: &ParseConfig) -> ParseResult<Document> {
+        if xml.trim().is_empty() {
+            return Err(ParseError::EmptyInput);
+        }
+
+        let mut reader = XmlReader::new(Box::new(xml.as_bytes()));
+        let mut document = DocumentImpl::<Building>::with_capacity(256);
+        let mut index = DocumentIndex::new();
+        // Open elements, each with the number of namespace bindings in scope before it.
+        let mut open: Vec<(NodeId, usize)> = Vec::new();
+        let mut scope = vec![("xml".to_string(), XML_NAMESPACE.to_string())];
+
+        loop {
+            let offset = reader.position();
+            let token = reader.next_token(true).map_err(|e| match e {
+                XmlError::Io(message) => ParseError::InternalError(message),
+                XmlError::Syntax { offset, message } => {
+                    ParseError::MalformedXml { message, span: Some(span_at(xml, offset)) }
+                }
+            })?;
+            match token {
+                None => break,
+                Some(Token::Start { name, attrs, empty }) => {
+                    let parent = open.last().map(|&(id, _)| id);
+                    if parent.is_none() && document.root().is_some() {
+                        return Err(ParseError::MalformedXml {
+                            message: "multiple root elements".into(),
+                            span: Some(span_at(xml, offset)),
+                        });
+                    }
+                    if open.len() >= config.max_depth {
+                        return Err(ParseError::MaxDepthExceeded {
+                            max_depth: config.max_depth,
+                            span: Some(span_at(xml, offset)),
+                        });
+                    }
+                    check_node_count(&document)?;
+
+                    let outer_scope = scope.len();
+                    let mut attributes = Attributes::with_capacity(attrs.len());
+                    for (key, value) in attrs {
+                        if key == "xmlns" {
+                            scope.push((String::new(), value.clone()));
+                        } else if let Some(prefix) = key.strip_prefix("xmlns:") {
+                            scope.push((prefix.to_string(), value.clone()));
+                        }
+                        if config.preserve_duplicate_attributes || !attributes.contains_key(&key) {
+                            attributes.push(key, value);
+                        }
+                    }
+                    let prefix = name.split_once(':').map_or("", |(prefix, _)| prefix);
+                    let namespace = scope
+                        .iter()
+                        .rev()
+                        .find(|(bound, _)| bound == prefix)
+                        .and_then(|(_, url)| Namespace::from_url(url))
+                        .unwrap_or(Namespace::Other);
+
+                    let id = document.create_element_ns(name, attributes, namespace);
+                    if let Some(node) = document.get(id) {
+                        index.register_node(&node.kind, id);
+                    }
+                    match parent {
+                        Some(parent) => document.append_child(parent, id),
+                        None => document.set_root(id),
+                    }
+                    if empty {
+                        scope.truncate(outer_scope);
+                    } else {
+                        open.push((id, outer_scope));
+                    }
+                }
+                Some(Token::End) => {
+                    if let Some((_, outer_scope)) = open.pop() {
+                        scope.truncate(outer_scope);
+                    }
+                }
+                Some(Token::Text(bytes)) => {
+                    let Some(&(parent, _)) = open.last() else { continue };
+                    // Text around a CDATA section joins it in one node.
+                    let text = String::from_utf8_lossy(&bytes);
+                    if document.try_append_text_to_last_child(parent, &text)
+                        || (!config.preserve_whitespace && text.trim().is_empty())
+                    {
+                        continue;
+                    }
+                    check_node_count(&document)?;
+                    let id = document.create_text(text.into_owned());
+                    document.append_child(parent, id);
+                }
+            }
+        }
+
+        document.shrink_to_fit();
+        let mut document = document.build();
+        document.set_index(index);
+        Ok(document)
+    }
+}
+
+/// Returns an empty span at byte `offset` of `xml`.
+fn span_at(xml: &str, offset: u64) -> SourceSpan {
+    let offset = usize::try_from(offset).unwrap_or(usize::MAX).min(xml.len());
+    let before = &xml.as_bytes()[..offset];
+    let line = memchr::memchr_iter(b'\n', before).count() + 1;
+    let line_start = memchr::memrchr(b'\n', before).map_or(0, |i| i + 1);
+    // Count characters by their first bytes.
+    let column = before[line_start..].iter().filter(|&&b| (b & 0xC0) != 0x80).count() + 1;
+    let position = SourcePosition::new(line, column, offset);
+    SourceSpan::new(position, position)
+}
+
+/// Returns `TooManyNodes` if `document` has no room for another node.
+fn check_node_count(document: &DocumentImpl<Building>) -> ParseResult<()> {
+    if document.len() < NodeId::MAX_COUNT {
+        Ok(())
+    } else {
+        Err(ParseError::TooManyNodes { max_nodes: NodeId::MAX_COUNT })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::NodeKind;
+
+    fn parse(xml: &str) -> ParseResult<Document> {
+        XmlParser.parse(xml)
+    }
+
+    fn text(doc: &Document, id: NodeId) -> String {
+        doc.descendants(id).filter_map(|id| doc.get(id)?.kind.as_text()).collect()
+    }
+
+    fn names(doc: &Document) -> Vec<&str> {
+        let root = doc.root().unwrap();
+        std::iter::once(root)
+            .chain(doc.descendants(root))
+            .filter_map(|id| doc.get(id)?.kind.tag_name())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_keeps_case_and_empty_elements() {
+        let doc = parse(
+            "<?xml version=\"1.0\"?>\n<!-- feed -->\n<rss><channel><link/><pubDate>Mon</pubDate>\
+             <link>https://example.com/</link></channel></rss>",
+        )
+        .unwrap();
+        assert_eq!(names(&doc), ["rss", "channel", "link", "pubDate", "link"]);
+        let links = doc.index().unwrap().get_by_tag("link");
+        assert_eq!(doc.first_child(links[0]), None);
+        assert_eq!(text(&doc, links[1]), "https://example.com/");
+        assert_eq!(doc.index().unwrap().get_by_tag("pubdate").len(), 1);
+    }
+
+    #[test]
+    fn test_parse_text_and_cdata() {
+        let doc = parse("<a>\n  <b>x &amp; <![CDATA[<y>]]> z</b>\n</a>").unwrap();
+        let root = doc.root().unwrap();
+        let b = doc.first_child(root).unwrap();
+        assert_eq!(doc.children(b).count(), 1);
+        assert_eq!(text(&doc, b), "x & <y> z");
+        assert_eq!(doc.children(root).count(), 1);
+
+        let config = ParseConfig { preserve_whitespace: true, ..ParseConfig::default() };
+        let doc = XmlParser.parse_with_config("<a>\n  <b/>\n</a>", &config).unwrap();
+        assert_eq!(doc.children(doc.root().unwrap()).count(), 3);
+    }
+
+    #[test]
+    fn test_parse_namespaces() {
+        let doc = parse(
+            "<feed xmlns=\"http://www.w3.org/2005/Atom\" xmlns:h=\"http://www.w3.org/1999/xhtml\">\
+             <h:div><svg xmlns=\"http://www.w3.org/2000/svg\"/></h:div><title/></feed>",
+        )
+        .unwrap();
+        let namespaces: Vec<_> = doc
+            .descendants(doc.root().unwrap())
+            .filter_map(|id| match &doc.get(id)?.kind {
+                NodeKind::Element { name, namespace, .. } => Some((name.as_str(), *namespace)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            namespaces,
+            [("h:div", Namespace::Html), ("svg", Namespace::Svg), ("title", Namespace::Other)]
+        );
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let doc = parse("<a Href=\"1\" Href=\"2\" b='x &lt; y'/>").unwrap();
+        let attrs = doc.get(doc.root().unwrap()).unwrap().kind.attributes().unwrap();
+        assert_eq!(attrs.get("Href"), Some("1"));
+        assert_eq!(attrs.get("b"), Some("x < y"));
+        assert_eq!(attrs.len(), 2);
+
+        let config = ParseConfig { preserve_duplicate_attributes: true, ..ParseConfig::default() };
+        let doc = XmlParser.parse_with_config("<a Href=\"1\" Href=\"2\"/>", &config).unwrap();
+        let attrs = doc.get(doc.root().unwrap()).unwrap().kind.attributes().unwrap();
+        assert_eq!(attrs.get_all("Href").collect::<Vec<_>>(), ["1", "2"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse(" \n"), Err(ParseError::EmptyInput)));
+
+        let err = parse("<a>\n  <b").unwrap_err();
+        assert_eq!(err.to_string(), "malformed XML: unterminated markup at line 2, column 3");
+
+        let err = parse("<a/>\n<b/>").unwrap_err();
+        assert_eq!(err.to_string(), "malformed XML: multiple root elements at line 2, column 1");
+
+        let config = ParseConfig { max_depth: 2, ..ParseConfig::default() };
+        let err = XmlParser.parse_with_config("<a><b><c/></b></a>", &config).unwrap_err();
+        assert!(matches!(err, ParseError::MaxDepthExceeded { max_depth: 2, .. }));
+
+        // Unclosed elements are closed at the end of the input.
+        assert_eq!(names(&parse("<a><b>text").unwrap()), ["a", "b"]);
+    }
+}
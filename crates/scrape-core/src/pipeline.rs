@@ -0,0 +1,414 @@
+//! Bulk URL → record pipelines.
+//!
+//! [`run`] takes a list of URLs and an extraction [`Schema`], and wires the whole job
+//! together: each page is fetched, parsed, and extracted on one of a fixed number of
+//! worker threads, and the finished records are handed to a [`Sink`] on the calling
+//! thread. Memory stays bounded however many URLs there are: at most
+//! [`PipelineOptions::concurrency`] pages are held at once, and at most
+//! [`PipelineOptions::buffer`] records wait for the sink, so a slow sink slows the workers
+//! down instead of letting records pile up. All workers have finished when [`run`]
+//! returns.
+//!
+//! scrape-core has no HTTP client, so pages come from a [`PageFetcher`], usually a closure
+//! around the caller's client. Sinks are closures, [`mpsc::Sender`] and
+//! [`mpsc::SyncSender`] channels, and, with the `serde` feature, [`NdjsonSink`].
+//!
+//! Records reach the sink in completion order; [`PipelineRecord::index`] gives the
+//! position of the URL in the input.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{
+//!     extract::{Field, Schema},
+//!     pipeline::{self, PipelineOptions, PipelineRecord},
+//! };
+//!
+//! let schema = Schema::new().field("title", Field::text("h1")?).require("title");
+//! let fetch = |url: &str| match url {
+//!     "https://example.com/tea" => Ok(String::from("<h1>Tea</h1>")),
+//!     "https://example.com/cake" => Ok(String::from("<p>No title</p>")),
+//!     _ => Err("404 Not Found"),
+//! };
+//! let urls = ["https://example.com/tea", "https://example.com/cake", "https://example.com/pie"];
+//!
+//! let mut records = Vec::new();
+//! let mut sink = |record: PipelineRecord| records.push(record);
+//! let summary =
+//!     pipeline::run(urls, &schema, &fetch, &PipelineOptions::default(), &mut sink).unwrap();
+//!
+//! assert_eq!((summary.records, summary.rejected, summary.failed), (1, 1, 1));
+//! records.sort_by_key(|record| record.index);
+//! assert_eq!(records[0].result.as_ref().unwrap().get("title").unwrap().as_str(), Some("Tea"));
+//! # Ok::<(), scrape_core::QueryError>(())
+//! ```
+
+use std::{
+    convert::Infallible,
+    fmt,
+    sync::{Mutex, PoisonError, mpsc},
+    thread,
+};
+
+use crate::{
+    Soup,
+    extract::{Schema, Value},
+};
+
+/// Downloads the pages of a pipeline.
+///
+/// Implemented for closures `Fn(&str) -> Result<String, E>`. Called from several worker
+/// threads at once.
+pub trait PageFetcher: Sync {
+    /// Error returned when a download fails.
+    type Error: fmt::Display;
+
+    /// Downloads the HTML of the page at `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the page cannot be downloaded.
+    fn fetch(&self, url: &str) -> Result<String, Self::Error>;
+}
+
+impl<F, E> PageFetcher for F
+where
+    F: Fn(&str) -> Result<String, E> + Sync,
+    E: fmt::Display,
+{
+    type Error = E;
+
+    fn fetch(&self, url: &str) -> Result<String, E> {
+        self(url)
+    }
+}
+
+/// Receives the records of a pipeline, one at a time, on the thread that called [`run`].
+///
+/// Implemented for closures `FnMut(PipelineRecord)`, for [`mpsc::Sender`] and
+/// [`mpsc::SyncSender`], and for [`NdjsonSink`].
+pub trait Sink {
+    /// Error that stops the pipeline.
+    type Error;
+
+    /// Accepts one finished record.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error to stop the pipeline; pages already being processed are dropped.
+    fn accept(&mut self, record: PipelineRecord) -> Result<(), Self::Error>;
+}
+
+impl<F: FnMut(PipelineRecord)> Sink for F {
+    type Error = Infallible;
+
+    fn accept(&mut self, record: PipelineRecord) -> Result<(), Infallible> {
+        self(record);
+        Ok(())
+    }
+}
+
+impl Sink for mpsc::Sender<PipelineRecord> {
+    type Error = mpsc::SendError<PipelineRecord>;
+
+    fn accept(&mut self, record: PipelineRecord) -> Result<(), Self::Error> {
+        self.send(record)
+    }
+}
+
+impl Sink for mpsc::SyncSender<PipelineRecord> {
+    type Error = mpsc::SendError<PipelineRecord>;
+
+    fn accept(&mut self, record: PipelineRecord) -> Result<(), Self::Error> {
+        self.send(record)
+    }
+}
+
+/// Writes every record as a line of JSON.
+///
+/// Each line holds the `url` and either the extracted `data`, the `rejected` reason, or
+/// the fetch `error`. Requires the `serde` feature.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub struct NdjsonSink<W> {
+    writer: W,
+}
+
+#[cfg(feature = "serde")]
+impl<W: std::io::Write> NdjsonSink<W> {
+    /// Creates a sink writing to `writer`.
+    #[must_use]
+    pub const fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Returns the underlying writer.
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<W: std::io::Write> Sink for NdjsonSink<W> {
+    type Error = std::io::Error;
+
+    fn accept(&mut self, record: PipelineRecord) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, &record)?;
+        std::io::Write::write_all(&mut self.writer, b"\n")
+    }
+}
+
+/// Options for [`run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PipelineOptions {
+    /// Number of worker threads, and so the maximum number of pages fetched and held at
+    /// once. `0` is treated as `1`.
+    pub concurrency: usize,
+    /// Maximum number of finished records waiting for the sink. With `0`, each worker
+    /// waits until the sink takes its record.
+    pub buffer: usize,
+}
+
+impl Default for PipelineOptions {
+    fn default() -> Self {
+        Self { concurrency: 4, buffer: 64 }
+    }
+}
+
+/// Why a page did not produce a record.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PipelineError {
+    /// The fetcher failed; holds its error message.
+    #[error("fetch failed: {0}")]
+    Fetch(String),
+    /// A [`Schema::require`] rule or validator rejected the page's record; holds the
+    /// reason.
+    #[error("record rejected: {0}")]
+    Rejected(String),
+}
+
+/// The outcome for one URL.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineRecord {
+    /// Position of the URL in the input.
+    pub index: usize,
+    /// The URL.
+    pub url: String,
+    /// The extracted record, or why there is none.
+    pub result: Result<Value, PipelineError>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PipelineRecord {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("url", &self.url)?;
+        match &self.result {
+            Ok(value) => map.serialize_entry("data", value)?,
+            Err(PipelineError::Rejected(reason)) => map.serialize_entry("rejected", reason)?,
+            Err(PipelineError::Fetch(message)) => map.serialize_entry("error", message)?,
+        }
+        map.end()
+    }
+}
+
+/// Counts of the outcomes of a pipeline run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineSummary {
+    /// Number of records extracted.
+    pub records: usize,
+    /// Number of records rejected by the schema.
+    pub rejected: usize,
+    /// Number of pages that could not be fetched.
+    pub failed: usize,
+}
+
+impl PipelineSummary {
+    fn count(&mut self, record: &PipelineRecord) {
+        match record.result {
+            Ok(_) => self.records += 1,
+            Err(PipelineError::Rejected(_)) => self.rejected += 1,
+            Err(PipelineError::Fetch(_)) => self.failed += 1,
+        }
+    }
+}
+
+/// Fetches every URL, extracts its record with `schema`, and hands it to `sink`.
+///
+/// Returns once every URL has been processed, or as soon as the sink fails.
+///
+/// # Errors
+///
+/// Returns the first error of `sink`. Failed fetches and rejected records are not errors;
+/// they reach the sink as [`PipelineError`]s.
+pub fn run<I, F, S>(
+    urls: I,
+    schema: &Schema,
+    fetcher: &F,
+    options: &PipelineOptions,
+    sink: &mut S,
+) -> Result<PipelineSummary, S::Error>
+where
+    I: IntoIterator,
+    I::Item: Into<String>,
+    I::IntoIter: Send,
+    F: PageFetcher,
+    S: Sink,
+{
+    let urls = Mutex::new(urls.into_iter().enumerate());
+    let (sender, receiver) = mpsc::sync_channel(options.buffer);
+
+    thread::scope(|scope| {
+        for _ in 0..options.concurrency.max(1) {
+            let sender = sender.clone();
+            let urls = &urls;
+            scope.spawn(move || {
+                loop {
+                    // A panicking worker leaves the iterator usable, so keep going.
+                    let next = urls.lock().unwrap_or_else(PoisonError::into_inner).next();
+                    let Some((index, url)) = next else { break };
+                    let url = url.into();
+                    let result = fetcher
+                        .fetch(&url)
+                        .map_err(|e| PipelineError::Fetch(e.to_string()))
+                        .and_then(|html| extract_page(&html, schema));
+                    // The receiver is gone once the sink has failed.
+                    if sender.send(PipelineRecord { index, url, result }).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let mut summary = PipelineSummary::default();
+        for record in receiver {
+            summary.count(&record);
+            sink.accept(record)?;
+        }
+        Ok(summary)
+    })
+}
+
+/// Parses `html` and extracts its record with `schema`: the parse and extract step of
+/// [`run`] for a page that is already downloaded.
+///
+/// # Errors
+///
+/// Returns [`PipelineError::Rejected`] if the schema rejects the record. Rejected nested
+/// records are only left out of their list.
+pub fn extract_page(html: &str, schema: &Schema) -> Result<Value, PipelineError> {
+    let (value, rejections) = schema.extract_with_rejections(&Soup::parse(html));
+    match rejections.into_iter().find(|rejection| rejection.field.is_empty()) {
+        Some(rejection) => Err(PipelineError::Rejected(rejection.reason)),
+        None => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::Field;
+
+    fn schema() -> Schema {
+        Schema::new().field("title", Field::text("h1").unwrap()).require("title")
+    }
+
+    fn fetch(url: &str) -> Result<String, String> {
+        match url.strip_prefix("page/") {
+            Some("missing") => Err(format!("no page at {url}")),
+            Some("untitled") => Ok("<p>Nothing</p>".into()),
+            Some(name) => Ok(format!("<h1>{name}</h1>")),
+            None => Err("bad url".into()),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_runs_every_url() {
+        let urls: Vec<String> = (0..50).map(|i| format!("page/{i}")).collect();
+        let options = PipelineOptions { concurrency: 3, buffer: 0 };
+        let mut records = Vec::new();
+        let mut sink = |record: PipelineRecord| records.push(record);
+        let summary = run(urls, &schema(), &fetch, &options, &mut sink).unwrap();
+
+        assert_eq!(summary, PipelineSummary { records: 50, rejected: 0, failed: 0 });
+        records.sort_by_key(|record| record.index);
+        for (i, record) in records.iter().enumerate() {
+            assert_eq!(record.url, format!("page/{i}"));
+            let title = record.result.as_ref().unwrap().get("title").cloned();
+            assert_eq!(title, Some(Value::from(i.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_pipeline_errors_reach_the_sink() {
+        let (sender, receiver) = mpsc::channel();
+        let mut sink = sender;
+        let summary = run(
+            ["page/missing", "page/untitled"],
+            &schema(),
+            &fetch,
+            &PipelineOptions::default(),
+            &mut sink,
+        )
+        .unwrap();
+        drop(sink);
+
+        assert_eq!(summary, PipelineSummary { records: 0, rejected: 1, failed: 1 });
+        let mut results: Vec<_> = receiver.into_iter().map(|record| record.result).collect();
+        results.sort_by_key(|result| matches!(result, Err(PipelineError::Rejected(_))));
+        assert_eq!(
+            results,
+            [
+                Err(PipelineError::Fetch("no page at page/missing".into())),
+                Err(PipelineError::Rejected("missing required field `title`".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pipeline_stops_on_sink_error() {
+        struct FailingSink(usize);
+
+        impl Sink for FailingSink {
+            type Error = String;
+
+            fn accept(&mut self, _: PipelineRecord) -> Result<(), String> {
+                self.0 += 1;
+                if self.0 == 2 { Err("disk full".into()) } else { Ok(()) }
+            }
+        }
+
+        let urls = (0..1000).map(|i| format!("page/{i}"));
+        let mut sink = FailingSink(0);
+        let err = run(urls, &schema(), &fetch, &PipelineOptions::default(), &mut sink);
+        assert_eq!(err, Err("disk full".to_string()));
+        assert_eq!(sink.0, 2);
+    }
+
+    #[test]
+    fn test_extract_page_keeps_records_with_rejected_children() {
+        let items = Schema::new().field("name", Field::text("b").unwrap()).require("name");
+        let schema = Schema::new().field("items", Field::group("li", items).unwrap().many());
+        let value = extract_page("<li><b>Tea</b></li><li>Cake</li>", &schema).unwrap();
+        assert_eq!(value.get("items").and_then(Value::as_list).map(<[Value]>::len), Some(1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_ndjson_sink() {
+        let mut sink = NdjsonSink::new(Vec::new());
+        let options = PipelineOptions { concurrency: 1, buffer: 1 };
+        run(["page/Tea", "page/untitled", "page/missing"], &schema(), &fetch, &options, &mut sink)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(sink.into_inner()).unwrap(),
+            "{\"url\":\"page/Tea\",\"data\":{\"title\":\"Tea\"}}\n{\"url\":\"page/untitled\",\"\
+             rejected\":\"missing required field \
+             `title`\"}\n{\"url\":\"page/missing\",\"error\":\"no page at page/missing\"}\n"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Process-wide cache of compiled selectors.
+//!
+//! String-based queries such as [`find`](super::find) and [`Soup::find_all`] compile their
+//! selector through [`cached_compile`], so repeating a selector skips parsing it again.
+//! On short documents compilation can cost more than matching, so this makes string
+//! queries nearly as fast as managing a [`CompiledSelector`] by hand.
+//!
+//! The cache is shared by all threads and evicts the least recently used selector once it
+//! holds [`SELECTOR_CACHE_CAPACITY`] entries; see [`set_selector_cache_capacity`].
+//!
+//! [`Soup::find_all`]: crate::Soup::find_all
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use super::{CompiledSelector, QueryResult};
+
+/// The number of selectors the cache holds unless changed with
+/// [`set_selector_cache_capacity`].
+pub const SELECTOR_CACHE_CAPACITY: usize = 256;
+
+static CACHE: Mutex<SelectorCache> = Mutex::new(SelectorCache::new(SELECTOR_CACHE_CAPACITY));
+
+/// Least recently used map from selector source to compiled selector.
+struct SelectorCache {
+    entries: Option<HashMap<String, Entry>>,
+    capacity: usize,
+    clock: u64,
+}
+
+struct Entry {
+    selector: Arc<CompiledSelector>,
+    last_used: u64,
+}
+
+impl SelectorCache {
+    const fn new(capacity: usize) -> Self {
+        Self { entries: None, capacity, clock: 0 }
+    }
+
+    fn get(&mut self, source: &str) -> Option<Arc<CompiledSelector>> {
+        self.clock += 1;
+        let entry = self.entries.as_mut()?.get_mut(source)?;
+        entry.last_used = self.clock;
+        Some(Arc::clone(&entry.selector))
+    }
+
+    fn insert(&mut self, selector: Arc<CompiledSelector>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.evict_to(self.capacity - 1);
+        self.clock += 1;
+        let source = selector.source().to_string();
+        let entry = Entry { selector, last_used: self.clock };
+        self.entries.get_or_insert_with(HashMap::new).insert(source, entry);
+    }
+
+    /// Removes least recently used entries until at most `len` remain.
+    fn evict_to(&mut self, len: usize) {
+        let Some(entries) = self.entries.as_mut() else { return };
+        while entries.len() > len {
+            let oldest = entries.iter().min_by_key(|(_, entry)| entry.last_used);
+            let Some(oldest) = oldest.map(|(source, _)| source.clone()) else { break };
+            entries.remove(&oldest);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.as_ref().map_or(0, HashMap::len)
+    }
+}
+
+fn cache() -> MutexGuard<'static, SelectorCache> {
+    // The cache holds no invariants a panicking thread could break.
+    CACHE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Compiles a CSS selector, reusing the result of an earlier call with the same string.
+///
+/// Invalid selectors are not cached, so they report their error on every call.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+/// syntax is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use scrape_core::query::cached_compile;
+///
+/// let first = cached_compile("ul > li.item").unwrap();
+/// let second = cached_compile("ul > li.item").unwrap();
+/// assert!(Arc::ptr_eq(&first, &second));
+/// ```
+pub fn cached_compile(selector: &str) -> QueryResult<Arc<CompiledSelector>> {
+    let cached = cache().get(selector);
+    if let Some(compiled) = cached {
+        return Ok(compiled);
+    }
+    // Compile without holding the lock; a concurrent miss on the same selector just
+    // compiles it twice.
+    let compiled = Arc::new(CompiledSelector::compile(selector)?);
+    cache().insert(Arc::clone(&compiled));
+    Ok(compiled)
+}
+
+/// Sets how many selectors the cache holds, evicting the least recently used ones if it
+/// already holds more. A capacity of `0` disables caching.
+pub fn set_selector_cache_capacity(capacity: usize) {
+    let mut cache = cache();
+    cache.capacity = capacity;
+    cache.evict_to(capacity);
+}
+
+/// Removes every selector from the cache.
+pub fn clear_selector_cache() {
+    cache().entries = None;
+}
+
+/// Returns the number of selectors in the cache.
+#[must_use]
+pub fn selector_cache_len() -> usize {
+    cache().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The global cache is shared with every other test, so these use a private one.
+
+    fn compiled(source: &str) -> Arc<CompiledSelector> {
+        Arc::new(CompiledSelector::compile(source).unwrap())
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut cache = SelectorCache::new(2);
+        cache.insert(compiled("a"));
+        cache.insert(compiled("b"));
+        assert!(cache.get("a").is_some());
+        cache.insert(compiled("c"));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+
+        cache.capacity = 0;
+        cache.evict_to(0);
+        cache.insert(compiled("d"));
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn test_cached_compile() {
+        let first = cached_compile("section > p.cached-compile-test").unwrap();
+        let second = cached_compile("section > p.cached-compile-test").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(selector_cache_len() >= 1);
+        assert!(cached_compile("[[[").is_err());
+    }
+}
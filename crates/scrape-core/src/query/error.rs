@@ -22,6 +22,13 @@ pub enum QueryError {
         /// Source location, if available.
         span: Option<SourceSpan>,
     },
+
+    /// Invalid XPath expression, or one that failed to evaluate.
+    #[error("invalid XPath expression: {message}")]
+    InvalidXPath {
+        /// Description of the problem.
+        message: String,
+    },
 }
 
 fn format_position(span: Option<&SourceSpan>) -> String {
@@ -49,11 +56,18 @@ impl QueryError {
         }
     }
 
+    /// Creates a new invalid XPath error.
+    #[must_use]
+    pub fn invalid_xpath(message: impl Into<String>) -> Self {
+        Self::InvalidXPath { message: message.into() }
+    }
+
     /// Returns the source span if available.
     #[must_use]
     pub fn span(&self) -> Option<&SourceSpan> {
         match self {
             Self::InvalidSelector { span, .. } => span.as_ref(),
+            Self::InvalidXPath { .. } => None,
         }
     }
 
@@ -1,7 +1,20 @@
 //! Selector explanation and performance analysis.
 
-use super::{CompiledSelector, QueryResult, specificity::Specificity};
-use crate::dom::Document;
+use std::time::{Duration, Instant};
+
+use cssparser::ToCss;
+use selectors::parser::{Combinator, Component, Selector};
+
+use super::{
+    CompiledSelector, QueryResult, ScrapeSelector,
+    find::{is_simple_selector, is_simple_tag},
+    find_all_compiled,
+    specificity::Specificity,
+};
+use crate::dom::{Document, NodeId};
+
+/// Number of timed runs per selector when measuring a rewrite; the fastest run is kept.
+const SAMPLE_RUNS: usize = 5;
 
 /// Performance hint for selector optimization.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -29,6 +42,67 @@ pub enum OptimizationHint {
     AvoidUniversalSelector,
     /// Consider caching this compiled selector.
     CacheSelector,
+    /// A simpler selector returns the same elements on the analyzed document.
+    SimplerSelector {
+        /// Suggested selector.
+        suggested: String,
+    },
+}
+
+/// How a string query such as [`Soup::find`](crate::Soup::find) uses the document index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexUsage {
+    /// Looked up directly in the id index.
+    Id(String),
+    /// Looked up directly in the class index.
+    Class(String),
+    /// Looked up directly in the tag name index.
+    Tag(String),
+    /// Every element is tested against the selector.
+    Scan,
+}
+
+/// Candidate counts for one compound selector, measured against a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompoundStats {
+    /// The compound selector, such as `ul.nav`.
+    pub compound: String,
+    /// Elements matching the compound on its own.
+    pub matches: usize,
+    /// Elements the compound rules out.
+    pub eliminated: usize,
+}
+
+/// A simpler selector that returns the same elements on the analyzed document.
+///
+/// Equivalence is only checked against that document, so review the suggestion before
+/// using it on other pages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewriteSuggestion {
+    /// The rewritten selector.
+    pub selector: String,
+    /// Fastest of several runs of the original selector.
+    pub original_time: Duration,
+    /// Fastest of several runs of the rewritten selector.
+    pub rewritten_time: Duration,
+    /// `original_time / rewritten_time`; above 1.0 means the rewrite is faster.
+    pub speedup: f64,
+}
+
+/// Measurements from running a selector against a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentAnalysis {
+    /// Number of elements in the document.
+    pub element_count: usize,
+    /// Per-compound candidate counts, left to right, for each selector in the list.
+    pub compounds: Vec<CompoundStats>,
+    /// Index into [`compounds`](Self::compounds) of the compound that rules out the most
+    /// elements.
+    pub most_selective: Option<usize>,
+    /// Whether a string query for this selector uses the id or class index.
+    pub index: IndexUsage,
+    /// A simpler selector with identical results on this document, if one was found.
+    pub rewrite: Option<RewriteSuggestion>,
 }
 
 /// Detailed explanation of a CSS selector.
@@ -46,6 +120,8 @@ pub struct SelectorExplanation {
     pub hints: Vec<OptimizationHint>,
     /// Estimated match count (if document provided).
     pub estimated_matches: Option<usize>,
+    /// Document-driven measurements (if document provided).
+    pub document: Option<DocumentAnalysis>,
 }
 
 impl SelectorExplanation {
@@ -68,14 +144,59 @@ impl SelectorExplanation {
             performance_notes,
             hints,
             estimated_matches: None,
+            document: None,
         }
     }
 
-    /// Creates explanation with estimated match count from a document.
+    /// Creates explanation with match counts and data-driven hints from a document.
+    ///
+    /// Besides the match count, this reports how many elements each compound rules out,
+    /// whether the id or class index is used, and a simpler selector that returns the same
+    /// elements, timed against the original on the document.
     #[must_use]
     pub fn analyze_with_document(selector: &CompiledSelector, doc: &Document) -> Self {
         let mut explanation = Self::analyze(selector);
-        explanation.estimated_matches = Some(count_matches(selector, doc));
+        let matches = find_all_compiled(doc, selector);
+        let analysis = analyze_document(selector, doc, &matches);
+
+        if let Some(i) = analysis.most_selective {
+            let stats = &analysis.compounds[i];
+            explanation.performance_notes.push(format!(
+                "Most selective compound: '{}' rules out {} of {} elements",
+                stats.compound, stats.eliminated, analysis.element_count
+            ));
+        }
+        explanation.performance_notes.push(match &analysis.index {
+            IndexUsage::Id(id) => format!("Uses the id index for '{id}'"),
+            IndexUsage::Class(class) => format!("Uses the class index for '{class}'"),
+            IndexUsage::Tag(tag) => format!("Uses the tag index for '{tag}'"),
+            IndexUsage::Scan => {
+                format!("No index lookup - all {} elements are tested", analysis.element_count)
+            }
+        });
+        if let Some(rewrite) = &analysis.rewrite {
+            explanation.performance_notes.push(format!(
+                "'{}' returns the same {} elements in {:?} vs {:?} ({:.1}x)",
+                rewrite.selector,
+                matches.len(),
+                rewrite.rewritten_time,
+                rewrite.original_time,
+                rewrite.speedup
+            ));
+            explanation.hints.push(
+                if rewrite.selector.starts_with('#') && !selector.source().starts_with('#') {
+                    OptimizationHint::UseIdSelector {
+                        current: selector.source().to_string(),
+                        suggested: rewrite.selector.clone(),
+                    }
+                } else {
+                    OptimizationHint::SimplerSelector { suggested: rewrite.selector.clone() }
+                },
+            );
+        }
+
+        explanation.estimated_matches = Some(matches.len());
+        explanation.document = Some(analysis);
         explanation
     }
 
@@ -119,34 +240,65 @@ impl SelectorExplanation {
 // Internal functions for analysis
 
 fn calculate_specificity(selector: &CompiledSelector) -> Specificity {
-    use selectors::parser::Component;
+    selector.selector_list().slice().iter().fold(Specificity::default(), |total, sel| {
+        let s = selector_specificity(sel);
+        Specificity::new(total.ids + s.ids, total.classes + s.classes, total.elements + s.elements)
+    })
+}
 
+/// Specificity of one complex selector.
+///
+/// Per Selectors Level 4, `:not()`, `:is()`, and `:has()` take the specificity of their
+/// most specific argument and `:where()` contributes nothing. `:nth-child(An+B of S)` counts
+/// as a pseudo-class plus the most specific selector in `S`.
+fn selector_specificity(selector: &Selector<ScrapeSelector>) -> Specificity {
     let mut ids = 0u32;
     let mut classes = 0u32;
     let mut elements = 0u32;
 
-    for sel in selector.selector_list().slice() {
-        for component in sel.iter_raw_parse_order_from(0) {
-            match component {
-                Component::ID(_) => ids += 1,
-                Component::Class(_)
-                | Component::AttributeInNoNamespace { .. }
-                | Component::AttributeInNoNamespaceExists { .. }
-                | Component::AttributeOther(_)
-                | Component::NonTSPseudoClass(_)
-                | Component::Negation(_)
-                | Component::Is(_)
-                | Component::Where(_)
-                | Component::Has(_) => classes += 1,
-                Component::LocalName(_) | Component::PseudoElement(_) => elements += 1,
-                _ => {}
+    for component in selector.iter_raw_parse_order_from(0) {
+        match component {
+            Component::ID(_) => ids += 1,
+            Component::Class(_)
+            | Component::AttributeInNoNamespace { .. }
+            | Component::AttributeInNoNamespaceExists { .. }
+            | Component::AttributeOther(_)
+            | Component::NonTSPseudoClass(_)
+            | Component::Nth(_) => classes += 1,
+            Component::Negation(list) | Component::Is(list) => {
+                let inner = max_specificity(list.slice());
+                ids += inner.ids;
+                classes += inner.classes;
+                elements += inner.elements;
             }
+            Component::NthOf(data) => {
+                let inner = max_specificity(data.selectors());
+                ids += inner.ids;
+                classes += inner.classes + 1;
+                elements += inner.elements;
+            }
+            Component::Has(relative) => {
+                let inner = relative
+                    .iter()
+                    .map(|r| selector_specificity(&r.selector))
+                    .max()
+                    .unwrap_or_default();
+                ids += inner.ids;
+                classes += inner.classes;
+                elements += inner.elements;
+            }
+            Component::LocalName(_) | Component::PseudoElement(_) => elements += 1,
+            _ => {}
         }
     }
 
     Specificity::new(ids, classes, elements)
 }
 
+fn max_specificity(selectors: &[Selector<ScrapeSelector>]) -> Specificity {
+    selectors.iter().map(selector_specificity).max().unwrap_or_default()
+}
+
 fn generate_description(selector: &CompiledSelector) -> String {
     let source = selector.source();
 
@@ -192,9 +344,14 @@ fn analyze_performance(selector: &CompiledSelector) -> (Vec<String>, Vec<Optimiz
         });
     }
 
-    // Check for ID selector (fast path)
-    if source.starts_with('#') && !source.contains(' ') && !source.contains('>') {
-        notes.push("ID selector - uses fast indexed lookup".to_string());
+    // Check for selectors answered from the document index (fast path)
+    let indexed = match (source.strip_prefix('#'), source.strip_prefix('.')) {
+        (Some(id), _) => is_simple_selector(id).then_some("ID"),
+        (_, Some(class)) => is_simple_selector(class).then_some("Class"),
+        _ => is_simple_tag(source).then_some("Tag"),
+    };
+    if let Some(kind) = indexed {
+        notes.push(format!("{kind} selector - uses fast indexed lookup"));
         hints.push(OptimizationHint::Optimal);
     }
 
@@ -214,9 +371,180 @@ fn analyze_performance(selector: &CompiledSelector) -> (Vec<String>, Vec<Optimiz
     (notes, hints)
 }
 
-fn count_matches(selector: &CompiledSelector, doc: &Document) -> usize {
-    use super::find_all_compiled;
-    find_all_compiled(doc, selector).len()
+fn analyze_document(
+    selector: &CompiledSelector,
+    doc: &Document,
+    matches: &[NodeId],
+) -> DocumentAnalysis {
+    let element_count = doc.nodes().filter(|(_, node)| node.kind.is_element()).count();
+
+    let compounds: Vec<CompoundStats> = selector
+        .selector_list()
+        .slice()
+        .iter()
+        .flat_map(|complex| split_compounds(complex).0)
+        .filter_map(|compound| {
+            let compiled = CompiledSelector::compile(&compound.css).ok()?;
+            let matches = find_all_compiled(doc, &compiled).len();
+            Some(CompoundStats {
+                compound: compound.css,
+                matches,
+                eliminated: element_count.saturating_sub(matches),
+            })
+        })
+        .collect();
+    let most_selective = compounds
+        .iter()
+        .enumerate()
+        .max_by_key(|(i, stats)| (stats.eliminated, std::cmp::Reverse(*i)))
+        .map(|(i, _)| i);
+
+    let source = selector.source().trim();
+    let index = match (doc.index(), source.strip_prefix('#'), source.strip_prefix('.')) {
+        (Some(_), Some(id), _) if is_simple_selector(id) => IndexUsage::Id(id.to_string()),
+        (Some(_), _, Some(class)) if is_simple_selector(class) => {
+            IndexUsage::Class(class.to_string())
+        }
+        (Some(_), _, _) if is_simple_tag(source) => IndexUsage::Tag(source.to_string()),
+        _ => IndexUsage::Scan,
+    };
+
+    let rewrite = suggest_rewrite(selector, doc, matches).map(|rewritten| {
+        let original_time = time_query(doc, selector);
+        let rewritten_time = time_query(doc, &rewritten);
+        RewriteSuggestion {
+            selector: rewritten.source().to_string(),
+            original_time,
+            rewritten_time,
+            speedup: original_time.as_secs_f64() / rewritten_time.as_secs_f64().max(1e-9),
+        }
+    });
+
+    DocumentAnalysis { element_count, compounds, most_selective, index, rewrite }
+}
+
+/// One compound selector of a complex selector, serialized back to CSS.
+struct Compound {
+    css: String,
+    has_id: bool,
+}
+
+/// Splits a complex selector into its compounds and the combinators between them.
+fn split_compounds(selector: &Selector<ScrapeSelector>) -> (Vec<Compound>, Vec<Combinator>) {
+    fn compound(components: &[&Component<ScrapeSelector>]) -> Compound {
+        let mut css = String::new();
+        // Parse order visits compounds left to right, but each compound's simple
+        // selectors right to left.
+        for component in components.iter().rev() {
+            let _ = component.to_css(&mut css);
+        }
+        Compound { css, has_id: components.iter().any(|c| matches!(c, Component::ID(_))) }
+    }
+
+    let mut compounds = Vec::new();
+    let mut combinators = Vec::new();
+    let mut components = Vec::new();
+
+    for component in selector.iter_raw_parse_order_from(0) {
+        if let Component::Combinator(combinator) = component {
+            combinators.push(*combinator);
+            compounds.push(compound(&components));
+            components.clear();
+        } else {
+            components.push(component);
+        }
+    }
+    compounds.push(compound(&components));
+
+    (compounds, combinators)
+}
+
+fn join_compounds(compounds: &[Compound], combinators: &[Combinator]) -> String {
+    let mut css = compounds.first().map(|c| c.css.clone()).unwrap_or_default();
+    for (combinator, compound) in combinators.iter().zip(compounds.iter().skip(1)) {
+        let _ = combinator.to_css(&mut css);
+        css.push_str(&compound.css);
+    }
+    css
+}
+
+/// Looks for a simpler selector with the same matches on `doc`.
+///
+/// Tries, in order: dropping everything left of a compound with an id, dropping
+/// ancestor compounds that rule nothing out, and tightening descendant combinators to
+/// child combinators. Each step is kept only if the matches stay identical.
+fn suggest_rewrite(
+    selector: &CompiledSelector,
+    doc: &Document,
+    expected: &[NodeId],
+) -> Option<CompiledSelector> {
+    let [complex] = selector.selector_list().slice() else { return None };
+    let (mut compounds, mut combinators) = split_compounds(complex);
+    if combinators.iter().any(|c| {
+        !matches!(
+            c,
+            Combinator::Descendant
+                | Combinator::Child
+                | Combinator::NextSibling
+                | Combinator::LaterSibling
+        )
+    }) {
+        return None;
+    }
+    let original = join_compounds(&compounds, &combinators);
+    let equivalent = |compounds: &[Compound], combinators: &[Combinator]| {
+        CompiledSelector::compile(&join_compounds(compounds, combinators))
+            .is_ok_and(|candidate| find_all_compiled(doc, &candidate) == expected)
+    };
+
+    if let Some(k) = (1..compounds.len()).rev().find(|&k| compounds[k].has_id)
+        && equivalent(&compounds[k..], &combinators[k..])
+    {
+        compounds.drain(..k);
+        combinators.drain(..k);
+    }
+
+    let mut i = 0;
+    while i + 1 < compounds.len() {
+        let descendant = |j: usize| combinators[j] == Combinator::Descendant;
+        if descendant(i) && (i == 0 || descendant(i - 1)) {
+            let removed = compounds.remove(i);
+            let combinator = combinators.remove(i);
+            if equivalent(&compounds, &combinators) {
+                continue;
+            }
+            compounds.insert(i, removed);
+            combinators.insert(i, combinator);
+        }
+        i += 1;
+    }
+
+    for j in 0..combinators.len() {
+        if combinators[j] == Combinator::Descendant {
+            combinators[j] = Combinator::Child;
+            if !equivalent(&compounds, &combinators) {
+                combinators[j] = Combinator::Descendant;
+            }
+        }
+    }
+
+    let rewritten = join_compounds(&compounds, &combinators);
+    if rewritten == original {
+        return None;
+    }
+    CompiledSelector::compile(&rewritten).ok()
+}
+
+/// Fastest of [`SAMPLE_RUNS`] runs of `selector` against `doc`.
+fn time_query(doc: &Document, selector: &CompiledSelector) -> Duration {
+    (0..SAMPLE_RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(find_all_compiled(doc, selector));
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
 }
 
 fn format_hint(hint: &OptimizationHint) -> String {
@@ -235,6 +563,9 @@ fn format_hint(hint: &OptimizationHint) -> String {
         OptimizationHint::CacheSelector => {
             "Consider caching this compiled selector for reuse".to_string()
         }
+        OptimizationHint::SimplerSelector { suggested } => {
+            format!("Simpler selector with the same matches on this document: '{suggested}'")
+        }
     }
 }
 
@@ -271,6 +602,25 @@ mod tests {
         assert_eq!(explanation.specificity.elements, 1);
     }
 
+    #[test]
+    fn test_specificity_of_negation_uses_most_specific_argument() {
+        let explanation = explain("li:not(div > .ad, #promo)").unwrap();
+        assert_eq!(explanation.specificity, Specificity::new(1, 0, 1));
+
+        let explanation = explain("a:not(nav .menu a)").unwrap();
+        assert_eq!(explanation.specificity, Specificity::new(0, 1, 3));
+    }
+
+    #[test]
+    fn test_specificity_of_level4_pseudo_classes() {
+        let specificity = |selector| explain(selector).unwrap().specificity;
+        assert_eq!(specificity(":is(h1, #top) a"), Specificity::new(1, 0, 1));
+        assert_eq!(specificity(":where(h1, #top) a"), Specificity::new(0, 0, 1));
+        assert_eq!(specificity("li:has(> a.sale)"), Specificity::new(0, 1, 2));
+        assert_eq!(specificity("li:nth-child(2)"), Specificity::new(0, 1, 1));
+        assert_eq!(specificity("li:nth-child(2 of .item)"), Specificity::new(0, 2, 1));
+    }
+
     #[test]
     fn test_explain_performance_hint_universal() {
         let explanation = explain("*").unwrap();
@@ -283,6 +633,20 @@ mod tests {
         assert!(explanation.hints.contains(&OptimizationHint::Optimal));
     }
 
+    #[test]
+    fn test_explain_indexed_lookup_notes() {
+        for (selector, kind) in [("#a", "ID"), (".b", "Class"), ("li", "Tag")] {
+            let explanation = explain(selector).unwrap();
+            let note = format!("{kind} selector - uses fast indexed lookup");
+            assert!(explanation.performance_notes.contains(&note), "{selector}");
+            assert!(explanation.hints.contains(&OptimizationHint::Optimal));
+        }
+        for selector in ["#a.b", "#a > p", "div.x", "LI"] {
+            let explanation = explain(selector).unwrap();
+            assert!(!explanation.performance_notes.iter().any(|n| n.contains("indexed")));
+        }
+    }
+
     #[test]
     fn test_explain_deep_nesting() {
         let explanation = explain("div span p a").unwrap();
@@ -342,4 +706,71 @@ mod tests {
             elapsed.as_micros() / 1000
         );
     }
+
+    #[test]
+    fn test_explain_with_document_rewrite_and_compounds() {
+        use crate::Soup;
+
+        let soup = Soup::parse(concat!(
+            r#"<div class="page"><div id="main"><ul class="nav">"#,
+            r#"<li class="item">A</li><li class="item">B</li></ul></div></div>"#,
+            r#"<ul><li class="item">C</li></ul>"#,
+        ));
+        let source = "body div.page #main ul li.item";
+        let explanation = explain_with_document(source, soup.document()).unwrap();
+        assert_eq!(explanation.estimated_matches, Some(2));
+
+        let analysis = explanation.document.as_ref().unwrap();
+        let compounds: Vec<_> = analysis.compounds.iter().map(|c| c.compound.as_str()).collect();
+        assert_eq!(compounds, ["body", "div.page", "#main", "ul", "li.item"]);
+        assert_eq!(analysis.compounds[4].matches, 3);
+        let most_selective = &analysis.compounds[analysis.most_selective.unwrap()];
+        assert_eq!(most_selective.matches, 1);
+        assert_eq!(most_selective.eliminated, analysis.element_count - 1);
+        assert_eq!(analysis.index, IndexUsage::Scan);
+
+        let rewrite = analysis.rewrite.as_ref().unwrap();
+        assert_eq!(rewrite.selector, "#main li.item");
+        assert!(rewrite.speedup > 0.0);
+        assert!(explanation.hints.contains(&OptimizationHint::UseIdSelector {
+            current: source.to_string(),
+            suggested: "#main li.item".to_string(),
+        }));
+        assert!(explanation.format().contains("rules out"));
+    }
+
+    #[test]
+    fn test_explain_with_document_index_usage() {
+        use crate::Soup;
+
+        let soup = Soup::parse(r#"<p id="x" class="note">a</p><p class="note">b</p>"#);
+        let analysis = |selector| explain_with_document(selector, soup.document()).unwrap();
+
+        let by_id = analysis("#x");
+        assert_eq!(by_id.document.unwrap().index, IndexUsage::Id("x".to_string()));
+        let by_class = analysis(".note");
+        assert_eq!(by_class.document.unwrap().index, IndexUsage::Class("note".to_string()));
+
+        let by_tag = analysis("p");
+        assert_eq!(by_tag.document.unwrap().index, IndexUsage::Tag("p".to_string()));
+
+        let single = analysis("p.note").document.unwrap();
+        assert_eq!(single.index, IndexUsage::Scan);
+        assert!(single.rewrite.is_none());
+    }
+
+    #[test]
+    fn test_explain_with_document_tightens_combinators() {
+        use crate::Soup;
+
+        let soup = Soup::parse("<section><article><p>a</p></article><p>b</p></section>");
+        let explanation = explain_with_document("section article p", soup.document()).unwrap();
+        let rewrite = explanation.document.unwrap().rewrite.unwrap();
+        assert_eq!(rewrite.selector, "article > p");
+        assert!(
+            explanation.hints.contains(&OptimizationHint::SimplerSelector {
+                suggested: "article > p".to_string()
+            })
+        );
+    }
 }
@@ -1,7 +1,10 @@
-//! Text and attribute extraction from query results.
+//! Text, attribute, and mapped value extraction from query results.
 
-use super::{QueryResult, find_all, find_all_within, parse_selector};
-use crate::dom::{Document, NodeId, NodeKind};
+use super::{QueryResult, find_all, find_all_within};
+use crate::{
+    Tag,
+    dom::{Document, NodeId, NodeKind},
+};
 
 /// Extracts text content from all elements matching a CSS selector.
 ///
@@ -23,7 +26,6 @@ use crate::dom::{Document, NodeId, NodeKind};
 /// assert_eq!(texts, vec!["A", "B"]);
 /// ```
 pub fn select_text(doc: &Document, selector: &str) -> QueryResult<Vec<String>> {
-    let selector_list = parse_selector(selector)?;
     let node_ids = find_all(doc, selector)?;
 
     Ok(node_ids.into_iter().map(|id| extract_text(doc, id)).collect())
@@ -40,7 +42,6 @@ pub fn select_text_within(
     root: NodeId,
     selector: &str,
 ) -> QueryResult<Vec<String>> {
-    let selector_list = parse_selector(selector)?;
     let node_ids = find_all_within(doc, root, selector)?;
 
     Ok(node_ids.into_iter().map(|id| extract_text(doc, id)).collect())
@@ -66,7 +67,6 @@ pub fn select_text_within(
 /// assert_eq!(hrefs, vec![Some("/a".to_string()), Some("/b".to_string())]);
 /// ```
 pub fn select_attr(doc: &Document, selector: &str, attr: &str) -> QueryResult<Vec<Option<String>>> {
-    let selector_list = parse_selector(selector)?;
     let node_ids = find_all(doc, selector)?;
 
     Ok(node_ids.into_iter().map(|id| extract_attr(doc, id, attr)).collect())
@@ -84,12 +84,57 @@ pub fn select_attr_within(
     selector: &str,
     attr: &str,
 ) -> QueryResult<Vec<Option<String>>> {
-    let selector_list = parse_selector(selector)?;
     let node_ids = find_all_within(doc, root, selector)?;
 
     Ok(node_ids.into_iter().map(|id| extract_attr(doc, id, attr)).collect())
 }
 
+/// Applies `f` to every element matching a CSS selector, in document order.
+///
+/// Use this to convert matches in the same pass that finds them, e.g. with
+/// [`Tag::parse_number`] or [`Tag::parse_date`].
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+/// syntax is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, query::select_map};
+///
+/// let soup = Soup::parse("<li data-id='7'>Tea</li><li>Cake</li>");
+/// let ids = select_map(soup.document(), "li", |tag| tag.attr_int("data-id")).unwrap();
+/// assert_eq!(ids, vec![Some(7), None]);
+/// ```
+pub fn select_map<'a, T>(
+    doc: &'a Document,
+    selector: &str,
+    f: impl FnMut(Tag<'a>) -> T,
+) -> QueryResult<Vec<T>> {
+    let node_ids = find_all(doc, selector)?;
+
+    Ok(node_ids.into_iter().map(|id| Tag::new(doc, id)).map(f).collect())
+}
+
+/// Applies `f` to every element within a subtree matching a CSS selector.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+/// syntax is invalid.
+pub fn select_map_within<'a, T>(
+    doc: &'a Document,
+    root: NodeId,
+    selector: &str,
+    f: impl FnMut(Tag<'a>) -> T,
+) -> QueryResult<Vec<T>> {
+    let node_ids = find_all_within(doc, root, selector)?;
+
+    Ok(node_ids.into_iter().map(|id| Tag::new(doc, id)).map(f).collect())
+}
+
 /// Extracts all text content from an element and its descendants.
 fn extract_text(doc: &Document, root: NodeId) -> String {
     let mut text = String::new();
@@ -112,7 +157,7 @@ fn collect_text(doc: &Document, node_id: NodeId, buffer: &mut String) {
                 collect_text(doc, child_id, buffer);
             }
         }
-        NodeKind::Comment { .. } => {}
+        NodeKind::Comment { .. } | NodeKind::Doctype { .. } => {}
     }
 }
 
@@ -121,7 +166,7 @@ fn extract_attr(doc: &Document, node_id: NodeId, attr: &str) -> Option<String> {
     let node = doc.get(node_id)?;
 
     if let NodeKind::Element { attributes, .. } = &node.kind {
-        attributes.get(attr).cloned()
+        attributes.get(attr).map(str::to_string)
     } else {
         None
     }
@@ -230,6 +275,19 @@ mod tests {
         assert_eq!(hrefs, vec![Some("/1".to_string()), Some("/2".to_string())]);
     }
 
+    #[test]
+    fn test_select_map() {
+        let soup = Soup::parse("<ul><li>Tea</li><li>Cake</li></ul><li>Pie</li>");
+        let lens = select_map(soup.document(), "li", |tag| tag.text().len()).unwrap();
+        assert_eq!(lens, vec![3, 4, 3]);
+
+        let ul = soup.find("ul").unwrap().unwrap();
+        let names = select_map_within(soup.document(), ul.node_id(), "li", |tag| tag.text());
+        assert_eq!(names.unwrap(), vec!["Tea", "Cake"]);
+
+        assert!(select_map(soup.document(), "[", |tag| tag.text()).is_err());
+    }
+
     #[test]
     fn test_select_text_empty_element() {
         let soup = Soup::parse("<div></div>");
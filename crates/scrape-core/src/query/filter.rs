@@ -506,7 +506,7 @@ mod tests {
 
         let id = result.unwrap();
         let attrs = doc.get(id).unwrap().kind.attributes().unwrap();
-        assert_eq!(attrs.get("id"), Some(&"first".to_string()));
+        assert_eq!(attrs.get("id"), Some("first"));
     }
 
     #[test]
@@ -6,13 +6,16 @@
 //! - [`find_all`] - Find all matching elements in the entire document
 //! - [`find_within`] - Find first matching element within a subtree
 //! - [`find_all_within`] - Find all matching elements within a subtree
+//! - [`find_nth`] and [`find_last`] - Find one match by position, without collecting the others
 
 use selectors::{context::SelectorCaches, parser::SelectorList};
 
 use super::{
     CompiledSelector,
+    cache::cached_compile,
     error::QueryResult,
-    selector::{ScrapeSelector, matches_selector_with_caches, parse_selector},
+    iter::{find_iter, find_iter_within},
+    selector::{ScrapeSelector, matches_selector_with_caches},
 };
 use crate::dom::{Document, NodeId};
 
@@ -51,9 +54,16 @@ pub fn find(doc: &Document, selector: &str) -> QueryResult<Option<NodeId>> {
         return Ok(index.get_by_class(class).first().copied());
     }
 
+    // Fast path: bare tag name
+    if is_simple_tag(selector)
+        && let Some(index) = doc.index()
+    {
+        return Ok(index.get_by_tag(selector).first().copied());
+    }
+
     // Fall back to full selector matching
-    let selectors = parse_selector(selector)?;
-    Ok(find_with_selector(doc, &selectors))
+    let compiled = cached_compile(selector)?;
+    Ok(find_with_selector(doc, compiled.selector_list()))
 }
 
 /// Finds all elements matching a CSS selector.
@@ -91,9 +101,16 @@ pub fn find_all(doc: &Document, selector: &str) -> QueryResult<Vec<NodeId>> {
         return Ok(index.get_by_class(class).to_vec());
     }
 
+    // Fast path: bare tag name
+    if is_simple_tag(selector)
+        && let Some(index) = doc.index()
+    {
+        return Ok(index.get_by_tag(selector).to_vec());
+    }
+
     // Fall back to full selector matching
-    let selectors = parse_selector(selector)?;
-    Ok(find_all_with_selector(doc, &selectors))
+    let compiled = cached_compile(selector)?;
+    Ok(find_all_with_selector(doc, compiled.selector_list()))
 }
 
 /// Finds the first element matching a CSS selector within a subtree.
@@ -126,8 +143,8 @@ pub fn find_all(doc: &Document, selector: &str) -> QueryResult<Vec<NodeId>> {
 /// assert!(result.is_some());
 /// ```
 pub fn find_within(doc: &Document, scope: NodeId, selector: &str) -> QueryResult<Option<NodeId>> {
-    let selectors = parse_selector(selector)?;
-    Ok(find_within_with_selector(doc, scope, &selectors))
+    let compiled = cached_compile(selector)?;
+    Ok(find_within_with_selector(doc, scope, compiled.selector_list()))
 }
 
 /// Finds all elements matching a CSS selector within a subtree.
@@ -137,8 +154,94 @@ pub fn find_within(doc: &Document, scope: NodeId, selector: &str) -> QueryResult
 /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
 /// syntax is invalid.
 pub fn find_all_within(doc: &Document, scope: NodeId, selector: &str) -> QueryResult<Vec<NodeId>> {
-    let selectors = parse_selector(selector)?;
-    Ok(find_all_within_with_selector(doc, scope, &selectors))
+    let compiled = cached_compile(selector)?;
+    Ok(find_all_within_with_selector(doc, scope, compiled.selector_list()))
+}
+
+/// Finds the `n`th element (counting from zero) matching a CSS selector, in document order.
+///
+/// Matching stops at the `n`th match, and bare tag and class selectors are answered from
+/// the document index, so this is cheaper than indexing the result of [`find_all`].
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+/// syntax is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Html5everParser, Parser, query::find_nth};
+///
+/// let doc = Html5everParser.parse("<ul><li>A</li><li>B</li><li>C</li></ul>").unwrap();
+/// let third = find_nth(&doc, "li", 2).unwrap().unwrap();
+/// assert_eq!(doc.get(third).unwrap().kind.tag_name(), Some("li"));
+/// assert!(find_nth(&doc, "li", 3).unwrap().is_none());
+/// ```
+pub fn find_nth(doc: &Document, selector: &str, n: usize) -> QueryResult<Option<NodeId>> {
+    if let Some(ids) = indexed(doc, selector) {
+        return Ok(ids.get(n).copied());
+    }
+    Ok(find_iter(doc, selector)?.nth(n))
+}
+
+/// Finds the last element matching a CSS selector, in document order.
+///
+/// The document is walked backwards from its end, stopping at the first match, and bare
+/// tag and class selectors are answered from the document index.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+/// syntax is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Html5everParser, Parser, query::find_last};
+///
+/// let doc = Html5everParser.parse("<p id='a'><span id='b'></span></p><p id='c'></p>").unwrap();
+/// let last = find_last(&doc, "[id]").unwrap().unwrap();
+/// assert_eq!(&doc.get(last).unwrap().kind.attributes().unwrap()["id"], "c");
+/// ```
+pub fn find_last(doc: &Document, selector: &str) -> QueryResult<Option<NodeId>> {
+    if let Some(ids) = indexed(doc, selector) {
+        return Ok(ids.last().copied());
+    }
+    let compiled = cached_compile(selector)?;
+    Ok(doc.root().and_then(|root| find_last_in(doc, root, true, compiled.selector_list())))
+}
+
+/// Finds the `n`th descendant (counting from zero) matching a CSS selector within a
+/// subtree, stopping at that match.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+/// syntax is invalid.
+pub fn find_nth_within(
+    doc: &Document,
+    scope: NodeId,
+    selector: &str,
+    n: usize,
+) -> QueryResult<Option<NodeId>> {
+    Ok(find_iter_within(doc, scope, selector)?.nth(n))
+}
+
+/// Finds the last descendant matching a CSS selector within a subtree, walking it
+/// backwards from its end.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+/// syntax is invalid.
+pub fn find_last_within(
+    doc: &Document,
+    scope: NodeId,
+    selector: &str,
+) -> QueryResult<Option<NodeId>> {
+    let compiled = cached_compile(selector)?;
+    Ok(find_last_in(doc, scope, false, compiled.selector_list()))
 }
 
 /// Finds the first element matching a pre-parsed selector.
@@ -336,17 +439,77 @@ pub fn find_all_within_compiled(
 /// Checks if a selector string is simple (no combinators or complex syntax).
 ///
 /// A simple selector is one that contains only alphanumeric characters, hyphens,
-/// and underscores. It does not contain combinators (>, +, ~, space), attribute
-/// selectors, pseudo-classes, or multiple selectors.
+/// underscores, and non-ASCII characters. Anything else, including escapes, quotes,
+/// and stray attribute operators, goes through the full parser so that string and
+/// compiled queries agree on both matches and errors.
 #[inline]
-fn is_simple_selector(s: &str) -> bool {
-    !s.is_empty() && !s.contains(['.', '#', '[', ']', ':', ' ', '>', '+', '~', ',', '*', '(', ')'])
+/// Returns the indexed matches of a bare class or tag selector, in document order.
+fn indexed<'d>(doc: &'d Document, selector: &str) -> Option<&'d [NodeId]> {
+    let index = doc.index()?;
+    if let Some(class) = selector.strip_prefix('.')
+        && is_simple_selector(class)
+    {
+        return Some(index.get_by_class(class));
+    }
+    is_simple_tag(selector).then(|| index.get_by_tag(selector))
+}
+
+/// Finds the last element in the subtree of `scope` matching `selectors`, visiting the
+/// nodes in reverse document order.
+fn find_last_in(
+    doc: &Document,
+    scope: NodeId,
+    include_scope: bool,
+    selectors: &SelectorList<ScrapeSelector>,
+) -> Option<NodeId> {
+    let last_descendant = |mut id| {
+        while let Some(child) = doc.last_child(id) {
+            id = child;
+        }
+        id
+    };
+    let mut caches = SelectorCaches::default();
+    let mut id = last_descendant(scope);
+    while id != scope {
+        if doc.get(id).is_some_and(|node| node.kind.is_element())
+            && matches_selector_with_caches(doc, id, selectors, &mut caches)
+        {
+            return Some(id);
+        }
+        id = match doc.prev_sibling(id) {
+            Some(prev) => last_descendant(prev),
+            None => doc.parent(id)?,
+        };
+    }
+    (include_scope && matches_selector_with_caches(doc, scope, selectors, &mut caches))
+        .then_some(scope)
+}
+
+pub(super) fn is_simple_selector(s: &str) -> bool {
+    let is_ident_char =
+        |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_') || !c.is_ascii();
+    // An identifier cannot start with a digit, or with a hyphen followed by a digit
+    let start = s.strip_prefix('-').unwrap_or(s);
+    start.chars().next().is_some_and(|c| is_ident_char(c) && !c.is_ascii_digit())
+        && s.chars().all(is_ident_char)
+}
+
+/// Checks if a selector string is a bare, lowercase tag name.
+///
+/// Uppercase names go through the parser, which matches HTML elements
+/// case-insensitively but foreign (SVG, MathML) elements case-sensitively.
+#[inline]
+pub(super) fn is_simple_tag(s: &str) -> bool {
+    is_simple_selector(s) && !s.bytes().any(|b| b.is_ascii_uppercase())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser::{Html5everParser, Parser};
+    use crate::{
+        parser::{Html5everParser, Parser},
+        query::parse_selector,
+    };
 
     fn parse_doc(html: &str) -> Document {
         Html5everParser.parse(html).unwrap()
@@ -485,7 +648,7 @@ mod tests {
 
         let id = result.unwrap();
         let attrs = doc.get(id).unwrap().kind.attributes().unwrap();
-        assert_eq!(attrs.get("id"), Some(&"first".to_string()));
+        assert_eq!(attrs.get("id"), Some("first"));
     }
 
     #[test]
@@ -496,7 +659,9 @@ mod tests {
         let ids: Vec<_> = results
             .iter()
             .map(|id| {
-                doc.get(*id).and_then(|n| n.kind.attributes()).and_then(|a| a.get("id").cloned())
+                doc.get(*id)
+                    .and_then(|n| n.kind.attributes())
+                    .and_then(|a| a.get("id").map(str::to_string))
             })
             .collect();
 
@@ -558,6 +723,14 @@ mod tests {
         assert!(!is_simple_selector("foo,bar"));
         assert!(!is_simple_selector("*"));
         assert!(!is_simple_selector("foo(bar)"));
+        assert!(!is_simple_selector("a\\31"));
+        assert!(!is_simple_selector("foo=bar"));
+        assert!(!is_simple_selector("foo\"bar"));
+        assert!(!is_simple_selector("1st"));
+        assert!(!is_simple_selector("-1st"));
+        assert!(!is_simple_selector("-"));
+        assert!(is_simple_selector("-webkit"));
+        assert!(is_simple_selector("日本語"));
     }
 
     #[test]
@@ -607,6 +780,56 @@ mod tests {
         assert!(results.is_empty());
     }
 
+    #[test]
+    fn test_fast_path_tag_selector() {
+        let doc =
+            parse_doc("<ul><li>A</li><li>B</li></ul><svg><foreignObject></foreignObject></svg>");
+        assert!(doc.index().is_some_and(|index| index.get_by_tag("li").len() == 2));
+
+        for selector in ["li", "ul", "html", "body", "p", "LI", "foreignObject", "foreignobject"] {
+            let parsed = parse_selector(selector).unwrap();
+            assert_eq!(
+                find_all(&doc, selector).unwrap(),
+                find_all_with_selector(&doc, &parsed),
+                "{selector}"
+            );
+            assert_eq!(find(&doc, selector).unwrap(), find_with_selector(&doc, &parsed));
+        }
+    }
+
+    #[test]
+    fn test_fast_paths_follow_document_order() {
+        // Foster parenting moves the second <p> in front of the table, misnested
+        // formatting tags clone <b> elements after their children were created, and
+        // template contents are kept outside the tree.
+        for html in [
+            "<table><caption><p class='x'>1</p></caption><p class='x'>2</p></table>",
+            "<b>1<p>2</b>3</p><b>4</b>",
+            "<table><tr><td>a</td></tr><div id='d'>out</div></table><div id='d'>after</div>",
+            "<template><p class='x'>t</p><div id='d'>t</div></template><p class='x'>1</p>",
+        ] {
+            let doc = parse_doc(html);
+            for selector in ["p", ".x", "b", "td", "div"] {
+                let parsed = parse_selector(selector).unwrap();
+                assert_eq!(
+                    find_all(&doc, selector).unwrap(),
+                    find_all_with_selector(&doc, &parsed),
+                    "{selector} in {html}"
+                );
+            }
+            // With duplicate ids the first one in document order wins.
+            let parsed = parse_selector("#d").unwrap();
+            assert_eq!(find(&doc, "#d").unwrap(), find_with_selector(&doc, &parsed), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_fast_path_tag_in_fragment() {
+        let doc = crate::parser::fragment::parse_fragment("<li>A</li>").unwrap();
+        assert!(find_all(&doc, "body").unwrap().is_empty());
+        assert_eq!(find_all(&doc, "li").unwrap().len(), 1);
+    }
+
     #[test]
     fn test_complex_selector_fallback() {
         let doc = parse_doc("<div id='main' class='container'>text</div>");
@@ -640,11 +863,9 @@ mod tests {
     #[test]
     fn test_fast_path_with_no_index() {
         let mut doc = Document::new();
-        #[allow(clippy::default_trait_access)]
-        let root_id = doc.create_element("html".to_string(), Default::default());
+        let root_id = doc.create_element("html".to_string(), crate::Attributes::new());
         doc.set_root(root_id);
-        #[allow(clippy::default_trait_access)]
-        let elem = doc.create_element("div".to_string(), Default::default());
+        let elem = doc.create_element("div".to_string(), crate::Attributes::new());
         doc.append_child(root_id, elem);
 
         let result = find(&doc, "#test").unwrap();
@@ -707,4 +928,75 @@ mod tests {
         let fallback_results = find_all(&doc, "[class~='container']").unwrap();
         assert_eq!(fast_results.len(), fallback_results.len());
     }
+
+    #[test]
+    fn test_fast_path_defers_escapes_and_errors_to_parser() {
+        let doc = parse_doc("<div id='a1' class='x'>A</div>");
+
+        assert!(find(&doc, "#a\\31").unwrap().is_some());
+        assert!(find(&doc, ".foo=bar").is_err());
+        assert!(find_all(&doc, ".1x").is_err());
+        assert_eq!(
+            find(&doc, ".foo=bar").unwrap_err(),
+            CompiledSelector::compile(".foo=bar").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_attribute_operators_string_vs_compiled() {
+        let doc = parse_doc(concat!(
+            r#"<a href="https://example.com/a.PDF" title="say &quot;hi&quot;" lang="en-US">1</a>"#,
+            r#"<a href="" title="it's" lang="en">2</a>"#,
+            r#"<a href="/日本語/ページ" title="" class="btn  btn-primary" lang="">3</a>"#,
+        ));
+
+        let cases = [
+            (r#"[href^="https"]"#, 1),
+            (r#"[href^=""]"#, 0),
+            (r#"[href$=".pdf"]"#, 0),
+            (r#"[href$=".pdf" i]"#, 1),
+            (r#"[href$=""]"#, 0),
+            (r#"[href*="日本"]"#, 1),
+            (r#"[href*=""]"#, 0),
+            (r#"[class~="btn"]"#, 1),
+            (r#"[class~=""]"#, 0),
+            (r#"[class~="btn btn-primary"]"#, 0),
+            (r#"[lang|="en"]"#, 2),
+            (r#"[lang|=""]"#, 1),
+            (r#"[title="say \"hi\""]"#, 1),
+            (r"[title='it\'s']", 1),
+            (r#"[title*='"hi']"#, 1),
+            (r#"[title=""]"#, 1),
+            (r"[title=it\'s]", 1),
+            (r#"[href*="\65 xample"]"#, 1),
+        ];
+        for (selector, expected) in cases {
+            let from_string = find_all(&doc, selector).unwrap();
+            let compiled = CompiledSelector::compile(selector).unwrap();
+            assert_eq!(from_string.len(), expected, "{selector}");
+            assert_eq!(find_all_compiled(&doc, &compiled), from_string, "{selector}");
+        }
+    }
+    #[test]
+    fn test_find_nth_and_last_agree_with_find_all() {
+        let doc = parse_doc(
+            "<table><tr class='r'><td>1</td></tr><tr><td>2<b>x</b></td></tr><tr \
+             class='r'><td>3</td></tr></table><p class='r'>end</p>",
+        );
+        let table = find(&doc, "table").unwrap().unwrap();
+        for selector in ["tr", ".r", "tr.r", "td:has(b), p", "html", "[class]", "ol"] {
+            let all = find_all(&doc, selector).unwrap();
+            for n in 0..=all.len() {
+                assert_eq!(find_nth(&doc, selector, n).unwrap(), all.get(n).copied(), "{selector}");
+            }
+            assert_eq!(find_last(&doc, selector).unwrap(), all.last().copied(), "{selector}");
+
+            let within = find_all_within(&doc, table, selector).unwrap();
+            assert_eq!(find_nth_within(&doc, table, selector, 1).unwrap(), within.get(1).copied());
+            assert_eq!(find_last_within(&doc, table, selector).unwrap(), within.last().copied());
+        }
+        assert!(find_last_within(&doc, table, "table").unwrap().is_none());
+        assert!(find_nth(&doc, "tr[", 0).is_err());
+        assert!(find_last(&doc, "tr[").is_err());
+    }
 }
@@ -0,0 +1,144 @@
+//! Lazy iteration over elements matching a CSS selector.
+
+use std::sync::Arc;
+
+use selectors::context::SelectorCaches;
+
+use super::{
+    CompiledSelector, cache::cached_compile, error::QueryResult,
+    selector::matches_selector_with_caches,
+};
+use crate::dom::{DescendantsIter, Document, NodeId};
+
+/// Iterator over elements matching a selector, in document order.
+///
+/// Matching happens as the tree is walked, so stopping early (`next()`, `take(n)`,
+/// `find(..)`) skips the rest of the document. Created by [`find_iter`] and
+/// [`find_iter_within`].
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, query::find_iter};
+///
+/// let soup = Soup::parse("<ul><li>A</li><li class=\"x\">B</li><li class=\"x\">C</li></ul>");
+/// let first = find_iter(soup.document(), "li.x").unwrap().next();
+/// assert_eq!(first, soup.find("li.x").unwrap().map(|tag| tag.node_id()));
+/// ```
+pub struct MatchesIter<'a> {
+    doc: &'a Document,
+    selector: Arc<CompiledSelector>,
+    caches: SelectorCaches,
+    root: Option<NodeId>,
+    descendants: Option<DescendantsIter<'a>>,
+}
+
+impl MatchesIter<'_> {
+    fn matches(
+        doc: &Document,
+        selector: &CompiledSelector,
+        caches: &mut SelectorCaches,
+        id: NodeId,
+    ) -> bool {
+        doc.get(id).is_some_and(|node| node.kind.is_element())
+            && matches_selector_with_caches(doc, id, selector.selector_list(), caches)
+    }
+}
+
+impl Iterator for MatchesIter<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Self { doc, selector, caches, root, descendants } = self;
+        if let Some(root) = root.take()
+            && Self::matches(doc, selector, caches, root)
+        {
+            return Some(root);
+        }
+        descendants.as_mut()?.find(|&id| Self::matches(doc, selector, caches, id))
+    }
+}
+
+/// Returns a lazy iterator over all elements matching a CSS selector, in document order.
+///
+/// Unlike [`find_all`](super::find_all), nothing is matched until the iterator is
+/// advanced.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+/// syntax is invalid.
+pub fn find_iter<'a>(doc: &'a Document, selector: &str) -> QueryResult<MatchesIter<'a>> {
+    let root = doc.root();
+    Ok(MatchesIter {
+        doc,
+        selector: cached_compile(selector)?,
+        caches: SelectorCaches::default(),
+        root,
+        descendants: root.map(|root| doc.descendants(root)),
+    })
+}
+
+/// Returns a lazy iterator over the descendants of `scope` matching a CSS selector.
+///
+/// The scope element itself is not included, as with
+/// [`find_all_within`](super::find_all_within).
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+/// syntax is invalid.
+pub fn find_iter_within<'a>(
+    doc: &'a Document,
+    scope: NodeId,
+    selector: &str,
+) -> QueryResult<MatchesIter<'a>> {
+    Ok(MatchesIter {
+        doc,
+        selector: cached_compile(selector)?,
+        caches: SelectorCaches::default(),
+        root: None,
+        descendants: Some(doc.descendants(scope)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Soup,
+        query::{find_all, find_all_within},
+    };
+
+    #[test]
+    fn test_find_iter_matches_find_all() {
+        let soup = Soup::parse(
+            "<div class=\"a\"><p>1</p><section><p class=\"a\">2</p></section></div><p>3</p>",
+        );
+        let doc = soup.document();
+        for selector in ["p", ".a", "div p", "html", "section ~ p, div > p", "table"] {
+            let lazy: Vec<_> = find_iter(doc, selector).unwrap().collect();
+            assert_eq!(lazy, find_all(doc, selector).unwrap(), "{selector}");
+        }
+
+        let div = soup.find("div").unwrap().unwrap().node_id();
+        let within: Vec<_> = find_iter_within(doc, div, "p, div").unwrap().collect();
+        assert_eq!(within, find_all_within(doc, div, "p, div").unwrap());
+        assert!(find_iter(doc, "p[").is_err());
+    }
+
+    #[test]
+    fn test_find_iter_is_lazy() {
+        let soup = Soup::parse("<p>1</p><p>2</p><p>3</p>");
+        let mut iter = find_iter(soup.document(), "p").unwrap();
+        let first = iter.next().unwrap();
+        assert_eq!(soup.document().next_sibling(first), iter.next());
+        assert_eq!(iter.count(), 1);
+    }
+
+    #[test]
+    fn test_find_iter_empty_document() {
+        let doc = Document::new();
+        assert_eq!(find_iter(&doc, "p").unwrap().next(), None);
+    }
+}
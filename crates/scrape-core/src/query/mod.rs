@@ -4,6 +4,7 @@
 //!
 //! - **CSS Selectors**: Standard CSS selector syntax via the `selectors` crate
 //! - **Filters**: BeautifulSoup-style attribute filtering
+//! - **XPath**: XPath 1.0 expressions via [`xpath()`] and [`XPath`]
 //!
 //! # CSS Selectors
 //!
@@ -25,6 +26,9 @@
 //! let items = find_all(&doc, ".item").unwrap();
 //! ```
 //!
+//! Selector strings are compiled once and kept in a process-wide cache, so repeated
+//! queries with the same string skip parsing; see [`cached_compile`].
+//!
 //! # Attribute Filters
 //!
 //! Use [`Filter`] for BeautifulSoup-style queries:
@@ -43,6 +47,20 @@
 //! let results = find_by_filter(&doc, &filter);
 //! ```
 //!
+//! # Text Search
+//!
+//! Use [`find_by_text`] to locate an element by its text, e.g. a table label, or
+//! [`FilterText`] to narrow the results of a CSS query:
+//!
+//! ```rust
+//! use scrape_core::{Soup, query::FilterText};
+//!
+//! let soup = Soup::parse("<table><tr><th>Price</th><td>4.50</td></tr></table><p>Sold out</p>");
+//! let price = soup.find_by_text("Price").and_then(|th| th.next_sibling());
+//! assert_eq!(price.unwrap().text(), "4.50");
+//! assert_eq!(soup.select("p").filter_text("Sold").unwrap().len(), 1);
+//! ```
+//!
 //! # Supported CSS Selectors
 //!
 //! | Selector | Example | Description |
@@ -53,6 +71,8 @@
 //! | Universal | `*` | Matches all elements |
 //! | Attribute | `[href]` | Matches elements with attribute |
 //! | Attribute value | `[type="text"]` | Matches attribute with value |
+//! | Attribute case flag | `[type="TEXT" i]` | Case-insensitive (`i`) or sensitive (`s`) value |
+//! | Namespace | `svg\|*`, `[xlink\|href]`, `[*\|href]` | Matches by element or attribute namespace |
 //! | Descendant | `div span` | Matches descendants |
 //! | Child | `div > span` | Matches direct children |
 //! | Adjacent sibling | `h1 + p` | Matches adjacent sibling |
@@ -61,31 +81,56 @@
 //! | :last-child | `li:last-child` | Last child element |
 //! | :nth-child | `li:nth-child(2n)` | Nth child element |
 //! | :empty | `div:empty` | Elements with no children |
-//! | :not() | `div:not(.hidden)` | Negation |
+//! | :not() | `div:not(.hidden)`, `p:not(div > .ad)` | Negation with complex selectors |
+//! | :is() / :where() | `:is(h1, h2) > a` | Matches any selector in the list |
+//! | :has() | `li:has(> a.sale)` | Elements with a matching relative |
+//! | :nth-child(of) | `li:nth-child(2 of .item)` | Nth child among those matching |
 
+mod cache;
 mod compiled;
 mod error;
 mod explain;
 mod extraction;
 mod filter;
 mod find;
+mod iter;
 mod selector;
 mod specificity;
 mod text;
+mod text_index;
+mod text_search;
+mod xpath;
 
+pub use cache::{
+    SELECTOR_CACHE_CAPACITY, cached_compile, clear_selector_cache, selector_cache_len,
+    set_selector_cache_capacity,
+};
 pub use compiled::{CompiledSelector, compile_selector};
 pub use error::{QueryError, QueryResult};
-pub use explain::{OptimizationHint, SelectorExplanation, explain, explain_with_document};
-pub use extraction::{select_attr, select_attr_within, select_text, select_text_within};
+pub use explain::{
+    CompoundStats, DocumentAnalysis, IndexUsage, OptimizationHint, RewriteSuggestion,
+    SelectorExplanation, explain, explain_with_document,
+};
+pub use extraction::{
+    select_attr, select_attr_within, select_map, select_map_within, select_text, select_text_within,
+};
 pub use filter::{Filter, find_by_filter, find_first_by_filter};
 pub use find::{
     find, find_all, find_all_compiled, find_all_with_selector, find_all_within,
-    find_all_within_compiled, find_all_within_with_selector, find_compiled, find_with_selector,
-    find_within, find_within_compiled, find_within_with_selector,
+    find_all_within_compiled, find_all_within_with_selector, find_compiled, find_last,
+    find_last_within, find_nth, find_nth_within, find_with_selector, find_within,
+    find_within_compiled, find_within_with_selector,
 };
+pub use iter::{MatchesIter, find_iter, find_iter_within};
 pub use selector::{
     ElementWrapper, NonTSPseudoClass, PseudoElement, ScrapeSelector, matches_selector,
     matches_selector_list, matches_selector_with_caches, parse_selector,
 };
 pub use specificity::Specificity;
 pub use text::TextNodesIter;
+pub use text_index::TextIndex;
+pub use text_search::{
+    FilterText, TextPattern, find_all_by_text, find_all_by_text_within, find_by_text,
+    find_by_text_within,
+};
+pub use xpath::{XPath, XPathNode, XPathValue, xpath, xpath_within};
@@ -22,7 +22,10 @@ use selectors::{
 };
 
 use super::error::{QueryError, QueryResult};
-use crate::dom::{Document, NodeId};
+use crate::{
+    dom::{Document, Namespace, NodeId, NodeKind},
+    serialize::is_hidden_element,
+};
 
 /// A CSS value string that implements the traits required by `selectors`.
 #[derive(Debug, Clone, PartialEq, Eq, Default, Hash)]
@@ -53,11 +56,13 @@ impl AsRef<str> for CssString {
 }
 
 impl ToCss for CssString {
+    /// Serializes as a quoted CSS string, so attribute values that are empty or contain
+    /// quotes round-trip.
     fn to_css<W>(&self, dest: &mut W) -> fmt::Result
     where
         W: fmt::Write,
     {
-        cssparser::serialize_identifier(&self.0, dest)
+        cssparser::serialize_string(&self.0, dest)
     }
 }
 
@@ -79,14 +84,18 @@ impl precomputed_hash::PrecomputedHash for CssString {
     }
 }
 
-/// A local name (tag name) that implements the traits required by `selectors`.
+/// A local name (tag or attribute name) or identifier (class or ID) that implements the
+/// traits required by `selectors`.
+///
+/// The name is kept as written: tag and attribute names are compared ignoring ASCII case,
+/// while class and ID names are case-sensitive.
 #[derive(Debug, Clone, PartialEq, Eq, Default, Hash)]
 pub struct CssLocalName(String);
 
 impl CssLocalName {
     /// Creates a new local name.
     pub fn new(s: impl Into<String>) -> Self {
-        Self(s.into().to_ascii_lowercase())
+        Self(s.into())
     }
 
     /// Returns the underlying string.
@@ -97,7 +106,7 @@ impl CssLocalName {
 
 impl From<&str> for CssLocalName {
     fn from(s: &str) -> Self {
-        Self(s.to_ascii_lowercase())
+        Self(s.to_owned())
     }
 }
 
@@ -151,6 +160,11 @@ pub enum NonTSPseudoClass {
     Link,
     /// The `:any-link` pseudo-class.
     AnyLink,
+    /// The `:hidden` pseudo-class (matches elements whose text
+    /// [`Tag::visible_text`](crate::Tag::visible_text) would skip).
+    Hidden,
+    /// The `:visible` pseudo-class (the negation of `:hidden`).
+    Visible,
 }
 
 impl selectors::parser::NonTSPseudoClass for NonTSPseudoClass {
@@ -173,6 +187,8 @@ impl ToCss for NonTSPseudoClass {
         match self {
             Self::Link => dest.write_str(":link"),
             Self::AnyLink => dest.write_str(":any-link"),
+            Self::Hidden => dest.write_str(":hidden"),
+            Self::Visible => dest.write_str(":visible"),
         }
     }
 }
@@ -208,6 +224,20 @@ impl SelectorImpl for ScrapeSelector {
     type PseudoElement = PseudoElement;
 }
 
+/// Namespace prefixes usable in selectors such as `svg|rect` or `[xlink|href]`.
+///
+/// Selectors have no `@namespace` rule to declare prefixes, so these are built in.
+const NAMESPACE_PREFIXES: &[(&str, &str)] = &[
+    ("html", "http://www.w3.org/1999/xhtml"),
+    ("xhtml", "http://www.w3.org/1999/xhtml"),
+    ("svg", "http://www.w3.org/2000/svg"),
+    ("math", "http://www.w3.org/1998/Math/MathML"),
+    ("mathml", "http://www.w3.org/1998/Math/MathML"),
+    ("xlink", "http://www.w3.org/1999/xlink"),
+    ("xml", "http://www.w3.org/XML/1998/namespace"),
+    ("xmlns", "http://www.w3.org/2000/xmlns/"),
+];
+
 /// Custom selector parser for our implementation.
 struct SelectorParser;
 
@@ -215,6 +245,25 @@ impl<'i> Parser<'i> for SelectorParser {
     type Impl = ScrapeSelector;
     type Error = SelectorParseErrorKind<'i>;
 
+    fn parse_nth_child_of(&self) -> bool {
+        true
+    }
+
+    fn parse_is_and_where(&self) -> bool {
+        true
+    }
+
+    fn parse_has(&self) -> bool {
+        true
+    }
+
+    fn namespace_for_prefix(&self, prefix: &CssLocalName) -> Option<CssString> {
+        NAMESPACE_PREFIXES
+            .iter()
+            .find(|(name, _)| *name == prefix.as_str())
+            .map(|(_, url)| CssString::from(*url))
+    }
+
     fn parse_non_ts_pseudo_class(
         &self,
         location: cssparser::SourceLocation,
@@ -223,6 +272,8 @@ impl<'i> Parser<'i> for SelectorParser {
         match name.as_ref() {
             "link" => Ok(NonTSPseudoClass::Link),
             "any-link" => Ok(NonTSPseudoClass::AnyLink),
+            "hidden" => Ok(NonTSPseudoClass::Hidden),
+            "visible" => Ok(NonTSPseudoClass::Visible),
             _ => Err(cssparser::ParseError {
                 kind: cssparser::ParseErrorKind::Custom(
                     SelectorParseErrorKind::UnsupportedPseudoClassOrElement(name),
@@ -251,15 +302,90 @@ pub fn parse_selector(selector: &str) -> QueryResult<SelectorList<ScrapeSelector
     let mut parser = cssparser::Parser::new(&mut parser_input);
 
     SelectorList::parse(&SelectorParser, &mut parser, ParseRelative::No).map_err(|e| {
-        // Sanitize error messages to expose only position info, avoiding potential
-        // information disclosure from internal parser state in public error messages.
-        QueryError::invalid_selector(format!(
-            "invalid selector at line {}, column {}",
-            e.location.line, e.location.column
-        ))
+        // cssparser lines are 0-based, columns 1-based
+        QueryError::invalid_selector_at(
+            describe_parse_error(&e.kind),
+            e.location.line as usize + 1,
+            e.location.column as usize,
+        )
     })
 }
 
+/// Describes a selector parse error in terms of the offending token.
+///
+/// Only the kind of error and the token from the selector itself are reported, never
+/// internal parser state.
+fn describe_parse_error(
+    kind: &cssparser::ParseErrorKind<'_, SelectorParseErrorKind<'_>>,
+) -> String {
+    use cssparser::{BasicParseErrorKind, ParseErrorKind};
+
+    const OPERATORS: &str = "`=`, `~=`, `|=`, `^=`, `$=`, or `*=`";
+
+    match kind {
+        ParseErrorKind::Basic(BasicParseErrorKind::UnexpectedToken(t)) => {
+            format!("unexpected {}", describe_token(t))
+        }
+        ParseErrorKind::Basic(BasicParseErrorKind::EndOfInput) => {
+            "unexpected end of selector".to_string()
+        }
+        ParseErrorKind::Custom(kind) => match kind {
+            SelectorParseErrorKind::EmptySelector => "empty selector".to_string(),
+            SelectorParseErrorKind::DanglingCombinator => {
+                "combinator is not followed by a selector".to_string()
+            }
+            SelectorParseErrorKind::NoQualifiedNameInAttributeSelector(t)
+            | SelectorParseErrorKind::InvalidQualNameInAttr(t) => {
+                format!("expected attribute name, found {}", describe_token(t))
+            }
+            SelectorParseErrorKind::UnexpectedTokenInAttributeSelector(t) => format!(
+                "unexpected {} in attribute selector, expected {OPERATORS}",
+                describe_token(t)
+            ),
+            SelectorParseErrorKind::BadValueInAttr(t) => format!(
+                "attribute value must be an identifier or a quoted string, found {}; try quoting \
+                 the value",
+                describe_token(t)
+            ),
+            SelectorParseErrorKind::ClassNeedsIdent(t) => {
+                format!("expected class name after `.`, found {}", describe_token(t))
+            }
+            SelectorParseErrorKind::UnsupportedPseudoClassOrElement(name) => {
+                format!("unsupported pseudo-class or pseudo-element `{name}`")
+            }
+            SelectorParseErrorKind::UnexpectedIdent(name) => {
+                format!("unexpected identifier `{name}`")
+            }
+            SelectorParseErrorKind::ExpectedNamespace(prefix) => {
+                let known: Vec<_> =
+                    NAMESPACE_PREFIXES.iter().map(|(name, _)| format!("`{name}`")).collect();
+                format!("unknown namespace prefix `{prefix}`, expected one of {}", known.join(", "))
+            }
+            _ => "invalid selector syntax".to_string(),
+        },
+        ParseErrorKind::Basic(_) => "invalid selector syntax".to_string(),
+    }
+}
+
+fn describe_token(token: &cssparser::Token<'_>) -> String {
+    match token {
+        cssparser::Token::WhiteSpace(_) => "whitespace".to_string(),
+        cssparser::Token::CloseSquareBracket => "`]`".to_string(),
+        token => format!("`{}`", token.to_css_string()),
+    }
+}
+
+/// Splits an attribute key into its namespace URL, empty for no namespace, and local name.
+///
+/// The parser keys namespaced attributes as `{url}:{local}`; see [`NodeKind::Element`].
+fn split_attribute_key(key: &str) -> (&str, &str) {
+    match key.rsplit_once(':') {
+        // HTML attributes such as `xml:lang` contain a colon but no namespace URL.
+        Some((url, name)) if url.contains("://") => (url, name),
+        _ => ("", key),
+    }
+}
+
 /// Adapter wrapping a DOM node for selector matching.
 ///
 /// This type implements the [`selectors::Element`] trait, allowing our
@@ -306,7 +432,9 @@ impl Element for ElementWrapper<'_> {
     type Impl = ScrapeSelector;
 
     fn opaque(&self) -> OpaqueElement {
-        OpaqueElement::new(self)
+        // Wrappers are short-lived copies, so the arena node is what identifies the
+        // element for the `:has()` and nth-index caches.
+        self.doc.get(self.id).map_or_else(|| OpaqueElement::new(self), OpaqueElement::new)
     }
 
     fn parent_element(&self) -> Option<Self> {
@@ -365,7 +493,7 @@ impl Element for ElementWrapper<'_> {
     }
 
     fn is_html_element_in_html_document(&self) -> bool {
-        true
+        self.doc.get(self.id).and_then(|n| n.kind.namespace()) == Some(Namespace::Html)
     }
 
     fn has_local_name(&self, local_name: &<Self::Impl as SelectorImpl>::BorrowedLocalName) -> bool {
@@ -375,9 +503,11 @@ impl Element for ElementWrapper<'_> {
             .is_some_and(|name| name.eq_ignore_ascii_case(local_name.as_str()))
     }
 
-    fn has_namespace(&self, _ns: &<Self::Impl as SelectorImpl>::BorrowedNamespaceUrl) -> bool {
-        // We don't track namespaces, so match everything
-        true
+    fn has_namespace(&self, ns: &<Self::Impl as SelectorImpl>::BorrowedNamespaceUrl) -> bool {
+        self.doc
+            .get(self.id)
+            .and_then(|n| n.kind.namespace())
+            .is_some_and(|namespace| namespace.url() == ns.as_str())
     }
 
     fn is_same_type(&self, other: &Self) -> bool {
@@ -394,22 +524,21 @@ impl Element for ElementWrapper<'_> {
         local_name: &<Self::Impl as SelectorImpl>::BorrowedLocalName,
         operation: &AttrSelectorOperation<&<Self::Impl as SelectorImpl>::AttrValue>,
     ) -> bool {
-        // In HTML, we don't track namespaces, so we accept all namespace constraints
-        // - NamespaceConstraint::Any: matches any namespace (e.g., [*|href])
-        // - NamespaceConstraint::Specific: matches a specific namespace (we ignore since HTML has
-        //   no namespaces)
-        let _ = ns;
-
         let Some(node) = self.doc.get(self.id) else { return false };
         let Some(attrs) = node.kind.attributes() else { return false };
 
-        // HTML attribute names are case-insensitive
-        let attr_name = local_name.as_str();
-        let value = attrs.iter().find(|(k, _)| k.eq_ignore_ascii_case(attr_name)).map(|(_, v)| v);
-
-        let Some(value) = value else { return false };
-
-        operation.eval_str(value)
+        // HTML attribute names are case-insensitive. `[href]` only matches attributes in
+        // no namespace, `[*|href]` matches any namespace.
+        attrs.iter().any(|(key, value)| {
+            let (url, name) = split_attribute_key(key);
+            let in_namespace = match ns {
+                NamespaceConstraint::Any => true,
+                NamespaceConstraint::Specific(wanted) => wanted.as_str() == url,
+            };
+            in_namespace
+                && name.eq_ignore_ascii_case(local_name.as_str())
+                && operation.eval_str(value)
+        })
     }
 
     fn match_non_ts_pseudo_class(
@@ -426,6 +555,8 @@ impl Element for ElementWrapper<'_> {
 
                 matches!(tag_name, "a" | "area" | "link") && attrs.contains_key("href")
             }
+            NonTSPseudoClass::Hidden => is_hidden_element(self.doc, self.id),
+            NonTSPseudoClass::Visible => !is_hidden_element(self.doc, self.id),
         }
     }
 
@@ -471,6 +602,11 @@ impl Element for ElementWrapper<'_> {
         let Some(attrs) = node.kind.attributes() else { return false };
         let Some(class_attr) = attrs.get("class") else { return false };
 
+        #[cfg(feature = "simd")]
+        if matches!(case_sensitivity, CaseSensitivity::CaseSensitive) {
+            return crate::simd::contains_class(class_attr, name.as_str());
+        }
+
         class_attr
             .split_whitespace()
             .any(|class| case_sensitivity.eq(class.as_bytes(), name.as_str().as_bytes()))
@@ -492,13 +628,13 @@ impl Element for ElementWrapper<'_> {
         for child_id in self.doc.children(self.id) {
             if let Some(node) = self.doc.get(child_id) {
                 match &node.kind {
-                    crate::dom::NodeKind::Element { .. } => return false,
-                    crate::dom::NodeKind::Text { content } => {
+                    NodeKind::Element { .. } => return false,
+                    NodeKind::Text { content } => {
                         if !content.trim().is_empty() {
                             return false;
                         }
                     }
-                    crate::dom::NodeKind::Comment { .. } => {}
+                    NodeKind::Comment { .. } | NodeKind::Doctype { .. } => {}
                 }
             }
         }
@@ -731,6 +867,33 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_attribute_errors_are_descriptive() {
+        let err = parse_selector("a[href^=/docs]").unwrap_err();
+        assert_eq!(err.line(), Some(1));
+        assert_eq!(err.column(), Some(9));
+        assert!(err.to_string().contains("quoted string"), "{err}");
+
+        let err = parse_selector("[=value]").unwrap_err();
+        assert!(err.to_string().contains("expected attribute name, found `=`"), "{err}");
+
+        let err = parse_selector("[href!=x]").unwrap_err();
+        assert!(err.to_string().contains("unexpected `!` in attribute selector"), "{err}");
+
+        let err = parse_selector(r#"[href="x" q]"#).unwrap_err();
+        assert!(err.to_string().contains("unexpected `q`"), "{err}");
+    }
+
+    #[test]
+    fn test_attribute_value_serializes_as_string() {
+        let selectors = parse_selector(r#"[title=""], [title="say \"hi\""], [lang|=en]"#).unwrap();
+        let serialized: Vec<_> = selectors.slice().iter().map(ToCss::to_css_string).collect();
+        assert_eq!(serialized, [r#"[title=""]"#, r#"[title="say \"hi\""]"#, r#"[lang|="en"]"#]);
+        for css in serialized {
+            assert!(parse_selector(&css).is_ok(), "{css}");
+        }
+    }
+
     #[test]
     fn test_match_tag_selector() {
         let doc = parse_doc("<div><span>text</span></div>");
@@ -754,6 +917,19 @@ mod tests {
         assert!(!matches_selector(&doc, div_id, &selectors));
     }
 
+    #[test]
+    fn test_match_class_selector_whitespace() {
+        let doc = parse_doc("<div class=\"\tfoo\n bar-baz\r\n\">text</div>");
+        let div_id = find_element_by_tag(&doc, "div").unwrap();
+
+        for (selector, expected) in
+            [(".foo", true), (".bar-baz", true), (".bar", false), (".baz", false), (".Foo", false)]
+        {
+            let selectors = parse_selector(selector).unwrap();
+            assert_eq!(matches_selector(&doc, div_id, &selectors), expected, "{selector}");
+        }
+    }
+
     #[test]
     fn test_match_id_selector() {
         let doc = parse_doc("<div id=\"main\">text</div>");
@@ -808,6 +984,60 @@ mod tests {
         assert!(!matches_selector(&doc, input_id, &selectors));
     }
 
+    #[test]
+    fn test_match_attribute_case_flags() {
+        let doc = parse_doc(r#"<a href="/Docs" type="TEXT/html"></a><svg type="Icon"></svg>"#);
+        let a_id = find_element_by_tag(&doc, "a").unwrap();
+        let svg_id = find_element_by_tag(&doc, "svg").unwrap();
+
+        let matches =
+            |selector: &str, id| matches_selector(&doc, id, &parse_selector(selector).unwrap());
+        assert!(matches("[href='/docs' i]", a_id));
+        assert!(matches("[href^=\"/DOCS\" I]", a_id));
+        assert!(!matches("[href='/docs']", a_id));
+        assert!(!matches("[href='/docs' s]", a_id));
+        // `type` is case-insensitive in HTML, unless `s` is given, but not in SVG.
+        assert!(matches("[type=\"text/html\"]", a_id));
+        assert!(!matches("[type=\"text/html\" s]", a_id));
+        assert!(!matches("[type=icon]", svg_id));
+        assert!(matches("[type=icon i]", svg_id));
+    }
+
+    #[test]
+    fn test_match_namespaces() {
+        let doc = parse_doc(
+            r#"<a href="/a"></a><svg><a xlink:href="/b"><title>T</title></a></svg>
+               <math><mi>x</mi></math>"#,
+        );
+        let elements: Vec<_> = doc.nodes().filter(|(_, n)| n.kind.is_element()).collect();
+        let matching = |selector: &str| {
+            let selectors = parse_selector(selector).unwrap();
+            elements
+                .iter()
+                .filter(|(id, _)| matches_selector(&doc, *id, &selectors))
+                .map(|(_, n)| n.kind.tag_name().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching("svg|*"), ["svg", "a", "title"]);
+        assert_eq!(matching("svg|a"), ["a"]);
+        assert_eq!(matching("html|a"), ["a"]);
+        assert_eq!(matching("*|a"), ["a", "a"]);
+        assert_eq!(matching("math|*"), ["math", "mi"]);
+        assert!(matching("|a").is_empty());
+
+        assert_eq!(matching("[href]"), ["a"]);
+        assert_eq!(matching("[xlink|href=\"/b\"]"), ["a"]);
+        assert_eq!(matching("[*|href]"), ["a", "a"]);
+        assert_eq!(matching("svg a[*|href]"), ["a"]);
+
+        let svg = find_element_by_tag(&doc, "svg").unwrap();
+        assert_eq!(doc.get(svg).unwrap().kind.namespace(), Some(Namespace::Svg));
+
+        let err = parse_selector("foo|a").unwrap_err();
+        assert!(err.to_string().contains("unknown namespace prefix `foo`"), "{err}");
+    }
+
     #[test]
     fn test_element_is_empty() {
         let doc = parse_doc("<div></div><span>text</span>");
@@ -848,6 +1078,83 @@ mod tests {
         assert_eq!(match_count, 1);
     }
 
+    #[test]
+    fn test_match_not_complex_selector() {
+        let doc = parse_doc(
+            r#"<div><p class="ad">1</p></div><section><p class="ad">2</p></section><p>3</p>"#,
+        );
+        let paragraphs: Vec<_> =
+            doc.nodes().filter(|(_, n)| n.kind.tag_name() == Some("p")).map(|(id, _)| id).collect();
+        let matching = |selector: &str| -> Vec<usize> {
+            let selectors = parse_selector(selector).unwrap();
+            paragraphs
+                .iter()
+                .enumerate()
+                .filter(|(_, id)| matches_selector(&doc, **id, &selectors))
+                .map(|(i, _)| i + 1)
+                .collect()
+        };
+
+        assert_eq!(matching("p:not(div > .ad)"), vec![2, 3]);
+        assert_eq!(matching("p:not(section .ad, div .ad)"), vec![3]);
+        assert_eq!(matching(".ad:not(body > div > p)"), vec![2]);
+        assert_eq!(matching("p:not(:not(div > *))"), vec![1]);
+        assert_eq!(matching("p:not(div + section > p)"), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_not_rejects_pseudo_elements_and_empty_argument() {
+        assert!(parse_selector("p:not()").is_err());
+        assert!(parse_selector("p:not(div >)").is_err());
+        assert!(parse_selector("p:not(::before)").is_err());
+    }
+
+    // ==================== Level 4 Pseudo-classes ====================
+
+    #[test]
+    fn test_match_level4_pseudo_classes() {
+        let doc = parse_doc(
+            r#"<ul>
+                <li class="item"><a class="sale" href="/a">1</a></li>
+                <li class="ad"><span><a class="sale" href="/x">2</a></span></li>
+                <li class="item"><h2>3</h2></li>
+                <li class="item"><a href="/c">4</a></li>
+            </ul>"#,
+        );
+        let items: Vec<_> = doc
+            .nodes()
+            .filter(|(_, n)| n.kind.tag_name() == Some("li"))
+            .map(|(id, _)| id)
+            .collect();
+        let matching = |selector: &str| -> Vec<usize> {
+            let selectors = parse_selector(selector).unwrap();
+            items
+                .iter()
+                .enumerate()
+                .filter(|(_, id)| matches_selector(&doc, **id, &selectors))
+                .map(|(i, _)| i + 1)
+                .collect()
+        };
+
+        assert_eq!(matching("li:has(a.sale)"), vec![1, 2]);
+        assert_eq!(matching("li:has(> a.sale)"), vec![1]);
+        assert_eq!(matching("li:has(+ .ad, ~ li > h2)"), vec![1, 2]);
+        assert_eq!(matching("li:not(:has(a))"), vec![3]);
+        assert_eq!(matching(":is(.ad, .item):has(h2, span)"), vec![2, 3]);
+        assert_eq!(matching("li:where(.item)"), vec![1, 3, 4]);
+        assert_eq!(matching("li:not(.ad, :has(h2))"), vec![1, 4]);
+        assert_eq!(matching("li:nth-child(2 of .item)"), vec![3]);
+        assert_eq!(matching("li:nth-last-child(odd of .item)"), vec![1, 4]);
+        assert_eq!(matching("li:nth-child(2n+1 of :has(a))"), vec![1, 4]);
+    }
+
+    #[test]
+    fn test_level4_pseudo_classes_reject_invalid_arguments() {
+        assert!(parse_selector("li:has()").is_err());
+        assert!(parse_selector("li:has(:has(a))").is_err());
+        assert!(parse_selector("li:nth-child(2 of)").is_err());
+    }
+
     // ==================== Attribute Substring Selectors ====================
 
     #[test]
@@ -985,7 +1292,7 @@ mod tests {
         let last_id = matches[0];
         let node = doc.get(*last_id).unwrap();
         let attrs = node.kind.attributes().unwrap();
-        assert_eq!(attrs.get("id"), Some(&"last".to_string()));
+        assert_eq!(attrs.get("id"), Some("last"));
     }
 
     // ==================== Sibling Combinator Selectors ====================
@@ -1059,4 +1366,25 @@ mod tests {
             .count();
         assert_eq!(count, 3);
     }
+
+    // ==================== Visibility Pseudo-classes ====================
+
+    #[test]
+    fn test_match_hidden_and_visible() {
+        let doc = parse_doc(
+            "<p id=\"a\">A</p><p id=\"b\" style=\"color:red; DISPLAY: none\">B</p><div hidden><p \
+             id=\"c\">C</p></div><p id=\"d\" aria-hidden=\"true\">D</p>",
+        );
+        let ids = |selector: &str| {
+            let selectors = parse_selector(selector).unwrap();
+            doc.nodes()
+                .filter(|(id, _)| matches_selector(&doc, *id, &selectors))
+                .filter_map(|(_, n)| n.kind.attributes()?.get("id").map(str::to_string))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids("p:hidden"), ["b", "c", "d"]);
+        assert_eq!(ids("p:visible"), ["a"]);
+        assert_eq!(ids("p:not(:hidden)"), ["a"]);
+        assert_eq!(parse_selector("p:hidden").unwrap().slice()[0].to_css_string(), "p:hidden");
+    }
 }
@@ -53,7 +53,7 @@ impl<'a> Iterator for TextNodesIter<'a> {
                         self.stack.push(child_id);
                     }
                 }
-                NodeKind::Comment { .. } => {}
+                NodeKind::Comment { .. } | NodeKind::Doctype { .. } => {}
             }
         }
         None
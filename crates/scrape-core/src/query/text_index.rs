@@ -0,0 +1,205 @@
+//! An index over a document's text for repeated substring searches.
+//!
+//! [`find_all_by_text`](super::find_all_by_text) collects the text of every element for each
+//! search. [`TextIndex`] instead concatenates the document's text nodes once and indexes
+//! every three-byte sequence in it, so a search only verifies the positions of the
+//! needle's rarest trigram. Results are the same innermost elements `find_all_by_text`
+//! returns for a substring.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{Soup, query::TextIndex};
+//!
+//! let soup = Soup::parse("<ul><li>Free shipping</li><li>Ships <b>free</b> today</li></ul>");
+//! let index = TextIndex::build(soup.document());
+//! let ids = index.search(soup.document(), "free");
+//! assert_eq!(ids.len(), 1);
+//! assert_eq!(soup.document().get(ids[0]).unwrap().kind.tag_name(), Some("b"));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use super::find_all_by_text;
+use crate::dom::{Document, NodeId, NodeKind};
+
+/// A trigram index over the text of a document.
+///
+/// The index is a snapshot: it must be rebuilt after the document is modified.
+#[derive(Debug, Clone, Default)]
+pub struct TextIndex {
+    /// The text nodes of the document, concatenated in document order.
+    text: String,
+    /// The start of each non-empty text node in `text`, with its node.
+    spans: Vec<(usize, NodeId)>,
+    /// The start positions in `text` of every three-byte sequence.
+    trigrams: HashMap<[u8; 3], Vec<usize>>,
+}
+
+impl TextIndex {
+    /// Indexes the text of a document.
+    #[must_use]
+    pub fn build(doc: &Document) -> Self {
+        let mut text = String::new();
+        let mut spans = Vec::new();
+        if let Some(root) = doc.root() {
+            for id in doc.descendants(root) {
+                if let Some(NodeKind::Text { content }) = doc.get(id).map(|node| &node.kind)
+                    && !content.is_empty()
+                {
+                    spans.push((text.len(), id));
+                    text.push_str(content);
+                }
+            }
+        }
+
+        let mut trigrams: HashMap<[u8; 3], Vec<usize>> = HashMap::new();
+        for (start, window) in text.as_bytes().windows(3).enumerate() {
+            trigrams.entry([window[0], window[1], window[2]]).or_default().push(start);
+        }
+        Self { text, spans, trigrams }
+    }
+
+    /// Returns the length in bytes of the indexed text.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Returns whether the document has no text.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Finds all innermost elements whose text contains `needle`, in document order.
+    ///
+    /// `doc` must be the document the index was built from. An empty needle matches
+    /// every element and is answered without the index.
+    #[must_use]
+    pub fn search(&self, doc: &Document, needle: &str) -> Vec<NodeId> {
+        if needle.is_empty() {
+            return find_all_by_text(doc, needle);
+        }
+
+        // The element holding each match, keyed to the first match inside it.
+        let mut found: HashMap<NodeId, usize> = HashMap::new();
+        for start in self.occurrences(needle) {
+            if let Some(id) = self.element_around(doc, start, start + needle.len()) {
+                found.entry(id).or_insert(start);
+            }
+        }
+
+        // Keep only the innermost elements, as `find_all_by_text` does.
+        let mut outer = HashSet::new();
+        for &id in found.keys() {
+            outer.extend(doc.ancestors(id).filter(|ancestor| found.contains_key(ancestor)));
+        }
+        let mut found: Vec<_> = found.into_iter().filter(|(id, _)| !outer.contains(id)).collect();
+
+        // Innermost elements never contain each other, so their matches are in document order.
+        found.sort_unstable_by_key(|&(_, start)| start);
+        found.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Returns the start of every match of `needle`, including overlapping ones.
+    fn occurrences(&self, needle: &str) -> Vec<usize> {
+        let text = self.text.as_bytes();
+        let needle = needle.as_bytes();
+        if needle.len() < 3 {
+            #[cfg(feature = "simd")]
+            {
+                let mut starts = Vec::new();
+                let mut from = 0;
+                while let Some(offset) = crate::simd::find_text(&text[from..], needle) {
+                    starts.push(from + offset);
+                    from += offset + 1;
+                }
+                return starts;
+            }
+            #[cfg(not(feature = "simd"))]
+            return (0..text.len()).filter(|&start| text[start..].starts_with(needle)).collect();
+        }
+
+        let rarest = needle
+            .windows(3)
+            .enumerate()
+            .map(|(offset, window)| {
+                (offset, self.trigrams.get(window).map_or(&[][..], Vec::as_slice))
+            })
+            .min_by_key(|(_, positions)| positions.len());
+        let Some((offset, positions)) = rarest else { return Vec::new() };
+        positions
+            .iter()
+            .filter_map(|&position| position.checked_sub(offset))
+            .filter(|&start| text[start..].starts_with(needle))
+            .collect()
+    }
+
+    /// Returns the innermost element whose text covers `start..end`.
+    fn element_around(&self, doc: &Document, start: usize, end: usize) -> Option<NodeId> {
+        let first = self.text_node_at(start);
+        let last = self.text_node_at(end - 1);
+        let first_parent = doc.parent(first)?;
+        if first == last {
+            return Some(first_parent);
+        }
+        let around_first: HashSet<_> =
+            std::iter::once(first_parent).chain(doc.ancestors(first_parent)).collect();
+        doc.ancestors(last).find(|id| around_first.contains(id))
+    }
+
+    /// Returns the text node containing byte `position` of the indexed text.
+    fn text_node_at(&self, position: usize) -> NodeId {
+        let index = self.spans.partition_point(|&(start, _)| start <= position);
+        self.spans[index - 1].1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Soup;
+
+    fn search(soup: &Soup, index: &TextIndex, needle: &str) -> Vec<NodeId> {
+        let ids = index.search(soup.document(), needle);
+        assert_eq!(ids, find_all_by_text(soup.document(), needle), "needle {needle:?}");
+        ids
+    }
+
+    #[test]
+    fn test_search_matches_find_all_by_text() {
+        let soup = Soup::parse(
+            "<div><p>Order <b>#1042</b> shipped</p><p>Order #1043 \
+             pending</p><span>aa</span>a<i>x</i><span>ship</span>ped</div><footer>€ price: \
+             5€</footer>",
+        );
+        let index = TextIndex::build(soup.document());
+        let needles =
+            ["#", "Order", "Order #10", "shipped", "pending", "aa", "aaa", "€", "5€", "x"];
+        for needle in needles {
+            search(&soup, &index, needle);
+        }
+        assert!(search(&soup, &index, "missing").is_empty());
+        assert!(search(&soup, &index, "shipped pending").is_empty());
+        assert_eq!(search(&soup, &index, "Order").len(), 2);
+    }
+
+    #[test]
+    fn test_search_spanning_elements() {
+        let soup = Soup::parse("<ul><li>Ships <b>free</b> today</li><li>free</li></ul>");
+        let index = TextIndex::build(soup.document());
+        let ids = search(&soup, &index, "free today");
+        assert_eq!(ids.len(), 1);
+        assert_eq!(soup.document().get(ids[0]).unwrap().kind.tag_name(), Some("li"));
+        assert_eq!(search(&soup, &index, "free").len(), 2);
+    }
+
+    #[test]
+    fn test_empty_document() {
+        let soup = Soup::parse("");
+        let index = TextIndex::build(soup.document());
+        assert!(index.is_empty());
+        assert!(index.search(soup.document(), "a").is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+//! BeautifulSoup-style text search, like `find(string=re.compile(...))`.
+//!
+//! [`find_by_text`] and [`find_all_by_text`] return the innermost elements whose text
+//! matches a [`TextPattern`]: an element is skipped when one of its child elements already
+//! matches, so searching for `"Price"` finds the `<th>` rather than the `<tr>`, `<table>`,
+//! and `<body>` around it. [`FilterText`] narrows the results of a CSS query the same way.
+//!
+//! Patterns are substrings (`&str`, `String`, `char`) or, with the `regex` feature,
+//! `regex::Regex`. Text is matched as [`Tag::text`] returns it, including whitespace.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::Soup;
+//!
+//! let soup = Soup::parse(
+//!     "<table><tr><th>Name</th><td>Tea</td></tr><tr><th>Price</th><td>4.50</td></tr></table>",
+//! );
+//! let label = soup.find_by_text("Price").unwrap();
+//! assert_eq!(label.next_sibling().unwrap().text(), "4.50");
+//! ```
+
+use crate::{
+    Tag,
+    dom::{Document, NodeId},
+    serialize::collect_text,
+};
+
+/// Something that element text can be matched against.
+pub trait TextPattern {
+    /// Returns true if `text` matches this pattern.
+    fn is_match(&self, text: &str) -> bool;
+}
+
+impl TextPattern for str {
+    fn is_match(&self, text: &str) -> bool {
+        #[cfg(feature = "simd")]
+        {
+            crate::simd::contains_text(text.as_bytes(), self.as_bytes())
+        }
+        #[cfg(not(feature = "simd"))]
+        {
+            text.contains(self)
+        }
+    }
+}
+
+impl TextPattern for String {
+    fn is_match(&self, text: &str) -> bool {
+        self.as_str().is_match(text)
+    }
+}
+
+impl TextPattern for char {
+    fn is_match(&self, text: &str) -> bool {
+        text.contains(*self)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl TextPattern for regex::Regex {
+    fn is_match(&self, text: &str) -> bool {
+        regex::Regex::is_match(self, text)
+    }
+}
+
+impl<T: TextPattern + ?Sized> TextPattern for &T {
+    fn is_match(&self, text: &str) -> bool {
+        (**self).is_match(text)
+    }
+}
+
+/// Finds the first innermost element whose text matches `pattern`, in document order.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, query::find_by_text};
+///
+/// let soup = Soup::parse("<dl><dt>SKU</dt><dd>T-100</dd></dl>");
+/// let id = find_by_text(soup.document(), "SKU").unwrap();
+/// assert_eq!(soup.document().get(id).unwrap().kind.tag_name(), Some("dt"));
+/// ```
+#[must_use]
+pub fn find_by_text(doc: &Document, pattern: impl TextPattern) -> Option<NodeId> {
+    let mut found = Vec::new();
+    collect(doc, doc.root()?, &pattern, true, &mut found);
+    found.pop()
+}
+
+/// Finds all innermost elements whose text matches `pattern`, in document order.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, query::find_all_by_text};
+///
+/// let soup = Soup::parse("<ul><li>Tea <b>sale</b></li><li>Cake</li><li>Sale</li></ul>");
+/// assert_eq!(find_all_by_text(soup.document(), "sale").len(), 1);
+/// ```
+#[must_use]
+pub fn find_all_by_text(doc: &Document, pattern: impl TextPattern) -> Vec<NodeId> {
+    let mut found = Vec::new();
+    if let Some(root) = doc.root() {
+        collect(doc, root, &pattern, false, &mut found);
+    }
+    found
+}
+
+/// Finds the first innermost descendant of `root` whose text matches `pattern`.
+#[must_use]
+pub fn find_by_text_within(
+    doc: &Document,
+    root: NodeId,
+    pattern: impl TextPattern,
+) -> Option<NodeId> {
+    let mut found = Vec::new();
+    collect_children(doc, root, &pattern, true, &mut found);
+    found.pop()
+}
+
+/// Finds all innermost descendants of `root` whose text matches `pattern`.
+#[must_use]
+pub fn find_all_by_text_within(
+    doc: &Document,
+    root: NodeId,
+    pattern: impl TextPattern,
+) -> Vec<NodeId> {
+    let mut found = Vec::new();
+    collect_children(doc, root, &pattern, false, &mut found);
+    found
+}
+
+/// Pushes the innermost matching elements at or below `id` onto `found`, returning whether
+/// there were any. Matches never contain each other, so they are pushed in document order.
+fn collect<P: TextPattern + ?Sized>(
+    doc: &Document,
+    id: NodeId,
+    pattern: &P,
+    first: bool,
+    found: &mut Vec<NodeId>,
+) -> bool {
+    if !doc.get(id).is_some_and(|node| node.kind.is_element()) {
+        return false;
+    }
+    if collect_children(doc, id, pattern, first, found) {
+        return true;
+    }
+    let mut text = String::new();
+    collect_text(doc, id, &mut text);
+    let matched = pattern.is_match(&text);
+    if matched {
+        found.push(id);
+    }
+    matched
+}
+
+fn collect_children<P: TextPattern + ?Sized>(
+    doc: &Document,
+    id: NodeId,
+    pattern: &P,
+    first: bool,
+    found: &mut Vec<NodeId>,
+) -> bool {
+    let mut matched = false;
+    for child in doc.children(id) {
+        matched |= collect(doc, child, pattern, first, found);
+        if first && matched {
+            break;
+        }
+    }
+    matched
+}
+
+/// Keeps only the elements whose text matches a pattern.
+///
+/// Implemented for the results of [`Soup::select`](crate::Soup::select) and
+/// [`Tag::select`], with or without unwrapping them first.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, query::FilterText};
+///
+/// let soup = Soup::parse("<p>Ships today</p><p>Out of stock</p><p>Ships in 3 days</p>");
+/// let shipping = soup.select("p").filter_text("Ships").unwrap();
+/// assert_eq!(shipping.len(), 2);
+/// ```
+pub trait FilterText: Sized {
+    /// Removes the elements whose text does not match `pattern`.
+    #[must_use]
+    fn filter_text(self, pattern: impl TextPattern) -> Self;
+}
+
+impl FilterText for Vec<Tag<'_>> {
+    fn filter_text(mut self, pattern: impl TextPattern) -> Self {
+        let mut text = String::new();
+        self.retain(|tag| {
+            text.clear();
+            tag.text_into(&mut text);
+            pattern.is_match(&text)
+        });
+        self
+    }
+}
+
+impl<T: FilterText, E> FilterText for Result<T, E> {
+    fn filter_text(self, pattern: impl TextPattern) -> Self {
+        self.map(|tags| tags.filter_text(pattern))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Soup;
+
+    fn html_of(soup: &Soup, ids: &[NodeId]) -> Vec<String> {
+        ids.iter().map(|&id| Tag::new(soup.document(), id).outer_html()).collect()
+    }
+
+    #[test]
+    fn test_find_by_text_innermost() {
+        let soup = Soup::parse(
+            "<table><tr><th>Price \
+             <small>(USD)</small></th><td>4</td></tr><tr><th><b>Price</b></th><td>5</td></\
+             tr><tr><td>Pri<i>ce</i></td></tr></table>",
+        );
+        let doc = soup.document();
+        assert_eq!(
+            html_of(&soup, &find_all_by_text(doc, "Price")),
+            ["<th>Price <small>(USD)</small></th>", "<b>Price</b>", "<td>Pri<i>ce</i></td>"]
+        );
+        let small = find_by_text(doc, 'U').unwrap();
+        assert_eq!(html_of(&soup, &[small]), ["<small>(USD)</small>"]);
+        assert!(find_by_text(doc, "Weight").is_none());
+    }
+
+    #[test]
+    fn test_find_by_text_within() {
+        let soup = Soup::parse("<div id='a'><p>Tea</p></div><div id='b'><p>Tea</p></div>");
+        let b = soup.find("#b").unwrap().unwrap();
+        let found = find_all_by_text_within(soup.document(), b.node_id(), "Tea".to_string());
+        assert_eq!(found.len(), 1);
+        assert_eq!(Tag::new(soup.document(), found[0]).parent().unwrap().get("id"), Some("b"));
+
+        let p = b.find("p").unwrap().unwrap();
+        assert!(find_by_text_within(soup.document(), p.node_id(), "Tea").is_none());
+    }
+
+    #[test]
+    fn test_filter_text() {
+        let soup = Soup::parse("<li>Tea</li><li>Cake</li><li>Iced tea</li>");
+        let tags = soup.select("li").unwrap().filter_text("ea");
+        assert_eq!(tags.iter().map(Tag::text).collect::<Vec<_>>(), ["Tea", "Iced tea"]);
+        assert!(soup.select("li").filter_text("Pie").unwrap().is_empty());
+        assert!(soup.select("li[").filter_text("Tea").is_err());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_regex_pattern() {
+        let soup = Soup::parse("<p>Order #1042</p><p>Order pending</p>");
+        let re = regex::Regex::new(r"#\d+").unwrap();
+        assert_eq!(soup.find_all_by_text(&re).len(), 1);
+        assert_eq!(soup.select("p").filter_text(re).unwrap()[0].text(), "Order #1042");
+    }
+}
@@ -0,0 +1,555 @@
+//! XPath 1.0 evaluation over a [`Document`].
+
+// Positions, sizes, and string lengths become XPath numbers, which are `f64`, and XPath
+// defines equality on them exactly.
+#![allow(clippy::cast_precision_loss, clippy::float_cmp)]
+
+use std::cmp::Ordering;
+
+use super::{
+    XPathNode, XPathValue,
+    parser::{ArithOp, Axis, CompareOp, Expr, Function, NodeTest, Path, PathStart, Step},
+};
+use crate::{
+    dom::{Atom, Document, NodeId, NodeKind},
+    query::{QueryError, QueryResult},
+    serialize::collect_text,
+};
+
+/// Marker for nodes that are not reachable from the document root.
+const DETACHED: usize = usize::MAX;
+
+/// The dynamic context for evaluating an expression.
+struct Context {
+    node: XPathNode,
+    position: usize,
+    size: usize,
+}
+
+/// Evaluates expressions against one document.
+pub struct Evaluator<'a> {
+    doc: &'a Document,
+    /// Pre-order position of each arena node; the root node is 0.
+    order: Vec<usize>,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(doc: &'a Document) -> Self {
+        let mut order = vec![DETACHED; doc.len()];
+        if let Some(root) = doc.root() {
+            for (position, id) in std::iter::once(root).chain(doc.descendants(root)).enumerate() {
+                order[id.index()] = position + 1;
+            }
+        }
+        Self { doc, order }
+    }
+
+    /// Evaluates `expr` with `node` as the context node.
+    pub fn evaluate(&self, expr: &Expr, node: XPathNode) -> QueryResult<XPathValue> {
+        self.eval(expr, &Context { node, position: 1, size: 1 })
+    }
+
+    /// Returns the string-value of a node.
+    pub fn string_value(&self, node: &XPathNode) -> String {
+        match node {
+            XPathNode::Root => {
+                let mut buf = String::new();
+                if let Some(root) = self.doc.root() {
+                    collect_text(self.doc, root, &mut buf);
+                }
+                buf
+            }
+            XPathNode::Node(id) => match self.doc.get(*id).map(|n| &n.kind) {
+                Some(NodeKind::Element { .. }) => {
+                    let mut buf = String::new();
+                    collect_text(self.doc, *id, &mut buf);
+                    buf
+                }
+                Some(NodeKind::Text { content }) => content.to_string(),
+                Some(NodeKind::Comment { content }) => content.clone(),
+                Some(NodeKind::Doctype { .. }) | None => String::new(),
+            },
+            XPathNode::Attribute { element, name } => self
+                .doc
+                .get(*element)
+                .and_then(|n| n.kind.attributes())
+                .and_then(|attrs| attrs.get(name))
+                .map(str::to_string)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Converts a value to a string (XPath `string()`).
+    pub fn string(&self, value: &XPathValue) -> String {
+        match value {
+            XPathValue::Nodes(nodes) => {
+                nodes.first().map(|n| self.string_value(n)).unwrap_or_default()
+            }
+            XPathValue::String(s) => s.clone(),
+            XPathValue::Number(n) => format_number(*n),
+            XPathValue::Boolean(b) => b.to_string(),
+        }
+    }
+
+    fn number(&self, value: &XPathValue) -> f64 {
+        match value {
+            XPathValue::Number(n) => *n,
+            XPathValue::Boolean(b) => f64::from(u8::from(*b)),
+            XPathValue::String(s) => parse_number(s),
+            XPathValue::Nodes(_) => parse_number(&self.string(value)),
+        }
+    }
+
+    // ==================== Expressions ====================
+
+    fn eval(&self, expr: &Expr, ctx: &Context) -> QueryResult<XPathValue> {
+        Ok(match expr {
+            Expr::Or(left, right) => XPathValue::Boolean(
+                self.eval(left, ctx)?.to_boolean() || self.eval(right, ctx)?.to_boolean(),
+            ),
+            Expr::And(left, right) => XPathValue::Boolean(
+                self.eval(left, ctx)?.to_boolean() && self.eval(right, ctx)?.to_boolean(),
+            ),
+            Expr::Compare(op, left, right) => {
+                let left = self.eval(left, ctx)?;
+                let right = self.eval(right, ctx)?;
+                XPathValue::Boolean(self.compare(*op, &left, &right))
+            }
+            Expr::Arith(op, left, right) => {
+                let left = self.number(&self.eval(left, ctx)?);
+                let right = self.number(&self.eval(right, ctx)?);
+                XPathValue::Number(match op {
+                    ArithOp::Add => left + right,
+                    ArithOp::Sub => left - right,
+                    ArithOp::Mul => left * right,
+                    ArithOp::Div => left / right,
+                    ArithOp::Mod => left % right,
+                })
+            }
+            Expr::Negate(inner) => XPathValue::Number(-self.number(&self.eval(inner, ctx)?)),
+            Expr::Union(left, right) => {
+                let mut nodes = self.node_set(left, ctx)?;
+                nodes.extend(self.node_set(right, ctx)?);
+                self.sort_unique(&mut nodes);
+                XPathValue::Nodes(nodes)
+            }
+            Expr::Literal(s) => XPathValue::String(s.clone()),
+            Expr::Number(n) => XPathValue::Number(*n),
+            Expr::Function(function, args) => self.call(*function, args, ctx)?,
+            Expr::Path(path) => XPathValue::Nodes(self.eval_path(path, ctx)?),
+        })
+    }
+
+    fn node_set(&self, expr: &Expr, ctx: &Context) -> QueryResult<Vec<XPathNode>> {
+        match self.eval(expr, ctx)? {
+            XPathValue::Nodes(nodes) => Ok(nodes),
+            other => Err(QueryError::invalid_xpath(format!(
+                "expected a node-set, found a {}",
+                other.type_name()
+            ))),
+        }
+    }
+
+    fn eval_path(&self, path: &Path, ctx: &Context) -> QueryResult<Vec<XPathNode>> {
+        let mut nodes = match &path.start {
+            PathStart::Root => vec![XPathNode::Root],
+            PathStart::Context => vec![ctx.node.clone()],
+            PathStart::Filter(primary, predicates) => {
+                let mut nodes = self.node_set(primary, ctx)?;
+                self.sort_unique(&mut nodes);
+                for predicate in predicates {
+                    nodes = self.filter(nodes, predicate)?;
+                }
+                nodes
+            }
+        };
+        for step in &path.steps {
+            nodes = self.eval_step(step, &nodes)?;
+        }
+        Ok(nodes)
+    }
+
+    fn eval_step(&self, step: &Step, input: &[XPathNode]) -> QueryResult<Vec<XPathNode>> {
+        let mut output = Vec::new();
+        for node in input {
+            let mut selected: Vec<XPathNode> = self
+                .axis(node, step.axis)
+                .into_iter()
+                .filter(|candidate| self.matches_test(candidate, step.axis, &step.test))
+                .collect();
+            for predicate in &step.predicates {
+                selected = self.filter(selected, predicate)?;
+            }
+            output.extend(selected);
+        }
+        self.sort_unique(&mut output);
+        Ok(output)
+    }
+
+    /// Applies a predicate, keeping nodes in their current (axis) order.
+    fn filter(&self, nodes: Vec<XPathNode>, predicate: &Expr) -> QueryResult<Vec<XPathNode>> {
+        let size = nodes.len();
+        let mut kept = Vec::with_capacity(size);
+        for (index, node) in nodes.into_iter().enumerate() {
+            let ctx = Context { node, position: index + 1, size };
+            let keep = match self.eval(predicate, &ctx)? {
+                XPathValue::Number(n) => n == ctx.position as f64,
+                other => other.to_boolean(),
+            };
+            if keep {
+                kept.push(ctx.node);
+            }
+        }
+        Ok(kept)
+    }
+
+    fn compare(&self, op: CompareOp, left: &XPathValue, right: &XPathValue) -> bool {
+        match (left, right) {
+            (XPathValue::Nodes(a), XPathValue::Nodes(b)) => {
+                let b: Vec<XPathValue> =
+                    b.iter().map(|n| XPathValue::String(self.string_value(n))).collect();
+                a.iter().any(|n| {
+                    let a = XPathValue::String(self.string_value(n));
+                    b.iter().any(|b| self.compare_scalars(op, &a, b))
+                })
+            }
+            (XPathValue::Nodes(a), XPathValue::Boolean(_)) => {
+                self.compare_scalars(op, &XPathValue::Boolean(!a.is_empty()), right)
+            }
+            (XPathValue::Boolean(_), XPathValue::Nodes(b)) => {
+                self.compare_scalars(op, left, &XPathValue::Boolean(!b.is_empty()))
+            }
+            (XPathValue::Nodes(a), _) => a.iter().any(|n| {
+                self.compare_scalars(op, &XPathValue::String(self.string_value(n)), right)
+            }),
+            (_, XPathValue::Nodes(b)) => b
+                .iter()
+                .any(|n| self.compare_scalars(op, left, &XPathValue::String(self.string_value(n)))),
+            _ => self.compare_scalars(op, left, right),
+        }
+    }
+
+    fn compare_scalars(&self, op: CompareOp, left: &XPathValue, right: &XPathValue) -> bool {
+        let either = |f: fn(&XPathValue) -> bool| f(left) || f(right);
+        match op {
+            CompareOp::Eq | CompareOp::Ne => {
+                let equal = if either(|v| matches!(v, XPathValue::Boolean(_))) {
+                    left.to_boolean() == right.to_boolean()
+                } else if either(|v| matches!(v, XPathValue::Number(_))) {
+                    self.number(left) == self.number(right)
+                } else {
+                    self.string(left) == self.string(right)
+                };
+                equal == (op == CompareOp::Eq)
+            }
+            CompareOp::Lt => self.number(left) < self.number(right),
+            CompareOp::Le => self.number(left) <= self.number(right),
+            CompareOp::Gt => self.number(left) > self.number(right),
+            CompareOp::Ge => self.number(left) >= self.number(right),
+        }
+    }
+
+    // ==================== Functions ====================
+
+    #[allow(clippy::too_many_lines)]
+    fn call(&self, function: Function, args: &[Expr], ctx: &Context) -> QueryResult<XPathValue> {
+        let string_arg = |i: usize| -> QueryResult<String> {
+            match args.get(i) {
+                Some(arg) => Ok(self.string(&self.eval(arg, ctx)?)),
+                None => Ok(self.string_value(&ctx.node)),
+            }
+        };
+        let number_arg =
+            |i: usize| -> QueryResult<f64> { Ok(self.number(&self.eval(&args[i], ctx)?)) };
+
+        Ok(match function {
+            Function::Last => XPathValue::Number(ctx.size as f64),
+            Function::Position => XPathValue::Number(ctx.position as f64),
+            Function::Count => XPathValue::Number(self.node_set(&args[0], ctx)?.len() as f64),
+            Function::LocalName | Function::Name => {
+                let node = match args.first() {
+                    Some(arg) => self.node_set(arg, ctx)?.into_iter().next(),
+                    None => Some(ctx.node.clone()),
+                };
+                XPathValue::String(node.map(|n| self.node_name(&n)).unwrap_or_default())
+            }
+            Function::String => XPathValue::String(string_arg(0)?),
+            Function::Concat => {
+                let mut result = String::new();
+                for i in 0..args.len() {
+                    result.push_str(&string_arg(i)?);
+                }
+                XPathValue::String(result)
+            }
+            Function::StartsWith => {
+                XPathValue::Boolean(string_arg(0)?.starts_with(&string_arg(1)?))
+            }
+            Function::EndsWith => XPathValue::Boolean(string_arg(0)?.ends_with(&string_arg(1)?)),
+            Function::Contains => XPathValue::Boolean(string_arg(0)?.contains(&string_arg(1)?)),
+            Function::SubstringBefore => {
+                let (haystack, needle) = (string_arg(0)?, string_arg(1)?);
+                let before = haystack.find(&needle).map_or("", |i| &haystack[..i]);
+                XPathValue::String(before.to_string())
+            }
+            Function::SubstringAfter => {
+                let (haystack, needle) = (string_arg(0)?, string_arg(1)?);
+                let after = haystack.find(&needle).map_or("", |i| &haystack[i + needle.len()..]);
+                XPathValue::String(after.to_string())
+            }
+            Function::Substring => {
+                let s = string_arg(0)?;
+                let start = round(number_arg(1)?);
+                let end =
+                    if args.len() > 2 { start + round(number_arg(2)?) } else { f64::INFINITY };
+                let result = s
+                    .chars()
+                    .enumerate()
+                    .filter(|&(i, _)| {
+                        let position = (i + 1) as f64;
+                        position >= start && position < end
+                    })
+                    .map(|(_, c)| c)
+                    .collect();
+                XPathValue::String(result)
+            }
+            Function::StringLength => XPathValue::Number(string_arg(0)?.chars().count() as f64),
+            Function::NormalizeSpace => {
+                XPathValue::String(string_arg(0)?.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+            Function::Translate => {
+                let (s, from, to) = (string_arg(0)?, string_arg(1)?, string_arg(2)?);
+                let from: Vec<char> = from.chars().collect();
+                let to: Vec<char> = to.chars().collect();
+                let result = s
+                    .chars()
+                    .filter_map(|c| {
+                        from.iter().position(|&f| f == c).map_or(Some(c), |i| to.get(i).copied())
+                    })
+                    .collect();
+                XPathValue::String(result)
+            }
+            Function::LowerCase => XPathValue::String(string_arg(0)?.to_lowercase()),
+            Function::UpperCase => XPathValue::String(string_arg(0)?.to_uppercase()),
+            Function::Boolean => XPathValue::Boolean(self.eval(&args[0], ctx)?.to_boolean()),
+            Function::Not => XPathValue::Boolean(!self.eval(&args[0], ctx)?.to_boolean()),
+            Function::True => XPathValue::Boolean(true),
+            Function::False => XPathValue::Boolean(false),
+            Function::Number => XPathValue::Number(match args.first() {
+                Some(arg) => self.number(&self.eval(arg, ctx)?),
+                None => parse_number(&self.string_value(&ctx.node)),
+            }),
+            Function::Sum => XPathValue::Number(
+                self.node_set(&args[0], ctx)?
+                    .iter()
+                    .map(|n| parse_number(&self.string_value(n)))
+                    .sum(),
+            ),
+            Function::Floor => XPathValue::Number(number_arg(0)?.floor()),
+            Function::Ceiling => XPathValue::Number(number_arg(0)?.ceil()),
+            Function::Round => XPathValue::Number(round(number_arg(0)?)),
+        })
+    }
+
+    fn node_name(&self, node: &XPathNode) -> String {
+        match node {
+            XPathNode::Root => String::new(),
+            XPathNode::Node(id) => {
+                self.doc.get(*id).and_then(|n| n.kind.tag_name()).unwrap_or_default().to_string()
+            }
+            XPathNode::Attribute { name, .. } => name.clone(),
+        }
+    }
+
+    // ==================== Axes ====================
+
+    /// Returns the nodes on `axis` from `node`, in axis order.
+    fn axis(&self, node: &XPathNode, axis: Axis) -> Vec<XPathNode> {
+        let doc = self.doc;
+        match node {
+            XPathNode::Root => {
+                let root = doc.root();
+                match axis {
+                    Axis::Child => root.map(XPathNode::Node).into_iter().collect(),
+                    Axis::Descendant | Axis::DescendantOrSelf => {
+                        let mut nodes = Vec::new();
+                        if axis == Axis::DescendantOrSelf {
+                            nodes.push(XPathNode::Root);
+                        }
+                        if let Some(root) = root {
+                            nodes.push(XPathNode::Node(root));
+                            nodes.extend(doc.descendants(root).map(XPathNode::Node));
+                        }
+                        nodes
+                    }
+                    Axis::SelfAxis | Axis::AncestorOrSelf => vec![XPathNode::Root],
+                    _ => Vec::new(),
+                }
+            }
+            XPathNode::Node(id) => self.node_axis(*id, axis),
+            XPathNode::Attribute { element, .. } => match axis {
+                Axis::SelfAxis => vec![node.clone()],
+                Axis::Parent => vec![XPathNode::Node(*element)],
+                Axis::Ancestor | Axis::AncestorOrSelf => {
+                    let mut nodes = Vec::new();
+                    if axis == Axis::AncestorOrSelf {
+                        nodes.push(node.clone());
+                    }
+                    nodes.extend(self.node_axis(*element, Axis::AncestorOrSelf));
+                    nodes
+                }
+                Axis::Following => {
+                    let mut nodes: Vec<_> =
+                        doc.descendants(*element).map(XPathNode::Node).collect();
+                    nodes.extend(self.node_axis(*element, Axis::Following));
+                    nodes
+                }
+                Axis::Preceding => self.node_axis(*element, Axis::Preceding),
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    fn node_axis(&self, id: NodeId, axis: Axis) -> Vec<XPathNode> {
+        let doc = self.doc;
+        match axis {
+            Axis::Child => doc.children(id).map(XPathNode::Node).collect(),
+            Axis::Descendant => doc.descendants(id).map(XPathNode::Node).collect(),
+            Axis::DescendantOrSelf => {
+                std::iter::once(id).chain(doc.descendants(id)).map(XPathNode::Node).collect()
+            }
+            Axis::SelfAxis => vec![XPathNode::Node(id)],
+            Axis::Parent => match doc.parent(id) {
+                Some(parent) => vec![XPathNode::Node(parent)],
+                None if doc.root() == Some(id) => vec![XPathNode::Root],
+                None => Vec::new(),
+            },
+            Axis::Ancestor | Axis::AncestorOrSelf => {
+                let mut nodes = Vec::new();
+                if axis == Axis::AncestorOrSelf {
+                    nodes.push(XPathNode::Node(id));
+                }
+                nodes.extend(doc.ancestors(id).map(XPathNode::Node));
+                if self.order[id.index()] != DETACHED {
+                    nodes.push(XPathNode::Root);
+                }
+                nodes
+            }
+            Axis::FollowingSibling => doc.next_siblings(id).map(XPathNode::Node).collect(),
+            Axis::PrecedingSibling => doc.prev_siblings(id).map(XPathNode::Node).collect(),
+            Axis::Following => {
+                let mut nodes = Vec::new();
+                for node in std::iter::once(id).chain(doc.ancestors(id)) {
+                    for sibling in doc.next_siblings(node) {
+                        nodes.push(XPathNode::Node(sibling));
+                        nodes.extend(doc.descendants(sibling).map(XPathNode::Node));
+                    }
+                }
+                nodes
+            }
+            Axis::Preceding => {
+                let mut nodes = Vec::new();
+                for node in std::iter::once(id).chain(doc.ancestors(id)) {
+                    for sibling in doc.prev_siblings(node) {
+                        let start = nodes.len();
+                        nodes.push(XPathNode::Node(sibling));
+                        nodes.extend(doc.descendants(sibling).map(XPathNode::Node));
+                        nodes[start..].reverse();
+                    }
+                }
+                nodes
+            }
+            Axis::Attribute => {
+                let Some(attributes) = doc.get(id).and_then(|n| n.kind.attributes()) else {
+                    return Vec::new();
+                };
+                let mut names: Vec<&Atom> = attributes.keys().collect();
+                names.sort();
+                // Only the first of repeated attributes is addressable by name.
+                names.dedup();
+                names
+                    .into_iter()
+                    .map(|name| XPathNode::Attribute { element: id, name: name.to_string() })
+                    .collect()
+            }
+        }
+    }
+
+    fn matches_test(&self, node: &XPathNode, axis: Axis, test: &NodeTest) -> bool {
+        match node {
+            XPathNode::Root => *test == NodeTest::Node,
+            XPathNode::Attribute { name, .. } => match test {
+                NodeTest::Name(expected) => name.eq_ignore_ascii_case(expected),
+                NodeTest::Wildcard | NodeTest::Node => true,
+                NodeTest::Text | NodeTest::Comment => false,
+            },
+            XPathNode::Node(id) => {
+                let Some(kind) = self.doc.get(*id).map(|n| &n.kind) else { return false };
+                match test {
+                    _ if axis == Axis::Attribute => false,
+                    NodeTest::Name(expected) => {
+                        kind.tag_name().is_some_and(|name| name.eq_ignore_ascii_case(expected))
+                    }
+                    NodeTest::Wildcard => kind.is_element(),
+                    NodeTest::Node => true,
+                    NodeTest::Text => kind.is_text(),
+                    NodeTest::Comment => kind.is_comment(),
+                }
+            }
+        }
+    }
+
+    // ==================== Document Order ====================
+
+    fn order_key<'n>(&self, node: &'n XPathNode) -> (usize, u8, &'n str) {
+        match node {
+            XPathNode::Root => (0, 0, ""),
+            XPathNode::Node(id) => (self.order.get(id.index()).copied().unwrap_or(DETACHED), 0, ""),
+            XPathNode::Attribute { element, name } => {
+                (self.order.get(element.index()).copied().unwrap_or(DETACHED), 1, name)
+            }
+        }
+    }
+
+    fn sort_unique(&self, nodes: &mut Vec<XPathNode>) {
+        nodes.sort_by(|a, b| self.compare_order(a, b));
+        nodes.dedup();
+    }
+
+    fn compare_order(&self, a: &XPathNode, b: &XPathNode) -> Ordering {
+        self.order_key(a).cmp(&self.order_key(b)).then_with(|| match (a, b) {
+            // Detached nodes share an order key; fall back to arena order for stability.
+            (XPathNode::Node(x), XPathNode::Node(y)) => x.index().cmp(&y.index()),
+            _ => Ordering::Equal,
+        })
+    }
+}
+
+/// Formats a number as XPath `string()` does: integers without a fraction.
+pub fn format_number(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_string()
+    } else if n.is_infinite() {
+        let sign = if n > 0.0 { "" } else { "-" };
+        format!("{sign}Infinity")
+    } else if n == 0.0 {
+        "0".to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+/// Parses a string as XPath `number()` does; anything but a plain decimal is `NaN`.
+fn parse_number(s: &str) -> f64 {
+    let s = s.trim_matches(|c: char| matches!(c, ' ' | '\t' | '\n' | '\r'));
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let valid = !digits.is_empty()
+        && digits.bytes().any(|b| b.is_ascii_digit())
+        && digits.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+        && digits.bytes().filter(|&b| b == b'.').count() <= 1;
+    if valid { s.parse().unwrap_or(f64::NAN) } else { f64::NAN }
+}
+
+/// XPath `round()`: halves round towards positive infinity.
+fn round(n: f64) -> f64 {
+    if n.is_nan() || n.is_infinite() { n } else { (n + 0.5).floor() }
+}
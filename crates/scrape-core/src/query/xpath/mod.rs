@@ -0,0 +1,312 @@
+//! XPath 1.0 queries.
+//!
+//! Supports the full XPath 1.0 expression grammar minus variables and namespaces:
+//!
+//! - Abbreviated syntax: `//div/p`, `.`, `..`, `@href`, `(//li)[last()]`
+//! - All axes, e.g. `ancestor::ul`, `following-sibling::td[1]`, `preceding::h2`
+//! - Node tests: names (matched ASCII case-insensitively), `*`, `node()`, `text()`, `comment()`
+//! - Predicates, operators (`or`, `and`, `=`, `!=`, `<`, `+`, `div`, `mod`, `|`, ...)
+//! - Core functions such as `contains()`, `starts-with()`, `normalize-space()`, `count()`,
+//!   `position()`, `last()`, `string()`, `substring()`, `translate()`, plus the XPath 2.0
+//!   conveniences `ends-with()`, `lower-case()`, and `upper-case()`
+//!
+//! The document node is the parent of the root element, so absolute paths behave as in
+//! lxml: `/html/body` selects the `<body>` element.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::Soup;
+//!
+//! let soup = Soup::parse(
+//!     r#"<ul class="nav"><li><a href="/a">A</a></li><li class="active"><a href="/b">B</a></li></ul>"#,
+//! );
+//! let active = soup.xpath("//li[contains(@class, 'active')]/a").unwrap();
+//! assert_eq!(active[0].text(), "B");
+//!
+//! let hrefs = soup.xpath_text("//a/@href").unwrap();
+//! assert_eq!(hrefs, vec!["/a", "/b"]);
+//! ```
+
+mod eval;
+mod parser;
+
+use self::{eval::Evaluator, parser::Expr};
+use crate::{
+    dom::{Document, NodeId},
+    query::{QueryError, QueryResult},
+};
+
+/// A node selected by an XPath expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XPathNode {
+    /// The document node, parent of the root element.
+    Root,
+    /// An element, text, or comment node.
+    Node(NodeId),
+    /// An attribute of an element.
+    Attribute {
+        /// The element that owns the attribute.
+        element: NodeId,
+        /// The attribute name.
+        name: String,
+    },
+}
+
+/// The result of evaluating an XPath expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum XPathValue {
+    /// A node-set, in document order without duplicates.
+    Nodes(Vec<XPathNode>),
+    /// A string.
+    String(String),
+    /// A number.
+    Number(f64),
+    /// A boolean.
+    Boolean(bool),
+}
+
+impl XPathValue {
+    /// Converts the value to a boolean as XPath `boolean()` does.
+    #[must_use]
+    pub fn to_boolean(&self) -> bool {
+        match self {
+            Self::Nodes(nodes) => !nodes.is_empty(),
+            Self::String(s) => !s.is_empty(),
+            Self::Number(n) => *n != 0.0 && !n.is_nan(),
+            Self::Boolean(b) => *b,
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::Nodes(_) => "node-set",
+            Self::String(_) => "string",
+            Self::Number(_) => "number",
+            Self::Boolean(_) => "boolean",
+        }
+    }
+}
+
+/// A compiled XPath expression.
+///
+/// Compile once and evaluate against many documents to skip re-parsing.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{
+///     Soup,
+///     query::{XPath, XPathValue},
+/// };
+///
+/// let xpath = XPath::compile("count(//p[normalize-space()])").unwrap();
+/// let soup = Soup::parse("<p>one</p><p> </p><p>two</p>");
+/// assert_eq!(xpath.evaluate(soup.document(), None).unwrap(), XPathValue::Number(2.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct XPath {
+    expr: Expr,
+    source: String,
+}
+
+impl XPath {
+    /// Compiles an XPath expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidXPath`] if the expression is malformed, uses an
+    /// unknown axis or function, or passes the wrong number of arguments.
+    pub fn compile(expr: &str) -> QueryResult<Self> {
+        Ok(Self { expr: parser::parse(expr)?, source: expr.to_string() })
+    }
+
+    /// Returns the original expression string.
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluates the expression with `context` as the context node.
+    ///
+    /// `None` uses the document node, so relative and absolute paths start at the top.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidXPath`] if a path step or node-set function is
+    /// applied to a value that is not a node-set.
+    pub fn evaluate(&self, doc: &Document, context: Option<NodeId>) -> QueryResult<XPathValue> {
+        let node = context.map_or(XPathNode::Root, XPathNode::Node);
+        Evaluator::new(doc).evaluate(&self.expr, node)
+    }
+
+    /// Evaluates the expression and returns the selected elements in document order.
+    ///
+    /// Text, comment, and attribute nodes in the result are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidXPath`] if evaluation fails or the expression does not
+    /// return a node-set.
+    pub fn select(&self, doc: &Document, context: Option<NodeId>) -> QueryResult<Vec<NodeId>> {
+        match self.evaluate(doc, context)? {
+            XPathValue::Nodes(nodes) => Ok(nodes
+                .into_iter()
+                .filter_map(|node| match node {
+                    XPathNode::Node(id) if doc.get(id).is_some_and(|n| n.kind.is_element()) => {
+                        Some(id)
+                    }
+                    _ => None,
+                })
+                .collect()),
+            other => Err(QueryError::invalid_xpath(format!(
+                "expression returns a {}, not a node-set",
+                other.type_name()
+            ))),
+        }
+    }
+
+    /// Evaluates the expression and returns string values.
+    ///
+    /// A node-set yields the string-value of each node (text content for elements, the
+    /// value for attributes); any other result yields a single string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidXPath`] if evaluation fails.
+    pub fn select_text(&self, doc: &Document, context: Option<NodeId>) -> QueryResult<Vec<String>> {
+        let evaluator = Evaluator::new(doc);
+        let node = context.map_or(XPathNode::Root, XPathNode::Node);
+        Ok(match evaluator.evaluate(&self.expr, node)? {
+            XPathValue::Nodes(nodes) => nodes.iter().map(|n| evaluator.string_value(n)).collect(),
+            other => vec![evaluator.string(&other)],
+        })
+    }
+}
+
+/// Finds all elements selected by an XPath expression.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidXPath`] if the expression is invalid or does not return a
+/// node-set.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Html5everParser, Parser, query::xpath};
+///
+/// let doc = Html5everParser.parse("<div><p>A</p><p>B</p></div>").unwrap();
+/// assert_eq!(xpath(&doc, "//div/p[2]").unwrap().len(), 1);
+/// ```
+pub fn xpath(doc: &Document, expr: &str) -> QueryResult<Vec<NodeId>> {
+    XPath::compile(expr)?.select(doc, None)
+}
+
+/// Finds all elements selected by an XPath expression evaluated from `scope`.
+///
+/// Relative paths such as `./li` or `following-sibling::*` start at `scope`; absolute
+/// paths still start at the document node.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidXPath`] if the expression is invalid or does not return a
+/// node-set.
+pub fn xpath_within(doc: &Document, scope: NodeId, expr: &str) -> QueryResult<Vec<NodeId>> {
+    XPath::compile(expr)?.select(doc, Some(scope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Soup;
+
+    fn names(soup: &Soup, expr: &str) -> Vec<String> {
+        soup.xpath(expr)
+            .unwrap()
+            .iter()
+            .map(|tag| {
+                let name = tag.name().unwrap_or_default();
+                tag.get("id").map_or_else(|| name.to_string(), |id| format!("{name}#{id}"))
+            })
+            .collect()
+    }
+
+    const PAGE: &str = r#"<html><body>
+        <div id="main">
+            <h2 id="h1">First</h2>
+            <p id="p1">Alpha <b>bold</b></p>
+            <p id="p2" class="note important">Beta</p>
+            <h2 id="h2">Second</h2>
+            <p id="p3">Gamma</p>
+        </div>
+        <table><tr><td id="c1">1</td><td id="c2">2</td><td id="c3">3</td></tr></table>
+    </body></html>"#;
+
+    #[test]
+    fn test_xpath_abbreviated_paths() {
+        let soup = Soup::parse(PAGE);
+        assert_eq!(names(&soup, "/html/body/div/h2"), vec!["h2#h1", "h2#h2"]);
+        assert_eq!(names(&soup, "//p[2]"), vec!["p#p2"]);
+        assert_eq!(names(&soup, "(//p)[last()]"), vec!["p#p3"]);
+        assert_eq!(names(&soup, "//b/.."), vec!["p#p1"]);
+        assert_eq!(names(&soup, "//*[@class]"), vec!["p#p2"]);
+        assert_eq!(names(&soup, "//td[. = '2']"), vec!["td#c2"]);
+        assert_eq!(names(&soup, "//h2 | //b"), vec!["h2#h1", "b", "h2#h2"]);
+    }
+
+    #[test]
+    fn test_xpath_axes() {
+        let soup = Soup::parse(PAGE);
+        assert_eq!(names(&soup, "//b/ancestor::*[@id][1]"), vec!["p#p1"]);
+        assert_eq!(names(&soup, "//b/ancestor::div"), vec!["div#main"]);
+        assert_eq!(names(&soup, "//h2[1]/following-sibling::p"), vec!["p#p1", "p#p2", "p#p3"]);
+        assert_eq!(names(&soup, "//h2[2]/preceding-sibling::p[1]"), vec!["p#p2"]);
+        assert_eq!(names(&soup, "//p[@id='p3']/preceding::h2[1]"), vec!["h2#h2"]);
+        assert_eq!(names(&soup, "//div/following::td[position() > 1]"), vec!["td#c2", "td#c3"]);
+        assert_eq!(names(&soup, "//td[2]/self::td"), vec!["td#c2"]);
+        assert_eq!(names(&soup, "//p[@id='p1']/descendant-or-self::*"), vec!["p#p1", "b"]);
+    }
+
+    #[test]
+    fn test_xpath_functions() {
+        let soup = Soup::parse(PAGE);
+        assert_eq!(names(&soup, "//p[contains(@class, 'important')]"), vec!["p#p2"]);
+        assert_eq!(names(&soup, "//p[starts-with(., 'Gam')]"), vec!["p#p3"]);
+        assert_eq!(names(&soup, "//p[text()='Beta']"), vec!["p#p2"]);
+        assert_eq!(names(&soup, "//*[not(*) and normalize-space() = 'bold']"), vec!["b"]);
+        assert_eq!(names(&soup, "//td[number(.) mod 2 = 1]"), vec!["td#c1", "td#c3"]);
+        assert_eq!(names(&soup, "//div[count(p) = 3]"), vec!["div#main"]);
+
+        let text = |expr: &str| soup.xpath_text(expr).unwrap();
+        assert_eq!(text("//p[1]/text()"), vec!["Alpha "]);
+        assert_eq!(text("string(//h2[2])"), vec!["Second"]);
+        assert_eq!(text("concat(name(//td[1]), '-', sum(//td))"), vec!["td-6"]);
+        assert_eq!(text("substring('12345', 1.5, 2.6)"), vec!["234"]);
+        assert_eq!(text("translate('bar', 'abc', 'AB')"), vec!["BAr"]);
+        assert_eq!(text("substring-after(//p[2]/@class, ' ')"), vec!["important"]);
+        assert_eq!(text("upper-case(local-name(//*[@id='c1']))"), vec!["TD"]);
+        assert_eq!(text("1 div 0"), vec!["Infinity"]);
+        assert_eq!(text("round(-2.5)"), vec!["-2"]);
+        assert_eq!(text("7 div 2"), vec!["3.5"]);
+    }
+
+    #[test]
+    fn test_xpath_relative_to_tag() {
+        let soup = Soup::parse(PAGE);
+        let p2 = soup.xpath("//p[@id='p2']").unwrap()[0];
+        let next: Vec<_> = p2.xpath("following-sibling::*[1]").unwrap();
+        assert_eq!(next[0].get("id"), Some("h2"));
+        assert_eq!(p2.xpath_text("@class").unwrap(), vec!["note important"]);
+        assert_eq!(p2.xpath("//td").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_xpath_errors() {
+        let soup = Soup::parse(PAGE);
+        let err = soup.xpath("count(//p)").unwrap_err();
+        assert!(err.to_string().contains("not a node-set"), "{err}");
+        assert!(soup.xpath("'a'/b").is_err());
+        assert!(matches!(soup.xpath("//p[").unwrap_err(), QueryError::InvalidXPath { .. }));
+    }
+}
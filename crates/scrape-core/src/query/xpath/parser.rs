@@ -0,0 +1,924 @@
+//! XPath 1.0 tokenizer and recursive-descent parser.
+
+use crate::query::{QueryError, QueryResult};
+
+/// A parsed XPath expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// `a or b`
+    Or(Box<Expr>, Box<Expr>),
+    /// `a and b`
+    And(Box<Expr>, Box<Expr>),
+    /// Equality and relational comparisons.
+    Compare(CompareOp, Box<Expr>, Box<Expr>),
+    /// Arithmetic operators.
+    Arith(ArithOp, Box<Expr>, Box<Expr>),
+    /// Unary minus.
+    Negate(Box<Expr>),
+    /// `a | b`
+    Union(Box<Expr>, Box<Expr>),
+    /// String literal.
+    Literal(String),
+    /// Numeric literal.
+    Number(f64),
+    /// Function call.
+    Function(Function, Vec<Expr>),
+    /// Location path, optionally starting from a filter expression.
+    Path(Path),
+}
+
+/// Comparison operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Arithmetic operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+/// Where a location path starts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathStart {
+    /// `/...`: the document root.
+    Root,
+    /// A relative path: the context node.
+    Context,
+    /// A filter expression such as `(//a)[1]` or `id-function()/...`.
+    Filter(Box<Expr>, Vec<Expr>),
+}
+
+/// A location path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub start: PathStart,
+    pub steps: Vec<Step>,
+}
+
+/// A single location step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub axis: Axis,
+    pub test: NodeTest,
+    pub predicates: Vec<Expr>,
+}
+
+/// XPath axes.
+// `SelfAxis` because `Self` is a keyword.
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Ancestor,
+    AncestorOrSelf,
+    Attribute,
+    Child,
+    Descendant,
+    DescendantOrSelf,
+    Following,
+    FollowingSibling,
+    Parent,
+    Preceding,
+    PrecedingSibling,
+    SelfAxis,
+}
+
+impl Axis {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "ancestor" => Self::Ancestor,
+            "ancestor-or-self" => Self::AncestorOrSelf,
+            "attribute" => Self::Attribute,
+            "child" => Self::Child,
+            "descendant" => Self::Descendant,
+            "descendant-or-self" => Self::DescendantOrSelf,
+            "following" => Self::Following,
+            "following-sibling" => Self::FollowingSibling,
+            "parent" => Self::Parent,
+            "preceding" => Self::Preceding,
+            "preceding-sibling" => Self::PrecedingSibling,
+            "self" => Self::SelfAxis,
+            _ => return None,
+        })
+    }
+
+    /// Reverse axes number their predicate positions backwards from the context node.
+    pub fn is_reverse(self) -> bool {
+        matches!(
+            self,
+            Self::Ancestor | Self::AncestorOrSelf | Self::Preceding | Self::PrecedingSibling
+        )
+    }
+}
+
+/// Node tests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeTest {
+    /// `name`, compared ASCII case-insensitively as in HTML.
+    Name(String),
+    /// `*`
+    Wildcard,
+    /// `node()`
+    Node,
+    /// `text()`
+    Text,
+    /// `comment()`
+    Comment,
+}
+
+/// Supported functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    Last,
+    Position,
+    Count,
+    LocalName,
+    Name,
+    String,
+    Concat,
+    StartsWith,
+    EndsWith,
+    Contains,
+    SubstringBefore,
+    SubstringAfter,
+    Substring,
+    StringLength,
+    NormalizeSpace,
+    Translate,
+    LowerCase,
+    UpperCase,
+    Boolean,
+    Not,
+    True,
+    False,
+    Number,
+    Sum,
+    Floor,
+    Ceiling,
+    Round,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "last" => Self::Last,
+            "position" => Self::Position,
+            "count" => Self::Count,
+            "local-name" => Self::LocalName,
+            "name" => Self::Name,
+            "string" => Self::String,
+            "concat" => Self::Concat,
+            "starts-with" => Self::StartsWith,
+            "ends-with" => Self::EndsWith,
+            "contains" => Self::Contains,
+            "substring-before" => Self::SubstringBefore,
+            "substring-after" => Self::SubstringAfter,
+            "substring" => Self::Substring,
+            "string-length" => Self::StringLength,
+            "normalize-space" => Self::NormalizeSpace,
+            "translate" => Self::Translate,
+            "lower-case" => Self::LowerCase,
+            "upper-case" => Self::UpperCase,
+            "boolean" => Self::Boolean,
+            "not" => Self::Not,
+            "true" => Self::True,
+            "false" => Self::False,
+            "number" => Self::Number,
+            "sum" => Self::Sum,
+            "floor" => Self::Floor,
+            "ceiling" => Self::Ceiling,
+            "round" => Self::Round,
+            _ => return None,
+        })
+    }
+
+    /// Minimum and maximum number of arguments.
+    fn arity(self) -> (usize, usize) {
+        match self {
+            Self::Last | Self::Position | Self::True | Self::False => (0, 0),
+            Self::LocalName
+            | Self::Name
+            | Self::String
+            | Self::StringLength
+            | Self::NormalizeSpace
+            | Self::Number => (0, 1),
+            Self::Count
+            | Self::Boolean
+            | Self::Not
+            | Self::Sum
+            | Self::Floor
+            | Self::Ceiling
+            | Self::Round
+            | Self::LowerCase
+            | Self::UpperCase => (1, 1),
+            Self::StartsWith
+            | Self::EndsWith
+            | Self::Contains
+            | Self::SubstringBefore
+            | Self::SubstringAfter => (2, 2),
+            Self::Substring => (2, 3),
+            Self::Translate => (3, 3),
+            Self::Concat => (2, usize::MAX),
+        }
+    }
+}
+
+/// Parses an XPath expression.
+pub fn parse(input: &str) -> QueryResult<Expr> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some((token, offset)) => Err(error_at(format!("unexpected {}", token.describe()), *offset)),
+    }
+}
+
+fn error_at(message: impl Into<String>, offset: usize) -> QueryError {
+    QueryError::invalid_xpath(format!("{} at position {offset}", message.into()))
+}
+
+// ==================== Tokenizer ====================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Slash,
+    DoubleSlash,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Dot,
+    DotDot,
+    At,
+    Comma,
+    DoubleColon,
+    Pipe,
+    Plus,
+    Minus,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Star,
+    Literal(String),
+    Number(f64),
+    Name(String),
+    /// `and`, `or`, `div`, `mod` in operator position.
+    OperatorName(String),
+    /// `*` in operator position.
+    Multiply,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Self::Literal(s) => format!("string '{s}'"),
+            Self::Number(n) => format!("number {n}"),
+            Self::Name(n) | Self::OperatorName(n) => format!("'{n}'"),
+            other => format!("'{}'", other.symbol()),
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Self::Slash => "/",
+            Self::DoubleSlash => "//",
+            Self::LParen => "(",
+            Self::RParen => ")",
+            Self::LBracket => "[",
+            Self::RBracket => "]",
+            Self::Dot => ".",
+            Self::DotDot => "..",
+            Self::At => "@",
+            Self::Comma => ",",
+            Self::DoubleColon => "::",
+            Self::Pipe => "|",
+            Self::Plus => "+",
+            Self::Minus => "-",
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Star | Self::Multiply => "*",
+            Self::Literal(_) | Self::Number(_) | Self::Name(_) | Self::OperatorName(_) => "",
+        }
+    }
+
+    /// Whether a following `*` or name must be read as an operator (XPath 1.0 §3.7).
+    fn precedes_operator(&self) -> bool {
+        !matches!(
+            self,
+            Self::At
+                | Self::DoubleColon
+                | Self::LParen
+                | Self::LBracket
+                | Self::Comma
+                | Self::Slash
+                | Self::DoubleSlash
+                | Self::Pipe
+                | Self::Plus
+                | Self::Minus
+                | Self::Eq
+                | Self::Ne
+                | Self::Lt
+                | Self::Le
+                | Self::Gt
+                | Self::Ge
+                | Self::Multiply
+                | Self::OperatorName(_)
+        )
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+fn tokenize(input: &str) -> QueryResult<Vec<(Token, usize)>> {
+    let bytes = input.as_bytes();
+    let mut tokens: Vec<(Token, usize)> = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        let operator_position = tokens.last().is_some_and(|(t, _)| t.precedes_operator());
+        let next = bytes.get(i + 1).copied();
+
+        let token = match c {
+            b'/' if next == Some(b'/') => {
+                i += 2;
+                Token::DoubleSlash
+            }
+            b'/' => {
+                i += 1;
+                Token::Slash
+            }
+            b'(' => {
+                i += 1;
+                Token::LParen
+            }
+            b')' => {
+                i += 1;
+                Token::RParen
+            }
+            b'[' => {
+                i += 1;
+                Token::LBracket
+            }
+            b']' => {
+                i += 1;
+                Token::RBracket
+            }
+            b'.' if next == Some(b'.') => {
+                i += 2;
+                Token::DotDot
+            }
+            b'.' if next.is_some_and(|n| n.is_ascii_digit()) => {
+                let (number, end) = read_number(input, i);
+                i = end;
+                Token::Number(number)
+            }
+            b'.' => {
+                i += 1;
+                Token::Dot
+            }
+            b'@' => {
+                i += 1;
+                Token::At
+            }
+            b',' => {
+                i += 1;
+                Token::Comma
+            }
+            b':' if next == Some(b':') => {
+                i += 2;
+                Token::DoubleColon
+            }
+            b'|' => {
+                i += 1;
+                Token::Pipe
+            }
+            b'+' => {
+                i += 1;
+                Token::Plus
+            }
+            b'-' => {
+                i += 1;
+                Token::Minus
+            }
+            b'=' => {
+                i += 1;
+                Token::Eq
+            }
+            b'!' if next == Some(b'=') => {
+                i += 2;
+                Token::Ne
+            }
+            b'<' if next == Some(b'=') => {
+                i += 2;
+                Token::Le
+            }
+            b'<' => {
+                i += 1;
+                Token::Lt
+            }
+            b'>' if next == Some(b'=') => {
+                i += 2;
+                Token::Ge
+            }
+            b'>' => {
+                i += 1;
+                Token::Gt
+            }
+            b'*' => {
+                i += 1;
+                if operator_position { Token::Multiply } else { Token::Star }
+            }
+            b'"' | b'\'' => {
+                let Some(len) = input[i + 1..].find(char::from(c)) else {
+                    return Err(error_at("unterminated string literal", start));
+                };
+                let literal = input[i + 1..i + 1 + len].to_string();
+                i += len + 2;
+                Token::Literal(literal)
+            }
+            b'0'..=b'9' => {
+                let (number, end) = read_number(input, i);
+                i = end;
+                Token::Number(number)
+            }
+            b'$' => return Err(error_at("variables are not supported", start)),
+            _ if is_name_start(input[i..].chars().next().unwrap_or_default()) => {
+                let end = read_name(input, i);
+                let name = input[i..end].to_string();
+                i = end;
+                if operator_position && matches!(name.as_str(), "and" | "or" | "div" | "mod") {
+                    Token::OperatorName(name)
+                } else {
+                    Token::Name(name)
+                }
+            }
+            _ => {
+                let ch = input[i..].chars().next().unwrap_or_default();
+                return Err(error_at(format!("unexpected character '{ch}'"), start));
+            }
+        };
+        tokens.push((token, start));
+    }
+
+    Ok(tokens)
+}
+
+fn read_number(input: &str, start: usize) -> (f64, usize) {
+    let bytes = input.as_bytes();
+    let mut end = start;
+    let mut seen_dot = false;
+    while end < bytes.len() && (bytes[end].is_ascii_digit() || (bytes[end] == b'.' && !seen_dot)) {
+        seen_dot |= bytes[end] == b'.';
+        end += 1;
+    }
+    (input[start..end].parse().unwrap_or(f64::NAN), end)
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// Reads an NCName, or a QName with a single `prefix:` part.
+fn read_name(input: &str, start: usize) -> usize {
+    let mut end = start;
+    let mut chars = input[start..].char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        if is_name_char(c) {
+            end = start + offset + c.len_utf8();
+        } else if c == ':'
+            && !input[start..end].contains(':')
+            && chars.peek().is_some_and(|&(_, next)| is_name_start(next))
+        {
+            end = start + offset + 1;
+        } else {
+            break;
+        }
+    }
+    end
+}
+
+// ==================== Parser ====================
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_token(&self) -> Option<&Token> {
+        self.peek().map(|(t, _)| t)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n).map(|(t, _)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.peek().map_or_else(
+            || self.tokens.last().map_or(0, |(t, o)| o + t.symbol().len().max(1)),
+            |(_, o)| *o,
+        )
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek_token() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_operator_name(&mut self, name: &str) -> bool {
+        if matches!(self.peek_token(), Some(Token::OperatorName(n)) if n == name) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token) -> QueryResult<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", token.symbol())))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> QueryError {
+        match self.peek() {
+            Some((token, offset)) => {
+                error_at(format!("expected {expected}, found {}", token.describe()), *offset)
+            }
+            None => {
+                error_at(format!("expected {expected}, found end of expression"), self.offset())
+            }
+        }
+    }
+
+    fn parse_or(&mut self) -> QueryResult<Expr> {
+        let mut left = self.parse_and()?;
+        while self.eat_operator_name("or") {
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> QueryResult<Expr> {
+        let mut left = self.parse_equality()?;
+        while self.eat_operator_name("and") {
+            left = Expr::And(Box::new(left), Box::new(self.parse_equality()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_equality(&mut self) -> QueryResult<Expr> {
+        let mut left = self.parse_relational()?;
+        loop {
+            let op = match self.peek_token() {
+                Some(Token::Eq) => CompareOp::Eq,
+                Some(Token::Ne) => CompareOp::Ne,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::Compare(op, Box::new(left), Box::new(self.parse_relational()?));
+        }
+    }
+
+    fn parse_relational(&mut self) -> QueryResult<Expr> {
+        let mut left = self.parse_additive()?;
+        loop {
+            let op = match self.peek_token() {
+                Some(Token::Lt) => CompareOp::Lt,
+                Some(Token::Le) => CompareOp::Le,
+                Some(Token::Gt) => CompareOp::Gt,
+                Some(Token::Ge) => CompareOp::Ge,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::Compare(op, Box::new(left), Box::new(self.parse_additive()?));
+        }
+    }
+
+    fn parse_additive(&mut self) -> QueryResult<Expr> {
+        let mut left = self.parse_multiplicative()?;
+        loop {
+            let op = match self.peek_token() {
+                Some(Token::Plus) => ArithOp::Add,
+                Some(Token::Minus) => ArithOp::Sub,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::Arith(op, Box::new(left), Box::new(self.parse_multiplicative()?));
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> QueryResult<Expr> {
+        let mut left = self.parse_unary()?;
+        loop {
+            let op = match self.peek_token() {
+                Some(Token::Multiply) => ArithOp::Mul,
+                Some(Token::OperatorName(n)) if n == "div" => ArithOp::Div,
+                Some(Token::OperatorName(n)) if n == "mod" => ArithOp::Mod,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::Arith(op, Box::new(left), Box::new(self.parse_unary()?));
+        }
+    }
+
+    fn parse_unary(&mut self) -> QueryResult<Expr> {
+        if self.eat(&Token::Minus) {
+            return Ok(Expr::Negate(Box::new(self.parse_unary()?)));
+        }
+        self.parse_union()
+    }
+
+    fn parse_union(&mut self) -> QueryResult<Expr> {
+        let mut left = self.parse_path()?;
+        while self.eat(&Token::Pipe) {
+            left = Expr::Union(Box::new(left), Box::new(self.parse_path()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_path(&mut self) -> QueryResult<Expr> {
+        match self.peek_token() {
+            Some(Token::Slash) => {
+                self.pos += 1;
+                let steps =
+                    if self.starts_step() { self.parse_relative_steps()? } else { Vec::new() };
+                Ok(Expr::Path(Path { start: PathStart::Root, steps }))
+            }
+            Some(Token::DoubleSlash) => {
+                self.pos += 1;
+                let mut steps = vec![descendant_or_self_step()];
+                steps.extend(self.parse_relative_steps()?);
+                Ok(Expr::Path(Path { start: PathStart::Root, steps }))
+            }
+            _ if self.starts_filter() => {
+                let primary = self.parse_primary()?;
+                let predicates = self.parse_predicates()?;
+                let mut steps = Vec::new();
+                match self.peek_token() {
+                    Some(Token::Slash) => {
+                        self.pos += 1;
+                        steps = self.parse_relative_steps()?;
+                    }
+                    Some(Token::DoubleSlash) => {
+                        self.pos += 1;
+                        steps.push(descendant_or_self_step());
+                        steps.extend(self.parse_relative_steps()?);
+                    }
+                    _ if predicates.is_empty() => return Ok(primary),
+                    _ => {}
+                }
+                Ok(Expr::Path(Path {
+                    start: PathStart::Filter(Box::new(primary), predicates),
+                    steps,
+                }))
+            }
+            _ => Ok(Expr::Path(Path {
+                start: PathStart::Context,
+                steps: self.parse_relative_steps()?,
+            })),
+        }
+    }
+
+    /// Whether the next tokens begin a primary expression rather than a location step.
+    fn starts_filter(&self) -> bool {
+        match self.peek_token() {
+            Some(Token::LParen | Token::Literal(_) | Token::Number(_)) => true,
+            Some(Token::Name(name)) => {
+                self.peek_nth(1) == Some(&Token::LParen)
+                    && !matches!(
+                        name.as_str(),
+                        "node" | "text" | "comment" | "processing-instruction"
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    fn starts_step(&self) -> bool {
+        matches!(
+            self.peek_token(),
+            Some(Token::Name(_) | Token::Star | Token::At | Token::Dot | Token::DotDot)
+        )
+    }
+
+    fn parse_relative_steps(&mut self) -> QueryResult<Vec<Step>> {
+        let mut steps = vec![self.parse_step()?];
+        loop {
+            match self.peek_token() {
+                Some(Token::Slash) => {
+                    self.pos += 1;
+                    steps.push(self.parse_step()?);
+                }
+                Some(Token::DoubleSlash) => {
+                    self.pos += 1;
+                    steps.push(descendant_or_self_step());
+                    steps.push(self.parse_step()?);
+                }
+                _ => return Ok(steps),
+            }
+        }
+    }
+
+    fn parse_step(&mut self) -> QueryResult<Step> {
+        if self.eat(&Token::Dot) {
+            return Ok(Step { axis: Axis::SelfAxis, test: NodeTest::Node, predicates: Vec::new() });
+        }
+        if self.eat(&Token::DotDot) {
+            return Ok(Step { axis: Axis::Parent, test: NodeTest::Node, predicates: Vec::new() });
+        }
+
+        let axis = if self.eat(&Token::At) {
+            Axis::Attribute
+        } else if let (Some(Token::Name(name)), Some(Token::DoubleColon)) =
+            (self.peek_token(), self.peek_nth(1))
+        {
+            let offset = self.offset();
+            let axis = Axis::from_name(name)
+                .ok_or_else(|| error_at(format!("unknown axis '{name}'"), offset))?;
+            self.pos += 2;
+            axis
+        } else {
+            Axis::Child
+        };
+
+        let test = self.parse_node_test()?;
+        let predicates = self.parse_predicates()?;
+        Ok(Step { axis, test, predicates })
+    }
+
+    fn parse_node_test(&mut self) -> QueryResult<NodeTest> {
+        let offset = self.offset();
+        match self.peek_token().cloned() {
+            Some(Token::Star) => {
+                self.pos += 1;
+                Ok(NodeTest::Wildcard)
+            }
+            Some(Token::Name(name)) if self.peek_nth(1) == Some(&Token::LParen) => {
+                let test = match name.as_str() {
+                    "node" => NodeTest::Node,
+                    "text" => NodeTest::Text,
+                    "comment" => NodeTest::Comment,
+                    _ => return Err(error_at(format!("'{name}()' is not a node test"), offset)),
+                };
+                self.pos += 2;
+                self.expect(&Token::RParen)?;
+                Ok(test)
+            }
+            Some(Token::Name(name)) => {
+                self.pos += 1;
+                Ok(NodeTest::Name(name))
+            }
+            _ => Err(self.unexpected("a node test")),
+        }
+    }
+
+    fn parse_predicates(&mut self) -> QueryResult<Vec<Expr>> {
+        let mut predicates = Vec::new();
+        while self.eat(&Token::LBracket) {
+            predicates.push(self.parse_or()?);
+            self.expect(&Token::RBracket)?;
+        }
+        Ok(predicates)
+    }
+
+    fn parse_primary(&mut self) -> QueryResult<Expr> {
+        let offset = self.offset();
+        match self.peek_token().cloned() {
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                self.expect(&Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Literal(s)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(s))
+            }
+            Some(Token::Number(n)) => {
+                self.pos += 1;
+                Ok(Expr::Number(n))
+            }
+            Some(Token::Name(name)) => {
+                let function = Function::from_name(&name)
+                    .ok_or_else(|| error_at(format!("unknown function '{name}()'"), offset))?;
+                self.pos += 2;
+                let mut args = Vec::new();
+                if !self.eat(&Token::RParen) {
+                    loop {
+                        args.push(self.parse_or()?);
+                        if self.eat(&Token::RParen) {
+                            break;
+                        }
+                        self.expect(&Token::Comma)?;
+                    }
+                }
+                let (min, max) = function.arity();
+                if args.len() < min || args.len() > max {
+                    return Err(error_at(
+                        format!("wrong number of arguments to '{name}()': {}", args.len()),
+                        offset,
+                    ));
+                }
+                Ok(Expr::Function(function, args))
+            }
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+}
+
+fn descendant_or_self_step() -> Step {
+    Step { axis: Axis::DescendantOrSelf, test: NodeTest::Node, predicates: Vec::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn child(name: &str) -> Step {
+        Step { axis: Axis::Child, test: NodeTest::Name(name.into()), predicates: Vec::new() }
+    }
+
+    #[test]
+    fn test_parse_abbreviated_path() {
+        let expr = parse("//div/span").unwrap();
+        assert_eq!(
+            expr,
+            Expr::Path(Path {
+                start: PathStart::Root,
+                steps: vec![descendant_or_self_step(), child("div"), child("span")],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_operator_disambiguation() {
+        // `div` is an element name here, then the `div` operator, then a name again.
+        let expr = parse("div div div").unwrap();
+        let Expr::Arith(ArithOp::Div, left, right) = expr else { panic!("expected div") };
+        assert!(matches!(*left, Expr::Path(_)));
+        assert!(matches!(*right, Expr::Path(_)));
+
+        // `*` is a wildcard after `/` and multiplication after a number.
+        assert!(matches!(parse("2 * 3").unwrap(), Expr::Arith(ArithOp::Mul, _, _)));
+        assert!(matches!(parse("/*").unwrap(), Expr::Path(_)));
+    }
+
+    #[test]
+    fn test_parse_axes_and_predicates() {
+        let Expr::Path(path) = parse("ancestor::ul[@class='menu'][1]").unwrap() else {
+            panic!("expected path");
+        };
+        assert_eq!(path.steps[0].axis, Axis::Ancestor);
+        assert_eq!(path.steps[0].predicates.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_errors_report_position() {
+        let err = parse("//div[").unwrap_err().to_string();
+        assert!(err.contains("position 6"), "{err}");
+        assert!(parse("//div[@id='x'").is_err());
+        assert!(parse("foo::bar").unwrap_err().to_string().contains("unknown axis"));
+        assert!(parse("frobnicate()").unwrap_err().to_string().contains("unknown function"));
+        assert!(parse("contains('a')").unwrap_err().to_string().contains("wrong number"));
+        assert!(parse("$var").is_err());
+        assert!(parse("'open").is_err());
+    }
+}
@@ -0,0 +1,712 @@
+//! Robots directives from `<meta name="robots">` tags and `X-Robots-Tag` headers.
+//!
+//! [`robots_directives`] (also available as [`Soup::robots_directives`]) reads the
+//! `robots` meta tags of a page, and [`RobotsDirectives::from_header`] parses an
+//! `X-Robots-Tag` response header. Directives from several sources combine with
+//! [`RobotsDirectives::merge`], where the most restrictive value wins, so a fetcher
+//! can honor both:
+//!
+//! ```rust
+//! use scrape_core::{Soup, robots::RobotsDirectives};
+//!
+//! let soup = Soup::parse(r#"<meta name="robots" content="noindex, max-snippet:50">"#);
+//! let header = RobotsDirectives::from_header("nofollow, max-snippet:20", None);
+//!
+//! let directives = soup.robots_directives().merge(header);
+//! assert!(directives.noindex);
+//! assert!(directives.nofollow);
+//! assert_eq!(directives.max_snippet, Some(20));
+//! ```
+//!
+//! Directive names are case-insensitive; `none` means `noindex, nofollow`, and `all`
+//! and unknown directives are ignored.
+//!
+//! Site-wide crawl rules come from `robots.txt`, parsed by [`RobotsTxt`]:
+//!
+//! ```rust
+//! use scrape_core::robots::RobotsTxt;
+//!
+//! let robots = RobotsTxt::parse("User-agent: *\nDisallow: /private/\nAllow: /private/faq");
+//! assert!(robots.is_allowed("scrapebot", "/private/faq.html"));
+//! assert!(!robots.is_allowed("scrapebot", "/private/keys"));
+//! ```
+//!
+//! [`RateLimiter`] spaces out requests to each host with a token bucket, for example
+//! one request per `Crawl-delay`:
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use scrape_core::robots::{RateLimiter, RobotsTxt};
+//!
+//! let robots = RobotsTxt::parse("User-agent: *\nCrawl-delay: 2");
+//! let delay = robots.crawl_delay("scrapebot").unwrap_or(Duration::from_secs(1));
+//! let limiter = RateLimiter::new(delay, 1);
+//! assert_eq!(limiter.reserve("example.com"), Duration::ZERO);
+//! assert!(limiter.reserve("example.com") > Duration::from_secs(1));
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::Write,
+    sync::{Mutex, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Soup, Tag, query::find_all};
+
+/// Indexing and serving directives for a page.
+///
+/// The default value allows everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct RobotsDirectives {
+    /// `noindex`: the page must not be indexed.
+    pub noindex: bool,
+    /// `nofollow`: links on the page must not be followed.
+    pub nofollow: bool,
+    /// `noarchive`: no cached copy may be shown.
+    pub noarchive: bool,
+    /// `nosnippet`: no text snippet or video preview may be shown.
+    pub nosnippet: bool,
+    /// `noimageindex`: images on the page must not be indexed.
+    pub noimageindex: bool,
+    /// `notranslate`: no translation may be offered.
+    pub notranslate: bool,
+    /// `max-snippet:N`: the longest text snippet in characters; `-1` means no limit.
+    pub max_snippet: Option<i64>,
+    /// `max-image-preview:SIZE`: `none`, `standard`, or `large`.
+    pub max_image_preview: Option<String>,
+    /// `max-video-preview:N`: the longest video preview in seconds; `-1` means no limit.
+    pub max_video_preview: Option<i64>,
+    /// `unavailable_after:DATE`: the date after which the page must not be shown, as
+    /// written.
+    pub unavailable_after: Option<String>,
+}
+
+impl RobotsDirectives {
+    /// Parses a comma-separated directive list, such as the `content` of a robots meta
+    /// tag.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::robots::RobotsDirectives;
+    ///
+    /// let directives = RobotsDirectives::parse("NONE, max-image-preview:Large");
+    /// assert!(directives.noindex && directives.nofollow);
+    /// assert_eq!(directives.max_image_preview.as_deref(), Some("large"));
+    /// ```
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let mut directives = Self::default();
+        for directive in content.split(',') {
+            directives.apply(directive.trim());
+        }
+        directives
+    }
+
+    /// Parses an `X-Robots-Tag` header value.
+    ///
+    /// A value may start with a user agent, as in `googlebot: noindex`. Such values
+    /// apply only when `user_agent` matches the name, ignoring case; otherwise they
+    /// yield the default directives. Values without a user agent always apply.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::robots::RobotsDirectives;
+    ///
+    /// assert!(RobotsDirectives::from_header("noindex", None).noindex);
+    /// assert!(RobotsDirectives::from_header("scrapebot: noindex", Some("ScrapeBot")).noindex);
+    /// assert!(!RobotsDirectives::from_header("googlebot: noindex", Some("scrapebot")).noindex);
+    /// ```
+    #[must_use]
+    pub fn from_header(value: &str, user_agent: Option<&str>) -> Self {
+        if let Some((agent, rest)) = value.split_once(':') {
+            let agent = agent.trim();
+            if is_user_agent(agent) {
+                return if user_agent.is_some_and(|ua| ua.eq_ignore_ascii_case(agent)) {
+                    Self::parse(rest)
+                } else {
+                    Self::default()
+                };
+            }
+        }
+        Self::parse(value)
+    }
+
+    /// Combines two sets of directives, keeping the most restrictive value of each.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            noindex: self.noindex || other.noindex,
+            nofollow: self.nofollow || other.nofollow,
+            noarchive: self.noarchive || other.noarchive,
+            nosnippet: self.nosnippet || other.nosnippet,
+            noimageindex: self.noimageindex || other.noimageindex,
+            notranslate: self.notranslate || other.notranslate,
+            max_snippet: min_limit(self.max_snippet, other.max_snippet),
+            max_image_preview: [self.max_image_preview, other.max_image_preview]
+                .into_iter()
+                .flatten()
+                .min_by_key(|size| image_preview_rank(size)),
+            max_video_preview: min_limit(self.max_video_preview, other.max_video_preview),
+            unavailable_after: self.unavailable_after.or(other.unavailable_after),
+        }
+    }
+
+    /// Returns `true` if the page may be indexed.
+    #[must_use]
+    pub fn can_index(&self) -> bool {
+        !self.noindex
+    }
+
+    /// Returns `true` if the page's links may be followed.
+    #[must_use]
+    pub fn can_follow(&self) -> bool {
+        !self.nofollow
+    }
+
+    fn apply(&mut self, directive: &str) {
+        let (name, value) = match directive.split_once(':') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (directive, None),
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "noindex" => self.noindex = true,
+            "nofollow" => self.nofollow = true,
+            "none" => {
+                self.noindex = true;
+                self.nofollow = true;
+            }
+            "noarchive" | "nocache" => self.noarchive = true,
+            "nosnippet" => self.nosnippet = true,
+            "noimageindex" => self.noimageindex = true,
+            "notranslate" => self.notranslate = true,
+            "max-snippet" => {
+                self.max_snippet = min_limit(self.max_snippet, value.and_then(parse_limit));
+            }
+            "max-video-preview" => {
+                self.max_video_preview =
+                    min_limit(self.max_video_preview, value.and_then(parse_limit));
+            }
+            "max-image-preview" => {
+                if let Some(size) = value.map(str::to_ascii_lowercase)
+                    && image_preview_rank(&size) < 3
+                    && self.max_image_preview.as_deref().is_none_or(|current| {
+                        image_preview_rank(&size) < image_preview_rank(current)
+                    })
+                {
+                    self.max_image_preview = Some(size);
+                }
+            }
+            "unavailable_after" => {
+                if let Some(date) = value.filter(|date| !date.is_empty()) {
+                    self.unavailable_after.get_or_insert_with(|| date.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collects the directives of every `<meta name="robots">` tag, merged.
+///
+/// With a `user_agent`, tags named after it (such as `<meta name="googlebot">`) are
+/// merged in too, ignoring case.
+#[must_use]
+pub fn robots_directives(soup: &Soup, user_agent: Option<&str>) -> RobotsDirectives {
+    let doc = soup.document();
+    find_all(doc, "meta[name][content]")
+        .unwrap_or_default()
+        .into_iter()
+        .map(|id| Tag::new(doc, id))
+        .filter(|tag| {
+            tag.get("name").is_some_and(|name| {
+                let name = name.trim();
+                name.eq_ignore_ascii_case("robots")
+                    || user_agent.is_some_and(|ua| name.eq_ignore_ascii_case(ua))
+            })
+        })
+        .filter_map(|tag| tag.get("content").map(RobotsDirectives::parse))
+        .fold(RobotsDirectives::default(), RobotsDirectives::merge)
+}
+
+/// Crawl rules from a site's `robots.txt`, following RFC 9309.
+///
+/// Rules are grouped under `User-agent` lines. A crawler obeys the groups naming its
+/// product token, ignoring case, or the `*` groups if none do. Among the `Allow` and
+/// `Disallow` rules of those groups, the longest path matching a URL decides, and `Allow`
+/// wins ties; paths may use `*` wildcards and a `$` end anchor. Paths are compared with
+/// percent-encoding normalized, so `/caf%C3%A9` and `/café` are the same path.
+/// `Crawl-delay` and `Sitemap` lines are read as well, and everything else is ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsTxt {
+    groups: Vec<RobotsGroup>,
+    sitemaps: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct RobotsGroup {
+    /// Lowercased product tokens.
+    agents: Vec<String>,
+    /// `(allow, path pattern)` pairs.
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<Duration>,
+}
+
+impl RobotsTxt {
+    /// Number of bytes read from a `robots.txt` file; the rest is ignored, as RFC 9309
+    /// allows.
+    pub const MAX_SIZE: usize = 500 * 1024;
+
+    /// Parses the contents of a `robots.txt` file.
+    ///
+    /// An empty file, like a missing one, allows everything. Only the first
+    /// [`MAX_SIZE`](Self::MAX_SIZE) bytes are read.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let mut end = text.len().min(Self::MAX_SIZE);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let mut robots = Self::default();
+        // Consecutive `User-agent` lines share one group.
+        let mut in_agents = false;
+        for line in text[..end].split(['\n', '\r']) {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let value = value.trim();
+            let key = key.trim().to_ascii_lowercase();
+            if key == "sitemap" {
+                if !value.is_empty() {
+                    robots.sitemaps.push(value.to_string());
+                }
+                continue;
+            }
+            if key == "user-agent" {
+                if !in_agents {
+                    robots.groups.push(RobotsGroup::default());
+                }
+                in_agents = true;
+                if let Some(group) = robots.groups.last_mut() {
+                    group.agents.push(product_token(value));
+                }
+                continue;
+            }
+            in_agents = false;
+            let Some(group) = robots.groups.last_mut() else { continue };
+            match key.as_str() {
+                // An empty `Disallow` allows everything, like no rule at all.
+                "allow" | "disallow" if !value.is_empty() => {
+                    group.rules.push((key == "allow", normalize_path(value).into_owned()));
+                }
+                "crawl-delay" => {
+                    group.crawl_delay =
+                        value.parse().ok().and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+                }
+                _ => {}
+            }
+        }
+        robots
+    }
+
+    /// Returns `true` if `user_agent` may fetch `path`, the path and query of a URL.
+    ///
+    /// `user_agent` is a product token such as `scrapebot`; a full `User-Agent` header
+    /// value like `ScrapeBot/1.2 (+https://example.com/bot)` works too. `/robots.txt`
+    /// itself is always allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::robots::RobotsTxt;
+    ///
+    /// let robots =
+    ///     RobotsTxt::parse("User-agent: scrapebot\nDisallow: /*.pdf$\n\nUser-agent: *\nDisallow: /");
+    /// assert!(robots.is_allowed("ScrapeBot", "/docs/guide.html"));
+    /// assert!(!robots.is_allowed("ScrapeBot", "/docs/guide.pdf"));
+    /// assert!(!robots.is_allowed("otherbot", "/docs/guide.html"));
+    /// ```
+    #[must_use]
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        let path = normalize_path(path);
+        if path == "/robots.txt" {
+            return true;
+        }
+        let mut best: Option<(usize, bool)> = None;
+        for group in self.groups_for(user_agent) {
+            for (allow, pattern) in &group.rules {
+                if !matches_path(pattern, &path) {
+                    continue;
+                }
+                let better = best.is_none_or(|(len, best_allow)| {
+                    pattern.len() > len || (pattern.len() == len && *allow && !best_allow)
+                });
+                if better {
+                    best = Some((pattern.len(), *allow));
+                }
+            }
+        }
+        best.is_none_or(|(_, allow)| allow)
+    }
+
+    /// Returns `true` if `user_agent` may fetch `url`, an absolute URL or a path.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::robots::RobotsTxt;
+    ///
+    /// let robots = RobotsTxt::parse("User-agent: *\nDisallow: /search?");
+    /// assert!(robots.is_url_allowed("scrapebot", "https://example.com/search#top"));
+    /// assert!(!robots.is_url_allowed("scrapebot", "https://example.com/search?q=tea"));
+    /// ```
+    #[must_use]
+    pub fn is_url_allowed(&self, user_agent: &str, url: &str) -> bool {
+        self.is_allowed(user_agent, &path_and_query(url))
+    }
+
+    /// Returns the `Crawl-delay` that applies to `user_agent`, if any.
+    #[must_use]
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<Duration> {
+        self.groups_for(user_agent).find_map(|group| group.crawl_delay)
+    }
+
+    /// Returns the URLs of the `Sitemap` lines, in file order.
+    #[must_use]
+    pub fn sitemaps(&self) -> &[String] {
+        &self.sitemaps
+    }
+
+    fn groups_for(&self, user_agent: &str) -> impl Iterator<Item = &RobotsGroup> {
+        let agent = product_token(user_agent);
+        let named =
+            !agent.is_empty() && self.groups.iter().any(|group| group.agents.contains(&agent));
+        let agent = if named { agent } else { "*".to_string() };
+        self.groups.iter().filter(move |group| group.agents.contains(&agent))
+    }
+}
+
+/// A token-bucket rate limiter with a bucket per key, such as a host name.
+///
+/// Each bucket holds up to `burst` tokens and gains one every `interval`; a request
+/// takes a token, waiting for one if the bucket is empty. Waiting requests queue up in
+/// order, so `n` requests made at once to an empty bucket finish `interval` apart. The
+/// limiter can be shared between threads.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    burst: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Tokens left, negative while requests are waiting.
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing one request per `interval` for each key, after a
+    /// burst of up to `burst` requests.
+    ///
+    /// A zero `interval` allows everything, and a zero `burst` counts as one.
+    #[must_use]
+    pub fn new(interval: Duration, burst: u32) -> Self {
+        Self { interval, burst: f64::from(burst.max(1)), buckets: Mutex::default() }
+    }
+
+    /// Creates a limiter allowing `rate` requests per second for each key, one at a
+    /// time.
+    ///
+    /// A rate that is not positive and finite allows everything.
+    #[must_use]
+    pub fn per_second(rate: f64) -> Self {
+        Self::new(Duration::try_from_secs_f64(rate.recip()).unwrap_or_default(), 1)
+    }
+
+    /// Returns the time between requests for one key.
+    #[must_use]
+    pub const fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Takes a token for `key` and returns how long to wait before making the request.
+    ///
+    /// The token is taken even if the caller then waits, so calls made while others
+    /// are waiting are scheduled after them.
+    pub fn reserve(&self, key: &str) -> Duration {
+        if self.interval.is_zero() {
+            return Duration::ZERO;
+        }
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        let bucket =
+            buckets.entry(key.to_string()).or_insert(Bucket { tokens: self.burst, updated: now });
+        let elapsed = now.saturating_duration_since(bucket.updated);
+        let refill = elapsed.as_secs_f64() / self.interval.as_secs_f64();
+        let tokens = (bucket.tokens + refill).min(self.burst) - 1.0;
+        *bucket = Bucket { tokens, updated: now };
+        drop(buckets);
+        if tokens >= 0.0 { Duration::ZERO } else { self.interval.mul_f64(-tokens) }
+    }
+
+    /// Takes a token for `key`, sleeping until the request may be made.
+    pub fn acquire(&self, key: &str) {
+        thread::sleep(self.reserve(key));
+    }
+}
+
+// ==================== Helpers ====================
+
+/// Returns the lowercased product token at the start of a user agent, or `*`.
+fn product_token(user_agent: &str) -> String {
+    let user_agent = user_agent.trim();
+    if user_agent.starts_with('*') {
+        return "*".to_string();
+    }
+    user_agent
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic() || matches!(c, '-' | '_'))
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Returns the path and query of a URL, which is `/` for a bare origin.
+fn path_and_query(url: &str) -> String {
+    let url = url.split_once('#').map_or(url, |(url, _)| url);
+    let rest = url.find("://").map_or(url, |i| {
+        let rest = &url[i + 3..];
+        rest.find(['/', '?']).map_or("", |end| &rest[end..])
+    });
+    if rest.starts_with('/') { rest.to_string() } else { format!("/{rest}") }
+}
+
+/// Brings a path to the form RFC 9309 compares: non-ASCII bytes are percent-encoded,
+/// and escaped unreserved characters are decoded.
+fn normalize_path(path: &str) -> Cow<'_, str> {
+    if path.is_ascii() && !path.contains('%') {
+        return Cow::Borrowed(path);
+    }
+    let bytes = path.as_bytes();
+    let mut normalized = String::with_capacity(path.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| byte == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(decoded) if decoded.is_ascii_alphanumeric() || b"-._~".contains(&decoded) => {
+                normalized.push(char::from(decoded));
+                i += 3;
+            }
+            Some(decoded) => {
+                let _ = write!(normalized, "%{decoded:02X}");
+                i += 3;
+            }
+            None if byte.is_ascii() => {
+                normalized.push(char::from(byte));
+                i += 1;
+            }
+            None => {
+                let _ = write!(normalized, "%{byte:02X}");
+                i += 1;
+            }
+        }
+    }
+    Cow::Owned(normalized)
+}
+
+/// Returns `true` if a `robots.txt` path pattern matches the start of `path`.
+fn matches_path(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) =
+        pattern.strip_suffix('$').map_or((pattern, false), |pattern| (pattern, true));
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<_> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return !anchored || rest.is_empty();
+    };
+    for part in middle {
+        let Some(i) = rest.find(part) else { return false };
+        rest = &rest[i + part.len()..];
+    }
+    if anchored { rest.ends_with(last) } else { rest.contains(last) }
+}
+
+/// Returns `true` if the text before a `:` names a user agent rather than a directive.
+fn is_user_agent(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains([',', ' '])
+        && !matches!(
+            name.to_ascii_lowercase().as_str(),
+            "max-snippet" | "max-image-preview" | "max-video-preview" | "unavailable_after"
+        )
+}
+
+fn parse_limit(value: &str) -> Option<i64> {
+    value.parse().ok().filter(|&n| n >= -1)
+}
+
+/// Returns the smaller limit, where `-1` means no limit.
+fn min_limit(a: Option<i64>, b: Option<i64>) -> Option<i64> {
+    match (a, b) {
+        (Some(-1), other) | (other, Some(-1)) => other.or(Some(-1)),
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn image_preview_rank(size: &str) -> u8 {
+    match size {
+        "none" => 0,
+        "standard" => 1,
+        "large" => 2,
+        _ => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let directives = RobotsDirectives::parse(
+            " NoIndex ,noarchive, max-snippet : 30, max-video-preview:-1, unavailable_after: \
+             2025-12-31, max-image-preview:huge, bogus",
+        );
+        assert!(directives.noindex);
+        assert!(!directives.nofollow);
+        assert!(directives.noarchive);
+        assert_eq!(directives.max_snippet, Some(30));
+        assert_eq!(directives.max_video_preview, Some(-1));
+        assert_eq!(directives.max_image_preview, None);
+        assert_eq!(directives.unavailable_after.as_deref(), Some("2025-12-31"));
+        assert_eq!(RobotsDirectives::parse("all, index, follow"), RobotsDirectives::default());
+    }
+
+    #[test]
+    fn test_from_header() {
+        let directives = RobotsDirectives::from_header("unavailable_after: 25 Jun 2030", None);
+        assert_eq!(directives.unavailable_after.as_deref(), Some("25 Jun 2030"));
+        assert_eq!(RobotsDirectives::from_header("max-snippet:5", None).max_snippet, Some(5));
+        assert!(RobotsDirectives::from_header("otherbot: none", Some("bot")).can_index());
+        assert!(!RobotsDirectives::from_header("Bot: nofollow", Some("bot")).can_follow());
+    }
+
+    #[test]
+    fn test_merge() {
+        let merged = RobotsDirectives::parse("max-snippet:-1, max-image-preview:large")
+            .merge(RobotsDirectives::parse("max-snippet:10, max-image-preview:standard"));
+        assert_eq!(merged.max_snippet, Some(10));
+        assert_eq!(merged.max_image_preview.as_deref(), Some("standard"));
+        assert_eq!(min_limit(Some(-1), None), Some(-1));
+        assert_eq!(min_limit(Some(-1), Some(-1)), Some(-1));
+    }
+
+    #[test]
+    fn test_robots_txt_groups() {
+        let robots = RobotsTxt::parse(
+            "# comment\nUser-agent: ScrapeBot\nUser-agent: other\nDisallow: /tmp/ # scratch\n\
+             Crawl-delay: 1.5\n\nSitemap: https://example.com/sitemap.xml\n\
+             User-agent: *\nDisallow: /\nAllow: /public\nDisallow:\n",
+        );
+        assert!(robots.is_allowed("scrapebot", "/"));
+        assert!(!robots.is_allowed("OTHER", "/tmp/a"));
+        assert!(robots.is_allowed("crawler", "/public/a?x=1"));
+        assert!(!robots.is_allowed("crawler", "/private"));
+        assert!(robots.is_allowed("crawler", "/robots.txt"));
+        assert_eq!(robots.crawl_delay("scrapebot"), Some(Duration::from_millis(1500)));
+        assert_eq!(robots.crawl_delay("crawler"), None);
+        assert_eq!(robots.sitemaps(), ["https://example.com/sitemap.xml"]);
+        assert!(RobotsTxt::parse("").is_allowed("crawler", "/anything"));
+    }
+
+    #[test]
+    fn test_robots_txt_matching() {
+        assert!(matches_path("/a", "/abc"));
+        assert!(!matches_path("/b", "/abc"));
+        assert!(matches_path("/*/edit", "/posts/1/edit/history"));
+        assert!(matches_path("/*.php$", "/index.php"));
+        assert!(!matches_path("/*.php$", "/index.php?x"));
+        assert!(matches_path("/x$", "/x"));
+        assert!(!matches_path("/x$", "/xy"));
+
+        // The longest match wins, and allow wins ties.
+        let robots =
+            RobotsTxt::parse("User-agent: *\nAllow: /p\nDisallow: /p/\nDisallow: /q\nAllow: /q");
+        assert!(!robots.is_allowed("bot", "/p/1"));
+        assert!(robots.is_allowed("bot", "/p"));
+        assert!(robots.is_allowed("bot", "/q"));
+    }
+
+    #[test]
+    fn test_robots_txt_normalization() {
+        let robots = RobotsTxt::parse(
+            "\u{feff}User-agent: ScrapeBot/2.1\rDisallow: /caf%c3%a9\rDisallow: \
+             /%7Ejo\rUser-agent: *\nDisallow: /a%2Fb",
+        );
+        assert!(!robots.is_allowed("scrapebot", "/café/menu"));
+        assert!(!robots.is_allowed("ScrapeBot/2.1 (+https://example.com/bot)", "/~jo"));
+        assert!(robots.is_allowed("scrapebot", "/a%2Fb"));
+        assert!(!robots.is_allowed("otherbot", "/a%2fb"));
+        assert!(robots.is_allowed("otherbot", "/a/b"));
+        assert!(!robots.is_allowed("", "/a%2Fb"));
+        assert_eq!(normalize_path("/%41%2f%zz"), "/A%2F%zz");
+
+        let mut text = "User-agent: *\n".to_string();
+        text.push_str(&"#".repeat(RobotsTxt::MAX_SIZE));
+        text.push_str("\nDisallow: /");
+        assert!(RobotsTxt::parse(&text).is_allowed("bot", "/x"));
+    }
+
+    #[test]
+    fn test_robots_txt_urls() {
+        let robots = RobotsTxt::parse("User-agent: *\nDisallow: /$\nDisallow: /a/b?");
+        assert!(!robots.is_url_allowed("bot", "https://example.com"));
+        assert!(robots.is_url_allowed("bot", "https://example.com?q=1"));
+        assert!(robots.is_url_allowed("bot", "https://example.com:8080/a/b#c?d"));
+        assert!(!robots.is_url_allowed("bot", "/a/b?c"));
+        assert_eq!(path_and_query("https://example.com?q=1#x"), "/?q=1");
+        assert_eq!(path_and_query("https://example.com/a/b?q=1"), "/a/b?q=1");
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(Duration::from_secs(10), 2);
+        assert_eq!(limiter.reserve("a"), Duration::ZERO);
+        assert_eq!(limiter.reserve("a"), Duration::ZERO);
+        let wait = limiter.reserve("a");
+        assert!(wait > Duration::from_secs(9) && wait <= Duration::from_secs(10), "{wait:?}");
+        assert!(limiter.reserve("a") > Duration::from_secs(19));
+        assert_eq!(limiter.reserve("b"), Duration::ZERO);
+
+        assert_eq!(RateLimiter::per_second(4.0).interval(), Duration::from_millis(250));
+        assert_eq!(RateLimiter::per_second(0.0).interval(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_meta_tags() {
+        let soup = Soup::parse(
+            "<meta name=\"ROBOTS\" content=\"nofollow\"><meta name=\"robots\" \
+             content=\"noarchive\"><meta name=\"scrapebot\" content=\"noindex\"><meta \
+             name=\"description\" content=\"noindex\">",
+        );
+        let directives = robots_directives(&soup, None);
+        assert!(directives.nofollow && directives.noarchive);
+        assert!(directives.can_index());
+        assert!(!robots_directives(&soup, Some("ScrapeBot")).can_index());
+    }
+}
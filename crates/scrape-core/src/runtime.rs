@@ -0,0 +1,180 @@
+//! Thread pool shared by parallel parsing, querying, and batch processing.
+//!
+//! Parallel work runs on Rayon's global pool until [`configure`] installs a dedicated pool
+//! with a chosen thread count and stack size. Every parallel entry point in this crate, the
+//! CLI (`--parallel N`), and the Python and Node.js bindings run their work through
+//! [`install`], so one call controls them all. Calling [`configure`] again replaces the
+//! pool; work already running finishes on the previous one.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::{parallel::parse_batch, runtime};
+//!
+//! runtime::configure(Some(2), None).unwrap();
+//! assert_eq!(runtime::current_num_threads(), 2);
+//!
+//! let soups = parse_batch(&["<p>A</p>", "<p>B</p>"]);
+//! assert_eq!(soups.len(), 2);
+//!
+//! runtime::reset();
+//! ```
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+/// Error returned when a thread pool cannot be configured.
+#[derive(Debug, thiserror::Error)]
+pub enum RuntimeError {
+    /// The requested thread count was zero.
+    #[error("thread count must be at least 1")]
+    ZeroThreads,
+    /// The requested stack size was zero.
+    #[error("stack size must be greater than zero")]
+    ZeroStackSize,
+    /// Rayon failed to spawn the pool's threads.
+    #[error("failed to build thread pool: {0}")]
+    Build(#[from] ThreadPoolBuildError),
+}
+
+#[derive(Default)]
+struct Runtime {
+    pool: Option<Arc<ThreadPool>>,
+    stack_size: Option<usize>,
+}
+
+static RUNTIME: RwLock<Runtime> = RwLock::new(Runtime { pool: None, stack_size: None });
+
+/// Installs a shared pool with `threads` threads of `stack_size` bytes each.
+///
+/// `None` keeps Rayon's default for that setting (one thread per CPU, or
+/// `RAYON_NUM_THREADS`; the platform's default stack size). Passing `None` for both
+/// is the same as [`reset`].
+///
+/// # Errors
+///
+/// Returns [`RuntimeError`] if `threads` or `stack_size` is zero, or if the threads
+/// cannot be spawned. The previous pool stays in place on error.
+pub fn configure(threads: Option<usize>, stack_size: Option<usize>) -> Result<(), RuntimeError> {
+    if threads.is_none() && stack_size.is_none() {
+        reset();
+        return Ok(());
+    }
+    let pool = build_pool(threads, stack_size)?;
+    *write() = Runtime { pool: Some(Arc::new(pool)), stack_size };
+    Ok(())
+}
+
+/// Drops the configured pool; parallel work runs on Rayon's global pool again.
+pub fn reset() {
+    *write() = Runtime::default();
+}
+
+/// Runs `op` on the configured pool, or on Rayon's global pool if none is configured.
+///
+/// Rayon parallel iterators used inside `op` are scheduled on the same pool. When called
+/// from a Rayon worker thread, `op` stays on the current pool, so nested parallel work and
+/// pools from [`with_threads`] are not moved.
+pub fn install<OP, R>(op: OP) -> R
+where
+    OP: FnOnce() -> R + Send,
+    R: Send,
+{
+    let pool = read().pool.clone();
+    match pool {
+        Some(pool) if rayon::current_thread_index().is_none() => pool.install(op),
+        _ => op(),
+    }
+}
+
+/// Runs `op` on a temporary pool of `threads` threads, for per-call overrides.
+///
+/// The pool uses the stack size passed to [`configure`], if any.
+///
+/// # Errors
+///
+/// Returns [`RuntimeError`] if `threads` is zero or the pool cannot be built.
+pub fn with_threads<OP, R>(threads: usize, op: OP) -> Result<R, RuntimeError>
+where
+    OP: FnOnce() -> R + Send,
+    R: Send,
+{
+    let stack_size = read().stack_size;
+    Ok(build_pool(Some(threads), stack_size)?.install(op))
+}
+
+/// Returns the number of threads parallel work currently runs on.
+#[must_use]
+pub fn current_num_threads() -> usize {
+    read().pool.as_ref().map_or_else(rayon::current_num_threads, |pool| pool.current_num_threads())
+}
+
+fn build_pool(
+    threads: Option<usize>,
+    stack_size: Option<usize>,
+) -> Result<ThreadPool, RuntimeError> {
+    let mut builder = ThreadPoolBuilder::new().thread_name(|i| format!("scrape-worker-{i}"));
+    match threads {
+        Some(0) => return Err(RuntimeError::ZeroThreads),
+        Some(n) => builder = builder.num_threads(n),
+        None => {}
+    }
+    match stack_size {
+        Some(0) => return Err(RuntimeError::ZeroStackSize),
+        Some(size) => builder = builder.stack_size(size),
+        None => {}
+    }
+    Ok(builder.build()?)
+}
+
+fn read() -> std::sync::RwLockReadGuard<'static, Runtime> {
+    RUNTIME.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write() -> std::sync::RwLockWriteGuard<'static, Runtime> {
+    RUNTIME.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::prelude::*;
+
+    use super::*;
+
+    // The runtime is process-wide, so configuration changes are tested in one function
+    // to keep parallel tests from observing each other's pools.
+    #[test]
+    fn test_configure_install_and_reset() {
+        configure(Some(3), Some(4 * 1024 * 1024)).unwrap();
+        assert_eq!(current_num_threads(), 3);
+        let (threads, sum) =
+            install(|| (rayon::current_num_threads(), (1..=100).into_par_iter().sum::<i32>()));
+        assert_eq!((threads, sum), (3, 5050));
+        let name = install(|| std::thread::current().name().map(str::to_owned));
+        assert!(name.is_some_and(|name| name.starts_with("scrape-worker-")));
+        assert_eq!(with_threads(2, || install(rayon::current_num_threads)).unwrap(), 2);
+
+        assert!(matches!(configure(Some(0), None), Err(RuntimeError::ZeroThreads)));
+        assert!(matches!(configure(None, Some(0)), Err(RuntimeError::ZeroStackSize)));
+        assert_eq!(current_num_threads(), 3, "a failed configure keeps the previous pool");
+
+        configure(None, None).unwrap();
+        assert_eq!(current_num_threads(), rayon::current_num_threads());
+        configure(Some(2), None).unwrap();
+        reset();
+        assert_eq!(current_num_threads(), rayon::current_num_threads());
+    }
+
+    #[test]
+    fn test_with_threads() {
+        assert_eq!(with_threads(2, rayon::current_num_threads).unwrap(), 2);
+        assert!(matches!(with_threads(0, || ()), Err(RuntimeError::ZeroThreads)));
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(RuntimeError::ZeroThreads.to_string(), "thread count must be at least 1");
+        assert_eq!(RuntimeError::ZeroStackSize.to_string(), "stack size must be greater than zero");
+    }
+}
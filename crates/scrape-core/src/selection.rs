@@ -0,0 +1,326 @@
+//! Chainable sets of matched elements.
+//!
+//! A [`Selection`] holds the elements of one document in document order, without
+//! duplicates. Refining it with [`filter`](Selection::filter), [`not`](Selection::not),
+//! [`find`](Selection::find), or [`parent`](Selection::parent) returns a new selection,
+//! so queries chain the way they do in jQuery instead of looping over a `Vec<Tag>`.
+
+use std::collections::HashSet;
+
+use crate::{
+    Tag,
+    dom::{Document, NodeId},
+    query::{QueryResult, cached_compile, find_all_within_compiled, matches_selector},
+};
+
+/// A set of elements from one document, in document order.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::Soup;
+///
+/// let soup = Soup::parse(
+///     "<ul><li class=\"sale\"><a href=\"/a\">A</a></li><li><a href=\"/b\">B</a></li></ul>",
+/// );
+/// let items = soup.selection("li").unwrap();
+/// assert_eq!(items.len(), 2);
+///
+/// let links = items.not(".sale").unwrap().find("a").unwrap();
+/// assert_eq!(links.texts(), vec!["B"]);
+/// assert_eq!(links.attrs("href"), vec![Some("/b")]);
+/// assert_eq!(links.parent().node_ids(), items.eq(1).node_ids());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Selection<'a> {
+    doc: &'a Document,
+    ids: Vec<NodeId>,
+}
+
+impl<'a> Selection<'a> {
+    /// Creates a selection of the given elements.
+    ///
+    /// The elements are put in document order and duplicates removed. Nodes that are
+    /// not part of the document tree are dropped.
+    #[must_use]
+    pub fn new(doc: &'a Document, ids: impl IntoIterator<Item = NodeId>) -> Self {
+        Self { doc, ids: in_document_order(doc, ids) }
+    }
+
+    /// Creates a selection from elements that are already in document order without
+    /// duplicates, such as the [`node_ids`](Self::node_ids) of another selection.
+    ///
+    /// The order is not checked; use [`Selection::new`] for arbitrary nodes.
+    #[must_use]
+    pub fn from_ordered(doc: &'a Document, ids: Vec<NodeId>) -> Self {
+        Self { doc, ids }
+    }
+
+    /// Returns an empty selection.
+    #[must_use]
+    pub fn empty(doc: &'a Document) -> Self {
+        Self { doc, ids: Vec::new() }
+    }
+
+    /// Returns the document the elements belong to.
+    #[must_use]
+    pub fn document(&self) -> &'a Document {
+        self.doc
+    }
+
+    /// Returns the IDs of the selected elements, in document order.
+    #[must_use]
+    pub fn node_ids(&self) -> &[NodeId] {
+        &self.ids
+    }
+
+    /// Returns the number of selected elements.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns whether no element is selected.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns the element at `index`, if any.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<Tag<'a>> {
+        self.ids.get(index).map(|&id| Tag::new(self.doc, id))
+    }
+
+    /// Returns the first selected element, if any.
+    #[must_use]
+    pub fn first(&self) -> Option<Tag<'a>> {
+        self.get(0)
+    }
+
+    /// Returns the last selected element, if any.
+    #[must_use]
+    pub fn last(&self) -> Option<Tag<'a>> {
+        self.ids.last().map(|&id| Tag::new(self.doc, id))
+    }
+
+    /// Returns an iterator over the selected elements.
+    pub fn iter(&self) -> impl Iterator<Item = Tag<'a>> + '_ {
+        self.ids.iter().map(|&id| Tag::new(self.doc, id))
+    }
+
+    /// Returns the selected elements as tags.
+    #[must_use]
+    pub fn tags(&self) -> Vec<Tag<'a>> {
+        self.iter().collect()
+    }
+
+    /// Returns a selection of only the element at `index`, or an empty selection if
+    /// `index` is out of range.
+    #[must_use]
+    pub fn eq(&self, index: usize) -> Self {
+        Self { doc: self.doc, ids: self.ids.get(index).copied().into_iter().collect() }
+    }
+
+    /// Keeps the elements that match a CSS selector.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the
+    /// selector syntax is invalid.
+    pub fn filter(&self, selector: &str) -> QueryResult<Self> {
+        self.retain_matching(selector, true)
+    }
+
+    /// Removes the elements that match a CSS selector.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the
+    /// selector syntax is invalid.
+    pub fn not(&self, selector: &str) -> QueryResult<Self> {
+        self.retain_matching(selector, false)
+    }
+
+    fn retain_matching(&self, selector: &str, keep: bool) -> QueryResult<Self> {
+        let selector = cached_compile(selector)?;
+        let ids = self
+            .ids
+            .iter()
+            .copied()
+            .filter(|&id| matches_selector(self.doc, id, selector.selector_list()) == keep)
+            .collect();
+        Ok(Self { doc: self.doc, ids })
+    }
+
+    /// Selects the descendants of any selected element that match a CSS selector.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the
+    /// selector syntax is invalid.
+    pub fn find(&self, selector: &str) -> QueryResult<Self> {
+        let selector = cached_compile(selector)?;
+        if let [id] = self.ids[..] {
+            let ids = find_all_within_compiled(self.doc, id, &selector);
+            return Ok(Self { doc: self.doc, ids });
+        }
+        let ids = self.ids.iter().flat_map(|&id| find_all_within_compiled(self.doc, id, &selector));
+        Ok(Self::new(self.doc, ids))
+    }
+
+    /// Selects the parent element of each selected element.
+    #[must_use]
+    pub fn parent(&self) -> Self {
+        Self::new(self.doc, self.iter().filter_map(|tag| tag.parent()).map(|tag| tag.node_id()))
+    }
+
+    /// Returns the text content of each selected element.
+    #[must_use]
+    pub fn texts(&self) -> Vec<String> {
+        self.iter().map(|tag| tag.text()).collect()
+    }
+
+    /// Returns the value of an attribute on each selected element, or `None` where it is
+    /// missing.
+    #[must_use]
+    pub fn attrs(&self, name: &str) -> Vec<Option<&'a str>> {
+        let doc = self.doc;
+        self.ids
+            .iter()
+            .map(|&id| {
+                doc.get(id)
+                    .and_then(|node| node.kind.attributes())
+                    .and_then(|attributes| attributes.get(name))
+            })
+            .collect()
+    }
+
+    /// Returns the elements in either selection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the selections belong to different documents.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.assert_same_document(other);
+        Self::new(self.doc, self.ids.iter().chain(&other.ids).copied())
+    }
+
+    /// Returns the elements in both selections.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the selections belong to different documents.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.assert_same_document(other);
+        let other: HashSet<_> = other.ids.iter().collect();
+        let ids = self.ids.iter().copied().filter(|id| other.contains(id)).collect();
+        Self { doc: self.doc, ids }
+    }
+
+    fn assert_same_document(&self, other: &Self) {
+        assert!(
+            std::ptr::eq(self.doc, other.doc),
+            "cannot combine selections from different documents"
+        );
+    }
+}
+
+impl<'a> From<Tag<'a>> for Selection<'a> {
+    fn from(tag: Tag<'a>) -> Self {
+        Self { doc: tag.document(), ids: vec![tag.node_id()] }
+    }
+}
+
+impl<'a> IntoIterator for &Selection<'a> {
+    type Item = Tag<'a>;
+    type IntoIter = std::vec::IntoIter<Tag<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tags().into_iter()
+    }
+}
+
+/// Puts nodes in document order and removes duplicates and nodes outside the tree.
+fn in_document_order(doc: &Document, ids: impl IntoIterator<Item = NodeId>) -> Vec<NodeId> {
+    let mut wanted: HashSet<NodeId> = ids.into_iter().collect();
+    let Some(root) = doc.root() else { return Vec::new() };
+    let mut ordered = Vec::with_capacity(wanted.len());
+    for id in std::iter::once(root).chain(doc.descendants(root)) {
+        if wanted.is_empty() {
+            break;
+        }
+        if wanted.remove(&id) {
+            ordered.push(id);
+        }
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Soup;
+
+    const HTML: &str = "<div id=\"a\"><p class=\"x\">1</p><p>2</p></div><div id=\"b\"><p \
+                        class=\"x\">3</p><span>4</span></div>";
+
+    fn ids(selection: &super::Selection<'_>) -> Vec<String> {
+        selection.iter().map(|tag| tag.get("id").map_or_else(|| tag.text(), String::from)).collect()
+    }
+
+    #[test]
+    fn test_filter_not_eq() {
+        let soup = Soup::parse(HTML);
+        let ps = soup.selection("p").unwrap();
+        assert_eq!(ids(&ps), ["1", "2", "3"]);
+        assert_eq!(ids(&ps.filter(".x").unwrap()), ["1", "3"]);
+        assert_eq!(ids(&ps.not(".x").unwrap()), ["2"]);
+        assert_eq!(ids(&ps.eq(2)), ["3"]);
+        assert!(ps.eq(3).is_empty());
+        assert_eq!(ps.last().unwrap().text(), "3");
+        assert!(ps.filter("[").is_err());
+    }
+
+    #[test]
+    fn test_find_and_parent_are_ordered_and_unique() {
+        let soup = Soup::parse(HTML);
+        let divs = soup.selection("div").unwrap();
+        assert_eq!(ids(&divs.find("p, span").unwrap()), ["1", "2", "3", "4"]);
+        assert_eq!(ids(&divs.eq(1).find("p").unwrap()), ["3"]);
+
+        let parents = soup.selection("p, span").unwrap().parent();
+        assert_eq!(ids(&parents), ["a", "b"]);
+        assert_eq!(parents.parent().len(), 1);
+    }
+
+    #[test]
+    fn test_texts_and_attrs() {
+        let soup = Soup::parse(HTML);
+        let children = soup.selection("div > *").unwrap();
+        assert_eq!(children.texts(), ["1", "2", "3", "4"]);
+        assert_eq!(children.attrs("class"), [Some("x"), None, Some("x"), None]);
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let soup = Soup::parse(HTML);
+        let last = soup.selection("span, #b p").unwrap();
+        let first = soup.selection("#a p").unwrap();
+        assert_eq!(ids(&last.union(&first)), ["1", "2", "3", "4"]);
+        assert_eq!(ids(&first.union(&first)), ["1", "2"]);
+
+        let marked = soup.selection(".x").unwrap();
+        assert_eq!(ids(&marked.intersection(&last)), ["3"]);
+        assert!(marked.intersection(&soup.selection("span").unwrap()).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "different documents")]
+    fn test_union_across_documents_panics() {
+        let one = Soup::parse(HTML);
+        let two = Soup::parse(HTML);
+        let _ = one.selection("p").unwrap().union(&two.selection("p").unwrap());
+    }
+}
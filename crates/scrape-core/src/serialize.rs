@@ -5,11 +5,75 @@
 //! (Python, Node.js, WASM) to implement `inner_html`, `outer_html`, and `text`
 //! properties.
 
+use std::{borrow::Cow, fmt::Write};
+
 use crate::{
-    Document, NodeId, NodeKind, Tag,
-    utils::{escape_attr, escape_text, is_void_element},
+    Attributes, Document, NodeId, NodeKind, Tag,
+    dom::Namespace,
+    utils::{escape_attr, escape_text, is_void_element, parse_style},
 };
 
+/// How [`serialize_node_with`] quotes attribute values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum QuoteStyle {
+    /// `name="value"`.
+    #[default]
+    Double,
+    /// `name='value'`, with `'` in values written as `&#39;`.
+    Single,
+    /// `name=value` where HTML allows an unquoted value, `name="value"` otherwise.
+    Minimal,
+}
+
+/// Formatting options for [`serialize_node_with`] and [`serialize_inner_html_with`].
+///
+/// The default writes the same compact HTML as [`serialize_node`].
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{SerializeOptions, Soup};
+///
+/// let soup = Soup::parse("<div>\n  <p>Hello   <b>World</b></p>\n</div>");
+/// let div = soup.find("div").unwrap().unwrap();
+///
+/// let options = SerializeOptions { pretty: true, ..SerializeOptions::default() };
+/// assert_eq!(div.outer_html_with(&options), "<div>\n  <p>Hello <b>World</b></p>\n</div>\n");
+///
+/// let options = SerializeOptions { minify: true, ..SerializeOptions::default() };
+/// assert_eq!(div.outer_html_with(&options), "<div><p>Hello <b>World</b></p></div>");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerializeOptions {
+    /// Whether to put each block-level element on its own line, indented by depth.
+    ///
+    /// Elements holding only text and inline elements stay on one line, with their
+    /// whitespace collapsed. Preformatted elements such as `<pre>` are written as is.
+    pub pretty: bool,
+    /// Number of spaces per nesting level when pretty-printing.
+    pub indent: usize,
+    /// Whether to drop comments and whitespace between block-level elements, collapse
+    /// whitespace runs in text, and write empty attribute values as bare names.
+    pub minify: bool,
+    /// How attribute values are quoted.
+    pub quote_style: QuoteStyle,
+    /// Whether to write characters outside ASCII in text and attribute values as
+    /// numeric character references.
+    pub escape_non_ascii: bool,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self {
+            pretty: false,
+            indent: 2,
+            minify: false,
+            quote_style: QuoteStyle::Double,
+            escape_non_ascii: false,
+        }
+    }
+}
+
 /// Serializes a DOM node and its subtree to HTML.
 ///
 /// This function recursively serializes an element, its attributes, and all
@@ -41,7 +105,7 @@ pub fn serialize_node(doc: &Document, id: NodeId, buf: &mut String) {
     let Some(node) = doc.get(id) else { return };
 
     match &node.kind {
-        NodeKind::Element { name, attributes, .. } => {
+        NodeKind::Element { name, attributes, namespace, .. } => {
             buf.push('<');
             buf.push_str(name);
 
@@ -55,7 +119,7 @@ pub fn serialize_node(doc: &Document, id: NodeId, buf: &mut String) {
 
             buf.push('>');
 
-            if !is_void_element(name) {
+            if !is_void(name, *namespace) {
                 for child_id in doc.children(id) {
                     serialize_node(doc, child_id, buf);
                 }
@@ -72,6 +136,11 @@ pub fn serialize_node(doc: &Document, id: NodeId, buf: &mut String) {
             buf.push_str(content);
             buf.push_str("-->");
         }
+        NodeKind::Doctype { name, .. } => {
+            buf.push_str("<!DOCTYPE ");
+            buf.push_str(name);
+            buf.push('>');
+        }
     }
 }
 
@@ -99,6 +168,351 @@ pub fn serialize_inner_html(doc: &Document, id: NodeId, buf: &mut String) {
     }
 }
 
+/// Serializes a DOM node and its subtree to HTML formatted by `options`.
+///
+/// With the default options this writes the same HTML as [`serialize_node`].
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{QuoteStyle, SerializeOptions, Soup, serialize::serialize_node_with};
+///
+/// let soup = Soup::parse("<p title=\"café\">Hi<!-- note --></p>");
+/// let p = soup.find("p").unwrap().unwrap();
+/// let options = SerializeOptions {
+///     minify: true,
+///     quote_style: QuoteStyle::Minimal,
+///     escape_non_ascii: true,
+///     ..SerializeOptions::default()
+/// };
+///
+/// let mut html = String::new();
+/// serialize_node_with(soup.document(), p.node_id(), &options, &mut html);
+/// assert_eq!(html, "<p title=caf&#xE9;>Hi</p>");
+/// ```
+pub fn serialize_node_with(
+    doc: &Document,
+    id: NodeId,
+    options: &SerializeOptions,
+    buf: &mut String,
+) {
+    let mut writer = Writer::new(doc, options, buf, doc.parent(id));
+    writer.node(id, options.pretty.then_some(0));
+}
+
+/// Serializes only the children of a node to HTML formatted by `options`.
+///
+/// When pretty-printing, children that are all text and inline elements are written on
+/// one line, as they would be inside the node's own tags.
+pub fn serialize_inner_html_with(
+    doc: &Document,
+    id: NodeId,
+    options: &SerializeOptions,
+    buf: &mut String,
+) {
+    let mut writer = Writer::new(doc, options, buf, Some(id));
+    let pretty = options.pretty && writer.preformatted == 0;
+    let depth = (pretty && writer.has_block_children(id)).then_some(0);
+    let start = writer.buf.len();
+    for child_id in doc.children(id) {
+        writer.node(child_id, depth);
+    }
+    if pretty && depth.is_none() {
+        writer.trim_from(start);
+    }
+}
+
+/// Serializes nodes for [`serialize_node_with`] and [`serialize_inner_html_with`].
+struct Writer<'a> {
+    doc: &'a Document,
+    options: &'a SerializeOptions,
+    buf: &'a mut String,
+    /// Number of open elements, such as `<pre>`, whose whitespace must be kept.
+    preformatted: usize,
+}
+
+impl<'a> Writer<'a> {
+    /// Creates a writer for nodes inside `parent`.
+    fn new(
+        doc: &'a Document,
+        options: &'a SerializeOptions,
+        buf: &'a mut String,
+        parent: Option<NodeId>,
+    ) -> Self {
+        let preformatted = if options.pretty || options.minify {
+            parent
+                .into_iter()
+                .chain(parent.into_iter().flat_map(|id| doc.ancestors(id)))
+                .filter(|&id| {
+                    doc.get(id).and_then(|n| n.kind.tag_name()).is_some_and(is_preformatted)
+                })
+                .count()
+        } else {
+            0
+        };
+        Self { doc, options, buf, preformatted }
+    }
+
+    /// Writes a node; `depth` is the indentation level when it is laid out as a block.
+    fn node(&mut self, id: NodeId, depth: Option<usize>) {
+        let Some(node) = self.doc.get(id) else { return };
+
+        match &node.kind {
+            NodeKind::Element { name, attributes, namespace, .. } => {
+                if let Some(depth) = depth {
+                    self.indent(depth);
+                }
+                self.buf.push('<');
+                self.buf.push_str(name);
+                for (attr_name, attr_value) in attributes {
+                    self.buf.push(' ');
+                    self.buf.push_str(attr_name);
+                    if !(self.options.minify && attr_value.is_empty()) {
+                        self.buf.push('=');
+                        self.attr_value(attr_value);
+                    }
+                }
+                self.buf.push('>');
+
+                if !is_void(name, *namespace) {
+                    let preformatted = is_preformatted(name);
+                    self.preformatted += usize::from(preformatted);
+                    let block = depth.filter(|_| !preformatted && self.has_block_children(id));
+                    if let Some(depth) = block {
+                        self.buf.push('\n');
+                        for child_id in self.doc.children(id) {
+                            self.node(child_id, Some(depth + 1));
+                        }
+                        self.indent(depth);
+                    } else {
+                        let start = self.buf.len();
+                        for child_id in self.doc.children(id) {
+                            self.node(child_id, None);
+                        }
+                        if depth.is_some() && !preformatted {
+                            self.trim_from(start);
+                        }
+                    }
+                    self.preformatted -= usize::from(preformatted);
+                    self.buf.push_str("</");
+                    self.buf.push_str(name);
+                    self.buf.push('>');
+                }
+                if depth.is_some() {
+                    self.buf.push('\n');
+                }
+            }
+            NodeKind::Text { content } => {
+                let collapse =
+                    (self.options.pretty || self.options.minify) && self.preformatted == 0;
+                let text = if collapse {
+                    collapse_whitespace(content)
+                } else {
+                    Cow::Borrowed(content.as_str())
+                };
+                if let Some(depth) = depth {
+                    let text = text.trim();
+                    if !text.is_empty() {
+                        self.indent(depth);
+                        self.text(&escape_text(text));
+                        self.buf.push('\n');
+                    }
+                } else {
+                    let insignificant = collapse
+                        && self.options.minify
+                        && text.trim().is_empty()
+                        && !self.between_inline(id);
+                    if !insignificant {
+                        self.text(&escape_text(&text));
+                    }
+                }
+            }
+            NodeKind::Comment { content } => {
+                if self.options.minify {
+                    return;
+                }
+                if let Some(depth) = depth {
+                    self.indent(depth);
+                }
+                self.buf.push_str("<!--");
+                self.buf.push_str(content);
+                self.buf.push_str("-->");
+                if depth.is_some() {
+                    self.buf.push('\n');
+                }
+            }
+            NodeKind::Doctype { name, .. } => {
+                if let Some(depth) = depth {
+                    self.indent(depth);
+                }
+                self.buf.push_str("<!DOCTYPE ");
+                self.buf.push_str(name);
+                self.buf.push('>');
+                if depth.is_some() {
+                    self.buf.push('\n');
+                }
+            }
+        }
+    }
+
+    fn attr_value(&mut self, value: &str) {
+        match self.options.quote_style {
+            QuoteStyle::Double => {
+                self.buf.push('"');
+                self.text(&escape_attr(value));
+                self.buf.push('"');
+            }
+            QuoteStyle::Single => {
+                self.buf.push('\'');
+                self.text(&escape_attr(value).replace('\'', "&#39;"));
+                self.buf.push('\'');
+            }
+            QuoteStyle::Minimal => {
+                let unquoted = !value.is_empty()
+                    && !value.contains(|c: char| {
+                        c.is_ascii_whitespace() || matches!(c, '"' | '\'' | '=' | '<' | '>' | '`')
+                    });
+                if unquoted {
+                    self.text(&escape_attr(value));
+                } else {
+                    self.buf.push('"');
+                    self.text(&escape_attr(value));
+                    self.buf.push('"');
+                }
+            }
+        }
+    }
+
+    /// Appends already escaped text, encoding non-ASCII characters if asked to.
+    fn text(&mut self, text: &str) {
+        if !self.options.escape_non_ascii || text.is_ascii() {
+            self.buf.push_str(text);
+            return;
+        }
+        for c in text.chars() {
+            if c.is_ascii() {
+                self.buf.push(c);
+            } else {
+                let _ = write!(self.buf, "&#x{:X};", u32::from(c));
+            }
+        }
+    }
+
+    fn indent(&mut self, depth: usize) {
+        self.buf.extend(std::iter::repeat_n(' ', depth * self.options.indent));
+    }
+
+    /// Strips leading and trailing whitespace from what was written after `start`.
+    fn trim_from(&mut self, start: usize) {
+        let end = start.max(self.buf.trim_end().len());
+        self.buf.truncate(end);
+        let leading = self.buf[start..].len() - self.buf[start..].trim_start().len();
+        self.buf.drain(start..start + leading);
+    }
+
+    /// Returns whether `id` has a child that is laid out as a block when pretty-printing.
+    fn has_block_children(&self, id: NodeId) -> bool {
+        self.doc.children(id).any(|child| match self.doc.get(child).map(|n| &n.kind) {
+            Some(NodeKind::Element { name, .. }) => !is_inline_element(name),
+            Some(NodeKind::Comment { .. }) => !self.options.minify,
+            _ => false,
+        })
+    }
+
+    /// Returns whether the whitespace-only text `id` separates inline content, where
+    /// dropping it would join words.
+    fn between_inline(&self, id: NodeId) -> bool {
+        // At the edge of an inline parent, the text may separate the parent's siblings.
+        let edge = self
+            .doc
+            .parent(id)
+            .and_then(|parent| self.doc.get(parent))
+            .and_then(|n| n.kind.tag_name())
+            .is_some_and(is_inline_element);
+        let inline = |sibling: Option<NodeId>| {
+            sibling.map_or(edge, |sibling| match self.doc.get(sibling).map(|n| &n.kind) {
+                Some(NodeKind::Element { name, .. }) => is_inline_element(name),
+                Some(NodeKind::Text { .. }) => true,
+                _ => false,
+            })
+        };
+        inline(self.doc.prev_sibling(id)) && inline(self.doc.next_sibling(id))
+    }
+}
+
+/// Returns whether the element `name` has no end tag; only HTML has void elements.
+fn is_void(name: &str, namespace: Namespace) -> bool {
+    namespace == Namespace::Html && is_void_element(name)
+}
+
+/// Returns whether whitespace inside the element `name` is significant.
+fn is_preformatted(name: &str) -> bool {
+    matches!(name, "pre" | "textarea" | "script" | "style" | "listing" | "plaintext")
+}
+
+/// Returns whether the element `name` is laid out inline, as part of running text.
+fn is_inline_element(name: &str) -> bool {
+    matches!(
+        name,
+        "a" | "abbr"
+            | "b"
+            | "bdi"
+            | "bdo"
+            | "br"
+            | "button"
+            | "cite"
+            | "code"
+            | "data"
+            | "del"
+            | "dfn"
+            | "em"
+            | "i"
+            | "img"
+            | "input"
+            | "ins"
+            | "kbd"
+            | "label"
+            | "mark"
+            | "q"
+            | "s"
+            | "samp"
+            | "select"
+            | "small"
+            | "span"
+            | "strong"
+            | "sub"
+            | "sup"
+            | "time"
+            | "u"
+            | "var"
+            | "wbr"
+    )
+}
+
+/// Replaces each run of ASCII whitespace in `text` with a single space.
+fn collapse_whitespace(text: &str) -> Cow<'_, str> {
+    if !text.contains("  ") && !text.contains(|c: char| c.is_ascii_whitespace() && c != ' ') {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            space = true;
+        } else {
+            if space {
+                out.push(' ');
+                space = false;
+            }
+            out.push(c);
+        }
+    }
+    if space {
+        out.push(' ');
+    }
+    Cow::Owned(out)
+}
+
 /// Collects text content from a node and its descendants.
 ///
 /// This function recursively traverses the DOM subtree and concatenates
@@ -128,10 +542,232 @@ pub fn collect_text(doc: &Document, id: NodeId, buf: &mut String) {
                 collect_text(doc, child_id, buf);
             }
         }
-        NodeKind::Comment { .. } => {}
+        NodeKind::Comment { .. } | NodeKind::Doctype { .. } => {}
     }
 }
 
+/// Options for [`collect_text_with`] and [`Tag::text_with`].
+///
+/// The default gives the same text as [`collect_text`].
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, TextOptions};
+///
+/// let soup = Soup::parse("<ul>\n  <li>Green   tea</li>\n  <li>Black tea</li>\n</ul>");
+/// let ul = soup.find("ul").unwrap().unwrap();
+///
+/// let options = TextOptions { separator: "|".into(), strip: true, ..TextOptions::default() };
+/// assert_eq!(ul.text_with(&options), "Green   tea|Black tea");
+///
+/// let options = TextOptions::inner_text();
+/// assert_eq!(ul.text_with(&options), "Green tea\nBlack tea");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextOptions {
+    /// Written between consecutive pieces of text on the same line.
+    pub separator: String,
+    /// Whether to trim whitespace from each text node, leaving out nodes that are only
+    /// whitespace.
+    pub strip: bool,
+    /// Whether to collapse each run of whitespace into a single space and trim it from the
+    /// start and end of lines, as browsers render text. Whitespace inside preformatted
+    /// elements such as `<pre>` is kept.
+    pub collapse_whitespace: bool,
+    /// Whether to start a new line at each `<br>` and around each block-level element,
+    /// like a browser's `innerText`.
+    pub block_newlines: bool,
+}
+
+impl TextOptions {
+    /// Returns options that lay out text like a browser's `innerText`: whitespace
+    /// collapsed, with line breaks for `<br>` and block-level elements.
+    #[must_use]
+    pub fn inner_text() -> Self {
+        Self { collapse_whitespace: true, block_newlines: true, ..Self::default() }
+    }
+}
+
+/// Collects text content from a node and its descendants, laid out by `options`.
+///
+/// With default options this is the same as [`collect_text`]. Comments are skipped, and
+/// unlike [`collect_visible_text`] the content of `<script>` and hidden elements is
+/// included.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, TextOptions, serialize::collect_text_with};
+///
+/// let soup = Soup::parse("<div><p>Hello <b>World</b></p><p>Line<br>break</p></div>");
+/// let div = soup.find("div").unwrap().unwrap();
+///
+/// let mut text = String::new();
+/// collect_text_with(soup.document(), div.node_id(), &TextOptions::inner_text(), &mut text);
+/// assert_eq!(text, "Hello World\nLine\nbreak");
+/// ```
+pub fn collect_text_with(doc: &Document, id: NodeId, options: &TextOptions, buf: &mut String) {
+    let preformatted = if options.collapse_whitespace {
+        doc.ancestors(id)
+            .filter(|&id| doc.get(id).and_then(|n| n.kind.tag_name()).is_some_and(is_preformatted))
+            .count()
+    } else {
+        0
+    };
+    let mut writer =
+        TextWriter { doc, options, start: buf.len(), buf, preformatted, started: false, breaks: 0 };
+    writer.node(id);
+    if options.collapse_whitespace {
+        writer.trim_end();
+    }
+}
+
+/// Writes text for [`collect_text_with`].
+struct TextWriter<'a> {
+    doc: &'a Document,
+    options: &'a TextOptions,
+    buf: &'a mut String,
+    /// Where the text starts in `buf`.
+    start: usize,
+    /// Number of open elements, such as `<pre>`, whose whitespace must be kept.
+    preformatted: usize,
+    /// Whether any text has been written.
+    started: bool,
+    /// Line breaks to write before the next text.
+    breaks: usize,
+}
+
+impl TextWriter<'_> {
+    fn node(&mut self, id: NodeId) {
+        let Some(node) = self.doc.get(id) else { return };
+
+        match &node.kind {
+            NodeKind::Text { content } => self.text(content),
+            NodeKind::Element { name, .. } => {
+                if self.options.block_newlines && name == "br" {
+                    self.breaks += 1;
+                    return;
+                }
+                let block = self.options.block_newlines && !is_inline_element(name);
+                let preformatted = is_preformatted(name);
+                if block {
+                    self.breaks = self.breaks.max(1);
+                }
+                self.preformatted += usize::from(preformatted);
+                for child_id in self.doc.children(id) {
+                    self.node(child_id);
+                }
+                self.preformatted -= usize::from(preformatted);
+                if block {
+                    self.breaks = self.breaks.max(1);
+                }
+            }
+            NodeKind::Comment { .. } | NodeKind::Doctype { .. } => {}
+        }
+    }
+
+    fn text(&mut self, content: &str) {
+        let collapse = self.options.collapse_whitespace && self.preformatted == 0;
+        let text = if collapse { collapse_whitespace(content) } else { Cow::Borrowed(content) };
+        let mut text = if self.options.strip { text.trim() } else { &*text };
+        let line_start = !self.started || self.breaks > 0;
+        if collapse {
+            if line_start {
+                text = text.trim_start();
+            } else if self.options.separator.is_empty() && self.buf.ends_with(' ') {
+                // A space was already written at the end of the previous text.
+                text = text.strip_prefix(' ').unwrap_or(text);
+            }
+        }
+        if text.is_empty() {
+            return;
+        }
+
+        if self.started {
+            if self.breaks > 0 {
+                if self.options.collapse_whitespace {
+                    self.trim_end();
+                }
+                self.buf.extend(std::iter::repeat_n('\n', self.breaks));
+            } else {
+                self.buf.push_str(&self.options.separator);
+            }
+        }
+        self.buf.push_str(text);
+        self.started = true;
+        self.breaks = 0;
+    }
+
+    /// Strips trailing spaces from the text written so far.
+    fn trim_end(&mut self) {
+        let end = self.start.max(self.buf.trim_end_matches(' ').len());
+        self.buf.truncate(end);
+    }
+}
+
+/// Collects the text a browser would show from a node and its descendants.
+///
+/// Like [`collect_text`], but `<script>`, `<style>`, `<template>`, and `<noscript>`
+/// elements are skipped with their subtrees, as are elements hidden by a `hidden`
+/// attribute, `aria-hidden="true"`, or `display: none` in their `style` attribute.
+/// Stylesheets are not applied, so elements hidden by CSS rules are still included.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::{Soup, serialize::collect_visible_text};
+///
+/// let soup = Soup::parse(
+///     "<div>Hello <script>track()</script><b style=\"display:none\">x</b>World</div>",
+/// );
+/// let div = soup.find("div").unwrap().unwrap();
+///
+/// let mut text = String::new();
+/// collect_visible_text(soup.document(), div.node_id(), &mut text);
+/// assert_eq!(text, "Hello World");
+/// ```
+pub fn collect_visible_text(doc: &Document, id: NodeId, buf: &mut String) {
+    let Some(node) = doc.get(id) else { return };
+
+    match &node.kind {
+        NodeKind::Text { content } => buf.push_str(content),
+        NodeKind::Element { name, attributes, .. } => {
+            if is_hidden(name, attributes) {
+                return;
+            }
+            for child_id in doc.children(id) {
+                collect_visible_text(doc, child_id, buf);
+            }
+        }
+        NodeKind::Comment { .. } | NodeKind::Doctype { .. } => {}
+    }
+}
+
+/// Returns whether the element `name` is never rendered or hidden by its attributes.
+fn is_hidden(name: &str, attributes: &Attributes) -> bool {
+    matches!(name, "script" | "style" | "template" | "noscript")
+        || attributes.contains_key("hidden")
+        || attributes.get("aria-hidden").is_some_and(|v| v.trim().eq_ignore_ascii_case("true"))
+        || attributes.get("style").is_some_and(is_display_none)
+}
+
+/// Returns whether an inline style sets `display: none`.
+fn is_display_none(style: &str) -> bool {
+    parse_style(style).get("display").is_some_and(|value| value.eq_ignore_ascii_case("none"))
+}
+
+/// Returns whether the element `id` or one of its ancestors would be skipped by
+/// [`collect_visible_text`].
+pub(crate) fn is_hidden_element(doc: &Document, id: NodeId) -> bool {
+    std::iter::once(id).chain(doc.ancestors(id)).any(|id| {
+        doc.get(id).is_some_and(|node| match &node.kind {
+            NodeKind::Element { name, attributes, .. } => is_hidden(name, attributes),
+            _ => false,
+        })
+    })
+}
+
 /// Trait for types that can be serialized to HTML.
 ///
 /// This trait provides a unified interface for HTML serialization operations.
@@ -223,7 +859,14 @@ impl HtmlSerializer for Tag<'_> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Soup;
+    use crate::{Soup, SoupConfig};
+
+    /// Parses `html` keeping whitespace-only text and comments, which the layout
+    /// options act on.
+    fn parse_verbatim(html: &str) -> Soup {
+        let config = SoupConfig::builder().preserve_whitespace(true).include_comments(true).build();
+        Soup::parse_with_config(html, config)
+    }
 
     #[test]
     fn test_serialize_node_element() {
@@ -356,6 +999,74 @@ mod tests {
         assert_eq!(buf, "");
     }
 
+    #[test]
+    fn test_collect_visible_text() {
+        let soup = Soup::parse(
+            "<body><p>A<!-- c --><style>p{}</style><noscript>enable JS</noscript></p><p \
+             hidden>B</p><p aria-hidden=\"TRUE\">C</p><p aria-hidden=\"false\">D</p><p \
+             style=\"color: red; display: none !important\">E</p><p \
+             style=\"display:none;display:block\">F</p><template>G</template></body>",
+        );
+        let body = soup.find("body").unwrap().unwrap();
+
+        let mut text = String::new();
+        collect_visible_text(soup.document(), body.node_id(), &mut text);
+        assert_eq!(text, "ADF");
+
+        let style = soup.find("style").unwrap().unwrap();
+        text.clear();
+        collect_visible_text(soup.document(), style.node_id(), &mut text);
+        assert_eq!(text, "");
+    }
+
+    #[test]
+    fn test_collect_text_with_separator_and_strip() {
+        let soup = Soup::parse("<div>Hello <b>World</b>!<!-- c --></div>");
+        let div = soup.find("div").unwrap().unwrap();
+        let text = |options: TextOptions| div.text_with(&options);
+
+        assert_eq!(text(TextOptions::default()), div.text());
+        assert_eq!(
+            text(TextOptions { separator: "|".into(), ..TextOptions::default() }),
+            "Hello |World|!"
+        );
+        assert_eq!(text(TextOptions { strip: true, ..TextOptions::default() }), "HelloWorld!");
+        assert_eq!(
+            text(TextOptions { separator: " ".into(), strip: true, ..TextOptions::default() }),
+            "Hello World !"
+        );
+    }
+
+    #[test]
+    fn test_collect_text_with_inner_text() {
+        let soup = Soup::parse(
+            "<div><h1>Title</h1>\n<p>  First   line<br>second <b>bold</b> text \
+             </p><ul><li>One</li><li>Two</li></ul>End<br><br>after</div>",
+        );
+        let div = soup.find("div").unwrap().unwrap();
+        assert_eq!(
+            div.text_with(&TextOptions::inner_text()),
+            "Title\nFirst line\nsecond bold text\nOne\nTwo\nEnd\n\nafter"
+        );
+
+        // Without block newlines, the leading whitespace of `<p>` still collapses to a space,
+        // while elements without whitespace between them run together.
+        let options = TextOptions { collapse_whitespace: true, ..TextOptions::default() };
+        assert_eq!(div.text_with(&options), "Title First linesecond bold text OneTwoEndafter");
+    }
+
+    #[test]
+    fn test_collect_text_with_keeps_preformatted() {
+        let soup = Soup::parse("<div><p>Code:</p><pre>  let  x;\n<code>\ty  </code></pre></div>");
+        let div = soup.find("div").unwrap().unwrap();
+        assert_eq!(div.text_with(&TextOptions::inner_text()), "Code:\n  let  x;\n\ty");
+
+        let code = soup.find("code").unwrap().unwrap();
+        let mut buf = String::from("> ");
+        collect_text_with(soup.document(), code.node_id(), &TextOptions::inner_text(), &mut buf);
+        assert_eq!(buf, "> \ty");
+    }
+
     #[test]
     fn test_html_serializer_serialize_html() {
         let soup = Soup::parse("<div class=\"test\"><span>Hi</span></div>");
@@ -400,4 +1111,67 @@ mod tests {
 
         assert_eq!(cap1, cap2); // No reallocation
     }
+
+    #[test]
+    fn test_serialize_with_default_matches_serialize_node() {
+        let soup = Soup::parse("<div id=\"a\">\n  <p>x &amp; y<!--c--></p>\n</div>");
+        let div = soup.find("div").unwrap().unwrap();
+
+        assert_eq!(div.outer_html_with(&SerializeOptions::default()), div.outer_html());
+        assert_eq!(div.inner_html_with(&SerializeOptions::default()), div.inner_html());
+    }
+
+    #[test]
+    fn test_serialize_pretty() {
+        let soup = parse_verbatim(
+            "<div><p>  Hello   <b>World</b> <i>x</i></p><!--c--><pre> a\n  b </pre></div>",
+        );
+        let div = soup.find("div").unwrap().unwrap();
+        let options = SerializeOptions { pretty: true, indent: 4, ..SerializeOptions::default() };
+
+        assert_eq!(
+            div.outer_html_with(&options),
+            "<div>\n    <p>Hello <b>World</b> <i>x</i></p>\n    <!--c-->\n    <pre> a\n  b \
+             </pre>\n</div>\n"
+        );
+        let p = soup.find("p").unwrap().unwrap();
+        assert_eq!(p.inner_html_with(&options), "Hello <b>World</b> <i>x</i>");
+    }
+
+    #[test]
+    fn test_serialize_minify() {
+        let soup = parse_verbatim(
+            "<ul>\n  <li><b>a</b> <i>b</i></li>\n  <!-- c -->\n  <li><input \
+             disabled=\"\"></li>\n</ul><pre> x\n  y </pre>",
+        );
+        let ul = soup.find("ul").unwrap().unwrap();
+        let options = SerializeOptions { minify: true, ..SerializeOptions::default() };
+
+        assert_eq!(
+            ul.outer_html_with(&options),
+            "<ul><li><b>a</b> <i>b</i></li><li><input disabled></li></ul>"
+        );
+        let pre = soup.find("pre").unwrap().unwrap();
+        assert_eq!(pre.outer_html_with(&options), "<pre> x\n  y </pre>");
+    }
+
+    #[test]
+    fn test_serialize_quote_styles() {
+        let soup =
+            Soup::parse("<a title=\"it's ok\">x</a><a href=\"/a?b=1\">y</a><a href=\"/é\">z</a>");
+        let links = soup.find_all("a").unwrap();
+        let html = |style, escape_non_ascii, i: usize| {
+            let options = SerializeOptions {
+                quote_style: style,
+                escape_non_ascii,
+                ..SerializeOptions::default()
+            };
+            links[i].outer_html_with(&options)
+        };
+
+        assert_eq!(html(QuoteStyle::Single, false, 0), "<a title='it&#39;s ok'>x</a>");
+        assert_eq!(html(QuoteStyle::Minimal, false, 0), "<a title=\"it's ok\">x</a>");
+        assert_eq!(html(QuoteStyle::Minimal, false, 1), "<a href=\"/a?b=1\">y</a>");
+        assert_eq!(html(QuoteStyle::Minimal, true, 2), "<a href=/&#xE9;>z</a>");
+    }
 }
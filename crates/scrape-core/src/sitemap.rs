@@ -0,0 +1,540 @@
+//! Parsing `sitemap.xml` files and sitemap indexes.
+//!
+//! [`Sitemap::parse`] reads a whole sitemap into a [`Sitemap`]. [`SitemapReader`] yields
+//! the entries one at a time from any [`Read`] source, holding only the entry being read
+//! in memory, for sitemaps too large to load at once. Both accept `<urlset>` sitemaps,
+//! `<sitemapindex>` files listing further sitemaps, and, with the `gzip` feature,
+//! gzipped input such as `sitemap.xml.gz`.
+//!
+//! The parser is a small XML tokenizer rather than a full XML parser: it understands
+//! elements, CDATA sections, comments, processing instructions, and the predefined and
+//! numeric character references, which is all the sitemap protocol uses. Extension
+//! elements such as `<image:image>` are skipped.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::sitemap::{ChangeFreq, Sitemap};
+//!
+//! let sitemap = Sitemap::parse(
+//!     r#"<?xml version="1.0" encoding="UTF-8"?>
+//!     <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+//!       <url>
+//!         <loc>https://example.com/?page=1&amp;sort=new</loc>
+//!         <lastmod>2024-05-01</lastmod>
+//!         <changefreq>daily</changefreq>
+//!         <priority>0.8</priority>
+//!       </url>
+//!       <url><loc>https://example.com/about</loc></url>
+//!     </urlset>"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(sitemap.urls.len(), 2);
+//! assert_eq!(sitemap.urls[0].loc, "https://example.com/?page=1&sort=new");
+//! assert_eq!(sitemap.urls[0].changefreq, Some(ChangeFreq::Daily));
+//! assert_eq!(sitemap.urls[0].priority, Some(0.8));
+//! assert!(sitemap.sitemaps.is_empty());
+//! ```
+
+use std::io::{BufRead, BufReader, Read};
+
+use crate::xml::{CHUNK_SIZE, Token, XmlError, XmlReader, local_name};
+
+/// The first bytes of a gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// A page listed in a `<urlset>` sitemap.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SitemapUrl {
+    /// Page URL, from `<loc>`.
+    pub loc: String,
+    /// When the page last changed, from `<lastmod>`, as written: a W3C Datetime such as
+    /// `2024-05-01` or `2024-05-01T09:30:00+00:00`.
+    pub lastmod: Option<String>,
+    /// How often the page is likely to change.
+    pub changefreq: Option<ChangeFreq>,
+    /// Priority relative to the other pages of the site, from `0.0` to `1.0`.
+    pub priority: Option<f32>,
+}
+
+/// A sitemap listed in a `<sitemapindex>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SitemapRef {
+    /// Sitemap URL, from `<loc>`.
+    pub loc: String,
+    /// When the sitemap last changed, from `<lastmod>`, as written.
+    pub lastmod: Option<String>,
+}
+
+/// How often a page is likely to change, from `<changefreq>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeFreq {
+    /// Changes every time it is accessed.
+    Always,
+    /// Changes hourly.
+    Hourly,
+    /// Changes daily.
+    Daily,
+    /// Changes weekly.
+    Weekly,
+    /// Changes monthly.
+    Monthly,
+    /// Changes yearly.
+    Yearly,
+    /// Archived and no longer changes.
+    Never,
+}
+
+impl ChangeFreq {
+    /// Parses a `<changefreq>` value, ignoring case and surrounding whitespace.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        [
+            Self::Always,
+            Self::Hourly,
+            Self::Daily,
+            Self::Weekly,
+            Self::Monthly,
+            Self::Yearly,
+            Self::Never,
+        ]
+        .into_iter()
+        .find(|freq| freq.as_str().eq_ignore_ascii_case(value))
+    }
+
+    /// Returns the value as written in a sitemap.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+            Self::Never => "never",
+        }
+    }
+}
+
+/// An entry read by [`SitemapReader`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SitemapEntry {
+    /// A page, from a `<url>` element of a `<urlset>`.
+    Url(SitemapUrl),
+    /// A nested sitemap, from a `<sitemap>` element of a `<sitemapindex>`.
+    Sitemap(SitemapRef),
+}
+
+/// Error reading a sitemap.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SitemapError {
+    /// Reading or decompressing the input failed.
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    /// The input is not well-formed XML.
+    #[error("malformed sitemap at byte {offset}: {message}")]
+    Syntax {
+        /// Byte offset of the error in the (decompressed) input.
+        offset: u64,
+        /// Description of the problem.
+        message: String,
+    },
+
+    /// The root element is neither `<urlset>` nor `<sitemapindex>`.
+    #[error("not a sitemap: root element is <{0}>")]
+    NotSitemap(String),
+
+    /// The input is gzipped, and the `gzip` feature is disabled.
+    #[error("gzipped sitemap; enable the `gzip` feature to read it")]
+    Gzip,
+}
+
+impl From<XmlError> for SitemapError {
+    fn from(error: XmlError) -> Self {
+        match error {
+            XmlError::Io(message) => Self::Io(message),
+            XmlError::Syntax { offset, message } => Self::Syntax { offset, message },
+        }
+    }
+}
+
+/// A parsed sitemap or sitemap index.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sitemap {
+    /// Pages listed in a `<urlset>`, in document order.
+    pub urls: Vec<SitemapUrl>,
+    /// Sitemaps listed in a `<sitemapindex>`, in document order.
+    pub sitemaps: Vec<SitemapRef>,
+}
+
+impl Sitemap {
+    /// Parses a sitemap or sitemap index.
+    ///
+    /// Entries without a `<loc>` are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SitemapError`] if the input is malformed or not a sitemap.
+    pub fn parse(xml: &str) -> Result<Self, SitemapError> {
+        Self::from_reader(xml.as_bytes())
+    }
+
+    /// Reads a sitemap or sitemap index, decompressing it first if it is gzipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SitemapError`] if reading fails or the input is malformed or not a
+    /// sitemap.
+    pub fn from_reader(reader: impl Read) -> Result<Self, SitemapError> {
+        let mut sitemap = Self::default();
+        for entry in SitemapReader::new(reader) {
+            match entry? {
+                SitemapEntry::Url(url) => sitemap.urls.push(url),
+                SitemapEntry::Sitemap(nested) => sitemap.sitemaps.push(nested),
+            }
+        }
+        Ok(sitemap)
+    }
+}
+
+/// Reads the entries of a sitemap one at a time.
+///
+/// The input is read in small chunks as the iterator is advanced, so memory use stays
+/// flat however large the sitemap is. Gzipped input is detected from its first bytes.
+/// Iteration stops after the first error.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::fs::File;
+///
+/// use scrape_core::sitemap::{SitemapEntry, SitemapReader};
+///
+/// for entry in SitemapReader::new(File::open("sitemap.xml.gz").unwrap()) {
+///     match entry.unwrap() {
+///         SitemapEntry::Url(url) => println!("{}", url.loc),
+///         SitemapEntry::Sitemap(nested) => println!("see also {}", nested.loc),
+///     }
+/// }
+/// ```
+pub struct SitemapReader<'a> {
+    xml: XmlReader<'a>,
+    /// Error to return before reading anything.
+    pending: Option<SitemapError>,
+    done: bool,
+    root: Option<Root>,
+    /// Namespace prefix of the root element, which entries and fields must share.
+    prefix: String,
+    depth: usize,
+    entry: Option<SitemapUrl>,
+    field: Option<Field>,
+    text: Vec<u8>,
+}
+
+impl<'a> SitemapReader<'a> {
+    /// Creates a reader over `reader`, decompressing it if it is gzipped.
+    pub fn new(reader: impl Read + 'a) -> Self {
+        let mut input = BufReader::with_capacity(CHUNK_SIZE, reader);
+        let gzipped = input.fill_buf().is_ok_and(|head| head.starts_with(&GZIP_MAGIC));
+        let (input, pending): (Box<dyn BufRead + 'a>, _) = match gzipped {
+            #[cfg(feature = "gzip")]
+            true => {
+                let decoder = flate2::read::MultiGzDecoder::new(input);
+                (Box::new(BufReader::with_capacity(CHUNK_SIZE, decoder)), None)
+            }
+            #[cfg(not(feature = "gzip"))]
+            true => (Box::new(input), Some(SitemapError::Gzip)),
+            false => (Box::new(input), None),
+        };
+        Self {
+            xml: XmlReader::new(input),
+            pending,
+            done: false,
+            root: None,
+            prefix: String::new(),
+            depth: 0,
+            entry: None,
+            field: None,
+            text: Vec::new(),
+        }
+    }
+
+    fn read_entry(&mut self) -> Result<Option<SitemapEntry>, SitemapError> {
+        if let Some(error) = self.pending.take() {
+            return Err(error);
+        }
+        loop {
+            let Some(token) = self.xml.next_token(self.field.is_some())? else {
+                return match self.root {
+                    None => Err(self.xml.syntax("no root element").into()),
+                    Some(_) if self.depth > 0 => {
+                        Err(self.xml.syntax("unexpected end of input").into())
+                    }
+                    Some(_) => Ok(None),
+                };
+            };
+            match token {
+                Token::Text(text) => self.text.extend_from_slice(&text),
+                Token::Start { name, empty, .. } => {
+                    self.depth += 1;
+                    self.open(name)?;
+                    if empty && let Some(entry) = self.close() {
+                        return Ok(Some(entry));
+                    }
+                }
+                Token::End => {
+                    if let Some(entry) = self.close() {
+                        return Ok(Some(entry));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handles the start of an element at the current depth.
+    fn open(&mut self, name: String) -> Result<(), SitemapError> {
+        if self.depth == 1 && self.root.is_none() {
+            let (prefix, local) = name.split_once(':').unwrap_or(("", &name));
+            let root = match local {
+                "urlset" => Root::Urlset,
+                "sitemapindex" => Root::Index,
+                _ => return Err(SitemapError::NotSitemap(name)),
+            };
+            self.prefix = prefix.to_string();
+            self.root = Some(root);
+            return Ok(());
+        }
+        let (Some(root), Some(local)) = (self.root, local_name(&name, &self.prefix)) else {
+            return Ok(());
+        };
+        match self.depth {
+            2 if local == root.entry_name() => self.entry = Some(SitemapUrl::default()),
+            3 if self.entry.is_some() => {
+                self.field = Field::from_name(local);
+                self.text.clear();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Closes the innermost element, returning the entry it completes, if any.
+    fn close(&mut self) -> Option<SitemapEntry> {
+        let depth = self.depth;
+        self.depth = depth.saturating_sub(1);
+        match depth {
+            3 => {
+                if let (Some(field), Some(entry)) = (self.field.take(), &mut self.entry) {
+                    field.set(entry, &self.text);
+                }
+                None
+            }
+            2 => {
+                let entry = self.entry.take().filter(|entry| !entry.loc.is_empty())?;
+                Some(match self.root? {
+                    Root::Urlset => SitemapEntry::Url(entry),
+                    Root::Index => {
+                        SitemapEntry::Sitemap(SitemapRef { loc: entry.loc, lastmod: entry.lastmod })
+                    }
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Iterator for SitemapReader<'_> {
+    type Item = Result<SitemapEntry, SitemapError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.read_entry().transpose();
+        self.done = !matches!(result, Some(Ok(_)));
+        result
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Root {
+    Urlset,
+    Index,
+}
+
+impl Root {
+    const fn entry_name(self) -> &'static str {
+        match self {
+            Self::Urlset => "url",
+            Self::Index => "sitemap",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Loc,
+    Lastmod,
+    Changefreq,
+    Priority,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "loc" => Some(Self::Loc),
+            "lastmod" => Some(Self::Lastmod),
+            "changefreq" => Some(Self::Changefreq),
+            "priority" => Some(Self::Priority),
+            _ => None,
+        }
+    }
+
+    fn set(self, entry: &mut SitemapUrl, text: &[u8]) {
+        let text = String::from_utf8_lossy(text);
+        let value = text.trim();
+        match self {
+            Self::Loc => value.clone_into(&mut entry.loc),
+            Self::Lastmod => entry.lastmod = (!value.is_empty()).then(|| value.to_string()),
+            Self::Changefreq => entry.changefreq = ChangeFreq::parse(value),
+            Self::Priority => {
+                entry.priority = value.parse().ok().filter(|p| (0.0..=1.0).contains(p));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URLSET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- generated -->
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">
+  <url>
+    <loc> https://example.com/a?x=1&amp;y=&#50;&#x33; </loc>
+    <lastmod>2024-05-01T09:30:00+00:00</lastmod>
+    <changefreq>Weekly</changefreq>
+    <priority>0.5</priority>
+    <image:image><image:loc>https://example.com/a.png</image:loc></image:image>
+  </url>
+  <url><loc><![CDATA[https://example.com/b?q=<tea>]]></loc><priority>2</priority>
+    <changefreq>sometimes</changefreq></url>
+  <url><lastmod>2024-01-01</lastmod></url>
+  <url/>
+</urlset>
+"#;
+
+    /// A reader returning one byte per read, to split every token across chunks.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let Some((&first, rest)) = self.0.split_first() else { return Ok(0) };
+            buf[0] = first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn test_parse_urlset() {
+        let sitemap = Sitemap::parse(URLSET).unwrap();
+        assert!(sitemap.sitemaps.is_empty());
+        assert_eq!(
+            sitemap.urls,
+            [
+                SitemapUrl {
+                    loc: "https://example.com/a?x=1&y=23".into(),
+                    lastmod: Some("2024-05-01T09:30:00+00:00".into()),
+                    changefreq: Some(ChangeFreq::Weekly),
+                    priority: Some(0.5),
+                },
+                SitemapUrl { loc: "https://example.com/b?q=<tea>".into(), ..SitemapUrl::default() },
+            ]
+        );
+        assert_eq!(Sitemap::from_reader(Trickle(URLSET.as_bytes())).unwrap(), sitemap);
+    }
+
+    #[test]
+    fn test_parse_index() {
+        let sitemap = Sitemap::parse(
+            "<sm:sitemapindex xmlns:sm=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\
+             <sm:sitemap><sm:loc>https://example.com/s1.xml.gz</sm:loc>\
+             <sm:lastmod>2024-05-01</sm:lastmod></sm:sitemap>\
+             <sm:sitemap><loc>https://example.com/ignored.xml</loc></sm:sitemap>\
+             </sm:sitemapindex>",
+        )
+        .unwrap();
+        assert!(sitemap.urls.is_empty());
+        assert_eq!(
+            sitemap.sitemaps,
+            [SitemapRef {
+                loc: "https://example.com/s1.xml.gz".into(),
+                lastmod: Some("2024-05-01".into()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_reader_is_lazy() {
+        let mut reader = SitemapReader::new(URLSET.as_bytes());
+        let Some(Ok(SitemapEntry::Url(first))) = reader.next() else { panic!("no entry") };
+        assert_eq!(first.loc, "https://example.com/a?x=1&y=23");
+        assert!(reader.depth > 0);
+        assert_eq!(reader.count(), 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Sitemap::parse("<html><p>hi</p></html>"),
+            Err(SitemapError::NotSitemap("html".into()))
+        );
+        assert!(matches!(Sitemap::parse(""), Err(SitemapError::Syntax { offset: 0, .. })));
+        assert!(matches!(
+            Sitemap::parse("<urlset><url><loc>https://example.com/</loc></url>"),
+            Err(SitemapError::Syntax { offset: 50, .. })
+        ));
+        assert!(matches!(
+            Sitemap::parse("<urlset><url><loc>x</loc"),
+            Err(SitemapError::Syntax { offset: 19, .. })
+        ));
+
+        // Entries before the error are still yielded.
+        let mut reader =
+            SitemapReader::new(&b"<urlset><url><loc>https://example.com/</loc></url><url"[..]);
+        assert!(matches!(reader.next(), Some(Ok(SitemapEntry::Url(_)))));
+        assert!(matches!(reader.next(), Some(Err(SitemapError::Syntax { .. }))));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_change_freq() {
+        assert_eq!(ChangeFreq::parse(" NEVER "), Some(ChangeFreq::Never));
+        assert_eq!(ChangeFreq::parse("sometimes"), None);
+        assert_eq!(ChangeFreq::Hourly.as_str(), "hourly");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(URLSET.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        assert_eq!(Sitemap::from_reader(gzipped.as_slice()), Sitemap::parse(URLSET));
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn test_gzip_needs_feature() {
+        assert_eq!(Sitemap::from_reader(&[0x1f, 0x8b, 8, 0][..]), Err(SitemapError::Gzip));
+    }
+}
@@ -2,14 +2,35 @@
 //!
 //! The [`Soup`] struct is the primary entry point for parsing and querying HTML documents.
 
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
 use crate::{
-    Result, Tag,
-    dom::{Document, NodeId, NodeKind},
-    parser::{Html5everParser, ParseConfig},
+    Error, Result, Selection, Tag, TagMut,
+    archive::{ArchiveReport, AssetFetcher, inline_external_assets},
+    article::{Article, extract_article},
+    canonical::{amp_variant_url, canonical_url, is_amp, mobile_variant_url},
+    convert::MarkdownOptions,
+    dom::{Doctype, Document, NodeId, NodeKind},
+    extract::{
+        assets::{InlineAsset, inline_assets},
+        dates::{DateOrder, DateTime},
+        links::{self, Link, Resource},
+        srcset::ImageConstraints,
+    },
+    forms::{Form, forms},
+    lint::{LintWarning, validate},
+    parser::{Html5everParser, ParseConfig, ParseError, ParseWarning, Parser, XmlParser},
     query::{
-        CompiledSelector, QueryResult, find, find_all, find_all_compiled, find_compiled,
-        select_attr, select_text,
+        CompiledSelector, QueryResult, TextIndex, TextPattern, XPath, find, find_all,
+        find_all_by_text, find_all_compiled, find_by_text, find_compiled, find_iter, find_last,
+        find_nth, select_attr, select_map, select_text,
     },
+    robots::{RobotsDirectives, robots_directives},
+    serialize::{SerializeOptions, TextOptions},
+    variants::merge_text_variants,
 };
 
 /// Configuration options for HTML parsing.
@@ -21,16 +42,33 @@ use crate::{
 ///
 /// let config = SoupConfig::builder().max_depth(256).strict_mode(false).build();
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct SoupConfig {
     /// Maximum nesting depth for DOM tree.
     pub max_depth: usize,
-    /// Enable strict parsing mode (fail on malformed HTML).
+    /// Enable strict parsing mode: [`Soup::parse_checked_with_config`] fails on the first
+    /// parse error instead of recovering from it.
     pub strict_mode: bool,
     /// Whether to preserve whitespace-only text nodes.
+    ///
+    /// Whitespace inside `<pre>`, `<code>`, and `<textarea>` is always preserved.
     pub preserve_whitespace: bool,
     /// Whether to include comment nodes.
     pub include_comments: bool,
+    /// Whether to memoize [`Soup::find_all`] results by selector string.
+    ///
+    /// The cache is cleared whenever the document is borrowed mutably.
+    pub query_cache: bool,
+    /// Whether to attach declarative shadow roots (`<template shadowrootmode>`) to the
+    /// tree, so that queries reach into them.
+    ///
+    /// See [`ParseConfig::pierce_shadow`](crate::ParseConfig::pierce_shadow).
+    pub pierce_shadow: bool,
+    /// Whether to keep repeated attributes on an element after the first.
+    ///
+    /// See [`ParseConfig::preserve_duplicate_attributes`](crate::ParseConfig::preserve_duplicate_attributes).
+    pub preserve_duplicate_attributes: bool,
 }
 
 impl Default for SoupConfig {
@@ -40,6 +78,9 @@ impl Default for SoupConfig {
             strict_mode: false,
             preserve_whitespace: false,
             include_comments: false,
+            query_cache: false,
+            pierce_shadow: false,
+            preserve_duplicate_attributes: false,
         }
     }
 }
@@ -59,6 +100,9 @@ pub struct SoupConfigBuilder {
     strict_mode: Option<bool>,
     preserve_whitespace: Option<bool>,
     include_comments: Option<bool>,
+    query_cache: Option<bool>,
+    pierce_shadow: Option<bool>,
+    preserve_duplicate_attributes: Option<bool>,
 }
 
 impl SoupConfigBuilder {
@@ -90,6 +134,30 @@ impl SoupConfigBuilder {
         self
     }
 
+    /// Enables or disables the per-document query cache.
+    ///
+    /// Worth enabling when the same selectors are queried repeatedly against one
+    /// document, as in interactive sessions or multi-field extraction.
+    #[must_use]
+    pub fn enable_query_cache(mut self, enable: bool) -> Self {
+        self.query_cache = Some(enable);
+        self
+    }
+
+    /// Enables or disables attaching declarative shadow roots to the tree.
+    #[must_use]
+    pub fn pierce_shadow(mut self, pierce: bool) -> Self {
+        self.pierce_shadow = Some(pierce);
+        self
+    }
+
+    /// Enables or disables keeping repeated attributes.
+    #[must_use]
+    pub fn preserve_duplicate_attributes(mut self, preserve: bool) -> Self {
+        self.preserve_duplicate_attributes = Some(preserve);
+        self
+    }
+
     /// Builds the configuration.
     #[must_use]
     pub fn build(self) -> SoupConfig {
@@ -98,6 +166,9 @@ impl SoupConfigBuilder {
             strict_mode: self.strict_mode.unwrap_or(false),
             preserve_whitespace: self.preserve_whitespace.unwrap_or(false),
             include_comments: self.include_comments.unwrap_or(false),
+            query_cache: self.query_cache.unwrap_or(false),
+            pierce_shadow: self.pierce_shadow.unwrap_or(false),
+            preserve_duplicate_attributes: self.preserve_duplicate_attributes.unwrap_or(false),
         }
     }
 }
@@ -143,6 +214,8 @@ pub struct Soup {
     document: Document,
     #[allow(dead_code)]
     config: SoupConfig,
+    query_cache: Option<Mutex<HashMap<String, Vec<NodeId>>>>,
+    text_index: Option<TextIndex>,
 }
 
 impl Soup {
@@ -156,341 +229,1603 @@ impl Soup {
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse("<html><body>Hello</body></html>");
+    /// let soup = Soup::parse("<html><body>Hello</body></html>");
+    /// ```
+    #[must_use]
+    pub fn parse(html: &str) -> Self {
+        Self::parse_with_config(html, SoupConfig::default())
+    }
+
+    /// Parses an HTML string with custom configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, SoupConfig};
+    ///
+    /// let config = SoupConfig::builder().max_depth(128).build();
+    /// let soup = Soup::parse_with_config("<html>...</html>", config);
+    /// ```
+    #[must_use]
+    pub fn parse_with_config(html: &str, config: SoupConfig) -> Self {
+        let parser = Html5everParser;
+        let parse_config = ParseConfig {
+            max_depth: config.max_depth,
+            preserve_whitespace: config.preserve_whitespace,
+            include_comments: config.include_comments,
+            pierce_shadow: config.pierce_shadow,
+            preserve_duplicate_attributes: config.preserve_duplicate_attributes,
+        };
+
+        let estimated_nodes = estimate_node_count(html.len());
+        let document = parser
+            .parse_with_config_and_capacity(html, &parse_config, estimated_nodes)
+            .unwrap_or_default();
+
+        Self::new(document, config)
+    }
+
+    /// Parses an HTML string, storing text nodes as byte ranges of the input instead of
+    /// giving each its own allocation.
+    ///
+    /// The input is copied once into a buffer shared by the text nodes, which keep it
+    /// alive. Text that does not appear verbatim in the input, such as text containing
+    /// character references, is copied as usual, and modified text is copied on its first
+    /// change (see [`TextContent`](crate::TextContent)).
+    ///
+    /// This suits read-only scraping of text-heavy pages. Because any text node keeps the
+    /// whole input alive, prefer [`Soup::parse`] when only a few strings are kept.
+    ///
+    /// # Limitations
+    ///
+    /// Only text nodes borrow from the input. Attribute values and comments are still
+    /// copied, as with [`Soup::parse`], and tag and attribute names are interned
+    /// [`Atom`](crate::Atom)s either way. Pages whose size is mostly in attributes, such as
+    /// long `href`, `srcset`, or `data-*` values, save little.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{NodeKind, Soup};
+    ///
+    /// let soup = Soup::parse_borrowed("<p>Fish &amp; chips</p><p>Tea</p>");
+    /// let paragraphs = soup.select("p").unwrap();
+    /// assert_eq!(paragraphs[0].text(), "Fish & chips");
+    ///
+    /// let doc = soup.document();
+    /// let tea = doc.first_child(paragraphs[1].node_id()).unwrap();
+    /// let Some(NodeKind::Text { content }) = doc.get(tea).map(|node| &node.kind) else {
+    ///     unreachable!();
+    /// };
+    /// assert_eq!(content, "Tea");
+    /// assert_eq!(content.span(), Some(26..29));
+    /// ```
+    #[must_use]
+    pub fn parse_borrowed(html: &str) -> Self {
+        Self::parse_borrowed_with_config(html, SoupConfig::default())
+    }
+
+    /// Parses an HTML string like [`Soup::parse_borrowed`] with custom configuration.
+    #[must_use]
+    pub fn parse_borrowed_with_config(html: &str, config: SoupConfig) -> Self {
+        let parse_config = ParseConfig {
+            max_depth: config.max_depth,
+            preserve_whitespace: config.preserve_whitespace,
+            include_comments: config.include_comments,
+            pierce_shadow: config.pierce_shadow,
+            preserve_duplicate_attributes: config.preserve_duplicate_attributes,
+        };
+
+        let source: Arc<str> = Arc::from(html);
+        let document = Html5everParser
+            .parse_borrowed_with_config_and_capacity(
+                &source,
+                &parse_config,
+                estimate_node_count(html.len()),
+            )
+            .unwrap_or_default();
+
+        Self::new(document, config)
+    }
+
+    /// Parses an HTML string and reports the parse errors the parser recovered from.
+    ///
+    /// Each warning carries the line it was reported on. Use
+    /// [`Soup::parse_checked_with_config`] with `strict_mode` to reject malformed HTML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document exceeds the maximum nesting depth.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let (soup, warnings) = Soup::parse_checked("<!DOCTYPE html>\n<p>Tea</span>").unwrap();
+    /// assert_eq!(soup.find("p").unwrap().unwrap().text(), "Tea");
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].span.unwrap().start.line, 2);
+    /// ```
+    pub fn parse_checked(html: &str) -> Result<(Self, Vec<ParseWarning>)> {
+        Self::parse_checked_with_config(html, SoupConfig::default())
+    }
+
+    /// Parses an HTML string with custom configuration and reports parse errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document exceeds the maximum nesting depth, or, with
+    /// `strict_mode`, if it has any parse error. The message names the first error and
+    /// its line.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, SoupConfig};
+    ///
+    /// let config = SoupConfig::builder().strict_mode(true).build();
+    /// let valid = Soup::parse_checked_with_config("<!DOCTYPE html><p>Tea</p>", config.clone());
+    /// assert!(valid.is_ok());
+    /// let invalid = Soup::parse_checked_with_config("<!DOCTYPE html><p>Tea</span>", config);
+    /// assert!(invalid.unwrap_err().to_string().contains("line 1"));
+    /// ```
+    pub fn parse_checked_with_config(
+        html: &str,
+        config: SoupConfig,
+    ) -> Result<(Self, Vec<ParseWarning>)> {
+        let parse_config = ParseConfig {
+            max_depth: config.max_depth,
+            preserve_whitespace: config.preserve_whitespace,
+            include_comments: config.include_comments,
+            pierce_shadow: config.pierce_shadow,
+            preserve_duplicate_attributes: config.preserve_duplicate_attributes,
+        };
+
+        let parsed = match Html5everParser.parse_with_warnings(html, &parse_config) {
+            Ok(parsed) => parsed,
+            Err(ParseError::EmptyInput) => {
+                return Ok((Self::new(Document::default(), config), Vec::new()));
+            }
+            Err(e) => return Err(Error::parse(e.to_string())),
+        };
+        if config.strict_mode
+            && let Some(warning) = parsed.warnings.first()
+        {
+            return Err(Error::parse(warning.to_string()));
+        }
+        Ok((Self::new(parsed.result, config), parsed.warnings))
+    }
+
+    /// Parses an XML document, such as a feed or sitemap, with [`XmlParser`].
+    ///
+    /// Tag names keep their case, self-closing tags close any element, and CDATA
+    /// sections become text, where the HTML parser would fold `<pubDate>` to lowercase
+    /// and read `<link/>` as a void element. Selectors still match tag names regardless
+    /// of case. Empty input gives an empty document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the XML is malformed, has more than one root element, or
+    /// exceeds the maximum nesting depth.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse_xml(
+    ///     "<rss><channel><item><link>https://example.com/a</link><pubDate>Mon, 01 Jan \
+    ///      2024</pubDate></item></channel></rss>",
+    /// )
+    /// .unwrap();
+    /// let item = soup.find("item").unwrap().unwrap();
+    /// assert_eq!(item.find("link").unwrap().unwrap().text(), "https://example.com/a");
+    /// assert_eq!(item.find("pubDate").unwrap().unwrap().name(), Some("pubDate"));
+    /// ```
+    pub fn parse_xml(xml: &str) -> Result<Self> {
+        Self::parse_xml_with_config(xml, SoupConfig::default())
+    }
+
+    /// Parses an XML document like [`Soup::parse_xml`] with custom configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the XML is malformed, has more than one root element, or
+    /// exceeds the maximum nesting depth.
+    pub fn parse_xml_with_config(xml: &str, config: SoupConfig) -> Result<Self> {
+        let parse_config = ParseConfig {
+            max_depth: config.max_depth,
+            preserve_whitespace: config.preserve_whitespace,
+            include_comments: config.include_comments,
+            pierce_shadow: config.pierce_shadow,
+            preserve_duplicate_attributes: config.preserve_duplicate_attributes,
+        };
+
+        match XmlParser.parse_with_config(xml, &parse_config) {
+            Ok(document) => Ok(Self::new(document, config)),
+            Err(ParseError::EmptyInput) => Ok(Self::new(Document::default(), config)),
+            Err(e) => Err(Error::parse(e.to_string())),
+        }
+    }
+
+    /// Wraps an already-parsed document.
+    ///
+    /// Useful together with [`IncrementalParser`](crate::IncrementalParser) when the
+    /// HTML arrives in chunks rather than as a single string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{IncrementalParser, ParseConfig, Soup, SoupConfig};
+    ///
+    /// let mut parser = IncrementalParser::new(&ParseConfig::default());
+    /// parser.feed(b"<div>Hello</div>");
+    /// let soup = Soup::from_document(parser.finish().unwrap(), SoupConfig::default());
+    /// assert_eq!(soup.find("div").unwrap().unwrap().text(), "Hello");
+    /// ```
+    #[must_use]
+    pub fn from_document(document: Document, config: SoupConfig) -> Self {
+        Self::new(document, config)
+    }
+
+    fn new(document: Document, config: SoupConfig) -> Self {
+        let query_cache = config.query_cache.then(Mutex::default);
+        Self { document, config, query_cache, text_index: None }
+    }
+
+    /// Returns a reference to the underlying document.
+    #[must_use]
+    pub fn document(&self) -> &Document {
+        &self.document
+    }
+
+    /// Returns a mutable reference to the underlying document.
+    ///
+    /// Use the document's tree mutation methods (`insert_before`, `remove_node`,
+    /// `wrap`, ...) for low-level edits, or [`Soup::tag_mut`] for a handle.
+    ///
+    /// Clears the query cache, if enabled, and the text index.
+    #[must_use]
+    pub fn document_mut(&mut self) -> &mut Document {
+        self.invalidate_caches();
+        &mut self.document
+    }
+
+    /// Returns a mutable handle to the node with the given ID.
+    ///
+    /// Returns `None` if the ID does not belong to this document.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<div><span>x</span></div>");
+    /// let span = soup.find("span").unwrap().unwrap().node_id();
+    /// soup.tag_mut(span).unwrap().unwrap();
+    /// assert_eq!(soup.find("div").unwrap().unwrap().inner_html(), "x");
+    /// ```
+    #[must_use]
+    pub fn tag_mut(&mut self, id: NodeId) -> Option<TagMut<'_>> {
+        self.document.get(id)?;
+        self.invalidate_caches();
+        Some(TagMut::new(&mut self.document, id))
+    }
+
+    /// Parses an HTML fragment and appends it to the end of the document's `<body>`, or of
+    /// the root element if there is no body.
+    ///
+    /// Only the fragment is parsed, in the context of the element it is appended to, and
+    /// its nodes are spliced into the existing document; use this to grow a document
+    /// page by page instead of concatenating HTML and parsing it again. Returns the IDs
+    /// of the appended top-level nodes, which is empty if `html` is blank.
+    ///
+    /// Clears the query cache, if enabled, and the text index.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<div class=\"item\">1</div>");
+    /// soup.append_html("<div class=\"item\">2</div><div class=\"item\">3</div>");
+    /// assert_eq!(soup.select_text(".item").unwrap(), ["1", "2", "3"]);
+    /// ```
+    pub fn append_html(&mut self, html: &str) -> Vec<NodeId> {
+        let Some(root) = self.document.root() else { return Vec::new() };
+        let target = std::iter::once(root)
+            .chain(self.document.descendants(root))
+            .find(|&id| self.document.get(id).and_then(|node| node.kind.tag_name()) == Some("body"))
+            .unwrap_or(root);
+        let parse_config = ParseConfig {
+            max_depth: self.config.max_depth,
+            preserve_whitespace: self.config.preserve_whitespace,
+            include_comments: self.config.include_comments,
+            pierce_shadow: self.config.pierce_shadow,
+            preserve_duplicate_attributes: self.config.preserve_duplicate_attributes,
+        };
+        self.invalidate_caches();
+        crate::parser::fragment::append_html(&mut self.document, target, html, &parse_config)
+    }
+
+    /// Returns the number of selectors held in the query cache.
+    ///
+    /// Always `0` unless [`SoupConfig::query_cache`] is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, SoupConfig};
+    ///
+    /// let config = SoupConfig::builder().enable_query_cache(true).build();
+    /// let mut soup = Soup::parse_with_config("<ul><li>A</li><li>B</li></ul>", config);
+    /// soup.find_all("li").unwrap();
+    /// soup.find_all("li").unwrap();
+    /// assert_eq!(soup.query_cache_len(), 1);
+    ///
+    /// soup.document_mut();
+    /// assert_eq!(soup.query_cache_len(), 0);
+    /// ```
+    #[must_use]
+    pub fn query_cache_len(&self) -> usize {
+        self.query_cache.as_ref().map_or(0, |cache| lock_cache(cache).len())
+    }
+
+    fn invalidate_caches(&mut self) {
+        if let Some(cache) = &mut self.query_cache {
+            cache.get_mut().unwrap_or_else(std::sync::PoisonError::into_inner).clear();
+        }
+        self.text_index = None;
+    }
+
+    /// Parses HTML from a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::path::Path;
+    ///
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::from_file(Path::new("index.html")).unwrap();
+    /// ```
+    pub fn from_file(path: &std::path::Path) -> Result<Self> {
+        let html = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&html))
+    }
+
+    /// Downloads and parses the HTML document at `url`.
+    ///
+    /// The body is decoded with the `charset` of the `Content-Type` header, or detected
+    /// as in [`Soup::parse_bytes`]; see [`fetch`](crate::fetch) for the request itself.
+    ///
+    /// Requires the `fetch` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Fetch`](crate::Error::Fetch) if the request fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use scrape_core::{Soup, fetch::FetchOptions};
+    ///
+    /// let soup = Soup::from_url("https://example.com/", &FetchOptions::default()).unwrap();
+    /// ```
+    #[cfg(feature = "fetch")]
+    pub fn from_url(url: &str, options: &crate::fetch::FetchOptions) -> Result<Self> {
+        let response = crate::fetch::fetch(url, options)?;
+        Ok(Self::parse(&response.text()))
+    }
+
+    /// Parses an HTML document from bytes in an unknown encoding.
+    ///
+    /// The encoding is detected from a byte order mark or a `<meta>` declaration, falling
+    /// back to UTF-8 for valid UTF-8 and windows-1252 otherwise; see
+    /// [`encoding`](crate::encoding). UTF-8 input is parsed without copying.
+    ///
+    /// Requires the `encoding` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse_bytes(b"<meta charset=\"latin1\"><p>Caf\xe9</p>");
+    /// assert_eq!(soup.find("p").unwrap().unwrap().text(), "Café");
+    /// ```
+    #[cfg(feature = "encoding")]
+    #[must_use]
+    pub fn parse_bytes(bytes: &[u8]) -> Self {
+        Self::parse(&crate::encoding::decode(bytes).html)
+    }
+
+    /// Parses an HTML document from bytes with custom configuration.
+    ///
+    /// `encoding` is an encoding label, such as the charset of a `Content-Type` header,
+    /// that overrides `<meta>` declarations; with `None` the encoding is detected as in
+    /// [`Soup::parse_bytes`].
+    ///
+    /// Requires the `encoding` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownEncoding`](crate::Error::UnknownEncoding) if `encoding` is
+    /// not a known encoding label.
+    #[cfg(feature = "encoding")]
+    pub fn parse_bytes_with_config(
+        bytes: &[u8],
+        encoding: Option<&str>,
+        config: SoupConfig,
+    ) -> Result<Self> {
+        let decoded = match encoding {
+            Some(label) => crate::encoding::decode_with_label(bytes, label)?,
+            None => crate::encoding::decode(bytes),
+        };
+        Ok(Self::parse_with_config(&decoded.html, config))
+    }
+
+    /// Parses an HTML fragment without wrapping in html/body tags.
+    ///
+    /// Unlike [`Soup::parse`], this does not wrap content in `<html><body>` structure.
+    /// The fragment is parsed as if it appeared inside a `<body>` element.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse_fragment("<span>A</span><span>B</span>");
+    /// let spans = soup.find_all("span").unwrap();
+    /// assert_eq!(spans.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn parse_fragment(html: &str) -> Self {
+        Self::parse_fragment_with_context(html, "body")
+    }
+
+    /// Parses an HTML fragment with a custom context element.
+    ///
+    /// The context element determines parsing behavior:
+    /// - `"body"`: Standard HTML elements (default)
+    /// - `"table"`: Allows tr/td without explicit tbody
+    /// - `"tbody"`: Allows tr directly
+    ///
+    /// The context name is case-insensitive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse_fragment_with_context("<tr><td>A</td></tr>", "tbody");
+    /// let tr = soup.find("tr").unwrap();
+    /// assert!(tr.is_some());
+    /// ```
+    #[must_use]
+    pub fn parse_fragment_with_context(html: &str, context: &str) -> Self {
+        Self::parse_fragment_with_config(html, context, SoupConfig::default())
+    }
+
+    /// Parses an HTML fragment with custom context and configuration.
+    #[must_use]
+    pub fn parse_fragment_with_config(html: &str, context: &str, config: SoupConfig) -> Self {
+        let parse_config = ParseConfig {
+            max_depth: config.max_depth,
+            preserve_whitespace: config.preserve_whitespace,
+            include_comments: config.include_comments,
+            pierce_shadow: config.pierce_shadow,
+            preserve_duplicate_attributes: config.preserve_duplicate_attributes,
+        };
+
+        let document = crate::parser::fragment::parse_fragment_impl(html, context, &parse_config)
+            .unwrap_or_default();
+
+        Self::new(document, config)
+    }
+
+    /// Merges several fetches of the same page into one document of their stable content.
+    ///
+    /// Text that is missing from any variant, such as A/B test copy or rotating
+    /// promotions, is dropped from a copy of the first variant; see
+    /// [`merge_text_variants`](crate::variants::merge_text_variants).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let a = Soup::parse("<p>Price: 4 EUR</p><p>Hello, Ann</p>");
+    /// let b = Soup::parse("<p>Price: 4 EUR</p><p>Hello, Bo</p>");
+    /// let stable = Soup::merge_text_variants(&[a, b]);
+    /// assert_eq!(stable.select_text("p").unwrap(), ["Price: 4 EUR"]);
+    /// ```
+    #[must_use]
+    pub fn merge_text_variants(variants: &[Self]) -> Self {
+        merge_text_variants(variants)
+    }
+
+    // ==================== Query Methods ====================
+
+    /// Finds the first element matching the given CSS selector.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<div><span class=\"item\">Hello</span></div>");
+    /// let span = soup.find("span.item").unwrap().unwrap();
+    /// assert_eq!(span.text(), "Hello");
+    /// ```
+    pub fn find(&self, selector: &str) -> QueryResult<Option<Tag<'_>>> {
+        find(&self.document, selector).map(|opt| opt.map(|id| Tag::new(&self.document, id)))
+    }
+
+    /// Finds all elements matching the given CSS selector.
+    ///
+    /// With [`SoupConfig::query_cache`] enabled, results are memoized per selector string
+    /// until the document is next borrowed mutably.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li>A</li><li>B</li></ul>");
+    /// let items = soup.find_all("li").unwrap();
+    /// assert_eq!(items.len(), 2);
+    /// ```
+    pub fn find_all(&self, selector: &str) -> QueryResult<Vec<Tag<'_>>> {
+        let ids = match &self.query_cache {
+            Some(cache) => {
+                if let Some(ids) = lock_cache(cache).get(selector) {
+                    return Ok(self.tags(ids));
+                }
+                let ids = find_all(&self.document, selector)?;
+                lock_cache(cache).insert(selector.to_string(), ids.clone());
+                ids
+            }
+            None => find_all(&self.document, selector)?,
+        };
+        Ok(self.tags(&ids))
+    }
+
+    /// Returns a lazy iterator over the elements matching the given CSS selector, in
+    /// document order.
+    ///
+    /// Elements are matched as the tree is walked, so `next()`, `take(n)`, and other
+    /// early exits skip the rest of the document. Bypasses the query cache; use
+    /// [`Soup::find_all`] when every match is needed anyway.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li>A</li><li>B</li><li>C</li></ul>");
+    /// let first_two: Vec<_> = soup.iter("li").unwrap().take(2).map(|li| li.text()).collect();
+    /// assert_eq!(first_two, ["A", "B"]);
+    /// ```
+    #[allow(clippy::iter_not_returning_iterator)]
+    pub fn iter(&self, selector: &str) -> QueryResult<impl Iterator<Item = Tag<'_>>> {
+        let doc = &self.document;
+        Ok(find_iter(doc, selector)?.map(move |id| Tag::new(doc, id)))
+    }
+
+    /// Finds the `n`th element (counting from zero) matching the given CSS selector.
+    ///
+    /// Stops at the `n`th match instead of collecting every match as
+    /// `find_all(selector)?.get(n)` would; see [`find_nth`](crate::query::find_nth).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<table><tr><td>1</td></tr><tr><td>2</td></tr></table>");
+    /// assert_eq!(soup.find_nth("tr", 1).unwrap().unwrap().text(), "2");
+    /// assert!(soup.find_nth("tr", 2).unwrap().is_none());
+    /// ```
+    pub fn find_nth(&self, selector: &str, n: usize) -> QueryResult<Option<Tag<'_>>> {
+        find_nth(&self.document, selector, n).map(|opt| opt.map(|id| Tag::new(&self.document, id)))
+    }
+
+    /// Finds the last element matching the given CSS selector.
+    ///
+    /// The document is searched backwards from its end; see
+    /// [`find_last`](crate::query::find_last).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li>A</li><li>B</li><li>C</li></ul>");
+    /// assert_eq!(soup.find_last("li").unwrap().unwrap().text(), "C");
+    /// ```
+    pub fn find_last(&self, selector: &str) -> QueryResult<Option<Tag<'_>>> {
+        find_last(&self.document, selector).map(|opt| opt.map(|id| Tag::new(&self.document, id)))
+    }
+
+    /// Finds all elements matching the given CSS selector, matching on several threads.
+    ///
+    /// Returns the same elements as [`Soup::find_all`], in document order; see
+    /// [`find_all_parallel`](crate::parallel::find_all_parallel). Bypasses the query cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li>A</li><li><b>B</b></li></ul>");
+    /// let items = soup.find_all_parallel("li:has(b)").unwrap();
+    /// assert_eq!(items[0].text(), "B");
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn find_all_parallel(&self, selector: &str) -> QueryResult<Vec<Tag<'_>>> {
+        let ids = crate::parallel::find_all_parallel(&self.document, selector)?;
+        Ok(self.tags(&ids))
+    }
+
+    fn tags(&self, ids: &[NodeId]) -> Vec<Tag<'_>> {
+        ids.iter().map(|&id| Tag::new(&self.document, id)).collect()
+    }
+
+    /// Selects elements using a CSS selector.
+    ///
+    /// This is an alias for [`Soup::find_all`] for users familiar with
+    /// the CSS selector API.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<div class=\"a\"><span class=\"b\">Text</span></div>");
+    /// let results = soup.select("div.a > span.b").unwrap();
+    /// assert_eq!(results.len(), 1);
+    /// ```
+    pub fn select(&self, selector: &str) -> QueryResult<Vec<Tag<'_>>> {
+        self.find_all(selector)
+    }
+
+    /// Selects elements using a CSS selector, as a [`Selection`] that can be refined
+    /// further.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<p class=\"a\">1</p><p>2</p><p class=\"a\">3</p>");
+    /// let selection = soup.selection("p").unwrap().filter(".a").unwrap();
+    /// assert_eq!(selection.texts(), vec!["1", "3"]);
+    /// ```
+    pub fn selection(&self, selector: &str) -> QueryResult<Selection<'_>> {
+        let tags = self.find_all(selector)?;
+        Ok(Selection::from_ordered(&self.document, tags.iter().map(Tag::node_id).collect()))
+    }
+
+    /// Finds the first element using a pre-compiled selector.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, query::CompiledSelector};
+    ///
+    /// let selector = CompiledSelector::compile("div.item").unwrap();
+    /// let soup = Soup::parse("<div class=\"item\">Text</div>");
+    /// let result = soup.find_compiled(&selector);
+    /// assert!(result.is_some());
+    /// ```
+    #[must_use]
+    pub fn find_compiled(&self, selector: &CompiledSelector) -> Option<Tag<'_>> {
+        find_compiled(&self.document, selector).map(|id| Tag::new(&self.document, id))
+    }
+
+    /// Finds all elements using a pre-compiled selector.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, query::CompiledSelector};
+    ///
+    /// let selector = CompiledSelector::compile("li").unwrap();
+    /// let soup = Soup::parse("<ul><li>A</li><li>B</li></ul>");
+    /// let items = soup.select_compiled(&selector);
+    /// assert_eq!(items.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn select_compiled(&self, selector: &CompiledSelector) -> Vec<Tag<'_>> {
+        find_all_compiled(&self.document, selector)
+            .into_iter()
+            .map(|id| Tag::new(&self.document, id))
+            .collect()
+    }
+
+    /// Extracts text content from all elements matching a CSS selector.
+    ///
+    /// Returns the concatenated text content of each matching element.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li>First</li><li>Second</li></ul>");
+    /// let texts = soup.select_text("li").unwrap();
+    /// assert_eq!(texts, vec!["First", "Second"]);
+    /// ```
+    pub fn select_text(&self, selector: &str) -> QueryResult<Vec<String>> {
+        select_text(&self.document, selector)
+    }
+
+    /// Extracts attribute values from all elements matching a CSS selector.
+    ///
+    /// Returns `Some(value)` if the attribute exists, `None` if it doesn't.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<a href='/a'>A</a><a>B</a>");
+    /// let hrefs = soup.select_attr("a", "href").unwrap();
+    /// assert_eq!(hrefs, vec![Some("/a".to_string()), None]);
+    /// ```
+    pub fn select_attr(&self, selector: &str, attr: &str) -> QueryResult<Vec<Option<String>>> {
+        select_attr(&self.document, selector, attr)
+    }
+
+    /// Applies `f` to every element matching a CSS selector, in document order.
+    ///
+    /// Converting matches in the same pass that finds them avoids collecting
+    /// [`Tag`]s or strings first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<a href='/a'>A</a><a>B</a>");
+    /// let links = soup.select_map("a", |a| (a.text(), a.has_attr("href"))).unwrap();
+    /// assert_eq!(links, vec![("A".to_string(), true), ("B".to_string(), false)]);
+    /// ```
+    pub fn select_map<'a, T>(
+        &'a self,
+        selector: &str,
+        f: impl FnMut(Tag<'a>) -> T,
+    ) -> QueryResult<Vec<T>> {
+        select_map(&self.document, selector, f)
+    }
+
+    /// Parses a number from every element matching a CSS selector.
+    ///
+    /// Each match is parsed like [`Tag::parse_number`], from its `content` attribute or
+    /// its text, with the separator conventions of
+    /// [`parse_number`](crate::extract::numbers::parse_number). `None` marks a match
+    /// without a number.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<b>1,299.00 $</b><b>12,5 kg</b><b>n/a</b>");
+    /// assert_eq!(soup.select_numbers("b").unwrap(), vec![Some(1299.0), Some(12.5), None]);
+    /// ```
+    pub fn select_numbers(&self, selector: &str) -> QueryResult<Vec<Option<f64>>> {
+        self.select_map(selector, |tag| tag.parse_number())
+    }
+
+    /// Parses a date from every element matching a CSS selector.
+    ///
+    /// Each match is parsed like [`Tag::parse_date`], from its `datetime` or `content`
+    /// attribute or its text. `order` says how to read ambiguous numeric dates such as
+    /// `03/05/2024`. `None` marks a match without a date.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, extract::DateOrder};
+    ///
+    /// let soup = Soup::parse("<time datetime='2024-03-05'>Tuesday</time><span>31/12/2024</span>");
+    /// let dates = soup.select_dates("time, span", DateOrder::DayFirst).unwrap();
+    /// let dates: Vec<_> = dates.iter().flatten().map(ToString::to_string).collect();
+    /// assert_eq!(dates, vec!["2024-03-05", "2024-12-31"]);
+    /// ```
+    pub fn select_dates(
+        &self,
+        selector: &str,
+        order: DateOrder,
+    ) -> QueryResult<Vec<Option<DateTime>>> {
+        self.select_map(selector, |tag| tag.parse_date(order))
+    }
+
+    /// Resolves the URL of every element matching a CSS selector.
+    ///
+    /// Each match contributes its `href`, `src`, or `content` attribute, whichever comes
+    /// first, resolved against `<base href>` and `base_url` like [`Soup::links`]. `None`
+    /// marks a match without a reference, or with a relative one and no absolute base.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<a href='/tea'>Tea</a><img src='cup.png'><a>None</a>");
+    /// let urls = soup.select_urls("a, img", Some("https://example.com/shop/")).unwrap();
+    /// assert_eq!(
+    ///     urls,
+    ///     vec![
+    ///         Some("https://example.com/tea".to_string()),
+    ///         Some("https://example.com/shop/cup.png".to_string()),
+    ///         None,
+    ///     ]
+    /// );
+    /// ```
+    pub fn select_urls(
+        &self,
+        selector: &str,
+        base_url: Option<&str>,
+    ) -> QueryResult<Vec<Option<String>>> {
+        let base = links::base_url(self, base_url);
+        self.select_map(selector, |tag| links::reference_url(&tag, base.as_deref()))
+    }
+
+    /// Finds the first innermost element whose text matches `pattern`.
+    ///
+    /// `pattern` is a substring or, with the `regex` feature, a `regex::Regex`; see
+    /// [`TextPattern`]. An element is skipped when one of its children already matches, so
+    /// this returns the label itself rather than the row or table around it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse(
+    ///     "<table><tr><th>Name</th><td>Tea</td></tr><tr><th>Price</th><td>4.50</td></tr></table>",
+    /// );
+    /// let th = soup.find_by_text("Price").unwrap();
+    /// assert_eq!(th.next_sibling().unwrap().text(), "4.50");
+    /// ```
+    #[must_use]
+    pub fn find_by_text(&self, pattern: impl TextPattern) -> Option<Tag<'_>> {
+        find_by_text(&self.document, pattern).map(|id| Tag::new(&self.document, id))
+    }
+
+    /// Finds all innermost elements whose text matches `pattern`, in document order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<p>Order <b>#1042</b> shipped</p><p>Order #1043 pending</p>");
+    /// let orders = soup.find_all_by_text('#');
+    /// assert_eq!(orders[0].text(), "#1042");
+    /// assert_eq!(orders[1].text(), "Order #1043 pending");
+    /// ```
+    #[must_use]
+    pub fn find_all_by_text(&self, pattern: impl TextPattern) -> Vec<Tag<'_>> {
+        find_all_by_text(&self.document, pattern)
+            .into_iter()
+            .map(|id| Tag::new(&self.document, id))
+            .collect()
+    }
+
+    /// Indexes the document's text so that [`Soup::search_text`] answers without scanning
+    /// every element.
+    ///
+    /// Worth it when many searches run on the same document; see [`TextIndex`]. The index
+    /// is dropped when the document is modified through this `Soup`.
+    pub fn build_text_index(&mut self) {
+        self.text_index = Some(TextIndex::build(&self.document));
+    }
+
+    /// Returns whether [`Soup::build_text_index`] has been called since the document was
+    /// last modified.
+    #[must_use]
+    pub fn has_text_index(&self) -> bool {
+        self.text_index.is_some()
+    }
+
+    /// Finds all innermost elements whose text contains `needle`, in document order.
+    ///
+    /// Returns the same elements as [`Soup::find_all_by_text`], using the text index when
+    /// [`Soup::build_text_index`] has built one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<p>Order <b>#1042</b> shipped</p><p>Order #1043 pending</p>");
+    /// soup.build_text_index();
+    /// let orders = soup.search_text("#10");
+    /// assert_eq!(orders[0].text(), "#1042");
+    /// assert_eq!(orders[1].text(), "Order #1043 pending");
+    /// assert_eq!(soup.search_text("shipped").len(), 1);
+    /// ```
+    #[must_use]
+    pub fn search_text(&self, needle: &str) -> Vec<Tag<'_>> {
+        let ids = self.text_index.as_ref().map_or_else(
+            || find_all_by_text(&self.document, needle),
+            |index| index.search(&self.document, needle),
+        );
+        self.tags(&ids)
+    }
+
+    /// Selects elements using an XPath 1.0 expression.
+    ///
+    /// Only element nodes are returned; use [`Soup::xpath_text`] for text and attribute
+    /// values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidXPath`](crate::QueryError::InvalidXPath) if the expression
+    /// is invalid or does not return a node-set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li>A</li><li class=\"x\">B</li></ul>");
+    /// let items = soup.xpath("//li[@class='x']/preceding-sibling::li").unwrap();
+    /// assert_eq!(items[0].text(), "A");
+    /// ```
+    pub fn xpath(&self, expr: &str) -> QueryResult<Vec<Tag<'_>>> {
+        XPath::compile(expr)?
+            .select(&self.document, None)
+            .map(|ids| ids.into_iter().map(|id| Tag::new(&self.document, id)).collect())
+    }
+
+    /// Evaluates an XPath 1.0 expression and returns string values.
+    ///
+    /// Node-sets yield one string per node; scalar results yield a single string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidXPath`](crate::QueryError::InvalidXPath) if the expression
+    /// is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<a href='/1'>One</a><a href='/2'>Two</a>");
+    /// assert_eq!(soup.xpath_text("//a/@href").unwrap(), vec!["/1", "/2"]);
+    /// assert_eq!(soup.xpath_text("count(//a)").unwrap(), vec!["2"]);
+    /// ```
+    pub fn xpath_text(&self, expr: &str) -> QueryResult<Vec<String>> {
+        XPath::compile(expr)?.select_text(&self.document, None)
+    }
+
+    // ==================== Document Methods ====================
+
+    /// Returns the root element of the document.
+    ///
+    /// This is typically the `<html>` element.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<html><body>text</body></html>");
+    /// if let Some(root) = soup.root() {
+    ///     assert_eq!(root.name(), Some("html"));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn root(&self) -> Option<Tag<'_>> {
+        self.document.root().map(|id| Tag::new(&self.document, id))
+    }
+
+    /// Returns the document's title, if present.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<html><head><title>My Page</title></head></html>");
+    /// assert_eq!(soup.title(), Some("My Page".to_string()));
+    /// ```
+    #[must_use]
+    pub fn title(&self) -> Option<String> {
+        self.find("title").ok()?.map(|tag| tag.text())
+    }
+
+    /// Returns the document's doctype, if it has one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<!DOCTYPE html><p>Hi</p>");
+    /// assert_eq!(soup.doctype().map(|doctype| doctype.name), Some("html"));
+    /// assert!(Soup::parse("<p>Hi</p>").doctype().is_none());
+    /// ```
+    #[must_use]
+    pub fn doctype(&self) -> Option<Doctype<'_>> {
+        let doc = &self.document;
+        doc.top_level().find_map(|id| doc.get(id)?.kind.as_doctype())
+    }
+
+    /// Returns the content of every comment in the document, in document order.
+    ///
+    /// Comments are only kept when parsing with
+    /// [`include_comments`](SoupConfig::include_comments). Comments before and after
+    /// `<html>` are included; those inside `<template>` contents are not.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, SoupConfig};
+    ///
+    /// let config = SoupConfig::builder().include_comments(true).build();
+    /// let soup = Soup::parse_with_config(
+    ///     "<!-- build: 4f2a --><!DOCTYPE html><p>Hi<!-- note --></p>",
+    ///     config,
+    /// );
+    /// let comments: Vec<_> = soup.comments().collect();
+    /// assert_eq!(comments, [" build: 4f2a ", " note "]);
+    /// ```
+    pub fn comments(&self) -> impl Iterator<Item = &str> {
+        let doc = &self.document;
+        doc.top_level()
+            .flat_map(move |id| std::iter::once(id).chain(doc.descendants(id)))
+            .filter_map(move |id| doc.get(id)?.kind.as_comment())
+    }
+
+    /// Returns the document's text content with tags stripped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<div>Hello <b>World</b></div>");
+    /// let text = soup.text();
+    /// assert!(text.contains("Hello"));
+    /// assert!(text.contains("World"));
+    /// ```
+    #[must_use]
+    pub fn text(&self) -> String {
+        let Some(root) = self.document.root() else {
+            return String::new();
+        };
+        let mut result = String::new();
+        collect_text(&self.document, root, &mut result);
+        result
+    }
+
+    /// Returns the document's text that a browser would show.
+    ///
+    /// See [`Tag::visible_text`] for what is left out.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<p>Hello</p><script>var x = 1;</script><p hidden>draft</p>");
+    /// assert_eq!(soup.visible_text(), "Hello");
+    /// ```
+    #[must_use]
+    pub fn visible_text(&self) -> String {
+        self.root().map(|tag| tag.visible_text()).unwrap_or_default()
+    }
+
+    /// Returns the document's text content laid out by `options`.
+    ///
+    /// See [`Tag::text_with`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, TextOptions};
+    ///
+    /// let soup = Soup::parse("<p>Hello</p><p>World</p>");
+    /// assert_eq!(soup.text_with(&TextOptions::inner_text()), "Hello\nWorld");
+    /// ```
+    #[must_use]
+    pub fn text_with(&self, options: &TextOptions) -> String {
+        self.root().map(|tag| tag.text_with(options)).unwrap_or_default()
+    }
+
+    /// Returns the document as an HTML string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<div><span>text</span></div>");
+    /// let html = soup.to_html();
+    /// assert!(html.contains("<div>"));
+    /// assert!(html.contains("<span>"));
+    /// ```
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        self.root().map(|tag| tag.outer_html()).unwrap_or_default()
+    }
+
+    /// Returns the document as an HTML string formatted by `options`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{SerializeOptions, Soup};
+    ///
+    /// let soup = Soup::parse("<p>Hello</p>");
+    /// let options = SerializeOptions { minify: true, ..SerializeOptions::default() };
+    /// assert_eq!(soup.to_html_with(&options), "<html><head></head><body><p>Hello</p></body></html>");
+    /// ```
+    #[must_use]
+    pub fn to_html_with(&self, options: &SerializeOptions) -> String {
+        self.root().map(|tag| tag.outer_html_with(options)).unwrap_or_default()
+    }
+
+    /// Returns the document as indented HTML, one block-level element per line.
+    ///
+    /// This is [`to_html_with`](Self::to_html_with) with
+    /// [`pretty`](SerializeOptions::pretty) set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<div><p>Hello</p></div>");
+    /// assert!(soup.to_html_pretty().contains("\n    <div>\n      <p>Hello</p>\n    </div>\n"));
     /// ```
     #[must_use]
-    pub fn parse(html: &str) -> Self {
-        Self::parse_with_config(html, SoupConfig::default())
+    pub fn to_html_pretty(&self) -> String {
+        self.to_html_with(&SerializeOptions { pretty: true, ..SerializeOptions::default() })
     }
 
-    /// Parses an HTML string with custom configuration.
+    /// Returns the document as Markdown.
+    ///
+    /// The `<head>` is left out. See [`convert`](crate::convert) for the supported
+    /// elements.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use scrape_core::{Soup, SoupConfig};
+    /// use scrape_core::Soup;
     ///
-    /// let config = SoupConfig::builder().max_depth(128).build();
-    /// let soup = Soup::parse_with_config("<html>...</html>", config);
+    /// let soup = Soup::parse("<title>Tea</title><h1>Tea</h1><p>Brew it <em>hot</em>.</p>");
+    /// assert_eq!(soup.to_markdown(), "# Tea\n\nBrew it *hot*.");
     /// ```
     #[must_use]
-    pub fn parse_with_config(html: &str, config: SoupConfig) -> Self {
-        let parser = Html5everParser;
-        let parse_config = ParseConfig {
-            max_depth: config.max_depth,
-            preserve_whitespace: config.preserve_whitespace,
-            include_comments: config.include_comments,
-        };
-
-        let estimated_nodes = estimate_node_count(html.len());
-        let document = parser
-            .parse_with_config_and_capacity(html, &parse_config, estimated_nodes)
-            .unwrap_or_default();
-
-        Self { document, config }
-    }
-
-    /// Returns a reference to the underlying document.
-    #[must_use]
-    pub fn document(&self) -> &Document {
-        &self.document
+    pub fn to_markdown(&self) -> String {
+        self.root().map(|tag| tag.to_markdown()).unwrap_or_default()
     }
 
-    /// Parses HTML from a file.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if the file cannot be read.
+    /// Returns the document as Markdown with custom link and image handling.
     ///
     /// # Examples
     ///
-    /// ```rust,no_run
-    /// use std::path::Path;
-    ///
-    /// use scrape_core::Soup;
-    ///
-    /// let soup = Soup::from_file(Path::new("index.html")).unwrap();
+    /// ```rust
+    /// use scrape_core::{
+    ///     Soup,
+    ///     convert::{LinkStyle, MarkdownOptions},
+    /// };
+    ///
+    /// let soup = Soup::parse("<p>See <a href=\"/faq\">the FAQ</a>.</p>");
+    /// let options = MarkdownOptions { links: LinkStyle::Text, ..MarkdownOptions::default() };
+    /// assert_eq!(soup.to_markdown_with_options(&options), "See the FAQ.");
     /// ```
-    pub fn from_file(path: &std::path::Path) -> Result<Self> {
-        let html = std::fs::read_to_string(path)?;
-        Ok(Self::parse(&html))
+    #[must_use]
+    pub fn to_markdown_with_options(&self, options: &MarkdownOptions) -> String {
+        self.root().map(|tag| tag.to_markdown_with_options(options)).unwrap_or_default()
     }
 
-    /// Parses an HTML fragment without wrapping in html/body tags.
+    /// Returns the document's `<a href>` and `<area href>` links in document order.
     ///
-    /// Unlike [`Soup::parse`], this does not wrap content in `<html><body>` structure.
-    /// The fragment is parsed as if it appeared inside a `<body>` element.
+    /// Each link carries its absolute URL, resolved against `<base href>` and `base_url`,
+    /// its `rel` tokens, and its text. See [`extract::links`](mod@crate::extract::links)
+    /// for the resolution rules.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse_fragment("<span>A</span><span>B</span>");
-    /// let spans = soup.find_all("span").unwrap();
-    /// assert_eq!(spans.len(), 2);
+    /// let soup = Soup::parse("<a href=\"/about\" rel=\"nofollow\">About</a>");
+    /// let links = soup.links(Some("https://example.com/blog/"));
+    /// assert_eq!(links[0].url.as_deref(), Some("https://example.com/about"));
+    /// assert_eq!(links[0].rel, vec!["nofollow"]);
     /// ```
     #[must_use]
-    pub fn parse_fragment(html: &str) -> Self {
-        Self::parse_fragment_with_context(html, "body")
+    pub fn links(&self, base_url: Option<&str>) -> Vec<Link> {
+        links::links(self, base_url)
     }
 
-    /// Parses an HTML fragment with a custom context element.
+    /// Returns the document's `<img src>` references with absolute URLs.
     ///
-    /// The context element determines parsing behavior:
-    /// - `"body"`: Standard HTML elements (default)
-    /// - `"table"`: Allows tr/td without explicit tbody
-    /// - `"tbody"`: Allows tr directly
+    /// URLs are resolved like [`Soup::links`].
+    #[must_use]
+    pub fn images(&self, base_url: Option<&str>) -> Vec<Resource> {
+        links::images(self, base_url)
+    }
+
+    /// Returns the image each `<img>` shows under `constraints`, with absolute URLs.
+    ///
+    /// Unlike [`Soup::images`], this picks among the `srcset` candidates of each `<img>`
+    /// and the `<source>`s of its `<picture>` the way a browser would; see
+    /// [`extract::srcset`](mod@crate::extract::srcset). URLs are resolved like
+    /// [`Soup::links`].
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use scrape_core::Soup;
-    ///
-    /// let soup = Soup::parse_fragment_with_context("<tr><td>A</td></tr>", "tbody");
-    /// let tr = soup.find("tr").unwrap();
-    /// assert!(tr.is_some());
+    /// use scrape_core::{Soup, extract::ImageConstraints};
+    ///
+    /// let soup = Soup::parse(
+    ///     r#"<picture>
+    ///          <source media="(min-width: 800px)" srcset="wide.jpg, wide@2x.jpg 2x">
+    ///          <img src="narrow.jpg">
+    ///        </picture>"#,
+    /// );
+    /// let laptop = ImageConstraints { viewport_width: Some(1280), ..ImageConstraints::default() };
+    /// let images = soup.images_with(Some("https://example.com/"), &laptop);
+    /// assert_eq!(images[0].url.as_deref(), Some("https://example.com/wide.jpg"));
+    ///
+    /// let phone = ImageConstraints { viewport_width: Some(390), density: 3.0, types: vec![] };
+    /// assert_eq!(soup.images_with(None, &phone)[0].reference, "narrow.jpg");
     /// ```
     #[must_use]
-    pub fn parse_fragment_with_context(html: &str, context: &str) -> Self {
-        Self::parse_fragment_with_config(html, context, SoupConfig::default())
+    pub fn images_with(
+        &self,
+        base_url: Option<&str>,
+        constraints: &ImageConstraints,
+    ) -> Vec<Resource> {
+        links::images_with(self, base_url, constraints)
     }
 
-    /// Parses an HTML fragment with custom context and configuration.
+    /// Returns the document's external `<script src>` references with absolute URLs.
+    ///
+    /// URLs are resolved like [`Soup::links`].
     #[must_use]
-    pub fn parse_fragment_with_config(html: &str, context: &str, config: SoupConfig) -> Self {
-        let parse_config = ParseConfig {
-            max_depth: config.max_depth,
-            preserve_whitespace: config.preserve_whitespace,
-            include_comments: config.include_comments,
-        };
-
-        let document = crate::parser::fragment::parse_fragment_impl(html, context, &parse_config)
-            .unwrap_or_default();
-
-        Self { document, config }
+    pub fn scripts(&self, base_url: Option<&str>) -> Vec<Resource> {
+        links::scripts(self, base_url)
     }
 
-    // ==================== Query Methods ====================
-
-    /// Finds the first element matching the given CSS selector.
+    /// Returns the document's `<link rel="stylesheet">` references with absolute URLs.
     ///
-    /// # Errors
+    /// URLs are resolved like [`Soup::links`].
+    #[must_use]
+    pub fn stylesheets(&self, base_url: Option<&str>) -> Vec<Resource> {
+        links::stylesheets(self, base_url)
+    }
+
+    /// Returns the absolute URL of the document's first `<link rel="canonical">`.
     ///
-    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
-    /// syntax is invalid.
+    /// The `href` is resolved like [`Soup::links`]. Group pages by this URL with
+    /// [`CanonicalClusters`](crate::canonical::CanonicalClusters).
     ///
     /// # Examples
     ///
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse("<div><span class=\"item\">Hello</span></div>");
-    /// let span = soup.find("span.item").unwrap().unwrap();
-    /// assert_eq!(span.text(), "Hello");
+    /// let soup = Soup::parse("<link rel=\"canonical\" href=\"/tea\">");
+    /// assert_eq!(
+    ///     soup.canonical_url(Some("https://example.com/tea?ref=feed")).as_deref(),
+    ///     Some("https://example.com/tea")
+    /// );
     /// ```
-    pub fn find(&self, selector: &str) -> QueryResult<Option<Tag<'_>>> {
-        find(&self.document, selector).map(|opt| opt.map(|id| Tag::new(&self.document, id)))
+    #[must_use]
+    pub fn canonical_url(&self, base_url: Option<&str>) -> Option<String> {
+        canonical_url(self, base_url)
     }
 
-    /// Finds all elements matching the given CSS selector.
+    /// Returns the absolute URL of the page's AMP version from `<link rel="amphtml">`.
     ///
-    /// # Errors
-    ///
-    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
-    /// syntax is invalid.
+    /// The `href` is resolved like [`Soup::links`].
     ///
     /// # Examples
     ///
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse("<ul><li>A</li><li>B</li></ul>");
-    /// let items = soup.find_all("li").unwrap();
-    /// assert_eq!(items.len(), 2);
+    /// let soup = Soup::parse("<link rel=\"amphtml\" href=\"/amp/tea\">");
+    /// assert_eq!(
+    ///     soup.amp_variant_url(Some("https://example.com/tea")).as_deref(),
+    ///     Some("https://example.com/amp/tea")
+    /// );
     /// ```
-    pub fn find_all(&self, selector: &str) -> QueryResult<Vec<Tag<'_>>> {
-        find_all(&self.document, selector)
-            .map(|ids| ids.into_iter().map(|id| Tag::new(&self.document, id)).collect())
+    #[must_use]
+    pub fn amp_variant_url(&self, base_url: Option<&str>) -> Option<String> {
+        amp_variant_url(self, base_url)
     }
 
-    /// Selects elements using a CSS selector.
-    ///
-    /// This is an alias for [`Soup::find_all`] for users familiar with
-    /// the CSS selector API.
-    ///
-    /// # Errors
+    /// Returns the absolute URL of the page's separate mobile version.
     ///
-    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
-    /// syntax is invalid.
+    /// See [`mobile_variant_url`](crate::canonical::mobile_variant_url) for how the link
+    /// is found.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse("<div class=\"a\"><span class=\"b\">Text</span></div>");
-    /// let results = soup.select("div.a > span.b").unwrap();
-    /// assert_eq!(results.len(), 1);
+    /// let soup = Soup::parse(
+    ///     "<link rel=\"alternate\" media=\"only screen and (max-width: 640px)\" \
+    ///      href=\"https://m.example.com/tea\">",
+    /// );
+    /// assert_eq!(soup.mobile_variant_url(None).as_deref(), Some("https://m.example.com/tea"));
     /// ```
-    pub fn select(&self, selector: &str) -> QueryResult<Vec<Tag<'_>>> {
-        self.find_all(selector)
+    #[must_use]
+    pub fn mobile_variant_url(&self, base_url: Option<&str>) -> Option<String> {
+        mobile_variant_url(self, base_url)
     }
 
-    /// Finds the first element using a pre-compiled selector.
+    /// Returns `true` if the document is an AMP page (`<html amp>` or `<html ⚡>`).
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use scrape_core::{Soup, query::CompiledSelector};
+    /// use scrape_core::Soup;
     ///
-    /// let selector = CompiledSelector::compile("div.item").unwrap();
-    /// let soup = Soup::parse("<div class=\"item\">Text</div>");
-    /// let result = soup.find_compiled(&selector);
-    /// assert!(result.is_some());
+    /// assert!(Soup::parse("<html amp><body>Tea</body></html>").is_amp());
+    /// assert!(!Soup::parse("<html><body>Tea</body></html>").is_amp());
     /// ```
     #[must_use]
-    pub fn find_compiled(&self, selector: &CompiledSelector) -> Option<Tag<'_>> {
-        find_compiled(&self.document, selector).map(|id| Tag::new(&self.document, id))
+    pub fn is_amp(&self) -> bool {
+        is_amp(self)
     }
 
-    /// Finds all elements using a pre-compiled selector.
+    /// Returns the document's inline assets with their decoded bytes.
+    ///
+    /// Collects `data:` URIs from attributes and CSS `url(...)` references, inline
+    /// `<svg>` elements, and `<style>` blocks, in document order. See
+    /// [`extract::assets`](mod@crate::extract::assets) for details.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use scrape_core::{Soup, query::CompiledSelector};
+    /// use scrape_core::Soup;
     ///
-    /// let selector = CompiledSelector::compile("li").unwrap();
-    /// let soup = Soup::parse("<ul><li>A</li><li>B</li></ul>");
-    /// let items = soup.select_compiled(&selector);
-    /// assert_eq!(items.len(), 2);
+    /// let soup = Soup::parse("<img src=\"data:image/gif;base64,R0lGODlh\"><svg></svg>");
+    /// let mimes: Vec<_> = soup.inline_assets().into_iter().map(|a| a.mime).collect();
+    /// assert_eq!(mimes, vec!["image/gif", "image/svg+xml"]);
     /// ```
     #[must_use]
-    pub fn select_compiled(&self, selector: &CompiledSelector) -> Vec<Tag<'_>> {
-        find_all_compiled(&self.document, selector)
-            .into_iter()
-            .map(|id| Tag::new(&self.document, id))
-            .collect()
+    pub fn inline_assets(&self) -> Vec<InlineAsset> {
+        inline_assets(self)
     }
 
-    /// Extracts text content from all elements matching a CSS selector.
-    ///
-    /// Returns the concatenated text content of each matching element.
+    /// Downloads the document's external stylesheets and images through `fetcher` and
+    /// embeds them, producing a single-file page.
     ///
-    /// # Errors
+    /// Stylesheet links become `<style>` blocks and image references become `data:`
+    /// URIs. References are resolved against `<base href>` and `base_url` like
+    /// [`Soup::links`]. Failed downloads are reported and leave their references
+    /// unchanged. See [`archive`](crate::archive) for details.
     ///
-    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
-    /// syntax is invalid.
+    /// Clears the query cache, if enabled, and the text index.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use scrape_core::Soup;
-    ///
-    /// let soup = Soup::parse("<ul><li>First</li><li>Second</li></ul>");
-    /// let texts = soup.select_text("li").unwrap();
-    /// assert_eq!(texts, vec!["First", "Second"]);
+    /// use scrape_core::{Soup, archive::FetchedAsset};
+    ///
+    /// let mut soup = Soup::parse("<img src=\"/dot.gif\"><img src=\"/gone.png\">");
+    /// let fetch = |url: &str| match url {
+    ///     "https://example.com/dot.gif" => Ok(FetchedAsset::new("image/gif", "GIF89a")),
+    ///     _ => Err("404"),
+    /// };
+    /// let report = soup.inline_external_assets(&fetch, Some("https://example.com/"));
+    /// assert_eq!(report.inlined, ["https://example.com/dot.gif"]);
+    /// assert_eq!(report.failed[0].url, "https://example.com/gone.png");
     /// ```
-    pub fn select_text(&self, selector: &str) -> QueryResult<Vec<String>> {
-        select_text(&self.document, selector)
+    pub fn inline_external_assets<F: AssetFetcher>(
+        &mut self,
+        fetcher: &F,
+        base_url: Option<&str>,
+    ) -> ArchiveReport {
+        inline_external_assets(self, fetcher, base_url)
     }
 
-    /// Extracts attribute values from all elements matching a CSS selector.
-    ///
-    /// Returns `Some(value)` if the attribute exists, `None` if it doesn't.
-    ///
-    /// # Errors
+    /// Extracts the main content of the page, like a browser's reader mode.
     ///
-    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
-    /// syntax is invalid.
+    /// Returns the content element with the title, byline, and body as plain text and
+    /// Markdown. Navigation, sidebars, comments, and other clutter are left out. See
+    /// [`article`](crate::article) for how the content is found.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse("<a href='/a'>A</a><a>B</a>");
-    /// let hrefs = soup.select_attr("a", "href").unwrap();
-    /// assert_eq!(hrefs, vec![Some("/a".to_string()), None]);
+    /// let soup = Soup::parse(
+    ///     "<title>Tea</title><nav><a href=\"/\">Home</a></nav><article><h1>Tea</h1><p>Tea is brewed \
+    ///      from leaves, buds, and hot water.</p></article>",
+    /// );
+    /// let article = soup.extract_article();
+    /// assert_eq!(article.markdown, "# Tea\n\nTea is brewed from leaves, buds, and hot water.");
     /// ```
-    pub fn select_attr(&self, selector: &str, attr: &str) -> QueryResult<Vec<Option<String>>> {
-        select_attr(&self.document, selector, attr)
+    #[must_use]
+    pub fn extract_article(&self) -> Article {
+        extract_article(self)
     }
 
-    // ==================== Document Methods ====================
-
-    /// Returns the root element of the document.
+    /// Returns the directives of the document's `<meta name="robots">` tags, merged.
     ///
-    /// This is typically the `<html>` element.
+    /// Combine them with `X-Robots-Tag` headers through
+    /// [`RobotsDirectives::from_header`] and [`RobotsDirectives::merge`]. See
+    /// [`robots`](crate::robots) for details.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse("<html><body>text</body></html>");
-    /// if let Some(root) = soup.root() {
-    ///     assert_eq!(root.name(), Some("html"));
-    /// }
+    /// let soup = Soup::parse("<meta name=\"robots\" content=\"noindex, nofollow\">");
+    /// let directives = soup.robots_directives();
+    /// assert!(!directives.can_index());
+    /// assert!(!directives.can_follow());
     /// ```
     #[must_use]
-    pub fn root(&self) -> Option<Tag<'_>> {
-        self.document.root().map(|id| Tag::new(&self.document, id))
+    pub fn robots_directives(&self) -> RobotsDirectives {
+        robots_directives(self, None)
     }
 
-    /// Returns the document's title, if present.
+    /// Returns the document's forms with their fields, in document order.
+    ///
+    /// Form actions are resolved like [`Soup::links`]. See [`forms`](crate::forms) for
+    /// details and for building the payload a form submits.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse("<html><head><title>My Page</title></head></html>");
-    /// assert_eq!(soup.title(), Some("My Page".to_string()));
+    /// let soup =
+    ///     Soup::parse("<form action=/search><input name=q value=tea><input type=submit></form>");
+    /// let forms = soup.forms(Some("https://example.com/"));
+    /// assert_eq!(forms[0].url.as_deref(), Some("https://example.com/search"));
+    /// assert_eq!(forms[0].fields.len(), 2);
+    /// let payload = forms[0].submit_payload(&[("q", "oolong")]);
+    /// assert_eq!(payload, [("q".to_string(), "oolong".to_string())]);
     /// ```
     #[must_use]
-    pub fn title(&self) -> Option<String> {
-        self.find("title").ok()?.map(|tag| tag.text())
+    pub fn forms(&self, base_url: Option<&str>) -> Vec<Form> {
+        forms(self, base_url)
     }
 
-    /// Returns the document's text content with tags stripped.
+    /// Checks the document for duplicate `id` values, elements nested where HTML does
+    /// not allow them, obsolete tags, and images without `alt` text.
+    ///
+    /// Warnings are in document order. Elements the source left unclosed are only known
+    /// while parsing; use [`lint`](crate::lint::lint) to check HTML source for them too.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use scrape_core::Soup;
+    /// use scrape_core::{Soup, lint::LintKind};
     ///
-    /// let soup = Soup::parse("<div>Hello <b>World</b></div>");
-    /// let text = soup.text();
-    /// assert!(text.contains("Hello"));
-    /// assert!(text.contains("World"));
+    /// let soup = Soup::parse("<span><div>Tea</div></span><img src=\"tea.png\">");
+    /// let warnings = soup.validate();
+    /// assert_eq!(warnings[0].kind, LintKind::InvalidNesting);
+    /// assert_eq!(warnings[0].path.as_deref(), Some("html > body > span > div"));
+    /// assert_eq!(warnings[1].kind, LintKind::MissingAlt);
     /// ```
     #[must_use]
-    pub fn text(&self) -> String {
-        let Some(root) = self.document.root() else {
-            return String::new();
-        };
-        let mut result = String::new();
-        collect_text(&self.document, root, &mut result);
-        result
+    pub fn validate(&self) -> Vec<LintWarning> {
+        validate(self)
     }
 
-    /// Returns the document as an HTML string.
+    /// Returns the document's metadata: meta tags, Open Graph properties, Twitter card
+    /// fields, the canonical URL, favicons, and parsed JSON-LD blocks.
+    ///
+    /// See [`metadata`](crate::metadata) for details. Requires the `serde` feature.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse("<div><span>text</span></div>");
-    /// let html = soup.to_html();
-    /// assert!(html.contains("<div>"));
-    /// assert!(html.contains("<span>"));
+    /// let soup = Soup::parse("<title>Hi</title><meta property=\"og:type\" content=\"website\">");
+    /// let meta = soup.metadata();
+    /// assert_eq!(meta.title.as_deref(), Some("Hi"));
+    /// assert_eq!(meta.open_graph["og:type"], ["website"]);
     /// ```
+    #[cfg(feature = "serde")]
     #[must_use]
-    pub fn to_html(&self) -> String {
-        self.root().map(|tag| tag.outer_html()).unwrap_or_default()
+    pub fn metadata(&self) -> crate::metadata::Metadata {
+        crate::metadata::metadata(self)
+    }
+}
+
+impl Clone for Soup {
+    /// Deep-copies the document and configuration; the clone starts with an empty query cache.
+    fn clone(&self) -> Self {
+        Self::new(self.document.clone(), self.config.clone())
     }
 }
 
@@ -505,7 +1840,7 @@ fn collect_text(doc: &Document, id: NodeId, buf: &mut String) {
                 collect_text(doc, child_id, buf);
             }
         }
-        NodeKind::Comment { .. } => {}
+        NodeKind::Comment { .. } | NodeKind::Doctype { .. } => {}
     }
 }
 
@@ -518,6 +1853,13 @@ fn estimate_node_count(html_len: usize) -> usize {
     (html_len / 50).max(256)
 }
 
+/// Locks the query cache, recovering from poisoning since entries are always complete.
+fn lock_cache(
+    cache: &Mutex<HashMap<String, Vec<NodeId>>>,
+) -> std::sync::MutexGuard<'_, HashMap<String, Vec<NodeId>>> {
+    cache.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -545,6 +1887,256 @@ mod tests {
         assert!(config.include_comments);
     }
 
+    #[test]
+    fn test_query_cache_memoizes_find_all() {
+        let config = SoupConfig::builder().enable_query_cache(true).build();
+        assert!(config.query_cache);
+        let soup = Soup::parse_with_config("<ul><li>A</li><li>B</li></ul><p>C</p>", config);
+
+        let first: Vec<_> = soup.find_all("li").unwrap().iter().map(Tag::text).collect();
+        let second: Vec<_> = soup.select("li").unwrap().iter().map(Tag::text).collect();
+        assert_eq!(first, ["A", "B"]);
+        assert_eq!(first, second);
+        assert_eq!(soup.query_cache_len(), 1);
+
+        assert!(soup.find_all("[").is_err());
+        assert_eq!(soup.query_cache_len(), 1);
+    }
+
+    #[test]
+    fn test_parse_checked() {
+        let html = "<!DOCTYPE html>\n<ul>\n  <li>One</b></li>\n</ul>";
+        let (soup, warnings) = Soup::parse_checked(html).unwrap();
+        assert_eq!(soup.find("li").unwrap().unwrap().text(), "One");
+        assert_eq!(warnings.len(), 1);
+        let span = warnings[0].span.unwrap();
+        assert_eq!((span.start.line, span.start.column, span.start.offset), (3, 1, 21));
+        assert_eq!((span.end.column, span.end.offset), (19, 39));
+
+        let (_, warnings) = Soup::parse_checked("<!DOCTYPE html><p>Fine</p>").unwrap();
+        assert!(warnings.is_empty());
+        let (soup, _) = Soup::parse_checked("  ").unwrap();
+        assert!(soup.root().is_none());
+
+        let strict = SoupConfig::builder().strict_mode(true).build();
+        let err = Soup::parse_checked_with_config(html, strict).unwrap_err();
+        assert!(err.to_string().ends_with("at line 3, column 1"));
+
+        let shallow = SoupConfig::builder().max_depth(2).build();
+        assert!(
+            Soup::parse_checked_with_config("<div><div><div>x</div></div></div>", shallow).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_xml() {
+        let xml = "<?xml version=\"1.0\"?>\n<rss xmlns:atom=\"http://www.w3.org/2005/Atom\">\
+                   <channel><atom:link href=\"/feed\"/><link>https://example.com/</link>\
+                   <item><pubDate>Mon</pubDate><description><![CDATA[<b>Hi</b>]]></description>\
+                   </item></channel></rss>";
+        let soup = Soup::parse_xml(xml).unwrap();
+        let channel = soup.find("channel").unwrap().unwrap();
+        assert_eq!(
+            channel.find("link").unwrap().unwrap().outer_html(),
+            "<link>https://example.com/</link>"
+        );
+        assert_eq!(soup.find("atom\\:link").unwrap().unwrap().get("href"), Some("/feed"));
+        assert_eq!(soup.find("pubdate").unwrap().unwrap().name(), Some("pubDate"));
+        assert_eq!(soup.find("description").unwrap().unwrap().text(), "<b>Hi</b>");
+        assert!(soup.find("b").unwrap().is_none());
+
+        assert!(Soup::parse_xml("").unwrap().root().is_none());
+        let err = Soup::parse_xml("<a/><b/>").unwrap_err();
+        assert!(err.to_string().contains("multiple root elements"));
+    }
+
+    #[test]
+    fn test_parse_borrowed_matches_parse() {
+        let html = "<!DOCTYPE html><title>A &lt; \
+                    B</title><table>stray<tr><td>caf\u{e9}</td></tr></table>\r\n<pre>\r\nline \
+                    one\r\nline two</pre><p>x<b>y</b>z</p>";
+        let borrowed = Soup::parse_borrowed(html);
+        assert_eq!(borrowed.to_html(), Soup::parse(html).to_html());
+
+        let doc = borrowed.document();
+        let texts: Vec<_> = doc
+            .descendants(doc.root().unwrap())
+            .filter_map(|id| match &doc.get(id)?.kind {
+                NodeKind::Text { content } => Some(content),
+                _ => None,
+            })
+            .collect();
+        for content in &texts {
+            if let Some(span) = content.span() {
+                assert_eq!(&html[span], content.as_str());
+            }
+        }
+        let shared: Vec<_> = texts.iter().filter(|c| c.is_shared()).map(|c| c.as_str()).collect();
+        for text in ["stray", "caf\u{e9}", "x", "y", "z"] {
+            assert!(shared.contains(&text), "{text:?} in {shared:?}");
+        }
+        assert!(!shared.contains(&"A < B"));
+    }
+
+    #[test]
+    fn test_pierce_shadow() {
+        let html = "<my-card><template \
+                    shadowrootmode=\"open\"><h2>Shadow</h2></template><span>Light</span></\
+                    my-card><div><template shadowrootmode=\"bogus\"><p>Inert</p></template></div>";
+
+        let soup = Soup::parse(html);
+        assert!(soup.find("h2").unwrap().is_none());
+
+        let config = SoupConfig::builder().pierce_shadow(true).build();
+        assert!(config.pierce_shadow);
+        let soup = Soup::parse_with_config(html, config);
+        let title = soup.find("my-card > template[shadowrootmode] > h2").unwrap().unwrap();
+        assert_eq!(title.text(), "Shadow");
+        assert_eq!(soup.find("my-card").unwrap().unwrap().text(), "ShadowLight");
+        assert!(soup.find("p").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_comments_and_doctype() {
+        let html = "<!-- build 42 --><!DOCTYPE html><html><head><!-- a --></head><body><p>x<!-- b \
+                    --></p></body></html><!-- after -->";
+        let config = SoupConfig::builder().include_comments(true).build();
+        let mut soup = Soup::parse_with_config(html, config);
+        assert_eq!(soup.root().unwrap().name(), Some("html"));
+        assert_eq!(soup.comments().collect::<Vec<_>>(), [" build 42 ", " a ", " b ", " after "]);
+        assert_eq!(soup.doctype().unwrap().name, "html");
+        let p = soup.find("p").unwrap().unwrap();
+        assert_eq!(p.comments().collect::<Vec<_>>(), [" b "]);
+        assert!(soup.to_html().starts_with("<html>"));
+
+        soup.document_mut().compact();
+        assert_eq!(soup.comments().count(), 4);
+        assert_eq!(soup.doctype().unwrap().name, "html");
+
+        let soup = Soup::parse(html);
+        assert_eq!(soup.comments().count(), 0);
+        let soup = Soup::parse(
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Strict//EN\" \
+             \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd\"><p>x</p>",
+        );
+        let doctype = soup.doctype().unwrap();
+        assert_eq!(doctype.public_id, "-//W3C//DTD XHTML 1.0 Strict//EN");
+        assert_eq!(doctype.system_id, "http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd");
+    }
+
+    #[test]
+    fn test_attributes_keep_source_order() {
+        let html = "<a z=\"1\" href=\"/x\" a=\"2\" href=\"/y\" Z=\"3\">link</a>";
+
+        let soup = Soup::parse(html);
+        let link = soup.find("a").unwrap().unwrap();
+        assert_eq!(link.outer_html(), "<a z=\"1\" href=\"/x\" a=\"2\">link</a>");
+
+        let config = SoupConfig::builder().preserve_duplicate_attributes(true).build();
+        let soup = Soup::parse_with_config(html, config);
+        let link = soup.find("a[href='/x']").unwrap().unwrap();
+        let attrs = link.attrs().unwrap();
+        assert!(attrs.has_duplicates());
+        assert_eq!(attrs.keys().collect::<Vec<_>>(), ["z", "href", "a", "href", "z"]);
+        assert_eq!(attrs.get_all("z").collect::<Vec<_>>(), ["1", "3"]);
+        assert_eq!(link.get("href"), Some("/x"));
+    }
+
+    #[test]
+    fn test_query_cache_invalidated_on_mutation() {
+        let config = SoupConfig::builder().enable_query_cache(true).build();
+        let mut soup = Soup::parse_with_config("<ul><li>A</li><li class=\"x\">B</li></ul>", config);
+        assert_eq!(soup.find_all("li").unwrap().len(), 2);
+        assert_eq!(soup.find_all(".x").unwrap().len(), 1);
+
+        let li = soup.find("li").unwrap().unwrap().node_id();
+        assert!(soup.tag_mut(li).unwrap().set_attribute("class", "x"));
+        assert_eq!(soup.query_cache_len(), 0);
+        assert_eq!(soup.find_all(".x").unwrap().len(), 2);
+
+        let li = soup.find("li").unwrap().unwrap().node_id();
+        assert!(soup.document_mut().remove_node(li));
+        assert_eq!(soup.find_all("li").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_text_index_dropped_on_mutation() {
+        let mut soup = Soup::parse("<ul><li>Tea</li><li>Green tea</li></ul>");
+        assert_eq!(soup.search_text("tea").len(), 1);
+        soup.build_text_index();
+        assert!(soup.has_text_index());
+        assert_eq!(soup.search_text("ea").len(), 2);
+
+        soup.append_html("<li>Iced tea</li>");
+        assert!(!soup.has_text_index());
+        assert_eq!(soup.search_text("tea").len(), 2);
+        soup.build_text_index();
+        assert_eq!(soup.search_text("tea").len(), 2);
+    }
+
+    #[test]
+    fn test_append_html() {
+        let config = SoupConfig::builder().enable_query_cache(true).build();
+        let mut soup = Soup::parse_with_config("<p id=\"a\">1</p>", config);
+        assert_eq!(soup.find_all("p").unwrap().len(), 1);
+
+        let added = soup.append_html("<p class=\"x\">2</p>\n<p id=\"b\">3</p>");
+        assert_eq!(added.len(), 2);
+        assert_eq!(soup.query_cache_len(), 0);
+        assert_eq!(soup.select_text("body > p").unwrap(), ["1", "2", "3"]);
+        assert_eq!(soup.find("#b").unwrap().unwrap().node_id(), added[1]);
+        assert_eq!(soup.find(".x").unwrap().unwrap().node_id(), added[0]);
+        assert!(soup.document().index().is_some());
+        assert!(soup.append_html("  ").is_empty());
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let config = SoupConfig::builder().enable_query_cache(true).build();
+        let mut soup = Soup::parse_with_config("<ul><li>A</li><li>B</li></ul>", config);
+        assert_eq!(soup.find_all("li").unwrap().len(), 2);
+
+        let copy = soup.clone();
+        assert_eq!(copy.query_cache_len(), 0);
+        let li = soup.find("li").unwrap().unwrap().node_id();
+        assert!(soup.document_mut().remove_node(li));
+
+        assert_eq!(soup.find_all("li").unwrap().len(), 1);
+        assert_eq!(copy.find_all("li").unwrap().len(), 2);
+        assert_eq!(copy.query_cache_len(), 1);
+    }
+
+    #[test]
+    fn test_extract_soup() {
+        let soup = Soup::parse(
+            "<body><nav id=\"n\">Menu</nav><article class=\"post\"><h1 id=\"t\">Title</h1><p \
+             class=\"x\">One</p><p>Two</p></article></body>",
+        );
+        let article = soup.find("article").unwrap().unwrap().extract_soup();
+        drop(soup);
+
+        assert_eq!(article.root().unwrap().name(), Some("article"));
+        assert_eq!(article.document().len(), 7);
+        assert_eq!(article.find("#t").unwrap().unwrap().text(), "Title");
+        assert!(article.find("#n").unwrap().is_none());
+        assert_eq!(article.select_text("p").unwrap(), ["One", "Two"]);
+        assert_eq!(article.find_all(".post").unwrap().len(), 1);
+        assert_eq!(
+            article.to_html(),
+            "<article class=\"post\"><h1 id=\"t\">Title</h1><p \
+             class=\"x\">One</p><p>Two</p></article>"
+        );
+        assert!(article.find("h1").unwrap().unwrap().parent().is_some());
+    }
+
+    #[test]
+    fn test_query_cache_disabled_by_default() {
+        let soup = Soup::parse("<li>A</li>");
+        assert!(!SoupConfig::default().query_cache);
+        assert_eq!(soup.find_all("li").unwrap().len(), 1);
+        assert_eq!(soup.query_cache_len(), 0);
+    }
+
     #[test]
     fn test_soup_parse_creates_document() {
         let soup = Soup::parse("<html><body>Hello</body></html>");
@@ -724,6 +2316,27 @@ mod tests {
         assert_eq!(soup2.select_compiled(&selector).len(), 2);
     }
 
+    #[test]
+    fn test_soup_typed_selects() {
+        let soup = Soup::parse(
+            "<meta property=\"og:image\" content=\"/og.png\"><span itemprop=\"price\" \
+             content=\"19.90\">$19.90</span><span class=\"date\">5 March 2024</span><span \
+             class=\"date\">03/05/2024</span>",
+        );
+
+        assert_eq!(soup.select_numbers("[itemprop=price], .date").unwrap()[0], Some(19.9));
+        let strict = soup.select_dates(".date", DateOrder::Strict).unwrap();
+        assert_eq!(strict.iter().flatten().count(), 1);
+        let day_first = soup.select_dates(".date", DateOrder::DayFirst).unwrap();
+        assert_eq!(day_first[1].map(|d| d.to_string()).as_deref(), Some("2024-05-03"));
+        assert_eq!(
+            soup.select_urls("meta", Some("https://example.com/a/b")).unwrap(),
+            vec![Some("https://example.com/og.png".to_string())]
+        );
+        let lens = soup.select_map(".date", |tag| tag.text().len()).unwrap();
+        assert_eq!(lens, vec![12, 10]);
+    }
+
     #[test]
     fn test_estimate_node_count_minimum() {
         assert_eq!(estimate_node_count(0), 256);
@@ -0,0 +1,146 @@
+//! Splitting input at adversarial chunk boundaries.
+//!
+//! A network read can end anywhere: inside a tag, halfway through `&amp;`, or between the
+//! bytes of a multi-byte UTF-8 character. [`fragment`] reproduces such reads on demand, so
+//! handlers can be checked for boundary safety before they meet a slow connection.
+//! [`StreamingSoup::write_fragmented`](super::StreamingSoup::write_fragmented) feeds the
+//! pieces straight to the streaming parser, and they can be passed to
+//! [`RewriterSession::write`](super::RewriterSession::write) one by one as well.
+//!
+//! Requires the `test-support` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::streaming::{FragmentPattern, fragment};
+//!
+//! let chunks = fragment("<a href=x>caf\u{e9} &amp;</a>".as_bytes(), FragmentPattern::Adversarial);
+//! assert_eq!(chunks[..3], [b"<".as_slice(), b"a href=", b"x"]);
+//! assert!(chunks.contains(&b">caf\xc3".as_slice()));
+//! ```
+
+/// Where [`fragment`] splits its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FragmentPattern {
+    /// Splits after every byte.
+    EveryByte,
+    /// Splits into chunks of the given size; the last chunk may be shorter. A size of `0`
+    /// is treated as `1`.
+    Fixed(usize),
+    /// Splits after every `<`, `=`, and `&`, before every `>` and `;`, and between the
+    /// bytes of every multi-byte UTF-8 character, so tags, attributes, entities, and
+    /// characters all straddle a boundary.
+    Adversarial,
+    /// Splits into chunks of 1 to 16 bytes chosen by a pseudo-random generator, so a
+    /// failure reproduces with the same seed.
+    Seeded(u64),
+}
+
+/// Splits `data` into consecutive non-empty chunks according to `pattern`.
+///
+/// Concatenating the chunks always gives back `data`.
+#[must_use]
+pub fn fragment(data: &[u8], pattern: FragmentPattern) -> Vec<&[u8]> {
+    match pattern {
+        FragmentPattern::EveryByte => data.chunks(1).collect(),
+        FragmentPattern::Fixed(size) => data.chunks(size.max(1)).collect(),
+        FragmentPattern::Adversarial => split_at(
+            data,
+            (1..data.len()).filter(|&i| {
+                matches!(data[i - 1], b'<' | b'=' | b'&')
+                    || matches!(data[i], b'>' | b';')
+                    || is_continuation(data[i])
+            }),
+        ),
+        FragmentPattern::Seeded(seed) => {
+            // xorshift64 never leaves a zero state.
+            let mut state = seed.max(1);
+            let mut at = 0;
+            split_at(
+                data,
+                std::iter::from_fn(|| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    // The remainder is below 16, so the cast cannot truncate.
+                    #[allow(clippy::cast_possible_truncation)]
+                    let len = (state % 16) as usize + 1;
+                    at += len;
+                    (at < data.len()).then_some(at)
+                }),
+            )
+        }
+    }
+}
+
+/// Splits `data` at the given ascending offsets, each between `1` and `data.len() - 1`.
+fn split_at(data: &[u8], offsets: impl Iterator<Item = usize>) -> Vec<&[u8]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    for offset in offsets {
+        chunks.push(&data[start..offset]);
+        start = offset;
+    }
+    if start < data.len() {
+        chunks.push(&data[start..]);
+    }
+    chunks
+}
+
+const fn is_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = "<p class=\"x\">Caf\u{e9} &amp; \u{1f375}</p>";
+
+    #[test]
+    fn test_fragment_round_trips() {
+        for pattern in [
+            FragmentPattern::EveryByte,
+            FragmentPattern::Fixed(0),
+            FragmentPattern::Fixed(5),
+            FragmentPattern::Adversarial,
+            FragmentPattern::Seeded(0),
+            FragmentPattern::Seeded(42),
+        ] {
+            let chunks = fragment(HTML.as_bytes(), pattern);
+            assert!(chunks.iter().all(|chunk| !chunk.is_empty()), "{pattern:?}");
+            assert_eq!(chunks.concat(), HTML.as_bytes(), "{pattern:?}");
+        }
+        assert!(fragment(b"", FragmentPattern::Adversarial).is_empty());
+        assert!(fragment(b"", FragmentPattern::Seeded(7)).is_empty());
+    }
+
+    #[test]
+    fn test_fragment_adversarial() {
+        let chunks = fragment(HTML.as_bytes(), FragmentPattern::Adversarial);
+        let expected: [&[u8]; 12] = [
+            b"<",
+            b"p class=",
+            b"\"x\"",
+            b">Caf\xc3",
+            b"\xa9 &",
+            b"amp",
+            b"; \xf0",
+            b"\x9f",
+            b"\x8d",
+            b"\xb5<",
+            b"/p",
+            b">",
+        ];
+        assert_eq!(chunks, expected);
+    }
+
+    #[test]
+    fn test_fragment_seeded_is_reproducible() {
+        let data = "x".repeat(200);
+        let first = fragment(data.as_bytes(), FragmentPattern::Seeded(9));
+        assert_eq!(first, fragment(data.as_bytes(), FragmentPattern::Seeded(9)));
+        assert_ne!(first, fragment(data.as_bytes(), FragmentPattern::Seeded(10)));
+        assert!(first.iter().all(|chunk| (1..=16).contains(&chunk.len())));
+    }
+}
@@ -1,6 +1,6 @@
 //! Handler traits and registry for streaming parser.
 
-use crate::{Result, streaming::element::StreamingElement};
+use crate::{Result, Soup, streaming::element::StreamingElement};
 
 /// Handler for element events during streaming.
 ///
@@ -50,6 +50,19 @@ pub trait EndTagHandler: Send {
     fn handle(&mut self, tag_name: &str) -> Result<()>;
 }
 
+/// Handler for subtrees captured during streaming.
+///
+/// Implement this trait to query a matched element with the full DOM API while the
+/// rest of the document is streamed.
+pub trait CaptureHandler: Send {
+    /// Called with a document holding a matched element and its subtree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handler fails to process the subtree.
+    fn handle(&mut self, soup: &Soup) -> Result<()>;
+}
+
 /// Wrapper for boxed element handler functions.
 struct BoxedElementHandler<F>
 where
@@ -101,6 +114,23 @@ where
     }
 }
 
+/// Wrapper for boxed capture handler functions.
+struct BoxedCaptureHandler<F>
+where
+    F: FnMut(&Soup) -> Result<()> + Send,
+{
+    handler: F,
+}
+
+impl<F> CaptureHandler for BoxedCaptureHandler<F>
+where
+    F: FnMut(&Soup) -> Result<()> + Send,
+{
+    fn handle(&mut self, soup: &Soup) -> Result<()> {
+        (self.handler)(soup)
+    }
+}
+
 /// Registry for streaming handlers.
 ///
 /// Manages registered handlers and their associated selectors.
@@ -110,6 +140,7 @@ pub(crate) struct HandlerRegistry {
     element_handlers: Vec<(String, Box<dyn ElementHandler>)>,
     text_handlers: Vec<(String, Box<dyn TextHandler>)>,
     end_tag_handlers: Vec<(String, Box<dyn EndTagHandler>)>,
+    capture_handlers: Vec<(String, Box<dyn CaptureHandler>)>,
 }
 
 impl HandlerRegistry {
@@ -146,6 +177,15 @@ impl HandlerRegistry {
         self.end_tag_handlers.push((selector, boxed));
     }
 
+    /// Registers a capture handler for the given selector.
+    pub fn register_capture<F>(&mut self, selector: String, handler: F)
+    where
+        F: FnMut(&Soup) -> Result<()> + Send + 'static,
+    {
+        let boxed = Box::new(BoxedCaptureHandler { handler });
+        self.capture_handlers.push((selector, boxed));
+    }
+
     /// Returns the number of registered element handlers.
     #[must_use]
     pub fn element_count(&self) -> usize {
@@ -164,6 +204,12 @@ impl HandlerRegistry {
         self.end_tag_handlers.len()
     }
 
+    /// Returns the number of registered capture handlers.
+    #[must_use]
+    pub fn capture_count(&self) -> usize {
+        self.capture_handlers.len()
+    }
+
     /// Returns an iterator over element handler selectors.
     pub fn element_selectors(&self) -> impl Iterator<Item = &str> {
         self.element_handlers.iter().map(|(sel, _)| sel.as_str())
@@ -179,6 +225,11 @@ impl HandlerRegistry {
         self.end_tag_handlers.iter().map(|(sel, _)| sel.as_str())
     }
 
+    /// Returns an iterator over capture handler selectors.
+    pub fn capture_selectors(&self) -> impl Iterator<Item = &str> {
+        self.capture_handlers.iter().map(|(sel, _)| sel.as_str())
+    }
+
     /// Returns a mutable reference to element handlers.
     pub fn element_handlers_mut(&mut self) -> &mut Vec<(String, Box<dyn ElementHandler>)> {
         &mut self.element_handlers
@@ -193,6 +244,11 @@ impl HandlerRegistry {
     pub fn end_tag_handlers_mut(&mut self) -> &mut Vec<(String, Box<dyn EndTagHandler>)> {
         &mut self.end_tag_handlers
     }
+
+    /// Returns a mutable reference to capture handlers.
+    pub fn capture_handlers_mut(&mut self) -> &mut Vec<(String, Box<dyn CaptureHandler>)> {
+        &mut self.capture_handlers
+    }
 }
 
 #[cfg(test)]
@@ -237,6 +293,16 @@ mod tests {
         assert_eq!(selectors, vec!["div"]);
     }
 
+    #[test]
+    fn test_register_capture_handler() {
+        let mut registry = HandlerRegistry::new();
+        registry.register_capture("table".to_string(), |_soup| Ok(()));
+        assert_eq!(registry.capture_count(), 1);
+
+        let selectors: Vec<_> = registry.capture_selectors().collect();
+        assert_eq!(selectors, vec!["table"]);
+    }
+
     #[test]
     fn test_multiple_handlers() {
         let mut registry = HandlerRegistry::new();
@@ -10,7 +10,15 @@
 //! - **Constant memory usage**: Process GB-scale documents without loading everything into RAM
 //! - **Callback-based**: Register handlers for elements, text, and end tags
 //! - **HTML rewriting**: Modify HTML content on-the-fly during streaming
+//! - **Selective capture**: `StreamingSoup::capture` builds a [`Soup`](crate::Soup) for each
+//!   matched subtree only, for full DOM queries on the parts that matter
 //! - **Typestate safety**: Compile-time enforcement of valid state transitions
+//! - **Selector checks**: [`supports`] reports selector features that cannot be matched while
+//!   streaming, and handlers for such selectors are rejected when registered
+//! - **Async input** (feature `async`): feed chunks from an `AsyncRead` or a byte `Stream` with
+//!   `StreamingSoup::process_async` and `StreamingSoup::process_stream`
+//! - **Boundary testing** (feature `test-support`): split input inside tags, entities, and
+//!   characters with `fragment` and `StreamingSoup::write_fragmented`
 //!
 //! # Example
 //!
@@ -35,11 +43,17 @@
 
 pub mod config;
 pub mod element;
+#[cfg(feature = "test-support")]
+pub mod fragment;
 pub(crate) mod handlers;
 pub mod parser;
 pub mod rewriter;
+pub mod support;
 
 pub use config::{RewriterConfig, StreamingConfig};
 pub use element::{ContentType, StreamingElement};
+#[cfg(feature = "test-support")]
+pub use fragment::{FragmentPattern, fragment};
 pub use parser::{StreamingSoup, StreamingStats, state};
-pub use rewriter::HtmlRewriter;
+pub use rewriter::{HtmlRewriter, OutputSink, RewriterSession, WriterSink};
+pub use support::{UnsupportedFeature, supports};
@@ -1,14 +1,31 @@
 //! Streaming HTML parser with typestate pattern.
 
-use std::{cell::RefCell, marker::PhantomData, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    ops::Range,
+    rc::Rc,
+};
 
 use lol_html::AsciiCompatibleEncoding;
 
 use crate::{
-    Error, Result,
-    streaming::{StreamingConfig, StreamingElement, handlers::HandlerRegistry},
+    Error, Result, Soup,
+    streaming::{StreamingConfig, StreamingElement, handlers::HandlerRegistry, support},
 };
 
+/// Prefix of the comments that mark captured elements in the rewritten output.
+///
+/// Starts with a Unicode noncharacter so that it cannot collide with comments in real
+/// documents.
+const CAPTURE_MARKER: &str = "<!--\u{fdd0}capture:";
+
+/// Type alias for the output callback of the underlying rewriter.
+type OutputFn = Box<dyn FnMut(&[u8])>;
+
+/// Capture numbers, each with the range of cleaned output between its markers.
+type CaptureRanges = Vec<(usize, Range<usize>)>;
+
 /// State markers for streaming parser typestate pattern.
 pub mod state {
     /// Parser is idle and accepting handler registrations.
@@ -75,6 +92,27 @@ struct StreamingSoupInner {
     handlers: HandlerRegistry,
     stats: StreamingStats,
     output_buffer: Vec<u8>,
+    session: Option<Session>,
+}
+
+/// The rewriter of a started parser, kept across `write` calls so that tags, text, and
+/// matched elements may span chunk boundaries.
+struct Session {
+    rewriter: lol_html::HtmlRewriter<'static, OutputFn>,
+    shared: Rc<Shared>,
+    /// Rewritten output held back while a capture is open, markers included.
+    pending: Vec<u8>,
+}
+
+/// State shared between the rewriter's handlers and the parser.
+#[derive(Default)]
+struct Shared {
+    output: RefCell<Vec<u8>>,
+    error: RefCell<Option<Error>>,
+    elements: Cell<usize>,
+    text_nodes: Cell<usize>,
+    /// Capture handler index and tag name of each marked element, by marker number.
+    captures: RefCell<Vec<(usize, String)>>,
 }
 
 /// Statistics collected during streaming parse.
@@ -86,6 +124,8 @@ pub struct StreamingStats {
     pub elements_count: usize,
     /// Number of text nodes encountered.
     pub text_nodes_count: usize,
+    /// Number of subtrees passed to capture handlers.
+    pub captured_count: usize,
 }
 
 impl StreamingSoup<state::Idle> {
@@ -104,6 +144,7 @@ impl StreamingSoup<state::Idle> {
                 handlers: HandlerRegistry::new(),
                 stats: StreamingStats::default(),
                 output_buffer: Vec::new(),
+                session: None,
             },
             _state: PhantomData,
         }
@@ -116,7 +157,8 @@ impl StreamingSoup<state::Idle> {
     ///
     /// # Errors
     ///
-    /// Returns an error if the selector is invalid.
+    /// Returns [`Error::StreamingSelectorError`] if the selector is invalid or uses a
+    /// feature that cannot be matched while streaming; see [`supports`](super::supports).
     ///
     /// # Examples
     ///
@@ -130,23 +172,20 @@ impl StreamingSoup<state::Idle> {
     where
         F: FnMut(&mut StreamingElement) -> Result<()> + Send + 'static,
     {
-        // Basic validation - ensure non-empty selector
-        if selector.is_empty() {
-            return Err(Error::streaming_selector_error("selector cannot be empty"));
-        }
-
-        // NOTE: Full selector validation deferred later when we integrate lol_html rewriter
-        // lol_html will validate selectors when building the rewriter
-
+        support::validate(selector)?;
         self.inner.handlers.register_element(selector.to_string(), handler);
         Ok(self)
     }
 
     /// Registers a handler for text nodes within elements matching the selector.
     ///
+    /// The handler is called once per text node with its raw text, entities not decoded,
+    /// even when the tokenizer delivers the node in several pieces.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the selector is invalid.
+    /// Returns [`Error::StreamingSelectorError`] if the selector is invalid or uses a
+    /// feature that cannot be matched while streaming; see [`supports`](super::supports).
     ///
     /// # Examples
     ///
@@ -160,11 +199,7 @@ impl StreamingSoup<state::Idle> {
     where
         F: FnMut(&str) -> Result<()> + Send + 'static,
     {
-        // Basic validation
-        if selector.is_empty() {
-            return Err(Error::streaming_selector_error("selector cannot be empty"));
-        }
-
+        support::validate(selector)?;
         self.inner.handlers.register_text(selector.to_string(), handler);
         Ok(self)
     }
@@ -173,7 +208,8 @@ impl StreamingSoup<state::Idle> {
     ///
     /// # Errors
     ///
-    /// Returns an error if the selector is invalid.
+    /// Returns [`Error::StreamingSelectorError`] if the selector is invalid or uses a
+    /// feature that cannot be matched while streaming; see [`supports`](super::supports).
     ///
     /// # Examples
     ///
@@ -187,156 +223,245 @@ impl StreamingSoup<state::Idle> {
     where
         F: FnMut(&str) -> Result<()> + Send + 'static,
     {
-        // Basic validation
-        if selector.is_empty() {
-            return Err(Error::streaming_selector_error("selector cannot be empty"));
-        }
-
+        support::validate(selector)?;
         self.inner.handlers.register_end_tag(selector.to_string(), handler);
         Ok(self)
     }
 
-    /// Starts the streaming parser, transitioning to Processing state.
+    /// Registers a handler that receives each element matching the selector, with its
+    /// subtree, as a [`Soup`].
     ///
-    /// After calling this method, you can write chunks using `write()`.
-    #[must_use]
-    pub fn start(self) -> StreamingSoup<state::Processing> {
-        StreamingSoup { inner: self.inner, _state: PhantomData }
-    }
-}
-
-impl StreamingSoup<state::Processing> {
-    /// Writes a chunk of HTML to the streaming parser.
-    ///
-    /// The chunk will be processed and any registered handlers will be called
-    /// for matching elements/text.
+    /// Only matched subtrees are built into a DOM, by parsing their serialized HTML after
+    /// the chunk containing them is rewritten; the rest of the document is streamed.
+    /// Nested matches are each captured. Output is held back from the output buffer
+    /// while a match is open.
     ///
     /// # Errors
     ///
-    /// Returns an error if parsing fails or a handler returns an error.
-    ///
-    /// # Panics
-    ///
-    /// This method contains an `expect()` that should never panic as UTF-8 is always
-    /// ASCII-compatible. If it panics, it indicates a bug in `lol_html`.
+    /// Returns [`Error::StreamingSelectorError`] if the selector is invalid or uses a
+    /// feature that cannot be matched while streaming; see [`supports`](super::supports).
     ///
     /// # Examples
     ///
     /// ```ignore
-    /// processor.write(b"<div>content</div>")?;
+    /// streaming.capture("table.prices", |soup| {
+    ///     for row in soup.select("tr")? {
+    ///         println!("{}", row.text());
+    ///     }
+    ///     Ok(())
+    /// })?;
     /// ```
-    pub fn write(&mut self, chunk: &[u8]) -> Result<()> {
-        // Update stats
-        self.inner.stats.bytes_processed += chunk.len();
+    pub fn capture<F>(&mut self, selector: &str, handler: F) -> Result<&mut Self>
+    where
+        F: FnMut(&Soup) -> Result<()> + Send + 'static,
+    {
+        support::validate(selector)?;
+        self.inner.handlers.register_capture(selector.to_string(), handler);
+        Ok(self)
+    }
+
+    /// Starts the streaming parser, transitioning to Processing state.
+    ///
+    /// After calling this method, you can write chunks using `write()`. The started
+    /// parser owns a single rewriter for the whole input, so it is not `Send`.
+    ///
+    /// # Panics
+    ///
+    /// This method contains an `expect()` that should never panic as UTF-8 is always
+    /// ASCII-compatible. If it panics, it indicates a bug in `lol_html`.
+    #[must_use]
+    pub fn start(mut self) -> StreamingSoup<state::Processing> {
+        self.inner.session = Session::new(&self.inner.config, &mut self.inner.handlers);
+        StreamingSoup { inner: self.inner, _state: PhantomData }
+    }
+}
 
-        // Early return if no handlers registered - just pass through
-        if self.inner.handlers.element_count() == 0
-            && self.inner.handlers.text_count() == 0
-            && self.inner.handlers.end_tag_count() == 0
+impl Session {
+    /// Builds the rewriter, moving element and text handlers into it.
+    ///
+    /// Returns `None` if no handlers are registered, in which case input is passed
+    /// through unchanged.
+    fn new(config: &StreamingConfig, handlers: &mut HandlerRegistry) -> Option<Self> {
+        if handlers.element_count() == 0
+            && handlers.text_count() == 0
+            && handlers.end_tag_count() == 0
+            && handlers.capture_count() == 0
         {
-            self.inner.output_buffer.extend_from_slice(chunk);
-            return Ok(());
+            return None;
         }
 
-        // lol_html requires building handlers at Settings creation time
-        // We use Cell/RefCell to share mutable access safely within single-threaded context
-        let error_cell: Rc<RefCell<Option<Error>>> = Rc::new(RefCell::new(None));
-
-        // Share stats for updating counts
-        let element_count: Rc<RefCell<usize>> = Rc::new(RefCell::new(0));
-        let text_count: Rc<RefCell<usize>> = Rc::new(RefCell::new(0));
-
-        // Build element content handlers for lol_html
+        let shared = Rc::new(Shared::default());
         let mut element_handlers = Vec::new();
 
-        // We need to capture handlers but lol_html wants to own the closures
-        // Solution: use unsafe pointer + runtime checks to bridge the ownership gap
-        // SAFETY: This is safe because:
-        // 1. We're single-threaded (no Send/Sync issues)
-        // 2. Handlers live in StreamingSoupInner which outlives this method
-        // 3. lol_html closures don't outlive this method call
-        // 4. The pointer remains valid for the entire duration of HtmlRewriter usage
+        for (selector, mut handler) in std::mem::take(handlers.element_handlers_mut()) {
+            let shared = Rc::clone(&shared);
+            element_handlers.push(lol_html::element!(selector, move |el| {
+                // Stop processing if previous handler failed
+                if shared.error.borrow().is_some() {
+                    return Ok(());
+                }
+                shared.elements.set(shared.elements.get() + 1);
 
-        let handlers_ptr = &raw mut self.inner.handlers;
+                let mut streaming_el = StreamingElement::new(el);
+                if let Err(e) = handler.handle(&mut streaming_el) {
+                    *shared.error.borrow_mut() = Some(e);
+                }
+                Ok(())
+            }));
+        }
 
-        // Process element handlers
-        for (idx, (selector, _)) in self.inner.handlers.element_handlers_mut().iter().enumerate() {
-            let selector_owned = selector.clone();
-            let error_clone = Rc::clone(&error_cell);
-            let elem_count_clone = Rc::clone(&element_count);
+        // lol_html may split a text node into several chunks, so chunks are buffered
+        // until the last one and handlers see whole text nodes.
+        for (selector, mut handler) in std::mem::take(handlers.text_handlers_mut()) {
+            let shared = Rc::clone(&shared);
+            let mut buffer = String::new();
+            element_handlers.push(lol_html::text!(selector, move |chunk| {
+                if shared.error.borrow().is_some() {
+                    return Ok(());
+                }
 
-            element_handlers.push(lol_html::element!(selector_owned, move |el| {
-                // Stop processing if previous handler failed
-                if error_clone.borrow().is_some() {
+                buffer.push_str(chunk.as_str());
+                if !chunk.last_in_text_node() || buffer.is_empty() {
                     return Ok(());
                 }
+                shared.text_nodes.set(shared.text_nodes.get() + 1);
 
-                // Increment element count
-                *elem_count_clone.borrow_mut() += 1;
-
-                // Get handler
-                // SAFETY: handlers_ptr points to self.inner.handlers which:
-                // 1. Is valid for the entire duration of this method
-                // 2. Will not be moved or dropped while HtmlRewriter is active
-                // 3. We are in single-threaded context (Rc<RefCell> is not Send)
-                // 4. Each handler is accessed at most once per element
-                #[allow(unsafe_code)]
-                let handler =
-                    unsafe { (*handlers_ptr).element_handlers_mut().get_mut(idx).map(|(_, h)| h) };
-
-                if let Some(handler) = handler {
-                    let mut streaming_el = StreamingElement::new(el);
-                    if let Err(e) = handler.handle(&mut streaming_el) {
-                        *error_clone.borrow_mut() = Some(e);
-                    }
+                if let Err(e) = handler.handle(&buffer) {
+                    *shared.error.borrow_mut() = Some(e);
                 }
+                buffer.clear();
+                Ok(())
+            }));
+        }
 
+        // Capture handlers wrap each match in marker comments; the marked HTML is cut out
+        // of the output and parsed once the match has been rewritten.
+        for (idx, selector) in handlers.capture_selectors().enumerate() {
+            let shared = Rc::clone(&shared);
+            element_handlers.push(lol_html::element!(selector, move |el| {
+                let mut captures = shared.captures.borrow_mut();
+                let n = captures.len();
+                captures.push((idx, el.tag_name()));
+                el.before(
+                    &format!("{CAPTURE_MARKER}{n}-->"),
+                    lol_html::html_content::ContentType::Html,
+                );
+                el.after(
+                    &format!("{CAPTURE_MARKER}/{n}-->"),
+                    lol_html::html_content::ContentType::Html,
+                );
                 Ok(())
             }));
         }
 
-        // Build lol_html settings
         let settings = element_handlers.into_iter().fold(
             lol_html::Settings::new()
                 .with_encoding(
                     AsciiCompatibleEncoding::new(encoding_rs::UTF_8)
                         .expect("UTF-8 is always ASCII-compatible"),
                 )
-                .with_strict(self.inner.config.strict_mode)
+                .with_strict(config.strict_mode)
                 .with_adjust_charset_on_meta_tag(true),
             lol_html::Settings::append_element_content_handler,
         );
 
-        // Create output sink
-        let mut output = Vec::new();
+        let sink = Rc::clone(&shared);
+        let output: OutputFn =
+            Box::new(move |chunk: &[u8]| sink.output.borrow_mut().extend_from_slice(chunk));
+        let rewriter = lol_html::HtmlRewriter::new(settings, output);
 
-        // Create rewriter
-        let mut rewriter = lol_html::HtmlRewriter::new(settings, |chunk: &[u8]| {
-            output.extend_from_slice(chunk);
-        });
-
-        // Write chunk through rewriter
-        rewriter
-            .write(chunk)
-            .map_err(|e| Error::handler_error(format!("lol_html write failed: {e}")))?;
+        Some(Self { rewriter, shared, pending: Vec::new() })
+    }
+}
 
-        // Finish rewriter to flush remaining output
-        rewriter.end().map_err(|e| Error::handler_error(format!("lol_html end failed: {e}")))?;
+impl StreamingSoupInner {
+    /// Moves rewritten output to the output buffer, updates stats, and runs capture
+    /// handlers for the matches completed so far.
+    ///
+    /// Output is held back while a capture is open, unless the input has ended.
+    fn drain(&mut self, shared: &Shared, pending: &mut Vec<u8>, at_end: bool) -> Result<()> {
+        self.stats.elements_count += shared.elements.take();
+        self.stats.text_nodes_count += shared.text_nodes.take();
 
-        // Update stats with counts from this chunk
-        self.inner.stats.elements_count += *element_count.borrow();
-        self.inner.stats.text_nodes_count += *text_count.borrow();
+        if let Some(error) = shared.error.borrow_mut().take() {
+            return Err(error);
+        }
 
-        // Append output to buffer
-        self.inner.output_buffer.extend_from_slice(&output);
+        let mut output = shared.output.take();
+        if self.handlers.capture_count() == 0 {
+            self.output_buffer.append(&mut output);
+            return Ok(());
+        }
 
-        // Check if any handler failed
-        if let Some(error) = error_cell.borrow_mut().take() {
-            return Err(error);
+        // Without a new marker, no capture has opened or closed since the last call.
+        if !at_end && memchr::memmem::find(&output, CAPTURE_MARKER.as_bytes()).is_none() {
+            if pending.is_empty() {
+                self.output_buffer.append(&mut output);
+            } else {
+                pending.append(&mut output);
+            }
+            return Ok(());
         }
+        pending.append(&mut output);
 
+        let (cleaned, mut captured, open) = split_captures(pending);
+        if open && !at_end {
+            return Ok(());
+        }
+        pending.clear();
+        self.output_buffer.extend_from_slice(&cleaned);
+
+        // Run capture handlers in document order
+        captured.sort_unstable_by_key(|&(n, _)| n);
+        let registered = shared.captures.borrow();
+        for (n, range) in captured {
+            let Some((idx, tag_name)) = registered.get(n) else { continue };
+            let html = String::from_utf8_lossy(&cleaned[range]);
+            let soup = Soup::parse_fragment_with_context(&html, fragment_context(tag_name));
+            if let Some((_, handler)) = self.handlers.capture_handlers_mut().get_mut(*idx) {
+                handler.handle(&soup)?;
+                self.stats.captured_count += 1;
+            }
+        }
         Ok(())
     }
+}
+
+impl StreamingSoup<state::Processing> {
+    /// Writes a chunk of HTML to the streaming parser.
+    ///
+    /// The chunk will be processed and any registered handlers will be called
+    /// for matching elements/text. Chunks may be split anywhere, including inside tags,
+    /// entities, and multi-byte characters; each element is matched once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parsing fails or a handler returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// processor.write(b"<div cla")?;
+    /// processor.write(b"ss=\"a\">content</div>")?;
+    /// ```
+    pub fn write(&mut self, chunk: &[u8]) -> Result<()> {
+        // Update stats
+        self.inner.stats.bytes_processed += chunk.len();
+
+        // No handlers registered - just pass through
+        let Some(mut session) = self.inner.session.take() else {
+            self.inner.output_buffer.extend_from_slice(chunk);
+            return Ok(());
+        };
+
+        let result = session
+            .rewriter
+            .write(chunk)
+            .map_err(|e| Error::handler_error(format!("lol_html write failed: {e}")));
+        let drained = self.inner.drain(&session.shared, &mut session.pending, false);
+        self.inner.session = Some(session);
+        drained.and(result)
+    }
 
     /// Writes multiple chunks to the streaming parser.
     ///
@@ -350,6 +475,34 @@ impl StreamingSoup<state::Processing> {
         Ok(())
     }
 
+    /// Writes `data` in chunks split at the boundaries `pattern` picks, such as inside
+    /// tags, entities, and multi-byte characters.
+    ///
+    /// Handlers that produce the same results here as with a single [`write`](Self::write)
+    /// do not depend on how the input arrives.
+    ///
+    /// Requires the `test-support` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parsing fails or a handler returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use scrape_core::streaming::FragmentPattern;
+    ///
+    /// processor.write_fragmented(b"<p>Caf\xc3\xa9 &amp; tea</p>", FragmentPattern::Adversarial)?;
+    /// ```
+    #[cfg(feature = "test-support")]
+    pub fn write_fragmented(
+        &mut self,
+        data: &[u8],
+        pattern: super::fragment::FragmentPattern,
+    ) -> Result<()> {
+        self.write_all(super::fragment::fragment(data, pattern).into_iter())
+    }
+
     /// Finishes processing and transitions to Finished state.
     ///
     /// After calling this method, you can access statistics via `stats()`.
@@ -357,11 +510,162 @@ impl StreamingSoup<state::Processing> {
     /// # Errors
     ///
     /// Returns an error if finalizing the parse fails.
-    pub fn end(self) -> Result<StreamingSoup<state::Finished>> {
+    pub fn end(mut self) -> Result<StreamingSoup<state::Finished>> {
+        if let Some(Session { rewriter, shared, mut pending }) = self.inner.session.take() {
+            let result = rewriter
+                .end()
+                .map_err(|e| Error::handler_error(format!("lol_html end failed: {e}")));
+            self.inner.drain(&shared, &mut pending, true)?;
+            result?;
+        }
         Ok(StreamingSoup { inner: self.inner, _state: PhantomData })
     }
 }
 
+/// Removes capture markers from rewritten output.
+///
+/// Returns the output without markers; for each complete marker pair, its number and
+/// the range of the cleaned output between the pair; and whether a pair is still open.
+fn split_captures(output: &[u8]) -> (Vec<u8>, CaptureRanges, bool) {
+    let mut cleaned = Vec::with_capacity(output.len());
+    let mut starts = Vec::new();
+    let mut ranges = Vec::new();
+    let mut rest = output;
+    while let Some(pos) = memchr::memmem::find(rest, CAPTURE_MARKER.as_bytes()) {
+        cleaned.extend_from_slice(&rest[..pos]);
+        let marker = &rest[pos + CAPTURE_MARKER.len()..];
+        let Some(end) = memchr::memmem::find(marker, b"-->") else {
+            cleaned.extend_from_slice(&rest[pos..]);
+            rest = &[];
+            break;
+        };
+        let label = std::str::from_utf8(&marker[..end]).unwrap_or_default();
+        if let Some(n) = label.strip_prefix('/').and_then(|n| n.parse::<usize>().ok()) {
+            if let Some(pos) = starts.iter().rposition(|&(open, _)| open == n) {
+                let (_, start) = starts.remove(pos);
+                ranges.push((n, start..cleaned.len()));
+            }
+        } else if let Ok(n) = label.parse::<usize>() {
+            starts.push((n, cleaned.len()));
+        }
+        rest = &marker[end + 3..];
+    }
+    cleaned.extend_from_slice(rest);
+    (cleaned, ranges, !starts.is_empty())
+}
+
+/// Returns the fragment parsing context that keeps an element named `tag_name`.
+///
+/// Table parts and options are dropped when parsed in the default `body` context.
+fn fragment_context(tag_name: &str) -> &'static str {
+    match tag_name {
+        "tr" => "tbody",
+        "td" | "th" => "tr",
+        "thead" | "tbody" | "tfoot" | "caption" | "colgroup" => "table",
+        "col" => "colgroup",
+        "option" | "optgroup" => "select",
+        _ => "body",
+    }
+}
+
+#[cfg(feature = "async")]
+// The futures hold the started parser, which is not `Send`; run them on the current thread.
+#[allow(clippy::future_not_send)]
+impl StreamingSoup<state::Idle> {
+    /// Reads `reader` to the end, feeding it through the registered handlers.
+    ///
+    /// Reads in chunks of [`StreamingConfig::buffer_size`] bytes, so the document is never
+    /// buffered in full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, parsing fails, or a handler returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut streaming = StreamingSoup::new();
+    /// streaming.on_element("a[href]", |el| Ok(()))?;
+    /// let finished = streaming.process_async(response.into_async_read()).await?;
+    /// ```
+    pub async fn process_async<R>(self, reader: R) -> Result<StreamingSoup<state::Finished>>
+    where
+        R: futures_util::io::AsyncRead + Unpin,
+    {
+        let mut processor = self.start();
+        processor.write_async(reader).await?;
+        processor.end()
+    }
+
+    /// Consumes a stream of byte chunks, such as an HTTP response body, feeding each chunk
+    /// through the registered handlers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the stream yields an error, or an error if parsing fails or
+    /// a handler returns an error.
+    pub async fn process_stream<St, B, E>(
+        self,
+        stream: St,
+    ) -> Result<StreamingSoup<state::Finished>>
+    where
+        St: futures_util::Stream<Item = std::result::Result<B, E>> + Unpin,
+        B: AsRef<[u8]>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let mut processor = self.start();
+        processor.write_stream(stream).await?;
+        processor.end()
+    }
+}
+
+#[cfg(feature = "async")]
+// The futures hold the started parser, which is not `Send`; run them on the current thread.
+#[allow(clippy::future_not_send)]
+impl StreamingSoup<state::Processing> {
+    /// Reads `reader` to the end and writes each chunk with [`write`](Self::write).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, parsing fails, or a handler returns an error.
+    pub async fn write_async<R>(&mut self, mut reader: R) -> Result<()>
+    where
+        R: futures_util::io::AsyncRead + Unpin,
+    {
+        use futures_util::io::AsyncReadExt;
+
+        let mut buf = vec![0; self.inner.config.buffer_size.max(1)];
+        loop {
+            let n = reader.read(&mut buf).await?;
+            if n == 0 {
+                return Ok(());
+            }
+            self.write(&buf[..n])?;
+        }
+    }
+
+    /// Writes every chunk of `stream` with [`write`](Self::write).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the stream yields an error, or an error if parsing fails or
+    /// a handler returns an error.
+    pub async fn write_stream<St, B, E>(&mut self, mut stream: St) -> Result<()>
+    where
+        St: futures_util::Stream<Item = std::result::Result<B, E>> + Unpin,
+        B: AsRef<[u8]>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        use futures_util::StreamExt;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(std::io::Error::other)?;
+            self.write(chunk.as_ref())?;
+        }
+        Ok(())
+    }
+}
+
 impl StreamingSoup<state::Finished> {
     /// Returns statistics about the streaming parse.
     #[must_use]
@@ -448,6 +752,14 @@ mod tests {
         let mut streaming = StreamingSoup::new();
         let result = streaming.on_element("", |_el| Ok(()));
         assert!(result.is_err());
+
+        let result = streaming.on_element("li:last-child", |_el| Ok(()));
+        assert!(matches!(result, Err(Error::StreamingSelectorError { .. })));
+        assert!(streaming.on_text("h1 + p", |_text| Ok(())).is_err());
+        assert!(streaming.on_end_tag("div:has(a)", |_tag| Ok(())).is_err());
+        let err = streaming.on_text("p[", |_text| Ok(())).err().unwrap();
+        assert_eq!(err.span().map(|span| span.start.line), Some(1));
+        assert_eq!(streaming.inner.handlers.element_count(), 0);
     }
 
     #[test]
@@ -463,11 +775,201 @@ mod tests {
         assert_eq!(finished.stats().bytes_processed, 15); // "<div>" + "test" + "</div>" = 5 + 4 + 6 = 15 bytes
     }
 
+    #[cfg(feature = "test-support")]
+    #[test]
+    fn test_write_fragmented() {
+        use crate::streaming::FragmentPattern;
+
+        let html = "<p class=\"x\">Caf\u{e9} &amp; tea</p>";
+        for pattern in [FragmentPattern::EveryByte, FragmentPattern::Seeded(3)] {
+            let mut processor = StreamingSoup::new().start();
+            processor.write_fragmented(html.as_bytes(), pattern).unwrap();
+            let finished = processor.end().unwrap();
+            assert_eq!(finished.stats().bytes_processed, html.len());
+            assert_eq!(finished.output(), html.as_bytes());
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        use std::task::{Context, Poll, Waker};
+
+        let mut future = std::pin::pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_process_async_reader() {
+        let links = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut streaming = StreamingSoup::with_config(StreamingConfig::new().buffer_size(64));
+        let sink = std::sync::Arc::clone(&links);
+        streaming
+            .on_element("a[href]", move |el| {
+                sink.lock().unwrap().extend(el.get_attribute("href"));
+                Ok(())
+            })
+            .unwrap();
+
+        let html = b"<p><a href='/1'>1</a></p>".repeat(10);
+        let reader = futures_util::io::Cursor::new(html.clone());
+        let finished = block_on(streaming.process_async(reader)).unwrap();
+
+        assert_eq!(finished.stats().bytes_processed, html.len());
+        assert!(!links.lock().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_process_stream() {
+        let chunks: Vec<std::io::Result<&[u8]>> =
+            vec![Ok(b"<div>".as_slice()), Ok(b"test".as_slice()), Ok(b"</div>".as_slice())];
+        let finished =
+            block_on(StreamingSoup::new().process_stream(futures_util::stream::iter(chunks)))
+                .unwrap();
+        assert_eq!(finished.stats().bytes_processed, 15);
+        assert_eq!(finished.output(), b"<div>test</div>");
+
+        let failing: Vec<std::io::Result<&[u8]>> =
+            vec![Ok(b"<div>".as_slice()), Err(std::io::Error::other("connection reset"))];
+        let result =
+            block_on(StreamingSoup::new().process_stream(futures_util::stream::iter(failing)));
+        assert!(matches!(result, Err(Error::Io(_))));
+    }
+
     #[test]
     fn test_streaming_stats_default() {
         let stats = StreamingStats::default();
         assert_eq!(stats.bytes_processed, 0);
         assert_eq!(stats.elements_count, 0);
         assert_eq!(stats.text_nodes_count, 0);
+        assert_eq!(stats.captured_count, 0);
+    }
+
+    #[test]
+    fn test_text_handler_receives_whole_text_nodes() {
+        let texts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&texts);
+        let mut streaming = StreamingSoup::with_config(StreamingConfig::new().buffer_size(16));
+        streaming
+            .on_text("p", move |text| {
+                sink.lock().unwrap().push(text.to_string());
+                Ok(())
+            })
+            .unwrap();
+
+        let long = "word ".repeat(2000);
+        let html = format!("<p>{long}</p><div>skip</div><p>Caf\u{e9} &amp; tea</p>");
+        let mut processor = streaming.start();
+        processor.write(html.as_bytes()).unwrap();
+        let finished = processor.end().unwrap();
+
+        assert_eq!(*texts.lock().unwrap(), [long.as_str(), "Caf\u{e9} &amp; tea"]);
+        assert_eq!(finished.stats().text_nodes_count, 2);
+        assert_eq!(finished.output(), html.as_bytes());
+    }
+
+    #[test]
+    fn test_text_handler_error() {
+        let mut streaming = StreamingSoup::new();
+        streaming.on_text("p", |_text| Err(Error::handler_error("stop"))).unwrap();
+        let mut processor = streaming.start();
+        assert!(processor.write(b"<p>x</p>").is_err());
+    }
+
+    #[test]
+    fn test_handlers_span_chunk_boundaries() {
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut streaming = StreamingSoup::new();
+        let sink = std::sync::Arc::clone(&events);
+        streaming
+            .on_element("div.box p", move |el| {
+                sink.lock().unwrap().push(format!("p {:?}", el.get_attribute("id")));
+                Ok(())
+            })
+            .unwrap();
+        let sink = std::sync::Arc::clone(&events);
+        streaming
+            .on_text("p", move |text| {
+                sink.lock().unwrap().push(format!("text {text}"));
+                Ok(())
+            })
+            .unwrap();
+        let sink = std::sync::Arc::clone(&events);
+        streaming
+            .capture("ul", move |soup| {
+                sink.lock().unwrap().push(format!("ul {:?}", soup.select_text("li").unwrap()));
+                Ok(())
+            })
+            .unwrap();
+
+        let html =
+            "<div class=\"box\"><p id=\"a\">Hello world</p></div><ul><li>1</li><li>2</li></ul>";
+        let mut processor = streaming.start();
+        for chunk in html.as_bytes().chunks(3) {
+            processor.write(chunk).unwrap();
+        }
+        let finished = processor.end().unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            ["p Some(\"a\")", "text Hello world", "ul [\"1\", \"2\"]"]
+        );
+        assert_eq!(finished.stats().elements_count, 1);
+        assert_eq!(finished.stats().text_nodes_count, 1);
+        assert_eq!(finished.stats().captured_count, 1);
+        assert_eq!(finished.output(), html.as_bytes());
+    }
+
+    #[test]
+    fn test_split_captures() {
+        let output = format!(
+            "<ul>{CAPTURE_MARKER}0--><li>A{CAPTURE_MARKER}1--><b>x</b>{CAPTURE_MARKER}/1--></\
+             li>{CAPTURE_MARKER}/0--></ul>{CAPTURE_MARKER}2--><p>"
+        );
+        let (cleaned, ranges, open) = split_captures(output.as_bytes());
+        assert_eq!(cleaned, b"<ul><li>A<b>x</b></li></ul><p>");
+        assert!(open);
+        let captured: Vec<_> = ranges.into_iter().map(|(n, range)| (n, &cleaned[range])).collect();
+        assert_eq!(captured, [(1, b"<b>x</b>".as_slice()), (0, b"<li>A<b>x</b></li>")]);
+    }
+
+    #[test]
+    fn test_capture_builds_subtree_soups() {
+        let rows = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&rows);
+        let mut streaming = StreamingSoup::new();
+        streaming
+            .capture("tr.item", move |soup| {
+                let cells = soup.select_text("td").unwrap();
+                sink.lock().unwrap().push(cells.join("|"));
+                Ok(())
+            })
+            .unwrap();
+
+        let html = "<table><tr class=\"item\"><td>a</td><td>1</td></tr><tr><td>skip</td></tr><tr \
+                    class=\"item\"><td>b</td><td>2</td></tr></table>";
+        let mut processor = streaming.start();
+        processor.write(html.as_bytes()).unwrap();
+        let finished = processor.end().unwrap();
+
+        assert_eq!(*rows.lock().unwrap(), ["a|1", "b|2"]);
+        assert_eq!(finished.stats().captured_count, 2);
+        assert_eq!(finished.output(), html.as_bytes());
+    }
+
+    #[test]
+    fn test_capture_handler_error() {
+        let mut streaming = StreamingSoup::new();
+        streaming.capture("p", |_soup| Err(Error::handler_error("stop"))).unwrap();
+        assert!(streaming.capture("p:last-child", |_soup| Ok(())).is_err());
+
+        let mut processor = streaming.start();
+        assert!(processor.write(b"<p>x</p>").is_err());
     }
 }
@@ -1,54 +1,109 @@
 //! HTML rewriter for streaming modification.
 
-use std::io::Write;
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+    rc::Rc,
+};
+
+use lol_html::AsciiCompatibleEncoding;
 
 use crate::{
     Error, Result,
-    streaming::{RewriterConfig, StreamingElement},
+    streaming::{RewriterConfig, StreamingElement, support},
 };
 
-/// HTML rewriter for modifying HTML during streaming.
+/// Type alias for element handler functions.
+type ElementHandlerFn = Box<dyn FnMut(&mut StreamingElement) -> Result<()> + Send>;
+
+/// Type alias for the output callback of the underlying rewriter.
+type OutputFn<'h> = Box<dyn FnMut(&[u8]) + 'h>;
+
+/// Destination for rewritten HTML.
 ///
-/// This type allows you to register handlers that modify HTML elements,
-/// text, and other content as it is being processed. The modified HTML
-/// is output to a buffer or writer.
+/// Implemented for `Vec<u8>` (collects output in memory), for closures taking `&[u8]`
+/// (e.g. forwarding to a channel or socket), and for any [`Write`] through [`WriterSink`].
+pub trait OutputSink {
+    /// Receives the next chunk of rewritten output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk cannot be written; rewriting stops at the next
+    /// [`RewriterSession::write`] or [`RewriterSession::end`].
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<()>;
+}
+
+impl OutputSink for Vec<u8> {
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<()> {
+        self.extend_from_slice(chunk);
+        Ok(())
+    }
+}
+
+impl<F: FnMut(&[u8])> OutputSink for F {
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<()> {
+        self(chunk);
+        Ok(())
+    }
+}
+
+/// [`OutputSink`] adapter for any [`Write`] implementation.
 ///
 /// # Examples
 ///
 /// ```ignore
-/// use scrape_core::HtmlRewriter;
-///
-/// let mut rewriter = HtmlRewriter::new();
-///
-/// rewriter.on_element("img", |el| {
-///     el.set_attribute("loading", "lazy")?;
-///     Ok(())
-/// })?;
-///
-/// let output = rewriter.process("<img src='test.jpg'>")?;
-/// assert!(output.contains("loading=\"lazy\""));
+/// let stdout = std::io::stdout();
+/// let mut session = rewriter.start(WriterSink(stdout.lock()));
 /// ```
-/// Type alias for element handler functions.
-type ElementHandlerFn = Box<dyn FnMut(&mut StreamingElement) -> Result<()> + Send>;
+#[derive(Debug)]
+pub struct WriterSink<W: Write>(pub W);
+
+impl<W: Write> WriterSink<W> {
+    /// Returns the wrapped writer.
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+}
+
+impl<W: Write> OutputSink for WriterSink<W> {
+    fn write_chunk(&mut self, chunk: &[u8]) -> io::Result<()> {
+        self.0.write_all(chunk)
+    }
+}
 
 /// In-flight HTML rewriter for modifying elements during streaming.
 ///
-/// Processes HTML chunks, allowing modification of attributes and content
-/// as elements are encountered. Useful for transformations like adding `loading="lazy"`
-/// to images or rewriting URLs.
+/// Register handlers with [`on_element`](Self::on_element), then either rewrite a whole
+/// document with [`process`](Self::process) or feed it chunk by chunk through a
+/// [`RewriterSession`] created with [`start`](Self::start). Elements split across chunk
+/// boundaries are handled, and output is flushed to the sink as soon as it is ready, so
+/// the rewriter can sit between an upstream response and a client as a transformation
+/// proxy.
+///
+/// # Examples
 ///
-/// # Example
+/// ```rust
+/// use scrape_core::{ContentType, HtmlRewriter};
 ///
-/// ```ignore
 /// let mut rewriter = HtmlRewriter::new();
-/// rewriter.on_element("img", |el| {
-///     el.set_attribute("loading", "lazy");
+/// rewriter.on_element("img", |el| el.set_attribute("loading", "lazy"))?;
+/// rewriter.on_element("script", |el| {
+///     el.remove();
+///     Ok(())
+/// })?;
+/// rewriter.on_element("body", |el| {
+///     el.append("<footer>proxied</footer>", ContentType::Html);
 ///     Ok(())
 /// })?;
+///
+/// let output = rewriter.process(r#"<body><img src="a.jpg"><script>x()</script></body>"#)?;
+/// assert_eq!(output, r#"<body><img src="a.jpg" loading="lazy"><footer>proxied</footer></body>"#);
+/// # Ok::<(), scrape_core::Error>(())
 /// ```
 pub struct HtmlRewriter {
-    _config: RewriterConfig,
-    _element_handlers: Vec<(String, ElementHandlerFn)>,
+    config: RewriterConfig,
+    element_handlers: Vec<(String, ElementHandlerFn)>,
 }
 
 impl HtmlRewriter {
@@ -61,14 +116,19 @@ impl HtmlRewriter {
     /// Creates a new HTML rewriter with the given configuration.
     #[must_use]
     pub fn with_config(config: RewriterConfig) -> Self {
-        Self { _config: config, _element_handlers: Vec::new() }
+        Self { config, element_handlers: Vec::new() }
     }
 
     /// Registers a handler for elements matching the given selector.
     ///
+    /// Handlers run in registration order. The handler receives a [`StreamingElement`]
+    /// that can change attributes, insert content around or inside the element, replace
+    /// it, or remove it.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the selector is invalid.
+    /// Returns an error if the selector is empty or not supported by the streaming
+    /// selector engine; see [`supports`](super::supports).
     ///
     /// # Examples
     ///
@@ -78,19 +138,78 @@ impl HtmlRewriter {
     ///     Ok(())
     /// })?;
     /// ```
-    pub fn on_element<F>(&mut self, selector: &str, _handler: F) -> Result<&mut Self>
+    pub fn on_element<F>(&mut self, selector: &str, handler: F) -> Result<&mut Self>
     where
-        F: FnMut(&mut StreamingElement) -> Result<()> + 'static,
+        F: FnMut(&mut StreamingElement) -> Result<()> + Send + 'static,
     {
-        // Basic validation
-        if selector.is_empty() {
-            return Err(Error::streaming_selector_error("selector cannot be empty"));
-        }
+        support::validate(selector)?;
 
-        // NOTE: Full implementation and selector validation deferred to Week 3
+        self.element_handlers.push((selector.to_string(), Box::new(handler)));
         Ok(self)
     }
 
+    /// Starts a chunked rewriting session that writes output to `sink`.
+    ///
+    /// The session borrows the rewriter's handlers until it is ended or dropped.
+    ///
+    /// # Panics
+    ///
+    /// This method contains an `expect()` that should never panic as UTF-8 is always
+    /// ASCII-compatible. If it panics, it indicates a bug in `lol_html`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut session = rewriter.start(Vec::new());
+    /// session.write(b"<div cla")?;
+    /// session.write(b"ss=\"a\">content</div>")?;
+    /// let output: Vec<u8> = session.end()?;
+    /// ```
+    pub fn start<'h, O: OutputSink + 'h>(&'h mut self, sink: O) -> RewriterSession<'h, O> {
+        let error_cell: Rc<RefCell<Option<Error>>> = Rc::new(RefCell::new(None));
+
+        let element_handlers: Vec<_> = self
+            .element_handlers
+            .iter_mut()
+            .map(|(selector, handler)| {
+                let error_clone = Rc::clone(&error_cell);
+                lol_html::element!(selector, move |el| {
+                    let mut streaming_el = StreamingElement::new(el);
+                    handler(&mut streaming_el).map_err(|e| {
+                        let message = e.to_string();
+                        *error_clone.borrow_mut() = Some(e);
+                        message.into()
+                    })
+                })
+            })
+            .collect();
+
+        let settings = element_handlers.into_iter().fold(
+            lol_html::Settings::new()
+                .with_encoding(
+                    AsciiCompatibleEncoding::new(encoding_rs::UTF_8)
+                        .expect("UTF-8 is always ASCII-compatible"),
+                )
+                .with_strict(self.config.strict_mode)
+                .with_adjust_charset_on_meta_tag(true),
+            lol_html::Settings::append_element_content_handler,
+        );
+
+        let sink = Rc::new(RefCell::new(SinkState { sink, error: None }));
+        let sink_clone = Rc::clone(&sink);
+        let output: OutputFn<'h> = Box::new(move |chunk: &[u8]| {
+            let state = &mut *sink_clone.borrow_mut();
+            if state.error.is_none()
+                && let Err(e) = state.sink.write_chunk(chunk)
+            {
+                state.error = Some(e);
+            }
+        });
+        let rewriter = lol_html::HtmlRewriter::new(settings, output);
+
+        RewriterSession { rewriter, sink, error: error_cell }
+    }
+
     /// Processes HTML string and returns modified output.
     ///
     /// # Errors
@@ -113,19 +232,22 @@ impl HtmlRewriter {
     ///
     /// Returns an error if parsing or rewriting fails.
     pub fn process_bytes(&mut self, html: &[u8]) -> Result<Vec<u8>> {
-        // NOTE: Actual implementation deferred to Week 3
-        // For now, just return input unchanged
-        Ok(html.to_vec())
+        let mut session = self.start(Vec::with_capacity(html.len()));
+        session.write(html)?;
+        session.end()
     }
 
     /// Processes HTML and writes output to the given writer.
     ///
+    /// Output is written as it is produced rather than buffered in full.
+    ///
     /// # Errors
     ///
     /// Returns an error if parsing, rewriting, or writing fails.
-    pub fn process_to<W: Write>(&mut self, html: &[u8], mut output: W) -> Result<()> {
-        let result = self.process_bytes(html)?;
-        output.write_all(&result)?;
+    pub fn process_to<W: Write>(&mut self, html: &[u8], output: W) -> Result<()> {
+        let mut session = self.start(WriterSink(output));
+        session.write(html)?;
+        session.end()?.0.flush()?;
         Ok(())
     }
 }
@@ -136,9 +258,79 @@ impl Default for HtmlRewriter {
     }
 }
 
+struct SinkState<O> {
+    sink: O,
+    error: Option<io::Error>,
+}
+
+/// Chunked rewriting session created by [`HtmlRewriter::start`].
+///
+/// A single underlying rewriter spans all chunks, so tags, attributes, and text may be
+/// split at arbitrary byte boundaries.
+pub struct RewriterSession<'h, O: OutputSink> {
+    rewriter: lol_html::HtmlRewriter<'h, OutputFn<'h>>,
+    sink: Rc<RefCell<SinkState<O>>>,
+    error: Rc<RefCell<Option<Error>>>,
+}
+
+impl<O: OutputSink> RewriterSession<'_, O> {
+    /// Rewrites the next chunk of input, passing any completed output to the sink.
+    ///
+    /// # Errors
+    ///
+    /// Returns the handler's error if a handler fails, [`Error::Io`] if the sink fails,
+    /// or an error if parsing fails.
+    pub fn write(&mut self, chunk: &[u8]) -> Result<()> {
+        let result = self.rewriter.write(chunk);
+        self.check(result)
+    }
+
+    /// Finishes rewriting, flushes the remaining output, and returns the sink.
+    ///
+    /// # Errors
+    ///
+    /// Returns the handler's error if a handler fails, [`Error::Io`] if the sink fails,
+    /// or an error if parsing fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sink is still shared after the underlying rewriter is dropped, which
+    /// indicates a bug in this crate.
+    pub fn end(self) -> Result<O> {
+        let Self { rewriter, sink, error } = self;
+        let result = rewriter.end();
+        Self::check_parts(&sink, &error, result)?;
+
+        let state = Rc::into_inner(sink).expect("output sink is no longer shared").into_inner();
+        Ok(state.sink)
+    }
+
+    fn check(
+        &self,
+        result: std::result::Result<(), lol_html::errors::RewritingError>,
+    ) -> Result<()> {
+        Self::check_parts(&self.sink, &self.error, result)
+    }
+
+    fn check_parts(
+        sink: &RefCell<SinkState<O>>,
+        error: &RefCell<Option<Error>>,
+        result: std::result::Result<(), lol_html::errors::RewritingError>,
+    ) -> Result<()> {
+        if let Some(e) = error.borrow_mut().take() {
+            return Err(e);
+        }
+        if let Some(e) = sink.borrow_mut().error.take() {
+            return Err(Error::Io(e));
+        }
+        result.map_err(|e| Error::handler_error(format!("lol_html rewrite failed: {e}")))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::streaming::ContentType;
 
     #[test]
     fn test_rewriter_new() {
@@ -163,6 +355,12 @@ mod tests {
         let mut rewriter = HtmlRewriter::new();
         let result = rewriter.on_element("", |_el| Ok(()));
         assert!(result.is_err());
+
+        let result = rewriter.on_element("div[", |_el| Ok(()));
+        assert!(matches!(result, Err(Error::StreamingSelectorError { .. })));
+
+        let err = rewriter.on_element("p:empty", |_el| Ok(())).err().unwrap();
+        assert!(err.to_string().contains("`:empty` is not supported"), "{err}");
     }
 
     #[test]
@@ -191,4 +389,121 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(output, input);
     }
+
+    #[test]
+    fn test_set_and_remove_attribute() {
+        let mut rewriter = HtmlRewriter::new();
+        rewriter
+            .on_element("a[href]", |el| el.set_attribute("rel", "noopener"))
+            .unwrap()
+            .on_element("a", |el| {
+                el.remove_attribute("onclick");
+                Ok(())
+            })
+            .unwrap();
+
+        let output = rewriter.process(r#"<a href="/x" onclick="f()">x</a><a>y</a>"#).unwrap();
+        assert_eq!(output, r#"<a href="/x" rel="noopener">x</a><a>y</a>"#);
+    }
+
+    #[test]
+    fn test_remove_and_replace() {
+        let mut rewriter = HtmlRewriter::new();
+        rewriter
+            .on_element("script", |el| {
+                el.remove();
+                Ok(())
+            })
+            .unwrap()
+            .on_element("b", |el| {
+                el.replace("<strong>bold</strong>", ContentType::Html);
+                Ok(())
+            })
+            .unwrap()
+            .on_element("span", |el| {
+                el.remove_and_keep_content();
+                Ok(())
+            })
+            .unwrap();
+
+        let output =
+            rewriter.process("<p><script>x()</script><b>b</b> <span>kept</span></p>").unwrap();
+        assert_eq!(output, "<p><strong>bold</strong> kept</p>");
+    }
+
+    #[test]
+    fn test_prepend_append_before_after() {
+        let mut rewriter = HtmlRewriter::new();
+        rewriter
+            .on_element("ul", |el| {
+                el.prepend("<li>first</li>", ContentType::Html);
+                el.append("<li>last</li>", ContentType::Html);
+                el.before("<b>", ContentType::Text);
+                el.after("<hr>", ContentType::Html);
+                Ok(())
+            })
+            .unwrap();
+
+        let output = rewriter.process("<ul><li>mid</li></ul>").unwrap();
+        assert_eq!(output, "&lt;b&gt;<ul><li>first</li><li>mid</li><li>last</li></ul><hr>");
+    }
+
+    #[test]
+    fn test_handler_error_stops_rewriting() {
+        let mut rewriter = HtmlRewriter::new();
+        rewriter.on_element("p", |_el| Err(Error::handler_error("rejected"))).unwrap();
+
+        let err = rewriter.process("<div><p>x</p></div>").unwrap_err();
+        assert!(matches!(err, Error::HandlerError { message } if message == "rejected"));
+    }
+
+    #[test]
+    fn test_session_chunk_boundaries() {
+        let mut rewriter = HtmlRewriter::new();
+        rewriter.on_element("img.lazy", |el| el.set_attribute("loading", "lazy")).unwrap();
+
+        let input = r#"<div><img class="lazy" src="a.png"><img src="b.png"></div>"#;
+        let mut session = rewriter.start(Vec::new());
+        for chunk in input.as_bytes().chunks(3) {
+            session.write(chunk).unwrap();
+        }
+        let output = session.end().unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"<div><img class="lazy" src="a.png" loading="lazy"><img src="b.png"></div>"#
+        );
+    }
+
+    #[test]
+    fn test_callback_sink() {
+        let mut rewriter = HtmlRewriter::new();
+        rewriter.on_element("i", |el| el.set_attribute("x", "1")).unwrap();
+
+        let mut chunks = Vec::new();
+        let mut session = rewriter.start(|chunk: &[u8]| chunks.push(chunk.to_vec()));
+        session.write(b"<i>a</i>").unwrap();
+        let _ = session.end().unwrap();
+
+        assert!(!chunks.is_empty());
+        assert_eq!(chunks.concat(), br#"<i x="1">a</i>"#);
+    }
+
+    #[test]
+    fn test_writer_sink_error() {
+        struct FailingWriter;
+
+        impl Write for FailingWriter {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("closed"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut rewriter = HtmlRewriter::new();
+        let err = rewriter.process_to(b"<div>test</div>", FailingWriter).unwrap_err();
+        assert!(matches!(err, Error::Io(_)));
+    }
 }
@@ -0,0 +1,205 @@
+//! Which selectors the streaming parser can match.
+//!
+//! The streaming parser decides whether an element matches when its start tag arrives, so
+//! it cannot evaluate selectors that depend on later content or on earlier siblings, such
+//! as `:last-child`, `:has()`, or `h1 + p`. [`supports`] names the first such feature in a
+//! selector, and the handler registrations of [`StreamingSoup`](super::StreamingSoup) and
+//! [`HtmlRewriter`](super::HtmlRewriter) reject those selectors with the same message
+//! instead of never matching.
+//!
+//! Supported are type, universal, `#id`, `.class`, and attribute selectors (including the
+//! `i` and `s` flags, with attribute names in lower case), `:first-child`, `:nth-child(An+B)`,
+//! `:first-of-type`, `:nth-of-type(An+B)`, `:not()` of compound selectors, and the descendant and
+//! child combinators.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::streaming::{UnsupportedFeature, supports};
+//!
+//! assert!(supports("article > p.lead a[href^='https:' i]").is_ok());
+//! assert_eq!(
+//!     supports("li:last-child").unwrap_err().to_string(),
+//!     "`:last-child` is not supported when streaming"
+//! );
+//! assert!(matches!(supports("h1 + p"), Err(UnsupportedFeature::Feature(_))));
+//! ```
+
+use cssparser::ToCss;
+use selectors::parser::{Combinator, Component, NthType, Selector, SelectorList};
+
+use crate::{
+    Error, Result,
+    query::{QueryError, ScrapeSelector, parse_selector},
+};
+
+/// Why a selector cannot be used by the streaming parser.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum UnsupportedFeature {
+    /// The selector is not valid CSS.
+    #[error("{0}")]
+    InvalidSelector(String),
+    /// The selector uses a feature the streaming parser cannot evaluate, such as `:has()`.
+    #[error("{0} is not supported when streaming")]
+    Feature(String),
+    /// The streaming parser rejected a selector that passed the checks above.
+    #[error("rejected by the streaming parser: {0}")]
+    Rejected(String),
+}
+
+/// Checks whether the streaming parser can match `selector`.
+///
+/// # Errors
+///
+/// Returns the first feature of `selector` that the streaming parser cannot evaluate, or
+/// [`UnsupportedFeature::InvalidSelector`] if `selector` is not valid CSS.
+pub fn supports(selector: &str) -> std::result::Result<(), UnsupportedFeature> {
+    let list =
+        parse_selector(selector).map_err(|e| UnsupportedFeature::InvalidSelector(e.to_string()))?;
+    check_list(selector, &list)
+}
+
+/// Rejects empty and unsupported selectors for the handler registrations.
+///
+/// Syntax errors keep the position reported by the selector parser.
+pub(crate) fn validate(selector: &str) -> Result<()> {
+    if selector.is_empty() {
+        return Err(Error::streaming_selector_error("selector cannot be empty"));
+    }
+    let list = parse_selector(selector).map_err(|e| match e {
+        QueryError::InvalidSelector { message, span } => {
+            Error::StreamingSelectorError { message: format!("'{selector}': {message}"), span }
+        }
+        other @ QueryError::InvalidXPath { .. } => {
+            Error::streaming_selector_error(format!("'{selector}': {other}"))
+        }
+    })?;
+    check_list(selector, &list)
+        .map_err(|e| Error::streaming_selector_error(format!("'{selector}': {e}")))
+}
+
+fn check_list(
+    source: &str,
+    list: &SelectorList<ScrapeSelector>,
+) -> std::result::Result<(), UnsupportedFeature> {
+    for selector in list.slice() {
+        check(selector, false)?;
+    }
+    // The streaming parser has the last word, so nothing it rejects gets past here.
+    source
+        .parse::<lol_html::Selector>()
+        .map(drop)
+        .map_err(|e| UnsupportedFeature::Rejected(e.to_string()))
+}
+
+fn check(
+    selector: &Selector<ScrapeSelector>,
+    in_negation: bool,
+) -> std::result::Result<(), UnsupportedFeature> {
+    for component in selector.iter_raw_parse_order_from(0) {
+        let feature = match component {
+            Component::Combinator(combinator) => match combinator {
+                Combinator::NextSibling => "the `+` combinator".to_string(),
+                Combinator::LaterSibling => "the `~` combinator".to_string(),
+                _ if in_negation => "a combinator inside `:not()`".to_string(),
+                Combinator::Descendant | Combinator::Child => continue,
+                _ => "pseudo-elements".to_string(),
+            },
+            Component::LocalName(_)
+            | Component::ExplicitUniversalType
+            | Component::ID(_)
+            | Component::Class(_)
+            | Component::AttributeInNoNamespaceExists { .. }
+            | Component::AttributeInNoNamespace { .. } => continue,
+            // The streaming parser only takes attribute names as written in lower case.
+            Component::AttributeOther(attr)
+                if attr.namespace.is_none() && attr.local_name != attr.local_name_lower =>
+            {
+                "upper-case attribute names".to_string()
+            }
+            Component::AttributeOther(attr) if attr.namespace.is_none() => continue,
+            Component::AttributeOther(_)
+            | Component::Namespace(..)
+            | Component::DefaultNamespace(_)
+            | Component::ExplicitNoNamespace
+            | Component::ExplicitAnyNamespace => "namespace prefixes".to_string(),
+            Component::Nth(data) if matches!(data.ty, NthType::Child | NthType::OfType) => {
+                continue;
+            }
+            Component::Negation(_) if in_negation => "nested `:not()`".to_string(),
+            Component::Negation(list) => {
+                for inner in list.slice() {
+                    check(inner, true)?;
+                }
+                continue;
+            }
+            other => format!("`{}`", other.to_css_string()),
+        };
+        return Err(UnsupportedFeature::Feature(feature));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(selector: &str) -> String {
+        supports(selector).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_supported_selectors() {
+        for selector in [
+            "*",
+            "div.card#main",
+            "a[href], img[src$='.png' i], [lang|=en s]",
+            "ul > li:first-child:nth-child(2n+1)",
+            "tr:first-of-type td:nth-of-type(3)",
+            "p:not(.ad):not([hidden])",
+        ] {
+            assert_eq!(supports(selector), Ok(()), "{selector}");
+        }
+    }
+
+    #[test]
+    fn test_unsupported_features() {
+        assert_eq!(feature("h1 + p"), "the `+` combinator is not supported when streaming");
+        assert_eq!(feature("h1 ~ p"), "the `~` combinator is not supported when streaming");
+        assert_eq!(feature("li:last-child"), "`:last-child` is not supported when streaming");
+        assert_eq!(feature("li:only-child"), "`:only-child` is not supported when streaming");
+        assert_eq!(feature("div:has(> a)"), "`:has(> a)` is not supported when streaming");
+        assert_eq!(feature("a.x, :is(h1, h2)"), "`:is(h1, h2)` is not supported when streaming");
+        assert!(feature("div:empty").starts_with("`:empty`"));
+        assert!(feature("svg|rect").starts_with("namespace prefixes"));
+        assert!(feature("img[SRC$='.png']").starts_with("upper-case attribute names"));
+        assert!(feature("p:not(div > .ad)").starts_with("a combinator inside `:not()`"));
+        assert!(feature("p:not(:not(.a))").starts_with("nested `:not()`"));
+        assert!(matches!(supports("div["), Err(UnsupportedFeature::InvalidSelector(_))));
+    }
+
+    #[test]
+    fn test_validate_message() {
+        let err = validate("li:nth-last-child(2)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "streaming selector error: 'li:nth-last-child(2)': `:nth-last-child(2)` is not \
+             supported when streaming"
+        );
+        assert_eq!(err.span(), None);
+        assert!(validate("").is_err());
+    }
+
+    #[test]
+    fn test_validate_syntax_error_position() {
+        let err = validate("a[href^=/docs]").unwrap_err();
+        let span = err.span().expect("syntax errors carry a span");
+        assert_eq!((span.start.line, span.start.column), (1, 9));
+        assert!(
+            err.to_string().starts_with(
+                "streaming selector error at line 1, column 9: 'a[href^=/docs]': attribute value"
+            ),
+            "{err}"
+        );
+    }
+}
@@ -6,12 +6,28 @@
 use std::collections::HashMap;
 
 use crate::{
-    dom::{Document, NodeId},
+    Soup, SoupConfig,
+    convert::{MarkdownOptions, to_markdown},
+    dom::{Attributes, Document, NodeId},
+    extract::{
+        dates::{DateOrder, DateTime, parse_date_with},
+        links,
+        numbers::{Price, parse_number, parse_price},
+        srcset::{SrcsetCandidate, parse_srcset},
+        table::{Table, table},
+    },
+    parser::ParseConfig,
     query::{
-        CompiledSelector, QueryResult, TextNodesIter, find_all_within, find_all_within_compiled,
-        find_within, find_within_compiled, select_attr_within, select_text_within,
+        CompiledSelector, QueryResult, TextNodesIter, TextPattern, XPath, cached_compile,
+        find_all_by_text_within, find_all_within, find_all_within_compiled, find_by_text_within,
+        find_iter_within, find_last_within, find_nth_within, find_within, find_within_compiled,
+        matches_selector, select_attr_within, select_map_within, select_text_within,
+    },
+    serialize::{
+        SerializeOptions, TextOptions, collect_text as serialize_collect_text, collect_text_with,
+        collect_visible_text, serialize_inner_html_with, serialize_node, serialize_node_with,
     },
-    serialize::{collect_text as serialize_collect_text, serialize_node},
+    utils::{parse_attr_bool, parse_attr_int, parse_style, resolve_url, split_attr_list},
 };
 
 /// A reference to an element in the document.
@@ -115,10 +131,7 @@ impl<'a> Tag<'a> {
     #[inline]
     #[must_use]
     pub fn get(&self, attr: &str) -> Option<&str> {
-        self.doc
-            .get(self.id)
-            .and_then(|n| n.kind.attributes())
-            .and_then(|attrs| attrs.get(attr).map(String::as_str))
+        self.doc.get(self.id).and_then(|n| n.kind.attributes()).and_then(|attrs| attrs.get(attr))
     }
 
     /// Checks if this element has the specified attribute.
@@ -147,10 +160,50 @@ impl<'a> Tag<'a> {
     ///
     /// Returns `None` if this is not an element node.
     #[must_use]
-    pub fn attrs(&self) -> Option<&HashMap<String, String>> {
+    pub fn attrs(&self) -> Option<&Attributes> {
         self.doc.get(self.id).and_then(|n| n.kind.attributes())
     }
 
+    /// Returns the declarations of this element's inline `style` attribute.
+    ///
+    /// Property names are lowercased. The map is empty if the element has no
+    /// `style` attribute. See [`parse_style`] for the parsing rules.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<div style=\"Color: red; margin: 0 auto\"></div>");
+    /// let div = soup.find("div").unwrap().unwrap();
+    /// let styles = div.styles();
+    /// assert_eq!(styles["color"], "red");
+    /// assert_eq!(styles["margin"], "0 auto");
+    /// ```
+    #[must_use]
+    pub fn styles(&self) -> HashMap<String, String> {
+        self.get("style").map(parse_style).unwrap_or_default()
+    }
+
+    /// Returns the value of a property in this element's inline `style` attribute.
+    ///
+    /// The property name is matched case-insensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<p style=\"display: none !important\">Hi</p>");
+    /// let p = soup.find("p").unwrap().unwrap();
+    /// assert_eq!(p.style("display").as_deref(), Some("none"));
+    /// assert_eq!(p.style("color"), None);
+    /// ```
+    #[must_use]
+    pub fn style(&self, property: &str) -> Option<String> {
+        self.styles().remove(&property.to_ascii_lowercase())
+    }
+
     /// Checks if this element has the specified class.
     ///
     /// # Examples
@@ -197,6 +250,115 @@ impl<'a> Tag<'a> {
         self.get("class").map(|s| s.split_whitespace()).into_iter().flatten()
     }
 
+    /// Returns an attribute value parsed as an integer.
+    ///
+    /// Returns `None` if the attribute is missing or not a valid integer.
+    /// See [`parse_attr_int`](crate::utils::parse_attr_int) for the parsing rules.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<div data-count=\" 42 \" data-size=\"big\"></div>");
+    /// if let Ok(Some(div)) = soup.find("div") {
+    ///     assert_eq!(div.attr_int("data-count"), Some(42));
+    ///     assert_eq!(div.attr_int("data-size"), None);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn attr_int(&self, attr: &str) -> Option<i64> {
+        self.get(attr).and_then(parse_attr_int)
+    }
+
+    /// Returns an attribute value parsed as a boolean.
+    ///
+    /// Returns `None` if the attribute is missing. A present attribute is `true`
+    /// unless its value is `false`, `0`, `no`, or `off`.
+    /// See [`parse_attr_bool`](crate::utils::parse_attr_bool) for the parsing rules.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<input disabled data-active=\"false\">");
+    /// if let Ok(Some(input)) = soup.find("input") {
+    ///     assert_eq!(input.attr_bool("disabled"), Some(true));
+    ///     assert_eq!(input.attr_bool("data-active"), Some(false));
+    ///     assert_eq!(input.attr_bool("readonly"), None);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn attr_bool(&self, attr: &str) -> Option<bool> {
+        self.get(attr).map(parse_attr_bool)
+    }
+
+    /// Returns the whitespace-separated tokens of an attribute value.
+    ///
+    /// Yields nothing if the attribute is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<a rel=\"nofollow noopener\">Link</a>");
+    /// if let Ok(Some(link)) = soup.find("a") {
+    ///     let rel: Vec<_> = link.attr_list("rel").collect();
+    ///     assert_eq!(rel, vec!["nofollow", "noopener"]);
+    /// }
+    /// ```
+    pub fn attr_list(&self, attr: &str) -> impl Iterator<Item = &str> {
+        self.get(attr).map(split_attr_list).into_iter().flatten()
+    }
+
+    /// Returns an attribute value resolved as a URL against `base`.
+    ///
+    /// Returns `None` if the attribute is missing or `base` is not an absolute URL.
+    /// See [`resolve_url`](crate::utils::resolve_url) for the resolution rules.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<a href=\"../about\">About</a>");
+    /// if let Ok(Some(link)) = soup.find("a") {
+    ///     assert_eq!(
+    ///         link.attr_url("href", "https://example.com/docs/index.html").as_deref(),
+    ///         Some("https://example.com/about")
+    ///     );
+    /// }
+    /// ```
+    #[must_use]
+    pub fn attr_url(&self, attr: &str, base: &str) -> Option<String> {
+        self.get(attr).and_then(|value| resolve_url(base, value))
+    }
+
+    /// Returns the candidates of this element's `srcset` attribute.
+    ///
+    /// Returns an empty list if the attribute is missing. See
+    /// [`parse_srcset`](crate::extract::srcset::parse_srcset) for the parsing rules, and
+    /// [`image_source`](crate::extract::srcset::image_source) to pick a candidate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, extract::Descriptor};
+    ///
+    /// let soup = Soup::parse("<img srcset=\"small.jpg 480w, large.jpg 1080w\">");
+    /// if let Ok(Some(img)) = soup.find("img") {
+    ///     let candidates = img.srcset();
+    ///     assert_eq!(candidates[1].url, "large.jpg");
+    ///     assert_eq!(candidates[1].descriptor, Descriptor::Width(1080));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn srcset(&self) -> Vec<SrcsetCandidate> {
+        self.get("srcset").map(parse_srcset).unwrap_or_default()
+    }
+
     /// Returns the text content of this element and its descendants.
     ///
     /// HTML tags are stripped and only text nodes are included.
@@ -246,6 +408,156 @@ impl<'a> Tag<'a> {
         serialize_collect_text(self.doc, self.id, buf);
     }
 
+    /// Returns the text of this element that a browser would show.
+    ///
+    /// Like [`text`](Self::text), but skips `<script>`, `<style>`, `<template>`, and
+    /// `<noscript>` elements and elements hidden by a `hidden` attribute,
+    /// `aria-hidden="true"`, or an inline `display: none` style. See
+    /// [`collect_visible_text`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<div>Hi<script>track()</script><span hidden>x</span>!</div>");
+    /// let div = soup.find("div").unwrap().unwrap();
+    /// assert_eq!(div.visible_text(), "Hi!");
+    /// ```
+    #[must_use]
+    pub fn visible_text(&self) -> String {
+        let mut result = String::new();
+        collect_visible_text(self.doc, self.id, &mut result);
+        result
+    }
+
+    /// Returns the text content of this element laid out by `options`.
+    ///
+    /// Where [`text`](Self::text) joins text nodes as they are, this can put a
+    /// separator between them, strip or collapse whitespace, and break lines around
+    /// block-level elements the way a browser's `innerText` does. See [`TextOptions`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, TextOptions};
+    ///
+    /// let soup = Soup::parse("<div><h1>Title</h1><p>Some\n   <b>bold</b> text</p></div>");
+    /// let div = soup.find("div").unwrap().unwrap();
+    /// assert_eq!(div.text_with(&TextOptions::inner_text()), "Title\nSome bold text");
+    ///
+    /// let options = TextOptions { separator: " | ".into(), strip: true, ..Default::default() };
+    /// assert_eq!(div.text_with(&options), "Title | Some | bold | text");
+    /// ```
+    #[must_use]
+    pub fn text_with(&self, options: &TextOptions) -> String {
+        let mut result = String::new();
+        collect_text_with(self.doc, self.id, options, &mut result);
+        result
+    }
+
+    /// Parses a price from this element.
+    ///
+    /// Uses the `content` attribute when present (as in microdata
+    /// `<meta itemprop="price" content="...">`) and the text content otherwise.
+    /// See [`parse_price`](crate::extract::numbers::parse_price) for the parsing rules.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<span class=\"price\">1 299,00 €</span>");
+    /// if let Ok(Some(span)) = soup.find(".price") {
+    ///     let price = span.parse_price().unwrap();
+    ///     assert_eq!(price.amount, 1299.0);
+    ///     assert_eq!(price.currency.as_deref(), Some("EUR"));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn parse_price(&self) -> Option<Price> {
+        self.get("content").map_or_else(|| parse_price(&self.text()), parse_price)
+    }
+
+    /// Parses a number from this element.
+    ///
+    /// Uses the `content` attribute when present and the text content otherwise, like
+    /// [`Tag::parse_price`]. See [`parse_number`](crate::extract::numbers::parse_number) for
+    /// the parsing rules.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<span class=\"rating\">4,5 / 5</span>");
+    /// if let Ok(Some(span)) = soup.find(".rating") {
+    ///     assert_eq!(span.parse_number(), Some(4.5));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn parse_number(&self) -> Option<f64> {
+        self.get("content").map_or_else(|| parse_number(&self.text()), parse_number)
+    }
+
+    /// Parses a date from this element.
+    ///
+    /// Tries the `datetime` attribute (as on `<time>`), then the `content` attribute, then
+    /// the `datetime` attribute of a sole `<time>` child (as in `<td><time>`), then the
+    /// text content. `order` says how to read ambiguous numeric dates such as
+    /// `03/05/2024`; see [`parse_date_with`](crate::extract::dates::parse_date_with).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, extract::DateOrder};
+    ///
+    /// let soup = Soup::parse("<span class=\"date\">03/05/2024</span>");
+    /// if let Ok(Some(span)) = soup.find(".date") {
+    ///     let date = span.parse_date(DateOrder::MonthFirst).unwrap();
+    ///     assert_eq!(date.to_string(), "2024-03-05");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn parse_date(&self, order: DateOrder) -> Option<DateTime> {
+        ["datetime", "content"]
+            .into_iter()
+            .find_map(|attr| self.get(attr).and_then(|value| parse_date_with(value, order)))
+            .or_else(|| {
+                let mut children = self.children();
+                let time = children.next().filter(|child| child.name() == Some("time"))?;
+                children
+                    .next()
+                    .is_none()
+                    .then(|| time.get("datetime").and_then(|value| parse_date_with(value, order)))
+                    .flatten()
+            })
+            .or_else(|| parse_date_with(&self.text(), order))
+    }
+
+    /// Reads this `<table>` element into a [`Table`] of headers and rows.
+    ///
+    /// `colspan` and `rowspan` are resolved, so every row has one cell per column.
+    /// Returns `None` if this element is not a `<table>`. See
+    /// [`extract::table`](mod@crate::extract::table) for the layout rules.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse(
+    ///     "<table><tr><th>Item</th><th>Price</th></tr><tr><td>Tea</td><td>3</td></tr></table>",
+    /// );
+    /// let table = soup.find("table").unwrap().unwrap().as_table().unwrap();
+    /// assert_eq!(table.headers, vec!["Item", "Price"]);
+    /// assert_eq!(table.to_records()[0]["Price"], "3");
+    /// ```
+    #[must_use]
+    pub fn as_table(&self) -> Option<Table> {
+        table(self)
+    }
+
     /// Returns the inner HTML of this element.
     ///
     /// # Examples
@@ -286,10 +598,133 @@ impl<'a> Tag<'a> {
         result
     }
 
+    /// Returns the inner HTML of this element formatted by `options`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{SerializeOptions, Soup};
+    ///
+    /// let soup = Soup::parse("<ul>\n  <li>One</li>  <li>Two</li>\n</ul>");
+    /// let ul = soup.find("ul").unwrap().unwrap();
+    /// let options = SerializeOptions { minify: true, ..SerializeOptions::default() };
+    /// assert_eq!(ul.inner_html_with(&options), "<li>One</li><li>Two</li>");
+    /// ```
+    #[must_use]
+    pub fn inner_html_with(&self, options: &SerializeOptions) -> String {
+        let mut result = String::new();
+        serialize_inner_html_with(self.doc, self.id, options, &mut result);
+        result
+    }
+
+    /// Returns the outer HTML of this element formatted by `options`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{SerializeOptions, Soup};
+    ///
+    /// let soup = Soup::parse("<ul><li>One</li><li>Two</li></ul>");
+    /// let ul = soup.find("ul").unwrap().unwrap();
+    /// let options = SerializeOptions { pretty: true, ..SerializeOptions::default() };
+    /// assert_eq!(ul.outer_html_with(&options), "<ul>\n  <li>One</li>\n  <li>Two</li>\n</ul>\n");
+    /// ```
+    #[must_use]
+    pub fn outer_html_with(&self, options: &SerializeOptions) -> String {
+        let mut result = String::new();
+        serialize_node_with(self.doc, self.id, options, &mut result);
+        result
+    }
+
+    /// Returns this element and its descendants as nested JSON objects.
+    ///
+    /// Elements become `{"type": "element", "name", "attributes", "children"}`, with a
+    /// `namespace` of `"svg"` or `"mathml"` outside HTML; text and comment nodes become
+    /// `{"type": "text"}` or `{"type": "comment"}` objects with their `content`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<a href=\"/tea\">Tea</a>");
+    /// let tree = soup.find("a").unwrap().unwrap().to_json_tree();
+    /// assert_eq!(tree["attributes"]["href"], "/tea");
+    /// assert_eq!(tree["children"][0]["content"], "Tea");
+    /// ```
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn to_json_tree(&self) -> serde_json::Value {
+        crate::dom::json_tree(self.doc, self.id)
+    }
+
+    /// Renders this element and its descendants as Markdown.
+    ///
+    /// See [`convert`](crate::convert) for the supported elements.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li><b>Tea</b></li><li><a href=\"/tea\">More</a></li></ul>");
+    /// let list = soup.find("ul").unwrap().unwrap();
+    /// assert_eq!(list.to_markdown(), "- **Tea**\n- [More](/tea)");
+    /// ```
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        self.to_markdown_with_options(&MarkdownOptions::default())
+    }
+
+    /// Renders this element and its descendants as Markdown with custom link and image
+    /// handling.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{
+    ///     Soup,
+    ///     convert::{ImageStyle, MarkdownOptions},
+    /// };
+    ///
+    /// let soup = Soup::parse("<p><img src=\"tea.png\" alt=\"Tea\"> time</p>");
+    /// let options = MarkdownOptions { images: ImageStyle::Alt, ..MarkdownOptions::default() };
+    /// let p = soup.find("p").unwrap().unwrap();
+    /// assert_eq!(p.to_markdown_with_options(&options), "Tea time");
+    /// ```
+    #[must_use]
+    pub fn to_markdown_with_options(&self, options: &MarkdownOptions) -> String {
+        to_markdown(self.doc, self.id, options)
+    }
+
     fn serialize_to(&self, buf: &mut String) {
         serialize_node(self.doc, self.id, buf);
     }
 
+    /// Copies this element and its descendants into a standalone [`Soup`].
+    ///
+    /// Only the subtree is copied, with this element as the new root, so the original
+    /// document can be dropped early and memory stays proportional to the extracted
+    /// region. The new soup uses the default [`SoupConfig`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let article = {
+    ///     let page = Soup::parse("<nav>Menu</nav><article><h1>Title</h1><p>Body</p></article>");
+    ///     page.find("article").unwrap().unwrap().extract_soup()
+    /// };
+    /// assert_eq!(article.find("h1").unwrap().unwrap().text(), "Title");
+    /// assert!(article.find("nav").unwrap().is_none());
+    /// ```
+    #[must_use]
+    pub fn extract_soup(&self) -> Soup {
+        let document = self.doc.subtree(self.id).unwrap_or_default();
+        Soup::from_document(document, SoupConfig::default())
+    }
+
     // ==================== Navigation ====================
 
     /// Returns the parent element, if any.
@@ -395,6 +830,29 @@ impl<'a> Tag<'a> {
             .map(move |id| Tag::new(doc, id))
     }
 
+    /// Returns the content of every comment inside this element, in document order.
+    ///
+    /// Comments are only kept when parsing with
+    /// [`include_comments`](crate::SoupConfig::include_comments).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, SoupConfig};
+    ///
+    /// let config = SoupConfig::builder().include_comments(true).build();
+    /// let soup = Soup::parse_with_config(
+    ///     "<footer><!-- rev 1042 --><p>Hi<!-- note --></p></footer><!-- end -->",
+    ///     config,
+    /// );
+    /// let footer = soup.find("footer").unwrap().unwrap();
+    /// assert_eq!(footer.comments().collect::<Vec<_>>(), [" rev 1042 ", " note "]);
+    /// ```
+    pub fn comments(&self) -> impl Iterator<Item = &'a str> {
+        let doc = self.doc;
+        doc.descendants(self.id).filter_map(move |id| doc.get(id)?.kind.as_comment())
+    }
+
     /// Returns an iterator over all ancestor elements.
     ///
     /// Iterates from parent toward root (does not include the element itself).
@@ -489,6 +947,56 @@ impl<'a> Tag<'a> {
         Ok(None)
     }
 
+    /// Returns whether this element itself matches a CSS selector.
+    ///
+    /// Descendants are not searched, which makes this the filter for [`children`],
+    /// [`descendants`], and the other navigation iterators. Combinators still look at the
+    /// element's ancestors and siblings, so `"ul > li"` matches an `li` whose parent is a
+    /// `ul`. Compiled selectors are cached, as for [`find`].
+    ///
+    /// [`children`]: Self::children
+    /// [`descendants`]: Self::descendants
+    /// [`find`]: Self::find
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup =
+    ///     Soup::parse("<ul><li class='item' data-x='1'>A</li><li class='item'>B</li><li>C</li></ul>");
+    /// let ul = soup.find("ul").unwrap().unwrap();
+    /// let items: Vec<_> = ul.children().filter(|li| li.matches(".item[data-x]").unwrap()).collect();
+    /// assert_eq!(items.len(), 1);
+    /// assert_eq!(items[0].text(), "A");
+    /// ```
+    pub fn matches(&self, selector: &str) -> QueryResult<bool> {
+        let compiled = cached_compile(selector)?;
+        Ok(self.matches_compiled(&compiled))
+    }
+
+    /// Returns whether this element itself matches a pre-compiled selector.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::{Soup, query::CompiledSelector};
+    ///
+    /// let selector = CompiledSelector::compile("li:not(.skip)").unwrap();
+    /// let soup = Soup::parse("<ul><li>A</li><li class='skip'>B</li><li>C</li></ul>");
+    /// let ul = soup.find("ul").unwrap().unwrap();
+    /// assert_eq!(ul.children().filter(|li| li.matches_compiled(&selector)).count(), 2);
+    /// ```
+    #[must_use]
+    pub fn matches_compiled(&self, selector: &CompiledSelector) -> bool {
+        matches_selector(self.doc, self.id, selector.selector_list())
+    }
+
     /// Returns an iterator over following sibling elements.
     ///
     /// Does not include the element itself. Only element nodes are included.
@@ -569,17 +1077,130 @@ impl<'a> Tag<'a> {
     ///     assert_eq!(ids, vec!["a", "c"]); // Document order
     /// }
     /// ```
-    pub fn siblings(&self) -> impl Iterator<Item = Tag<'a>> {
+    pub fn siblings(&self) -> impl Iterator<Item = Tag<'a>> {
+        let doc = self.doc;
+        self.doc
+            .siblings(self.id)
+            .filter(move |id| doc.get(*id).is_some_and(|n| n.kind.is_element()))
+            .map(move |id| Tag::new(doc, id))
+    }
+
+    /// Returns a CSS selector that locates this element from the root element.
+    ///
+    /// Each step is a tag name, followed by `:nth-of-type(n)` when the element has
+    /// sibling elements with the same name, so the path matches exactly this element.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li>A</li><li><a href=\"/b\">B</a></li></ul>");
+    /// let link = soup.find("a").unwrap().unwrap();
+    /// assert_eq!(link.css_path(), "html > body > ul > li:nth-of-type(2) > a");
+    /// ```
+    #[must_use]
+    pub fn css_path(&self) -> String {
+        let mut steps: Vec<String> = std::iter::once(*self)
+            .chain(self.parents())
+            .map(|tag| {
+                let name = tag.name().unwrap_or_default();
+                let Some(parent) = tag.doc.parent(tag.id) else { return name.to_string() };
+                let mut position = None;
+                let mut count = 0;
+                for child in tag.doc.children(parent) {
+                    if tag.doc.get(child).and_then(|n| n.kind.tag_name()) == Some(name) {
+                        count += 1;
+                        if child == tag.id {
+                            position = Some(count);
+                        }
+                    }
+                }
+                match position {
+                    Some(n) if count > 1 => format!("{name}:nth-of-type({n})"),
+                    _ => name.to_string(),
+                }
+            })
+            .collect();
+        steps.reverse();
+        steps.join(" > ")
+    }
+
+    // ==================== Scoped Queries ====================
+
+    /// Finds the first descendant matching the selector.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<div><ul><li class=\"item\">text</li></ul></div>");
+    /// if let Ok(Some(div)) = soup.find("div") {
+    ///     let item = div.find(".item").unwrap();
+    ///     assert!(item.is_some());
+    /// }
+    /// ```
+    pub fn find(&self, selector: &str) -> QueryResult<Option<Tag<'a>>> {
+        find_within(self.doc, self.id, selector).map(|opt| opt.map(|id| Tag::new(self.doc, id)))
+    }
+
+    /// Finds all descendants matching the selector.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li>A</li><li>B</li><li>C</li></ul>");
+    /// if let Ok(Some(ul)) = soup.find("ul") {
+    ///     let items = ul.find_all("li").unwrap();
+    ///     assert_eq!(items.len(), 3);
+    /// }
+    /// ```
+    pub fn find_all(&self, selector: &str) -> QueryResult<Vec<Tag<'a>>> {
+        find_all_within(self.doc, self.id, selector)
+            .map(|ids| ids.into_iter().map(|id| Tag::new(self.doc, id)).collect())
+    }
+
+    /// Returns a lazy iterator over the descendants matching the selector.
+    ///
+    /// See [`Soup::iter`](crate::Soup::iter).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li>A</li><li class=\"sale\">B</li><li>C</li></ul>");
+    /// let ul = soup.find("ul").unwrap().unwrap();
+    /// let sale = ul.iter("li").unwrap().find(|li| li.has_class("sale"));
+    /// assert_eq!(sale.unwrap().text(), "B");
+    /// ```
+    #[allow(clippy::iter_not_returning_iterator)]
+    pub fn iter(&self, selector: &str) -> QueryResult<impl Iterator<Item = Tag<'a>> + use<'a>> {
         let doc = self.doc;
-        self.doc
-            .siblings(self.id)
-            .filter(move |id| doc.get(*id).is_some_and(|n| n.kind.is_element()))
-            .map(move |id| Tag::new(doc, id))
+        Ok(find_iter_within(doc, self.id, selector)?.map(move |id| Tag::new(doc, id)))
     }
 
-    // ==================== Scoped Queries ====================
-
-    /// Finds the first descendant matching the selector.
+    /// Finds the `n`th descendant (counting from zero) matching the selector.
+    ///
+    /// See [`Soup::find_nth`](crate::Soup::find_nth).
     ///
     /// # Errors
     ///
@@ -591,17 +1212,18 @@ impl<'a> Tag<'a> {
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse("<div><ul><li class=\"item\">text</li></ul></div>");
-    /// if let Ok(Some(div)) = soup.find("div") {
-    ///     let item = div.find(".item").unwrap();
-    ///     assert!(item.is_some());
-    /// }
+    /// let soup = Soup::parse("<table><tr><td>1</td><td>2</td><td>3</td></tr></table>");
+    /// let row = soup.find("tr").unwrap().unwrap();
+    /// assert_eq!(row.find_nth("td", 2).unwrap().unwrap().text(), "3");
     /// ```
-    pub fn find(&self, selector: &str) -> QueryResult<Option<Tag<'a>>> {
-        find_within(self.doc, self.id, selector).map(|opt| opt.map(|id| Tag::new(self.doc, id)))
+    pub fn find_nth(&self, selector: &str, n: usize) -> QueryResult<Option<Tag<'a>>> {
+        find_nth_within(self.doc, self.id, selector, n)
+            .map(|opt| opt.map(|id| Tag::new(self.doc, id)))
     }
 
-    /// Finds all descendants matching the selector.
+    /// Finds the last descendant matching the selector.
+    ///
+    /// See [`Soup::find_last`](crate::Soup::find_last).
     ///
     /// # Errors
     ///
@@ -613,15 +1235,13 @@ impl<'a> Tag<'a> {
     /// ```rust
     /// use scrape_core::Soup;
     ///
-    /// let soup = Soup::parse("<ul><li>A</li><li>B</li><li>C</li></ul>");
-    /// if let Ok(Some(ul)) = soup.find("ul") {
-    ///     let items = ul.find_all("li").unwrap();
-    ///     assert_eq!(items.len(), 3);
-    /// }
+    /// let soup = Soup::parse("<div><p>A</p><section><p>B</p></section></div><p>C</p>");
+    /// let div = soup.find("div").unwrap().unwrap();
+    /// assert_eq!(div.find_last("p").unwrap().unwrap().text(), "B");
     /// ```
-    pub fn find_all(&self, selector: &str) -> QueryResult<Vec<Tag<'a>>> {
-        find_all_within(self.doc, self.id, selector)
-            .map(|ids| ids.into_iter().map(|id| Tag::new(self.doc, id)).collect())
+    pub fn find_last(&self, selector: &str) -> QueryResult<Option<Tag<'a>>> {
+        find_last_within(self.doc, self.id, selector)
+            .map(|opt| opt.map(|id| Tag::new(self.doc, id)))
     }
 
     /// Selects descendants using a CSS selector.
@@ -726,6 +1346,163 @@ impl<'a> Tag<'a> {
         select_attr_within(self.doc, self.id, selector, attr)
     }
 
+    /// Applies `f` to every descendant matching a CSS selector, in document order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li data-id='1'>A</li><li data-id='x'>B</li></ul>");
+    /// if let Ok(Some(ul)) = soup.find("ul") {
+    ///     let ids = ul.select_map("li", |li| li.attr_int("data-id")).unwrap();
+    ///     assert_eq!(ids, vec![Some(1), None]);
+    /// }
+    /// ```
+    pub fn select_map<T>(
+        &self,
+        selector: &str,
+        f: impl FnMut(Tag<'a>) -> T,
+    ) -> QueryResult<Vec<T>> {
+        select_map_within(self.doc, self.id, selector, f)
+    }
+
+    /// Parses a number from every descendant matching a CSS selector.
+    ///
+    /// Each match is parsed like [`Tag::parse_number`]; `None` marks a match without one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    pub fn select_numbers(&self, selector: &str) -> QueryResult<Vec<Option<f64>>> {
+        self.select_map(selector, |tag| tag.parse_number())
+    }
+
+    /// Parses a date from every descendant matching a CSS selector.
+    ///
+    /// Each match is parsed like [`Tag::parse_date`]; `None` marks a match without one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    pub fn select_dates(
+        &self,
+        selector: &str,
+        order: DateOrder,
+    ) -> QueryResult<Vec<Option<DateTime>>> {
+        self.select_map(selector, |tag| tag.parse_date(order))
+    }
+
+    /// Resolves the URL of every descendant matching a CSS selector.
+    ///
+    /// Works like [`Soup::select_urls`], including `<base href>` handling.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidSelector`](crate::QueryError::InvalidSelector) if the selector
+    /// syntax is invalid.
+    pub fn select_urls(
+        &self,
+        selector: &str,
+        base_url: Option<&str>,
+    ) -> QueryResult<Vec<Option<String>>> {
+        let base = links::document_base_url(self.doc, base_url);
+        self.select_map(selector, |tag| links::reference_url(&tag, base.as_deref()))
+    }
+
+    /// Finds the first innermost descendant whose text matches `pattern`.
+    ///
+    /// `pattern` is a substring or, with the `regex` feature, a `regex::Regex`; see
+    /// [`TextPattern`]. An element is skipped when one of its children already matches.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<table><tr><th>Price</th><td>4.50</td></tr></table>");
+    /// let table = soup.find("table").unwrap().unwrap();
+    /// let th = table.find_by_text("Price").unwrap();
+    /// assert_eq!(th.next_sibling().unwrap().text(), "4.50");
+    /// ```
+    #[must_use]
+    pub fn find_by_text(&self, pattern: impl TextPattern) -> Option<Tag<'a>> {
+        find_by_text_within(self.doc, self.id, pattern).map(|id| Tag::new(self.doc, id))
+    }
+
+    /// Finds all innermost descendants whose text matches `pattern`, in document order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<ul><li>Green tea</li><li>Cake</li><li>Black tea</li></ul>");
+    /// let ul = soup.find("ul").unwrap().unwrap();
+    /// assert_eq!(ul.find_all_by_text("tea").len(), 2);
+    /// ```
+    #[must_use]
+    pub fn find_all_by_text(&self, pattern: impl TextPattern) -> Vec<Tag<'a>> {
+        find_all_by_text_within(self.doc, self.id, pattern)
+            .into_iter()
+            .map(|id| Tag::new(self.doc, id))
+            .collect()
+    }
+
+    /// Selects elements using an XPath 1.0 expression with this element as context node.
+    ///
+    /// Relative paths such as `./li` or `following-sibling::*` start here; absolute paths
+    /// still search the whole document.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidXPath`](crate::QueryError::InvalidXPath) if the expression
+    /// is invalid or does not return a node-set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<dl><dt>Price</dt><dd>10</dd><dt>Stock</dt><dd>3</dd></dl>");
+    /// let dt = soup.find("dt").unwrap().unwrap();
+    /// let dd = dt.xpath("following-sibling::dd[1]").unwrap();
+    /// assert_eq!(dd[0].text(), "10");
+    /// ```
+    pub fn xpath(&self, expr: &str) -> QueryResult<Vec<Tag<'a>>> {
+        XPath::compile(expr)?
+            .select(self.doc, Some(self.id))
+            .map(|ids| ids.into_iter().map(|id| Tag::new(self.doc, id)).collect())
+    }
+
+    /// Evaluates an XPath 1.0 expression with this element as context node and returns
+    /// string values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidXPath`](crate::QueryError::InvalidXPath) if the expression
+    /// is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let soup = Soup::parse("<div><a href='/x'>X</a></div>");
+    /// let div = soup.find("div").unwrap().unwrap();
+    /// assert_eq!(div.xpath_text("a/@href").unwrap(), vec!["/x"]);
+    /// ```
+    pub fn xpath_text(&self, expr: &str) -> QueryResult<Vec<String>> {
+        XPath::compile(expr)?.select_text(self.doc, Some(self.id))
+    }
+
     /// Returns an iterator over all text nodes in this subtree.
     ///
     /// Only text node content is returned; element tags and comments are skipped.
@@ -807,6 +1584,185 @@ impl<'a> Tag<'a> {
     }
 }
 
+/// A mutable handle to a node in a [`Soup`](crate::Soup) document.
+///
+/// Obtained from [`Soup::tag_mut`](crate::Soup::tag_mut). Mutations are applied
+/// directly to the underlying [`Document`]; see its tree mutation methods for the
+/// exact rules.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::Soup;
+///
+/// let mut soup = Soup::parse("<div><script>track()</script><p class=\"ad\">Hi</p></div>");
+/// let script = soup.find("script").unwrap().unwrap().node_id();
+/// let p = soup.find("p").unwrap().unwrap().node_id();
+///
+/// soup.tag_mut(script).unwrap().remove();
+/// let mut p = soup.tag_mut(p).unwrap();
+/// p.remove_attribute("class");
+/// p.set_attribute("lang", "en");
+///
+/// assert_eq!(soup.find("div").unwrap().unwrap().inner_html(), "<p lang=\"en\">Hi</p>");
+/// ```
+#[derive(Debug)]
+pub struct TagMut<'a> {
+    doc: &'a mut Document,
+    id: NodeId,
+}
+
+impl<'a> TagMut<'a> {
+    /// Creates a new mutable tag handle.
+    #[must_use]
+    pub(crate) fn new(doc: &'a mut Document, id: NodeId) -> Self {
+        Self { doc, id }
+    }
+
+    /// Returns the node ID.
+    #[must_use]
+    pub fn node_id(&self) -> NodeId {
+        self.id
+    }
+
+    /// Returns a read-only view of this node.
+    #[must_use]
+    pub fn as_tag(&self) -> Tag<'_> {
+        Tag::new(self.doc, self.id)
+    }
+
+    /// Sets an attribute, replacing any existing value.
+    ///
+    /// Returns `false` if this node is not an element.
+    pub fn set_attribute(&mut self, name: impl Into<String>, value: impl Into<String>) -> bool {
+        self.doc.set_attribute(self.id, name, value)
+    }
+
+    /// Removes an attribute, returning its previous value.
+    pub fn remove_attribute(&mut self, name: &str) -> Option<String> {
+        self.doc.remove_attribute(self.id, name)
+    }
+
+    /// Moves `node` to just before this node.
+    ///
+    /// Returns `false` if this node has no parent or `node` is this node or an ancestor.
+    pub fn insert_before(&mut self, node: NodeId) -> bool {
+        self.doc.insert_before(self.id, node)
+    }
+
+    /// Moves `node` to just after this node.
+    ///
+    /// Returns `false` if this node has no parent or `node` is this node or an ancestor.
+    pub fn insert_after(&mut self, node: NodeId) -> bool {
+        self.doc.insert_after(self.id, node)
+    }
+
+    /// Detaches this node and its subtree from the document.
+    pub fn remove(&mut self) -> bool {
+        self.doc.remove_node(self.id)
+    }
+
+    /// Puts `node` in this node's place, detaching this node.
+    pub fn replace_with(&mut self, node: NodeId) -> bool {
+        self.doc.replace_node(self.id, node)
+    }
+
+    /// Wraps this node in a new element with the given tag name.
+    ///
+    /// Returns the wrapper's ID, or `None` if this node has no parent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<div><img src=\"a.png\"></div>");
+    /// let img = soup.find("img").unwrap().unwrap().node_id();
+    /// soup.tag_mut(img).unwrap().wrap("figure");
+    /// assert!(soup.find("div > figure > img").unwrap().is_some());
+    /// ```
+    pub fn wrap(&mut self, name: &str) -> Option<NodeId> {
+        let wrapper = self.doc.create_element(name, Attributes::new());
+        self.doc.wrap(self.id, wrapper).then_some(wrapper)
+    }
+
+    /// Replaces this node with its children.
+    pub fn unwrap(&mut self) -> bool {
+        self.doc.unwrap(self.id)
+    }
+
+    /// Parses an HTML fragment in the context of this element and appends its nodes as
+    /// this element's last children.
+    ///
+    /// The fragment is parsed with the default [`ParseConfig`](crate::ParseConfig).
+    /// Returns the IDs of the appended top-level nodes, which is empty if this node is
+    /// not an element or `html` is blank.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<table><tbody><tr><td>1</td></tr></tbody></table>");
+    /// let tbody = soup.find("tbody").unwrap().unwrap().node_id();
+    /// soup.tag_mut(tbody).unwrap().append_html("<tr><td>2</td></tr>");
+    /// assert_eq!(soup.select_text("td").unwrap(), ["1", "2"]);
+    /// ```
+    pub fn append_html(&mut self, html: &str) -> Vec<NodeId> {
+        crate::parser::fragment::append_html(self.doc, self.id, html, &ParseConfig::default())
+    }
+
+    /// Parses an HTML fragment in the context of this element and inserts its nodes as
+    /// this element's first children.
+    ///
+    /// Follows the same rules as [`append_html`](Self::append_html).
+    pub fn prepend_html(&mut self, html: &str) -> Vec<NodeId> {
+        crate::parser::fragment::prepend_html(self.doc, self.id, html, &ParseConfig::default())
+    }
+
+    /// Replaces this element's children with the nodes of an HTML fragment parsed in its
+    /// context.
+    ///
+    /// The old children are detached as by [`remove`](Self::remove). Returns the IDs of
+    /// the new top-level nodes. Nothing changes if this node is not an element or `html`
+    /// cannot be parsed; an empty `html` removes every child.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<div id=\"card\"><p>Loading…</p></div>");
+    /// let card = soup.find("#card").unwrap().unwrap().node_id();
+    /// soup.tag_mut(card).unwrap().set_inner_html("<h2>Title</h2><p>Body</p>");
+    /// assert_eq!(soup.find("#card").unwrap().unwrap().inner_html(), "<h2>Title</h2><p>Body</p>");
+    /// ```
+    pub fn set_inner_html(&mut self, html: &str) -> Vec<NodeId> {
+        crate::parser::fragment::set_inner_html(self.doc, self.id, html, &ParseConfig::default())
+    }
+
+    /// Puts the nodes of an HTML fragment, parsed in the context of this node's parent,
+    /// in this node's place.
+    ///
+    /// This node is detached as by [`remove`](Self::remove). Returns the IDs of the new
+    /// top-level nodes. Nothing changes if this node has no parent element or `html`
+    /// cannot be parsed; an empty `html` just removes this node.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use scrape_core::Soup;
+    ///
+    /// let mut soup = Soup::parse("<ul><li>A</li><li class=\"slot\"></li></ul>");
+    /// let slot = soup.find(".slot").unwrap().unwrap().node_id();
+    /// soup.tag_mut(slot).unwrap().replace_with_html("<li>B</li><li>C</li>");
+    /// assert_eq!(soup.select_text("li").unwrap(), ["A", "B", "C"]);
+    /// ```
+    pub fn replace_with_html(&mut self, html: &str) -> Vec<NodeId> {
+        crate::parser::fragment::replace_with_html(self.doc, self.id, html, &ParseConfig::default())
+    }
+}
+
 impl PartialEq for Tag<'_> {
     fn eq(&self, other: &Self) -> bool {
         // Document equality via pointer comparison ensures tags from different documents
@@ -904,6 +1860,36 @@ mod tests {
         assert!(html.contains(">text</a>"));
     }
 
+    #[test]
+    fn test_tag_typed_attr_accessors() {
+        let soup = Soup::parse(
+            "<a href=\"/b\" rel=\" nofollow  ugc\" data-count=\"7\" data-new=\"no\" hidden>x</a>",
+        );
+        let a = soup.find("a").unwrap().unwrap();
+        assert_eq!(a.attr_int("data-count"), Some(7));
+        assert_eq!(a.attr_int("href"), None);
+        assert_eq!(a.attr_bool("hidden"), Some(true));
+        assert_eq!(a.attr_bool("data-new"), Some(false));
+        assert_eq!(a.attr_bool("missing"), None);
+        assert_eq!(a.attr_list("rel").collect::<Vec<_>>(), vec!["nofollow", "ugc"]);
+        assert_eq!(a.attr_list("missing").count(), 0);
+        assert_eq!(a.attr_url("href", "https://x.org/a/c").as_deref(), Some("https://x.org/b"));
+        assert_eq!(a.attr_url("missing", "https://x.org/"), None);
+    }
+
+    #[test]
+    fn test_tag_parse_price() {
+        let soup = Soup::parse(
+            "<div><span class=\"a\">Now only $1,299.99!</span><meta itemprop=\"price\" \
+             content=\"19.90\"><span class=\"b\">TBD</span></div>",
+        );
+        let a = soup.find(".a").unwrap().unwrap().parse_price().unwrap();
+        assert_eq!(a.to_string(), "1299.99 USD");
+        let meta = soup.find("meta").unwrap().unwrap().parse_price().unwrap();
+        assert!((meta.amount - 19.9).abs() < f64::EPSILON);
+        assert!(soup.find(".b").unwrap().unwrap().parse_price().is_none());
+    }
+
     #[test]
     fn test_tag_parent() {
         let soup = Soup::parse("<div><span>text</span></div>");
@@ -1005,9 +1991,23 @@ mod tests {
             Soup::parse("<div id=\"main\" class=\"container\" data-value=\"123\">text</div>");
         let div = soup.find("div").unwrap().unwrap();
         let attrs = div.attrs().unwrap();
-        assert_eq!(attrs.get("id"), Some(&"main".to_string()));
-        assert_eq!(attrs.get("class"), Some(&"container".to_string()));
-        assert_eq!(attrs.get("data-value"), Some(&"123".to_string()));
+        assert_eq!(attrs.get("id"), Some("main"));
+        assert_eq!(attrs.get("class"), Some("container"));
+        assert_eq!(attrs.get("data-value"), Some("123"));
+    }
+
+    #[test]
+    fn test_tag_styles() {
+        let soup = Soup::parse(
+            "<div style=\"WIDTH: 10px; background: url('a;b.png')\"></div><span>x</span>",
+        );
+        let div = soup.find("div").unwrap().unwrap();
+        assert_eq!(div.styles().len(), 2);
+        assert_eq!(div.style("Width").as_deref(), Some("10px"));
+        assert_eq!(div.style("background").as_deref(), Some("url('a;b.png')"));
+        let span = soup.find("span").unwrap().unwrap();
+        assert!(span.styles().is_empty());
+        assert_eq!(span.style("width"), None);
     }
 
     #[test]
@@ -1038,6 +2038,26 @@ mod tests {
         assert_eq!(span.parents().count(), span.ancestors().count());
     }
 
+    #[test]
+    fn test_css_path() {
+        let soup = Soup::parse(
+            "<div><p>One</p><p>Two <b>bold</b> <i>a</i><i>b</i></p></div><!-- c --><div></div>",
+        );
+        for tag in soup.find_all("*").unwrap() {
+            let path = tag.css_path();
+            let matches = soup.select(&path).unwrap();
+            assert_eq!(matches.len(), 1, "{path}");
+            assert_eq!(matches[0].node_id(), tag.node_id(), "{path}");
+        }
+
+        let i = soup.find("i + i").unwrap().unwrap();
+        assert_eq!(
+            i.css_path(),
+            "html > body > div:nth-of-type(1) > p:nth-of-type(2) > i:nth-of-type(2)"
+        );
+        assert_eq!(soup.root().unwrap().css_path(), "html");
+    }
+
     #[test]
     fn test_closest_basic() {
         let soup =
@@ -1070,6 +2090,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_matches() {
+        let soup = Soup::parse(
+            "<div class='list'><p class='item' data-x='1'><b>A</b></p><p class='item'>B</p></div>",
+        );
+        let div = soup.find("div").unwrap().unwrap();
+        let first = soup.find("p").unwrap().unwrap();
+
+        assert!(first.matches("div.list > p.item[data-x]").unwrap());
+        assert!(first.matches("p:first-child, span").unwrap());
+        assert!(!first.next_sibling().unwrap().matches("[data-x]").unwrap());
+        // Descendants are not searched.
+        assert!(!div.matches("b").unwrap());
+        assert!(div.matches(":has(b)").unwrap());
+        assert!(div.matches("p[").is_err());
+
+        let selector = crate::query::CompiledSelector::compile(".item").unwrap();
+        assert_eq!(div.descendants().filter(|tag| tag.matches_compiled(&selector)).count(), 2);
+    }
+
     #[test]
     fn test_closest_does_not_match_self() {
         let soup = Soup::parse("<div><span class='target'>text</span></div>");
@@ -1158,9 +2198,7 @@ mod tests {
 
     #[test]
     fn test_tag_find_compiled() {
-        use crate::query::CompiledSelector;
-
-        let selector = CompiledSelector::compile(".item").unwrap();
+        let selector = crate::query::CompiledSelector::compile(".item").unwrap();
         let soup = Soup::parse("<div><ul><li class=\"item\">text</li></ul></div>");
         let div = soup.find("div").unwrap().unwrap();
         let item = div.find_compiled(&selector);
@@ -1179,6 +2217,30 @@ mod tests {
         assert_eq!(items.len(), 3);
     }
 
+    #[test]
+    fn test_tag_typed_selects() {
+        use crate::extract::DateOrder;
+
+        let soup = Soup::parse(
+            "<base href=\"/shop/\"><table><tr><td><a href=\"tea\">Tea</a></td><td \
+             class=\"price\">1.299,00</td><td><time \
+             datetime=\"2024-03-05\">Tue</time></td></tr><tr><td><a>Cake</a></td><td \
+             class=\"price\">-</td><td>12/31/2024</td></tr></table><p class=\"price\">7</p>",
+        );
+        let table = soup.find("table").unwrap().unwrap();
+
+        assert_eq!(table.select_numbers(".price").unwrap(), vec![Some(1299.0), None]);
+        let dates = table.select_dates("td:last-child", DateOrder::MonthFirst).unwrap();
+        let dates: Vec<_> = dates.iter().map(|d| d.map(|d| d.to_string())).collect();
+        assert_eq!(dates, vec![Some("2024-03-05".to_string()), Some("2024-12-31".to_string())]);
+        assert_eq!(
+            table.select_urls("a", Some("https://example.com/")).unwrap(),
+            vec![Some("https://example.com/shop/tea".to_string()), None]
+        );
+        assert_eq!(table.select_urls("a", None).unwrap(), vec![None, None]);
+        assert!(table.select_numbers("[").is_err());
+    }
+
     #[test]
     fn test_children_by_name() {
         let soup = Soup::parse("<ul><li>A</li><span>X</span><li>B</li></ul>");
@@ -1338,4 +2400,97 @@ mod tests {
         assert_eq!(capacity_after_first, capacity_after_second);
         assert_eq!(buffer, "Test");
     }
+
+    #[test]
+    fn test_tag_mut_structural_edits() {
+        let mut soup = Soup::parse("<ul><li class=\"a\">A</li><li class=\"b\">B</li></ul>");
+        let a = soup.find(".a").unwrap().unwrap().node_id();
+        let b = soup.find(".b").unwrap().unwrap().node_id();
+
+        assert!(!soup.tag_mut(a).unwrap().insert_after(a));
+        assert!(soup.tag_mut(a).unwrap().insert_before(b));
+        assert_eq!(soup.select_text("li").unwrap(), vec!["B", "A"]);
+
+        let wrapper = soup.tag_mut(a).unwrap().wrap("strong").unwrap();
+        assert_eq!(soup.find("ul > strong").unwrap().unwrap().node_id(), wrapper);
+        assert_eq!(soup.find("strong > li").unwrap().unwrap().node_id(), a);
+        assert!(soup.tag_mut(wrapper).unwrap().unwrap());
+        assert!(soup.find("strong").unwrap().is_none());
+
+        assert!(soup.tag_mut(b).unwrap().remove());
+        assert_eq!(soup.find("ul").unwrap().unwrap().inner_html(), "<li class=\"a\">A</li>");
+    }
+
+    #[test]
+    fn test_tag_mut_attributes_invalidate_index() {
+        let mut soup = Soup::parse("<div id=\"old\" class=\"x\">text</div>");
+        let div = soup.find("#old").unwrap().unwrap().node_id();
+
+        let mut tag = soup.tag_mut(div).unwrap();
+        assert!(tag.set_attribute("id", "new"));
+        assert_eq!(tag.remove_attribute("class").as_deref(), Some("x"));
+        assert_eq!(tag.as_tag().get("id"), Some("new"));
+
+        assert!(soup.find("#old").unwrap().is_none());
+        assert!(soup.find("#new").unwrap().is_some());
+        assert!(soup.find(".x").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_tag_mut_append_html() {
+        let mut soup = Soup::parse("<ul><li>A</li></ul><p>after</p>");
+        let ul = soup.find("ul").unwrap().unwrap().node_id();
+        let added = soup.tag_mut(ul).unwrap().append_html("<li>B</li><li id=\"c\">C</li>");
+
+        assert_eq!(added.len(), 2);
+        assert_eq!(soup.select_text("ul > li").unwrap(), vec!["A", "B", "C"]);
+        assert_eq!(soup.find("#c").unwrap().unwrap().node_id(), added[1]);
+        assert_eq!(soup.find("ul + p").unwrap().unwrap().text(), "after");
+
+        let p = soup.find("p").unwrap().unwrap().node_id();
+        let text = soup.document().first_child(p).unwrap();
+        assert!(soup.tag_mut(text).unwrap().append_html("<b>x</b>").is_empty());
+    }
+
+    #[test]
+    fn test_tag_mut_prepend_and_set_inner_html() {
+        let mut soup = Soup::parse("<table><tbody><tr><td>2</td></tr></tbody></table>");
+        let tbody = soup.find("tbody").unwrap().unwrap().node_id();
+        let added =
+            soup.tag_mut(tbody).unwrap().prepend_html("<tr><td>0</td></tr><tr><td>1</td></tr>");
+        assert_eq!(added.len(), 2);
+        assert_eq!(soup.select_text("td").unwrap(), vec!["0", "1", "2"]);
+
+        let old = soup.find("tr").unwrap().unwrap().node_id();
+        let added = soup.tag_mut(tbody).unwrap().set_inner_html("<tr><td>new</td></tr>");
+        assert_eq!(added.len(), 1);
+        assert_eq!(soup.select_text("tbody > tr > td").unwrap(), vec!["new"]);
+        assert_eq!(soup.document().parent(old), None);
+
+        assert!(soup.tag_mut(tbody).unwrap().set_inner_html("").is_empty());
+        assert_eq!(soup.document().first_child(tbody), None);
+    }
+
+    #[test]
+    fn test_tag_mut_replace_with_html() {
+        let mut soup = Soup::parse("<p>Hello <span class=\"name\"></span>!</p>");
+        let span = soup.find(".name").unwrap().unwrap().node_id();
+        let added = soup.tag_mut(span).unwrap().replace_with_html("<b>Ada</b> and <b>Alan</b>");
+
+        assert_eq!(added.len(), 3);
+        assert_eq!(
+            soup.find("p").unwrap().unwrap().inner_html(),
+            "Hello <b>Ada</b> and <b>Alan</b>!"
+        );
+        assert_eq!(soup.document().parent(span), None);
+        assert!(soup.find(".name").unwrap().is_none());
+
+        let b = added[0];
+        assert!(soup.tag_mut(b).unwrap().replace_with_html("").is_empty());
+        assert_eq!(soup.find("p").unwrap().unwrap().text(), "Hello  and Alan!");
+
+        let root = soup.root().unwrap().node_id();
+        assert!(soup.tag_mut(root).unwrap().replace_with_html("<p>x</p>").is_empty());
+        assert!(soup.root().is_some());
+    }
 }
@@ -1,9 +1,10 @@
 //! Shared utility functions for HTML processing.
 //!
 //! This module provides common utilities used across the library and bindings
-//! for HTML text escaping, attribute escaping, and void element detection.
+//! for HTML text escaping, attribute escaping, void element detection, and
+//! attribute value parsing.
 
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashMap};
 
 /// Escapes special characters for HTML text content.
 ///
@@ -14,7 +15,8 @@ use std::borrow::Cow;
 ///
 /// This function uses a fast-path check to avoid allocation when the input
 /// contains no special characters. In typical HTML content, 80-90% of text
-/// nodes require no escaping.
+/// nodes require no escaping. With the `simd` feature, the check is a vectorized
+/// byte scan.
 ///
 /// # Examples
 ///
@@ -35,7 +37,11 @@ use std::borrow::Cow;
 /// ```
 #[must_use]
 pub fn escape_text(s: &str) -> Cow<'_, str> {
-    if !s.contains(['&', '<', '>']) {
+    #[cfg(feature = "simd")]
+    let needs_escape = crate::simd::scan_until_any(s.as_bytes(), b"&<>").is_some();
+    #[cfg(not(feature = "simd"))]
+    let needs_escape = s.contains(['&', '<', '>']);
+    if !needs_escape {
         return Cow::Borrowed(s);
     }
 
@@ -137,6 +143,237 @@ pub fn is_void_element(name: &str) -> bool {
     )
 }
 
+/// Parses an attribute value as an integer.
+///
+/// Leading and trailing ASCII whitespace is ignored and an optional `+` or `-`
+/// sign is accepted. Returns `None` for empty or non-numeric values.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::utils::parse_attr_int;
+///
+/// assert_eq!(parse_attr_int(" 42 "), Some(42));
+/// assert_eq!(parse_attr_int("-7"), Some(-7));
+/// assert_eq!(parse_attr_int("4.2"), None);
+/// assert_eq!(parse_attr_int(""), None);
+/// ```
+#[must_use]
+pub fn parse_attr_int(value: &str) -> Option<i64> {
+    value.trim_ascii().parse().ok()
+}
+
+/// Parses an attribute value as a boolean.
+///
+/// Follows HTML boolean attribute semantics, where presence means `true`
+/// (`disabled`, `disabled=""`, `disabled="disabled"`), with the explicit
+/// tokens `false`, `0`, `no`, and `off` (ASCII case-insensitive) mapping to
+/// `false` so `data-*` flags round-trip.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::utils::parse_attr_bool;
+///
+/// assert!(parse_attr_bool(""));
+/// assert!(parse_attr_bool("disabled"));
+/// assert!(parse_attr_bool("true"));
+/// assert!(!parse_attr_bool("False"));
+/// assert!(!parse_attr_bool("0"));
+/// ```
+#[must_use]
+pub fn parse_attr_bool(value: &str) -> bool {
+    let value = value.trim_ascii();
+    !["false", "0", "no", "off"].iter().any(|token| value.eq_ignore_ascii_case(token))
+}
+
+/// Splits an attribute value into its whitespace-separated tokens.
+///
+/// This is the parsing rule for token-list attributes such as `class`, `rel`,
+/// and `headers`.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::utils::split_attr_list;
+///
+/// let tokens: Vec<_> = split_attr_list(" nofollow  noopener ").collect();
+/// assert_eq!(tokens, vec!["nofollow", "noopener"]);
+/// ```
+pub fn split_attr_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split_ascii_whitespace()
+}
+
+/// Parses the declarations of an inline `style` attribute.
+///
+/// Property names are lowercased and values trimmed, with any `!important`
+/// flag removed. When a property is declared more than once the last
+/// declaration wins, unless an earlier one is `!important` and the later one
+/// is not. Semicolons inside quotes or parentheses, as in
+/// `url(data:image/png;base64,...)`, do not end a declaration. Declarations
+/// without a `:` or with an empty name or value are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::utils::parse_style;
+///
+/// let styles = parse_style("Display: none; color: red !important; color: blue");
+/// assert_eq!(styles.get("display").map(String::as_str), Some("none"));
+/// assert_eq!(styles.get("color").map(String::as_str), Some("red"));
+/// ```
+#[must_use]
+pub fn parse_style(style: &str) -> HashMap<String, String> {
+    let mut important = Vec::new();
+    let mut styles = HashMap::new();
+    for declaration in split_declarations(style) {
+        let Some((property, value)) = declaration.split_once(':') else { continue };
+        let property = property.trim_ascii().to_ascii_lowercase();
+        let value = value.trim_ascii();
+        let (value, is_important) = value
+            .strip_suffix("!important")
+            .map_or((value, false), |value| (value.trim_ascii_end(), true));
+        if property.is_empty() || value.is_empty() {
+            continue;
+        }
+        if is_important {
+            important.push(property.clone());
+        } else if important.contains(&property) {
+            continue;
+        }
+        styles.insert(property, value.to_string());
+    }
+    styles
+}
+
+/// Splits a `style` attribute on the semicolons outside quotes and parentheses.
+fn split_declarations(style: &str) -> impl Iterator<Item = &str> {
+    let mut depth = 0_usize;
+    let mut quote = None;
+    style.split(move |c: char| {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, ';') => return depth == 0,
+            _ => {}
+        }
+        false
+    })
+}
+
+/// Resolves a URL reference against a base URL.
+///
+/// Implements reference resolution from RFC 3986 section 5.2, including
+/// removal of `.` and `..` path segments. Leading and trailing ASCII whitespace
+/// in the reference is ignored. Absolute references are returned unchanged.
+///
+/// Returns `None` if `base` is not an absolute URL.
+///
+/// # Examples
+///
+/// ```rust
+/// use scrape_core::utils::resolve_url;
+///
+/// let base = "https://example.com/docs/guide/index.html?v=1";
+/// assert_eq!(
+///     resolve_url(base, "../api.html").as_deref(),
+///     Some("https://example.com/docs/api.html")
+/// );
+/// assert_eq!(resolve_url(base, "/about").as_deref(), Some("https://example.com/about"));
+/// assert_eq!(
+///     resolve_url(base, "//cdn.example.com/a.js").as_deref(),
+///     Some("https://cdn.example.com/a.js")
+/// );
+/// assert_eq!(resolve_url("not a url", "/about"), None);
+/// ```
+#[must_use]
+pub fn resolve_url(base: &str, reference: &str) -> Option<String> {
+    let reference = reference.trim_ascii();
+    if url_scheme(reference).is_some() {
+        return Some(reference.to_string());
+    }
+
+    let base = base.trim_ascii();
+    let scheme = url_scheme(base)?;
+    let base = base.split('#').next().unwrap_or_default();
+    let rest = &base[scheme.len() + 1..];
+    let (authority, base_path_and_query) = rest.strip_prefix("//").map_or((None, rest), |after| {
+        let end = after.find(['/', '?']).unwrap_or(after.len());
+        (Some(&after[..end]), &after[end..])
+    });
+    let base_path = base_path_and_query.split('?').next().unwrap_or_default();
+
+    let mut prefix = format!("{scheme}:");
+    if let Some(authority) = authority {
+        prefix.push_str("//");
+        prefix.push_str(authority);
+    }
+
+    if reference.starts_with("//") {
+        return Some(format!("{scheme}:{reference}"));
+    }
+    if reference.is_empty() {
+        return Some(base.to_string());
+    }
+    if reference.starts_with('#') {
+        return Some(format!("{base}{reference}"));
+    }
+    if reference.starts_with('?') {
+        return Some(format!("{prefix}{base_path}{reference}"));
+    }
+
+    let split = reference.find(['?', '#']).unwrap_or(reference.len());
+    let (ref_path, suffix) = reference.split_at(split);
+    let merged = if ref_path.starts_with('/') {
+        ref_path.to_string()
+    } else if authority.is_some() && base_path.is_empty() {
+        format!("/{ref_path}")
+    } else {
+        let dir = base_path.rfind('/').map_or("", |i| &base_path[..=i]);
+        format!("{dir}{ref_path}")
+    };
+
+    Some(format!("{prefix}{}{suffix}", remove_dot_segments(&merged)))
+}
+
+/// Returns the scheme of an absolute URL, if it has one.
+fn url_scheme(url: &str) -> Option<&str> {
+    let colon = url.find(':')?;
+    let scheme = &url[..colon];
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some(scheme)
+}
+
+/// Removes `.` and `..` segments from a URL path (RFC 3986 section 5.2.4).
+fn remove_dot_segments(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/').collect();
+    let mut output: Vec<&str> = Vec::with_capacity(segments.len());
+    for (i, segment) in segments.iter().enumerate() {
+        let is_last = i + 1 == segments.len();
+        match *segment {
+            "." => {
+                if is_last {
+                    output.push("");
+                }
+            }
+            ".." => {
+                if output.len() > 1 {
+                    output.pop();
+                }
+                if is_last {
+                    output.push("");
+                }
+            }
+            segment => output.push(segment),
+        }
+    }
+    output.join("/")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,4 +448,88 @@ mod tests {
             assert!(!is_void_element(tag), "{tag} should not be a void element");
         }
     }
+
+    #[test]
+    fn test_parse_attr_int() {
+        assert_eq!(parse_attr_int("10"), Some(10));
+        assert_eq!(parse_attr_int("+3"), Some(3));
+        assert_eq!(parse_attr_int("\n-12\t"), Some(-12));
+        assert_eq!(parse_attr_int("12px"), None);
+        assert_eq!(parse_attr_int("   "), None);
+    }
+
+    #[test]
+    fn test_parse_attr_bool() {
+        for value in ["", "true", "disabled", "yes", "1", " TRUE "] {
+            assert!(parse_attr_bool(value), "{value:?} should be true");
+        }
+        for value in ["false", "FALSE", "0", "no", "off", " Off "] {
+            assert!(!parse_attr_bool(value), "{value:?} should be false");
+        }
+    }
+
+    #[test]
+    fn test_split_attr_list() {
+        assert_eq!(split_attr_list("a\tb\nc").collect::<Vec<_>>(), vec!["a", "b", "c"]);
+        assert_eq!(split_attr_list("   ").count(), 0);
+    }
+
+    #[test]
+    fn test_parse_style() {
+        let styles = parse_style(
+            " COLOR : Red;;display:block;display : none ;background:url(\"a;b.png\") ;bogus; :x",
+        );
+        assert_eq!(styles.len(), 3);
+        assert_eq!(styles["color"], "Red");
+        assert_eq!(styles["display"], "none");
+        assert_eq!(styles["background"], "url(\"a;b.png\")");
+
+        let styles = parse_style("display:none!important;display:block;width:1px !important");
+        assert_eq!(styles["display"], "none");
+        assert_eq!(styles["width"], "1px");
+        assert!(parse_style("").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_url_rfc3986_examples() {
+        let base = "http://a/b/c/d;p?q";
+        let cases = [
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("g:h", "g:h"),
+        ];
+        for (reference, expected) in cases {
+            assert_eq!(resolve_url(base, reference).as_deref(), Some(expected), "{reference:?}");
+        }
+    }
+
+    #[test]
+    fn test_resolve_url_host_without_path() {
+        assert_eq!(
+            resolve_url("https://example.com", "page").as_deref(),
+            Some("https://example.com/page")
+        );
+    }
+
+    #[test]
+    fn test_resolve_url_relative_base_returns_none() {
+        assert_eq!(resolve_url("/relative/base", "page"), None);
+    }
 }
@@ -0,0 +1,159 @@
+//! Reconciling several fetches of the same page.
+//!
+//! Pages under A/B tests, rotating promotions, or personalization render differently on
+//! every fetch, which makes change monitoring noisy. [`merge_text_variants`] (also
+//! available as [`Soup::merge_text_variants`]) takes several fetches of one URL and
+//! keeps only the text that all of them share, so what remains is the page's stable
+//! content.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use scrape_core::Soup;
+//!
+//! let a = Soup::parse("<h1>Tea</h1><div class=\"promo\"><p>10% off today</p></div>");
+//! let b = Soup::parse("<h1>Tea</h1><div class=\"promo\"><p>Free shipping</p></div>");
+//!
+//! let stable = Soup::merge_text_variants(&[a, b]);
+//! assert_eq!(stable.text(), "Tea");
+//! assert!(stable.find(".promo").unwrap().is_none());
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    Soup,
+    dom::{Document, DocumentIndex, NodeId, NodeKind},
+};
+
+/// Merges several fetches of the same page into one document of their stable content.
+///
+/// The result is a copy of the first variant from which every text node whose
+/// whitespace-normalized text does not occur in all other variants is removed. Repeated
+/// text is matched by count, so a line shown twice in one fetch and once in another is
+/// kept once. Elements left without text or child elements by the removal are removed
+/// too, while elements that were empty to begin with, such as `<img>` or `<br>`, stay.
+///
+/// Returns an empty document if `variants` is empty.
+#[must_use]
+pub fn merge_text_variants(variants: &[Soup]) -> Soup {
+    let Some((first, rest)) = variants.split_first() else { return Soup::parse("") };
+
+    let mut stable = text_counts(first.document());
+    for other in rest {
+        let counts = text_counts(other.document());
+        stable.retain(|text, count| {
+            let Some(&other_count) = counts.get(text) else { return false };
+            *count = (*count).min(other_count);
+            true
+        });
+    }
+
+    let mut soup = first.clone();
+    let doc = soup.document_mut();
+    let Some(root) = doc.root() else { return soup };
+    let nodes: Vec<NodeId> = std::iter::once(root).chain(doc.descendants(root)).collect();
+    let had_content: Vec<bool> = nodes.iter().map(|&id| !is_blank(doc, id)).collect();
+
+    for &id in &nodes {
+        let Some(NodeKind::Text { content }) = doc.get(id).map(|node| &node.kind) else {
+            continue;
+        };
+        let text = normalize(content);
+        if text.is_empty() {
+            continue;
+        }
+        match stable.get_mut(&text) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => {
+                doc.remove_node(id);
+            }
+        }
+    }
+
+    // Children come after their parents in document order, so walking backwards
+    // empties nested wrappers before their containers are checked.
+    for (&id, had_content) in nodes.iter().zip(had_content).rev() {
+        let is_element = doc.get(id).is_some_and(|node| node.kind.is_element());
+        if is_element && had_content && is_blank(doc, id) {
+            doc.remove_node(id);
+        }
+    }
+
+    let index = DocumentIndex::from_document(doc);
+    doc.set_index(index);
+    soup
+}
+
+/// Counts the non-blank text nodes of `doc` by normalized text.
+fn text_counts(doc: &Document) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    let Some(root) = doc.root() else { return counts };
+    for id in doc.descendants(root) {
+        if let Some(NodeKind::Text { content }) = doc.get(id).map(|node| &node.kind) {
+            let text = normalize(content);
+            if !text.is_empty() {
+                *counts.entry(text).or_default() += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Returns `true` if `id` has no child elements and no non-whitespace text.
+fn is_blank(doc: &Document, id: NodeId) -> bool {
+    doc.children(id).all(|child| match doc.get(child).map(|node| &node.kind) {
+        Some(NodeKind::Element { .. }) => false,
+        Some(NodeKind::Text { content }) => content.trim().is_empty(),
+        Some(NodeKind::Comment { .. } | NodeKind::Doctype { .. }) | None => true,
+    })
+}
+
+/// Collapses runs of whitespace to single spaces and trims the ends.
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_text_variants() {
+        let a = Soup::parse(
+            "<ul><li>Green</li><li>Black</li></ul><aside><p>Try <b>oolong</b></p></aside><p><img \
+             src=\"tea.png\"> Sale</p>",
+        );
+        let b = Soup::parse(
+            "<ul><li>Green</li><li>Black</li></ul><aside><p>Try <b>pu-erh</b></p></aside><p><img \
+             src=\"tea.png\"> Sale ends soon</p>",
+        );
+
+        let merged = merge_text_variants(&[a, b]);
+        let body = merged.find("body").unwrap().unwrap();
+        assert_eq!(
+            body.inner_html(),
+            "<ul><li>Green</li><li>Black</li></ul><aside><p>Try </p></aside><p><img \
+             src=\"tea.png\"></p>"
+        );
+        assert_eq!(merged.find_all("li").unwrap().len(), 2);
+        assert!(merged.find("b").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_merge_repeated_text() {
+        let a = Soup::parse("<p>Item</p><p>Item</p><p>Item</p>");
+        let b = Soup::parse("<p>Item</p><div><p>  Item </p></div>");
+
+        let merged = merge_text_variants(&[a, b]);
+        assert_eq!(merged.find_all("p").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_merge_edge_cases() {
+        assert!(merge_text_variants(&[]).document().is_empty());
+
+        let only = Soup::parse("<p>Alone</p>");
+        assert_eq!(merge_text_variants(std::slice::from_ref(&only)).to_html(), only.to_html());
+    }
+}
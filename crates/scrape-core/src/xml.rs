@@ -0,0 +1,293 @@
+//! A small pull tokenizer for the XML of sitemaps and feeds.
+//!
+//! This is not a full XML parser: it understands elements and their attributes, CDATA
+//! sections, comments, processing instructions, doctypes without an internal subset, and
+//! the predefined and numeric character references. Well-formedness is only checked as
+//! far as tokenizing needs, and end tags are not matched against start tags.
+
+use std::io::BufRead;
+
+use memchr::{memchr, memmem};
+
+/// Size of the reads from the underlying source.
+pub const CHUNK_SIZE: usize = 8 * 1024;
+
+/// A token read by [`XmlReader`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// A start tag; `empty` for a self-closing `<name/>` tag.
+    Start { name: String, attrs: Vec<(String, String)>, empty: bool },
+    /// An end tag.
+    End,
+    /// Text with character references decoded, or the contents of a CDATA section.
+    Text(Vec<u8>),
+}
+
+/// Error reading XML.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlError {
+    /// Reading the input failed.
+    Io(String),
+    /// The input is malformed at byte `offset`.
+    Syntax { offset: u64, message: String },
+}
+
+/// Reads XML tokens from a buffered source, a chunk at a time.
+pub struct XmlReader<'a> {
+    input: Box<dyn BufRead + 'a>,
+    buf: Vec<u8>,
+    pos: usize,
+    /// Number of bytes dropped from the front of `buf` so far.
+    offset: u64,
+    eof: bool,
+}
+
+impl<'a> XmlReader<'a> {
+    pub fn new(input: Box<dyn BufRead + 'a>) -> Self {
+        Self { input, buf: Vec::with_capacity(CHUNK_SIZE), pos: 0, offset: 0, eof: false }
+    }
+
+    /// Returns the byte offset of the next token in the input.
+    pub const fn position(&self) -> u64 {
+        self.offset + self.pos as u64
+    }
+
+    /// Returns a syntax error at the current position.
+    pub fn syntax(&self, message: &str) -> XmlError {
+        XmlError::Syntax { offset: self.position(), message: message.into() }
+    }
+
+    /// Returns the next token, or `None` at the end of the input.
+    ///
+    /// Text is only returned if `keep_text` is set, and skipped otherwise.
+    pub fn next_token(&mut self, keep_text: bool) -> Result<Option<Token>, XmlError> {
+        loop {
+            let rest = &self.buf[self.pos..];
+            if rest.is_empty() {
+                if self.fill()? {
+                    continue;
+                }
+                return Ok(None);
+            }
+
+            if rest[0] != b'<' {
+                if let Some(end) = memchr(b'<', rest) {
+                    let text = keep_text.then(|| unescape(&rest[..end]));
+                    self.pos += end;
+                    if let Some(text) = text {
+                        return Ok(Some(Token::Text(text)));
+                    }
+                } else if !keep_text {
+                    self.pos = self.buf.len();
+                } else if !self.fill()? {
+                    let text = unescape(&self.buf[self.pos..]);
+                    self.pos = self.buf.len();
+                    return Ok(Some(Token::Text(text)));
+                }
+                continue;
+            }
+
+            // Long enough to tell `<![CDATA[` from other markup.
+            if rest.len() < 9 && self.fill()? {
+                continue;
+            }
+            let rest = &self.buf[self.pos..];
+            let (start, end) = if rest.starts_with(b"<!--") {
+                (4, memmem::find(&rest[4..], b"-->").map(|i| (i, 3)))
+            } else if rest.starts_with(b"<![CDATA[") {
+                (9, memmem::find(&rest[9..], b"]]>").map(|i| (i, 3)))
+            } else if rest.starts_with(b"<?") {
+                (2, memmem::find(&rest[2..], b"?>").map(|i| (i, 2)))
+            } else if rest.starts_with(b"<!") || rest.starts_with(b"</") {
+                (2, memchr(b'>', &rest[2..]).map(|i| (i, 1)))
+            } else {
+                (1, tag_end(&rest[1..]).map(|i| (i, 1)))
+            };
+            let Some((len, close_len)) = end else {
+                if self.fill()? {
+                    continue;
+                }
+                return Err(self.syntax("unterminated markup"));
+            };
+            let body = &rest[start..start + len];
+            let token = match &rest[..start] {
+                b"<![CDATA[" => keep_text.then(|| Token::Text(body.to_vec())),
+                b"</" => Some(Token::End),
+                b"<" => {
+                    let (body, empty) =
+                        body.strip_suffix(b"/").map_or((body, false), |body| (body, true));
+                    let name_len =
+                        body.iter().position(u8::is_ascii_whitespace).unwrap_or(body.len());
+                    if name_len == 0 {
+                        return Err(self.syntax("missing element name"));
+                    }
+                    let name = String::from_utf8_lossy(&body[..name_len]).into_owned();
+                    Some(Token::Start { name, attrs: parse_attrs(&body[name_len..]), empty })
+                }
+                _ => None,
+            };
+            self.pos += start + len + close_len;
+            if let Some(token) = token {
+                return Ok(Some(token));
+            }
+        }
+    }
+
+    /// Appends the next chunk of input to the buffer; returns `false` at the end of input.
+    fn fill(&mut self) -> Result<bool, XmlError> {
+        if self.eof {
+            return Ok(false);
+        }
+        if self.pos > 0 {
+            self.buf.drain(..self.pos);
+            self.offset += self.pos as u64;
+            self.pos = 0;
+        }
+        let chunk = self.input.fill_buf().map_err(|e| XmlError::Io(e.to_string()))?;
+        let len = chunk.len();
+        self.buf.extend_from_slice(chunk);
+        self.input.consume(len);
+        self.eof = len == 0;
+        Ok(len > 0)
+    }
+}
+
+/// Returns the local part of an element `name` if its namespace prefix is `prefix`.
+pub fn local_name<'n>(name: &'n str, prefix: &str) -> Option<&'n str> {
+    match name.split_once(':') {
+        Some((name_prefix, local)) => (name_prefix == prefix).then_some(local),
+        None => prefix.is_empty().then_some(name),
+    }
+}
+
+/// Returns the index of the `>` closing a start tag, skipping quoted attribute values.
+fn tag_end(bytes: &[u8]) -> Option<usize> {
+    let mut quote = None;
+    bytes.iter().position(|&b| match quote {
+        Some(q) => {
+            if b == q {
+                quote = None;
+            }
+            false
+        }
+        None if b == b'"' || b == b'\'' => {
+            quote = Some(b);
+            false
+        }
+        None => b == b'>',
+    })
+}
+
+/// Parses the `name="value"` attributes after an element name.
+///
+/// Attributes without a value are skipped, and parsing stops at an unquoted value.
+fn parse_attrs(mut bytes: &[u8]) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    loop {
+        bytes = bytes.trim_ascii_start();
+        if bytes.is_empty() {
+            return attrs;
+        }
+        let name_len =
+            bytes.iter().position(|&b| b == b'=' || b.is_ascii_whitespace()).unwrap_or(bytes.len());
+        let name = String::from_utf8_lossy(&bytes[..name_len]).into_owned();
+        let rest = bytes[name_len..].trim_ascii_start();
+        let Some(value) = rest.strip_prefix(b"=") else {
+            bytes = rest;
+            continue;
+        };
+        let Some((&quote, value)) =
+            value.trim_ascii_start().split_first().filter(|(q, _)| matches!(q, b'"' | b'\''))
+        else {
+            return attrs;
+        };
+        let Some(end) = memchr(quote, value) else { return attrs };
+        attrs.push((name, String::from_utf8_lossy(&unescape(&value[..end])).into_owned()));
+        bytes = &value[end + 1..];
+    }
+}
+
+/// Decodes the predefined and numeric character references in XML text.
+///
+/// Unknown or malformed references are kept as written.
+pub fn unescape(text: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = memchr(b'&', rest) {
+        out.extend_from_slice(&rest[..i]);
+        rest = &rest[i..];
+        let decoded = memchr(b';', rest).filter(|&end| end <= 10).and_then(|end| {
+            let name = std::str::from_utf8(&rest[1..end]).ok()?;
+            let c = match name {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                _ => {
+                    let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => name.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end + 1))
+        });
+        if let Some((c, len)) = decoded {
+            out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            rest = &rest[len..];
+        } else {
+            out.push(b'&');
+            rest = &rest[1..];
+        }
+    }
+    out.extend_from_slice(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(xml: &str) -> Vec<Token> {
+        let mut reader = XmlReader::new(Box::new(xml.as_bytes()));
+        std::iter::from_fn(|| reader.next_token(true).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_tokens() {
+        let start = |name: &str, attrs: &[(&str, &str)], empty| Token::Start {
+            name: name.into(),
+            attrs: attrs.iter().map(|&(n, v)| (n.into(), v.into())).collect(),
+            empty,
+        };
+        assert_eq!(
+            tokens(
+                "<?xml version=\"1.0\"?><!DOCTYPE x><!-- c --><a x = '1' \
+                 y=\"&lt;&#x41;\">t<![CDATA[<b>&amp;</b>]]><br/></a>"
+            ),
+            [
+                start("a", &[("x", "1"), ("y", "<A")], false),
+                Token::Text(b"t".to_vec()),
+                Token::Text(b"<b>&amp;</b>".to_vec()),
+                start("br", &[], true),
+                Token::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape(b"a &lt;&gt;&quot;&apos; &#233;&#xE9;"), "a <>\"' éé".as_bytes());
+        assert_eq!(unescape(b"&bogus; & &#xZZ; &amp"), b"&bogus; & &#xZZ; &amp");
+        assert_eq!(tag_end(b"a href='>' b=\">\">x"), Some(16));
+        assert_eq!(
+            parse_attrs(b" a=\"1\" b c='2'"),
+            [("a".into(), "1".into()), ("c".into(), "2".into())]
+        );
+        assert_eq!(local_name("atom:link", "atom"), Some("link"));
+        assert_eq!(local_name("link", "atom"), None);
+        assert_eq!(local_name("link", ""), Some("link"));
+    }
+}
@@ -80,12 +80,10 @@ fn test_streaming_multi_chunk() -> Result<()> {
 
     let finished = processor.end()?;
 
-    // Each write() creates a new rewriter, so stats accumulate
-    // But because we write 4 times, we get counts for each chunk
-    // The actual count depends on how lol_html parses across chunks
-    let count_value = *count.lock().unwrap();
-    assert!(count_value >= 2, "Should find at least 2 paragraphs");
-    assert_eq!(finished.stats().elements_count, count_value);
+    // One rewriter spans all chunks, so each paragraph is matched once
+    assert_eq!(*count.lock().unwrap(), 2);
+    assert_eq!(finished.stats().elements_count, 2);
+    assert_eq!(finished.output(), b"<html><body><p>First</p><p>Second</p></body></html>");
 
     Ok(())
 }
@@ -32,6 +32,103 @@ export declare class CompiledSelector {
   get source(): string
 }
 
+/**
+ * A set of elements from one document, in document order.
+ *
+ * Refining methods return a new Selection, so queries can be chained.
+ *
+ * @example
+ * ```javascript
+ * const soup = new Soup('<ul><li class="sale"><a>A</a></li><li><a href="/b">B</a></li></ul>');
+ * const links = soup.selection("li").not(".sale").find("a");
+ * console.log(links.texts());        // ["B"]
+ * console.log(links.attrs("href"));  // ["/b"]
+ * ```
+ */
+export declare class Selection {
+  /**
+   * Keep the elements that match a CSS selector.
+   *
+   * @param selector - CSS selector string
+   * @returns A new Selection
+   * @throws Error if the selector syntax is invalid
+   */
+  filter(selector: string): Selection
+  /**
+   * Remove the elements that match a CSS selector.
+   *
+   * @param selector - CSS selector string
+   * @returns A new Selection
+   * @throws Error if the selector syntax is invalid
+   */
+  not(selector: string): Selection
+  /**
+   * Select the descendants of any element that match a CSS selector.
+   *
+   * @param selector - CSS selector string
+   * @returns A new Selection, in document order without duplicates
+   * @throws Error if the selector syntax is invalid
+   */
+  find(selector: string): Selection
+  /**
+   * Select the parent element of each element.
+   *
+   * @returns A new Selection, in document order without duplicates
+   */
+  parent(): Selection
+  /**
+   * Select only the element at an index.
+   *
+   * @param index - Position of the element; negative values count from the end
+   * @returns A Selection of one element, or an empty one if index is out of range
+   */
+  eq(index: number): Selection
+  /**
+   * Get the element at an index.
+   *
+   * @param index - Position of the element; negative values count from the end
+   * @returns The Tag, or null if index is out of range
+   */
+  get(index: number): Tag | null
+  /**
+   * Get the text content of each element.
+   *
+   * @returns Array of text strings, one per element
+   */
+  texts(): Array<string>
+  /**
+   * Get an attribute value from each element.
+   *
+   * @param name - Attribute name
+   * @returns Array of attribute values (null for missing attributes)
+   */
+  attrs(name: string): Array<string | undefined | null>
+  /**
+   * Get the elements in either selection.
+   *
+   * @param other - A Selection of the same document
+   * @returns A new Selection, in document order without duplicates
+   * @throws Error if the selections belong to different documents
+   */
+  union(other: Selection): Selection
+  /**
+   * Get the elements in both selections.
+   *
+   * @param other - A Selection of the same document
+   * @returns A new Selection
+   * @throws Error if the selections belong to different documents
+   */
+  intersection(other: Selection): Selection
+  /**
+   * Get the elements as an array of Tags.
+   *
+   * @returns Array of Tag instances in document order
+   */
+  toArray(): Array<Tag>
+  /** Get the number of elements. */
+  get length(): number
+}
+
 /**
  * A parsed HTML document.
  *
@@ -61,6 +158,47 @@ export declare class Soup {
    * @throws Error if the file cannot be read
    */
   static fromFile(path: string, config?: SoupConfig | undefined | null): Soup
+  /**
+   * Parse HTML from a Buffer or Uint8Array of bytes in any encoding.
+   *
+   * The encoding is taken from a byte order mark, then `encoding`, then a
+   * `<meta charset>` declaration, and defaults to UTF-8 for valid UTF-8 and
+   * windows-1252 otherwise. The bytes are read in place, and UTF-8 input is parsed
+   * without an intermediate string.
+   *
+   * @param buffer - The raw document bytes, such as a fetch or file read result
+   * @param encoding - Optional encoding label, such as the charset of a Content-Type header
+   * @param config - Optional parsing configuration
+   * @returns A new Soup instance
+   * @throws Error if `encoding` is not a known encoding label
+   *
+   * @example
+   * ```javascript
+   * const soup = Soup.fromBuffer(fs.readFileSync("page.html"));
+   * const legacy = Soup.fromBuffer(body, "shift_jis");
+   * ```
+   */
+  static fromBuffer(buffer: Uint8Array, encoding?: string | undefined | null, config?: SoupConfig | undefined | null): Soup
+  /**
+   * Download and parse the HTML document at a URL without blocking the event loop.
+   *
+   * Redirects are followed and gzip and brotli responses decompressed. The body is
+   * decoded with the charset of the Content-Type header, or detected as in
+   * `fromBuffer`.
+   *
+   * @param url - An http:// or https:// URL
+   * @param options - Optional request headers, user agent, timeout, and limits
+   * @param config - Optional parsing configuration
+   * @returns Promise of a new Soup instance
+   * @throws Error if the request fails, times out, or answers with an error status
+   *
+   * @example
+   * ```javascript
+   * const soup = await Soup.fromUrl("https://example.com/", { timeoutMs: 5000 });
+   * console.log(soup.title);
+   * ```
+   */
+  static fromUrl(url: string, options?: FetchOptions | undefined | null, config?: SoupConfig | undefined | null): Promise<Soup>
   /**
    * Find the first element matching a CSS selector.
    *
@@ -84,6 +222,14 @@ export declare class Soup {
    * @returns Array of matching Tag instances
    */
   select(selector: string): Array<Tag>
+  /**
+   * Find all elements matching a CSS selector, as a chainable Selection.
+   *
+   * @param selector - CSS selector string
+   * @returns A Selection of the matching elements in document order
+   * @throws Error if the selector syntax is invalid
+   */
+  selection(selector: string): Selection
   /**
    * Get the root element of the document.
    *
@@ -96,6 +242,19 @@ export declare class Soup {
    * @returns The title text, or null if no <title> element exists
    */
   get title(): string | null
+  /**
+   * Get the document metadata: meta tags, Open Graph properties, Twitter card fields,
+   * the canonical URL, favicons, and parsed JSON-LD blocks.
+   *
+   * @returns The document metadata
+   */
+  metadata(): Metadata
+  /**
+   * Extract the main article content, like a browser's reader mode.
+   *
+   * @returns The content element, title, byline, and body as text and Markdown
+   */
+  extractArticle(): Article
   /**
    * Get the text content of the entire document.
    *
@@ -234,6 +393,37 @@ export declare class Tag {
   hasClass(className: string): boolean
   /** Get all classes as an array. */
   get classes(): Array<string>
+  /**
+   * Get an attribute value parsed as an integer.
+   *
+   * @param name - The attribute name
+   * @returns The integer value, or null if missing or not a valid integer
+   */
+  attrInt(name: string): number | null
+  /**
+   * Get an attribute value parsed as a boolean.
+   *
+   * A present attribute is true unless its value is "false", "0", "no", or "off".
+   *
+   * @param name - The attribute name
+   * @returns The boolean value, or null if the attribute is missing
+   */
+  attrBool(name: string): boolean | null
+  /**
+   * Get the whitespace-separated tokens of an attribute value.
+   *
+   * @param name - The attribute name (e.g., "rel")
+   * @returns Array of tokens, empty if the attribute is missing
+   */
+  attrList(name: string): Array<string>
+  /**
+   * Get an attribute value resolved as a URL against a base URL.
+   *
+   * @param name - The attribute name (e.g., "href")
+   * @param base - Absolute base URL to resolve against
+   * @returns The absolute URL, or null if the attribute is missing or base is not absolute
+   */
+  attrUrl(name: string, base: string): string | null
   /** Get the parent element. */
   get parent(): Tag | null
   /** Get all direct child elements. */
@@ -256,6 +446,17 @@ export declare class Tag {
    * @throws Error if the selector syntax is invalid
    */
   closest(selector: string): Tag | null
+  /**
+   * Check whether this element itself matches a CSS selector.
+   *
+   * Descendants are not searched, so this can filter the results of
+   * `children`, `descendants`, and the other navigation getters.
+   *
+   * @param selector - CSS selector string
+   * @returns True if the element matches
+   * @throws Error if the selector syntax is invalid
+   */
+  matches(selector: string): boolean
   /** Get all following sibling elements. */
   get nextSiblings(): Array<Tag>
   /** Get all preceding sibling elements (in reverse order). */
@@ -296,6 +497,13 @@ export declare class Tag {
    * @returns Array of matching Tag instances
    */
   selectCompiled(selector: CompiledSelector): Array<Tag>
+  /**
+   * Check whether this element itself matches a compiled selector.
+   *
+   * @param selector - A compiled CSS selector
+   * @returns True if the element matches
+   */
+  matchesCompiled(selector: CompiledSelector): boolean
   /**
    * Get all direct text nodes (excluding descendants).
    *
@@ -373,6 +581,21 @@ export declare class Tag {
    * ```
    */
   selectAttr(selector: string, attr: string): Array<string | undefined | null>
+  /**
+   * Copy this element and its descendants into a standalone Soup.
+   *
+   * Only the subtree is copied, so the original document can be released
+   * while the extracted region is kept.
+   *
+   * @returns A new Soup whose root is a copy of this element
+   *
+   * @example
+   * ```javascript
+   * const article = new Soup(page).find("article").extractSoup();
+   * const title = article.find("h1").text;
+   * ```
+   */
+  extractSoup(): Soup
 }
 
 /**
@@ -397,6 +620,146 @@ export declare class Tag {
  */
 export declare function parseBatch(documents: Array<string>, config?: SoupConfig | undefined | null): Array<Soup>
 
+/**
+ * Parse multiple HTML documents in parallel without blocking the event loop.
+ *
+ * Parsing runs on the libuv worker pool, fanning out to Rayon threads,
+ * and the returned Promise resolves once every document is parsed.
+ *
+ * @param documents - Array of HTML strings to parse
+ * @param config - Optional parsing configuration
+ * @returns Promise of Soup instances in the same order as input
+ *
+ * @example
+ * ```javascript
+ * import { parseBatchAsync } from 'scrape-rs';
+ *
+ * const soups = await parseBatchAsync(['<div>A</div>', '<div>B</div>']);
+ * const texts = soups.map(s => s.find('div').text);
+ * // texts: ['A', 'B']
+ * ```
+ */
+export declare function parseBatchAsync(documents: Array<string>, config?: SoupConfig | undefined | null): Promise<Array<Soup>>
+
+/**
+ * Configure the thread pool shared by all parallel operations.
+ *
+ * Applies to `parseBatch`, `parseBatchAsync`, and every other parallel
+ * operation. Calling it again replaces the pool; calling it without
+ * options restores the default of one thread per CPU.
+ *
+ * @param options - Thread count and stack size
+ * @throws Error if `threads` or `stackSize` is 0, or the pool cannot be created
+ *
+ * @example
+ * ```javascript
+ * import { configureThreads, parseBatch } from 'scrape-rs';
+ *
+ * configureThreads({ threads: 4 });
+ * const soups = parseBatch(htmls);
+ * ```
+ */
+export declare function configureThreads(options?: ThreadPoolOptions | undefined | null): void
+
+/**
+ * The main content of a page, as found by a browser's reader mode.
+ *
+ * @example
+ * ```javascript
+ * const article = new Soup(html).extractArticle();
+ * console.log(article.title, article.byline);
+ * console.log(article.markdown);
+ * ```
+ */
+export interface Article {
+  /** The element holding the content, or undefined for an empty document. */
+  content?: Tag
+  /** The headline, without a trailing site name. */
+  title?: string
+  /** The author, from `<meta name="author">` or a byline element. */
+  byline?: string
+  /**
+   * The publication date as an ISO 8601 string, from meta tags, microdata, or a
+   * `<time>` element.
+   */
+  publishedDate?: string
+  /** The content as plain text, with blank lines between paragraphs. */
+  text: string
+  /** The content as Markdown. */
+  markdown: string
+}
+
+/**
+ * Options for fetching a document with `Soup.fromUrl`.
+ *
+ * @example
+ * ```javascript
+ * const options = {
+ *   headers: { "Accept-Language": "en" },
+ *   userAgent: "my-crawler/1.0",
+ *   timeoutMs: 10000,
+ *   maxRedirects: 5
+ * };
+ * const soup = await Soup.fromUrl("https://example.com/", options);
+ * ```
+ */
+export interface FetchOptions {
+  /** Additional request headers. Default: none */
+  headers?: Record<string, string>
+  /** User-Agent header. Default: `scrape-rs/<version>` */
+  userAgent?: string
+  /** Timeout for the whole request in milliseconds. Default: 30000 */
+  timeoutMs?: number
+  /** Maximum number of redirects to follow. Default: 10 */
+  maxRedirects?: number
+  /** Maximum size of the response body in bytes. Default: 10 MiB */
+  maxSize?: number
+}
+
+/** An icon declared with `<link rel="icon">`, `apple-touch-icon`, or `mask-icon`. */
+export interface Icon {
+  /** The href attribute as written. */
+  href: string
+  /** Lowercase tokens of the rel attribute, joined by spaces. */
+  rel: string
+  /** The sizes attribute, such as `32x32`. */
+  sizes?: string
+  /** The type attribute, such as `image/png`. */
+  type?: string
+}
+
+/**
+ * Metadata collected from a document's head and JSON-LD blocks.
+ *
+ * @example
+ * ```javascript
+ * const meta = new Soup(html).metadata();
+ * console.log(meta.title, meta.openGraph["og:image"], meta.jsonLd[0]);
+ * ```
+ */
+export interface Metadata {
+  /** Text of the first `<title>`. */
+  title?: string
+  /** Content of `<meta name="description">`. */
+  description?: string
+  /** The `lang` attribute of `<html>`. */
+  lang?: string
+  /** The charset of the first `<meta charset>`. */
+  charset?: string
+  /** The href of the first `<link rel="canonical">`. */
+  canonical?: string
+  /** `<meta name>` and `<meta http-equiv>` content by lowercase name. */
+  meta: Record<string, string>
+  /** `<meta property>` values by lowercase property, such as `og:image`. */
+  openGraph: Record<string, Array<string>>
+  /** Twitter card fields by lowercase name, such as `twitter:card`. */
+  twitter: Record<string, string>
+  /** Icons from `<link>` tags in document order. */
+  favicons: Array<Icon>
+  /** Parsed JSON-LD blocks in document order. */
+  jsonLd: Array<any>
+}
+
 /**
  * Configuration options for HTML parsing.
  *
@@ -422,5 +785,20 @@ export interface SoupConfig {
   includeComments?: boolean
 }
 
+/**
+ * Options for the thread pool shared by parallel operations.
+ *
+ * @example
+ * ```javascript
+ * configureThreads({ threads: 4, stackSize: 8 * 1024 * 1024 });
+ * ```
+ */
+export interface ThreadPoolOptions {
+  /** Number of worker threads. Default: one per CPU */
+  threads?: number
+  /** Stack size of each worker thread in bytes. Default: platform default */
+  stackSize?: number
+}
+
 /** Get the library version. */
 export declare function version(): string
@@ -0,0 +1,51 @@
+//! Main article content returned by `Soup.extractArticle()`.
+
+use std::sync::Arc;
+
+use napi_derive::napi;
+use scrape_core::Soup as CoreSoup;
+
+use crate::tag::Tag;
+
+/// The main content of a page, as found by a browser's reader mode.
+///
+/// @example
+/// ```javascript
+/// const article = new Soup(html).extractArticle();
+/// console.log(article.title, article.byline);
+/// console.log(article.markdown);
+/// ```
+#[napi(object, object_from_js = false)]
+pub struct Article {
+    /// The element holding the content, or undefined for an empty document.
+    pub content: Option<Tag>,
+
+    /// The headline, without a trailing site name.
+    pub title: Option<String>,
+
+    /// The author, from `<meta name="author">` or a byline element.
+    pub byline: Option<String>,
+
+    /// The publication date as an ISO 8601 string, from meta tags, microdata, or a
+    /// `<time>` element.
+    pub published_date: Option<String>,
+
+    /// The content as plain text, with blank lines between paragraphs.
+    pub text: String,
+
+    /// The content as Markdown.
+    pub markdown: String,
+}
+
+impl Article {
+    pub(crate) fn new(soup: &Arc<CoreSoup>, article: scrape_core::article::Article) -> Self {
+        Self {
+            content: article.node.map(|node| Tag::new(Arc::clone(soup), node)),
+            title: article.title,
+            byline: article.byline,
+            published_date: article.published_date.map(|date| date.to_string()),
+            text: article.text,
+            markdown: article.markdown,
+        }
+    }
+}
@@ -1,5 +1,7 @@
 //! Configuration options for HTML parsing.
 
+use std::{collections::HashMap, time::Duration};
+
 use napi_derive::napi;
 
 /// Configuration options for HTML parsing.
@@ -42,3 +44,67 @@ impl SoupConfig {
             .build()
     }
 }
+
+/// Options for fetching a document with `Soup.fromUrl`.
+///
+/// @example
+/// ```javascript
+/// const options = {
+///   headers: { "Accept-Language": "en" },
+///   userAgent: "my-crawler/1.0",
+///   timeoutMs: 10000,
+///   maxRedirects: 5
+/// };
+/// const soup = await Soup.fromUrl("https://example.com/", options);
+/// ```
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct FetchOptions {
+    /// Additional request headers. Default: none
+    pub headers: Option<HashMap<String, String>>,
+
+    /// User-Agent header. Default: `scrape-rs/<version>`
+    pub user_agent: Option<String>,
+
+    /// Timeout for the whole request in milliseconds. Default: 30000
+    pub timeout_ms: Option<u32>,
+
+    /// Maximum number of redirects to follow. Default: 10
+    pub max_redirects: Option<u32>,
+
+    /// Maximum size of the response body in bytes. Default: 10 MiB
+    pub max_size: Option<u32>,
+}
+
+impl FetchOptions {
+    /// Convert to core FetchOptions.
+    #[must_use]
+    pub fn to_core(&self) -> scrape_core::fetch::FetchOptions {
+        let defaults = scrape_core::fetch::FetchOptions::default();
+        scrape_core::fetch::FetchOptions {
+            user_agent: self.user_agent.clone().unwrap_or(defaults.user_agent),
+            headers: self.headers.clone().unwrap_or_default().into_iter().collect(),
+            timeout: self
+                .timeout_ms
+                .map_or(defaults.timeout, |ms| Duration::from_millis(ms.into())),
+            max_redirects: self.max_redirects.unwrap_or(defaults.max_redirects),
+            max_size: self.max_size.map_or(defaults.max_size, |size| size as usize),
+        }
+    }
+}
+
+/// Options for the thread pool shared by parallel operations.
+///
+/// @example
+/// ```javascript
+/// configureThreads({ threads: 4, stackSize: 8 * 1024 * 1024 });
+/// ```
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ThreadPoolOptions {
+    /// Number of worker threads. Default: one per CPU
+    pub threads: Option<u32>,
+
+    /// Stack size of each worker thread in bytes. Default: platform default
+    pub stack_size: Option<u32>,
+}
@@ -13,9 +13,14 @@ pub trait IntoNapiError {
 
 impl IntoNapiError for QueryError {
     fn into_napi_error(self) -> Error {
+        let position =
+            self.column().map(|column| format!(" at column {column}")).unwrap_or_default();
         match self {
             QueryError::InvalidSelector { message, .. } => {
-                Error::new(Status::InvalidArg, format!("Invalid CSS selector: {message}"))
+                Error::new(Status::InvalidArg, format!("Invalid CSS selector{position}: {message}"))
+            }
+            QueryError::InvalidXPath { message } => {
+                Error::new(Status::InvalidArg, format!("Invalid XPath expression: {message}"))
             }
         }
     }
@@ -27,3 +32,25 @@ impl IntoNapiError for std::io::Error {
         Error::new(Status::GenericFailure, format!("I/O error: {self}"))
     }
 }
+
+/// Convert fetch errors to napi errors.
+impl IntoNapiError for scrape_core::fetch::FetchError {
+    fn into_napi_error(self) -> Error {
+        let status = match self {
+            scrape_core::fetch::FetchError::InvalidUrl(_) => Status::InvalidArg,
+            _ => Status::GenericFailure,
+        };
+        Error::new(status, self.to_string())
+    }
+}
+
+/// Convert core errors to napi errors.
+impl IntoNapiError for scrape_core::Error {
+    fn into_napi_error(self) -> Error {
+        let status = match self {
+            scrape_core::Error::UnknownEncoding { .. } => Status::InvalidArg,
+            _ => Status::GenericFailure,
+        };
+        Error::new(status, self.to_string())
+    }
+}
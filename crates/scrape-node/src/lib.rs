@@ -15,21 +15,32 @@
 //! // Parse multiple documents in parallel
 //! const htmls = ['<div>A</div>', '<div>B</div>'];
 //! const soups = parseBatch(htmls);
+//!
+//! // Same, without blocking the event loop
+//! const soupsAsync = await parseBatchAsync(htmls);
 //! ```
 
 #![deny(clippy::all)]
 
+mod article;
 mod config;
 mod error;
+mod metadata;
+mod selection;
 mod selector;
 mod soup;
 mod tag;
 
 use std::sync::Arc;
 
-pub use config::SoupConfig;
+pub use article::Article;
+pub use config::{FetchOptions, SoupConfig, ThreadPoolOptions};
+pub use metadata::{Icon, Metadata};
+use napi::{Env, Task, bindgen_prelude::AsyncTask};
 use napi_derive::napi;
 use rayon::prelude::*;
+use scrape_core::runtime;
+pub use selection::Selection;
 pub use selector::CompiledSelector;
 pub use soup::Soup;
 pub use tag::Tag;
@@ -55,14 +66,94 @@ pub use tag::Tag;
 #[napi(js_name = "parseBatch")]
 pub fn parse_batch(documents: Vec<String>, config: Option<SoupConfig>) -> Vec<Soup> {
     let core_config = config.map(|c| c.to_core()).unwrap_or_default();
+    parse_all(&documents, &core_config)
+        .into_iter()
+        .map(|soup| Soup { inner: Arc::new(soup) })
+        .collect()
+}
+
+/// Parse multiple HTML documents in parallel without blocking the event loop.
+///
+/// Parsing runs on the libuv worker pool, fanning out to Rayon threads,
+/// and the returned Promise resolves once every document is parsed.
+///
+/// @param documents - Array of HTML strings to parse
+/// @param config - Optional parsing configuration
+/// @returns Promise of Soup instances in the same order as input
+///
+/// @example
+/// ```javascript
+/// import { parseBatchAsync } from 'scrape-rs';
+///
+/// const soups = await parseBatchAsync(['<div>A</div>', '<div>B</div>']);
+/// const texts = soups.map(s => s.find('div').text);
+/// // texts: ['A', 'B']
+/// ```
+#[napi(js_name = "parseBatchAsync")]
+pub fn parse_batch_async(
+    documents: Vec<String>,
+    config: Option<SoupConfig>,
+) -> AsyncTask<ParseBatchTask> {
+    let config = config.map(|c| c.to_core()).unwrap_or_default();
+    AsyncTask::new(ParseBatchTask { documents, config })
+}
+
+/// Background task behind [`parse_batch_async`].
+pub struct ParseBatchTask {
+    documents: Vec<String>,
+    config: scrape_core::SoupConfig,
+}
 
-    documents
-        .par_iter()
-        .map(|html| {
-            let soup = scrape_core::Soup::parse_with_config(html, core_config.clone());
-            Soup { inner: Arc::new(soup) }
+#[napi]
+impl Task for ParseBatchTask {
+    type Output = Vec<scrape_core::Soup>;
+    type JsValue = Vec<Soup>;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        Ok(parse_all(&self.documents, &self.config))
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output.into_iter().map(|soup| Soup { inner: Arc::new(soup) }).collect())
+    }
+}
+
+fn parse_all(documents: &[String], config: &scrape_core::SoupConfig) -> Vec<scrape_core::Soup> {
+    runtime::install(|| {
+        documents
+            .par_iter()
+            .map(|html| scrape_core::Soup::parse_with_config(html, config.clone()))
+            .collect()
+    })
+}
+
+/// Configure the thread pool shared by all parallel operations.
+///
+/// Applies to `parseBatch`, `parseBatchAsync`, and every other parallel
+/// operation. Calling it again replaces the pool; calling it without
+/// options restores the default of one thread per CPU.
+///
+/// @param options - Thread count and stack size
+/// @throws Error if `threads` or `stackSize` is 0, or the pool cannot be created
+///
+/// @example
+/// ```javascript
+/// import { configureThreads, parseBatch } from 'scrape-rs';
+///
+/// configureThreads({ threads: 4 });
+/// const soups = parseBatch(htmls);
+/// ```
+#[napi(js_name = "configureThreads")]
+pub fn configure_threads(options: Option<ThreadPoolOptions>) -> napi::Result<()> {
+    let options = options.unwrap_or_default();
+    runtime::configure(options.threads.map(|n| n as usize), options.stack_size.map(|n| n as usize))
+        .map_err(|e| {
+            let status = match &e {
+                runtime::RuntimeError::Build(_) => napi::Status::GenericFailure,
+                _ => napi::Status::InvalidArg,
+            };
+            napi::Error::new(status, e.to_string())
         })
-        .collect()
 }
 
 /// Get the library version.
@@ -0,0 +1,90 @@
+//! Document metadata returned by `Soup.metadata()`.
+
+use std::collections::HashMap;
+
+use napi_derive::napi;
+
+/// Metadata collected from a document's head and JSON-LD blocks.
+///
+/// @example
+/// ```javascript
+/// const meta = new Soup(html).metadata();
+/// console.log(meta.title, meta.openGraph["og:image"], meta.jsonLd[0]);
+/// ```
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    /// Text of the first `<title>`.
+    pub title: Option<String>,
+
+    /// Content of `<meta name="description">`.
+    pub description: Option<String>,
+
+    /// The `lang` attribute of `<html>`.
+    pub lang: Option<String>,
+
+    /// The charset of the first `<meta charset>`.
+    pub charset: Option<String>,
+
+    /// The href of the first `<link rel="canonical">`.
+    pub canonical: Option<String>,
+
+    /// `<meta name>` and `<meta http-equiv>` content by lowercase name.
+    pub meta: HashMap<String, String>,
+
+    /// `<meta property>` values by lowercase property, such as `og:image`.
+    pub open_graph: HashMap<String, Vec<String>>,
+
+    /// Twitter card fields by lowercase name, such as `twitter:card`.
+    pub twitter: HashMap<String, String>,
+
+    /// Icons from `<link>` tags in document order.
+    pub favicons: Vec<Icon>,
+
+    /// Parsed JSON-LD blocks in document order.
+    pub json_ld: Vec<serde_json::Value>,
+}
+
+/// An icon declared with `<link rel="icon">`, `apple-touch-icon`, or `mask-icon`.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Icon {
+    /// The href attribute as written.
+    pub href: String,
+
+    /// Lowercase tokens of the rel attribute, joined by spaces.
+    pub rel: String,
+
+    /// The sizes attribute, such as `32x32`.
+    pub sizes: Option<String>,
+
+    /// The type attribute, such as `image/png`.
+    #[napi(js_name = "type")]
+    pub mime: Option<String>,
+}
+
+impl From<scrape_core::metadata::Metadata> for Metadata {
+    fn from(metadata: scrape_core::metadata::Metadata) -> Self {
+        Self {
+            title: metadata.title,
+            description: metadata.description,
+            lang: metadata.lang,
+            charset: metadata.charset,
+            canonical: metadata.canonical,
+            meta: metadata.meta.into_iter().collect(),
+            open_graph: metadata.open_graph.into_iter().collect(),
+            twitter: metadata.twitter.into_iter().collect(),
+            favicons: metadata
+                .favicons
+                .into_iter()
+                .map(|icon| Icon {
+                    href: icon.href,
+                    rel: icon.rel,
+                    sizes: icon.sizes,
+                    mime: icon.mime,
+                })
+                .collect(),
+            json_ld: metadata.json_ld,
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Node.js wrapper for Selection.
+
+use std::sync::Arc;
+
+use napi::{Status, bindgen_prelude::*};
+use napi_derive::napi;
+use scrape_core::{NodeId, QueryResult, Selection as CoreSelection, Soup as CoreSoup};
+
+use crate::{error::IntoNapiError, tag::Tag};
+
+/// A set of elements from one document, in document order.
+///
+/// Refining methods return a new Selection, so queries can be chained.
+///
+/// @example
+/// ```javascript
+/// const soup = new Soup('<ul><li class="sale"><a>A</a></li><li><a href="/b">B</a></li></ul>');
+/// const links = soup.selection("li").not(".sale").find("a");
+/// console.log(links.texts());        // ["B"]
+/// console.log(links.attrs("href"));  // ["/b"]
+/// ```
+#[napi]
+pub struct Selection {
+    soup: Arc<CoreSoup>,
+    ids: Vec<NodeId>,
+}
+
+impl Selection {
+    /// Create a new Selection from soup reference and node IDs in document order.
+    pub fn new(soup: Arc<CoreSoup>, ids: Vec<NodeId>) -> Self {
+        Self { soup, ids }
+    }
+
+    /// Get the core selection over the same elements.
+    fn inner(&self) -> CoreSelection<'_> {
+        CoreSelection::from_ordered(self.soup.document(), self.ids.clone())
+    }
+
+    /// Wrap a core selection of the same document.
+    fn wrap(&self, selection: &CoreSelection<'_>) -> Self {
+        Self::new(Arc::clone(&self.soup), selection.node_ids().to_vec())
+    }
+
+    fn wrap_result(&self, selection: QueryResult<CoreSelection<'_>>) -> Result<Self> {
+        selection.map(|selection| self.wrap(&selection)).map_err(IntoNapiError::into_napi_error)
+    }
+
+    fn check_same_document(&self, other: &Self) -> Result<()> {
+        if Arc::ptr_eq(&self.soup, &other.soup) {
+            Ok(())
+        } else {
+            Err(Error::new(
+                Status::InvalidArg,
+                "Cannot combine selections from different documents".to_string(),
+            ))
+        }
+    }
+
+    /// Resolve an index, counting from the end when negative.
+    fn resolve(&self, index: i32) -> Option<usize> {
+        usize::try_from(index)
+            .ok()
+            .or_else(|| self.ids.len().checked_sub(index.unsigned_abs() as usize))
+    }
+}
+
+#[napi]
+impl Selection {
+    /// Keep the elements that match a CSS selector.
+    ///
+    /// @param selector - CSS selector string
+    /// @returns A new Selection
+    /// @throws Error if the selector syntax is invalid
+    #[napi]
+    pub fn filter(&self, selector: String) -> Result<Selection> {
+        self.wrap_result(self.inner().filter(&selector))
+    }
+
+    /// Remove the elements that match a CSS selector.
+    ///
+    /// @param selector - CSS selector string
+    /// @returns A new Selection
+    /// @throws Error if the selector syntax is invalid
+    #[napi]
+    pub fn not(&self, selector: String) -> Result<Selection> {
+        self.wrap_result(self.inner().not(&selector))
+    }
+
+    /// Select the descendants of any element that match a CSS selector.
+    ///
+    /// @param selector - CSS selector string
+    /// @returns A new Selection, in document order without duplicates
+    /// @throws Error if the selector syntax is invalid
+    #[napi]
+    pub fn find(&self, selector: String) -> Result<Selection> {
+        self.wrap_result(self.inner().find(&selector))
+    }
+
+    /// Select the parent element of each element.
+    ///
+    /// @returns A new Selection, in document order without duplicates
+    #[napi]
+    pub fn parent(&self) -> Selection {
+        self.wrap(&self.inner().parent())
+    }
+
+    /// Select only the element at an index.
+    ///
+    /// @param index - Position of the element; negative values count from the end
+    /// @returns A Selection of one element, or an empty one if index is out of range
+    #[napi]
+    pub fn eq(&self, index: i32) -> Selection {
+        let ids = self.resolve(index).and_then(|i| self.ids.get(i)).copied();
+        Self::new(Arc::clone(&self.soup), ids.into_iter().collect())
+    }
+
+    /// Get the element at an index.
+    ///
+    /// @param index - Position of the element; negative values count from the end
+    /// @returns The Tag, or null if index is out of range
+    #[napi]
+    pub fn get(&self, index: i32) -> Option<Tag> {
+        self.resolve(index)
+            .and_then(|i| self.ids.get(i))
+            .map(|&id| Tag::new(Arc::clone(&self.soup), id))
+    }
+
+    /// Get the text content of each element.
+    ///
+    /// @returns Array of text strings, one per element
+    #[napi]
+    pub fn texts(&self) -> Vec<String> {
+        self.inner().texts()
+    }
+
+    /// Get an attribute value from each element.
+    ///
+    /// @param name - Attribute name
+    /// @returns Array of attribute values (null for missing attributes)
+    #[napi]
+    pub fn attrs(&self, name: String) -> Vec<Option<String>> {
+        self.inner().attrs(&name).into_iter().map(|value| value.map(String::from)).collect()
+    }
+
+    /// Get the elements in either selection.
+    ///
+    /// @param other - A Selection of the same document
+    /// @returns A new Selection, in document order without duplicates
+    /// @throws Error if the selections belong to different documents
+    #[napi]
+    pub fn union(&self, other: &Selection) -> Result<Selection> {
+        self.check_same_document(other)?;
+        Ok(self.wrap(&self.inner().union(&other.inner())))
+    }
+
+    /// Get the elements in both selections.
+    ///
+    /// @param other - A Selection of the same document
+    /// @returns A new Selection
+    /// @throws Error if the selections belong to different documents
+    #[napi]
+    pub fn intersection(&self, other: &Selection) -> Result<Selection> {
+        self.check_same_document(other)?;
+        Ok(self.wrap(&self.inner().intersection(&other.inner())))
+    }
+
+    /// Get the elements as an array of Tags.
+    ///
+    /// @returns Array of Tag instances in document order
+    #[napi(js_name = "toArray")]
+    pub fn to_array(&self) -> Vec<Tag> {
+        self.ids.iter().map(|&id| Tag::new(Arc::clone(&self.soup), id)).collect()
+    }
+
+    /// Get the number of elements.
+    #[napi(getter)]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn length(&self) -> u32 {
+        self.ids.len() as u32
+    }
+}
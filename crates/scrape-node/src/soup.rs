@@ -2,11 +2,19 @@
 
 use std::sync::Arc;
 
-use napi::bindgen_prelude::*;
+use napi::{Env, Task, bindgen_prelude::*};
 use napi_derive::napi;
 use scrape_core::Soup as CoreSoup;
 
-use crate::{config::SoupConfig, error::IntoNapiError, selector::CompiledSelector, tag::Tag};
+use crate::{
+    article::Article,
+    config::{FetchOptions, SoupConfig},
+    error::IntoNapiError,
+    metadata::Metadata,
+    selection::Selection,
+    selector::CompiledSelector,
+    tag::Tag,
+};
 
 /// A parsed HTML document.
 ///
@@ -49,6 +57,67 @@ impl Soup {
         Ok(Self::new(html, config))
     }
 
+    /// Parse HTML from a Buffer or Uint8Array of bytes in any encoding.
+    ///
+    /// The encoding is taken from a byte order mark, then `encoding`, then a
+    /// `<meta charset>` declaration, and defaults to UTF-8 for valid UTF-8 and
+    /// windows-1252 otherwise. The bytes are read in place, and UTF-8 input is parsed
+    /// without an intermediate string.
+    ///
+    /// @param buffer - The raw document bytes, such as a fetch or file read result
+    /// @param encoding - Optional encoding label, such as the charset of a Content-Type header
+    /// @param config - Optional parsing configuration
+    /// @returns A new Soup instance
+    /// @throws Error if `encoding` is not a known encoding label
+    ///
+    /// @example
+    /// ```javascript
+    /// const soup = Soup.fromBuffer(fs.readFileSync("page.html"));
+    /// const legacy = Soup.fromBuffer(body, "shift_jis");
+    /// ```
+    #[napi(factory, js_name = "fromBuffer")]
+    pub fn from_buffer(
+        buffer: Uint8Array,
+        encoding: Option<String>,
+        config: Option<SoupConfig>,
+    ) -> Result<Self> {
+        let core_config = config.map(|c| c.to_core()).unwrap_or_default();
+
+        let soup = CoreSoup::parse_bytes_with_config(&buffer, encoding.as_deref(), core_config)
+            .map_err(IntoNapiError::into_napi_error)?;
+        Ok(Self { inner: Arc::new(soup) })
+    }
+
+    /// Download and parse the HTML document at a URL without blocking the event loop.
+    ///
+    /// Redirects are followed and gzip and brotli responses decompressed. The body is
+    /// decoded with the charset of the Content-Type header, or detected as in
+    /// `fromBuffer`.
+    ///
+    /// @param url - An http:// or https:// URL
+    /// @param options - Optional request headers, user agent, timeout, and limits
+    /// @param config - Optional parsing configuration
+    /// @returns Promise of a new Soup instance
+    /// @throws Error if the request fails, times out, or answers with an error status
+    ///
+    /// @example
+    /// ```javascript
+    /// const soup = await Soup.fromUrl("https://example.com/", { timeoutMs: 5000 });
+    /// console.log(soup.title);
+    /// ```
+    #[napi(js_name = "fromUrl")]
+    pub fn from_url(
+        url: String,
+        options: Option<FetchOptions>,
+        config: Option<SoupConfig>,
+    ) -> AsyncTask<FetchTask> {
+        AsyncTask::new(FetchTask {
+            url,
+            options: options.unwrap_or_default().to_core(),
+            config: config.map(|c| c.to_core()).unwrap_or_default(),
+        })
+    }
+
     /// Find the first element matching a CSS selector.
     ///
     /// @param selector - CSS selector string
@@ -83,6 +152,17 @@ impl Soup {
         self.find_all(selector)
     }
 
+    /// Find all elements matching a CSS selector, as a chainable Selection.
+    ///
+    /// @param selector - CSS selector string
+    /// @returns A Selection of the matching elements in document order
+    /// @throws Error if the selector syntax is invalid
+    #[napi]
+    pub fn selection(&self, selector: String) -> Result<Selection> {
+        let selection = self.inner.selection(&selector).map_err(IntoNapiError::into_napi_error)?;
+        Ok(Selection::new(Arc::clone(&self.inner), selection.node_ids().to_vec()))
+    }
+
     /// Get the root element of the document.
     ///
     /// @returns The root Tag (usually <html>), or null for empty documents
@@ -99,6 +179,23 @@ impl Soup {
         self.inner.title()
     }
 
+    /// Get the document metadata: meta tags, Open Graph properties, Twitter card fields,
+    /// the canonical URL, favicons, and parsed JSON-LD blocks.
+    ///
+    /// @returns The document metadata
+    #[napi]
+    pub fn metadata(&self) -> Metadata {
+        self.inner.metadata().into()
+    }
+
+    /// Extract the main article content, like a browser's reader mode.
+    ///
+    /// @returns The content element, title, byline, and body as text and Markdown
+    #[napi(js_name = "extractArticle")]
+    pub fn extract_article(&self) -> Article {
+        Article::new(&self.inner, self.inner.extract_article())
+    }
+
     /// Get the text content of the entire document.
     ///
     /// @returns All text content with HTML tags stripped
@@ -219,3 +316,26 @@ impl Soup {
         self.inner.select_attr(&selector, &attr).map_err(IntoNapiError::into_napi_error)
     }
 }
+
+/// Background task behind [`Soup::from_url`].
+pub struct FetchTask {
+    url: String,
+    options: scrape_core::fetch::FetchOptions,
+    config: scrape_core::SoupConfig,
+}
+
+#[napi]
+impl Task for FetchTask {
+    type Output = CoreSoup;
+    type JsValue = Soup;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let response = scrape_core::fetch::fetch(&self.url, &self.options)
+            .map_err(IntoNapiError::into_napi_error)?;
+        Ok(CoreSoup::parse_with_config(&response.text(), self.config.clone()))
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(Soup { inner: Arc::new(output) })
+    }
+}
@@ -4,9 +4,9 @@ use std::{collections::HashMap, sync::Arc};
 
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
-use scrape_core::{Document, NodeId, NodeKind, Soup as CoreSoup};
+use scrape_core::{Document, NodeId, NodeKind, Soup as CoreSoup, SoupConfig};
 
-use crate::{error::IntoNapiError, selector::CompiledSelector};
+use crate::{Soup, error::IntoNapiError, selector::CompiledSelector};
 
 /// An HTML element in the document.
 ///
@@ -84,7 +84,7 @@ impl Tag {
             .get(self.id)
             .and_then(|n| n.kind.attributes())
             .and_then(|attrs| attrs.get(&name))
-            .cloned()
+            .map(str::to_string)
     }
 
     /// Get an attribute value by name (alias for attr).
@@ -111,7 +111,11 @@ impl Tag {
     /// Get all attributes as an object.
     #[napi(getter)]
     pub fn attrs(&self) -> HashMap<String, String> {
-        self.doc().get(self.id).and_then(|n| n.kind.attributes()).cloned().unwrap_or_default()
+        self.doc()
+            .get(self.id)
+            .and_then(|n| n.kind.attributes())
+            .map(|attrs| attrs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect())
+            .unwrap_or_default()
     }
 
     /// Check if the element has a specific class.
@@ -132,6 +136,47 @@ impl Tag {
             .unwrap_or_default()
     }
 
+    /// Get an attribute value parsed as an integer.
+    ///
+    /// @param name - The attribute name
+    /// @returns The integer value, or null if missing or not a valid integer
+    #[napi(js_name = "attrInt")]
+    pub fn attr_int(&self, name: String) -> Option<i64> {
+        self.get_attr(name).as_deref().and_then(scrape_core::utils::parse_attr_int)
+    }
+
+    /// Get an attribute value parsed as a boolean.
+    ///
+    /// A present attribute is true unless its value is "false", "0", "no", or "off".
+    ///
+    /// @param name - The attribute name
+    /// @returns The boolean value, or null if the attribute is missing
+    #[napi(js_name = "attrBool")]
+    pub fn attr_bool(&self, name: String) -> Option<bool> {
+        self.get_attr(name).as_deref().map(scrape_core::utils::parse_attr_bool)
+    }
+
+    /// Get the whitespace-separated tokens of an attribute value.
+    ///
+    /// @param name - The attribute name (e.g., "rel")
+    /// @returns Array of tokens, empty if the attribute is missing
+    #[napi(js_name = "attrList")]
+    pub fn attr_list(&self, name: String) -> Vec<String> {
+        self.get_attr(name)
+            .map(|s| scrape_core::utils::split_attr_list(&s).map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Get an attribute value resolved as a URL against a base URL.
+    ///
+    /// @param name - The attribute name (e.g., "href")
+    /// @param base - Absolute base URL to resolve against
+    /// @returns The absolute URL, or null if the attribute is missing or base is not absolute
+    #[napi(js_name = "attrUrl")]
+    pub fn attr_url(&self, name: String, base: String) -> Option<String> {
+        self.get_attr(name).and_then(|value| scrape_core::utils::resolve_url(&base, &value))
+    }
+
     // ==================== Navigation Properties ====================
 
     /// Get the parent element.
@@ -244,6 +289,21 @@ impl Tag {
         Ok(None)
     }
 
+    /// Check whether this element itself matches a CSS selector.
+    ///
+    /// Descendants are not searched, so this can filter the results of
+    /// `children`, `descendants`, and the other navigation getters.
+    ///
+    /// @param selector - CSS selector string
+    /// @returns True if the element matches
+    /// @throws Error if the selector syntax is invalid
+    #[napi]
+    pub fn matches(&self, selector: String) -> Result<bool> {
+        let selector = scrape_core::query::cached_compile(&selector)
+            .map_err(IntoNapiError::into_napi_error)?;
+        Ok(scrape_core::query::matches_selector(self.doc(), self.id, selector.selector_list()))
+    }
+
     /// Get all following sibling elements.
     #[napi(getter, js_name = "nextSiblings")]
     pub fn next_siblings(&self) -> Vec<Tag> {
@@ -340,6 +400,15 @@ impl Tag {
             .collect()
     }
 
+    /// Check whether this element itself matches a compiled selector.
+    ///
+    /// @param selector - A compiled CSS selector
+    /// @returns True if the element matches
+    #[napi(js_name = "matchesCompiled")]
+    pub fn matches_compiled(&self, selector: &CompiledSelector) -> bool {
+        scrape_core::query::matches_selector(self.doc(), self.id, selector.inner.selector_list())
+    }
+
     // ==================== Text and Iterator Methods ====================
 
     /// Get all direct text nodes (excluding descendants).
@@ -360,7 +429,7 @@ impl Tag {
             .filter_map(|child_id| {
                 let node = self.doc().get(child_id)?;
                 match &node.kind {
-                    NodeKind::Text { content } => Some(content.clone()),
+                    NodeKind::Text { content } => Some(content.to_string()),
                     _ => None,
                 }
             })
@@ -464,6 +533,24 @@ impl Tag {
         scrape_core::query::select_attr_within(self.doc(), self.id, &selector, &attr)
             .map_err(IntoNapiError::into_napi_error)
     }
+
+    /// Copy this element and its descendants into a standalone Soup.
+    ///
+    /// Only the subtree is copied, so the original document can be released
+    /// while the extracted region is kept.
+    ///
+    /// @returns A new Soup whose root is a copy of this element
+    ///
+    /// @example
+    /// ```javascript
+    /// const article = new Soup(page).find("article").extractSoup();
+    /// const title = article.find("h1").text;
+    /// ```
+    #[napi(js_name = "extractSoup")]
+    pub fn extract_soup(&self) -> Soup {
+        let document = self.doc().subtree(self.id).unwrap_or_default();
+        Soup { inner: Arc::new(CoreSoup::from_document(document, SoupConfig::default())) }
+    }
 }
 
 impl Clone for Tag {
@@ -2,6 +2,7 @@ const { describe, it, before } = require("node:test");
 const assert = require("node:assert");
 
 let parseBatch;
+let parseBatchAsync;
 
 describe("parseBatch", () => {
 	before(async () => {
@@ -76,3 +77,74 @@ describe("parseBatch", () => {
 		assert.strictEqual(soups[1].find(".content").text, "Content 2");
 	});
 });
+
+describe("parseBatchAsync", () => {
+	before(async () => {
+		const mod = await import("../index.js");
+		parseBatch = mod.parseBatch;
+		parseBatchAsync = mod.parseBatchAsync;
+	});
+
+	it("should return a Promise", async () => {
+		const pending = parseBatchAsync(["<div>A</div>"]);
+		assert.ok(pending instanceof Promise);
+		await pending;
+	});
+
+	it("should resolve documents in order", async () => {
+		const htmls = Array.from({ length: 50 }, (_, i) => `<span>${i}</span>`);
+		const soups = await parseBatchAsync(htmls);
+		assert.strictEqual(soups.length, 50);
+		soups.forEach((soup, i) => {
+			assert.strictEqual(soup.find("span").text, String(i));
+		});
+	});
+
+	it("should resolve an empty array", async () => {
+		const soups = await parseBatchAsync([]);
+		assert.strictEqual(soups.length, 0);
+	});
+
+	it("should accept a config", async () => {
+		const soups = await parseBatchAsync(["<div>A</div>", "<div>B</div>"], { maxDepth: 256 });
+		assert.strictEqual(soups.length, 2);
+		assert.strictEqual(soups[1].find("div").text, "B");
+	});
+
+	it("should match parseBatch", async () => {
+		const htmls = ["<p>One</p>", "<html><head><title>T</title></head></html>"];
+		const sync = parseBatch(htmls);
+		const soups = await parseBatchAsync(htmls);
+		assert.strictEqual(soups[0].find("p").text, sync[0].find("p").text);
+		assert.strictEqual(soups[1].title, sync[1].title);
+	});
+});
+
+describe("configureThreads", () => {
+	let configureThreads;
+
+	before(async () => {
+		const mod = await import("../index.js");
+		configureThreads = mod.configureThreads;
+		parseBatch = mod.parseBatch;
+	});
+
+	it("should size the shared pool used by parseBatch", () => {
+		const htmls = Array.from({ length: 64 }, (_, i) => `<div>${i}</div>`);
+		try {
+			configureThreads({ threads: 2, stackSize: 4 * 1024 * 1024 });
+			const texts = parseBatch(htmls).map((s) => s.find("div").text);
+			assert.deepStrictEqual(
+				texts,
+				htmls.map((_, i) => String(i)),
+			);
+		} finally {
+			configureThreads();
+		}
+	});
+
+	it("should reject zero sizes", () => {
+		assert.throws(() => configureThreads({ threads: 0 }), /thread count/);
+		assert.throws(() => configureThreads({ stackSize: 0 }), /stack size/);
+	});
+});
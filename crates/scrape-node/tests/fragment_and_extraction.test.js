@@ -160,6 +160,22 @@ describe("CompiledSelector", () => {
 		const results = soup.selectCompiled(selector);
 		assert.strictEqual(results.length, 0);
 	});
+
+	it("should match tags against selectors", () => {
+		const soup = new Soup(
+			"<ul><li class='item' data-x='1'><b>A</b></li><li class='item'>B</li></ul>",
+		);
+		const ul = soup.find("ul");
+
+		const items = ul?.children.filter((li) => li.matches(".item[data-x]"));
+		assert.deepStrictEqual(
+			items?.map((li) => li.text),
+			["A"],
+		);
+		assert.strictEqual(ul?.matches("b"), false);
+		assert.ok(ul?.children[1].matchesCompiled(CompiledSelector.compile("ul > li.item")));
+		assert.throws(() => ul?.matches("[[[invalid"));
+	});
 });
 
 // ==================== Extraction Methods Tests ====================
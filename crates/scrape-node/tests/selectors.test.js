@@ -200,6 +200,37 @@ describe("CSS Selectors", () => {
 		});
 	});
 
+	describe("selection", () => {
+		it("should filter, exclude, and pick elements", () => {
+			const items = new Soup(html).selection("li");
+			assert.strictEqual(items.length, 3);
+			assert.deepStrictEqual(items.filter(".active").texts(), ["First"]);
+			assert.deepStrictEqual(items.not(".active").texts(), ["Second", "Third"]);
+			assert.deepStrictEqual(items.eq(-1).texts(), ["Third"]);
+			assert.strictEqual(items.eq(3).length, 0);
+			assert.strictEqual(items.get(1).text, "Second");
+			assert.strictEqual(items.get(3), null);
+		});
+
+		it("should find descendants and parents in document order", () => {
+			const soup = new Soup(html);
+			const scopes = soup.selection("footer, #main");
+			assert.deepStrictEqual(scopes.find(".item").attrs("data-id"), ["1", "2", "3", null]);
+			assert.strictEqual(soup.selection("li").parent().length, 1);
+			assert.deepStrictEqual(scopes.toArray().map((tag) => tag.name), ["div", "footer"]);
+		});
+
+		it("should combine selections", () => {
+			const soup = new Soup(html);
+			const active = soup.selection(".active");
+			const last = soup.selection("li:last-child");
+			assert.deepStrictEqual(last.union(active).texts(), ["First", "Third"]);
+			assert.deepStrictEqual(soup.selection("li").intersection(last).texts(), ["Third"]);
+			assert.strictEqual(active.intersection(last).length, 0);
+			assert.throws(() => active.union(new Soup(html).selection("li")), /different documents/);
+		});
+	});
+
 	describe("error handling", () => {
 		it("should throw on invalid selector syntax", () => {
 			const soup = new Soup(html);
@@ -1,6 +1,7 @@
-const { describe, it, before } = require("node:test");
+const { describe, it, before, after } = require("node:test");
 const assert = require("node:assert");
 const fs = require("node:fs");
+const http = require("node:http");
 const path = require("node:path");
 const os = require("node:os");
 
@@ -60,6 +61,38 @@ describe("Soup", () => {
 			assert.strictEqual(soup.root.name, "html");
 		});
 
+		it("should extract metadata", () => {
+			const soup = new Soup(
+				'<head><title>Tea</title><meta property="og:image" content="a.png">' +
+					'<link rel="icon" href="/favicon.ico" type="image/x-icon">' +
+					'<script type="application/ld+json">{"@type": "Product"}</script></head>',
+			);
+			const meta = soup.metadata();
+			assert.strictEqual(meta.title, "Tea");
+			assert.strictEqual(meta.canonical, undefined);
+			assert.deepStrictEqual(meta.openGraph, { "og:image": ["a.png"] });
+			assert.strictEqual(meta.favicons[0].type, "image/x-icon");
+			assert.deepStrictEqual(meta.jsonLd, [{ "@type": "Product" }]);
+		});
+
+		it("should extract the main article", () => {
+			const soup = new Soup(
+				"<title>Brewing Tea | Blog</title><nav><a href='/'>Home</a></nav>" +
+					"<div class='post'><h2>Steps</h2>" +
+					"<p>Warm the pot, add the leaves, and pour <b>hot</b> water.</p></div>",
+			);
+			const article = soup.extractArticle();
+			assert.strictEqual(article.content.attr("class"), "post");
+			assert.strictEqual(article.title, "Brewing Tea");
+			assert.strictEqual(article.byline, undefined);
+			assert.strictEqual(article.publishedDate, undefined);
+			assert.strictEqual(
+				article.text,
+				"Steps\n\nWarm the pot, add the leaves, and pour hot water.",
+			);
+			assert.ok(article.markdown.includes("**hot**"));
+		});
+
 		it("should convert to HTML", () => {
 			const soup = new Soup("<div><span>text</span></div>");
 			const html = soup.toHtml();
@@ -174,6 +207,65 @@ describe("Soup", () => {
 		});
 	});
 
+	describe("fromBuffer", () => {
+		it("should parse UTF-8 bytes", () => {
+			const soup = Soup.fromBuffer(Buffer.from("<p>Café</p>"));
+			assert.strictEqual(soup.find("p").text, "Café");
+		});
+
+		it("should detect the encoding from meta charset", () => {
+			const bytes = Buffer.from([
+				...Buffer.from('<meta charset="latin1"><p>Caf'),
+				0xe9,
+				...Buffer.from("</p>"),
+			]);
+			assert.strictEqual(Soup.fromBuffer(bytes).find("p").text, "Café");
+		});
+
+		it("should prefer the given encoding over meta charset", () => {
+			const bytes = new Uint8Array([...Buffer.from("<meta charset=utf-8><p>"), 0xe9]);
+			assert.strictEqual(Soup.fromBuffer(bytes, "windows-1252").find("p").text, "é");
+		});
+
+		it("should throw on an unknown encoding", () => {
+			assert.throws(() => Soup.fromBuffer(Buffer.from("<p>"), "klingon"), /unknown encoding/);
+		});
+	});
+
+	describe("fromUrl", () => {
+		let server, base, headers;
+
+		before(async () => {
+			server = http.createServer((req, res) => {
+				headers = req.headers;
+				if (req.url === "/missing") {
+					res.writeHead(404).end();
+					return;
+				}
+				res.writeHead(200, { "Content-Type": "text/html; charset=windows-1252" });
+				res.end(Buffer.from([...Buffer.from("<title>Caf"), 0xe9, ...Buffer.from("</title>")]));
+			});
+			await new Promise((resolve) => server.listen(0, "127.0.0.1", resolve));
+			base = `http://127.0.0.1:${server.address().port}`;
+		});
+
+		after(() => server.close());
+
+		it("should fetch, decode, and parse a page", async () => {
+			const soup = await Soup.fromUrl(`${base}/page`, {
+				headers: { "X-Token": "abc" },
+				userAgent: "test-agent/1.0",
+			});
+			assert.strictEqual(soup.title, "Café");
+			assert.strictEqual(headers["x-token"], "abc");
+			assert.strictEqual(headers["user-agent"], "test-agent/1.0");
+		});
+
+		it("should reject on an error status", async () => {
+			await assert.rejects(Soup.fromUrl(`${base}/missing`), /HTTP 404/);
+		});
+	});
+
 	describe("version", () => {
 		it("should return version string", () => {
 			const v = version();
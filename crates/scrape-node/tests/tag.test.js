@@ -98,6 +98,20 @@ describe("Tag", () => {
 			assert.ok(classes.includes("baz"));
 		});
 
+		it("should parse typed attributes", () => {
+			const soup = new Soup(
+				'<a href="../b" rel="nofollow  ugc" data-count="7" data-new="no" hidden>x</a>',
+			);
+			const tag = soup.find("a");
+			assert.strictEqual(tag.attrInt("data-count"), 7);
+			assert.strictEqual(tag.attrInt("href"), null);
+			assert.strictEqual(tag.attrBool("hidden"), true);
+			assert.strictEqual(tag.attrBool("data-new"), false);
+			assert.strictEqual(tag.attrBool("missing"), null);
+			assert.deepStrictEqual(tag.attrList("rel"), ["nofollow", "ugc"]);
+			assert.strictEqual(tag.attrUrl("href", "https://example.com/a/c/d"), "https://example.com/a/b");
+		});
+
 		it("should return empty classes for elements without class", () => {
 			const soup = new Soup("<div>text</div>");
 			const tag = soup.find("div");
@@ -124,4 +138,13 @@ describe("Tag", () => {
 			assert.strictEqual(span.length, 0);
 		});
 	});
+
+	describe("extractSoup", () => {
+		it("should copy the subtree into a new Soup", () => {
+			const soup = new Soup("<div><article><p>A</p></article><p>B</p></div>");
+			const article = soup.find("article").extractSoup();
+			assert.strictEqual(article.root.name, "article");
+			assert.deepStrictEqual(article.selectText("p"), ["A"]);
+		});
+	});
 });
@@ -12,20 +12,24 @@ Example:
 
 from scrape_rs._core import (
     CompiledSelector,
+    Selection,
     Soup,
     SoupConfig,
     Tag,
     __version__,
     compile_selector,
+    configure_threads,
     parse_batch,
 )
 
 __all__ = [
     "CompiledSelector",
+    "Selection",
     "Soup",
     "SoupConfig",
     "Tag",
     "compile_selector",
+    "configure_threads",
     "parse_batch",
     "__version__",
 ]
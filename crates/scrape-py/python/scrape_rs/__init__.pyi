@@ -1,6 +1,7 @@
 """Type stubs for scrape_rs."""
 
 from collections.abc import Iterator
+from typing import Any
 
 __version__: str
 
@@ -34,6 +35,16 @@ class Tag:
         """Get text content of this element and all descendants."""
         ...
 
+    def get_text(self, separator: str = "", strip: bool = False) -> str:
+        """Get text content, joining text nodes with a separator.
+
+        Args:
+            separator: String written between text nodes.
+            strip: Whether to strip whitespace from each text node and skip
+                nodes that are only whitespace.
+        """
+        ...
+
     @property
     def inner_html(self) -> str:
         """Get inner HTML content (excluding this element's tags)."""
@@ -91,6 +102,22 @@ class Tag:
         """Check if element has a specific CSS class."""
         ...
 
+    def attr_int(self, name: str) -> int | None:
+        """Get attribute value parsed as an integer."""
+        ...
+
+    def attr_bool(self, name: str) -> bool | None:
+        """Get attribute value parsed as a boolean (None if missing)."""
+        ...
+
+    def attr_list(self, name: str) -> list[str]:
+        """Get whitespace-separated tokens of an attribute value."""
+        ...
+
+    def attr_url(self, name: str, base: str) -> str | None:
+        """Get attribute value resolved as a URL against an absolute base URL."""
+        ...
+
     def find(self, selector: str) -> Tag | None:
         """Find first descendant matching CSS selector."""
         ...
@@ -103,6 +130,26 @@ class Tag:
         """Find all descendants matching CSS selector (alias for find_all)."""
         ...
 
+    def iter_select(self, selector: str) -> Iterator[Tag]:
+        """Lazily iterate over descendants matching CSS selector.
+
+        Raises:
+            ValueError: If selector syntax is invalid.
+        """
+        ...
+
+    def matches(self, selector: str) -> bool:
+        """Check whether this element itself matches CSS selector.
+
+        Raises:
+            ValueError: If selector syntax is invalid.
+        """
+        ...
+
+    def extract_soup(self) -> Soup:
+        """Copy this element and its descendants into a standalone Soup."""
+        ...
+
     def __getitem__(self, name: str) -> str:
         """Get attribute value using dict-like access.
 
@@ -135,6 +182,85 @@ class Tag:
         """Get string representation for debugging."""
         ...
 
+class Selection:
+    """A set of elements from one document, in document order."""
+
+    def filter(self, selector: str) -> Selection:
+        """Keep the elements that match a CSS selector.
+
+        Raises:
+            ValueError: If selector syntax is invalid.
+        """
+        ...
+
+    def not_(self, selector: str) -> Selection:
+        """Remove the elements that match a CSS selector.
+
+        Raises:
+            ValueError: If selector syntax is invalid.
+        """
+        ...
+
+    def find(self, selector: str) -> Selection:
+        """Select the descendants of any element that match a CSS selector.
+
+        Raises:
+            ValueError: If selector syntax is invalid.
+        """
+        ...
+
+    def parent(self) -> Selection:
+        """Select the parent element of each element."""
+        ...
+
+    def eq(self, index: int) -> Selection:
+        """Select only the element at index (negative counts from the end)."""
+        ...
+
+    def texts(self) -> list[str]:
+        """Get the text content of each element."""
+        ...
+
+    def attrs(self, name: str) -> list[str | None]:
+        """Get an attribute value from each element (None where missing)."""
+        ...
+
+    def union(self, other: Selection) -> Selection:
+        """Get the elements in either selection.
+
+        Raises:
+            ValueError: If the selections belong to different documents.
+        """
+        ...
+
+    def intersection(self, other: Selection) -> Selection:
+        """Get the elements in both selections.
+
+        Raises:
+            ValueError: If the selections belong to different documents.
+        """
+        ...
+
+    def __or__(self, other: Selection) -> Selection:
+        """Union of two selections."""
+        ...
+
+    def __and__(self, other: Selection) -> Selection:
+        """Intersection of two selections."""
+        ...
+
+    def __getitem__(self, index: int) -> Tag:
+        """Get the element at index (negative counts from the end)."""
+        ...
+
+    def __len__(self) -> int:
+        """Get the number of elements."""
+        ...
+
+    def __iter__(self) -> Iterator[Tag]:
+        """Iterate over the elements."""
+        ...
+
 class Soup:
     """A parsed HTML document."""
 
@@ -170,6 +296,40 @@ class Soup:
         """
         ...
 
+    @staticmethod
+    def from_url(
+        url: str,
+        config: SoupConfig | None = None,
+        *,
+        headers: dict[str, str] | None = None,
+        user_agent: str | None = None,
+        timeout: float | None = None,
+        max_redirects: int | None = None,
+    ) -> Soup:
+        """Download and parse the HTML document at a URL.
+
+        Redirects are followed and gzip and brotli responses decompressed. The body is
+        decoded with the charset of the Content-Type header, or detected from a
+        `<meta charset>` declaration. The GIL is released during the request.
+
+        Args:
+            url: An http:// or https:// URL.
+            config: Optional parsing configuration.
+            headers: Optional additional request headers.
+            user_agent: Optional User-Agent header.
+            timeout: Optional timeout for the whole request in seconds (default: 30).
+            max_redirects: Optional maximum number of redirects to follow (default: 10).
+
+        Returns:
+            A new Soup instance.
+
+        Raises:
+            ValueError: If the URL or timeout is invalid.
+            TimeoutError: If the request times out.
+            OSError: If the request fails or answers with an error status.
+        """
+        ...
+
     @property
     def root(self) -> Tag | None:
         """Get the root element (usually <html>)."""
@@ -185,6 +345,36 @@ class Soup:
         """Get all text content with tags stripped."""
         ...
 
+    def get_text(self, separator: str = "", strip: bool = False) -> str:
+        """Get all text content, joining text nodes with a separator.
+
+        Args:
+            separator: String written between text nodes.
+            strip: Whether to strip whitespace from each text node and skip
+                nodes that are only whitespace.
+        """
+        ...
+
+    def metadata(self) -> dict[str, Any]:
+        """Get the document metadata.
+
+        Returns:
+            A dict with title, description, lang, charset, and canonical (None when
+            missing), meta, open_graph (property to list of values), twitter, favicons,
+            and json_ld (parsed JSON-LD blocks).
+        """
+        ...
+
+    def extract_article(self) -> dict[str, Any]:
+        """Extract the main article content, like a browser's reader mode.
+
+        Returns:
+            A dict with content (the Tag holding the article, or None), title, byline,
+            and published_date as an ISO 8601 string (None when missing), and the body
+            as text and markdown.
+        """
+        ...
+
     def find(self, selector: str) -> Tag | None:
         """Find the first element matching a CSS selector.
 
@@ -217,6 +407,31 @@ class Soup:
         """Find all elements matching a CSS selector (alias for find_all)."""
         ...
 
+    def iter_select(self, selector: str) -> Iterator[Tag]:
+        """Lazily iterate over elements matching a CSS selector.
+
+        Matches are found one at a time as the iterator advances, so no list
+        of all matches is built.
+
+        Args:
+            selector: CSS selector string.
+
+        Returns:
+            Iterator yielding matching Tag instances in document order.
+
+        Raises:
+            ValueError: If selector syntax is invalid.
+        """
+        ...
+
+    def selection(self, selector: str) -> Selection:
+        """Find all elements matching a CSS selector, as a chainable Selection.
+
+        Raises:
+            ValueError: If selector syntax is invalid.
+        """
+        ...
+
     def to_html(self) -> str:
         """Get the HTML representation of the document."""
         ...
@@ -235,13 +450,39 @@ def parse_batch(
 ) -> list[Soup]:
     """Parse multiple HTML documents in parallel.
 
-    Uses Rayon for parallel processing with automatic thread pool management.
+    Uses Rayon for parallel processing. The GIL is released while parsing.
 
     Args:
         documents: List of HTML strings to parse.
-        n_threads: Optional number of threads (defaults to CPU count).
+        n_threads: Optional number of threads (defaults to the pool set with
+            configure_threads, or CPU count). When given, a dedicated pool of that
+            size is used for this call.
 
     Returns:
         List of Soup instances in the same order as input.
+
+    Raises:
+        ValueError: If n_threads is 0.
+        RuntimeError: If the thread pool cannot be created.
+    """
+    ...
+
+def configure_threads(
+    n_threads: int | None = None,
+    stack_size: int | None = None,
+) -> None:
+    """Configure the thread pool shared by all parallel operations.
+
+    Applies to parse_batch calls without n_threads and to every other parallel
+    operation. Calling it again replaces the pool; calling it without arguments
+    restores the default of one thread per CPU.
+
+    Args:
+        n_threads: Number of worker threads (defaults to CPU count).
+        stack_size: Stack size of each worker thread in bytes.
+
+    Raises:
+        ValueError: If n_threads or stack_size is 0.
+        RuntimeError: If the thread pool cannot be created.
     """
     ...
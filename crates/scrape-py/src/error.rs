@@ -2,8 +2,11 @@
 //!
 //! Maps scrape_core errors to Python exceptions.
 
-use pyo3::{PyErr, exceptions::PyValueError};
-use scrape_core::QueryError;
+use pyo3::{
+    PyErr,
+    exceptions::{PyIOError, PyTimeoutError, PyValueError},
+};
+use scrape_core::{QueryError, fetch::FetchError};
 
 /// Convert query errors to Python exceptions.
 pub trait IntoPyErr {
@@ -13,10 +16,25 @@ pub trait IntoPyErr {
 
 impl IntoPyErr for QueryError {
     fn into_py_err(self) -> PyErr {
+        let position =
+            self.column().map(|column| format!(" at column {column}")).unwrap_or_default();
         match self {
             QueryError::InvalidSelector { message, .. } => {
-                PyValueError::new_err(format!("Invalid CSS selector: {message}"))
+                PyValueError::new_err(format!("Invalid CSS selector{position}: {message}"))
             }
+            QueryError::InvalidXPath { message } => {
+                PyValueError::new_err(format!("Invalid XPath expression: {message}"))
+            }
+        }
+    }
+}
+
+impl IntoPyErr for FetchError {
+    fn into_py_err(self) -> PyErr {
+        match self {
+            Self::Timeout(_) => PyTimeoutError::new_err(self.to_string()),
+            Self::InvalidUrl(_) => PyValueError::new_err(self.to_string()),
+            _ => PyIOError::new_err(self.to_string()),
         }
     }
 }
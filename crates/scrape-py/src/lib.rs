@@ -2,18 +2,24 @@
 //!
 //! This module provides Python bindings for the scrape-core library using PyO3.
 
-use pyo3::prelude::*;
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+};
+use scrape_core::runtime;
 
 mod config;
 mod error;
+mod selection;
 mod selector;
 mod soup;
 mod tag;
 
 use config::PySoupConfig;
+use selection::PySelection;
 use selector::PyCompiledSelector;
 use soup::PySoup;
-use tag::{PyTag, PyTagIterator};
+use tag::{PySelectIterator, PyTag, PyTagIterator};
 
 /// Parse multiple HTML documents in parallel.
 ///
@@ -22,41 +28,81 @@ use tag::{PyTag, PyTagIterator};
 ///
 /// Args:
 ///     documents: List of HTML strings to parse.
-///     n_threads: Optional number of threads (defaults to CPU count).
-///         Note: This parameter only takes effect on the first call per process.
-///         Rayon's global thread pool is initialized once and cannot be reconfigured.
-///         Subsequent calls with different n_threads values will be silently ignored.
+///     n_threads: Optional number of threads. Defaults to the shared pool set
+///         with `configure_threads`, or one thread per CPU. When given, a
+///         dedicated pool of that size is used for this call only.
 ///
 /// Returns:
 ///     List of Soup instances in the same order as input.
 ///
+/// Raises:
+///     ValueError: If n_threads is 0.
+///     RuntimeError: If the thread pool cannot be created.
+///
 /// Example:
 ///     >>> htmls = ["<div>A</div>", "<div>B</div>", "<div>C</div>"]
-///     >>> soups = parse_batch(htmls)
+///     >>> soups = parse_batch(htmls, n_threads=2)
 ///     >>> texts = [s.find("div").text for s in soups]
 ///     # Returns: A, B, C
 #[pyfunction]
 #[pyo3(signature = (documents, n_threads=None))]
-fn parse_batch(py: Python<'_>, documents: Vec<String>, n_threads: Option<usize>) -> Vec<PySoup> {
+fn parse_batch(
+    py: Python<'_>,
+    documents: Vec<String>,
+    n_threads: Option<usize>,
+) -> PyResult<Vec<PySoup>> {
     use std::sync::Arc;
 
-    // Configure thread pool if specified
-    if let Some(threads) = n_threads {
-        let _ = rayon::ThreadPoolBuilder::new().num_threads(threads).build_global();
-    }
+    use scrape_core::parallel::parse_batch_owned;
 
     // Release GIL during parsing using Python::detach
-    py.detach(|| {
-        use rayon::prelude::*;
+    let soups = py.detach(|| {
+        n_threads.map_or_else(
+            || Ok(parse_batch_owned(&documents)),
+            |threads| runtime::with_threads(threads, || parse_batch_owned(&documents)),
+        )
+    });
+    let soups = soups.map_err(|e| runtime_error(&e))?;
+
+    Ok(soups.into_iter().map(|soup| PySoup { inner: Arc::new(soup) }).collect())
+}
+
+/// Configure the thread pool shared by all parallel operations.
+///
+/// Applies to `parse_batch` calls without `n_threads` and to every other
+/// parallel operation in the library. Calling it again replaces the pool;
+/// calling it without arguments restores the default of one thread per CPU.
+///
+/// Args:
+///     n_threads: Number of worker threads. Defaults to one per CPU.
+///     stack_size: Stack size of each worker thread in bytes. Defaults to
+///         the platform default.
+///
+/// Raises:
+///     ValueError: If n_threads or stack_size is 0.
+///     RuntimeError: If the thread pool cannot be created.
+///
+/// Example:
+///     >>> from scrape_rs import configure_threads, parse_batch
+///     >>> configure_threads(n_threads=4)
+///     >>> soups = parse_batch(["<div>A</div>"] * 100)
+#[pyfunction]
+#[pyo3(signature = (n_threads=None, stack_size=None))]
+fn configure_threads(n_threads: Option<usize>, stack_size: Option<usize>) -> PyResult<()> {
+    runtime::configure(n_threads, stack_size).map_err(|e| runtime_error(&e))
+}
 
-        documents
-            .par_iter()
-            .map(|html| {
-                let soup = scrape_core::Soup::parse(html);
-                PySoup { inner: Arc::new(soup) }
-            })
-            .collect()
-    })
+/// Map a thread pool error to `ValueError` for invalid sizes, `RuntimeError` otherwise.
+fn runtime_error(err: &runtime::RuntimeError) -> PyErr {
+    match err {
+        runtime::RuntimeError::ZeroThreads => PyValueError::new_err("n_threads must be at least 1"),
+        runtime::RuntimeError::ZeroStackSize => {
+            PyValueError::new_err("stack_size must be greater than zero")
+        }
+        runtime::RuntimeError::Build(_) => {
+            PyRuntimeError::new_err(format!("Failed to create thread pool: {err}"))
+        }
+    }
 }
 
 /// Compile a CSS selector string for efficient repeated use.
@@ -87,8 +133,11 @@ fn _core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PySoup>()?;
     m.add_class::<PyTag>()?;
     m.add_class::<PyTagIterator>()?;
+    m.add_class::<PySelectIterator>()?;
+    m.add_class::<PySelection>()?;
     m.add_class::<PyCompiledSelector>()?;
     m.add_function(wrap_pyfunction!(parse_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(configure_threads, m)?)?;
     m.add_function(wrap_pyfunction!(compile_selector, m)?)?;
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     Ok(())
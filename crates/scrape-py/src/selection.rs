@@ -0,0 +1,214 @@
+//! Python wrapper for Selection.
+
+use std::sync::Arc;
+
+use pyo3::{
+    exceptions::{PyIndexError, PyValueError},
+    prelude::*,
+};
+use scrape_core::{NodeId, QueryResult, Selection, Soup};
+
+use crate::{
+    error::IntoPyErr,
+    tag::{PyTag, PyTagIterator},
+};
+
+/// A set of elements from one document, in document order.
+///
+/// Refining methods return a new Selection, so queries can be chained.
+///
+/// Example:
+///     >>> soup = Soup("<ul><li class='sale'><a>A</a></li><li><a href='/b'>B</a></li></ul>")
+///     >>> links = soup.selection("li").not_(".sale").find("a")
+///     >>> print(*links.texts())
+///     B
+///     >>> print(*links.attrs("href"))
+///     /b
+#[pyclass(name = "Selection")]
+pub struct PySelection {
+    soup: Arc<Soup>,
+    ids: Vec<NodeId>,
+}
+
+impl PySelection {
+    /// Create a new PySelection from soup reference and node IDs in document order.
+    pub fn new(soup: Arc<Soup>, ids: Vec<NodeId>) -> Self {
+        Self { soup, ids }
+    }
+
+    /// Get the core selection over the same elements.
+    fn inner(&self) -> Selection<'_> {
+        Selection::from_ordered(self.soup.document(), self.ids.clone())
+    }
+
+    /// Wrap a core selection of the same document.
+    fn wrap(&self, selection: &Selection<'_>) -> Self {
+        Self::new(Arc::clone(&self.soup), selection.node_ids().to_vec())
+    }
+
+    fn wrap_result(&self, selection: QueryResult<Selection<'_>>) -> PyResult<Self> {
+        selection.map(|selection| self.wrap(&selection)).map_err(IntoPyErr::into_py_err)
+    }
+
+    fn check_same_document(&self, other: &Self) -> PyResult<()> {
+        if Arc::ptr_eq(&self.soup, &other.soup) {
+            Ok(())
+        } else {
+            Err(PyValueError::new_err("Cannot combine selections from different documents"))
+        }
+    }
+
+    /// Resolve a Python index, counting from the end when negative.
+    fn resolve(&self, index: isize) -> Option<usize> {
+        usize::try_from(index).ok().or_else(|| self.ids.len().checked_sub(index.unsigned_abs()))
+    }
+}
+
+#[pymethods]
+impl PySelection {
+    /// Keep the elements that match a CSS selector.
+    ///
+    /// Args:
+    ///     selector: CSS selector string.
+    ///
+    /// Returns:
+    ///     A new Selection.
+    ///
+    /// Raises:
+    ///     ValueError: If the selector syntax is invalid.
+    fn filter(&self, selector: &str) -> PyResult<Self> {
+        self.wrap_result(self.inner().filter(selector))
+    }
+
+    /// Remove the elements that match a CSS selector.
+    ///
+    /// Args:
+    ///     selector: CSS selector string.
+    ///
+    /// Returns:
+    ///     A new Selection.
+    ///
+    /// Raises:
+    ///     ValueError: If the selector syntax is invalid.
+    #[pyo3(name = "not_")]
+    fn not(&self, selector: &str) -> PyResult<Self> {
+        self.wrap_result(self.inner().not(selector))
+    }
+
+    /// Select the descendants of any element that match a CSS selector.
+    ///
+    /// Args:
+    ///     selector: CSS selector string.
+    ///
+    /// Returns:
+    ///     A new Selection, in document order without duplicates.
+    ///
+    /// Raises:
+    ///     ValueError: If the selector syntax is invalid.
+    fn find(&self, selector: &str) -> PyResult<Self> {
+        self.wrap_result(self.inner().find(selector))
+    }
+
+    /// Select the parent element of each element.
+    ///
+    /// Returns:
+    ///     A new Selection, in document order without duplicates.
+    fn parent(&self) -> Self {
+        self.wrap(&self.inner().parent())
+    }
+
+    /// Select only the element at an index.
+    ///
+    /// Args:
+    ///     index: Position of the element; negative values count from the end.
+    ///
+    /// Returns:
+    ///     A Selection of one element, or an empty one if index is out of range.
+    fn eq(&self, index: isize) -> Self {
+        let ids = self.resolve(index).and_then(|i| self.ids.get(i)).copied();
+        Self::new(Arc::clone(&self.soup), ids.into_iter().collect())
+    }
+
+    /// Get the text content of each element.
+    ///
+    /// Returns:
+    ///     List of text strings, one per element.
+    fn texts(&self) -> Vec<String> {
+        self.inner().texts()
+    }
+
+    /// Get an attribute value from each element.
+    ///
+    /// Args:
+    ///     name: Attribute name.
+    ///
+    /// Returns:
+    ///     List of attribute values (None for missing attributes).
+    fn attrs(&self, name: &str) -> Vec<Option<String>> {
+        self.inner().attrs(name).into_iter().map(|value| value.map(String::from)).collect()
+    }
+
+    /// Get the elements in either selection.
+    ///
+    /// Args:
+    ///     other: A Selection of the same document.
+    ///
+    /// Returns:
+    ///     A new Selection, in document order without duplicates.
+    ///
+    /// Raises:
+    ///     ValueError: If the selections belong to different documents.
+    fn union(&self, other: &Self) -> PyResult<Self> {
+        self.check_same_document(other)?;
+        Ok(self.wrap(&self.inner().union(&other.inner())))
+    }
+
+    /// Get the elements in both selections.
+    ///
+    /// Args:
+    ///     other: A Selection of the same document.
+    ///
+    /// Returns:
+    ///     A new Selection.
+    ///
+    /// Raises:
+    ///     ValueError: If the selections belong to different documents.
+    fn intersection(&self, other: &Self) -> PyResult<Self> {
+        self.check_same_document(other)?;
+        Ok(self.wrap(&self.inner().intersection(&other.inner())))
+    }
+
+    /// Union of two selections: a | b
+    fn __or__(&self, other: &Self) -> PyResult<Self> {
+        self.union(other)
+    }
+
+    /// Intersection of two selections: a & b
+    fn __and__(&self, other: &Self) -> PyResult<Self> {
+        self.intersection(other)
+    }
+
+    /// Get the element at an index, counting from the end when negative.
+    fn __getitem__(&self, index: isize) -> PyResult<PyTag> {
+        self.resolve(index)
+            .and_then(|i| self.ids.get(i))
+            .map(|&id| PyTag::new(Arc::clone(&self.soup), id))
+            .ok_or_else(|| PyIndexError::new_err("Selection index out of range"))
+    }
+
+    /// Get the number of elements.
+    fn __len__(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Iterate over the elements.
+    fn __iter__(&self) -> PyTagIterator {
+        PyTagIterator::new(
+            self.ids.iter().map(|&id| PyTag::new(Arc::clone(&self.soup), id)).collect(),
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Selection(len={})", self.ids.len())
+    }
+}
@@ -1,11 +1,21 @@
 //! Python wrapper for Soup document.
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use pyo3::prelude::*;
-use scrape_core::Soup;
+use pyo3::{
+    IntoPyObjectExt,
+    prelude::*,
+    types::{PyDict, PyList},
+};
+use scrape_core::{Soup, TextOptions, fetch::FetchOptions};
 
-use crate::{config::PySoupConfig, error::IntoPyErr, selector::PyCompiledSelector, tag::PyTag};
+use crate::{
+    config::PySoupConfig,
+    error::IntoPyErr,
+    selection::PySelection,
+    selector::PyCompiledSelector,
+    tag::{PySelectIterator, PyTag},
+};
 
 /// A parsed HTML document.
 ///
@@ -58,6 +68,68 @@ impl PySoup {
         Ok(Self::new(&html, config))
     }
 
+    /// Download and parse the HTML document at a URL.
+    ///
+    /// Redirects are followed and gzip and brotli responses decompressed. The body is
+    /// decoded with the charset of the Content-Type header, or detected from a
+    /// `<meta charset>` declaration. The GIL is released during the request.
+    ///
+    /// Args:
+    ///     url: An http:// or https:// URL.
+    ///     config: Optional parsing configuration.
+    ///     headers: Optional additional request headers.
+    ///     user_agent: Optional User-Agent header.
+    ///     timeout: Optional timeout for the whole request in seconds (default: 30).
+    ///     max_redirects: Optional maximum number of redirects to follow (default: 10).
+    ///
+    /// Returns:
+    ///     A new Soup instance.
+    ///
+    /// Raises:
+    ///     ValueError: If the URL or timeout is invalid.
+    ///     TimeoutError: If the request times out.
+    ///     OSError: If the request fails or answers with an error status.
+    #[staticmethod]
+    #[pyo3(signature = (
+        url,
+        config=None,
+        *,
+        headers=None,
+        user_agent=None,
+        timeout=None,
+        max_redirects=None
+    ))]
+    fn from_url(
+        py: Python<'_>,
+        url: &str,
+        config: Option<&PySoupConfig>,
+        headers: Option<HashMap<String, String>>,
+        user_agent: Option<String>,
+        timeout: Option<f64>,
+        max_redirects: Option<u32>,
+    ) -> PyResult<Self> {
+        let mut options = FetchOptions::default();
+        options.headers.extend(headers.unwrap_or_default());
+        if let Some(user_agent) = user_agent {
+            options.user_agent = user_agent;
+        }
+        if let Some(timeout) = timeout {
+            options.timeout = Duration::try_from_secs_f64(timeout).map_err(|e| {
+                pyo3::exceptions::PyValueError::new_err(format!("Invalid timeout: {e}"))
+            })?;
+        }
+        if let Some(max_redirects) = max_redirects {
+            options.max_redirects = max_redirects;
+        }
+
+        let core_config = config.map(PySoupConfig::to_core).unwrap_or_default();
+        let soup = py.detach(|| {
+            scrape_core::fetch::fetch(url, &options)
+                .map(|response| Soup::parse_with_config(&response.text(), core_config))
+        });
+        Ok(Self { inner: Arc::new(soup.map_err(IntoPyErr::into_py_err)?) })
+    }
+
     /// Find the first element matching a CSS selector.
     ///
     /// Args:
@@ -102,6 +174,50 @@ impl PySoup {
         self.find_all(selector)
     }
 
+    /// Find all elements matching a CSS selector, as a chainable Selection.
+    ///
+    /// Args:
+    ///     selector: CSS selector string.
+    ///
+    /// Returns:
+    ///     A Selection of the matching elements in document order.
+    ///
+    /// Raises:
+    ///     ValueError: If the selector syntax is invalid.
+    ///
+    /// Example:
+    ///     >>> soup = Soup("<p class='a'>1</p><p>2</p><p class='a'>3</p>")
+    ///     >>> print(*soup.selection("p").filter(".a").texts())
+    ///     1 3
+    fn selection(&self, selector: &str) -> PyResult<PySelection> {
+        let selection = self.inner.selection(selector).map_err(IntoPyErr::into_py_err)?;
+        Ok(PySelection::new(Arc::clone(&self.inner), selection.node_ids().to_vec()))
+    }
+
+    /// Lazily iterate over elements matching a CSS selector.
+    ///
+    /// Unlike find_all, matches are found one at a time as the iterator
+    /// advances, so no list of all matches is built. This keeps peak memory
+    /// low on pages with many matches and lets a loop stop early.
+    ///
+    /// Args:
+    ///     selector: CSS selector string.
+    ///
+    /// Returns:
+    ///     An iterator yielding matching Tag instances in document order.
+    ///
+    /// Raises:
+    ///     ValueError: If the selector syntax is invalid.
+    ///
+    /// Example:
+    ///     >>> soup = Soup("<ul><li>A</li><li>B</li></ul>")
+    ///     >>> print(*(li.text for li in soup.iter_select("li")))
+    ///     A B
+    fn iter_select(&self, selector: &str) -> PyResult<PySelectIterator> {
+        let root = self.inner.document().root();
+        PySelectIterator::new(Arc::clone(&self.inner), selector, root, true)
+    }
+
     /// Find the first element using a pre-compiled selector.
     ///
     /// Args:
@@ -200,15 +316,62 @@ impl PySoup {
         self.inner.title()
     }
 
+    /// Get the document metadata.
+    ///
+    /// Returns:
+    ///     A dict with `title`, `description`, `lang`, `charset`, and `canonical`
+    ///     (None when missing), `meta` (name to content), `open_graph` (property to
+    ///     list of values), `twitter` (name to content), `favicons` (dicts with
+    ///     `href`, `rel`, `sizes`, and `type`), and `json_ld` (parsed JSON-LD blocks).
+    fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        json_to_py(py, &self.inner.metadata().into())
+    }
+
+    /// Extract the main article content, like a browser's reader mode.
+    ///
+    /// Returns:
+    ///     A dict with `content` (the Tag holding the article, or None for an empty
+    ///     document), `title`, `byline`, and `published_date` as an ISO 8601 string
+    ///     (None when missing), and the body as `text` (plain text) and `markdown`.
+    fn extract_article<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let article = self.inner.extract_article();
+        let dict = PyDict::new(py);
+        let content = article.node.map(|node| PyTag::new(Arc::clone(&self.inner), node));
+        dict.set_item("content", content)?;
+        dict.set_item("title", article.title)?;
+        dict.set_item("byline", article.byline)?;
+        dict.set_item("published_date", article.published_date.map(|date| date.to_string()))?;
+        dict.set_item("text", article.text)?;
+        dict.set_item("markdown", article.markdown)?;
+        Ok(dict)
+    }
+
     /// Get the text content of the entire document.
     ///
     /// Returns:
     ///     All text content with HTML tags stripped.
-    #[getter]
-    fn text(&self) -> String {
+    #[getter(text)]
+    fn text_content(&self) -> String {
         self.inner.text()
     }
 
+    /// Get the text content of the entire document, joining text nodes with a separator.
+    ///
+    /// Like BeautifulSoup's `get_text`.
+    ///
+    /// Args:
+    ///     separator: String written between text nodes (default: "").
+    ///     strip: Whether to strip whitespace from each text node and skip
+    ///         nodes that are only whitespace (default: False).
+    ///
+    /// Returns:
+    ///     The joined text content.
+    #[pyo3(signature = (separator="", strip=false))]
+    fn get_text(&self, separator: &str, strip: bool) -> String {
+        let options = TextOptions { separator: separator.to_string(), strip, ..Default::default() };
+        self.inner.text_with(&options)
+    }
+
     /// Get the HTML representation of the document.
     ///
     /// Returns:
@@ -230,3 +393,29 @@ impl PySoup {
         self.inner.document().len()
     }
 }
+
+/// Converts a JSON value to the equivalent Python object.
+fn json_to_py<'py>(py: Python<'py>, value: &serde_json::Value) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        serde_json::Value::Null => Ok(py.None().into_bound(py)),
+        serde_json::Value::Bool(b) => b.into_bound_py_any(py),
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.into_bound_py_any(py),
+            (None, Some(u)) => u.into_bound_py_any(py),
+            (None, None) => n.as_f64().unwrap_or(f64::NAN).into_bound_py_any(py),
+        },
+        serde_json::Value::String(s) => s.into_bound_py_any(py),
+        serde_json::Value::Array(items) => {
+            let items =
+                items.iter().map(|item| json_to_py(py, item)).collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_bound_py_any(py)
+        }
+        serde_json::Value::Object(fields) => {
+            let dict = PyDict::new(py);
+            for (key, value) in fields {
+                dict.set_item(key, json_to_py(py, value)?)?;
+            }
+            dict.into_bound_py_any(py)
+        }
+    }
+}
@@ -3,9 +3,12 @@
 use std::sync::Arc;
 
 use pyo3::{exceptions::PyKeyError, prelude::*, types::PyDict};
-use scrape_core::{Document, NodeId, NodeKind, Soup};
+use scrape_core::{
+    Document, NodeId, NodeKind, Soup, SoupConfig, TextOptions,
+    query::{CompiledSelector, matches_selector},
+};
 
-use crate::{error::IntoPyErr, selector::PyCompiledSelector};
+use crate::{error::IntoPyErr, selector::PyCompiledSelector, soup::PySoup};
 
 /// An HTML element in the document.
 ///
@@ -47,13 +50,32 @@ impl PyTag {
     }
 
     /// Get the text content of this element and all descendants.
-    #[getter]
-    fn text(&self) -> String {
+    #[getter(text)]
+    fn text_content(&self) -> String {
         let mut result = String::new();
         scrape_core::serialize::collect_text(self.doc(), self.id, &mut result);
         result
     }
 
+    /// Get the text content, joining text nodes with a separator.
+    ///
+    /// Like BeautifulSoup's `get_text`.
+    ///
+    /// Args:
+    ///     separator: String written between text nodes (default: "").
+    ///     strip: Whether to strip whitespace from each text node and skip
+    ///         nodes that are only whitespace (default: False).
+    ///
+    /// Returns:
+    ///     The joined text content.
+    #[pyo3(signature = (separator="", strip=false))]
+    fn get_text(&self, separator: &str, strip: bool) -> String {
+        let options = TextOptions { separator: separator.to_string(), strip, ..Default::default() };
+        let mut result = String::new();
+        scrape_core::serialize::collect_text_with(self.doc(), self.id, &options, &mut result);
+        result
+    }
+
     /// Get the inner HTML content (excluding this element's tags).
     #[getter]
     fn inner_html(&self) -> String {
@@ -84,7 +106,7 @@ impl PyTag {
             .get(self.id)
             .and_then(|n| n.kind.attributes())
             .and_then(|attrs| attrs.get(name))
-            .cloned()
+            .map(str::to_string)
     }
 
     /// Check if the element has an attribute.
@@ -109,7 +131,7 @@ impl PyTag {
             && let Some(attrs) = node.kind.attributes()
         {
             for (k, v) in attrs {
-                dict.set_item(k, v)?;
+                dict.set_item(k.as_str(), v)?;
             }
         }
         Ok(dict)
@@ -134,6 +156,55 @@ impl PyTag {
             .unwrap_or_default()
     }
 
+    /// Get an attribute value parsed as an integer.
+    ///
+    /// Args:
+    ///     name: The attribute name.
+    ///
+    /// Returns:
+    ///     The integer value, or None if missing or not a valid integer.
+    fn attr_int(&self, name: &str) -> Option<i64> {
+        self.get(name).as_deref().and_then(scrape_core::utils::parse_attr_int)
+    }
+
+    /// Get an attribute value parsed as a boolean.
+    ///
+    /// A present attribute is True unless its value is "false", "0", "no", or "off".
+    ///
+    /// Args:
+    ///     name: The attribute name.
+    ///
+    /// Returns:
+    ///     The boolean value, or None if the attribute is missing.
+    fn attr_bool(&self, name: &str) -> Option<bool> {
+        self.get(name).as_deref().map(scrape_core::utils::parse_attr_bool)
+    }
+
+    /// Get the whitespace-separated tokens of an attribute value.
+    ///
+    /// Args:
+    ///     name: The attribute name (e.g., "rel").
+    ///
+    /// Returns:
+    ///     List of tokens, empty if the attribute is missing.
+    fn attr_list(&self, name: &str) -> Vec<String> {
+        self.get(name)
+            .map(|s| scrape_core::utils::split_attr_list(&s).map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Get an attribute value resolved as a URL against a base URL.
+    ///
+    /// Args:
+    ///     name: The attribute name (e.g., "href").
+    ///     base: Absolute base URL to resolve against.
+    ///
+    /// Returns:
+    ///     The absolute URL, or None if the attribute is missing or base is not absolute.
+    fn attr_url(&self, name: &str, base: &str) -> Option<String> {
+        self.get(name).and_then(|value| scrape_core::utils::resolve_url(base, &value))
+    }
+
     // ==================== Navigation Properties ====================
 
     /// Get the parent element.
@@ -250,6 +321,25 @@ impl PyTag {
         Ok(None)
     }
 
+    /// Check whether this element itself matches a CSS selector.
+    ///
+    /// Descendants are not searched, so this can filter the results of
+    /// `children`, `descendants`, and the other navigation properties.
+    ///
+    /// Args:
+    ///     selector: CSS selector string.
+    ///
+    /// Returns:
+    ///     True if the element matches.
+    ///
+    /// Raises:
+    ///     ValueError: If the selector syntax is invalid.
+    fn matches(&self, selector: &str) -> PyResult<bool> {
+        let selector =
+            scrape_core::query::cached_compile(selector).map_err(IntoPyErr::into_py_err)?;
+        Ok(matches_selector(self.doc(), self.id, selector.selector_list()))
+    }
+
     /// Get all following sibling elements.
     #[getter]
     fn next_siblings(&self) -> Vec<PyTag> {
@@ -319,6 +409,23 @@ impl PyTag {
         self.find_all(selector)
     }
 
+    /// Lazily iterate over descendants matching a CSS selector.
+    ///
+    /// Unlike find_all, matches are found one at a time as the iterator
+    /// advances, so no list of all matches is built.
+    ///
+    /// Args:
+    ///     selector: CSS selector string.
+    ///
+    /// Returns:
+    ///     An iterator yielding matching Tag instances in document order.
+    ///
+    /// Raises:
+    ///     ValueError: If the selector syntax is invalid.
+    fn iter_select(&self, selector: &str) -> PyResult<PySelectIterator> {
+        PySelectIterator::new(Arc::clone(&self.soup), selector, Some(self.id), false)
+    }
+
     /// Find the first descendant using a pre-compiled selector.
     ///
     /// Args:
@@ -345,6 +452,17 @@ impl PyTag {
             .collect()
     }
 
+    /// Check whether this element itself matches a pre-compiled selector.
+    ///
+    /// Args:
+    ///     selector: A CompiledSelector instance.
+    ///
+    /// Returns:
+    ///     True if the element matches.
+    fn matches_compiled(&self, selector: &PyCompiledSelector) -> bool {
+        matches_selector(self.doc(), self.id, selector.inner.selector_list())
+    }
+
     /// Get all text nodes in this element's subtree.
     ///
     /// Returns:
@@ -429,6 +547,24 @@ impl PyTag {
             .map_err(IntoPyErr::into_py_err)
     }
 
+    /// Copy this element and its descendants into a standalone Soup.
+    ///
+    /// Only the subtree is copied, so the original document can be released
+    /// while the extracted region is kept.
+    ///
+    /// Returns:
+    ///     A new Soup whose root is a copy of this element.
+    ///
+    /// Example:
+    ///     >>> soup = Soup("<nav>Menu</nav><article><h1>Hi</h1></article>")
+    ///     >>> article = soup.find("article").extract_soup()
+    ///     >>> article.find("h1").text
+    ///     'Hi'
+    fn extract_soup(&self) -> PySoup {
+        let document = self.doc().subtree(self.id).unwrap_or_default();
+        PySoup { inner: Arc::new(Soup::from_document(document, SoupConfig::default())) }
+    }
+
     // ==================== Python Special Methods ====================
 
     fn __repr__(&self) -> String {
@@ -460,7 +596,7 @@ impl PyTag {
 
     /// Iterate over child elements.
     fn __iter__(&self) -> PyTagIterator {
-        PyTagIterator { children: self.children(), index: 0 }
+        PyTagIterator::new(self.children())
     }
 
     /// Compare two tags for equality (same document, same node).
@@ -490,6 +626,13 @@ pub struct PyTagIterator {
     index: usize,
 }
 
+impl PyTagIterator {
+    /// Create an iterator over the given tags.
+    pub fn new(children: Vec<PyTag>) -> Self {
+        Self { children, index: 0 }
+    }
+}
+
 #[pymethods]
 impl PyTagIterator {
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
@@ -506,3 +649,66 @@ impl PyTagIterator {
         }
     }
 }
+
+/// Iterator over elements matching a selector, found as it advances.
+#[pyclass(name = "SelectIterator")]
+pub struct PySelectIterator {
+    soup: Arc<Soup>,
+    selector: CompiledSelector,
+    scope: Option<NodeId>,
+    next: Option<NodeId>,
+}
+
+impl PySelectIterator {
+    /// Create an iterator over matches among the descendants of `scope`.
+    ///
+    /// With `include_scope`, `scope` itself is matched first.
+    pub fn new(
+        soup: Arc<Soup>,
+        selector: &str,
+        scope: Option<NodeId>,
+        include_scope: bool,
+    ) -> PyResult<Self> {
+        let selector = CompiledSelector::compile(selector).map_err(IntoPyErr::into_py_err)?;
+        let next = if include_scope {
+            scope
+        } else {
+            scope.and_then(|id| soup.document().first_child(id))
+        };
+        Ok(Self { soup, selector, scope, next })
+    }
+
+    /// Returns the node after `id` in document order, without leaving `scope`.
+    fn following(doc: &Document, mut id: NodeId, scope: Option<NodeId>) -> Option<NodeId> {
+        if let Some(child) = doc.first_child(id) {
+            return Some(child);
+        }
+        while Some(id) != scope {
+            if let Some(sibling) = doc.next_sibling(id) {
+                return Some(sibling);
+            }
+            id = doc.parent(id)?;
+        }
+        None
+    }
+}
+
+#[pymethods]
+impl PySelectIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<PyTag> {
+        let doc = self.soup.document();
+        while let Some(id) = self.next {
+            self.next = Self::following(doc, id, self.scope);
+            if doc.get(id).is_some_and(|n| n.kind.is_element())
+                && matches_selector(doc, id, self.selector.selector_list())
+            {
+                return Some(PyTag::new(Arc::clone(&self.soup), id));
+            }
+        }
+        None
+    }
+}
@@ -1,6 +1,8 @@
 """Tests for batch parsing."""
 
-from scrape_rs import Soup, parse_batch
+import pytest
+
+from scrape_rs import Soup, configure_threads, parse_batch
 
 
 class TestParseBatch:
@@ -26,6 +28,32 @@ class TestParseBatch:
         soups = parse_batch(htmls, n_threads=4)
         assert len(soups) == 50
 
+    def test_parse_batch_thread_count_per_call(self):
+        htmls = [f"<div>{i}</div>" for i in range(64)]
+        for n_threads in (1, 3, 2):
+            soups = parse_batch(htmls, n_threads=n_threads)
+            assert [s.find("div").text for s in soups] == [str(i) for i in range(64)]
+
+    def test_parse_batch_zero_threads_raises(self):
+        with pytest.raises(ValueError, match="n_threads"):
+            parse_batch(["<div>A</div>"], n_threads=0)
+
+    def test_configure_threads(self):
+        htmls = [f"<div>{i}</div>" for i in range(64)]
+        try:
+            configure_threads(n_threads=2, stack_size=4 * 1024 * 1024)
+            soups = parse_batch(htmls)
+            assert [s.find("div").text for s in soups] == [str(i) for i in range(64)]
+            assert len(parse_batch(htmls, n_threads=3)) == 64
+        finally:
+            configure_threads()
+
+    def test_configure_threads_zero_raises(self):
+        with pytest.raises(ValueError, match="n_threads"):
+            configure_threads(n_threads=0)
+        with pytest.raises(ValueError, match="stack_size"):
+            configure_threads(stack_size=0)
+
     def test_parse_batch_empty_list(self):
         soups = parse_batch([])
         assert soups == []
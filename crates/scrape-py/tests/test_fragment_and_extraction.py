@@ -158,6 +158,17 @@ class TestCompiledSelector:
         results = soup.select_compiled(selector)
         assert len(results) == 0
 
+    def test_tag_matches(self):
+        soup = Soup("<ul><li class='item' data-x='1'><b>A</b></li><li class='item'>B</li></ul>")
+        ul = soup.find("ul")
+
+        items = [li for li in ul.children if li.matches(".item[data-x]")]
+        assert [li.text for li in items] == ["A"]
+        assert not ul.matches("b")
+        assert ul.children[1].matches_compiled(compile_selector("ul > li.item"))
+        with pytest.raises(ValueError):
+            ul.matches("[[[invalid")
+
 
 # ==================== Extraction Methods Tests ====================
 
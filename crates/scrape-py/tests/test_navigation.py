@@ -113,6 +113,11 @@ class TestScopedQueries:
         spans2 = container.select("span")
         assert len(spans1) == len(spans2)
 
+    def test_iter_select_within(self, nav_soup):
+        container = nav_soup.find("#container")
+        ids = [span.get("id") for span in container.iter_select("span, div")]
+        assert ids == ["first", "second", "third"]  # excludes the container and footer
+
     def test_find_within_invalid_selector_raises(self, nav_soup):
         container = nav_soup.find("#container")
         with pytest.raises(ValueError):
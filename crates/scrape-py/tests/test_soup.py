@@ -1,5 +1,8 @@
 """Tests for Soup class."""
 
+import threading
+from http.server import BaseHTTPRequestHandler, HTTPServer
+
 import pytest
 
 from scrape_rs import Soup, SoupConfig
@@ -44,6 +47,11 @@ class TestSoupProperties:
         assert "Hello" in simple_soup.text
         assert "<div>" not in simple_soup.text
 
+    def test_get_text(self):
+        soup = Soup("<ul><li> One </li><li>Two</li></ul>")
+        assert soup.get_text() == " One Two"
+        assert soup.get_text("\n", strip=True) == "One\nTwo"
+
     def test_root_is_html(self, complex_soup):
         assert complex_soup.root.name == "html"
 
@@ -63,6 +71,39 @@ class TestSoupProperties:
         s = str(simple_soup)
         assert "<div>" in s
 
+    def test_metadata(self):
+        soup = Soup(
+            '<head><title>Tea</title><meta property="og:image" content="a.png">'
+            '<meta name="twitter:card" content="summary">'
+            '<link rel="icon" href="/favicon.ico" sizes="32x32">'
+            '<script type="application/ld+json">{"@type": "Product", "offers": [1, 2.5]}</script>'
+            "</head>"
+        )
+        meta = soup.metadata()
+        assert meta["title"] == "Tea"
+        assert meta["canonical"] is None
+        assert meta["open_graph"] == {"og:image": ["a.png"]}
+        assert meta["twitter"] == {"twitter:card": "summary"}
+        assert meta["favicons"] == [
+            {"href": "/favicon.ico", "rel": "icon", "sizes": "32x32", "type": None}
+        ]
+        assert meta["json_ld"] == [{"@type": "Product", "offers": [1, 2.5]}]
+
+    def test_extract_article(self):
+        soup = Soup(
+            "<title>Brewing Tea | Blog</title><nav><a href='/'>Home</a></nav>"
+            "<div class='post'><h2>Steps</h2>"
+            "<p>Warm the pot, add the leaves, and pour <b>hot</b> water.</p></div>"
+        )
+        article = soup.extract_article()
+        assert article["content"]["class"] == "post"
+        assert article["title"] == "Brewing Tea"
+        assert article["byline"] is None
+        assert article["published_date"] is None
+        assert article["text"] == "Steps\n\nWarm the pot, add the leaves, and pour hot water."
+        assert article["markdown"].startswith("## Steps\n\n")
+        assert "**hot**" in article["markdown"]
+
 
 class TestSoupFind:
     def test_find_by_tag(self, complex_soup):
@@ -112,6 +153,56 @@ class TestSoupFind:
         items2 = complex_soup.select(".item")
         assert len(items1) == len(items2)
 
+    def test_iter_select(self, complex_soup):
+        items = complex_soup.iter_select(".item")
+        assert not isinstance(items, list)
+        assert iter(items) is items
+        assert [t.text for t in items] == [t.text for t in complex_soup.select(".item")]
+        assert next(items, None) is None
+
+    def test_iter_select_includes_root_and_stops_early(self, complex_soup):
+        assert next(complex_soup.iter_select("html")).name == "html"
+        first = next(complex_soup.iter_select("li"))
+        assert first == complex_soup.find("li")
+
+    def test_iter_select_invalid_selector(self, complex_soup):
+        with pytest.raises(ValueError):
+            complex_soup.iter_select("div[[[")
+
+
+class TestSelection:
+    def test_filter_not_and_eq(self, complex_soup):
+        items = complex_soup.selection("li")
+        assert len(items) == 3
+        assert items.filter(".active").texts() == ["First"]
+        assert items.not_(".active").texts() == ["Second", "Third"]
+        assert items.eq(-1).texts() == ["Third"]
+        assert len(items.eq(3)) == 0
+        assert items[1].text == "Second"
+        with pytest.raises(IndexError):
+            items[3]
+
+    def test_find_parent_and_attrs(self, complex_soup):
+        main = complex_soup.selection("#main, footer")
+        assert main.find("a, span").texts() == ["Click me", "Copyright 2026"]
+        assert len(complex_soup.selection("li").parent()) == 1
+        assert main.find("h1, a").attrs("href") == [None, "/link"]
+        assert [tag.name for tag in main] == ["div", "footer"]
+
+    def test_union_and_intersection(self, complex_soup):
+        first = complex_soup.selection("li.active")
+        last = complex_soup.selection("li:last-child")
+        assert (last | first).texts() == ["First", "Third"]
+        assert first.union(first).texts() == ["First"]
+        assert (complex_soup.selection(".item") & last).texts() == ["Third"]
+        assert len(first.intersection(last)) == 0
+
+    def test_errors(self, complex_soup, simple_soup):
+        with pytest.raises(ValueError):
+            complex_soup.selection("li").filter("div[[[")
+        with pytest.raises(ValueError):
+            complex_soup.selection("div").union(simple_soup.selection("div"))
+
 
 class TestSoupErrors:
     def test_invalid_selector_raises_value_error(self, simple_soup):
@@ -140,3 +231,49 @@ class TestSoupFromFile:
         with pytest.raises(ValueError) as exc_info:
             Soup.from_file("/nonexistent/path.html")
         assert "read" in str(exc_info.value).lower()
+
+
+class _PageHandler(BaseHTTPRequestHandler):
+    def do_GET(self):
+        self.server.headers_seen = dict(self.headers)
+        if self.path == "/missing":
+            self.send_response(404)
+            self.send_header("Content-Length", "0")
+            self.end_headers()
+            return
+        body = b"<title>Caf\xe9</title>"
+        self.send_response(200)
+        self.send_header("Content-Type", "text/html; charset=windows-1252")
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, *args):
+        pass
+
+
+@pytest.fixture
+def http_base():
+    server = HTTPServer(("127.0.0.1", 0), _PageHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield server, f"http://127.0.0.1:{server.server_port}"
+    server.shutdown()
+
+
+class TestSoupFromUrl:
+    def test_from_url(self, http_base):
+        server, base = http_base
+        soup = Soup.from_url(f"{base}/page", headers={"X-Token": "abc"}, user_agent="test/1.0")
+        assert soup.title == "Café"
+        assert server.headers_seen["X-Token"] == "abc"
+        assert server.headers_seen["User-Agent"] == "test/1.0"
+
+    def test_from_url_error_status_raises(self, http_base):
+        _, base = http_base
+        with pytest.raises(OSError, match="HTTP 404"):
+            Soup.from_url(f"{base}/missing")
+
+    def test_from_url_negative_timeout_raises(self):
+        with pytest.raises(ValueError):
+            Soup.from_url("http://127.0.0.1:1/", timeout=-1.0)
@@ -18,6 +18,12 @@ class TestTagContent:
         assert "Hello" in tag.text
         assert "World" in tag.text
 
+    def test_get_text(self, tag):
+        assert tag.get_text() == tag.text
+        assert tag.get_text("|") == "Hello |World"
+        assert tag.get_text(" ", strip=True) == "Hello World"
+        assert tag.get_text(separator="|", strip=True) == "Hello|World"
+
     def test_inner_html(self, tag):
         html = tag.inner_html
         assert "Hello" in html
@@ -62,6 +68,32 @@ class TestTagAttributes:
         assert "link" in classes
         assert "primary" in classes
 
+    def test_attr_bool(self, tag):
+        assert tag.attr_bool("disabled") is True
+        assert tag.attr_bool("title") is None
+
+    def test_attr_list(self, tag):
+        assert tag.attr_list("class") == ["link", "primary"]
+        assert tag.attr_list("rel") == []
+
+    def test_attr_url(self, tag):
+        assert tag.attr_url("href", "https://example.com/a/b") == "https://example.com/page"
+        assert tag.attr_url("href", "relative/base") is None
+
+
+class TestTagTypedAttributes:
+    def test_attr_int(self):
+        tag = Soup('<div data-count=" 12 " data-size="big"></div>').find("div")
+        assert tag.attr_int("data-count") == 12
+        assert tag.attr_int("data-size") is None
+        assert tag.attr_int("data-missing") is None
+
+    def test_attr_bool_false_tokens(self):
+        tag = Soup('<div data-a="false" data-b="0" data-c="true"></div>').find("div")
+        assert tag.attr_bool("data-a") is False
+        assert tag.attr_bool("data-b") is False
+        assert tag.attr_bool("data-c") is True
+
 
 class TestTagPythonProtocol:
     @pytest.fixture
@@ -123,3 +155,15 @@ class TestTagPythonProtocol:
         tag1 = soup.find("span")
         tag2 = soup.find("span")
         assert hash(tag1) == hash(tag2)
+
+
+class TestTagExtractSoup:
+    def test_extract_soup(self):
+        soup = Soup("<div><article><p>A</p></article><p>B</p></div>")
+        article = soup.find("article").extract_soup()
+        assert article.root.name == "article"
+        assert [p.text for p in article.find_all("p")] == ["A"]
+
+    def test_extract_soup_outlives_original(self):
+        article = Soup("<article><h1>Title</h1></article>").find("article").extract_soup()
+        assert article.find("h1").text == "Title"
@@ -69,6 +69,36 @@ export declare class Tag {
 	/** Get all classes as an array. */
 	readonly classes: string[];
 
+	/**
+	 * Get an attribute value parsed as an integer.
+	 * @param name - The attribute name
+	 * @returns The integer value, or undefined if missing or not a valid integer
+	 */
+	attrInt(name: string): number | undefined;
+
+	/**
+	 * Get an attribute value parsed as a boolean.
+	 * A present attribute is true unless its value is "false", "0", "no", or "off".
+	 * @param name - The attribute name
+	 * @returns The boolean value, or undefined if the attribute is missing
+	 */
+	attrBool(name: string): boolean | undefined;
+
+	/**
+	 * Get the whitespace-separated tokens of an attribute value.
+	 * @param name - The attribute name (e.g., "rel")
+	 * @returns Array of tokens, empty if the attribute is missing
+	 */
+	attrList(name: string): string[];
+
+	/**
+	 * Get an attribute value resolved as a URL against a base URL.
+	 * @param name - The attribute name (e.g., "href")
+	 * @param base - Absolute base URL to resolve against
+	 * @returns The absolute URL, or undefined if the attribute is missing or base is not absolute
+	 */
+	attrUrl(name: string, base: string): string | undefined;
+
 	/** Get the parent element. */
 	readonly parent: Tag | undefined;
 
@@ -84,6 +114,39 @@ export declare class Tag {
 	/** Get all descendant elements. */
 	readonly descendants: Tag[];
 
+	/** Get all ancestor elements (from parent toward root). */
+	readonly parents: Tag[];
+
+	/** Get all ancestor elements (alias for parents). */
+	readonly ancestors: Tag[];
+
+	/**
+	 * Find the nearest ancestor matching a CSS selector.
+	 * @param selector - CSS selector string
+	 * @returns The nearest matching ancestor Tag, or undefined if not found
+	 * @throws Error if the selector syntax is invalid
+	 */
+	closest(selector: string): Tag | undefined;
+
+	/**
+	 * Check whether this element itself matches a CSS selector.
+	 * Descendants are not searched, so this can filter the results of
+	 * `children`, `descendants`, and the other navigation getters.
+	 * @param selector - CSS selector string
+	 * @returns True if the element matches
+	 * @throws Error if the selector syntax is invalid
+	 */
+	matches(selector: string): boolean;
+
+	/** Get all following sibling elements. */
+	readonly nextSiblings: Tag[];
+
+	/** Get all preceding sibling elements (in reverse order). */
+	readonly prevSiblings: Tag[];
+
+	/** Get all sibling elements (excluding self, in document order). */
+	readonly siblings: Tag[];
+
 	/**
 	 * Find the first descendant matching a CSS selector.
 	 * @param selector - CSS selector string
@@ -110,6 +173,195 @@ export declare class Tag {
 
 	/** Get the number of direct child elements. */
 	readonly length: number;
+
+	/**
+	 * Find the first descendant matching a compiled selector.
+	 * @param selector - A compiled CSS selector
+	 * @returns The first matching Tag, or undefined if not found
+	 */
+	findCompiled(selector: CompiledSelector): Tag | undefined;
+
+	/**
+	 * Find all descendants matching a compiled selector.
+	 * @param selector - A compiled CSS selector
+	 * @returns Array of matching Tag instances
+	 */
+	selectCompiled(selector: CompiledSelector): Tag[];
+
+	/**
+	 * Check whether this element itself matches a compiled selector.
+	 * @param selector - A compiled CSS selector
+	 * @returns True if the element matches
+	 */
+	matchesCompiled(selector: CompiledSelector): boolean;
+
+	/** Get all direct text nodes (excluding descendants). */
+	readonly textNodes: string[];
+
+	/**
+	 * Get all direct child elements with a specific tag name.
+	 * @param name - The tag name to filter by
+	 * @returns Array of matching child Tag instances
+	 */
+	childrenByName(name: string): Tag[];
+
+	/**
+	 * Get all direct child elements with a specific class.
+	 * @param className - The class name to filter by
+	 * @returns Array of matching child Tag instances
+	 */
+	childrenByClass(className: string): Tag[];
+
+	/**
+	 * Extract text content from all descendants matching a selector.
+	 * @param selector - CSS selector string
+	 * @returns Array of text content strings
+	 * @throws Error if the selector syntax is invalid
+	 */
+	selectText(selector: string): string[];
+
+	/**
+	 * Extract attribute values from all descendants matching a selector.
+	 * @param selector - CSS selector string
+	 * @param attr - Attribute name to extract
+	 * @returns Array of attribute values (undefined if attribute is missing)
+	 * @throws Error if the selector syntax is invalid
+	 */
+	selectAttr(selector: string, attr: string): (string | undefined)[];
+
+	/**
+	 * Copy this element and its descendants into a standalone Soup.
+	 * @returns A new Soup whose root is a copy of this element
+	 */
+	extractSoup(): Soup;
+}
+
+/** A set of elements from one document, in document order. */
+export declare class Selection {
+	/**
+	 * Keep the elements that match a CSS selector.
+	 * @param selector - CSS selector string
+	 * @returns A new Selection
+	 * @throws Error if the selector syntax is invalid
+	 */
+	filter(selector: string): Selection;
+
+	/**
+	 * Remove the elements that match a CSS selector.
+	 * @param selector - CSS selector string
+	 * @returns A new Selection
+	 * @throws Error if the selector syntax is invalid
+	 */
+	not(selector: string): Selection;
+
+	/**
+	 * Select the descendants of any element that match a CSS selector.
+	 * @param selector - CSS selector string
+	 * @returns A new Selection, in document order without duplicates
+	 * @throws Error if the selector syntax is invalid
+	 */
+	find(selector: string): Selection;
+
+	/** Select the parent element of each element, in document order without duplicates. */
+	parent(): Selection;
+
+	/**
+	 * Select only the element at an index.
+	 * @param index - Position of the element; negative values count from the end
+	 * @returns A Selection of one element, or an empty one if index is out of range
+	 */
+	eq(index: number): Selection;
+
+	/**
+	 * Get the element at an index.
+	 * @param index - Position of the element; negative values count from the end
+	 * @returns The Tag, or undefined if index is out of range
+	 */
+	get(index: number): Tag | undefined;
+
+	/** Get the text content of each element. */
+	texts(): string[];
+
+	/**
+	 * Get an attribute value from each element.
+	 * @param name - Attribute name
+	 * @returns Array of attribute values (undefined if attribute is missing)
+	 */
+	attrs(name: string): (string | undefined)[];
+
+	/**
+	 * Get the elements in either selection.
+	 * @param other - A Selection of the same document
+	 * @returns A new Selection, in document order without duplicates
+	 * @throws Error if the selections belong to different documents
+	 */
+	union(other: Selection): Selection;
+
+	/**
+	 * Get the elements in both selections.
+	 * @param other - A Selection of the same document
+	 * @returns A new Selection
+	 * @throws Error if the selections belong to different documents
+	 */
+	intersection(other: Selection): Selection;
+
+	/** Get the elements as an array of Tags, in document order. */
+	toArray(): Tag[];
+
+	/** Get the number of elements. */
+	readonly length: number;
+}
+
+/** A pre-compiled CSS selector for efficient repeated matching. */
+export declare class CompiledSelector {
+	/**
+	 * Compile a CSS selector string.
+	 * @param selector - The CSS selector to compile
+	 * @returns A compiled selector
+	 * @throws Error if the selector syntax is invalid
+	 */
+	static compile(selector: string): CompiledSelector;
+
+	/** Get the original selector string. */
+	readonly source: string;
+}
+
+/** Metadata collected from a document's head and JSON-LD blocks. */
+export interface Metadata {
+	title: string | null;
+	description: string | null;
+	lang: string | null;
+	charset: string | null;
+	canonical: string | null;
+	meta: Record<string, string>;
+	openGraph: Record<string, string[]>;
+	twitter: Record<string, string>;
+	favicons: { href: string; rel: string; sizes: string | null; type: string | null }[];
+	jsonLd: any[];
+}
+
+/** The main content of a page, as found by a browser's reader mode. */
+export declare class Article {
+	/** The element holding the content, or undefined for an empty document. */
+	readonly content: Tag | undefined;
+
+	/** The headline, without a trailing site name. */
+	readonly title: string | undefined;
+
+	/** The author, from `<meta name="author">` or a byline element. */
+	readonly byline: string | undefined;
+
+	/**
+	 * The publication date as an ISO 8601 string, from meta tags, microdata, or a
+	 * `<time>` element.
+	 */
+	readonly publishedDate: string | undefined;
+
+	/** The content as plain text, with blank lines between paragraphs. */
+	readonly text: string;
+
+	/** The content as Markdown. */
+	readonly markdown: string;
 }
 
 /** A parsed HTML document. */
@@ -121,6 +373,25 @@ export declare class Soup {
 	 */
 	constructor(html: string, config?: SoupConfig);
 
+	/**
+	 * Parse the body of a fetch Response as it streams in.
+	 * Available when built with the `web-fetch` feature.
+	 * @param response - A fetch Response whose body has not been consumed
+	 * @param config - Optional configuration options
+	 * @returns A Promise resolving to the parsed Soup document
+	 * @throws Error if the body was already used or reading the stream fails
+	 */
+	static fromResponse(response: Response, config?: SoupConfig): Promise<Soup>;
+
+	/**
+	 * Parse an HTML fragment without html/body wrapper.
+	 * @param html - HTML fragment string to parse
+	 * @param context - Optional context element name (default: "body")
+	 * @param config - Optional parsing configuration
+	 * @returns A new Soup instance containing the fragment
+	 */
+	static parseFragment(html: string, context?: string, config?: SoupConfig): Soup;
+
 	/**
 	 * Find the first element matching a CSS selector.
 	 * @param selector - CSS selector string
@@ -145,12 +416,33 @@ export declare class Soup {
 	 */
 	select(selector: string): Tag[];
 
+	/**
+	 * Find all elements matching a CSS selector, as a chainable Selection.
+	 * @param selector - CSS selector string
+	 * @returns A Selection of the matching elements in document order
+	 * @throws Error if the selector syntax is invalid
+	 */
+	selection(selector: string): Selection;
+
 	/** Get the root element of the document. */
 	readonly root: Tag | undefined;
 
 	/** Get the document title. */
 	readonly title: string | undefined;
 
+	/**
+	 * Get the document metadata: meta tags, Open Graph properties, Twitter card fields,
+	 * the canonical URL, favicons, and parsed JSON-LD blocks.
+	 * @returns The document metadata
+	 */
+	metadata(): Metadata;
+
+	/**
+	 * Extract the main article content, like a browser's reader mode.
+	 * @returns The content element, title, byline, and body as text and Markdown
+	 */
+	extractArticle(): Article;
+
 	/** Get the text content of the entire document. */
 	readonly text: string;
 
@@ -162,6 +454,111 @@ export declare class Soup {
 
 	/** Get the number of nodes in the document. */
 	readonly length: number;
+
+	/**
+	 * Find the first element matching a compiled selector.
+	 * @param selector - A compiled CSS selector
+	 * @returns The first matching Tag, or undefined if not found
+	 */
+	findCompiled(selector: CompiledSelector): Tag | undefined;
+
+	/**
+	 * Find all elements matching a compiled selector.
+	 * @param selector - A compiled CSS selector
+	 * @returns Array of matching Tag instances
+	 */
+	selectCompiled(selector: CompiledSelector): Tag[];
+
+	/**
+	 * Extract text content from all elements matching a selector.
+	 * @param selector - CSS selector string
+	 * @returns Array of text content strings
+	 * @throws Error if the selector syntax is invalid
+	 */
+	selectText(selector: string): string[];
+
+	/**
+	 * Extract attribute values from all elements matching a selector.
+	 * @param selector - CSS selector string
+	 * @param attr - Attribute name to extract
+	 * @returns Array of attribute values (undefined if attribute is missing)
+	 * @throws Error if the selector syntax is invalid
+	 */
+	selectAttr(selector: string, attr: string): (string | undefined)[];
+}
+
+/**
+ * Get a compiled selector from the selector cache, compiling it on first use.
+ *
+ * This is the same cache string queries like `find` and `select` use. It holds at most
+ * 256 selectors by default, evicting the least recently used.
+ * @param selector - The CSS selector to look up or compile
+ * @returns A compiled selector
+ * @throws Error if the selector syntax is invalid
+ */
+export declare function getSelector(selector: string): CompiledSelector;
+
+/**
+ * Set how many selectors the selector cache holds, evicting the least recently used
+ * ones if it already holds more. A capacity of 0 disables caching.
+ * @param capacity - The maximum number of cached selectors
+ */
+export declare function setSelectorCacheCapacity(capacity: number): void;
+
+/** Remove all entries from the selector cache used by `getSelector`. */
+export declare function clearSelectorCache(): void;
+
+/** Get the number of selectors currently held in the `getSelector` cache. */
+export declare function selectorCacheSize(): number;
+
+/** Statistics about a document processed by `StreamingSoup`. */
+export declare class StreamingStats {
+	/** Total bytes processed. */
+	readonly bytesProcessed: number;
+
+	/** Number of elements matched by handlers. */
+	readonly elementsCount: number;
+}
+
+/**
+ * A streaming HTML parser that calls handlers as matching elements arrive.
+ * Available when built with the `streaming` feature.
+ */
+export declare class StreamingSoup {
+	constructor();
+
+	/**
+	 * Register a handler for elements matching a CSS selector.
+	 * @param selector - CSS selector string
+	 * @param callback - Called with `{ name, attrs }` for each matching element
+	 * @throws Error if the selector is invalid or writing has already started
+	 */
+	onElement(
+		selector: string,
+		callback: (element: { name: string; attrs: Record<string, string> }) => void,
+	): void;
+
+	/**
+	 * Parse a chunk of the document and run the handlers for the elements it completes.
+	 * @param chunk - The next bytes of the document
+	 * @throws Error if parsing fails, the parser has ended, or a handler throws
+	 */
+	write(chunk: Uint8Array): void;
+
+	/**
+	 * Finish parsing.
+	 * @returns Statistics about the processed document
+	 * @throws Error if finishing the parse fails or the parser has already ended
+	 */
+	end(): StreamingStats;
+
+	/**
+	 * Parse a `ReadableStream` of bytes, such as a fetch response body, to the end.
+	 * @param stream - A stream of `Uint8Array` chunks
+	 * @returns A Promise resolving to statistics about the processed document
+	 * @throws Error if reading the stream or parsing fails, or a handler throws
+	 */
+	processStream(stream: ReadableStream<Uint8Array>): Promise<StreamingStats>;
 }
 
 /**
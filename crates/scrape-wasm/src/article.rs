@@ -0,0 +1,68 @@
+//! Main article content returned by `Soup.extractArticle()`.
+
+use std::rc::Rc;
+
+use scrape_core::Soup as CoreSoup;
+use wasm_bindgen::prelude::*;
+
+use crate::tag::Tag;
+
+/// The main content of a page, as found by a browser's reader mode.
+///
+/// @example
+/// ```javascript
+/// const article = new Soup(html).extractArticle();
+/// console.log(article.title, article.byline);
+/// console.log(article.markdown);
+/// ```
+#[wasm_bindgen]
+pub struct Article {
+    soup: Rc<CoreSoup>,
+    inner: scrape_core::article::Article,
+}
+
+impl Article {
+    pub(crate) fn new(soup: Rc<CoreSoup>, inner: scrape_core::article::Article) -> Self {
+        Self { soup, inner }
+    }
+}
+
+#[wasm_bindgen]
+impl Article {
+    /// The element holding the content, or undefined for an empty document.
+    #[wasm_bindgen(getter)]
+    pub fn content(&self) -> Option<Tag> {
+        self.inner.node.map(|node| Tag::new(Rc::clone(&self.soup), node))
+    }
+
+    /// The headline, without a trailing site name.
+    #[wasm_bindgen(getter)]
+    pub fn title(&self) -> Option<String> {
+        self.inner.title.clone()
+    }
+
+    /// The author, from `<meta name="author">` or a byline element.
+    #[wasm_bindgen(getter)]
+    pub fn byline(&self) -> Option<String> {
+        self.inner.byline.clone()
+    }
+
+    /// The publication date as an ISO 8601 string, from meta tags, microdata, or a
+    /// `<time>` element.
+    #[wasm_bindgen(getter, js_name = "publishedDate")]
+    pub fn published_date(&self) -> Option<String> {
+        self.inner.published_date.map(|date| date.to_string())
+    }
+
+    /// The content as plain text, with blank lines between paragraphs.
+    #[wasm_bindgen(getter)]
+    pub fn text(&self) -> String {
+        self.inner.text.clone()
+    }
+
+    /// The content as Markdown.
+    #[wasm_bindgen(getter)]
+    pub fn markdown(&self) -> String {
+        self.inner.markdown.clone()
+    }
+}
@@ -7,7 +7,7 @@
 //! # Example
 //!
 //! ```javascript
-//! import init, { Soup, parseBatch, hasSimdSupport, version } from '@scrape-rs/wasm';
+//! import init, { Soup, getSelector, parseBatch, hasSimdSupport, version } from '@scrape-rs/wasm';
 //!
 //! await init();
 //!
@@ -19,6 +19,9 @@
 //! // Batch processing (sequential in WASM)
 //! const soups = parseBatch(["<div>A</div>", "<div>B</div>"]);
 //!
+//! // Cached compiled selectors for hot paths
+//! const items = soup.selectCompiled(getSelector("div.item"));
+//!
 //! // Check SIMD support
 //! console.log("SIMD:", hasSimdSupport());
 //! console.log("Version:", version());
@@ -26,14 +29,25 @@
 
 use wasm_bindgen::prelude::*;
 
+mod article;
 mod config;
+mod selection;
 mod selector;
 mod soup;
+#[cfg(feature = "streaming")]
+mod streaming;
 mod tag;
 
+pub use article::Article;
 pub use config::SoupConfig;
-pub use selector::CompiledSelector;
+pub use selection::Selection;
+pub use selector::{
+    CompiledSelector, clear_selector_cache, get_selector, selector_cache_size,
+    set_selector_cache_capacity,
+};
 pub use soup::Soup;
+#[cfg(feature = "streaming")]
+pub use streaming::{StreamingSoup, StreamingStats};
 pub use tag::Tag;
 
 /// Initialize the WASM module.
@@ -1,6 +1,8 @@
 //! WASM wrapper for CompiledSelector.
 
-use scrape_core::query::CompiledSelector as CoreCompiledSelector;
+use std::sync::Arc;
+
+use scrape_core::query::{self, CompiledSelector as CoreCompiledSelector};
 use wasm_bindgen::prelude::*;
 
 /// A pre-compiled CSS selector for efficient repeated matching.
@@ -20,7 +22,7 @@ use wasm_bindgen::prelude::*;
 /// ```
 #[wasm_bindgen]
 pub struct CompiledSelector {
-    pub(crate) inner: CoreCompiledSelector,
+    pub(crate) inner: Arc<CoreCompiledSelector>,
 }
 
 #[wasm_bindgen]
@@ -32,7 +34,7 @@ impl CompiledSelector {
     /// @throws Error if the selector syntax is invalid
     pub fn compile(selector: &str) -> Result<CompiledSelector, JsError> {
         CoreCompiledSelector::compile(selector)
-            .map(|inner| Self { inner })
+            .map(|inner| Self { inner: Arc::new(inner) })
             .map_err(|e| JsError::new(&e.to_string()))
     }
 
@@ -44,3 +46,51 @@ impl CompiledSelector {
         self.inner.source().to_string()
     }
 }
+
+/// Get a compiled selector from the selector cache, compiling it on first use.
+///
+/// Repeated calls with the same selector string share one compiled selector, so hot
+/// paths can call this directly instead of holding on to `CompiledSelector` instances.
+/// This is the same cache string queries like `find` and `select` use. It holds at most
+/// 256 selectors by default, evicting the least recently used; see
+/// `setSelectorCacheCapacity`.
+///
+/// @param selector - The CSS selector to look up or compile
+/// @returns A compiled selector
+/// @throws Error if the selector syntax is invalid
+///
+/// @example
+/// ```javascript
+/// for (const html of pages) {
+///     const soup = new Soup(html);
+///     const items = soup.selectCompiled(getSelector("div.item"));
+/// }
+/// ```
+#[wasm_bindgen(js_name = "getSelector")]
+pub fn get_selector(selector: &str) -> Result<CompiledSelector, JsError> {
+    query::cached_compile(selector)
+        .map(|inner| CompiledSelector { inner })
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Set how many selectors the selector cache holds, evicting the least recently used
+/// ones if it already holds more. A capacity of 0 disables caching.
+///
+/// @param capacity - The maximum number of cached selectors
+#[wasm_bindgen(js_name = "setSelectorCacheCapacity")]
+pub fn set_selector_cache_capacity(capacity: u32) {
+    query::set_selector_cache_capacity(capacity as usize);
+}
+
+/// Remove all entries from the selector cache used by `getSelector`.
+#[wasm_bindgen(js_name = "clearSelectorCache")]
+pub fn clear_selector_cache() {
+    query::clear_selector_cache();
+}
+
+/// Get the number of selectors currently held in the `getSelector` cache.
+#[wasm_bindgen(js_name = "selectorCacheSize")]
+#[allow(clippy::cast_possible_truncation)]
+pub fn selector_cache_size() -> u32 {
+    query::selector_cache_len() as u32
+}
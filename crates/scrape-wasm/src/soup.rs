@@ -5,7 +5,27 @@ use std::rc::Rc;
 use scrape_core::Soup as CoreSoup;
 use wasm_bindgen::prelude::*;
 
-use crate::{config::SoupConfig, selector::CompiledSelector, tag::Tag};
+use crate::{
+    article::Article, config::SoupConfig, selection::Selection, selector::CompiledSelector,
+    tag::Tag,
+};
+
+#[wasm_bindgen(typescript_custom_section)]
+const METADATA_TS: &str = r#"
+/** Metadata collected from a document's head and JSON-LD blocks. */
+export interface Metadata {
+  title: string | null;
+  description: string | null;
+  lang: string | null;
+  charset: string | null;
+  canonical: string | null;
+  meta: Record<string, string>;
+  openGraph: Record<string, string[]>;
+  twitter: Record<string, string>;
+  favicons: { href: string; rel: string; sizes: string | null; type: string | null }[];
+  jsonLd: any[];
+}
+"#;
 
 /// A parsed HTML document.
 ///
@@ -72,6 +92,16 @@ impl Soup {
         self.find_all(selector)
     }
 
+    /// Finds all elements matching a CSS selector, as a chainable Selection.
+    ///
+    /// @param selector - CSS selector string
+    /// @returns A Selection of the matching elements in document order
+    /// @throws Error if the selector syntax is invalid
+    pub fn selection(&self, selector: &str) -> Result<Selection, JsError> {
+        let selection = self.inner.selection(selector).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(Selection::new(Rc::clone(&self.inner), selection.node_ids().to_vec()))
+    }
+
     /// Get the root element of the document.
     ///
     /// @returns The root Tag (usually <html>), or undefined for empty documents
@@ -88,6 +118,39 @@ impl Soup {
         self.inner.title()
     }
 
+    /// Get the document metadata: meta tags, Open Graph properties, Twitter card fields,
+    /// the canonical URL, favicons, and parsed JSON-LD blocks.
+    ///
+    /// Missing single values are `null`.
+    ///
+    /// @returns The document metadata
+    ///
+    /// @example
+    /// ```javascript
+    /// const meta = new Soup(html).metadata();
+    /// console.log(meta.title, meta.openGraph["og:image"], meta.jsonLd[0]);
+    /// ```
+    #[wasm_bindgen(unchecked_return_type = "Metadata")]
+    pub fn metadata(&self) -> Result<JsValue, JsError> {
+        let mut value = serde_json::Value::from(self.inner.metadata());
+        if let Some(fields) = value.as_object_mut() {
+            for (key, js_key) in [("open_graph", "openGraph"), ("json_ld", "jsonLd")] {
+                if let Some(field) = fields.remove(key) {
+                    fields.insert(js_key.to_string(), field);
+                }
+            }
+        }
+        js_sys::JSON::parse(&value.to_string()).map_err(|e| JsError::new(&format!("{e:?}")))
+    }
+
+    /// Extract the main article content, like a browser's reader mode.
+    ///
+    /// @returns The content element, title, byline, and body as text and Markdown
+    #[wasm_bindgen(js_name = "extractArticle")]
+    pub fn extract_article(&self) -> Article {
+        Article::new(Rc::clone(&self.inner), self.inner.extract_article())
+    }
+
     /// Get the text content of the entire document.
     ///
     /// @returns All text content with HTML tags stripped
@@ -165,6 +228,66 @@ impl Soup {
         Self { inner: Rc::new(soup) }
     }
 
+    // ==================== Fetch Integration ====================
+
+    /// Parse the body of a fetch `Response` as it streams in.
+    ///
+    /// Body chunks are fed to the parser as they arrive, so large pages are never
+    /// buffered as a single string via `await response.text()`.
+    ///
+    /// Requires the `web-fetch` feature.
+    ///
+    /// @param response - A fetch Response whose body has not been consumed
+    /// @param config - Optional parsing configuration
+    /// @returns A Promise resolving to a new Soup instance
+    /// @throws Error if the body was already used or reading the stream fails
+    ///
+    /// @example
+    /// ```javascript
+    /// const response = await fetch("https://example.com");
+    /// const soup = await Soup.fromResponse(response);
+    /// console.log(soup.title);
+    /// ```
+    #[cfg(feature = "web-fetch")]
+    #[wasm_bindgen(js_name = "fromResponse")]
+    #[allow(clippy::future_not_send)] // JS futures are single-threaded
+    pub async fn from_response(
+        response: web_sys::Response,
+        config: Option<SoupConfig>,
+    ) -> Result<Soup, JsError> {
+        use scrape_core::{IncrementalParser, ParseConfig};
+        use wasm_bindgen_futures::JsFuture;
+        use web_sys::{ReadableStreamDefaultReader, ReadableStreamReadResult};
+
+        if response.body_used() {
+            return Err(JsError::new("Response body has already been used"));
+        }
+
+        let core_config = config.map(|c| c.to_core()).unwrap_or_default();
+        let mut parser = IncrementalParser::new(&ParseConfig {
+            max_depth: core_config.max_depth,
+            preserve_whitespace: core_config.preserve_whitespace,
+            include_comments: core_config.include_comments,
+            pierce_shadow: core_config.pierce_shadow,
+            preserve_duplicate_attributes: core_config.preserve_duplicate_attributes,
+        });
+
+        if let Some(body) = response.body() {
+            let reader: ReadableStreamDefaultReader = body.get_reader().unchecked_into();
+            loop {
+                let result: ReadableStreamReadResult =
+                    JsFuture::from(reader.read()).await.map_err(js_error)?.unchecked_into();
+                if result.get_done().unwrap_or(true) {
+                    break;
+                }
+                parser.feed(&js_sys::Uint8Array::new(&result.get_value()).to_vec());
+            }
+        }
+
+        let document = parser.finish().unwrap_or_default();
+        Ok(Self { inner: Rc::new(CoreSoup::from_document(document, core_config)) })
+    }
+
     // ==================== Extraction Methods ====================
 
     /// Extract text content from all elements matching a selector.
@@ -210,7 +333,21 @@ impl Soup {
     }
 }
 
+/// Converts a rejected JS promise value into a `JsError`.
+#[cfg(feature = "web-fetch")]
+fn js_error(value: JsValue) -> JsError {
+    value.dyn_into::<js_sys::Error>().map_or_else(
+        |v| JsError::new(&format!("{v:?}")),
+        |e| JsError::new(&String::from(e.message())),
+    )
+}
+
 impl Soup {
+    /// Wraps a core soup, for Tag methods that produce a new document.
+    pub(crate) fn from_core(soup: CoreSoup) -> Self {
+        Self { inner: Rc::new(soup) }
+    }
+
     /// Returns a clone of the inner Rc for use by Tag.
     #[must_use]
     pub fn inner_rc(&self) -> Rc<CoreSoup> {
@@ -0,0 +1,195 @@
+//! Streaming parser wrapper for WASM.
+
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+use js_sys::{Function, Object};
+use scrape_core::{StreamingSoup as CoreStreamingSoup, state};
+use wasm_bindgen::prelude::*;
+
+/// An element matched by an `onElement` handler, queued until the write returns.
+struct MatchedElement {
+    handler: usize,
+    name: String,
+    attrs: Vec<(String, String)>,
+}
+
+enum Stage {
+    Idle(CoreStreamingSoup<state::Idle>),
+    Processing(CoreStreamingSoup<state::Processing>),
+    Ended,
+}
+
+/// A streaming HTML parser that calls handlers as matching elements arrive.
+///
+/// Register handlers with `onElement`, then feed the document with `write` and finish
+/// with `end`, or hand a `ReadableStream` to `processStream`. The document is never
+/// held in memory as a whole, so large fetched pages can be scanned with constant
+/// memory.
+///
+/// Handlers receive a plain `{ name, attrs }` object and run after the `write` call
+/// that matched the element has parsed its chunk.
+///
+/// Requires the `streaming` feature.
+///
+/// @example
+/// ```javascript
+/// const streaming = new StreamingSoup();
+/// streaming.onElement("a[href]", (el) => console.log(el.attrs.href));
+/// streaming.write(new TextEncoder().encode("<a href='/tea'>Tea</a>"));
+/// const stats = streaming.end();
+/// console.log(stats.elementsCount); // 1
+/// ```
+#[wasm_bindgen]
+pub struct StreamingSoup {
+    stage: Stage,
+    callbacks: Vec<Function>,
+    sender: Sender<MatchedElement>,
+    matches: Receiver<MatchedElement>,
+}
+
+#[wasm_bindgen]
+impl StreamingSoup {
+    /// Creates a streaming parser with no handlers.
+    #[wasm_bindgen(constructor)]
+    #[must_use]
+    pub fn new() -> Self {
+        let (sender, matches) = channel();
+        Self {
+            stage: Stage::Idle(CoreStreamingSoup::new()),
+            callbacks: Vec::new(),
+            sender,
+            matches,
+        }
+    }
+
+    /// Register a handler for elements matching a CSS selector.
+    ///
+    /// @param selector - CSS selector string
+    /// @param callback - Called with `{ name, attrs }` for each matching element
+    /// @throws Error if the selector is invalid or writing has already started
+    #[wasm_bindgen(js_name = "onElement")]
+    pub fn on_element(&mut self, selector: &str, callback: Function) -> Result<(), JsError> {
+        let Stage::Idle(idle) = &mut self.stage else {
+            return Err(JsError::new("Handlers must be registered before the first write"));
+        };
+
+        let handler = self.callbacks.len();
+        let sender = self.sender.clone();
+        idle.on_element(selector, move |el| {
+            let attrs = el.attributes().collect();
+            // The receiver lives as long as the wrapper that owns this handler.
+            let _ = sender.send(MatchedElement { handler, name: el.tag_name(), attrs });
+            Ok(())
+        })
+        .map_err(|e| JsError::new(&e.to_string()))?;
+        self.callbacks.push(callback);
+        Ok(())
+    }
+
+    /// Parse a chunk of the document and run the handlers for the elements it completes.
+    ///
+    /// @param chunk - The next bytes of the document
+    /// @throws Error if parsing fails, the parser has ended, or a handler throws
+    pub fn write(&mut self, chunk: &[u8]) -> Result<(), JsValue> {
+        if let Stage::Idle(_) = self.stage
+            && let Stage::Idle(idle) = std::mem::replace(&mut self.stage, Stage::Ended)
+        {
+            self.stage = Stage::Processing(idle.start());
+        }
+        let Stage::Processing(processor) = &mut self.stage else {
+            return Err(JsError::new("StreamingSoup has already ended").into());
+        };
+
+        let written = processor.write(chunk).map_err(|e| JsError::new(&e.to_string()));
+        self.dispatch()?;
+        written.map_err(Into::into)
+    }
+
+    /// Finish parsing.
+    ///
+    /// @returns Statistics about the processed document
+    /// @throws Error if finishing the parse fails or the parser has already ended
+    pub fn end(&mut self) -> Result<StreamingStats, JsError> {
+        let processor = match std::mem::replace(&mut self.stage, Stage::Ended) {
+            Stage::Idle(idle) => idle.start(),
+            Stage::Processing(processor) => processor,
+            Stage::Ended => return Err(JsError::new("StreamingSoup has already ended")),
+        };
+        let finished = processor.end().map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(StreamingStats::from(finished.stats()))
+    }
+
+    /// Parse a `ReadableStream` of bytes, such as a fetch response body, to the end.
+    ///
+    /// @param stream - A stream of `Uint8Array` chunks
+    /// @returns A Promise resolving to statistics about the processed document
+    /// @throws Error if reading the stream or parsing fails, or a handler throws
+    ///
+    /// @example
+    /// ```javascript
+    /// const streaming = new StreamingSoup();
+    /// streaming.onElement("img[src]", (el) => images.push(el.attrs.src));
+    /// const stats = await streaming.processStream((await fetch(url)).body);
+    /// ```
+    #[wasm_bindgen(js_name = "processStream")]
+    #[allow(clippy::future_not_send)] // JS futures are single-threaded
+    pub async fn process_stream(
+        mut self,
+        stream: web_sys::ReadableStream,
+    ) -> Result<StreamingStats, JsValue> {
+        use wasm_bindgen_futures::JsFuture;
+        use web_sys::{ReadableStreamDefaultReader, ReadableStreamReadResult};
+
+        let reader: ReadableStreamDefaultReader = stream.get_reader().unchecked_into();
+        loop {
+            let result: ReadableStreamReadResult =
+                JsFuture::from(reader.read()).await?.unchecked_into();
+            if result.get_done().unwrap_or(true) {
+                break;
+            }
+            self.write(&js_sys::Uint8Array::new(&result.get_value()).to_vec())?;
+        }
+        self.end().map_err(Into::into)
+    }
+}
+
+impl StreamingSoup {
+    /// Calls the JS handlers for the elements matched since the last dispatch.
+    fn dispatch(&self) -> Result<(), JsValue> {
+        for matched in self.matches.try_iter() {
+            let attrs = Object::new();
+            for (name, value) in matched.attrs {
+                js_sys::Reflect::set(&attrs, &name.into(), &value.into())?;
+            }
+            let element = Object::new();
+            js_sys::Reflect::set(&element, &"name".into(), &matched.name.into())?;
+            js_sys::Reflect::set(&element, &"attrs".into(), &attrs)?;
+            self.callbacks[matched.handler].call1(&JsValue::NULL, &element)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for StreamingSoup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Statistics about a document processed by `StreamingSoup`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct StreamingStats {
+    /// Total bytes processed.
+    #[wasm_bindgen(readonly, js_name = "bytesProcessed")]
+    pub bytes_processed: usize,
+    /// Number of elements matched by handlers.
+    #[wasm_bindgen(readonly, js_name = "elementsCount")]
+    pub elements_count: usize,
+}
+
+impl From<&scrape_core::StreamingStats> for StreamingStats {
+    fn from(stats: &scrape_core::StreamingStats) -> Self {
+        Self { bytes_processed: stats.bytes_processed, elements_count: stats.elements_count }
+    }
+}
@@ -3,10 +3,10 @@
 use std::rc::Rc;
 
 use js_sys::Object;
-use scrape_core::{Document, NodeId, NodeKind, Soup as CoreSoup};
+use scrape_core::{Document, NodeId, NodeKind, Soup as CoreSoup, SoupConfig};
 use wasm_bindgen::prelude::*;
 
-use crate::selector::CompiledSelector;
+use crate::{Soup, selector::CompiledSelector};
 
 /// An HTML element in the DOM tree.
 ///
@@ -84,7 +84,7 @@ impl Tag {
             .get(self.id)
             .and_then(|n| n.kind.attributes())
             .and_then(|attrs| attrs.get(name))
-            .cloned()
+            .map(str::to_string)
     }
 
     /// Get an attribute value by name (alias for get).
@@ -115,7 +115,7 @@ impl Tag {
             && let Some(attrs) = node.kind.attributes()
         {
             for (k, v) in attrs {
-                let _ = js_sys::Reflect::set(&obj, &k.into(), &v.into());
+                let _ = js_sys::Reflect::set(&obj, &k.as_str().into(), &v.into());
             }
         }
         obj
@@ -138,6 +138,48 @@ impl Tag {
             .unwrap_or_default()
     }
 
+    /// Get an attribute value parsed as an integer.
+    ///
+    /// @param name - The attribute name
+    /// @returns The integer value, or undefined if missing or not a valid integer
+    #[wasm_bindgen(js_name = "attrInt")]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn attr_int(&self, name: &str) -> Option<f64> {
+        self.get(name).as_deref().and_then(scrape_core::utils::parse_attr_int).map(|n| n as f64)
+    }
+
+    /// Get an attribute value parsed as a boolean.
+    ///
+    /// A present attribute is true unless its value is "false", "0", "no", or "off".
+    ///
+    /// @param name - The attribute name
+    /// @returns The boolean value, or undefined if the attribute is missing
+    #[wasm_bindgen(js_name = "attrBool")]
+    pub fn attr_bool(&self, name: &str) -> Option<bool> {
+        self.get(name).as_deref().map(scrape_core::utils::parse_attr_bool)
+    }
+
+    /// Get the whitespace-separated tokens of an attribute value.
+    ///
+    /// @param name - The attribute name (e.g., "rel")
+    /// @returns Array of tokens, empty if the attribute is missing
+    #[wasm_bindgen(js_name = "attrList")]
+    pub fn attr_list(&self, name: &str) -> Vec<String> {
+        self.get(name)
+            .map(|s| scrape_core::utils::split_attr_list(&s).map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Get an attribute value resolved as a URL against a base URL.
+    ///
+    /// @param name - The attribute name (e.g., "href")
+    /// @param base - Absolute base URL to resolve against
+    /// @returns The absolute URL, or undefined if the attribute is missing or base is not absolute
+    #[wasm_bindgen(js_name = "attrUrl")]
+    pub fn attr_url(&self, name: &str, base: &str) -> Option<String> {
+        self.get(name).and_then(|value| scrape_core::utils::resolve_url(base, &value))
+    }
+
     // ==================== Navigation Properties ====================
 
     /// Get the parent element.
@@ -249,6 +291,20 @@ impl Tag {
         Ok(None)
     }
 
+    /// Check whether this element itself matches a CSS selector.
+    ///
+    /// Descendants are not searched, so this can filter the results of
+    /// `children`, `descendants`, and the other navigation getters.
+    ///
+    /// @param selector - CSS selector string
+    /// @returns True if the element matches
+    /// @throws Error if the selector syntax is invalid
+    pub fn matches(&self, selector: &str) -> Result<bool, JsError> {
+        let selector = scrape_core::query::cached_compile(selector)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(scrape_core::query::matches_selector(self.doc(), self.id, selector.selector_list()))
+    }
+
     /// Get all following sibling elements.
     #[wasm_bindgen(getter, js_name = "nextSiblings")]
     pub fn next_siblings(&self) -> Vec<Tag> {
@@ -346,6 +402,15 @@ impl Tag {
             .collect()
     }
 
+    /// Check whether this element itself matches a compiled selector.
+    ///
+    /// @param selector - A compiled CSS selector
+    /// @returns True if the element matches
+    #[wasm_bindgen(js_name = "matchesCompiled")]
+    pub fn matches_compiled(&self, selector: &CompiledSelector) -> bool {
+        scrape_core::query::matches_selector(self.doc(), self.id, selector.inner.selector_list())
+    }
+
     // ==================== Text and Iterator Methods ====================
 
     /// Get all direct text nodes (excluding descendants).
@@ -366,7 +431,7 @@ impl Tag {
             .filter_map(|child_id| {
                 let node = self.doc().get(child_id)?;
                 match &node.kind {
-                    NodeKind::Text { content } => Some(content.clone()),
+                    NodeKind::Text { content } => Some(content.to_string()),
                     _ => None,
                 }
             })
@@ -476,6 +541,24 @@ impl Tag {
                     .collect()
             })
     }
+
+    /// Copy this element and its descendants into a standalone Soup.
+    ///
+    /// Only the subtree is copied, so the original document can be freed
+    /// while the extracted region is kept.
+    ///
+    /// @returns A new Soup whose root is a copy of this element
+    ///
+    /// @example
+    /// ```javascript
+    /// const article = new Soup(page).find("article").extractSoup();
+    /// const title = article.find("h1").text;
+    /// ```
+    #[wasm_bindgen(js_name = "extractSoup")]
+    pub fn extract_soup(&self) -> Soup {
+        let document = self.doc().subtree(self.id).unwrap_or_default();
+        Soup::from_core(CoreSoup::from_document(document, SoupConfig::default()))
+    }
 }
 
 impl Clone for Tag {
@@ -463,3 +463,17 @@ fn test_scoped_queries_suite() {
         run_test_case(case);
     }
 }
+
+#[wasm_bindgen_test]
+fn test_attribute_selectors_suite() {
+    let suite = load_test_suite();
+    let attributes = suite
+        .test_suites
+        .iter()
+        .find(|s| s.name == "attribute_selectors")
+        .expect("attribute_selectors suite not found");
+
+    for case in &attributes.cases {
+        run_test_case(case);
+    }
+}
@@ -173,6 +173,50 @@ fn test_tag_outer_html() {
     assert!(outer.contains("</div>"));
 }
 
+#[wasm_bindgen_test]
+fn test_tag_extract_soup() {
+    let soup = Soup::new("<div><article><p>A</p></article><p>B</p></div>", None);
+    let article = soup.find("article").unwrap().unwrap().extract_soup();
+    assert_eq!(article.root().unwrap().name(), Some("article".to_string()));
+    assert_eq!(article.find_all("p").unwrap().len(), 1);
+}
+
+#[wasm_bindgen_test]
+fn test_soup_metadata() {
+    let soup = Soup::new(
+        "<title>Tea</title><meta property='og:image' content='a.png'><script \
+         type='application/ld+json'>{\"@type\": \"Product\"}</script>",
+        None,
+    );
+    let meta = soup.metadata().unwrap();
+    let json = String::from(js_sys::JSON::stringify(&meta).unwrap());
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["title"], "Tea");
+    assert_eq!(value["canonical"], serde_json::Value::Null);
+    assert_eq!(value["openGraph"]["og:image"][0], "a.png");
+    assert_eq!(value["jsonLd"][0]["@type"], "Product");
+}
+
+#[wasm_bindgen_test]
+fn test_soup_extract_article() {
+    let soup = Soup::new(
+        "<html><head><title>Tea | Shop</title></head><body><nav><a \
+         href='/'>Home</a></nav><article><h1>Tea</h1><time datetime='2024-05-02'>May \
+         2</time><p>Brew green tea at eighty degrees for two minutes, then pour it into a warmed \
+         cup.</p></article></body></html>",
+        None,
+    );
+    let article = soup.extract_article();
+    assert_eq!(article.title(), Some("Tea".to_string()));
+    assert_eq!(article.published_date(), Some("2024-05-02".to_string()));
+    assert!(article.content().is_some());
+    assert!(article.text().contains("eighty degrees"));
+    assert!(article.markdown().contains("eighty degrees"));
+
+    let empty = Soup::new("", None).extract_article();
+    assert!(empty.content().is_none());
+}
+
 // ==================== Tag Attribute Tests ====================
 
 #[wasm_bindgen_test]
@@ -182,6 +226,16 @@ fn test_tag_get_attribute() {
     assert_eq!(a.get("href"), Some("https://example.com".to_string()));
 }
 
+#[wasm_bindgen_test]
+fn test_tag_typed_attributes() {
+    let soup = Soup::new("<a href='../b' rel='nofollow ugc' data-count='7' hidden>x</a>", None);
+    let a = soup.find("a").unwrap().unwrap();
+    assert_eq!(a.attr_int("data-count"), Some(7.0));
+    assert_eq!(a.attr_bool("hidden"), Some(true));
+    assert_eq!(a.attr_list("rel"), vec!["nofollow".to_string(), "ugc".to_string()]);
+    assert_eq!(a.attr_url("href", "https://x.org/a/c/d"), Some("https://x.org/a/b".to_string()));
+}
+
 #[wasm_bindgen_test]
 fn test_tag_attr_alias() {
     let soup = Soup::new("<a href='https://example.com'>Link</a>", None);
@@ -481,6 +535,37 @@ fn test_tag_length() {
     assert_eq!(div.length(), 3);
 }
 
+// ==================== Selection Tests ====================
+
+#[wasm_bindgen_test]
+fn test_selection_filter_and_find() {
+    let soup = Soup::new(
+        "<div id='a'><p class='x'>1</p><p>2</p></div><div id='b'><p class='x'>3</p></div>",
+        None,
+    );
+    let ps = soup.selection("p").unwrap();
+    assert_eq!(ps.length(), 3);
+    assert_eq!(ps.filter(".x").unwrap().texts(), vec!["1", "3"]);
+    assert_eq!(ps.not(".x").unwrap().texts(), vec!["2"]);
+    assert_eq!(ps.eq(-1).texts(), vec!["3"]);
+    assert!(ps.get(3).is_none());
+    assert_eq!(ps.parent().length(), 2);
+    assert_eq!(soup.selection("div").unwrap().find("p").unwrap().length(), 3);
+    assert!(ps.filter("[").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_selection_union_and_intersection() {
+    let soup = Soup::new("<p class='x'>1</p><p>2</p><p class='x'>3</p>", None);
+    let first = soup.selection("p:first-child").unwrap();
+    let last = soup.selection("p:last-child").unwrap();
+    assert_eq!(last.union(&first).unwrap().texts(), vec!["1", "3"]);
+    let marked = soup.selection(".x").unwrap();
+    assert_eq!(marked.intersection(&last).unwrap().length(), 1);
+    let other = Soup::new("<p>1</p>", None).selection("p").unwrap();
+    assert!(first.union(&other).is_err());
+}
+
 // ==================== Batch Processing Tests ====================
 
 #[wasm_bindgen_test]
@@ -1109,3 +1194,85 @@ fn test_select_attr_data_attributes() {
     let ids = soup.select_attr("button", "data-id").unwrap();
     assert_eq!(ids.len(), 3);
 }
+
+// ==================== Selector Cache ====================
+
+#[wasm_bindgen_test]
+fn test_get_selector_caches_by_string() {
+    scrape_wasm::clear_selector_cache();
+    let first = scrape_wasm::get_selector("li.item").unwrap();
+    let second = scrape_wasm::get_selector("li.item").unwrap();
+
+    assert_eq!(first.source(), second.source());
+    assert_eq!(scrape_wasm::selector_cache_size(), 1);
+
+    let soup = Soup::new("<ul><li class='item'>A</li><li class='item'>B</li></ul>", None);
+    assert_eq!(soup.select_compiled(&second).len(), 2);
+}
+
+#[wasm_bindgen_test]
+fn test_selector_cache_is_bounded() {
+    scrape_wasm::clear_selector_cache();
+    scrape_wasm::set_selector_cache_capacity(2);
+    for selector in ["a", "b", "c", "d"] {
+        scrape_wasm::get_selector(selector).unwrap();
+    }
+    assert_eq!(scrape_wasm::selector_cache_size(), 2);
+
+    scrape_wasm::set_selector_cache_capacity(256);
+    scrape_wasm::clear_selector_cache();
+}
+
+#[wasm_bindgen_test]
+fn test_get_selector_invalid_not_cached() {
+    scrape_wasm::clear_selector_cache();
+    assert!(scrape_wasm::get_selector("[[[").is_err());
+    assert_eq!(scrape_wasm::selector_cache_size(), 0);
+}
+
+// ==================== Fetch Integration ====================
+
+#[cfg(feature = "web-fetch")]
+#[wasm_bindgen_test]
+async fn test_from_response() {
+    let response = web_sys::Response::new_with_opt_str(Some(
+        "<html><head><title>Streamed</title></head><body><p>Hi</p></body></html>",
+    ))
+    .unwrap();
+    let soup = Soup::from_response(response, None).await.unwrap();
+
+    assert_eq!(soup.title(), Some("Streamed".to_string()));
+    assert_eq!(soup.find("p").unwrap().unwrap().text(), "Hi");
+}
+
+#[cfg(feature = "web-fetch")]
+#[wasm_bindgen_test]
+async fn test_from_response_empty_body() {
+    let response = web_sys::Response::new().unwrap();
+    let soup = Soup::from_response(response, None).await.unwrap();
+
+    assert!(soup.root().is_none());
+}
+
+// ==================== Streaming Tests ====================
+
+#[cfg(feature = "streaming")]
+#[wasm_bindgen_test]
+fn test_streaming_soup() {
+    use scrape_wasm::StreamingSoup;
+
+    let links = js_sys::Array::new();
+    let on_link = js_sys::Function::new_with_args("el", "this.push(el.attrs.href)").bind(&links);
+
+    let mut streaming = StreamingSoup::new();
+    streaming.on_element("a[href]", on_link).unwrap();
+    streaming.write(b"<p><a href='/green'>Green</a></p>").unwrap();
+    streaming.write(b"<p><a href='/black'>Black</a><a>None</a></p>").unwrap();
+    assert!(streaming.on_element("p", js_sys::Function::new_no_args("")).is_err());
+
+    let stats = streaming.end().unwrap();
+    assert_eq!(links.length(), 2);
+    assert_eq!(links.get(1).as_string().as_deref(), Some("/black"));
+    assert_eq!(stats.elements_count, 2);
+    assert!(streaming.end().is_err());
+}
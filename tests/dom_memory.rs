@@ -0,0 +1,174 @@
+//! Heap memory held by a parsed DOM.
+//!
+//! Parses the large benchmark fixture (see `benches/fixtures/generate.py`) under a counting
+//! allocator and checks the bytes the `Soup` keeps alive against the layout with owned
+//! `String` tag and attribute names. It runs without the test harness, so no other test
+//! allocates while it measures.
+
+// Counting allocations takes an `unsafe impl GlobalAlloc`.
+#![allow(unsafe_code)]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::Write,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use scrape_core::Soup;
+
+struct Counting;
+
+/// Bytes requested by live allocations.
+static REQUESTED: AtomicUsize = AtomicUsize::new(0);
+/// Bytes of the malloc chunks holding live allocations; see [`chunk_size`].
+static CHUNKS: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the size of the glibc malloc chunk serving a request of `size` bytes: an
+/// 8-byte header, rounded up to 16 bytes, and at least 32 bytes. Small allocations cost
+/// this much rather than the bytes requested, whichever allocator is actually in use.
+const fn chunk_size(size: usize) -> usize {
+    let size = if size + 8 < 32 { 32 } else { size + 8 };
+    size.next_multiple_of(16)
+}
+
+fn record(added: usize, removed: usize) {
+    REQUESTED.fetch_add(added, Ordering::Relaxed);
+    REQUESTED.fetch_sub(removed, Ordering::Relaxed);
+    CHUNKS.fetch_add(if added == 0 { 0 } else { chunk_size(added) }, Ordering::Relaxed);
+    CHUNKS.fetch_sub(if removed == 0 { 0 } else { chunk_size(removed) }, Ordering::Relaxed);
+}
+
+// SAFETY: every call is forwarded to the system allocator unchanged.
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size(), 0);
+        // SAFETY: forwarded with the caller's layout.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(0, layout.size());
+        // SAFETY: `ptr` was allocated by `System` with this layout.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size, layout.size());
+        // SAFETY: `ptr` was allocated by `System` with this layout.
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Builds the `large.html` fixture of `benches/fixtures/generate.py`.
+fn large_fixture() -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n    <meta charset=\"UTF-8\">\n    <meta \
+         name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n    <title>Large \
+         Test Page - Data Table</title>\n</head>\n<body>\n<div id=\"app\" \
+         class=\"app-container\">\n",
+    );
+    for section in 0..10 {
+        let _ = writeln!(
+            html,
+            "    <section class=\"data-section\" id=\"section-{section}\" \
+             data-section=\"{section}\">\n        \
+             <h2 class=\"section-title\">Data Section {section}</h2>\n        \
+             <table class=\"data-table\" id=\"table-{section}\">\n            <thead>\n                <tr>"
+        );
+        for col in 0..10 {
+            let _ = writeln!(
+                html,
+                "                    <th class=\"col-header\" data-col=\"{col}\">Column {col}</th>"
+            );
+        }
+        html.push_str("                </tr>\n            </thead>\n            <tbody>\n");
+        for row in 0..500 {
+            let row_id = section * 500 + row;
+            let parity = if row % 2 == 0 { "even" } else { "odd" };
+            let _ = writeln!(
+                html,
+                "                <tr class=\"data-row {parity}\" id=\"row-{row_id}\" \
+                 data-row=\"{row_id}\">"
+            );
+            for col in 0..10 {
+                let cell_id = row_id * 10 + col;
+                let _ = writeln!(
+                    html,
+                    "                    <td class=\"data-cell\" id=\"cell-{cell_id}\" \
+                     data-value=\"{cell_id}\">Data {cell_id}</td>"
+                );
+            }
+            html.push_str("                </tr>\n");
+        }
+        html.push_str("            </tbody>\n        </table>\n");
+        let _ = writeln!(html, "        <div class=\"card-grid\" id=\"cards-{section}\">");
+        for card in 0..100 {
+            let card_id = section * 100 + card;
+            let _ = writeln!(
+                html,
+                "            <div class=\"card\" id=\"card-{card_id}\" data-id=\"{card_id}\">\n                \
+                 <div class=\"card-header\">\n                    \
+                 <h3 class=\"card-title\">Card {card_id}</h3>\n                    \
+                 <span class=\"card-badge badge-{}\">Badge</span>\n                \
+                 </div>\n                <div class=\"card-body\">\n                    \
+                 <p class=\"card-text\">Card content {card_id} with additional text to increase \
+                 document size.</p>\n                    <ul class=\"card-list\">",
+                card % 5
+            );
+            for item in 0..3 {
+                let _ = writeln!(
+                    html,
+                    "                        <li class=\"list-item\">Item {item}</li>"
+                );
+            }
+            let _ = writeln!(
+                html,
+                "                    </ul>\n                </div>\n                <div \
+                 class=\"card-footer\">\n                    <button class=\"btn btn-action\" \
+                 data-action=\"edit\" data-card=\"{card_id}\">Edit</button>\n                    \
+                 <button class=\"btn btn-action\" data-action=\"delete\" \
+                 data-card=\"{card_id}\">Delete</button>\n                </div>\n            \
+                 </div>"
+            );
+        }
+        html.push_str("        </div>\n    </section>\n");
+    }
+    html.push_str("</div>\n</body>\n</html>");
+    html
+}
+
+/// Memory of the parsed fixture on 64-bit targets while tag and attribute names were
+/// `String`s and attributes a `Vec` of them: bytes requested and bytes in malloc chunks.
+const OWNED_NAMES: (usize, usize) = (28_637_702, 43_227_504);
+
+/// The reduction the compact layout must keep, in percent of the chunk bytes.
+const TARGET: usize = 40;
+
+fn main() {
+    let html = large_fixture();
+    let before = (REQUESTED.load(Ordering::Relaxed), CHUNKS.load(Ordering::Relaxed));
+    let soup = Soup::parse(&html);
+    let requested = REQUESTED.load(Ordering::Relaxed) - before.0;
+    let chunks = CHUNKS.load(Ordering::Relaxed) - before.1;
+    let nodes = soup.document().len();
+    drop(soup);
+
+    let saved = |now: usize, then: usize| 100 - now * 100 / then;
+    println!(
+        "dom_memory: {} bytes of HTML, {nodes} nodes: {requested} bytes requested ({}% less than \
+         with owned names), {chunks} bytes in malloc chunks ({}% less)",
+        html.len(),
+        saved(requested, OWNED_NAMES.0),
+        saved(chunks, OWNED_NAMES.1),
+    );
+    if cfg!(target_pointer_width = "64") {
+        assert!(
+            saved(chunks, OWNED_NAMES.1) >= TARGET,
+            "the DOM takes {chunks} bytes, less than {TARGET}% below {}",
+            OWNED_NAMES.1
+        );
+    }
+}
@@ -373,30 +373,32 @@ fn run_scoped_find_all_assertion(
 }
 
 fn run_test_case(case: &TestCase) {
-    let soup = Soup::parse(&case.input);
+    run_assertions(&Soup::parse(&case.input), case);
+}
 
+fn run_assertions(soup: &Soup, case: &TestCase) {
     for assertion in &case.assertions {
         match assertion {
             Assertion::Find { selector, expected } => {
-                run_find_assertion(&soup, selector, expected, &case.id);
+                run_find_assertion(soup, selector, expected, &case.id);
             }
             Assertion::FindAll { selector, expected } => {
-                run_find_all_assertion(&soup, selector, expected, &case.id);
+                run_find_all_assertion(soup, selector, expected, &case.id);
             }
             Assertion::FindThen { selector, chain, expected } => {
-                run_find_then_assertion(&soup, selector, chain, expected, &case.id);
+                run_find_then_assertion(soup, selector, chain, expected, &case.id);
             }
             Assertion::Text { expected } => {
-                run_text_assertion(&soup, expected, &case.id);
+                run_text_assertion(soup, expected, &case.id);
             }
             Assertion::Title { expected } => {
-                run_title_assertion(&soup, expected, &case.id);
+                run_title_assertion(soup, expected, &case.id);
             }
             Assertion::ScopedFind { scope, selector, expected } => {
-                run_scoped_find_assertion(&soup, scope, selector, expected, &case.id);
+                run_scoped_find_assertion(soup, scope, selector, expected, &case.id);
             }
             Assertion::ScopedFindAll { scope, selector, expected } => {
-                run_scoped_find_all_assertion(&soup, scope, selector, expected, &case.id);
+                run_scoped_find_all_assertion(soup, scope, selector, expected, &case.id);
             }
         }
     }
@@ -474,3 +476,37 @@ fn test_scoped_queries_suite() {
         run_test_case(case);
     }
 }
+
+#[test]
+fn test_attribute_selectors_suite() {
+    let suite = load_test_suite();
+    let attributes = suite
+        .test_suites
+        .iter()
+        .find(|s| s.name == "attribute_selectors")
+        .expect("attribute_selectors suite not found");
+
+    for case in &attributes.cases {
+        run_test_case(case);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip_suite() {
+    use scrape_core::{Document, SoupConfig};
+
+    // Every shared case must behave the same on a document restored from JSON.
+    let suite = load_test_suite();
+    for case in suite.test_suites.iter().flat_map(|suite| &suite.cases) {
+        let soup = Soup::parse(&case.input);
+        let json = serde_json::to_string(soup.document())
+            .unwrap_or_else(|e| panic!("[{}] Failed to serialize document: {e}", case.id));
+        let document: Document = serde_json::from_str(&json)
+            .unwrap_or_else(|e| panic!("[{}] Failed to deserialize document: {e}", case.id));
+        let restored = Soup::from_document(document, SoupConfig::default());
+
+        assert_eq!(restored.to_html(), soup.to_html(), "[{}] Round trip changed the HTML", case.id);
+        run_assertions(&restored, case);
+    }
+}